@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use tokio::time::{interval, sleep_until, Instant, MissedTickBehavior};
+use tracing::debug;
+
+use crate::client::{FalconClient, PlayEvent};
+use crate::error::ClientError;
+
+/// Behaviour of a simulated player.
+#[derive(Debug, Clone)]
+pub struct BotOptions {
+    pub username: String,
+    /// Walk around randomly once the spawn position is known.
+    pub random_movement: bool,
+    pub move_interval: Duration,
+    /// Leave after this much time, stay connected forever if `None`.
+    pub duration: Option<Duration>,
+}
+
+impl BotOptions {
+    pub fn new(username: String) -> Self {
+        BotOptions {
+            username,
+            random_movement: false,
+            move_interval: Duration::from_millis(250),
+            duration: None,
+        }
+    }
+}
+
+/// Statistics of a single bot session.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BotReport {
+    pub keep_alives: u32,
+    pub packets: u32,
+    pub moves: u32,
+}
+
+/// Logs in and keeps the connection alive, optionally moving around,
+/// until the configured duration passed or the server ends the connection.
+pub async fn run_bot(mut client: FalconClient, options: BotOptions) -> Result<BotReport, ClientError> {
+    let login = client.login(&options.username).await?;
+    debug!(username = %login.username, uuid = %login.uuid, "Bot logged in");
+
+    let mut report = BotReport::default();
+    let mut position = None;
+    let mut rng = XorShift::new(login.uuid.as_u128() as u64);
+    let mut move_timer = interval(options.move_interval);
+    move_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let deadline = options.duration.map(|d| Instant::now() + d);
+
+    loop {
+        // only the frame is raced, handling it answers keep-alives which
+        // must not be interrupted by the timers
+        tokio::select! {
+            frame = client.next_frame() => {
+                report.packets += 1;
+                match client.handle_frame(frame?).await? {
+                    PlayEvent::KeepAlive(_) => report.keep_alives += 1,
+                    PlayEvent::PositionLook { x, y, z, .. } => position = Some((x, y, z)),
+                    PlayEvent::BundleDelimiter | PlayEvent::Packet(..) => {},
+                }
+            }
+            _ = move_timer.tick(), if options.random_movement && position.is_some() => {
+                if let Some((x, y, z)) = position.as_mut() {
+                    *x += rng.next_step();
+                    *z += rng.next_step();
+                    client.send_position(*x, *y, *z, true).await?;
+                    report.moves += 1;
+                }
+            }
+            _ = async { sleep_until(deadline.unwrap()).await }, if deadline.is_some() => {
+                return Ok(report);
+            }
+        }
+    }
+}
+
+/// Tiny pseudo random generator, movement does not need anything better.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self { XorShift(seed | 1) }
+
+    /// Returns a step between -0.2 and 0.2 blocks.
+    fn next_step(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % 401) as f64 / 1000.0 - 0.2
+    }
+}
@@ -0,0 +1,295 @@
+use std::time::{Duration, Instant};
+
+use bytes::{Bytes, BytesMut};
+use falcon_packet_core::special::{read_frame_async, MAX_FRAME_LENGTH};
+use falcon_packet_core::protocol::{PacketDirection, PacketId, ProtocolState};
+use falcon_packet_core::{PacketRead, PacketSize, PacketWrite, ReadError, VarI32};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::sync::mpsc::{channel, Receiver};
+use tracing::trace;
+use uuid::Uuid;
+
+use crate::error::ClientError;
+use crate::packets::*;
+
+const FRAME_QUEUE_LEN: usize = 64;
+
+/// Result of a server list ping.
+#[derive(Debug, Clone)]
+pub struct StatusInfo {
+    /// The raw json response of the server.
+    pub response: String,
+    pub latency: Duration,
+}
+
+/// The profile the server assigned to this client on login.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginInfo {
+    pub uuid: Uuid,
+    pub username: String,
+}
+
+/// A play state packet, keep-alives are answered before they are returned.
+#[derive(Debug)]
+pub enum PlayEvent {
+    KeepAlive(i64),
+    BundleDelimiter,
+    PositionLook { x: f64, y: f64, z: f64, yaw: f32, pitch: f32 },
+    Packet(i32, Bytes),
+}
+
+/// A minimal logical minecraft client.
+///
+/// Frames are read by a background task so that waiting for the next packet
+/// can safely be raced against timers.
+pub struct FalconClient {
+    writer: Box<dyn AsyncWrite + Send + Unpin>,
+    frames: Receiver<Result<Bytes, ReadError>>,
+    buffer: BytesMut,
+    protocol: i32,
+    host: String,
+    port: u16,
+}
+
+impl FalconClient {
+    /// Opens a tcp connection to the given server.
+    pub async fn connect<A: ToSocketAddrs>(addr: A, protocol: i32) -> Result<Self, ClientError> {
+        let stream = TcpStream::connect(addr).await?;
+        stream.set_nodelay(true)?;
+        let peer = stream.peer_addr()?;
+        Ok(Self::new(stream, protocol, peer.ip().to_string(), peer.port()))
+    }
+
+    /// Wraps an existing byte stream, `host` and `port` are only
+    /// used in the handshake.
+    pub fn new<S>(stream: S, protocol: i32, host: String, port: u16) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (reader, writer) = tokio::io::split(stream);
+        let (sender, frames) = channel(FRAME_QUEUE_LEN);
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(reader);
+            loop {
+                let frame = match read_frame_async(&mut reader, MAX_FRAME_LENGTH).await {
+                    Ok(Some(frame)) => Ok(frame),
+                    Ok(None) => break,
+                    Err(error) => Err(error),
+                };
+                let failed = frame.is_err();
+                if sender.send(frame).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Self {
+            writer: Box::new(writer),
+            frames,
+            buffer: BytesMut::new(),
+            protocol,
+            host,
+            port,
+        }
+    }
+
+    pub fn protocol(&self) -> i32 { self.protocol }
+
+    /// Queries the server list information and measures the round trip
+    /// time of a ping, this consumes the connection.
+    pub async fn status(mut self) -> Result<StatusInfo, ClientError> {
+        self.handshake(NEXT_STATE_STATUS).await?;
+        self.send_packet(&StatusRequestPacket {}).await?;
+        let response = self.expect::<StatusResponsePacket>().await?.response;
+
+        let start = Instant::now();
+        self.send_packet(&StatusPingPacket { payload: 0 }).await?;
+        self.expect::<StatusPongPacket>().await?;
+        Ok(StatusInfo {
+            response,
+            latency: start.elapsed(),
+        })
+    }
+
+    /// Performs an offline mode login, the client is in the play state
+    /// afterwards.
+    pub async fn login(&mut self, username: &str) -> Result<LoginInfo, ClientError> {
+        if KeepAlivePacket::packet_id(self.protocol).is_none() {
+            return Err(ClientError::UnsupportedVersion(self.protocol));
+        }
+        self.handshake(NEXT_STATE_LOGIN).await?;
+        self.send_packet(&LoginStartPacket {
+            username: username.to_owned(),
+        })
+        .await?;
+
+        let (packet_id, packet) = self.next_packet(ProtocolState::Login).await?;
+        match packet {
+            Some(ClientboundPacket::LoginDisconnectPacket(packet)) => Err(ClientError::Disconnected(packet.reason)),
+            Some(ClientboundPacket::LoginSuccessPacket(packet)) => Ok(LoginInfo {
+                uuid: packet.uuid,
+                username: packet.username,
+            }),
+            Some(ClientboundPacket::LoginSuccessStrPacket(packet)) => Ok(LoginInfo {
+                uuid: packet.uuid,
+                username: packet.username,
+            }),
+            Some(ClientboundPacket::SetCompressionPacket(_)) => Err(ClientError::CompressionUnsupported),
+            _ => Err(ClientError::UnexpectedPacket(packet_id)),
+        }
+    }
+
+    /// Waits for the next play packet.
+    ///
+    /// This is not cancel safe as a keep-alive may be half answered, race
+    /// [`next_frame`](Self::next_frame) instead and pass the frame to
+    /// [`handle_frame`](Self::handle_frame).
+    pub async fn next_event(&mut self) -> Result<PlayEvent, ClientError> {
+        let frame = self.next_frame().await?;
+        self.handle_frame(frame).await
+    }
+
+    /// Reads a play packet returned by [`next_frame`](Self::next_frame),
+    /// keep-alives are answered.
+    pub async fn handle_frame(&mut self, (packet_id, mut frame): (i32, Bytes)) -> Result<PlayEvent, ClientError> {
+        match ClientboundPacket::read(ProtocolState::Play, PacketDirection::Clientbound, self.protocol, packet_id, &mut frame)? {
+            Some(ClientboundPacket::KeepAlivePacket(packet)) => {
+                self.send_packet(&KeepAliveResponsePacket { id: packet.id }).await?;
+                Ok(PlayEvent::KeepAlive(packet.id))
+            },
+            Some(ClientboundPacket::DisconnectPacket(packet)) => Err(ClientError::Disconnected(packet.reason)),
+            Some(ClientboundPacket::BundleDelimiterPacket(_)) => Ok(PlayEvent::BundleDelimiter),
+            Some(ClientboundPacket::PositionLookPacket(packet)) => Ok(PlayEvent::PositionLook {
+                x: packet.x,
+                y: packet.y,
+                z: packet.z,
+                yaw: packet.yaw,
+                pitch: packet.pitch,
+            }),
+            _ => Ok(PlayEvent::Packet(packet_id, frame)),
+        }
+    }
+
+    /// Sends a player position update.
+    pub async fn send_position(&mut self, x: f64, y: f64, z: f64, on_ground: bool) -> Result<(), ClientError> {
+        self.send_packet(&PlayerPositionPacket { x, y, z, on_ground }).await
+    }
+
+    /// Writes a packet using its id in the protocol version of this client.
+    pub async fn send_packet<P: PacketWrite + PacketId>(&mut self, packet: &P) -> Result<(), ClientError> {
+        let packet_id = P::packet_id(self.protocol).ok_or(ClientError::UnsupportedVersion(self.protocol))?;
+        self.send(packet_id, packet).await
+    }
+
+    /// Writes a single uncompressed packet to the connection.
+    pub async fn send<P: PacketWrite>(&mut self, packet_id: i32, packet: &P) -> Result<(), ClientError> {
+        let packet_id = VarI32::from(packet_id);
+        let length = packet_id.size() + packet.size();
+        self.buffer.clear();
+        self.buffer.reserve(length + 3);
+        VarI32::from(length).write(&mut self.buffer)?;
+        packet_id.write(&mut self.buffer)?;
+        packet.write(&mut self.buffer)?;
+        self.writer.write_all(&self.buffer).await?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    /// Waits for the next packet and splits off its id.
+    ///
+    /// This is cancel safe, no frame is lost when it is raced against other
+    /// futures.
+    pub async fn next_frame(&mut self) -> Result<(i32, Bytes), ClientError> {
+        let mut frame = self.frames.recv().await.ok_or(ClientError::Closed)??;
+        let packet_id = VarI32::read(&mut frame)?.val();
+        trace!(packet_id = packet_id, len = frame.len(), "Received packet");
+        Ok((packet_id, frame))
+    }
+
+    async fn handshake(&mut self, next_state: i32) -> Result<(), ClientError> {
+        let packet = HandshakePacket {
+            version: self.protocol,
+            address: self.host.clone(),
+            port: self.port,
+            next_state,
+        };
+        self.send_packet(&packet).await
+    }
+
+    /// Waits for the next packet and reads it if it's known in `state`.
+    async fn next_packet(&mut self, state: ProtocolState) -> Result<(i32, Option<ClientboundPacket>), ClientError> {
+        let (packet_id, mut frame) = self.next_frame().await?;
+        let packet = ClientboundPacket::read(state, PacketDirection::Clientbound, self.protocol, packet_id, &mut frame)?;
+        Ok((packet_id, packet))
+    }
+
+    async fn expect<P: PacketRead + PacketId>(&mut self) -> Result<P, ClientError> {
+        let (packet_id, mut frame) = self.next_frame().await?;
+        if Some(packet_id) != P::packet_id(self.protocol) {
+            return Err(ClientError::UnexpectedPacket(packet_id));
+        }
+        Ok(P::read(&mut frame)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::{Bytes, BytesMut};
+    use falcon_packet_core::protocol::PacketId;
+    use falcon_packet_core::special::{read_frame_async, MAX_FRAME_LENGTH};
+    use falcon_packet_core::{PacketRead, PacketSize, PacketWrite, VarI32};
+    use tokio::io::{duplex, AsyncWriteExt, DuplexStream};
+    use uuid::Uuid;
+
+    use super::{FalconClient, LoginInfo, PlayEvent};
+    use crate::packets::*;
+
+    const PROTOCOL: i32 = 756;
+
+    async fn read_frame(server: &mut DuplexStream) -> (i32, Bytes) {
+        let mut frame = read_frame_async(server, MAX_FRAME_LENGTH).await.unwrap().unwrap();
+        let packet_id = VarI32::read(&mut frame).unwrap().val();
+        (packet_id, frame)
+    }
+
+    async fn write_frame<P: PacketWrite + PacketId>(server: &mut DuplexStream, packet: &P) {
+        let packet_id = VarI32::from(P::packet_id(PROTOCOL).unwrap());
+        let mut buffer = BytesMut::new();
+        VarI32::from(packet_id.size() + packet.size()).write(&mut buffer).unwrap();
+        packet_id.write(&mut buffer).unwrap();
+        packet.write(&mut buffer).unwrap();
+        server.write_all(&buffer).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_login_keep_alive() {
+        let (stream, mut server) = duplex(4096);
+        let mut client = FalconClient::new(stream, PROTOCOL, String::from("localhost"), 25565);
+        let uuid = Uuid::from_u128(0x1234);
+
+        let server = tokio::spawn(async move {
+            let (packet_id, mut frame) = read_frame(&mut server).await;
+            assert_eq!(Some(packet_id), HandshakePacket::packet_id(PROTOCOL));
+            let handshake = HandshakePacket::read(&mut frame).unwrap();
+            assert_eq!((handshake.version, handshake.next_state), (PROTOCOL, NEXT_STATE_LOGIN));
+
+            let (packet_id, mut frame) = read_frame(&mut server).await;
+            assert_eq!(Some(packet_id), LoginStartPacket::packet_id(PROTOCOL));
+            let username = LoginStartPacket::read(&mut frame).unwrap().username;
+            write_frame(&mut server, &LoginSuccessPacket { uuid, username }).await;
+
+            write_frame(&mut server, &KeepAlivePacket { id: 42 }).await;
+            let (packet_id, mut frame) = read_frame(&mut server).await;
+            assert_eq!(Some(packet_id), KeepAliveResponsePacket::packet_id(PROTOCOL));
+            assert_eq!(KeepAliveResponsePacket::read(&mut frame).unwrap().id, 42);
+        });
+
+        let login = client.login("Falcon").await.unwrap();
+        assert_eq!(login, LoginInfo {
+            uuid,
+            username: String::from("Falcon"),
+        });
+        assert!(matches!(client.next_event().await.unwrap(), PlayEvent::KeepAlive(42)));
+        server.await.unwrap();
+    }
+}
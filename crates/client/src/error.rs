@@ -0,0 +1,22 @@
+use falcon_packet_core::{ReadError, WriteError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("Couldn't read packet: {0}")]
+    ReadError(#[from] ReadError),
+    #[error("Couldn't write packet: {0}")]
+    WriteError(#[from] WriteError),
+    #[error("Connection error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Server closed the connection")]
+    Closed,
+    #[error("Disconnected by server: {0}")]
+    Disconnected(String),
+    #[error("Unexpected packet 0x{0:02X} received")]
+    UnexpectedPacket(i32),
+    #[error("Protocol version {0} is not supported by this client")]
+    UnsupportedVersion(i32),
+    #[error("Server enabled compression which is not supported by this client")]
+    CompressionUnsupported,
+}
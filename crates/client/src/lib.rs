@@ -0,0 +1,21 @@
+//! ## **Falcon Client**
+//! A small logical minecraft client that speaks just enough of the protocol
+//! to test a server: handshake, server list ping, offline login and staying
+//! connected by answering keep-alives.
+//!
+//! It serves two purposes:
+//! - **Integration tests**: [`FalconClient::new`] accepts any byte stream, so
+//!   tests can drive a server over an in-memory pipe or a real socket.
+//! - **Load generation**: the `falcon-client` binary spawns many simulated
+//!   players using [`run_bot`].
+//!
+//! Compression and encryption are not supported.
+
+pub use bot::{run_bot, BotOptions, BotReport};
+pub use client::{FalconClient, LoginInfo, PlayEvent, StatusInfo};
+pub use error::ClientError;
+
+mod bot;
+mod client;
+mod error;
+pub mod packets;
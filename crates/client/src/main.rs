@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use falcon_client::{run_bot, BotOptions, FalconClient};
+use tokio::task::JoinSet;
+use tracing::metadata::LevelFilter;
+use tracing::{info, warn};
+
+const USAGE: &str = "Usage: falcon-client [address] [--status] [--bots <count>] [--protocol <id>] [--move] [--duration <seconds>]";
+
+struct Args {
+    address: String,
+    status: bool,
+    bots: usize,
+    protocol: i32,
+    random_movement: bool,
+    duration: Option<Duration>,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut args = Args {
+        address: String::from("127.0.0.1:30000"),
+        status: false,
+        bots: 1,
+        protocol: 736,
+        random_movement: false,
+        duration: None,
+    };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--status" => args.status = true,
+            "--move" => args.random_movement = true,
+            "--bots" => args.bots = iter.next().context(USAGE)?.parse().context("Invalid bot count")?,
+            "--protocol" => args.protocol = iter.next().context(USAGE)?.parse().context("Invalid protocol id")?,
+            "--duration" => args.duration = Some(Duration::from_secs(iter.next().context(USAGE)?.parse().context("Invalid duration")?)),
+            "-h" | "--help" => bail!(USAGE),
+            address if !address.starts_with('-') => args.address = address.to_owned(),
+            other => bail!("Unknown argument \"{}\"\n{}", other, USAGE),
+        }
+    }
+    Ok(args)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().with_target(false).with_max_level(LevelFilter::INFO).init();
+    let args = parse_args()?;
+
+    if args.status {
+        let client = FalconClient::connect(&args.address, args.protocol).await?;
+        let status = client.status().await?;
+        info!(latency = ?status.latency, "{}", status.response);
+        return Ok(());
+    }
+
+    info!("Launching {} bot(s) against {}", args.bots, args.address);
+    let mut bots = JoinSet::new();
+    for i in 0..args.bots {
+        let mut options = BotOptions::new(format!("FalconBot{}", i));
+        options.random_movement = args.random_movement;
+        options.duration = args.duration;
+        let address = args.address.clone();
+        let protocol = args.protocol;
+        bots.spawn(async move {
+            let client = FalconClient::connect(address, protocol).await?;
+            run_bot(client, options).await
+        });
+    }
+
+    let (mut finished, mut failed) = (0, 0);
+    while let Some(result) = bots.join_next().await {
+        match result? {
+            Ok(report) => {
+                finished += 1;
+                info!(keep_alives = report.keep_alives, packets = report.packets, moves = report.moves, "Bot finished");
+            },
+            Err(error) => {
+                failed += 1;
+                warn!("Bot failed: {}", error);
+            },
+        }
+    }
+    info!("{} bot(s) finished, {} failed", finished, failed);
+    Ok(())
+}
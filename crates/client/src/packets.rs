@@ -0,0 +1,164 @@
+//! The packets this client understands, together with their ids in every
+//! supported protocol version.
+
+use falcon_packet_core::packet;
+use falcon_packet_core::special::StrUuid;
+use uuid::Uuid;
+
+pub const NEXT_STATE_STATUS: i32 = 1;
+pub const NEXT_STATE_LOGIN: i32 = 2;
+
+packet! {
+    /// Packets sent by the client.
+    pub enum ServerboundPacket;
+
+    #[falcon_packet(state = handshake, direction = serverbound, versions = { -1 = 0x00 })]
+    pub struct HandshakePacket {
+        #[falcon(var32)]
+        pub version: i32,
+        #[falcon(string = 255)]
+        pub address: String,
+        pub port: u16,
+        #[falcon(var32)]
+        pub next_state: i32,
+    }
+
+    #[falcon_packet(state = status, direction = serverbound, versions = { -1 = 0x00 })]
+    pub struct StatusRequestPacket {}
+
+    #[falcon_packet(state = status, direction = serverbound, versions = { -1 = 0x01 })]
+    pub struct StatusPingPacket {
+        pub payload: i64,
+    }
+
+    #[falcon_packet(state = login, direction = serverbound, versions = { -1 = 0x00 })]
+    pub struct LoginStartPacket {
+        #[falcon(string = 16)]
+        pub username: String,
+    }
+
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        340 = 0x0B;
+        393, 401, 404 = 0x0E;
+        477..=578, 755, 756 = 0x0F;
+        735, 736 = 0x10;
+        759, 761 = 0x11;
+        760, 763 = 0x12;
+    })]
+    pub struct KeepAliveResponsePacket {
+        pub id: i64,
+    }
+
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        340 = 0x0D;
+        393, 401, 404 = 0x10;
+        477..=578, 755, 756 = 0x11;
+        735, 736 = 0x12;
+        759, 761 = 0x13;
+        760, 763 = 0x14;
+    })]
+    pub struct PlayerPositionPacket {
+        pub x: f64,
+        pub y: f64,
+        pub z: f64,
+        pub on_ground: bool,
+    }
+}
+
+packet! {
+    /// Packets sent by the server.
+    pub enum ClientboundPacket;
+
+    #[falcon_packet(state = status, direction = clientbound, versions = { -1 = 0x00 })]
+    pub struct StatusResponsePacket {
+        #[falcon(string = 32767)]
+        pub response: String,
+    }
+
+    #[falcon_packet(state = status, direction = clientbound, versions = { -1 = 0x01 })]
+    pub struct StatusPongPacket {
+        pub payload: i64,
+    }
+
+    #[falcon_packet(state = login, direction = clientbound, versions = { -1 = 0x00 })]
+    pub struct LoginDisconnectPacket {
+        #[falcon(string = 262144)]
+        pub reason: String,
+    }
+
+    /// Login success as sent before 1.16, uuid in string format.
+    #[falcon_packet(state = login, direction = clientbound, versions = {
+        47..=578 = 0x02;
+    })]
+    pub struct LoginSuccessStrPacket {
+        #[falcon(convert = "StrUuid")]
+        pub uuid: Uuid,
+        #[falcon(string = 16)]
+        pub username: String,
+    }
+
+    /// Login success as sent since 1.16, 1.19 appends the profile properties.
+    #[falcon_packet(state = login, direction = clientbound, versions = { 735, 736, 755, 756, 759..=761, 763 = 0x02 })]
+    pub struct LoginSuccessPacket {
+        pub uuid: Uuid,
+        #[falcon(string = 16)]
+        pub username: String,
+    }
+
+    #[falcon_packet(state = login, direction = clientbound, versions = { -1 = 0x03 })]
+    pub struct SetCompressionPacket {
+        #[falcon(var32)]
+        pub threshold: i32,
+    }
+
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        340 = 0x1F;
+        393, 401, 404, 573, 575, 578 = 0x21;
+        477, 480, 485, 490, 498, 735, 736 = 0x20;
+        755, 756 = 0x21;
+        759 = 0x1E;
+        760 = 0x20;
+        761 = 0x1F;
+        763 = 0x23;
+    })]
+    pub struct KeepAlivePacket {
+        pub id: i64,
+    }
+
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        340, 477, 480, 485, 490, 498, 735, 736, 755, 756 = 0x1A;
+        393, 401, 404, 573, 575, 578 = 0x1B;
+        759, 761 = 0x17;
+        760 = 0x19;
+        763 = 0x1A;
+    })]
+    pub struct DisconnectPacket {
+        #[falcon(string = 262144)]
+        pub reason: String,
+    }
+
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        340 = 0x2F;
+        393, 401, 404 = 0x32;
+        477, 480, 485, 490, 498, 735, 736 = 0x35;
+        573, 575, 578 = 0x36;
+        755, 756 = 0x38;
+        759 = 0x36;
+        760 = 0x39;
+        761 = 0x38;
+        763 = 0x3C;
+    })]
+    pub struct PositionLookPacket {
+        pub x: f64,
+        pub y: f64,
+        pub z: f64,
+        pub yaw: f32,
+        pub pitch: f32,
+        pub flags: u8,
+    }
+
+    /// Marks the start or end of a bundle of packets the client applies
+    /// in the same tick, 1.19.4+.
+    #[falcon_packet(state = play, direction = clientbound, versions = { 763 = 0x00 })]
+    pub struct BundleDelimiterPacket {}
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::protocol::{Direction, FieldType, Packet};
+
+const KEYWORDS: [&str; 20] = [
+    "as", "break", "const", "continue", "crate", "else", "enum", "fn", "for", "if", "impl", "in", "loop", "match", "mod", "move", "ref", "type", "use", "where",
+];
+
+/// Generates the module source for all packets of a protocol version.
+pub fn generate(protocol: i32, packets: &[Packet]) -> String {
+    let mut out = String::new();
+    writeln!(out, "// @generated by falcon-codegen from minecraft-data (protocol {})", protocol).unwrap();
+    for direction in [Direction::Serverbound, Direction::Clientbound] {
+        let packets: Vec<&Packet> = packets.iter().filter(|p| p.direction == direction).collect();
+        if packets.is_empty() {
+            continue;
+        }
+        writeln!(out).unwrap();
+        writeln!(out, "pub mod {} {{", direction.name()).unwrap();
+        writeln!(out, "    falcon_packet_core::packet! {{").unwrap();
+        writeln!(out, "        pub enum {}Packet;", pascal_case(direction.name())).unwrap();
+
+        let names = struct_names(&packets);
+        for (packet, name) in packets.iter().zip(names) {
+            writeln!(out).unwrap();
+            generate_packet(&mut out, protocol, packet, &name);
+        }
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+    }
+    out
+}
+
+fn generate_packet(out: &mut String, protocol: i32, packet: &Packet, name: &str) {
+    if !packet.unparsed.is_empty() {
+        writeln!(out, "        // TODO: unsupported fields kept as bytes: {}", packet.unparsed.join(", ")).unwrap();
+    }
+    writeln!(
+        out,
+        "        #[falcon_packet(state = {}, direction = {}, versions = {{ {} = {:#04X} }})]",
+        packet.state.name(),
+        packet.direction.name(),
+        protocol,
+        packet.id
+    )
+    .unwrap();
+    writeln!(out, "        #[derive(Debug)]").unwrap();
+    writeln!(out, "        pub struct {} {{", name).unwrap();
+    let mut has_rest = false;
+    for field in &packet.fields {
+        let (attribute, ty) = field_type(field.ty);
+        if let Some(attribute) = attribute {
+            writeln!(out, "            #[falcon({})]", attribute).unwrap();
+        }
+        writeln!(out, "            pub {}: {},", field_name(&field.name), ty).unwrap();
+        has_rest |= field.ty == FieldType::Rest;
+    }
+    if !packet.unparsed.is_empty() && !has_rest {
+        writeln!(out, "            #[falcon(rest)]").unwrap();
+        writeln!(out, "            pub unparsed: bytes::Bytes,").unwrap();
+    }
+    writeln!(out, "        }}").unwrap();
+}
+
+fn field_type(ty: FieldType) -> (Option<&'static str>, &'static str) {
+    match ty {
+        FieldType::VarInt => (Some("var32"), "i32"),
+        FieldType::VarLong => (Some("var64"), "i64"),
+        FieldType::String => (Some("string"), "String"),
+        FieldType::Bool => (None, "bool"),
+        FieldType::I8 => (None, "i8"),
+        FieldType::U8 => (None, "u8"),
+        FieldType::I16 => (None, "i16"),
+        FieldType::U16 => (None, "u16"),
+        FieldType::I32 => (None, "i32"),
+        FieldType::I64 | FieldType::Position => (None, "i64"),
+        FieldType::F32 => (None, "f32"),
+        FieldType::F64 => (None, "f64"),
+        FieldType::Uuid => (None, "uuid::Uuid"),
+        FieldType::Rest => (Some("rest"), "bytes::Bytes"),
+    }
+}
+
+/// Struct names for the packets of one direction, the state is prepended
+/// to names that appear in more than one state.
+fn struct_names(packets: &[&Packet]) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for packet in packets {
+        *counts.entry(packet.name.as_str()).or_default() += 1;
+    }
+    packets
+        .iter()
+        .map(|packet| {
+            if counts[packet.name.as_str()] > 1 {
+                format!("{}{}Packet", pascal_case(packet.state.name()), pascal_case(&packet.name))
+            } else {
+                format!("{}Packet", pascal_case(&packet.name))
+            }
+        })
+        .collect()
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+fn field_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 2);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i != 0 {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            result.push(c);
+        }
+    }
+    if KEYWORDS.contains(&result.as_str()) {
+        result.push('_');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::generate;
+    use crate::protocol::parse;
+
+    #[test]
+    fn test_generate() {
+        let protocol = json!({
+            "status": {
+                "toServer": { "types": {
+                    "packet": ["container", [
+                        { "name": "name", "type": ["mapper", { "type": "varint", "mappings": { "0x00": "ping_start", "0x01": "ping" } }] },
+                        { "name": "params", "type": ["switch", {}] }
+                    ]],
+                    "packet_ping_start": ["container", []],
+                    "packet_ping": ["container", [{ "name": "time", "type": "i64" }]]
+                }}
+            },
+            "play": {
+                "toServer": { "types": {
+                    "packet": ["container", [
+                        { "name": "name", "type": ["mapper", { "type": "varint", "mappings": { "0x0b": "ping", "0x0c": "use_entity" } }] }
+                    ]],
+                    "packet_ping": ["container", [{ "name": "keepAliveId", "type": "varint" }]],
+                    "packet_use_entity": ["container", [
+                        { "name": "target", "type": "varint" },
+                        { "name": "type", "type": "varint" },
+                        { "name": "x", "type": ["switch", {}] },
+                        { "name": "sneaking", "type": "bool" }
+                    ]]
+                }}
+            }
+        });
+        let code = generate(578, &parse(&protocol).unwrap());
+
+        assert!(code.contains("pub mod serverbound {"));
+        assert!(!code.contains("pub mod clientbound"));
+        assert!(code.contains("#[falcon_packet(state = status, direction = serverbound, versions = { 578 = 0x01 })]"));
+        assert!(code.contains("pub struct StatusPingPacket {"));
+        assert!(code.contains("pub struct PlayPingPacket {\n            #[falcon(var32)]\n            pub keep_alive_id: i32,"));
+        assert!(code.contains("pub struct PingStartPacket {\n        }"));
+        assert!(code.contains("// TODO: unsupported fields kept as bytes: x, sneaking"));
+        assert!(code.contains("pub type_: i32,\n            #[falcon(rest)]\n            pub unparsed: bytes::Bytes,"));
+    }
+}
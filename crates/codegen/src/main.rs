@@ -0,0 +1,97 @@
+//! ## **Falcon Codegen**
+//! Generates packet structs and their id mappings for a single protocol
+//! version from [minecraft-data](https://github.com/PrismarineJS/minecraft-data)'s
+//! `protocol.json`. The output is a module with a `packet!` block per
+//! direction that can be added to a crate depending on `falcon_packet_core`.
+//!
+//! Only fields with a fixed layout are translated, everything from the first
+//! unsupported field on is kept as raw bytes so the packet can still be read
+//! and forwarded. Those packets are marked with a `TODO` comment.
+//!
+//! With `--block-names` it generates the canonical names of the block states
+//! of falcon_core from its generated `blocks.rs` instead, see [`names`]:
+//! ```text
+//! falcon-codegen --block-names crates/core/src/world/blocks.rs -o crates/core/src/world/block_names.rs
+//! ```
+//!
+//! With `--block-states` it generates the block state ids of a version from
+//! its vanilla block report, see [`states`]:
+//! ```text
+//! falcon-codegen --block-states crates/core/src/world/blocks.rs --report blocks.json --data-version 3105 \
+//!     -o crates/core/src/world/block_ids_3105.rs
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+mod generate;
+mod names;
+mod protocol;
+mod states;
+
+const USAGE: &str = "Usage: falcon-codegen <protocol.json> --protocol <id> [--output <file>]
+       falcon-codegen --block-names <blocks.rs> [--output <file>]
+       falcon-codegen --block-states <blocks.rs> --report <blocks.json> --data-version <version> [--output <file>]";
+
+enum Mode {
+    Protocol(i32),
+    BlockNames,
+    BlockStates { report: PathBuf, data_version: i32 },
+}
+
+struct Args {
+    input: PathBuf,
+    mode: Mode,
+    output: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args> {
+    let (mut input, mut protocol, mut output, mut block_names, mut block_states) = (None, None, None, false, false);
+    let (mut report, mut data_version) = (None, None);
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--protocol" => protocol = Some(iter.next().context(USAGE)?.parse().context("Invalid protocol id")?),
+            "--block-names" => block_names = true,
+            "--block-states" => block_states = true,
+            "--report" => report = Some(PathBuf::from(iter.next().context(USAGE)?)),
+            "--data-version" => data_version = Some(iter.next().context(USAGE)?.parse().context("Invalid data version")?),
+            "--output" | "-o" => output = Some(PathBuf::from(iter.next().context(USAGE)?)),
+            "-h" | "--help" => bail!(USAGE),
+            path if !path.starts_with('-') => input = Some(PathBuf::from(path)),
+            other => bail!("Unknown argument \"{}\"\n{}", other, USAGE),
+        }
+    }
+    let mode = match (protocol, block_names, block_states) {
+        (Some(protocol), false, false) => Mode::Protocol(protocol),
+        (None, true, false) => Mode::BlockNames,
+        (None, false, true) => Mode::BlockStates {
+            report: report.context(USAGE)?,
+            data_version: data_version.context(USAGE)?,
+        },
+        _ => bail!(USAGE),
+    };
+    Ok(Args {
+        input: input.context(USAGE)?,
+        mode,
+        output,
+    })
+}
+
+fn read(path: &Path) -> Result<String> { std::fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display())) }
+
+fn main() -> Result<()> {
+    let args = parse_args()?;
+    let input = read(&args.input)?;
+    let code = match args.mode {
+        Mode::Protocol(protocol) => generate::generate(protocol, &protocol::parse(&serde_json::from_str(&input)?)?),
+        Mode::BlockNames => names::generate(&input)?,
+        Mode::BlockStates { report, data_version } => states::generate(&input, &serde_json::from_str(&read(&report)?)?, data_version)?,
+    };
+    match args.output {
+        Some(path) => std::fs::write(&path, code).with_context(|| format!("Could not write {}", path.display()))?,
+        None => print!("{}", code),
+    }
+    Ok(())
+}
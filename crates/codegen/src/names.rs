@@ -0,0 +1,208 @@
+//! The canonical names of block states, e.g.
+//! `minecraft:oak_slab[type=top,waterlogged=false]`, generated from the
+//! `FromStr` implementations of the generated `blocks.rs`.
+//!
+//! `blocks.rs` names the `type` property `typed` as `type` is a keyword,
+//! the names use the key of the vanilla block reports again.
+
+use std::fmt::Write;
+
+use anyhow::{bail, Context, Result};
+
+/// A block of the `FromStr` implementation of `Blocks`.
+pub(crate) struct Block {
+    pub name: String,
+    pub variant: String,
+    /// The properties, sorted by key.
+    pub properties: Vec<BlockProperty>,
+}
+
+/// A property of a block, e.g. `type`, `typed()` and `SlabType`.
+pub(crate) struct BlockProperty {
+    /// The vanilla key.
+    pub key: String,
+    pub accessor: String,
+    pub ty: String,
+}
+
+/// A property type and its values, e.g. `SlabType` and `top`.
+pub(crate) struct Property {
+    pub ty: String,
+    pub values: Vec<(String, String)>,
+}
+
+/// Generates `Display` for `Blocks` and its property types from the source
+/// of `blocks.rs`.
+pub fn generate(source: &str) -> Result<String> {
+    let blocks = parse_blocks(impl_body(source, "Blocks").context("blocks.rs has no FromStr for Blocks")?)?;
+    let properties = parse_properties(source)?;
+
+    let mut out = String::new();
+    writeln!(out, "// @generated by falcon-codegen from blocks.rs").unwrap();
+    writeln!(out, "use super::blocks::*;").unwrap();
+    writeln!(out, "impl std::fmt::Display for Blocks {{").unwrap();
+    writeln!(out, "    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{").unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for block in &blocks {
+        if block.properties.is_empty() {
+            writeln!(out, "            Blocks::{} => write!(f, \"minecraft:{}\"),", block.variant, block.name).unwrap();
+            continue;
+        }
+        let keys: Vec<String> = block.properties.iter().map(|property| format!("{}={{}}", property.key)).collect();
+        let values: Vec<String> = block.properties.iter().map(|property| format!("state.{}()", property.accessor)).collect();
+        writeln!(
+            out,
+            "            Blocks::{}(state) => write!(f, \"minecraft:{}[{}]\", {}),",
+            block.variant,
+            block.name,
+            keys.join(","),
+            values.join(", ")
+        )
+        .unwrap();
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+    for property in &properties {
+        writeln!(out, "impl std::fmt::Display for {} {{", property.ty).unwrap();
+        writeln!(out, "    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{").unwrap();
+        writeln!(out, "        f.write_str(match self {{").unwrap();
+        for (value, variant) in &property.values {
+            writeln!(out, "            {}::{} => \"{}\",", property.ty, variant, value).unwrap();
+        }
+        writeln!(out, "        }})").unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}").unwrap();
+    }
+    Ok(out)
+}
+
+/// The lines of `impl std::str::FromStr for <ty>`.
+pub(crate) fn impl_body<'a>(source: &'a str, ty: &str) -> Option<Vec<&'a str>> {
+    let header = format!("impl std::str::FromStr for {} {{", ty);
+    let mut lines = source.lines().skip_while(|line| *line != header);
+    lines.next()?;
+    Some(lines.take_while(|line| *line != "}").collect())
+}
+
+/// The string between the first pair of quotes of `line`.
+fn quoted(line: &str) -> Option<&str> { line.split('"').nth(1) }
+
+pub(crate) fn parse_blocks(lines: Vec<&str>) -> Result<Vec<Block>> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Block> = None;
+    let mut key = None;
+    for line in lines.iter().map(|line| line.trim()) {
+        if let Some(block) = &mut current {
+            if let Some(rest) = line.strip_prefix("if let Some(prop) = props.get(") {
+                key = quoted(rest).map(|key| if key == "typed" { "type" } else { key });
+            } else if let Some(rest) = line.strip_prefix("state_ident.with_") {
+                let (accessor, ty) = rest.split_once('(').unwrap_or_default();
+                let ty = ty.split("::from_str").next().unwrap_or_default();
+                let key = key.take().with_context(|| format!("Property without key in {}", block.name))?;
+                block.properties.push(BlockProperty {
+                    key: key.to_owned(),
+                    accessor: accessor.to_owned(),
+                    ty: ty.to_owned(),
+                });
+            } else if let Some(rest) = line.strip_prefix("Blocks::") {
+                let mut block = current.take().unwrap();
+                block.variant = rest.split('(').next().unwrap_or_default().to_owned();
+                block.properties.sort_by(|a, b| a.key.cmp(&b.key));
+                blocks.push(block);
+            }
+        } else if let Some((name, rest)) = line.strip_prefix('"').and_then(|line| line.split_once("\" => ")) {
+            let block = Block {
+                name: name.to_owned(),
+                variant: String::new(),
+                properties: Vec::new(),
+            };
+            match rest.strip_prefix("Blocks::").and_then(|variant| variant.strip_suffix(',')) {
+                Some(variant) => blocks.push(Block {
+                    variant: variant.to_owned(),
+                    ..block
+                }),
+                None if rest == "{" => current = Some(block),
+                None => bail!("Unexpected line in FromStr for Blocks: {}", line),
+            }
+        }
+    }
+    if blocks.is_empty() {
+        bail!("FromStr for Blocks has no blocks");
+    }
+    Ok(blocks)
+}
+
+/// Every property type, in the order of `blocks.rs`.
+pub(crate) fn parse_properties(source: &str) -> Result<Vec<Property>> {
+    let mut properties = Vec::new();
+    for line in source.lines() {
+        let Some(ty) = line.strip_prefix("impl std::str::FromStr for ").and_then(|ty| ty.strip_suffix(" {")) else {
+            continue;
+        };
+        if ty == "Blocks" {
+            continue;
+        }
+        let prefix = format!("Ok({}::", ty);
+        let values = impl_body(source, ty)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|line| {
+                let (value, rest) = line.trim().strip_prefix('"')?.split_once("\" => ")?;
+                Some((value.to_owned(), rest.strip_prefix(&prefix)?.strip_suffix("),")?.to_owned()))
+            })
+            .collect::<Vec<_>>();
+        if values.is_empty() {
+            bail!("FromStr for {} has no values", ty);
+        }
+        properties.push(Property { ty: ty.to_owned(), values });
+    }
+    Ok(properties)
+}
+
+#[cfg(test)]
+mod test {
+    use super::generate;
+
+    const BLOCKS: &str = r#"impl std::str::FromStr for Blocks {
+    type Err = ParseBlockError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "air" => Blocks::Air,
+            "oak_slab" => {
+                let mut state_ident = OakSlabState::default();
+                if let Some(prop) = props.get("waterlogged") {
+                    state_ident.with_waterlogged(bool::from_str(prop)?);
+                }
+                if let Some(prop) = props.get("typed") {
+                    state_ident.with_typed(SlabType::from_str(prop)?);
+                }
+                Blocks::OakSlab(state_ident)
+            }
+            _ => return Err(ParseBlockError::UnknownBlock),
+        })
+    }
+}
+impl std::str::FromStr for SlabType {
+    type Err = ParseBlockError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(SlabType::Top),
+            "double" => Ok(SlabType::Double),
+            _ => Err(ParseBlockError::InvalidProperty),
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn test_generate() {
+        let code = generate(BLOCKS).unwrap();
+        assert!(code.contains("Blocks::Air => write!(f, \"minecraft:air\"),"));
+        assert!(code.contains(
+            "Blocks::OakSlab(state) => write!(f, \"minecraft:oak_slab[type={},waterlogged={}]\", state.typed(), state.waterlogged()),"
+        ));
+        assert!(code.contains("SlabType::Double => \"double\","));
+        assert!(generate("").is_err());
+    }
+}
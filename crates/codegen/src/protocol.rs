@@ -0,0 +1,182 @@
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+    Handshake,
+    Status,
+    Login,
+    Play,
+}
+
+impl State {
+    const ALL: [(State, &'static str); 4] = [
+        (State::Handshake, "handshaking"),
+        (State::Status, "status"),
+        (State::Login, "login"),
+        (State::Play, "play"),
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            State::Handshake => "handshake",
+            State::Status => "status",
+            State::Login => "login",
+            State::Play => "play",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Serverbound,
+    Clientbound,
+}
+
+impl Direction {
+    const ALL: [(Direction, &'static str); 2] = [(Direction::Serverbound, "toServer"), (Direction::Clientbound, "toClient")];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Serverbound => "serverbound",
+            Direction::Clientbound => "clientbound",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldType {
+    VarInt,
+    VarLong,
+    String,
+    Bool,
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    I64,
+    F32,
+    F64,
+    Uuid,
+    Position,
+    Rest,
+}
+
+impl FieldType {
+    fn parse(value: &Value) -> Option<Self> {
+        Some(match value.as_str()? {
+            "varint" => FieldType::VarInt,
+            "varlong" => FieldType::VarLong,
+            "string" => FieldType::String,
+            "bool" => FieldType::Bool,
+            "i8" => FieldType::I8,
+            "u8" => FieldType::U8,
+            "i16" => FieldType::I16,
+            "u16" => FieldType::U16,
+            "i32" => FieldType::I32,
+            "i64" => FieldType::I64,
+            "f32" => FieldType::F32,
+            "f64" => FieldType::F64,
+            "UUID" => FieldType::Uuid,
+            "position" => FieldType::Position,
+            "restBuffer" => FieldType::Rest,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Field {
+    pub name: String,
+    pub ty: FieldType,
+}
+
+#[derive(Debug)]
+pub struct Packet {
+    pub state: State,
+    pub direction: Direction,
+    pub id: i32,
+    pub name: String,
+    pub fields: Vec<Field>,
+    /// Names of the fields that are kept as raw bytes.
+    pub unparsed: Vec<String>,
+}
+
+/// Reads all packets from minecraft-data's `protocol.json`.
+pub fn parse(protocol: &Value) -> Result<Vec<Packet>> {
+    let mut packets = Vec::new();
+    for (state, state_key) in State::ALL {
+        let state_value = match protocol.get(state_key) {
+            Some(value) => value,
+            None => continue,
+        };
+        for (direction, direction_key) in Direction::ALL {
+            let types = match state_value.get(direction_key).and_then(|d| d.get("types")) {
+                Some(types) => types,
+                None => continue,
+            };
+            for (id, name) in packet_ids(types).with_context(|| format!("Invalid packet mapper in {}.{}", state_key, direction_key))? {
+                let container = types
+                    .get(format!("packet_{}", name))
+                    .with_context(|| format!("Missing definition of packet \"{}\" in {}.{}", name, state_key, direction_key))?;
+                let (fields, unparsed) = parse_fields(container);
+                packets.push(Packet {
+                    state,
+                    direction,
+                    id,
+                    name,
+                    fields,
+                    unparsed,
+                });
+            }
+        }
+    }
+    Ok(packets)
+}
+
+/// Reads the id to name mappings from the `packet` type of a direction.
+fn packet_ids(types: &Value) -> Result<Vec<(i32, String)>> {
+    let fields = types
+        .get("packet")
+        .and_then(|p| p.get(1))
+        .and_then(Value::as_array)
+        .context("Missing packet container")?;
+    let mappings = fields
+        .iter()
+        .find(|f| f.get("name").and_then(Value::as_str) == Some("name"))
+        .and_then(|f| f.get("type"))
+        .and_then(|t| t.get(1))
+        .and_then(|t| t.get("mappings"))
+        .and_then(Value::as_object)
+        .context("Missing packet id mappings")?;
+
+    let mut ids = Vec::with_capacity(mappings.len());
+    for (id, name) in mappings {
+        let id = match id.strip_prefix("0x") {
+            Some(hex) => i32::from_str_radix(hex, 16),
+            None => id.parse(),
+        }
+        .with_context(|| format!("Invalid packet id \"{}\"", id))?;
+        match name.as_str() {
+            Some(name) => ids.push((id, name.to_owned())),
+            None => bail!("Invalid packet name for id {:#04X}", id),
+        }
+    }
+    ids.sort_by_key(|(id, _)| *id);
+    Ok(ids)
+}
+
+fn parse_fields(container: &Value) -> (Vec<Field>, Vec<String>) {
+    let mut fields = Vec::new();
+    let mut unparsed = Vec::new();
+    let list = container.get(1).and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+    for field in list {
+        let name = field.get("name").and_then(Value::as_str).unwrap_or("anonymous");
+        match field.get("type").and_then(FieldType::parse) {
+            Some(ty) if unparsed.is_empty() => fields.push(Field { name: name.to_owned(), ty }),
+            _ => unparsed.push(name.to_owned()),
+        }
+    }
+    (fields, unparsed)
+}
@@ -0,0 +1,231 @@
+//! The ids of the block states of one version, generated from the vanilla
+//! block report and the blocks of the generated `blocks.rs`.
+//!
+//! The report is `generated/reports/blocks.json` of the server jar:
+//! ```text
+//! java -DbundlerMainClass=net.minecraft.data.Main -jar server.jar --reports
+//! ```
+//! States of `blocks.rs` that are missing from the report get `None`.
+//! Properties that were added to a block later, like `waterlogged` of the
+//! leaves in 1.19, take the value of the default state of the report.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+
+use crate::names::{impl_body, parse_blocks, parse_properties, Block};
+
+/// A state of the report, its properties and its id.
+struct State<'a> {
+    properties: HashMap<&'a str, &'a str>,
+    id: i64,
+    default: bool,
+}
+
+/// Generates `get_global_id_<data_version>` for `Blocks` from the source of
+/// `blocks.rs` and the block report of that version.
+pub fn generate(source: &str, report: &Value, data_version: i32) -> Result<String> {
+    let blocks = parse_blocks(impl_body(source, "Blocks").context("blocks.rs has no FromStr for Blocks")?)?;
+    let properties: HashMap<String, Vec<(String, String)>> = parse_properties(source)?.into_iter().map(|property| (property.ty, property.values)).collect();
+    let report = report.as_object().context("The block report is not an object")?;
+
+    let mut out = String::new();
+    writeln!(out, "// @generated by falcon-codegen from blocks.rs and the block report of data version {}", data_version).unwrap();
+    writeln!(out, "use super::blocks::*;").unwrap();
+    writeln!(out, "impl Blocks {{").unwrap();
+    writeln!(out, "    #[allow(unreachable_patterns)]").unwrap();
+    writeln!(out, "    pub fn get_global_id_{}(&self) -> Option<i32> {{", data_version).unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for block in &blocks {
+        let states = match report.get(&format!("minecraft:{}", block.name)) {
+            Some(entry) => parse_states(entry).with_context(|| format!("Invalid report of {}", block.name))?,
+            None => Vec::new(),
+        };
+        write_block(&mut out, block, &states, &properties)?;
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+    Ok(out)
+}
+
+fn parse_states(entry: &Value) -> Result<Vec<State<'_>>> {
+    let states = entry.get("states").and_then(Value::as_array).context("No states")?;
+    states
+        .iter()
+        .map(|state| {
+            let properties = match state.get("properties").and_then(Value::as_object) {
+                Some(properties) => string_map(properties)?,
+                None => HashMap::new(),
+            };
+            Ok(State {
+                properties,
+                id: state.get("id").and_then(Value::as_i64).context("State without id")?,
+                default: state.get("default").and_then(Value::as_bool).unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+fn string_map(properties: &Map<String, Value>) -> Result<HashMap<&str, &str>> {
+    properties
+        .iter()
+        .map(|(key, value)| Ok((key.as_str(), value.as_str().with_context(|| format!("Property {} is not a string", key))?)))
+        .collect()
+}
+
+fn write_block(out: &mut String, block: &Block, states: &[State], properties: &HashMap<String, Vec<(String, String)>>) -> Result<()> {
+    let Some(default) = states.iter().find(|state| state.default).or_else(|| states.first()) else {
+        let binding = if block.properties.is_empty() { "" } else { "(_)" };
+        writeln!(out, "            Blocks::{}{} => None,", block.variant, binding).unwrap();
+        return Ok(());
+    };
+    // properties the report has but `blocks.rs` does not
+    let added: Vec<(&str, &str)> = default
+        .properties
+        .iter()
+        .filter(|(key, _)| !block.properties.iter().any(|property| property.key == **key))
+        .map(|(key, value)| (*key, *value))
+        .collect();
+    let states = states.iter().filter(|state| added.iter().all(|(key, value)| state.properties.get(key) == Some(value)));
+
+    if block.properties.is_empty() {
+        writeln!(out, "            Blocks::{} => Some({}i32),", block.variant, default.id).unwrap();
+        return Ok(());
+    }
+    let accessors: Vec<String> = block.properties.iter().map(|property| format!("state.{}()", property.accessor)).collect();
+    writeln!(out, "            Blocks::{}(state) => match {} {{", block.variant, tuple(&accessors)).unwrap();
+    for state in states {
+        let mut patterns = Vec::with_capacity(block.properties.len());
+        for property in &block.properties {
+            // properties removed since match any value
+            let Some(value) = state.properties.get(property.key.as_str()) else {
+                patterns.push("_".to_owned());
+                continue;
+            };
+            match pattern(&property.ty, value, properties)? {
+                Some(pattern) => patterns.push(pattern),
+                // values added since do not exist in `blocks.rs`
+                None => break,
+            }
+        }
+        if patterns.len() == block.properties.len() {
+            writeln!(out, "                {} => Some({}i32),", tuple(&patterns), state.id).unwrap();
+        }
+    }
+    writeln!(out, "                _ => None,").unwrap();
+    writeln!(out, "            }},").unwrap();
+    Ok(())
+}
+
+fn tuple(items: &[String]) -> String {
+    match items {
+        [item] => format!("({},)", item),
+        items => format!("({})", items.join(", ")),
+    }
+}
+
+/// The pattern of a value of a property of type `ty`, `None` if `ty` does
+/// not have this value.
+fn pattern(ty: &str, value: &str, properties: &HashMap<String, Vec<(String, String)>>) -> Result<Option<String>> {
+    Ok(match ty {
+        "bool" => value.parse::<bool>().ok().map(|value| value.to_string()),
+        "i32" => value.parse::<i32>().ok().map(|value| value.to_string()),
+        _ => {
+            let Some(values) = properties.get(ty) else {
+                bail!("Unknown property type {}", ty);
+            };
+            values.iter().find(|(name, _)| name == value).map(|(_, variant)| format!("{}::{}", ty, variant))
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::generate;
+
+    const BLOCKS: &str = r#"impl std::str::FromStr for Blocks {
+    type Err = ParseBlockError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match name {
+            "air" => Blocks::Air,
+            "oak_planks" => Blocks::OakPlanks,
+            "oak_leaves" => {
+                let mut state_ident = OakLeavesState::default();
+                if let Some(prop) = props.get("distance") {
+                    state_ident.with_distance(i32::from_str(prop)?);
+                }
+                Blocks::OakLeaves(state_ident)
+            }
+            "oak_slab" => {
+                let mut state_ident = OakSlabState::default();
+                if let Some(prop) = props.get("waterlogged") {
+                    state_ident.with_waterlogged(bool::from_str(prop)?);
+                }
+                if let Some(prop) = props.get("typed") {
+                    state_ident.with_typed(SlabType::from_str(prop)?);
+                }
+                Blocks::OakSlab(state_ident)
+            }
+            "grass_path" => Blocks::GrassPath,
+            _ => return Err(ParseBlockError::UnknownBlock),
+        })
+    }
+}
+impl std::str::FromStr for SlabType {
+    type Err = ParseBlockError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(SlabType::Top),
+            "double" => Ok(SlabType::Double),
+            _ => Err(ParseBlockError::InvalidProperty),
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn test_generate() {
+        let report = json!({
+            "minecraft:air": { "states": [{ "id": 0, "default": true }] },
+            "minecraft:oak_planks": { "states": [{ "id": 15, "default": true }] },
+            "minecraft:oak_leaves": {
+                "properties": { "distance": ["1", "2"], "waterlogged": ["true", "false"] },
+                "states": [
+                    { "id": 30, "properties": { "distance": "1", "waterlogged": "true" } },
+                    { "id": 31, "properties": { "distance": "1", "waterlogged": "false" } },
+                    { "id": 32, "properties": { "distance": "2", "waterlogged": "true" } },
+                    { "id": 33, "default": true, "properties": { "distance": "2", "waterlogged": "false" } }
+                ]
+            },
+            "minecraft:oak_slab": {
+                "properties": { "type": ["top", "bottom", "double"], "waterlogged": ["true", "false"] },
+                "states": [
+                    { "id": 40, "properties": { "type": "top", "waterlogged": "true" } },
+                    { "id": 41, "properties": { "type": "top", "waterlogged": "false" } },
+                    { "id": 42, "properties": { "type": "bottom", "waterlogged": "true" } },
+                    { "id": 43, "default": true, "properties": { "type": "bottom", "waterlogged": "false" } },
+                    { "id": 44, "properties": { "type": "double", "waterlogged": "true" } },
+                    { "id": 45, "properties": { "type": "double", "waterlogged": "false" } }
+                ]
+            }
+        });
+        let code = generate(BLOCKS, &report, 3105).unwrap();
+
+        assert!(code.contains("pub fn get_global_id_3105(&self) -> Option<i32> {"));
+        assert!(code.contains("Blocks::OakPlanks => Some(15i32),"));
+        // added properties take their default value
+        assert!(code
+            .contains("Blocks::OakLeaves(state) => match (state.distance(),) {\n                (1,) => Some(31i32),\n                (2,) => Some(33i32),"));
+        assert!(code.contains("(SlabType::Double, false) => Some(45i32),"));
+        // values unknown to blocks.rs are left out
+        assert!(!code.contains("Some(42i32)"));
+        // removed blocks do not have an id
+        assert!(code.contains("Blocks::GrassPath => None,"));
+        assert!(generate(BLOCKS, &json!([]), 3105).is_err());
+    }
+}
@@ -0,0 +1,338 @@
+//! Parsing of formatted text from configs and commands.
+//!
+//! Legacy formatting uses codes like `§c` (red) and `§l` (bold), a color
+//! code resets the decorations before it and `§r` resets everything. Hex
+//! colors are written as `§#RRGGBB` or `§x§R§R§G§G§B§B`.
+//!
+//! Markup uses tags, closed with `</tag>` or by closing a tag around them:
+//!
+//! | Tag | Effect |
+//! |-----|--------|
+//! | `<red>`, `<color:red>`, `<#FF5555>` | color |
+//! | `<bold>`/`<b>`, `<italic>`/`<i>`, `<underlined>`/`<u>`, `<strikethrough>`/`<st>`, `<obfuscated>`/`<obf>` | decoration, `<!bold>` turns it off |
+//! | `<click:run_command:/spawn>` | click event, any action of [`ClickEvent`] |
+//! | `<hover:show_text:'<red>text'>` | hover text, itself markup |
+//! | `<insert:text>` | shift click insertion |
+//! | `<key:key.jump>` | keybind |
+//! | `<lang:key:arg:...>` | translation, the arguments are markup |
+//! | `<newline>`/`<br>` | line break |
+//! | `<reset>` | closes every tag |
+//!
+//! `\<` is a literal `<`, tags that are not understood are kept as text.
+//! Markup also understands the legacy `§` codes.
+
+use super::{ChatColor, ChatComponent, ClickEvent, Decoration, HoverEvent, Style, TextColor};
+
+impl ChatComponent {
+    /// Parses legacy formatting codes that start with `§` or `marker`, e.g.
+    /// `&` for text that is typed in.
+    pub fn from_legacy(text: &str, marker: char) -> Self { Parser::new(marker, false).parse(text) }
+
+    /// Parses markup tags and legacy `§` codes, see the [module](self)
+    /// documentation.
+    pub fn from_markup(text: &str) -> Self { Parser::new('§', true).parse(text) }
+}
+
+/// Makes `text` show as is when it is part of markup, e.g. a chat message
+/// in a format.
+pub fn escape_markup(text: &str) -> String { text.replace('<', "\\<").replace('§', "") }
+
+struct Frame {
+    /// The name of the tag, empty for the outermost frame.
+    tag: String,
+    style: Style,
+}
+
+struct Parser {
+    marker: char,
+    markup: bool,
+    frames: Vec<Frame>,
+    buffer: String,
+    buffer_style: Style,
+    parts: Vec<ChatComponent>,
+}
+
+impl Parser {
+    fn new(marker: char, markup: bool) -> Self {
+        Parser {
+            marker,
+            markup,
+            frames: vec![Frame {
+                tag: String::new(),
+                style: Style::default(),
+            }],
+            buffer: String::new(),
+            buffer_style: Style::default(),
+            parts: Vec::new(),
+        }
+    }
+
+    fn parse(mut self, text: &str) -> ChatComponent {
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            let after = &rest[c.len_utf8()..];
+            rest = if c == '§' || c == self.marker {
+                match self.legacy_code(after) {
+                    Some(len) => &after[len..],
+                    None => {
+                        self.push_char(c);
+                        after
+                    },
+                }
+            } else if self.markup && c == '\\' && after.starts_with('<') {
+                self.push_char('<');
+                &after[1..]
+            } else if self.markup && c == '<' {
+                match read_tag(after).filter(|(_, closing, parts)| self.apply_tag(*closing, parts)) {
+                    Some((len, ..)) => &after[len..],
+                    None => {
+                        self.push_char(c);
+                        after
+                    },
+                }
+            } else {
+                self.push_char(c);
+                after
+            };
+        }
+        self.flush();
+        match self.parts.len() {
+            0 => ChatComponent::empty(),
+            1 => self.parts.pop().unwrap(),
+            _ => self.parts.into_iter().fold(ChatComponent::empty(), ChatComponent::append),
+        }
+    }
+
+    fn style(&mut self) -> &mut Style { &mut self.frames.last_mut().unwrap().style }
+
+    fn push_char(&mut self, c: char) {
+        if self.frames.last().unwrap().style != self.buffer_style {
+            self.flush();
+            self.buffer_style = self.frames.last().unwrap().style.clone();
+        }
+        self.buffer.push(c);
+    }
+
+    fn push_component(&mut self, component: ChatComponent) {
+        self.flush();
+        let style = self.frames.last().unwrap().style.clone();
+        self.parts.push(component.with_style(style));
+    }
+
+    fn flush(&mut self) {
+        if !self.buffer.is_empty() {
+            let text = std::mem::take(&mut self.buffer);
+            self.parts.push(ChatComponent::text(text).with_style(self.buffer_style.clone()));
+        }
+    }
+
+    /// Applies the legacy code at the start of `text`, returns its length.
+    fn legacy_code(&mut self, text: &str) -> Option<usize> {
+        let code = text.chars().next()?.to_ascii_lowercase();
+        if let Some(color) = ChatColor::from_code(code) {
+            self.set_legacy_color(TextColor::Named(color));
+            return Some(1);
+        }
+        if let Some(decoration) = Decoration::from_code(code) {
+            self.style().set_decoration(decoration, Some(true));
+            return Some(1);
+        }
+        match code {
+            'r' => {
+                // back to the style of the enclosing tag
+                let parent = self.frames.iter().rev().nth(1).map(|frame| frame.style.clone()).unwrap_or_default();
+                *self.style() = parent;
+                Some(1)
+            },
+            '#' => {
+                let rgb = text.get(1..7).filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))?;
+                self.set_legacy_color(TextColor::Hex(u32::from_str_radix(rgb, 16).ok()?));
+                Some(7)
+            },
+            'x' => {
+                // §x§R§R§G§G§B§B
+                let mut chars = text[1..].chars();
+                let mut hex = String::with_capacity(6);
+                for _ in 0..6 {
+                    let marker = chars.next()?;
+                    let digit = chars.next().filter(char::is_ascii_hexdigit)?;
+                    if marker != '§' && marker != self.marker {
+                        return None;
+                    }
+                    hex.push(digit);
+                }
+                self.set_legacy_color(TextColor::Hex(u32::from_str_radix(&hex, 16).ok()?));
+                Some(text.len() - chars.as_str().len())
+            },
+            _ => None,
+        }
+    }
+
+    fn set_legacy_color(&mut self, color: TextColor) {
+        let style = self.style();
+        Decoration::ALL.into_iter().for_each(|decoration| style.set_decoration(decoration, None));
+        style.color = Some(color);
+    }
+
+    /// Applies a tag, returns `false` if it is not understood.
+    fn apply_tag(&mut self, closing: bool, parts: &[String]) -> bool {
+        let name = parts[0].to_ascii_lowercase();
+        if closing {
+            return match self.frames.iter().rposition(|frame| frame.tag == name) {
+                Some(index) if index > 0 => {
+                    self.frames.truncate(index);
+                    true
+                },
+                _ => false,
+            };
+        }
+        let args = &parts[1..];
+        let mut style = self.frames.last().unwrap().style.clone();
+        match (name.as_str(), args) {
+            ("newline" | "br", []) => {
+                self.push_char('\n');
+                return true;
+            },
+            ("reset", []) => {
+                self.frames.truncate(1);
+                self.frames[0].style = Style::default();
+                return true;
+            },
+            ("key", [key]) => {
+                self.push_component(ChatComponent::keybind(key.as_str()));
+                return true;
+            },
+            ("lang" | "tr" | "translate", [key, with @ ..]) => {
+                let with = with.iter().map(|arg| ChatComponent::from_markup(arg)).collect();
+                self.push_component(ChatComponent::translate(key.as_str(), with));
+                return true;
+            },
+            ("color" | "colour" | "c", [color]) => match TextColor::parse(color) {
+                Some(color) => style.color = Some(color),
+                None => return false,
+            },
+            ("click", [action, value @ ..]) if !value.is_empty() => match ClickEvent::parse(&action.to_ascii_lowercase(), value.join(":")) {
+                Some(event) => style.click_event = Some(event),
+                None => return false,
+            },
+            ("hover", [action, value @ ..]) if action.eq_ignore_ascii_case("show_text") && !value.is_empty() => {
+                style.hover_event = Some(HoverEvent::ShowText(Box::new(ChatComponent::from_markup(&value.join(":")))));
+            },
+            ("insert" | "insertion", value) if !value.is_empty() => style.insertion = Some(value.join(":")),
+            (name, []) => {
+                let (negated, name) = match name.strip_prefix('!') {
+                    Some(name) => (true, name),
+                    None => (false, name),
+                };
+                match (decoration(name), TextColor::parse(name)) {
+                    (Some(decoration), _) => style.set_decoration(decoration, Some(!negated)),
+                    (None, Some(color)) if !negated => style.color = Some(color),
+                    _ => return false,
+                }
+            },
+            _ => return false,
+        }
+        // a negated decoration is closed by its plain name
+        self.frames.push(Frame {
+            tag: name.trim_start_matches('!').to_string(),
+            style,
+        });
+        true
+    }
+}
+
+fn decoration(name: &str) -> Option<Decoration> {
+    match name {
+        "bold" | "b" => Some(Decoration::Bold),
+        "italic" | "i" | "em" => Some(Decoration::Italic),
+        "underlined" | "u" => Some(Decoration::Underlined),
+        "strikethrough" | "st" => Some(Decoration::Strikethrough),
+        "obfuscated" | "obf" => Some(Decoration::Obfuscated),
+        _ => None,
+    }
+}
+
+/// Reads a tag after its `<`, returns its length with the `>`, whether it
+/// closes a tag and its parts separated by `:`. Quoted parts may contain
+/// `:` and `>`.
+fn read_tag(text: &str) -> Option<(usize, bool, Vec<String>)> {
+    let (closing, body) = match text.strip_prefix('/') {
+        Some(body) => (true, body),
+        None => (false, text),
+    };
+    let mut parts = vec![String::new()];
+    let mut quote = None;
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), '\\') => parts.last_mut()?.push(chars.next()?.1),
+            (Some(_), c) => parts.last_mut()?.push(c),
+            (None, '\'' | '"') => quote = Some(c),
+            (None, ':') => parts.push(String::new()),
+            (None, '>') => {
+                let valid = !parts[0].is_empty() && !parts[0].contains(char::is_whitespace);
+                return valid.then_some((text.len() - body.len() + i + 1, closing, parts));
+            },
+            (None, '<') => return None,
+            (None, c) => parts.last_mut()?.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use crate::chat::{escape_markup, ChatColor, ChatComponent, ClickEvent, TextColor};
+
+    #[test]
+    fn test_legacy() {
+        let component = ChatComponent::from_legacy("&eFalcon &lserver§r&b!!! &z&", '&');
+        assert_eq!(
+            component,
+            ChatComponent::empty()
+                .append(ChatComponent::text("Falcon ").color(ChatColor::Yellow))
+                .append(ChatComponent::text("server").color(ChatColor::Yellow).bold())
+                .append(ChatComponent::text("!!! &z&").color(ChatColor::Aqua))
+        );
+        assert_eq!(component.to_legacy(), "§eFalcon §e§lserver§b!!! &z&");
+        assert_eq!(ChatComponent::from_legacy("&#12AB34hex §x§1§2§a§b§3§4too", '&').to_plain(), "hex too");
+        assert_eq!(ChatComponent::from_legacy("&#12AB34hex", '&').style().color, Some(TextColor::Hex(0x12AB34)));
+        // the plain text is one component
+        assert_eq!(ChatComponent::from_legacy("plain", '&'), ChatComponent::text("plain"));
+    }
+
+    #[test]
+    fn test_markup() {
+        let component = ChatComponent::from_markup("<red>Hi <b>there</b>, <click:open_url:https://example.com><hover:show_text:'<green>Open'>click</hover></click>!</red> \\<b> <nope>");
+        assert_eq!(
+            component,
+            ChatComponent::empty()
+                .append(ChatComponent::text("Hi ").color(ChatColor::Red))
+                .append(ChatComponent::text("there").color(ChatColor::Red).bold())
+                .append(ChatComponent::text(", ").color(ChatColor::Red))
+                .append(
+                    ChatComponent::text("click")
+                        .color(ChatColor::Red)
+                        .click(ClickEvent::OpenUrl(String::from("https://example.com")))
+                        .hover(ChatComponent::text("Open").color(ChatColor::Green))
+                )
+                .append(ChatComponent::text("!").color(ChatColor::Red))
+                .append(ChatComponent::text(" <b> <nope>"))
+        );
+
+        let component = ChatComponent::from_markup("<#FF0000><!italic>a<key:key.jump><reset><lang:chat.type.text:'<gold>Steve':hi>");
+        assert_eq!(
+            component,
+            ChatComponent::empty()
+                .append(ChatComponent::text("a").color(TextColor::Hex(0xFF0000)).decoration(crate::chat::Decoration::Italic, false))
+                .append(ChatComponent::keybind("key.jump").color(TextColor::Hex(0xFF0000)).decoration(crate::chat::Decoration::Italic, false))
+                .append(ChatComponent::translate(
+                    "chat.type.text",
+                    vec![ChatComponent::text("Steve").color(ChatColor::Gold), ChatComponent::text("hi")]
+                ))
+        );
+        assert_eq!(ChatComponent::from_markup("§eFalcon server§r§b!!!").to_legacy(), "§eFalcon server§b!!!");
+        assert_eq!(ChatComponent::from_markup(&escape_markup("<red>a\\<b>§c")).to_plain(), "<red>a\\<b>c");
+    }
+}
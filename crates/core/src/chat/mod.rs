@@ -0,0 +1,245 @@
+//! Chat components, the formatted text of chat messages, kick messages
+//! and the server list.
+//!
+//! Components are made with the builder methods of [`ChatComponent`] or
+//! parsed from configured text, see [`ChatComponent::from_legacy`] and
+//! [`ChatComponent::from_markup`].
+
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+pub use self::format::escape_markup;
+pub use self::style::{ChatColor, ClickEvent, Decoration, HoverEvent, Style, TextColor};
+
+mod format;
+mod style;
+
+/// The first protocol with hex colors, 1.16.
+const HEX_COLOR_PROTOCOL: i32 = 735;
+/// The first protocol with keybind components, 1.12.
+const KEYBIND_PROTOCOL: i32 = 335;
+
+/// What a component shows.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Content {
+    Text(String),
+    /// A translation key of the client with its arguments.
+    Translate { key: String, with: Vec<ChatComponent> },
+    /// The key bound to a control, e.g. `key.jump`.
+    Keybind(String),
+}
+
+/// Formatted text with children that inherit its style.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChatComponent {
+    content: Content,
+    style: Style,
+    extra: Vec<ChatComponent>,
+}
+
+impl ChatComponent {
+    pub fn new(content: Content) -> Self {
+        ChatComponent {
+            content,
+            style: Style::default(),
+            extra: Vec::new(),
+        }
+    }
+
+    pub fn text(text: impl Into<String>) -> Self { ChatComponent::new(Content::Text(text.into())) }
+
+    /// An empty component, e.g. to hold children.
+    pub fn empty() -> Self { ChatComponent::text("") }
+
+    pub fn translate(key: impl Into<String>, with: Vec<ChatComponent>) -> Self { ChatComponent::new(Content::Translate { key: key.into(), with }) }
+
+    pub fn keybind(key: impl Into<String>) -> Self { ChatComponent::new(Content::Keybind(key.into())) }
+
+    pub fn content(&self) -> &Content { &self.content }
+
+    pub fn style(&self) -> &Style { &self.style }
+
+    pub fn style_mut(&mut self) -> &mut Style { &mut self.style }
+
+    pub fn extra(&self) -> &[ChatComponent] { &self.extra }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn color(mut self, color: impl Into<TextColor>) -> Self {
+        self.style.color = Some(color.into());
+        self
+    }
+
+    /// Sets the color unless this component has one already.
+    pub fn color_if_absent(mut self, color: impl Into<TextColor>) -> Self {
+        self.style.color.get_or_insert(color.into());
+        self
+    }
+
+    pub fn decoration(mut self, decoration: Decoration, value: bool) -> Self {
+        self.style.set_decoration(decoration, Some(value));
+        self
+    }
+
+    pub fn bold(self) -> Self { self.decoration(Decoration::Bold, true) }
+
+    pub fn italic(self) -> Self { self.decoration(Decoration::Italic, true) }
+
+    pub fn underlined(self) -> Self { self.decoration(Decoration::Underlined, true) }
+
+    pub fn strikethrough(self) -> Self { self.decoration(Decoration::Strikethrough, true) }
+
+    pub fn obfuscated(self) -> Self { self.decoration(Decoration::Obfuscated, true) }
+
+    /// Text that is inserted in the chat box on shift click.
+    pub fn insertion(mut self, insertion: impl Into<String>) -> Self {
+        self.style.insertion = Some(insertion.into());
+        self
+    }
+
+    pub fn click(mut self, event: ClickEvent) -> Self {
+        self.style.click_event = Some(event);
+        self
+    }
+
+    /// Shows `text` when hovering over this component.
+    pub fn hover(mut self, text: impl Into<ChatComponent>) -> Self {
+        self.style.hover_event = Some(HoverEvent::ShowText(Box::new(text.into())));
+        self
+    }
+
+    pub fn append(mut self, child: impl Into<ChatComponent>) -> Self {
+        self.extra.push(child.into());
+        self
+    }
+
+    pub fn push(&mut self, child: impl Into<ChatComponent>) { self.extra.push(child.into()); }
+
+    /// The text without formatting, translations and keybinds are shown
+    /// as their key.
+    pub fn to_plain(&self) -> String {
+        let mut plain = String::new();
+        self.walk(&Style::default(), &mut |content, _| match content {
+            Content::Text(text) => plain.push_str(text),
+            Content::Translate { key, .. } | Content::Keybind(key) => plain.push_str(key),
+        });
+        plain
+    }
+
+    /// The text with legacy `§` formatting codes, hex colors become the
+    /// nearest named color. Events are lost.
+    pub fn to_legacy(&self) -> String {
+        let mut legacy = String::new();
+        let mut last = (None, [false; 5]);
+        self.walk(&Style::default(), &mut |content, style| {
+            let text = match content {
+                Content::Text(text) => text.as_str(),
+                Content::Translate { key, .. } | Content::Keybind(key) => key.as_str(),
+            };
+            if text.is_empty() {
+                return;
+            }
+            let current = (style.color.map(TextColor::to_named), Decoration::ALL.map(|decoration| style.decoration(decoration) == Some(true)));
+            if current != last {
+                // a color code resets the decorations too
+                match current.0 {
+                    Some(color) => legacy.extend(['§', color.code()]),
+                    None => legacy.push_str("§r"),
+                }
+                for (decoration, _) in Decoration::ALL.into_iter().zip(current.1).filter(|(_, set)| *set) {
+                    legacy.extend(['§', decoration.code()]);
+                }
+                last = current;
+            }
+            legacy.push_str(text);
+        });
+        legacy
+    }
+
+    /// Replaces what `protocol` does not support, hex colors before 1.16
+    /// and keybinds before 1.12.
+    pub fn for_protocol(mut self, protocol: i32) -> Self {
+        if !(0..HEX_COLOR_PROTOCOL).contains(&protocol) {
+            return self;
+        }
+        if let Some(TextColor::Hex(rgb)) = self.style.color {
+            self.style.color = Some(TextColor::Named(ChatColor::nearest(rgb)));
+        }
+        if let Some(HoverEvent::ShowText(text)) = self.style.hover_event.take() {
+            self.style.hover_event = Some(HoverEvent::ShowText(Box::new(text.for_protocol(protocol))));
+        }
+        self.content = match self.content {
+            Content::Keybind(key) if protocol < KEYBIND_PROTOCOL => Content::Text(key),
+            Content::Translate { key, with } => Content::Translate {
+                key,
+                with: with.into_iter().map(|arg| arg.for_protocol(protocol)).collect(),
+            },
+            content => content,
+        };
+        self.extra = self.extra.into_iter().map(|child| child.for_protocol(protocol)).collect();
+        self
+    }
+
+    /// Visits every component in order with its inherited style.
+    fn walk<F: FnMut(&Content, &Style)>(&self, parent: &Style, visit: &mut F) {
+        let style = self.style.inherit(parent);
+        visit(&self.content, &style);
+        for child in &self.extra {
+            child.walk(&style, visit);
+        }
+    }
+}
+
+impl From<String> for ChatComponent {
+    fn from(text: String) -> Self { ChatComponent::text(text) }
+}
+
+impl From<&str> for ChatComponent {
+    fn from(text: &str) -> Self { ChatComponent::text(text) }
+}
+
+impl Serialize for ChatComponent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match &self.content {
+            Content::Text(text) => map.serialize_entry("text", text)?,
+            Content::Translate { key, with } => {
+                map.serialize_entry("translate", key)?;
+                if !with.is_empty() {
+                    map.serialize_entry("with", with)?;
+                }
+            },
+            Content::Keybind(key) => map.serialize_entry("keybind", key)?,
+        }
+        self.style.serialize_fields(&mut map)?;
+        if !self.extra.is_empty() {
+            map.serialize_entry("extra", &self.extra)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChatColor, ChatComponent, ClickEvent, TextColor};
+
+    #[test]
+    fn test_serialize() {
+        let component = ChatComponent::text("Hello ")
+            .color(ChatColor::Gold)
+            .append(ChatComponent::text("world").bold().click(ClickEvent::RunCommand(String::from("/spawn"))).hover("Teleport"))
+            .append(ChatComponent::keybind("key.jump").color(TextColor::Hex(0x12AB34)));
+        assert_eq!(
+            serde_json::to_string(&component).unwrap(),
+            r##"{"text":"Hello ","color":"gold","extra":[{"text":"world","bold":true,"clickEvent":{"action":"run_command","value":"/spawn"},"hoverEvent":{"action":"show_text","value":{"text":"Teleport"}}},{"keybind":"key.jump","color":"#12AB34"}]}"##
+        );
+        assert_eq!(component.to_plain(), "Hello worldkey.jump");
+        assert_eq!(component.to_legacy(), "§6Hello §6§lworld§2key.jump");
+
+        let old = component.for_protocol(47);
+        assert_eq!(old.extra()[1], ChatComponent::text("key.jump").color(ChatColor::DarkGreen));
+    }
+}
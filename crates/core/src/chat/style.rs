@@ -0,0 +1,327 @@
+use std::fmt::{Display, Formatter};
+
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+use super::ChatComponent;
+
+/// The 16 colors of the chat with their legacy formatting codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChatColor {
+    Black,
+    DarkBlue,
+    DarkGreen,
+    DarkAqua,
+    DarkRed,
+    DarkPurple,
+    Gold,
+    Gray,
+    DarkGray,
+    Blue,
+    Green,
+    Aqua,
+    Red,
+    LightPurple,
+    Yellow,
+    White,
+}
+
+impl ChatColor {
+    pub const ALL: [ChatColor; 16] = [
+        ChatColor::Black,
+        ChatColor::DarkBlue,
+        ChatColor::DarkGreen,
+        ChatColor::DarkAqua,
+        ChatColor::DarkRed,
+        ChatColor::DarkPurple,
+        ChatColor::Gold,
+        ChatColor::Gray,
+        ChatColor::DarkGray,
+        ChatColor::Blue,
+        ChatColor::Green,
+        ChatColor::Aqua,
+        ChatColor::Red,
+        ChatColor::LightPurple,
+        ChatColor::Yellow,
+        ChatColor::White,
+    ];
+
+    /// The name in the JSON chat format, e.g. `dark_blue`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ChatColor::Black => "black",
+            ChatColor::DarkBlue => "dark_blue",
+            ChatColor::DarkGreen => "dark_green",
+            ChatColor::DarkAqua => "dark_aqua",
+            ChatColor::DarkRed => "dark_red",
+            ChatColor::DarkPurple => "dark_purple",
+            ChatColor::Gold => "gold",
+            ChatColor::Gray => "gray",
+            ChatColor::DarkGray => "dark_gray",
+            ChatColor::Blue => "blue",
+            ChatColor::Green => "green",
+            ChatColor::Aqua => "aqua",
+            ChatColor::Red => "red",
+            ChatColor::LightPurple => "light_purple",
+            ChatColor::Yellow => "yellow",
+            ChatColor::White => "white",
+        }
+    }
+
+    /// The legacy formatting code, `0` to `f`.
+    pub fn code(self) -> char { char::from_digit(self as u32, 16).unwrap() }
+
+    pub fn from_code(code: char) -> Option<Self> { code.to_digit(16).map(|index| ChatColor::ALL[index as usize]) }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "grey" => Some(ChatColor::Gray),
+            "dark_grey" => Some(ChatColor::DarkGray),
+            name => ChatColor::ALL.into_iter().find(|color| color.name() == name),
+        }
+    }
+
+    /// The color as `0xRRGGBB`.
+    pub fn rgb(self) -> u32 {
+        match self {
+            ChatColor::Black => 0x000000,
+            ChatColor::DarkBlue => 0x0000AA,
+            ChatColor::DarkGreen => 0x00AA00,
+            ChatColor::DarkAqua => 0x00AAAA,
+            ChatColor::DarkRed => 0xAA0000,
+            ChatColor::DarkPurple => 0xAA00AA,
+            ChatColor::Gold => 0xFFAA00,
+            ChatColor::Gray => 0xAAAAAA,
+            ChatColor::DarkGray => 0x555555,
+            ChatColor::Blue => 0x5555FF,
+            ChatColor::Green => 0x55FF55,
+            ChatColor::Aqua => 0x55FFFF,
+            ChatColor::Red => 0xFF5555,
+            ChatColor::LightPurple => 0xFF55FF,
+            ChatColor::Yellow => 0xFFFF55,
+            ChatColor::White => 0xFFFFFF,
+        }
+    }
+
+    /// The color closest to `rgb`, for clients without hex colors.
+    pub fn nearest(rgb: u32) -> Self {
+        let channels = |rgb: u32| [(rgb >> 16) as i32 & 0xFF, (rgb >> 8) as i32 & 0xFF, rgb as i32 & 0xFF];
+        let target = channels(rgb);
+        ChatColor::ALL
+            .into_iter()
+            .min_by_key(|color| channels(color.rgb()).iter().zip(target).map(|(a, b)| (a - b).pow(2)).sum::<i32>())
+            .unwrap()
+    }
+}
+
+/// A named color or, since 1.16, any color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextColor {
+    Named(ChatColor),
+    Hex(u32),
+}
+
+impl TextColor {
+    /// Parses a color name or `#RRGGBB`.
+    pub fn parse(color: &str) -> Option<Self> {
+        match color.strip_prefix('#') {
+            Some(hex) if hex.len() == 6 => u32::from_str_radix(hex, 16).ok().map(TextColor::Hex),
+            Some(_) => None,
+            None => ChatColor::from_name(&color.to_ascii_lowercase()).map(TextColor::Named),
+        }
+    }
+
+    /// The nearest named color.
+    pub fn to_named(self) -> ChatColor {
+        match self {
+            TextColor::Named(color) => color,
+            TextColor::Hex(rgb) => ChatColor::nearest(rgb),
+        }
+    }
+}
+
+impl From<ChatColor> for TextColor {
+    fn from(color: ChatColor) -> Self { TextColor::Named(color) }
+}
+
+impl Display for TextColor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextColor::Named(color) => f.write_str(color.name()),
+            TextColor::Hex(rgb) => write!(f, "#{:06X}", rgb),
+        }
+    }
+}
+
+impl Serialize for TextColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> { serializer.collect_str(self) }
+}
+
+/// The formatting that can be turned on and off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decoration {
+    Bold,
+    Italic,
+    Underlined,
+    Strikethrough,
+    Obfuscated,
+}
+
+impl Decoration {
+    pub const ALL: [Decoration; 5] = [Decoration::Bold, Decoration::Italic, Decoration::Underlined, Decoration::Strikethrough, Decoration::Obfuscated];
+
+    /// The legacy formatting code, `l`, `o`, `n`, `m` or `k`.
+    pub fn code(self) -> char {
+        match self {
+            Decoration::Bold => 'l',
+            Decoration::Italic => 'o',
+            Decoration::Underlined => 'n',
+            Decoration::Strikethrough => 'm',
+            Decoration::Obfuscated => 'k',
+        }
+    }
+
+    pub fn from_code(code: char) -> Option<Self> { Decoration::ALL.into_iter().find(|decoration| decoration.code() == code) }
+}
+
+/// What happens when a player clicks on a component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClickEvent {
+    OpenUrl(String),
+    RunCommand(String),
+    SuggestCommand(String),
+    /// Only in books.
+    ChangePage(u32),
+    /// Since 1.15.
+    CopyToClipboard(String),
+}
+
+impl ClickEvent {
+    /// Parses the action name of the JSON chat format with its value.
+    pub fn parse(action: &str, value: String) -> Option<Self> {
+        match action {
+            "open_url" => Some(ClickEvent::OpenUrl(value)),
+            "run_command" => Some(ClickEvent::RunCommand(value)),
+            "suggest_command" => Some(ClickEvent::SuggestCommand(value)),
+            "change_page" => value.parse().ok().map(ClickEvent::ChangePage),
+            "copy_to_clipboard" => Some(ClickEvent::CopyToClipboard(value)),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for ClickEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (action, value) = match self {
+            ClickEvent::OpenUrl(url) => ("open_url", url.clone()),
+            ClickEvent::RunCommand(command) => ("run_command", command.clone()),
+            ClickEvent::SuggestCommand(command) => ("suggest_command", command.clone()),
+            ClickEvent::ChangePage(page) => ("change_page", page.to_string()),
+            ClickEvent::CopyToClipboard(text) => ("copy_to_clipboard", text.clone()),
+        };
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("action", action)?;
+        map.serialize_entry("value", &value)?;
+        map.end()
+    }
+}
+
+/// What is shown when a player hovers over a component.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HoverEvent {
+    ShowText(Box<ChatComponent>),
+}
+
+/// Uses `value`, which every supported version understands.
+impl Serialize for HoverEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        match self {
+            HoverEvent::ShowText(text) => {
+                map.serialize_entry("action", "show_text")?;
+                map.serialize_entry("value", text)?;
+            },
+        }
+        map.end()
+    }
+}
+
+/// The formatting of a component, unset fields are inherited from the
+/// parent component.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Style {
+    pub color: Option<TextColor>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+    /// Text inserted in the chat box on shift click.
+    pub insertion: Option<String>,
+    pub click_event: Option<ClickEvent>,
+    pub hover_event: Option<HoverEvent>,
+}
+
+impl Style {
+    pub fn decoration(&self, decoration: Decoration) -> Option<bool> {
+        match decoration {
+            Decoration::Bold => self.bold,
+            Decoration::Italic => self.italic,
+            Decoration::Underlined => self.underlined,
+            Decoration::Strikethrough => self.strikethrough,
+            Decoration::Obfuscated => self.obfuscated,
+        }
+    }
+
+    pub fn set_decoration(&mut self, decoration: Decoration, value: Option<bool>) {
+        match decoration {
+            Decoration::Bold => self.bold = value,
+            Decoration::Italic => self.italic = value,
+            Decoration::Underlined => self.underlined = value,
+            Decoration::Strikethrough => self.strikethrough = value,
+            Decoration::Obfuscated => self.obfuscated = value,
+        }
+    }
+
+    /// This style with the unset fields taken from `parent`.
+    pub fn inherit(&self, parent: &Style) -> Style {
+        Style {
+            color: self.color.or(parent.color),
+            bold: self.bold.or(parent.bold),
+            italic: self.italic.or(parent.italic),
+            underlined: self.underlined.or(parent.underlined),
+            strikethrough: self.strikethrough.or(parent.strikethrough),
+            obfuscated: self.obfuscated.or(parent.obfuscated),
+            insertion: self.insertion.clone().or_else(|| parent.insertion.clone()),
+            click_event: self.click_event.clone().or_else(|| parent.click_event.clone()),
+            hover_event: self.hover_event.clone().or_else(|| parent.hover_event.clone()),
+        }
+    }
+
+    pub(crate) fn serialize_fields<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        if let Some(color) = &self.color {
+            map.serialize_entry("color", color)?;
+        }
+        for (name, value) in [
+            ("bold", self.bold),
+            ("italic", self.italic),
+            ("underlined", self.underlined),
+            ("strikethrough", self.strikethrough),
+            ("obfuscated", self.obfuscated),
+        ] {
+            if let Some(value) = value {
+                map.serialize_entry(name, &value)?;
+            }
+        }
+        if let Some(insertion) = &self.insertion {
+            map.serialize_entry("insertion", insertion)?;
+        }
+        if let Some(click_event) = &self.click_event {
+            map.serialize_entry("clickEvent", click_event)?;
+        }
+        if let Some(hover_event) = &self.hover_event {
+            map.serialize_entry("hoverEvent", hover_event)?;
+        }
+        Ok(())
+    }
+}
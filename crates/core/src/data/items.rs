@@ -0,0 +1,176 @@
+//! The numeric ids of items, which differ between versions.
+//!
+//! The ids are read from the `registries.json` report of a version, vanilla
+//! writes it with `java -DbundlerMainClass=net.minecraft.data.Main -jar
+//! server.jar --reports`. Each report is saved as `<protocol>.json`:
+//! ```json
+//! {
+//!   "minecraft:item": {
+//!     "entries": { "minecraft:air": { "protocol_id": 0 }, "minecraft:stone": { "protocol_id": 1 } }
+//!   }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use ahash::AHashMap;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use thiserror::Error;
+use tracing::warn;
+
+static INSTANCE: OnceCell<ProtocolItems> = OnceCell::new();
+
+const ITEM_REGISTRY: &str = "minecraft:item";
+
+#[derive(Error, Debug)]
+pub enum ItemError {
+    #[error("Error while accessing an item report")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid item report {0:?}: {1}")]
+    Json(PathBuf, serde_json::Error),
+    #[error("The report {0:?} has no item registry")]
+    MissingItems(PathBuf),
+}
+
+/// The item ids of one protocol.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemRegistry {
+    ids: AHashMap<String, i32>,
+    names: AHashMap<i32, String>,
+}
+
+impl ItemRegistry {
+    /// Reads the items of a `registries.json` report.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ItemError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        let mut report: BTreeMap<String, RegistryReport> = serde_json::from_str(&contents).map_err(|e| ItemError::Json(path.to_path_buf(), e))?;
+        let items = report.remove(ITEM_REGISTRY).ok_or_else(|| ItemError::MissingItems(path.to_path_buf()))?;
+        let mut registry = ItemRegistry::default();
+        for (name, entry) in items.entries {
+            registry.insert(&name, entry.protocol_id);
+        }
+        Ok(registry)
+    }
+
+    /// Adds or replaces an item.
+    pub fn insert(&mut self, item: &str, id: i32) {
+        let item = namespaced(item);
+        self.names.insert(id, item.clone());
+        self.ids.insert(item, id);
+    }
+
+    /// The id of an item like `minecraft:diamond`, the namespace can be
+    /// left out for vanilla items.
+    pub fn id(&self, item: &str) -> Option<i32> {
+        match item.contains(':') {
+            true => self.ids.get(item).copied(),
+            false => self.ids.get(&namespaced(item)).copied(),
+        }
+    }
+
+    /// The name of an item id, e.g. of a slot a client sent.
+    pub fn name(&self, id: i32) -> Option<&str> { self.names.get(&id).map(String::as_str) }
+
+    pub fn len(&self) -> usize { self.ids.len() }
+
+    pub fn is_empty(&self) -> bool { self.ids.is_empty() }
+}
+
+fn namespaced(item: &str) -> String {
+    match item.contains(':') {
+        true => item.to_owned(),
+        false => format!("minecraft:{}", item),
+    }
+}
+
+#[derive(Deserialize)]
+struct RegistryReport {
+    entries: BTreeMap<String, EntryReport>,
+}
+
+#[derive(Deserialize)]
+struct EntryReport {
+    protocol_id: i32,
+}
+
+/// The item ids of every protocol with a report, items cannot be sent to
+/// other protocols.
+#[derive(Debug, Default)]
+pub struct ProtocolItems {
+    protocols: AHashMap<i32, ItemRegistry>,
+}
+
+impl ProtocolItems {
+    /// The global items, empty unless [`ProtocolItems::init_global`] was
+    /// called.
+    pub fn global() -> &'static ProtocolItems { INSTANCE.get_or_init(ProtocolItems::default) }
+
+    /// Installs these items as the global ones, returns them back when the
+    /// global items were already in use.
+    pub fn init_global(self) -> Result<(), ProtocolItems> { INSTANCE.set(self) }
+
+    /// Reads the `<protocol>.json` reports of `dir`. Reports that cannot be
+    /// read are skipped with a warning.
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Self {
+        let mut items = ProtocolItems::default();
+        let entries = match std::fs::read_dir(dir.as_ref()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return items,
+            Err(error) => {
+                warn!(dir = ?dir.as_ref(), %error, "Could not read the item directory");
+                return items;
+            },
+        };
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            let Some(protocol) = path.file_stem().and_then(|name| name.to_str()).and_then(|name| name.parse().ok()) else {
+                continue;
+            };
+            match ItemRegistry::load(&path) {
+                Ok(registry) => items.insert(protocol, registry),
+                Err(error) => warn!(?path, %error, "Invalid item report"),
+            }
+        }
+        items
+    }
+
+    pub fn insert(&mut self, protocol: i32, items: ItemRegistry) { self.protocols.insert(protocol, items); }
+
+    pub fn get(&self, protocol: i32) -> Option<&ItemRegistry> { self.protocols.get(&protocol) }
+
+    /// The id of an item for clients of `protocol`.
+    pub fn item_id(&self, item: &str, protocol: i32) -> Option<i32> { self.get(protocol)?.id(item) }
+
+    /// The number of protocols with items.
+    pub fn len(&self) -> usize { self.protocols.len() }
+
+    pub fn is_empty(&self) -> bool { self.protocols.is_empty() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_item_report() {
+        let dir = std::env::temp_dir().join(format!("falcon-items-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let report = r#"{
+            "minecraft:block": {"entries": {"minecraft:stone": {"protocol_id": 1}}},
+            "minecraft:item": {"entries": {"minecraft:air": {"protocol_id": 0}, "minecraft:diamond": {"protocol_id": 798}}}
+        }"#;
+        std::fs::write(dir.join("763.json"), report).unwrap();
+        std::fs::write(dir.join("762.json"), r#"{"minecraft:block": {"entries": {}}}"#).unwrap();
+        let items = ProtocolItems::load_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items.item_id("minecraft:diamond", 763), Some(798));
+        assert_eq!(items.item_id("diamond", 763), Some(798));
+        assert_eq!(items.item_id("minecraft:stone", 763), None);
+        assert_eq!(items.item_id("minecraft:diamond", 762), None);
+        assert_eq!(items.get(763).unwrap().name(0), Some("minecraft:air"));
+    }
+}
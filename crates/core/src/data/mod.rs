@@ -1,3 +1,4 @@
-pub use identifier::Identifier;
+pub use falcon_packet_core::{Identifier, IdentifierError};
 
-mod identifier;
+pub mod items;
+pub mod tags;
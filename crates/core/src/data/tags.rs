@@ -0,0 +1,151 @@
+//! Tags, named groups of registry entries like `minecraft:water`, which
+//! 1.13+ clients expect after joining.
+//!
+//! The ids of the entries differ between versions, a tag file holds the
+//! tags of a single protocol:
+//! ```json
+//! {
+//!   "minecraft:fluid": { "minecraft:water": [1, 2], "minecraft:lava": [3, 4] },
+//!   "minecraft:block": { "minecraft:logs": [35, 36, 37] }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use ahash::AHashMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+pub const BLOCK: &str = "minecraft:block";
+pub const ITEM: &str = "minecraft:item";
+pub const FLUID: &str = "minecraft:fluid";
+/// Since 1.14.
+pub const ENTITY_TYPE: &str = "minecraft:entity_type";
+
+#[derive(Error, Debug)]
+pub enum TagError {
+    #[error("Error while accessing a tag file")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid tag file {0:?}: {1}")]
+    Json(PathBuf, serde_json::Error),
+}
+
+/// The tags of every registry for one protocol.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TagRegistry {
+    registries: BTreeMap<String, BTreeMap<String, Vec<i32>>>,
+}
+
+impl TagRegistry {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TagError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(|e| TagError::Json(path.to_path_buf(), e))
+    }
+
+    /// The tags every version shares, fluid ids have not changed since
+    /// 1.13. Blocks and items need the registries of a version.
+    pub fn generated() -> Self {
+        let mut tags = TagRegistry::default();
+        tags.insert(FLUID, "minecraft:water", vec![1, 2]);
+        tags.insert(FLUID, "minecraft:lava", vec![3, 4]);
+        tags
+    }
+
+    /// Adds or replaces a tag.
+    pub fn insert(&mut self, registry: &str, tag: &str, ids: Vec<i32>) {
+        self.registries.entry(registry.to_owned()).or_default().insert(tag.to_owned(), ids);
+    }
+
+    /// Adds the tags of `other`, replacing tags with the same name.
+    pub fn merge(&mut self, other: TagRegistry) {
+        for (registry, tags) in other.registries {
+            self.registries.entry(registry).or_default().extend(tags);
+        }
+    }
+
+    pub fn get(&self, registry: &str, tag: &str) -> Option<&[i32]> { self.registries.get(registry)?.get(tag).map(Vec::as_slice) }
+
+    /// The tags of `registry`, sorted by name.
+    pub fn tags(&self, registry: &str) -> impl Iterator<Item = (&str, &[i32])> {
+        self.registries.get(registry).into_iter().flatten().map(|(tag, ids)| (tag.as_str(), ids.as_slice()))
+    }
+
+    /// The names of the registries with tags.
+    pub fn registries(&self) -> impl Iterator<Item = &str> { self.registries.keys().map(String::as_str) }
+}
+
+/// The tags of every protocol, protocols without a tag file get the
+/// [generated](TagRegistry::generated) tags.
+#[derive(Debug)]
+pub struct ProtocolTags {
+    protocols: AHashMap<i32, TagRegistry>,
+    generated: TagRegistry,
+}
+
+impl ProtocolTags {
+    /// Reads the `<protocol>.json` files of `dir`, on top of the generated
+    /// tags. Files that cannot be read are skipped with a warning.
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Self {
+        let mut tags = ProtocolTags::default();
+        let entries = match std::fs::read_dir(dir.as_ref()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return tags,
+            Err(error) => {
+                warn!(dir = ?dir.as_ref(), %error, "Could not read the tag directory");
+                return tags;
+            },
+        };
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            let Some(protocol) = path.file_stem().and_then(|name| name.to_str()).and_then(|name| name.parse().ok()) else {
+                continue;
+            };
+            match TagRegistry::load(&path) {
+                Ok(file) => tags.insert(protocol, file),
+                Err(error) => warn!(?path, %error, "Invalid tag file"),
+            }
+        }
+        tags
+    }
+
+    /// Sets the tags of `protocol`, on top of the generated tags.
+    pub fn insert(&mut self, protocol: i32, tags: TagRegistry) {
+        let mut registry = self.generated.clone();
+        registry.merge(tags);
+        self.protocols.insert(protocol, registry);
+    }
+
+    pub fn get(&self, protocol: i32) -> &TagRegistry { self.protocols.get(&protocol).unwrap_or(&self.generated) }
+}
+
+impl Default for ProtocolTags {
+    fn default() -> Self {
+        ProtocolTags {
+            protocols: AHashMap::new(),
+            generated: TagRegistry::generated(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_protocol_tags() {
+        let mut file: TagRegistry = serde_json::from_str(r#"{"minecraft:block": {"minecraft:logs": [35, 36]}, "minecraft:fluid": {"minecraft:lava": []}}"#).unwrap();
+        file.insert(ITEM, "minecraft:planks", vec![13]);
+        let mut tags = ProtocolTags::default();
+        tags.insert(578, file);
+
+        let tags_578 = tags.get(578);
+        assert_eq!(tags_578.get(BLOCK, "minecraft:logs"), Some(&[35, 36][..]));
+        assert_eq!(tags_578.get(FLUID, "minecraft:water"), Some(&[1, 2][..]));
+        assert_eq!(tags_578.get(FLUID, "minecraft:lava"), Some(&[][..]));
+        assert_eq!(tags_578.registries().collect::<Vec<_>>(), [BLOCK, FLUID, ITEM]);
+        assert_eq!(tags.get(763), &TagRegistry::generated());
+    }
+}
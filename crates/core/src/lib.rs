@@ -1,5 +1,6 @@
 extern crate self as falcon_core;
 
+pub mod chat;
 pub mod data;
 pub mod error;
 pub mod network;
@@ -2,9 +2,12 @@
 
 use std::fmt::{Display, Formatter};
 
+use falcon_packet_core::protocol::ProtocolState;
 use uuid::Uuid;
 
-pub mod util;
+use self::registry::{ProtocolRegistry, StateIds};
+
+pub mod registry;
 
 pub const UNKNOWN_PROTOCOL: i32 = -1;
 
@@ -13,6 +16,9 @@ pub struct PacketHandlerState {
     pub uuid: Option<Uuid>,
     pub last_keep_alive: u64,
     pub protocol_id: i32,
+    /// The protocol the client announced when it is handled as
+    /// `protocol_id` through the [`ProtocolRegistry`].
+    pub protocol_alias: Option<i32>,
     pub connection_state: ConnectionState,
 }
 
@@ -26,9 +32,57 @@ impl PacketHandlerState {
             uuid: None,
             last_keep_alive: 0,
             protocol_id,
+            protocol_alias: None,
             connection_state: ConnectionState::Handshake,
         }
     }
+
+    /// Sets the protocol announced by the client, resolving it through the
+    /// global [`ProtocolRegistry`].
+    pub fn set_protocol(&mut self, version: i32) {
+        match ProtocolRegistry::global().get(version) {
+            Some(table) => {
+                self.protocol_id = table.base();
+                self.protocol_alias = Some(version);
+            },
+            None => {
+                self.protocol_id = version;
+                self.protocol_alias = None;
+            },
+        }
+    }
+
+    /// The protocol the client announced.
+    pub fn client_protocol(&self) -> i32 { self.protocol_alias.unwrap_or(self.protocol_id) }
+
+    /// The packet id translations for the current state, `None` when the
+    /// protocol is supported natively.
+    pub fn packet_ids(&self) -> Option<&'static StateIds> {
+        self.protocol_alias
+            .and_then(|alias| ProtocolRegistry::global().get(alias))
+            .and_then(|table| table.ids(self.connection_state))
+    }
+}
+
+/// The address a client connected to as sent in its handshake, e.g. to
+/// tell apart the domains that point to the server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VirtualHost {
+    /// The lowercase host name without a trailing dot.
+    pub host: String,
+    pub port: u16,
+}
+
+impl VirtualHost {
+    /// Cleans up the address of a handshake, Forge and BungeeCord append
+    /// their own data after a nul character.
+    pub fn from_handshake(address: &str, port: u16) -> Self {
+        let host = address.split('\0').next().unwrap_or_default().trim_end_matches('.');
+        VirtualHost {
+            host: host.to_ascii_lowercase(),
+            port,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -39,3 +93,16 @@ pub enum ConnectionState {
     Play,
     Disconnected,
 }
+
+impl ConnectionState {
+    /// The state of the protocol, a disconnected connection has none.
+    pub fn protocol_state(self) -> Option<ProtocolState> {
+        match self {
+            ConnectionState::Handshake => Some(ProtocolState::Handshake),
+            ConnectionState::Status => Some(ProtocolState::Status),
+            ConnectionState::Login => Some(ProtocolState::Login),
+            ConnectionState::Play => Some(ProtocolState::Play),
+            ConnectionState::Disconnected => None,
+        }
+    }
+}
@@ -0,0 +1,455 @@
+//! Packet id tables for protocol versions that are not compiled in.
+//!
+//! New minecraft releases often only insert a packet, which shifts the ids
+//! of the packets after it. A [`ProtocolTable`] describes such a release as
+//! an alias of a supported `base` version together with the ids that moved,
+//! so server owners can support it without recompiling.
+//!
+//! The base may also be another table, the release then inherits the ids
+//! of that table and only lists what moved since. Adding the next release
+//! that only moves ids is a single table entry.
+//!
+//! Outgoing packets are written for the base version and sent with the ids
+//! of the table, see `falcon_send::layout`.
+//!
+//! Tables are loaded from TOML or JSON assets:
+//! ```toml
+//! [[protocols]]
+//! version = 737
+//! base = 736
+//! name = "1.16.2-rc1"
+//!
+//! [protocols.play.clientbound]
+//! # every id from 0x20 on moves up by one
+//! shift = [{ from = 0x20, by = 1 }]
+//! # explicit moves as [base id, new id]
+//! remap = [[0x1F, 0x20]]
+//!
+//! [[protocols]]
+//! version = 738
+//! # inherits the ids of 737, handled as 736
+//! base = 737
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use ahash::AHashMap;
+use falcon_packet_core::protocol::ProtocolState;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::network::ConnectionState;
+use crate::server::config::FalconConfig;
+
+static INSTANCE: OnceCell<ProtocolRegistry> = OnceCell::new();
+
+/// Shifts only apply to ids up to this value, explicit remaps have no limit.
+const MAX_SHIFTED_ID: i32 = 0xFF;
+
+#[derive(Error, Debug)]
+pub enum ProtocolRegistryError {
+    #[error("Error while reading a protocol table")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid TOML protocol table: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("Invalid JSON protocol table: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unknown protocol table format for {0:?}, expected a .toml or .json file")]
+    UnknownFormat(PathBuf),
+    #[error("Protocol {0} is defined more than once")]
+    DuplicateProtocol(i32),
+    #[error("Protocol {0} is already supported natively")]
+    NativeProtocol(i32),
+    #[error("Protocol {0} uses unsupported base protocol {1}")]
+    UnsupportedBase(i32, i32),
+    #[error("Protocol {0} maps more than one packet to id {1:#04X} in state {2:?}")]
+    IdCollision(i32, i32, ConnectionState),
+}
+
+/// All protocol tables known to the server.
+#[derive(Debug, Default)]
+pub struct ProtocolRegistry {
+    tables: AHashMap<i32, ProtocolTable>,
+}
+
+impl ProtocolRegistry {
+    /// The global registry, empty unless [`ProtocolRegistry::init_global`]
+    /// was called.
+    pub fn global() -> &'static ProtocolRegistry { INSTANCE.get_or_init(ProtocolRegistry::default) }
+
+    /// Installs this registry as the global one, returns it back when the
+    /// global registry was already in use.
+    pub fn init_global(self) -> Result<(), ProtocolRegistry> { INSTANCE.set(self) }
+
+    /// Loads a single table file or every table file in a directory.
+    pub fn load_path<P: AsRef<Path>>(path: P) -> Result<Self, ProtocolRegistryError> {
+        let path = path.as_ref();
+        let mut registry = ProtocolRegistry::default();
+        if path.is_dir() {
+            let mut files = std::fs::read_dir(path)?.map(|entry| entry.map(|e| e.path())).collect::<Result<Vec<_>, _>>()?;
+            files.sort();
+            for file in files.into_iter().filter(|f| f.is_file()) {
+                registry.load_file(&file)?;
+            }
+        } else {
+            registry.load_file(path)?;
+        }
+        Ok(registry)
+    }
+
+    /// Adds the tables of a `.toml` or `.json` file.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ProtocolRegistryError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => self.load_toml(&contents),
+            Some("json") => self.load_json(&contents),
+            _ => Err(ProtocolRegistryError::UnknownFormat(path.to_owned())),
+        }
+    }
+
+    pub fn load_toml(&mut self, input: &str) -> Result<(), ProtocolRegistryError> { self.add_file(toml::from_str(input)?) }
+
+    pub fn load_json(&mut self, input: &str) -> Result<(), ProtocolRegistryError> { self.add_file(serde_json::from_str(input)?) }
+
+    fn add_file(&mut self, file: RawFile) -> Result<(), ProtocolRegistryError> {
+        let mut pending = file.protocols;
+        // tables may come before the table they inherit from
+        while !pending.is_empty() {
+            let before = pending.len();
+            let mut waiting = Vec::new();
+            for table in pending {
+                if self.tables.contains_key(&table.base) || FalconConfig::ALLOWED_VERSIONS.contains(&table.base.unsigned_abs()) {
+                    self.insert(ProtocolTable::new(table)?)?;
+                } else {
+                    waiting.push(table);
+                }
+            }
+            if waiting.len() == before {
+                return Err(ProtocolRegistryError::UnsupportedBase(waiting[0].version, waiting[0].base));
+            }
+            pending = waiting;
+        }
+        Ok(())
+    }
+
+    /// Adds a table, a table based on another table inherits its ids and
+    /// is handled as the supported version that table is based on.
+    pub fn insert(&mut self, mut table: ProtocolTable) -> Result<(), ProtocolRegistryError> {
+        if FalconConfig::ALLOWED_VERSIONS.contains(&table.version.unsigned_abs()) {
+            return Err(ProtocolRegistryError::NativeProtocol(table.version));
+        }
+        if self.tables.contains_key(&table.version) {
+            return Err(ProtocolRegistryError::DuplicateProtocol(table.version));
+        }
+        if let Some(parent) = self.tables.get(&table.base) {
+            table = table.inherit(parent)?;
+        } else if !FalconConfig::ALLOWED_VERSIONS.contains(&table.base.unsigned_abs()) {
+            return Err(ProtocolRegistryError::UnsupportedBase(table.version, table.base));
+        }
+        self.tables.insert(table.version, table);
+        Ok(())
+    }
+
+    pub fn get(&self, version: i32) -> Option<&ProtocolTable> { self.tables.get(&version) }
+
+    pub fn len(&self) -> usize { self.tables.len() }
+
+    pub fn is_empty(&self) -> bool { self.tables.is_empty() }
+}
+
+/// A protocol version that is handled as its `base` version with some
+/// packet ids moved.
+#[derive(Debug)]
+pub struct ProtocolTable {
+    version: i32,
+    base: i32,
+    parent: i32,
+    name: Option<String>,
+    states: [StateIds; 4],
+}
+
+impl ProtocolTable {
+    fn new(raw: RawTable) -> Result<Self, ProtocolRegistryError> {
+        let version = raw.version;
+        let state = |ids: RawState, state| -> Result<StateIds, ProtocolRegistryError> {
+            Ok(StateIds {
+                serverbound: IdMap::new(ids.serverbound, version, state)?,
+                clientbound: IdMap::new(ids.clientbound, version, state)?,
+            })
+        };
+        Ok(ProtocolTable {
+            version,
+            base: raw.base,
+            parent: raw.base,
+            name: raw.name,
+            states: [
+                state(raw.handshake, ConnectionState::Handshake)?,
+                state(raw.status, ConnectionState::Status)?,
+                state(raw.login, ConnectionState::Login)?,
+                state(raw.play, ConnectionState::Play)?,
+            ],
+        })
+    }
+
+    /// Applies the ids of this table on top of the ids of `parent`.
+    fn inherit(self, parent: &ProtocolTable) -> Result<Self, ProtocolRegistryError> {
+        let version = self.version;
+        let [handshake, status, login, play] = self.states;
+        let state = |ids: StateIds, state| -> Result<StateIds, ProtocolRegistryError> {
+            let parent = parent.ids(state).expect("Tables have ids for every state");
+            Ok(StateIds {
+                serverbound: ids.serverbound.inherit(&parent.serverbound, version, state)?,
+                clientbound: ids.clientbound.inherit(&parent.clientbound, version, state)?,
+            })
+        };
+        Ok(ProtocolTable {
+            version,
+            base: parent.base,
+            parent: parent.version,
+            name: self.name,
+            states: [
+                state(handshake, ConnectionState::Handshake)?,
+                state(status, ConnectionState::Status)?,
+                state(login, ConnectionState::Login)?,
+                state(play, ConnectionState::Play)?,
+            ],
+        })
+    }
+
+    pub fn version(&self) -> i32 { self.version }
+
+    /// The supported version this version is handled as.
+    pub fn base(&self) -> i32 { self.base }
+
+    /// The version this table was based on, either [`base`](ProtocolTable::base)
+    /// or another table.
+    pub fn parent(&self) -> i32 { self.parent }
+
+    pub fn name(&self) -> Option<&str> { self.name.as_deref() }
+
+    pub fn ids(&self, state: ConnectionState) -> Option<&StateIds> { state.protocol_state().map(|state| self.state_ids(state)) }
+
+    pub fn state_ids(&self, state: ProtocolState) -> &StateIds {
+        match state {
+            ProtocolState::Handshake => &self.states[0],
+            ProtocolState::Status => &self.states[1],
+            ProtocolState::Login => &self.states[2],
+            ProtocolState::Play => &self.states[3],
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct StateIds {
+    pub serverbound: IdMap,
+    pub clientbound: IdMap,
+}
+
+/// Translates between the ids of the base protocol and the ids on the wire,
+/// only ids that moved are stored.
+#[derive(Debug, Default)]
+pub struct IdMap {
+    to_wire: AHashMap<i32, i32>,
+    from_wire: AHashMap<i32, i32>,
+}
+
+impl IdMap {
+    fn new(raw: RawIds, version: i32, state: ConnectionState) -> Result<Self, ProtocolRegistryError> {
+        let mut to_wire = AHashMap::new();
+        for id in 0..=MAX_SHIFTED_ID {
+            let shift: i32 = raw.shift.iter().filter(|s| id >= s.from).map(|s| s.by).sum();
+            if shift != 0 {
+                to_wire.insert(id, id + shift);
+            }
+        }
+        for (base, wire) in raw.remap {
+            to_wire.insert(base, wire);
+        }
+        IdMap::from_to_wire(to_wire, version, state)
+    }
+
+    /// The ids of `parent` moved once more by this map.
+    fn inherit(&self, parent: &IdMap, version: i32, state: ConnectionState) -> Result<Self, ProtocolRegistryError> {
+        let mut to_wire = AHashMap::new();
+        for (&base, &wire) in &parent.to_wire {
+            to_wire.insert(base, self.to_wire(wire));
+        }
+        for &moved in self.to_wire.keys() {
+            // the packets of the parent that kept the id of the base
+            if !parent.to_wire.contains_key(&moved) && !parent.from_wire.contains_key(&moved) {
+                to_wire.insert(moved, self.to_wire(moved));
+            }
+        }
+        IdMap::from_to_wire(to_wire, version, state)
+    }
+
+    fn from_to_wire(mut to_wire: AHashMap<i32, i32>, version: i32, state: ConnectionState) -> Result<Self, ProtocolRegistryError> {
+        to_wire.retain(|base, wire| base != wire);
+
+        let mut from_wire = AHashMap::with_capacity(to_wire.len());
+        for (&base, &wire) in &to_wire {
+            if from_wire.insert(wire, base).is_some() {
+                return Err(ProtocolRegistryError::IdCollision(version, wire, state));
+            }
+        }
+        Ok(IdMap { to_wire, from_wire })
+    }
+
+    /// The id to send for a packet that has `id` in the base protocol.
+    pub fn to_wire(&self, id: i32) -> i32 { self.to_wire.get(&id).copied().unwrap_or(id) }
+
+    /// The base protocol id of a received packet, `None` if no packet of
+    /// the base protocol uses this id anymore.
+    pub fn from_wire(&self, id: i32) -> Option<i32> {
+        match self.from_wire.get(&id) {
+            Some(base) => Some(*base),
+            None if self.to_wire.contains_key(&id) => None,
+            None => Some(id),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool { self.to_wire.is_empty() }
+}
+
+#[derive(Deserialize)]
+struct RawFile {
+    protocols: Vec<RawTable>,
+}
+
+#[derive(Deserialize)]
+struct RawTable {
+    version: i32,
+    base: i32,
+    name: Option<String>,
+    #[serde(default)]
+    handshake: RawState,
+    #[serde(default)]
+    status: RawState,
+    #[serde(default)]
+    login: RawState,
+    #[serde(default)]
+    play: RawState,
+}
+
+#[derive(Deserialize, Default)]
+struct RawState {
+    #[serde(default)]
+    serverbound: RawIds,
+    #[serde(default)]
+    clientbound: RawIds,
+}
+
+#[derive(Deserialize, Default)]
+struct RawIds {
+    #[serde(default)]
+    shift: Vec<RawShift>,
+    #[serde(default)]
+    remap: Vec<(i32, i32)>,
+}
+
+#[derive(Deserialize)]
+struct RawShift {
+    from: i32,
+    by: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProtocolRegistry, ProtocolRegistryError};
+    use crate::network::ConnectionState;
+
+    #[test]
+    fn test_shift_and_remap() {
+        let mut registry = ProtocolRegistry::default();
+        registry
+            .load_toml(
+                r#"
+                [[protocols]]
+                version = 737
+                base = 736
+
+                [protocols.play.clientbound]
+                shift = [{ from = 0x20, by = 1 }]
+                remap = [[0x1F, 0x20]]
+                "#,
+            )
+            .unwrap();
+
+        let table = registry.get(737).unwrap();
+        assert_eq!(table.base(), 736);
+        let ids = &table.ids(ConnectionState::Play).unwrap().clientbound;
+        assert_eq!(ids.to_wire(0x10), 0x10);
+        assert_eq!(ids.to_wire(0x1F), 0x20);
+        assert_eq!(ids.to_wire(0x20), 0x21);
+        assert_eq!(ids.from_wire(0x21), Some(0x20));
+        assert_eq!(ids.from_wire(0x20), Some(0x1F));
+        assert_eq!(ids.from_wire(0x1F), None);
+        assert!(table.ids(ConnectionState::Login).unwrap().clientbound.is_empty());
+    }
+
+    #[test]
+    fn test_inherit() {
+        let mut registry = ProtocolRegistry::default();
+        registry
+            .load_toml(
+                r#"
+                [[protocols]]
+                version = 738
+                base = 737
+
+                [protocols.play.clientbound]
+                # swaps the packets at 0x21 and 0x05 of 737
+                remap = [[0x21, 0x05], [0x05, 0x21]]
+
+                [[protocols]]
+                version = 737
+                base = 736
+
+                [protocols.play.clientbound]
+                shift = [{ from = 0x20, by = 1 }]
+                "#,
+            )
+            .unwrap();
+
+        let table = registry.get(738).unwrap();
+        assert_eq!((table.base(), table.parent()), (736, 737));
+        let ids = &table.ids(ConnectionState::Play).unwrap().clientbound;
+        assert_eq!(ids.to_wire(0x05), 0x21);
+        assert_eq!(ids.to_wire(0x20), 0x05);
+        assert_eq!(ids.to_wire(0x21), 0x22);
+        assert_eq!(ids.from_wire(0x05), Some(0x20));
+        assert_eq!(ids.from_wire(0x21), Some(0x05));
+        assert_eq!(ids.from_wire(0x22), Some(0x21));
+        assert_eq!(ids.from_wire(0x20), None);
+        assert_eq!(ids.from_wire(0x10), Some(0x10));
+    }
+
+    #[test]
+    fn test_json() {
+        let mut registry = ProtocolRegistry::default();
+        registry
+            .load_json(r#"{"protocols": [{"version": 737, "base": 736, "login": {"serverbound": {"remap": [[0, 1]]}}}]}"#)
+            .unwrap();
+        let ids = &registry.get(737).unwrap().ids(ConnectionState::Login).unwrap().serverbound;
+        assert_eq!(ids.from_wire(1), Some(0));
+        assert_eq!(ids.from_wire(0), None);
+    }
+
+    #[test]
+    fn test_invalid_tables() {
+        let mut registry = ProtocolRegistry::default();
+        let table = |version: i32, base: i32| format!("[[protocols]]\nversion = {}\nbase = {}", version, base);
+        assert!(matches!(registry.load_toml(&table(736, 735)), Err(ProtocolRegistryError::NativeProtocol(736))));
+        assert!(matches!(registry.load_toml(&table(737, 12)), Err(ProtocolRegistryError::UnsupportedBase(737, 12))));
+        registry.load_toml(&table(737, 736)).unwrap();
+        assert!(matches!(registry.load_toml(&table(737, 736)), Err(ProtocolRegistryError::DuplicateProtocol(737))));
+
+        let orphan = "[[protocols]]\nversion = 739\nbase = 1000";
+        assert!(matches!(registry.load_toml(orphan), Err(ProtocolRegistryError::UnsupportedBase(739, 1000))));
+
+        let collision = "[[protocols]]\nversion = 738\nbase = 736\n[protocols.play.serverbound]\nremap = [[1, 5], [2, 5]]";
+        assert!(matches!(registry.load_toml(collision), Err(ProtocolRegistryError::IdCollision(738, 5, ConnectionState::Play))));
+    }
+}
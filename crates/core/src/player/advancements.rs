@@ -0,0 +1,145 @@
+//! Advancements of the advancements screen of 1.12+ clients. The server
+//! decides when an advancement is done, so every advancement has the single
+//! criterion [`CRITERION`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::chat::ChatComponent;
+use crate::server::config::FalconConfig;
+
+/// The criterion of every advancement.
+pub const CRITERION: &str = "granted";
+
+/// The background of tabs that do not set one.
+pub const DEFAULT_BACKGROUND: &str = "minecraft:textures/gui/advancements/backgrounds/stone.png";
+
+/// The shape of the icon, it also picks the title of the toast.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdvancementFrame {
+    #[default]
+    Task,
+    Challenge,
+    Goal,
+}
+
+/// How an advancement is shown, advancements without one are invisible.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdvancementDisplay {
+    pub title: ChatComponent,
+    pub description: ChatComponent,
+    /// The item of the icon, e.g. `minecraft:diamond`.
+    pub icon: String,
+    pub frame: AdvancementFrame,
+    /// The texture behind the tab, only used by root advancements.
+    pub background: Option<String>,
+    pub show_toast: bool,
+    /// Only shown in the tab once done.
+    pub hidden: bool,
+    pub x: f32,
+    pub y: f32,
+}
+
+impl AdvancementDisplay {
+    /// A task that shows a toast when done.
+    pub fn new(title: impl Into<ChatComponent>, description: impl Into<ChatComponent>, icon: impl Into<String>) -> Self {
+        AdvancementDisplay {
+            title: title.into(),
+            description: description.into(),
+            icon: icon.into(),
+            frame: AdvancementFrame::Task,
+            background: None,
+            show_toast: true,
+            hidden: false,
+            x: 0.0,
+            y: 0.0,
+        }
+    }
+
+    pub fn frame(mut self, frame: AdvancementFrame) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    pub fn background(mut self, background: impl Into<String>) -> Self {
+        self.background = Some(background.into());
+        self
+    }
+
+    pub fn show_toast(mut self, show_toast: bool) -> Self {
+        self.show_toast = show_toast;
+        self
+    }
+
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// The position in the tab, in icons from the root.
+    pub fn position(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+}
+
+/// An advancement, the ones without a parent are the root of a tab.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Advancement {
+    /// An identifier, e.g. `falcon:welcome`.
+    pub id: String,
+    pub parent: Option<String>,
+    pub display: Option<AdvancementDisplay>,
+}
+
+impl Advancement {
+    pub fn new(id: impl Into<String>) -> Self {
+        Advancement {
+            id: id.into(),
+            parent: None,
+            display: None,
+        }
+    }
+
+    pub fn parent(mut self, parent: impl Into<String>) -> Self {
+        self.parent = Some(parent.into());
+        self
+    }
+
+    pub fn display(mut self, display: AdvancementDisplay) -> Self {
+        self.display = Some(display);
+        self
+    }
+
+    pub fn is_root(&self) -> bool { self.parent.is_none() }
+
+    /// A hidden root advancement that only shows a toast with `title` when
+    /// it is granted.
+    pub fn toast(id: impl Into<String>, title: impl Into<ChatComponent>, icon: impl Into<String>, frame: AdvancementFrame) -> Self {
+        let display = AdvancementDisplay::new(title, "", icon).frame(frame).background(DEFAULT_BACKGROUND).hidden(true);
+        Advancement::new(id).display(display)
+    }
+
+    /// The advancement tab of the config, roots without a background get
+    /// the [`DEFAULT_BACKGROUND`].
+    pub fn tab_from_config() -> Vec<Advancement> {
+        FalconConfig::global()
+            .advancements
+            .tab
+            .iter()
+            .map(|entry| {
+                let mut display = AdvancementDisplay::new(ChatComponent::from_markup(&entry.title), ChatComponent::from_markup(&entry.description), &entry.icon)
+                    .frame(entry.frame)
+                    .hidden(entry.hidden)
+                    .position(entry.x, entry.y);
+                let mut advancement = Advancement::new(&entry.id);
+                match &entry.parent {
+                    Some(parent) => advancement = advancement.parent(parent),
+                    None => display = display.background(entry.background.as_deref().unwrap_or(DEFAULT_BACKGROUND)),
+                }
+                advancement.display(display)
+            })
+            .collect()
+    }
+}
@@ -8,6 +8,28 @@ pub enum GameMode {
     Spectator,
 }
 
+impl GameMode {
+    /// Parses a game mode from its name or id, e.g. `creative` or `1`.
+    pub fn from_name(name: &str) -> Option<GameMode> {
+        match name.to_lowercase().as_str() {
+            "survival" | "s" | "0" => Some(GameMode::Survival),
+            "creative" | "c" | "1" => Some(GameMode::Creative),
+            "adventure" | "a" | "2" => Some(GameMode::Adventure),
+            "spectator" | "sp" | "3" => Some(GameMode::Spectator),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Survival => "survival",
+            GameMode::Creative => "creative",
+            GameMode::Adventure => "adventure",
+            GameMode::Spectator => "spectator",
+        }
+    }
+}
+
 impl From<GameMode> for u8 {
     fn from(src: GameMode) -> Self { src as u8 }
 }
@@ -29,6 +51,16 @@ impl PlayerAbilityFlags {
             instant_break,
         }
     }
+
+    /// The abilities a player has in `game_mode`, `flying` is kept when
+    /// flying is still allowed.
+    pub fn for_game_mode(game_mode: GameMode, flying: bool) -> Self {
+        match game_mode {
+            GameMode::Creative => PlayerAbilityFlags::new(true, flying, true, true),
+            GameMode::Spectator => PlayerAbilityFlags::new(true, true, true, false),
+            GameMode::Survival | GameMode::Adventure => PlayerAbilityFlags::new(false, false, false, false),
+        }
+    }
 }
 
 impl From<PlayerAbilityFlags> for u8 {
@@ -37,6 +69,78 @@ impl From<PlayerAbilityFlags> for u8 {
     }
 }
 
+bitflags::bitflags! {
+    /// The parts of a teleport that are relative to the current position
+    /// and rotation of the player instead of absolute.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct TeleportFlags: u8 {
+        const X = 0x01;
+        const Y = 0x02;
+        const Z = 0x04;
+        const YAW = 0x08;
+        const PITCH = 0x10;
+    }
+}
+
+/// The books of the recipe book, one for each crafting block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecipeBookType {
+    Crafting = 0,
+    Furnace,
+    /// Since 1.14.
+    BlastFurnace,
+    /// Since 1.14.
+    Smoker,
+}
+
+impl RecipeBookType {
+    pub fn from_id(id: i32) -> Option<RecipeBookType> {
+        match id {
+            0 => Some(RecipeBookType::Crafting),
+            1 => Some(RecipeBookType::Furnace),
+            2 => Some(RecipeBookType::BlastFurnace),
+            3 => Some(RecipeBookType::Smoker),
+            _ => None,
+        }
+    }
+}
+
+/// Whether each book of the recipe book is open and only shows the recipes
+/// that can be crafted, the client asks to change them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RecipeBookSettings {
+    books: [(bool, bool); 4],
+}
+
+impl RecipeBookSettings {
+    pub fn set(&mut self, book: RecipeBookType, open: bool, filtering: bool) { self.books[book as usize] = (open, filtering); }
+
+    pub fn is_open(&self, book: RecipeBookType) -> bool { self.books[book as usize].0 }
+
+    pub fn is_filtering(&self, book: RecipeBookType) -> bool { self.books[book as usize].1 }
+
+    /// The open and filtering flags of every book, in the order of their
+    /// ids.
+    pub fn books(&self) -> [(bool, bool); 4] { self.books }
+}
+
+/// A hand of a player, before 1.9 players only had the main hand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hand {
+    Main = 0,
+    Off,
+}
+
+impl Hand {
+    pub fn from_id(id: i32) -> Option<Hand> {
+        match id {
+            0 => Some(Hand::Main),
+            1 => Some(Hand::Off),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
 pub struct Position {
     pub x: f64,
@@ -1 +1,4 @@
+pub mod advancements;
 pub mod data;
+pub mod profile;
+pub mod stats;
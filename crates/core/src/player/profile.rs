@@ -0,0 +1,110 @@
+use bytes::{Buf, BufMut};
+use falcon_packet_core::{PacketRead, PacketReadSeed, PacketSize, PacketSizeSeed, PacketString, PacketWrite, PacketWriteSeed, ReadError, WriteError};
+use uuid::Uuid;
+
+/// Who a player is, as sent in the login success and the tab list.
+#[derive(Clone, Debug, PartialEq, Eq, PacketRead, PacketWrite, PacketSize)]
+pub struct GameProfile {
+    pub uuid: Uuid,
+    #[falcon(string = 16)]
+    pub name: String,
+    #[falcon(collection)]
+    pub properties: Vec<ProfileProperty>,
+}
+
+impl GameProfile {
+    pub fn new(uuid: Uuid, name: String, properties: Vec<ProfileProperty>) -> Self { GameProfile { uuid, name, properties } }
+}
+
+/// Whether `name` is a valid Java Edition username, 1 to 16 letters,
+/// digits or underscores.
+pub fn is_valid_username(name: &str) -> bool {
+    (1..=16).contains(&name.len()) && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// A property of a player profile, e.g. the `textures` of a skin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfileProperty {
+    pub name: String,
+    pub value: String,
+    /// Signed by Mojang, only for online players.
+    pub signature: Option<String>,
+}
+
+impl PacketSize for ProfileProperty {
+    fn size(&self) -> usize {
+        PacketString::new(32767).size(&self.name)
+            + PacketString::new(32767).size(&self.value)
+            + self.signature.is_some().size()
+            + self.signature.as_ref().map(|signature| PacketString::new(32767).size(signature)).unwrap_or(0)
+    }
+}
+
+impl PacketWrite for ProfileProperty {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        PacketString::new(32767).write(&self.name, buffer)?;
+        PacketString::new(32767).write(&self.value, buffer)?;
+        self.signature.is_some().write(buffer)?;
+        if let Some(signature) = &self.signature {
+            PacketString::new(32767).write(signature, buffer)?;
+        }
+        Ok(())
+    }
+}
+
+impl PacketRead for ProfileProperty {
+    fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized,
+    {
+        let name = PacketString::new(32767).read(buffer)?;
+        let value = PacketString::new(32767).read(buffer)?;
+        let signature = match bool::read(buffer)? {
+            true => Some(PacketString::new(32767).read(buffer)?),
+            false => None,
+        };
+        Ok(ProfileProperty { name, value, signature })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::Bytes;
+    use falcon_packet_core::{PacketRead, PacketSize, PacketWrite};
+    use uuid::Uuid;
+
+    use super::{is_valid_username, GameProfile, ProfileProperty};
+
+    #[test]
+    fn test_valid_username() {
+        assert!(is_valid_username("Notch"));
+        assert!(is_valid_username("jeb_"));
+        assert!(is_valid_username("a"));
+        assert!(is_valid_username("0123456789abcdef"));
+        assert!(!is_valid_username(""));
+        assert!(!is_valid_username("0123456789abcdefg"));
+        assert!(!is_valid_username("../Notch"));
+        assert!(!is_valid_username("Notch?unsigned=false"));
+        assert!(!is_valid_username("Nötch"));
+        assert!(!is_valid_username("Two words"));
+    }
+
+    #[test]
+    fn test_profile() {
+        let textures = ProfileProperty {
+            name: String::from("textures"),
+            value: String::from("e30="),
+            signature: Some(String::from("c2ln")),
+        };
+        let profile = GameProfile::new(Uuid::from_u128(7), String::from("Notch"), vec![textures]);
+        let mut buffer = Vec::new();
+        profile.write(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), profile.size());
+        assert_eq!(buffer.len(), 16 + 6 + 1 + 9 + 5 + 1 + 5);
+        assert_eq!(GameProfile::read(&mut Bytes::from(buffer)).unwrap(), profile);
+    }
+}
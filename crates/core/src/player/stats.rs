@@ -0,0 +1,183 @@
+//! The statistics of a player, stored in the same format as the vanilla
+//! `stats/<uuid>.json`:
+//! ```json
+//! {
+//!   "stats": {
+//!     "minecraft:custom": { "minecraft:play_time": 7200, "minecraft:deaths": 1 },
+//!     "minecraft:mined": { "minecraft:stone": 12 }
+//!   },
+//!   "DataVersion": 3465
+//! }
+//! ```
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use ahash::AHashMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The data version of 1.20.1, vanilla upgrades files with older versions.
+const DATA_VERSION: i32 = 3465;
+
+const CUSTOM: &str = "minecraft:custom";
+const MINED: &str = "minecraft:mined";
+const USED: &str = "minecraft:used";
+
+#[derive(Error, Debug)]
+pub enum StatisticsError {
+    #[error("Error while accessing a statistics file")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid statistics file {0:?}: {1}")]
+    Json(PathBuf, serde_json::Error),
+}
+
+/// A single statistic of the statistics screen.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Statistic {
+    /// In ticks.
+    PlayTime,
+    /// In centimeters, on the ground only.
+    WalkOneCm,
+    Deaths,
+    /// Blocks broken of a kind, e.g. `minecraft:stone`.
+    Mined(String),
+    /// Items used of a kind, placing a block uses its item.
+    Used(String),
+    /// A statistic this server does not track, kept to save it again.
+    Other { category: String, name: String },
+}
+
+impl Statistic {
+    /// The category and name in the statistics file.
+    pub fn key(&self) -> (&str, &str) {
+        match self {
+            Statistic::PlayTime => (CUSTOM, "minecraft:play_time"),
+            Statistic::WalkOneCm => (CUSTOM, "minecraft:walk_one_cm"),
+            Statistic::Deaths => (CUSTOM, "minecraft:deaths"),
+            Statistic::Mined(block) => (MINED, block),
+            Statistic::Used(item) => (USED, item),
+            Statistic::Other { category, name } => (category, name),
+        }
+    }
+
+    pub fn from_key(category: &str, name: &str) -> Self {
+        match (category, name) {
+            // the name before 1.17
+            (CUSTOM, "minecraft:play_time" | "minecraft:play_one_minute") => Statistic::PlayTime,
+            (CUSTOM, "minecraft:walk_one_cm") => Statistic::WalkOneCm,
+            (CUSTOM, "minecraft:deaths") => Statistic::Deaths,
+            (MINED, block) => Statistic::Mined(block.to_owned()),
+            (USED, item) => Statistic::Used(item.to_owned()),
+            (category, name) => Statistic::Other {
+                category: category.to_owned(),
+                name: name.to_owned(),
+            },
+        }
+    }
+
+    /// The name used by clients before 1.13, e.g. `stat.mineBlock.minecraft.stone`.
+    pub fn legacy_name(&self) -> Option<Cow<'static, str>> {
+        match self {
+            Statistic::PlayTime => Some(Cow::Borrowed("stat.playOneMinute")),
+            Statistic::WalkOneCm => Some(Cow::Borrowed("stat.walkOneCm")),
+            Statistic::Deaths => Some(Cow::Borrowed("stat.deaths")),
+            Statistic::Mined(block) => Some(Cow::Owned(format!("stat.mineBlock.{}", block.replace(':', ".")))),
+            Statistic::Used(item) => Some(Cow::Owned(format!("stat.useItem.{}", item.replace(':', ".")))),
+            Statistic::Other { .. } => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StatisticsFile {
+    stats: BTreeMap<String, BTreeMap<String, i32>>,
+    #[serde(rename = "DataVersion", default)]
+    data_version: i32,
+}
+
+/// The statistics of one player, saved to a file when loaded from one.
+#[derive(Debug, Default)]
+pub struct PlayerStatistics {
+    path: Option<PathBuf>,
+    values: AHashMap<Statistic, i32>,
+}
+
+impl PlayerStatistics {
+    /// Reads the statistics from `path`, a missing file has none.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, StatisticsError> {
+        let path = path.as_ref().to_path_buf();
+        let mut values = AHashMap::new();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let file: StatisticsFile = serde_json::from_str(&contents).map_err(|e| StatisticsError::Json(path.clone(), e))?;
+                for (category, stats) in &file.stats {
+                    values.extend(stats.iter().map(|(name, value)| (Statistic::from_key(category, name), *value)));
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {},
+            Err(e) => return Err(e.into()),
+        }
+        Ok(PlayerStatistics { path: Some(path), values })
+    }
+
+    /// Writes the statistics back to the file they were loaded from.
+    pub fn save(&self) -> Result<(), StatisticsError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut file = StatisticsFile {
+            stats: BTreeMap::new(),
+            data_version: DATA_VERSION,
+        };
+        for (statistic, value) in &self.values {
+            let (category, name) = statistic.key();
+            file.stats.entry(category.to_owned()).or_default().insert(name.to_owned(), *value);
+        }
+        let json = serde_json::to_string(&file).map_err(|e| StatisticsError::Json(path.clone(), e))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    pub fn get(&self, statistic: &Statistic) -> i32 { self.values.get(statistic).copied().unwrap_or(0) }
+
+    pub fn set(&mut self, statistic: Statistic, value: i32) { self.values.insert(statistic, value); }
+
+    /// Adds `amount`, stopping at `i32::MAX` like vanilla.
+    pub fn increment(&mut self, statistic: Statistic, amount: i32) {
+        let value = self.values.entry(statistic).or_insert(0);
+        *value = value.saturating_add(amount);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Statistic, i32)> { self.values.iter().map(|(statistic, value)| (statistic, *value)) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_vanilla_format() {
+        let dir = std::env::temp_dir().join(format!("falcon-stats-{}", std::process::id()));
+        let path = dir.join("player.json");
+        std::fs::create_dir_all(&dir).unwrap();
+        let json = r#"{"stats": {"minecraft:custom": {"minecraft:play_one_minute": 20, "minecraft:jump": 3}, "minecraft:mined": {"minecraft:stone": 2}}, "DataVersion": 1343}"#;
+        std::fs::write(&path, json).unwrap();
+
+        let mut stats = PlayerStatistics::load(&path).unwrap();
+        assert_eq!(stats.get(&Statistic::PlayTime), 20);
+        assert_eq!(stats.get(&Statistic::Mined(String::from("minecraft:stone"))), 2);
+        stats.increment(Statistic::Deaths, 1);
+        stats.save().unwrap();
+
+        let stats = PlayerStatistics::load(&path).unwrap();
+        assert_eq!(stats.get(&Statistic::Deaths), 1);
+        assert_eq!(stats.get(&Statistic::from_key("minecraft:custom", "minecraft:jump")), 3);
+        assert_eq!(Statistic::Mined(String::from("minecraft:stone")).legacy_name().unwrap(), "stat.mineBlock.minecraft.stone");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,11 +1,11 @@
 use std::borrow::Cow;
-use std::str::FromStr;
 
 use ahash::AHashMap;
 use fastnbt::borrow::{ByteArray, IntArray};
 use serde::Deserialize;
 
 use crate::error::FalconCoreError;
+use crate::world::block_states::parse_state;
 use crate::world::blocks::Blocks;
 
 pub const REQUIRED_DATA_VERSION: i32 = 2730;
@@ -81,7 +81,7 @@ impl<'a> TryFrom<SchematicVersionedRaw<'a>> for SchematicData<'a> {
 
         let mut effective_palette = AHashMap::new();
         for (state, index) in value.palette {
-            effective_palette.insert(index, Blocks::from_str(state.as_ref())?);
+            effective_palette.insert(index, parse_state(state.as_ref())?);
         }
 
         Ok(SchematicData::new(value.width as u16, value.height as u16, value.length as u16, effective_offset, effective_palette, block_data))
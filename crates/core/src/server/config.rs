@@ -1,5 +1,6 @@
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
+use std::time::Duration;
 
 use confy::ConfyError;
 use once_cell::sync::OnceCell;
@@ -7,21 +8,43 @@ use serde::{Deserialize, Serialize};
 use tokio::net::ToSocketAddrs;
 use tracing::metadata::LevelFilter;
 
+use crate::network::ConnectionState;
+use crate::player::advancements::AdvancementFrame;
 use crate::player::data::{LookAngles, Position};
+use crate::world::biome::Biome;
 
 static INSTANCE: OnceCell<FalconConfig> = OnceCell::new();
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct FalconConfig {
     pub connection: ConnectionSettings,
+    #[serde(default)]
+    pub network: NetworkSettings,
     pub players: PlayerSettings,
+    #[serde(default)]
+    pub profiles: ProfileSettings,
+    #[serde(default)]
+    pub floodgate: FloodgateSettings,
+    #[serde(default)]
+    pub movement: MovementSettings,
+    #[serde(default)]
+    pub void: VoidSettings,
+    #[serde(default)]
+    pub world: WorldSettings,
+    #[serde(default)]
+    pub chat: ChatSettings,
+    #[serde(default)]
+    pub advancements: AdvancementSettings,
     pub server: ServerSettings,
     pub versions: VersionSettings,
+    #[serde(default)]
+    pub debug: DebugSettings,
 }
 
 impl FalconConfig {
     // 47, 107, 108, 109, 110, 210, 315, 316, 335, 338, 340, --> for future update
-    pub const ALLOWED_VERSIONS: [u32; 13] = [393, 401, 404, 477, 480, 485, 490, 498, 573, 575, 578, 735, 736];
+    // 759, 760, 761, 763 --> once the block states of 1.19 and 1.20 are generated (falcon-codegen --block-states)
+    pub const ALLOWED_VERSIONS: [u32; 15] = [393, 401, 404, 477, 480, 485, 490, 498, 573, 575, 578, 735, 736, 755, 756];
 
     pub fn global() -> &'static FalconConfig { INSTANCE.get().expect("FalconConfig is not initialized!!") }
 
@@ -30,8 +53,28 @@ impl FalconConfig {
         Ok(())
     }
 
+    /// Initializes the global config with default values unless a config was
+    /// loaded already, this is mostly useful for tests.
+    pub fn init_default() -> &'static FalconConfig { INSTANCE.get_or_init(FalconConfig::default) }
+
+    /// Initializes the global config with `config` unless a config was
+    /// loaded already, e.g. for tests that need other settings.
+    pub fn init_with(config: FalconConfig) -> &'static FalconConfig { INSTANCE.get_or_init(|| config) }
+
     pub fn server_socket_addrs(&self) -> impl ToSocketAddrs + '_ { (self.connection.server_ip, self.connection.server_port) }
 
+    /// Every address the server listens on, `server_ip` and `server_port`
+    /// first.
+    pub fn listeners(&self) -> Vec<ListenerSettings> {
+        let connection = &self.connection;
+        let primary = ListenerSettings {
+            address: SocketAddr::new(connection.server_ip, connection.server_port),
+            proxy_protocol: connection.proxy_protocol,
+            online_mode: None,
+        };
+        std::iter::once(primary).chain(connection.listeners.iter().cloned()).collect()
+    }
+
     pub fn world_file(&self) -> Option<&str> { self.server.world.as_deref() }
 }
 
@@ -39,6 +82,21 @@ impl FalconConfig {
 pub struct ConnectionSettings {
     pub server_ip: IpAddr,
     pub server_port: u16,
+    /// Expect a PROXY protocol header (v1 or v2) on every connection, only
+    /// enable this behind a load balancer that sends one.
+    #[serde(default)]
+    pub proxy_protocol: bool,
+    /// Milliseconds an address has to wait between login attempts, 0
+    /// disables the throttle.
+    #[serde(default = "default_login_throttle")]
+    pub login_throttle: u64,
+    /// More addresses to listen on, e.g. an IPv6 address or a second port
+    /// for a proxy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub listeners: Vec<ListenerSettings>,
+    /// Where players spawn depending on the host name they connected with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forced_hosts: Vec<ForcedHostSettings>,
 }
 
 impl Default for ConnectionSettings {
@@ -46,6 +104,174 @@ impl Default for ConnectionSettings {
         ConnectionSettings {
             server_port: 30000,
             server_ip: IpAddr::from_str("0.0.0.0").unwrap(),
+            proxy_protocol: false,
+            login_throttle: default_login_throttle(),
+            listeners: Vec::new(),
+            forced_hosts: Vec::new(),
+        }
+    }
+}
+
+/// A spawn point for the players that connect with a host name.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ForcedHostSettings {
+    /// The host name, e.g. `lobby.example.com`, case does not matter.
+    pub host: String,
+    pub spawn_position: Position,
+    #[serde(default)]
+    pub spawn_look: LookAngles,
+}
+
+/// An address the server listens on with the options of its connections.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListenerSettings {
+    pub address: SocketAddr,
+    /// Expect a PROXY protocol header on the connections of this listener.
+    #[serde(default)]
+    pub proxy_protocol: bool,
+    /// Overrides the online mode of the server for the connections of this
+    /// listener.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub online_mode: Option<bool>,
+}
+
+/// How chat messages look and which are blocked.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatSettings {
+    /// The markup of a chat message with the placeholders `{prefix}`,
+    /// `{name}`, `{world}` and `{message}`.
+    pub format: String,
+    /// The `{prefix}` of operators.
+    pub op_prefix: String,
+    /// Milliseconds a player has to wait between two messages.
+    pub cooldown: u64,
+    /// Regular expressions of words that are replaced by `*`, case does
+    /// not matter.
+    pub blocked_words: Vec<String>,
+}
+
+impl Default for ChatSettings {
+    fn default() -> Self {
+        ChatSettings {
+            format: String::from("{prefix}\\<{name}> {message}"),
+            op_prefix: String::new(),
+            cooldown: 500,
+            blocked_words: Vec::new(),
+        }
+    }
+}
+
+/// Toasts and an advancement tab of the server, see
+/// [`Advancement::tab_from_config`](crate::player::advancements::Advancement::tab_from_config).
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdvancementSettings {
+    /// Markup of a toast shown to joining players.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub welcome_toast: Option<String>,
+    /// The advancements of the tab, the one without a parent is the root.
+    pub tab: Vec<AdvancementEntrySettings>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdvancementEntrySettings {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// The title and description as markup.
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// The item of the icon, e.g. `minecraft:diamond`.
+    pub icon: String,
+    #[serde(default)]
+    pub frame: AdvancementFrame,
+    /// The texture behind the tab, only for the root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub x: f32,
+    #[serde(default)]
+    pub y: f32,
+}
+
+/// Tuning of the connections, the defaults suit most servers.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// Packets of at least this many bytes are compressed, a negative
+    /// value disables compression.
+    pub compression_threshold: i32,
+    /// The zlib compression level, 0 (fastest) to 9 (smallest).
+    pub compression_level: u32,
+    /// Clients sending a larger packet are disconnected.
+    pub max_packet_size: usize,
+    /// The largest packet accepted during the handshake and server list
+    /// ping, proxies may forward player data in the handshake.
+    pub max_handshake_packet_size: usize,
+    /// The largest packet accepted during login.
+    pub max_login_packet_size: usize,
+    /// The size of the buffer incoming data is read into.
+    pub read_buffer_size: usize,
+    /// The size of the buffer outgoing packets are compressed from.
+    pub write_buffer_size: usize,
+    /// Send small packets right away instead of batching them.
+    pub tcp_nodelay: bool,
+    /// The threads that encode chunk packets, 0 uses one per CPU core.
+    pub chunk_workers: usize,
+    /// The most chunks sent to a player each tick, fewer are sent while
+    /// the connection has not written the previous ones yet.
+    pub max_chunks_per_tick: usize,
+    /// Seconds between two keep alives.
+    pub keep_alive_interval: u64,
+    /// Seconds without an answer to a keep alive before a client is
+    /// disconnected.
+    pub keep_alive_timeout: u64,
+}
+
+impl NetworkSettings {
+    pub fn chunk_workers(&self) -> usize {
+        match self.chunk_workers {
+            0 => std::thread::available_parallelism().map(usize::from).unwrap_or(1),
+            workers => workers,
+        }
+    }
+
+    pub fn keep_alive_interval(&self) -> Duration { Duration::from_secs(self.keep_alive_interval.max(1)) }
+
+    pub fn keep_alive_timeout(&self) -> Duration { Duration::from_secs(self.keep_alive_timeout.max(1)) }
+
+    /// The largest packet accepted from a client in the given state, never
+    /// more than `max_packet_size`.
+    pub fn packet_size_limit(&self, state: ConnectionState) -> usize {
+        let limit = match state {
+            ConnectionState::Handshake | ConnectionState::Status => self.max_handshake_packet_size,
+            ConnectionState::Login => self.max_login_packet_size,
+            ConnectionState::Play | ConnectionState::Disconnected => self.max_packet_size,
+        };
+        limit.min(self.max_packet_size)
+    }
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        NetworkSettings {
+            compression_threshold: -1,
+            compression_level: 5,
+            // the largest length that fits in the 3 byte frame length
+            max_packet_size: 2097151,
+            max_handshake_packet_size: 4096,
+            max_login_packet_size: 32767,
+            read_buffer_size: 4096,
+            write_buffer_size: 4096,
+            tcp_nodelay: true,
+            chunk_workers: 0,
+            max_chunks_per_tick: 32,
+            keep_alive_interval: 12,
+            keep_alive_timeout: 30,
         }
     }
 }
@@ -56,6 +282,25 @@ pub struct PlayerSettings {
     pub max_view_distance: u8,
     pub spawn_position: Position,
     pub spawn_look: LookAngles,
+    /// Look up the Mojang profile of joining players to show their skins.
+    #[serde(default)]
+    pub fetch_skins: bool,
+    /// Kick players that did not move, chat or use a command for this many
+    /// minutes, 0 never kicks.
+    #[serde(default)]
+    pub idle_timeout: u32,
+    /// The directory with the statistics of every player, in the format of
+    /// the vanilla `stats` directory.
+    #[serde(default = "default_statistics_dir")]
+    pub statistics_dir: String,
+    /// Players that attack another player show the hit to everyone and
+    /// knock the other player back, unless a listener cancels the attack.
+    #[serde(default)]
+    pub pvp: bool,
+    /// Players in survival or adventure mode take fall, void and attack
+    /// damage and can die. Lobbies keep this off so nobody gets hurt.
+    #[serde(default)]
+    pub damage: bool,
 }
 
 impl Default for PlayerSettings {
@@ -65,6 +310,165 @@ impl Default for PlayerSettings {
             max_view_distance: 10,
             spawn_position: Default::default(),
             spawn_look: Default::default(),
+            fetch_skins: false,
+            idle_timeout: 0,
+            statistics_dir: default_statistics_dir(),
+            pvp: false,
+            damage: false,
+        }
+    }
+}
+
+/// Where the profiles of players are looked up, see `players.fetch_skins`.
+/// Networks with their own Yggdrasil server, e.g. through authlib-injector,
+/// point the urls there.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileSettings {
+    /// The uuid of a username is requested at this url followed by the
+    /// username.
+    pub api_url: String,
+    /// The properties of a profile are requested at this url followed by
+    /// the uuid.
+    pub session_url: String,
+    /// Seconds a looked up profile is reused, also when no profile was
+    /// found.
+    pub cache_ttl: u64,
+    /// How often a lookup that failed because the server could not be
+    /// reached or had an error is tried again.
+    pub retries: u32,
+    /// Refuse players without a profile or whose profile could not be
+    /// looked up, instead of letting them join without a skin. Profiles are
+    /// then looked up even without `players.fetch_skins`.
+    pub require_profile: bool,
+}
+
+impl ProfileSettings {
+    pub fn cache_ttl(&self) -> Duration { Duration::from_secs(self.cache_ttl) }
+}
+
+impl Default for ProfileSettings {
+    fn default() -> Self {
+        ProfileSettings {
+            api_url: String::from("https://api.mojang.com/users/profiles/minecraft/"),
+            session_url: String::from("https://sessionserver.mojang.com/session/minecraft/profile/"),
+            cache_ttl: 6 * 60 * 60,
+            retries: 2,
+            require_profile: false,
+        }
+    }
+}
+
+/// Bedrock players that join through Geyser and Floodgate, and how they
+/// are named.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FloodgateSettings {
+    /// Let Bedrock players in, the proxy has to run Floodgate.
+    pub enabled: bool,
+    /// The key Floodgate encrypts its data with, the `key.pem` of the
+    /// Floodgate installation on the proxy.
+    pub key_file: String,
+    /// Put before the gamertag of Bedrock players so their names do not
+    /// clash with Java players.
+    pub username_prefix: String,
+    /// Replace the spaces of gamertags with underscores, Java clients do not
+    /// accept names with spaces.
+    pub replace_spaces: bool,
+}
+
+impl Default for FloodgateSettings {
+    fn default() -> Self {
+        FloodgateSettings {
+            enabled: false,
+            key_file: String::from("key.pem"),
+            username_prefix: String::from("."),
+            replace_spaces: true,
+        }
+    }
+}
+
+/// Checks on the position updates of players, players that fail one are
+/// sent back to where they were.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovementSettings {
+    /// The furthest a player may move in one update in blocks, 0 disables
+    /// this check.
+    pub max_distance: f64,
+    /// Players may not move into chunks that are not loaded yet.
+    pub check_unloaded_chunks: bool,
+    /// Updates in the air without falling before a player that may not fly
+    /// is sent back, 0 disables this check.
+    pub max_air_updates: u32,
+}
+
+impl Default for MovementSettings {
+    fn default() -> Self {
+        MovementSettings {
+            max_distance: 10.0,
+            check_unloaded_chunks: true,
+            // four seconds, like the vanilla server
+            max_air_updates: 80,
+        }
+    }
+}
+
+/// A place the chunks of the world come from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ChunkSource {
+    /// The region files of a vanilla world directory, 1.13 or later.
+    Anvil { path: String },
+    /// The `.linear` region files of a world directory, which are Anvil
+    /// regions compressed as a whole with zstd.
+    Linear { path: String },
+    /// A sponge schematic, like `server.world`.
+    Schematic { path: String },
+    /// A flat world of these blocks from the bottom up, e.g.
+    /// `minecraft:bedrock`.
+    Flat { layers: Vec<String> },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldSettings {
+    /// Tried in order until one has the chunk, without sources the world
+    /// is the schematic of `server.world`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<ChunkSource>,
+    /// The biome of chunks without biomes of their own, e.g. the chunks
+    /// of schematics.
+    pub default_biome: Biome,
+}
+
+/// What happens to players that fall below the world.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VoidAction {
+    /// Back to their spawn point.
+    #[default]
+    Teleport,
+    /// Killed, even when players cannot take damage.
+    Kill,
+    /// Players keep falling, they take void damage if damage is on.
+    Nothing,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoidSettings {
+    pub action: VoidAction,
+    /// Players below this height are in the void, the bottom of the world
+    /// is 0.
+    pub level: f64,
+}
+
+impl Default for VoidSettings {
+    fn default() -> Self {
+        VoidSettings {
+            action: VoidAction::Teleport,
+            level: 0.0,
         }
     }
 }
@@ -74,11 +478,83 @@ pub struct ServerSettings {
     #[serde(with = "tracing_serde")]
     pub tracing_level: LevelFilter,
     pub max_players: i32,
+    /// The server list message as markup, see [`ChatComponent::from_markup`].
+    ///
+    /// [`ChatComponent::from_markup`]: crate::chat::ChatComponent::from_markup
     pub description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub world: Option<String>,
+    #[serde(default)]
+    pub seed: i64,
+    /// The memory in MiB for chunks loaded from disk or a generator, 0 has
+    /// no limit. Chunks that were changed are always kept.
+    #[serde(default)]
+    pub chunk_cache_mb: usize,
+    /// The locale of messages for players that did not send their own.
+    #[serde(default = "default_locale")]
+    pub default_locale: String,
+    /// A directory with `<locale>.toml` files for the
+    /// [`MessageCatalog`](crate::server::lang::MessageCatalog).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_dir: Option<String>,
+    /// The operators, in the format of the vanilla `ops.json`.
+    #[serde(default = "default_ops_file")]
+    pub ops_file: String,
+    /// The level players get from the `op` command, 1 to 4.
+    #[serde(default = "default_op_level")]
+    pub op_permission_level: u8,
+    /// The groups and permission nodes of the
+    /// [`FilePermissions`](crate::server::permissions::FilePermissions).
+    #[serde(default = "default_permissions_file")]
+    pub permissions_file: String,
+    /// Only let players on the whitelist and operators join.
+    #[serde(default)]
+    pub whitelist: bool,
+    #[serde(default = "default_whitelist_file")]
+    pub whitelist_file: String,
+    #[serde(default = "default_banned_players_file")]
+    pub banned_players_file: String,
+    /// The banned addresses, behind a proxy these are the addresses of the
+    /// clients sent in the PROXY protocol header.
+    #[serde(default = "default_banned_ips_file")]
+    pub banned_ips_file: String,
+    /// A directory with a `<protocol>.json` file of tags for each
+    /// version, see [`ProtocolTags`](crate::data::tags::ProtocolTags).
+    #[serde(default = "default_tags_dir")]
+    pub tags_dir: String,
+    /// A directory with the `registries.json` report of each version as
+    /// `<protocol>.json`, see [`ProtocolItems`](crate::data::items::ProtocolItems).
+    #[serde(default = "default_items_dir")]
+    pub items_dir: String,
 }
 
+impl ServerSettings {
+    /// The chunk cache limit in bytes, if any.
+    pub fn chunk_cache_budget(&self) -> Option<usize> { (self.chunk_cache_mb > 0).then(|| self.chunk_cache_mb * 1024 * 1024) }
+}
+
+fn default_login_throttle() -> u64 { 4000 }
+
+fn default_locale() -> String { String::from(crate::server::lang::BUILTIN_LOCALE) }
+
+fn default_statistics_dir() -> String { String::from("stats") }
+
+fn default_ops_file() -> String { String::from("ops.json") }
+
+fn default_whitelist_file() -> String { String::from("whitelist.json") }
+
+fn default_banned_players_file() -> String { String::from("banned-players.json") }
+
+fn default_banned_ips_file() -> String { String::from("banned-ips.json") }
+
+fn default_tags_dir() -> String { String::from("tags") }
+
+fn default_items_dir() -> String { String::from("items") }
+
+fn default_permissions_file() -> String { String::from("permissions.toml") }
+
+fn default_op_level() -> u8 { crate::server::ops::MAX_PERMISSION_LEVEL }
+
 impl Default for ServerSettings {
     fn default() -> Self {
         ServerSettings {
@@ -86,6 +562,19 @@ impl Default for ServerSettings {
             max_players: -1,
             description: String::from("§eFalcon server§r§b!!!"),
             world: None,
+            seed: 0,
+            chunk_cache_mb: 0,
+            default_locale: default_locale(),
+            language_dir: None,
+            ops_file: default_ops_file(),
+            op_permission_level: default_op_level(),
+            permissions_file: default_permissions_file(),
+            whitelist: false,
+            whitelist_file: default_whitelist_file(),
+            banned_players_file: default_banned_players_file(),
+            banned_ips_file: default_banned_ips_file(),
+            tags_dir: default_tags_dir(),
+            items_dir: default_items_dir(),
         }
     }
 }
@@ -93,6 +582,64 @@ impl Default for ServerSettings {
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct VersionSettings {
     pub excluded: Vec<u32>,
+    /// The oldest protocol clients may join with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_protocol: Option<u32>,
+    /// The newest protocol clients may join with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_protocol: Option<u32>,
+    /// Unsupported clients this many protocol numbers away from an enabled
+    /// version see their own protocol in the server list, as if the server
+    /// was compatible. Joining is still refused.
+    #[serde(default)]
+    pub status_window: u32,
+    /// A file or directory with protocol tables for the
+    /// [`ProtocolRegistry`](crate::network::registry::ProtocolRegistry).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_tables: Option<String>,
+}
+
+impl VersionSettings {
+    /// Whether the config allows `protocol`, this does not check if the
+    /// protocol is supported at all.
+    pub fn allows(&self, protocol: u32) -> bool {
+        !self.excluded.contains(&protocol)
+            && !matches!(self.min_protocol, Some(min) if protocol < min)
+            && !matches!(self.max_protocol, Some(max) if protocol > max)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DebugSettings {
+    /// Log every packet on the `falcon::packets` tracing target at debug
+    /// level.
+    #[serde(default)]
+    pub packet_dump: bool,
+    /// Include a hex dump of the packet contents.
+    #[serde(default)]
+    pub hex_dump: bool,
+    /// Only dump packets of connections from these addresses, all
+    /// connections when empty.
+    #[serde(default)]
+    pub dump_addresses: Vec<IpAddr>,
+    /// Only dump packets with these ids as sent over the wire, all packets
+    /// when empty.
+    #[serde(default)]
+    pub dump_packet_ids: Vec<i32>,
+    /// Record the packets of every connection to a file in this directory,
+    /// see `falcon_logic::connection::record`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_dir: Option<String>,
+}
+
+impl DebugSettings {
+    /// Whether packets with `packet_id` of a connection from `address`
+    /// should be dumped.
+    pub fn dumps(&self, address: IpAddr, packet_id: i32) -> bool {
+        self.packet_dump
+            && (self.dump_addresses.is_empty() || self.dump_addresses.contains(&address))
+            && (self.dump_packet_ids.is_empty() || self.dump_packet_ids.contains(&packet_id))
+    }
 }
 
 mod tracing_serde {
@@ -2,7 +2,9 @@ use std::borrow::Cow;
 
 use serde::Serialize;
 
+use crate::network::registry::ProtocolRegistry;
 use crate::server::config::FalconConfig;
+use crate::server::lang::MessageCatalog;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Difficulty {
@@ -24,21 +26,104 @@ pub struct ServerVersion {
 
 impl ServerVersion {
     pub fn new<T: Into<Cow<'static, str>>>(name: T, protocol_id: i32) -> Self {
-        let excluded = &FalconConfig::global().versions.excluded;
-        let (name, version) = if !FalconConfig::ALLOWED_VERSIONS.contains(&protocol_id.unsigned_abs()) || excluded.contains(&protocol_id.unsigned_abs()) {
-            let (name, mut protocol) = ("Unsupported version".into(), FalconConfig::ALLOWED_VERSIONS[0]);
-            for version in FalconConfig::ALLOWED_VERSIONS {
-                if !excluded.contains(&version) {
-                    protocol = version;
-                }
-            }
-            (name, protocol as i32)
-        } else {
-            (name.into(), protocol_id)
+        let (name, protocol) = match ProtocolSupport::of(protocol_id) {
+            ProtocolSupport::Supported => (name.into(), protocol_id),
+            _ if in_status_window(protocol_id) => (name.into(), protocol_id),
+            ProtocolSupport::OutdatedClient => (format!("Outdated client, use {}", supported_versions()).into(), latest_protocol()),
+            ProtocolSupport::OutdatedServer => (format!("Outdated server, still on {}", supported_versions()).into(), latest_protocol()),
+            ProtocolSupport::Unsupported => (format!("Unsupported version, use {}", supported_versions()).into(), latest_protocol()),
         };
-        ServerVersion {
-            name,
-            protocol: version,
+        ServerVersion { name, protocol }
+    }
+}
+
+/// How the protocol of a client relates to the versions this server
+/// accepts, see [`VersionSettings`](crate::server::config::VersionSettings).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolSupport {
+    Supported,
+    /// The client is older than every accepted version.
+    OutdatedClient,
+    /// The client is newer than every accepted version.
+    OutdatedServer,
+    /// The version lies within the accepted range but is unknown or
+    /// disabled.
+    Unsupported,
+}
+
+impl ProtocolSupport {
+    pub fn of(protocol_id: i32) -> Self {
+        let protocol = protocol_id.unsigned_abs();
+        let settings = &FalconConfig::global().versions;
+        let known = FalconConfig::ALLOWED_VERSIONS.contains(&protocol) || ProtocolRegistry::global().get(protocol_id).is_some();
+        if known && settings.allows(protocol) {
+            return ProtocolSupport::Supported;
+        }
+        match (enabled_protocols().next(), enabled_protocols().last()) {
+            (Some(oldest), _) if protocol < oldest => ProtocolSupport::OutdatedClient,
+            (_, Some(newest)) if protocol > newest => ProtocolSupport::OutdatedServer,
+            _ => ProtocolSupport::Unsupported,
         }
     }
+
+    /// The reason shown to clients that are not allowed to join, in the
+    /// default locale as the locale of the client is not known yet.
+    pub fn kick_reason(&self) -> Option<String> {
+        match self {
+            ProtocolSupport::Supported => None,
+            ProtocolSupport::OutdatedClient => Some(MessageCatalog::global().default_message("version.outdated_client", &[&supported_versions()])),
+            ProtocolSupport::OutdatedServer => Some(MessageCatalog::global().default_message("version.outdated_server", &[&supported_versions()])),
+            ProtocolSupport::Unsupported => Some(MessageCatalog::global().default_message("version.unsupported", &[&supported_versions()])),
+        }
+    }
+}
+
+/// The natively supported protocols that are enabled in the config, in
+/// ascending order.
+pub fn enabled_protocols() -> impl Iterator<Item = u32> {
+    let settings = &FalconConfig::global().versions;
+    FalconConfig::ALLOWED_VERSIONS.into_iter().filter(|protocol| settings.allows(*protocol))
+}
+
+/// Whether `protocol_id` is close enough to an enabled protocol to echo it
+/// in the status response.
+fn in_status_window(protocol_id: i32) -> bool {
+    let window = FalconConfig::global().versions.status_window;
+    window > 0 && enabled_protocols().any(|protocol| protocol.abs_diff(protocol_id.unsigned_abs()) <= window)
+}
+
+/// The newest enabled protocol, used when a client can't be served.
+fn latest_protocol() -> i32 { enabled_protocols().last().unwrap_or(FalconConfig::ALLOWED_VERSIONS[0]) as i32 }
+
+/// The range of enabled versions, e.g. `1.13-1.17.1`.
+pub fn supported_versions() -> String {
+    let oldest = enabled_protocols().find_map(version_name);
+    let newest = enabled_protocols().filter_map(version_name).last();
+    match (oldest, newest) {
+        (Some(oldest), Some(newest)) if oldest != newest => format!("{}-{}", oldest, newest),
+        (Some(version), _) => version.to_owned(),
+        _ => String::from("no version"),
+    }
+}
+
+/// The name of the latest release using a natively supported protocol.
+pub fn version_name(protocol: u32) -> Option<&'static str> {
+    Some(match protocol {
+        393 => "1.13",
+        401 => "1.13.1",
+        404 => "1.13.2",
+        477 => "1.14",
+        480 => "1.14.1",
+        485 => "1.14.2",
+        490 => "1.14.3",
+        498 => "1.14.4",
+        573 => "1.15",
+        575 => "1.15.1",
+        578 => "1.15.2",
+        735 => "1.16",
+        736 => "1.16.1",
+        755 => "1.17",
+        756 => "1.17.1",
+        _ => return None,
+    })
 }
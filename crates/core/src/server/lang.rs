@@ -0,0 +1,156 @@
+//! Translated server messages.
+//!
+//! Every locale is a TOML file named after the locale, e.g. `de_de.toml`,
+//! with the messages as strings. Tables are flattened, so `timed_out` in
+//! the `disconnect` table has the key `disconnect.timed_out`:
+//! ```toml
+//! [disconnect]
+//! timed_out = "Zeitüberschreitung"
+//! invalid_packet = "Ungültiges Paket: {0}"
+//! ```
+//!
+//! Messages that are missing in a locale fall back to the default locale
+//! of the config and then to the built-in English messages.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use ahash::AHashMap;
+use once_cell::sync::OnceCell;
+use thiserror::Error;
+use toml::Value;
+
+use crate::server::config::FalconConfig;
+
+static INSTANCE: OnceCell<MessageCatalog> = OnceCell::new();
+
+/// The locale of the built-in messages.
+pub const BUILTIN_LOCALE: &str = "en_us";
+const BUILTIN_MESSAGES: &str = include_str!("../../lang/en_us.toml");
+
+#[derive(Error, Debug)]
+pub enum MessageCatalogError {
+    #[error("Error while reading a language file")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid language file {0:?}: {1}")]
+    Toml(PathBuf, toml::de::Error),
+    #[error("Message {0:?} is not a string")]
+    InvalidMessage(String),
+}
+
+/// The messages of every known locale.
+#[derive(Debug)]
+pub struct MessageCatalog {
+    locales: AHashMap<String, AHashMap<String, String>>,
+}
+
+impl Default for MessageCatalog {
+    fn default() -> Self {
+        let mut catalog = MessageCatalog { locales: AHashMap::new() };
+        catalog.load_toml(BUILTIN_LOCALE, BUILTIN_MESSAGES).expect("Invalid built-in messages");
+        catalog
+    }
+}
+
+impl MessageCatalog {
+    /// The global catalog, only the built-in messages unless
+    /// [`MessageCatalog::init_global`] was called.
+    pub fn global() -> &'static MessageCatalog { INSTANCE.get_or_init(MessageCatalog::default) }
+
+    /// Installs this catalog as the global one, returns it back when the
+    /// global catalog was already in use.
+    pub fn init_global(self) -> Result<(), MessageCatalog> { INSTANCE.set(self) }
+
+    /// Loads every `.toml` file in `dir` on top of the built-in messages.
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Result<Self, MessageCatalogError> {
+        let mut catalog = MessageCatalog::default();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+                continue;
+            }
+            if let Some(locale) = path.file_stem().and_then(|s| s.to_str()) {
+                let contents = std::fs::read_to_string(&path)?;
+                let value = contents.parse::<Value>().map_err(|e| MessageCatalogError::Toml(path.clone(), e))?;
+                catalog.insert_value(&locale.to_lowercase(), String::new(), value)?;
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// Adds the messages of a TOML document to `locale`.
+    pub fn load_toml(&mut self, locale: &str, input: &str) -> Result<(), MessageCatalogError> {
+        let value = input.parse::<Value>().map_err(|e| MessageCatalogError::Toml(PathBuf::from(locale), e))?;
+        self.insert_value(&locale.to_lowercase(), String::new(), value)
+    }
+
+    fn insert_value(&mut self, locale: &str, key: String, value: Value) -> Result<(), MessageCatalogError> {
+        match value {
+            Value::String(message) => {
+                self.locales.entry(locale.to_owned()).or_default().insert(key, message);
+            },
+            Value::Table(table) => {
+                for (name, value) in table {
+                    let key = if key.is_empty() { name } else { format!("{}.{}", key, name) };
+                    self.insert_value(locale, key, value)?;
+                }
+            },
+            _ => return Err(MessageCatalogError::InvalidMessage(key)),
+        }
+        Ok(())
+    }
+
+    /// Whether any message is known for `locale`.
+    pub fn has_locale(&self, locale: &str) -> bool { self.locales.contains_key(locale) }
+
+    /// The raw message for `key` in `locale` or one of the fallbacks.
+    pub fn get(&self, locale: &str, key: &str) -> Option<&str> {
+        [locale, default_locale(), BUILTIN_LOCALE]
+            .into_iter()
+            .find_map(|locale| self.locales.get(locale).and_then(|messages| messages.get(key)))
+            .map(String::as_str)
+    }
+
+    /// The message for `key` in `locale` with `{n}` replaced by the n-th
+    /// argument, the key itself when no message is known.
+    pub fn message(&self, locale: &str, key: &str, args: &[&dyn Display]) -> String {
+        let Some(message) = self.get(locale, key) else {
+            return key.to_owned();
+        };
+        args.iter().enumerate().fold(message.to_owned(), |message, (i, arg)| message.replace(&format!("{{{}}}", i), &arg.to_string()))
+    }
+
+    /// The message for `key` in the default locale.
+    pub fn default_message(&self, key: &str, args: &[&dyn Display]) -> String { self.message(default_locale(), key, args) }
+}
+
+/// The locale of the config, used when the locale of a client is unknown.
+pub fn default_locale() -> &'static str {
+    FalconConfig::global().server.default_locale.as_str()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        FalconConfig::init_default();
+        let mut catalog = MessageCatalog::default();
+        catalog
+            .load_toml(
+                "de_DE",
+                r#"
+                [disconnect]
+                invalid_packet = "Ungültiges Paket: {0}"
+                "#,
+            )
+            .unwrap();
+
+        assert_eq!(catalog.message("de_de", "disconnect.invalid_packet", &[&"id"]), "Ungültiges Paket: id");
+        assert_eq!(catalog.message("de_de", "disconnect.shutdown", &[]), "Server closed");
+        assert_eq!(catalog.message("fr_fr", "player.joined", &[&"Steve"]), "Steve joined the game");
+        assert_eq!(catalog.message("de_de", "missing.key", &[]), "missing.key");
+        assert!(catalog.load_toml("de_de", "number = 5").is_err());
+    }
+}
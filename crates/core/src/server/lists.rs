@@ -0,0 +1,275 @@
+//! Player lists stored in the JSON format of vanilla, like `whitelist.json`,
+//! `banned-players.json` and `banned-ips.json`.
+
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ahash::AHashMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum PlayerListError {
+    #[error("Error while accessing a player list file")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid player list file {0:?}: {1}")]
+    Json(PathBuf, serde_json::Error),
+}
+
+/// An entry of a [`JsonList`].
+pub trait ListEntry: Serialize + DeserializeOwned {
+    /// What the entries are looked up by, the uuid of a player or an
+    /// address.
+    type Key: Copy + Eq + Hash + Debug;
+
+    fn key(&self) -> Self::Key;
+
+    /// The name the entries are found and sorted by.
+    fn name(&self) -> Cow<'_, str>;
+}
+
+/// Players or addresses with an entry each, saved to a file on every change
+/// when loaded from one.
+#[derive(Debug)]
+pub struct JsonList<E: ListEntry> {
+    path: Option<PathBuf>,
+    entries: AHashMap<E::Key, E>,
+}
+
+impl<E: ListEntry> Default for JsonList<E> {
+    fn default() -> Self {
+        JsonList {
+            path: None,
+            entries: AHashMap::new(),
+        }
+    }
+}
+
+impl<E: ListEntry> JsonList<E> {
+    /// Reads the entries from `path`, a missing file is an empty list.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PlayerListError> {
+        let mut list = JsonList {
+            path: Some(path.as_ref().to_path_buf()),
+            entries: AHashMap::new(),
+        };
+        list.reload()?;
+        Ok(list)
+    }
+
+    /// A list that is only kept in memory.
+    pub fn from_entries(entries: Vec<E>) -> Self {
+        JsonList {
+            path: None,
+            entries: entries.into_iter().map(|entry| (entry.key(), entry)).collect(),
+        }
+    }
+
+    /// Reads the entries from the file again, dropping unsaved changes.
+    pub fn reload(&mut self) -> Result<(), PlayerListError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let entries: Vec<E> = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|e| PlayerListError::Json(path.clone(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        self.entries = entries.into_iter().map(|entry| (entry.key(), entry)).collect();
+        Ok(())
+    }
+
+    /// Writes the entries back to the file they were loaded from.
+    pub fn save(&self) -> Result<(), PlayerListError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut entries: Vec<&E> = self.entries.values().collect();
+        entries.sort_by(|a, b| a.name().cmp(&b.name()));
+        let json = serde_json::to_string_pretty(&entries).map_err(|e| PlayerListError::Json(path.clone(), e))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    pub fn get(&self, key: E::Key) -> Option<&E> { self.entries.get(&key) }
+
+    pub fn contains(&self, key: E::Key) -> bool { self.entries.contains_key(&key) }
+
+    /// Finds an entry by its name, ignoring case.
+    pub fn get_by_name(&self, name: &str) -> Option<&E> { self.entries.values().find(|entry| entry.name().eq_ignore_ascii_case(name)) }
+
+    pub fn entries(&self) -> impl Iterator<Item = &E> { self.entries.values() }
+
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Adds or replaces the entry with the same key and saves the list,
+    /// returns the replaced entry.
+    pub fn insert(&mut self, entry: E) -> Result<Option<E>, PlayerListError> {
+        let old = self.entries.insert(entry.key(), entry);
+        self.save()?;
+        Ok(old)
+    }
+
+    /// Removes the entry of `key` and saves the list when there was one.
+    pub fn remove(&mut self, key: E::Key) -> Result<Option<E>, PlayerListError> {
+        let old = self.entries.remove(&key);
+        if old.is_some() {
+            self.save()?;
+        }
+        Ok(old)
+    }
+}
+
+/// A player that may join while the whitelist is on.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhitelistEntry {
+    pub uuid: Uuid,
+    pub name: String,
+}
+
+impl ListEntry for WhitelistEntry {
+    type Key = Uuid;
+
+    fn key(&self) -> Uuid { self.uuid }
+
+    fn name(&self) -> Cow<'_, str> { Cow::Borrowed(&self.name) }
+}
+
+pub type Whitelist = JsonList<WhitelistEntry>;
+
+/// A banned player, bans do not expire yet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanEntry {
+    pub uuid: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub created: String,
+    #[serde(default)]
+    pub source: String,
+    #[serde(default = "forever")]
+    pub expires: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+fn forever() -> String { String::from("forever") }
+
+impl BanEntry {
+    /// A ban from now on by `source`.
+    pub fn new(uuid: Uuid, name: String, source: String, reason: String) -> Self {
+        BanEntry {
+            uuid,
+            name,
+            created: format_timestamp(SystemTime::now()),
+            source,
+            expires: forever(),
+            reason,
+        }
+    }
+}
+
+impl ListEntry for BanEntry {
+    type Key = Uuid;
+
+    fn key(&self) -> Uuid { self.uuid }
+
+    fn name(&self) -> Cow<'_, str> { Cow::Borrowed(&self.name) }
+}
+
+pub type BanList = JsonList<BanEntry>;
+
+/// A banned address, checked against the address of the client behind a
+/// proxy when the PROXY protocol is on.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpBanEntry {
+    pub ip: IpAddr,
+    #[serde(default)]
+    pub created: String,
+    #[serde(default)]
+    pub source: String,
+    #[serde(default = "forever")]
+    pub expires: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+impl IpBanEntry {
+    /// A ban from now on by `source`.
+    pub fn new(ip: IpAddr, source: String, reason: String) -> Self {
+        IpBanEntry {
+            ip,
+            created: format_timestamp(SystemTime::now()),
+            source,
+            expires: forever(),
+            reason,
+        }
+    }
+}
+
+impl ListEntry for IpBanEntry {
+    type Key = IpAddr;
+
+    fn key(&self) -> IpAddr { self.ip }
+
+    fn name(&self) -> Cow<'_, str> { Cow::Owned(self.ip.to_string()) }
+}
+
+pub type IpBanList = JsonList<IpBanEntry>;
+
+/// Formats `time` like vanilla, e.g. `2022-10-16 13:05:09 +0000`.
+pub fn format_timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, secs) = ((secs / 86400) as i64, secs % 86400);
+    // days since the epoch to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02} +0000", year, month, day, secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_ban_list() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01 00:00:00 +0000");
+        assert_eq!(format_timestamp(UNIX_EPOCH + Duration::from_secs(1665925509)), "2022-10-16 13:05:09 +0000");
+
+        let json = r#"[{"uuid": "069a79f4-44e9-4726-a5be-fca90e38aaf5", "name": "Notch", "created": "2022-10-16 13:05:09 +0000", "source": "Server", "expires": "forever", "reason": "Banned by an operator."}]"#;
+        let mut bans = BanList::from_entries(serde_json::from_str(json).unwrap());
+        let notch = Uuid::parse_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap();
+        assert_eq!(bans.get_by_name("notch").map(|entry| entry.uuid), Some(notch));
+        assert_eq!(bans.get(notch).unwrap().reason, "Banned by an operator.");
+        assert!(bans.remove(notch).unwrap().is_some());
+        assert!(bans.is_empty());
+    }
+
+    #[test]
+    fn test_ip_ban_list() {
+        let json = r#"[{"ip": "192.168.0.7", "created": "2022-10-16 13:05:09 +0000", "source": "Server", "expires": "forever", "reason": "Banned by an operator."}]"#;
+        let mut bans = IpBanList::from_entries(serde_json::from_str(json).unwrap());
+        let address: IpAddr = "192.168.0.7".parse().unwrap();
+        assert_eq!(bans.get(address).unwrap().reason, "Banned by an operator.");
+        assert!(!bans.contains("192.168.0.8".parse().unwrap()));
+        assert_eq!(bans.get_by_name("192.168.0.7").map(|entry| entry.ip), Some(address));
+        assert!(bans.remove(address).unwrap().is_some());
+        assert!(bans.is_empty());
+    }
+}
@@ -2,5 +2,9 @@
 
 pub mod config;
 pub mod data;
+pub mod lang;
+pub mod lists;
+pub mod ops;
+pub mod permissions;
 
 // pub type McTask<D, L> = dyn FnOnce(&mut MainServer<D, L>) + Send + Sync;
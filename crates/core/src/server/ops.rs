@@ -0,0 +1,96 @@
+//! Server operators and their permission levels.
+//!
+//! The operators are stored in the same format as the vanilla `ops.json`:
+//! ```json
+//! [
+//!   {
+//!     "uuid": "069a79f4-44e9-4726-a5be-fca90e38aaf5",
+//!     "name": "Notch",
+//!     "level": 4,
+//!     "bypassesPlayerLimit": false
+//!   }
+//! ]
+//! ```
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::lists::{JsonList, ListEntry, PlayerListError};
+
+/// The level of the console, operators can have at most this level.
+pub const MAX_PERMISSION_LEVEL: u8 = 4;
+
+/// A single operator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpEntry {
+    pub uuid: Uuid,
+    pub name: String,
+    pub level: u8,
+    #[serde(default)]
+    pub bypasses_player_limit: bool,
+}
+
+impl ListEntry for OpEntry {
+    type Key = Uuid;
+
+    fn key(&self) -> Uuid { self.uuid }
+
+    fn name(&self) -> Cow<'_, str> { Cow::Borrowed(&self.name) }
+}
+
+/// The operators of the server.
+pub type OpList = JsonList<OpEntry>;
+
+impl JsonList<OpEntry> {
+    /// The permission level of `uuid`, 0 for players that are not an
+    /// operator.
+    pub fn level(&self, uuid: Uuid) -> u8 { self.get(uuid).map(|entry| entry.level).unwrap_or(0) }
+
+    pub fn bypasses_player_limit(&self, uuid: Uuid) -> bool { self.get(uuid).map(|entry| entry.bypasses_player_limit).unwrap_or(false) }
+
+    /// Makes `uuid` an operator with `level`, returns false when it
+    /// already had that level.
+    pub fn op(&mut self, uuid: Uuid, name: String, level: u8) -> Result<bool, PlayerListError> {
+        let level = level.clamp(1, MAX_PERMISSION_LEVEL);
+        if self.level(uuid) == level {
+            return Ok(false);
+        }
+        let bypasses_player_limit = self.bypasses_player_limit(uuid);
+        self.insert(OpEntry {
+            uuid,
+            name,
+            level,
+            bypasses_player_limit,
+        })?;
+        Ok(true)
+    }
+
+    /// Removes `uuid` from the operators, returns false when it was not an
+    /// operator.
+    pub fn deop(&mut self, uuid: Uuid) -> Result<bool, PlayerListError> { Ok(self.remove(uuid)?.is_some()) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_vanilla_format() {
+        let json = r#"[{"uuid": "069a79f4-44e9-4726-a5be-fca90e38aaf5", "name": "Notch", "level": 3, "bypassesPlayerLimit": true}]"#;
+        let mut ops = OpList::from_entries(serde_json::from_str(json).unwrap());
+        let notch = Uuid::parse_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap();
+        assert_eq!(ops.level(notch), 3);
+        assert!(ops.bypasses_player_limit(notch));
+        assert_eq!(ops.level(Uuid::nil()), 0);
+
+        assert!(!ops.op(notch, String::from("Notch"), 3).unwrap());
+        assert!(ops.op(notch, String::from("Notch"), 9).unwrap());
+        assert_eq!(ops.level(notch), MAX_PERMISSION_LEVEL);
+        assert!(ops.bypasses_player_limit(notch));
+        assert!(ops.deop(notch).unwrap());
+        assert!(!ops.deop(notch).unwrap());
+    }
+}
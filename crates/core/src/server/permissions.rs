@@ -0,0 +1,183 @@
+//! Permission nodes of players.
+//!
+//! A [`PermissionsProvider`] decides whether a player has a permission
+//! node like `falcon.command.gamemode`. Operators keep the commands of
+//! their op level on top of this, see
+//! [`OpList`](crate::server::ops::OpList).
+//!
+//! The default provider is [`FilePermissions`], which reads a TOML file:
+//! ```toml
+//! # every player is in the default group
+//! [groups.default]
+//! permissions = ["falcon.command.list"]
+//!
+//! [groups.moderator]
+//! inherits = ["default"]
+//! permissions = ["falcon.command.*", "-falcon.command.stop"]
+//!
+//! [players.069a79f4-44e9-4726-a5be-fca90e38aaf5]
+//! groups = ["moderator"]
+//! permissions = ["falcon.command.stop"]
+//! ```
+//! A node ending in `*` grants every node starting with the part in front
+//! of it and a node starting with `-` takes a permission away. The most
+//! specific node wins. The nodes of the player win over those of its
+//! groups, which win over inherited groups and lastly the default group.
+
+use std::path::{Path, PathBuf};
+
+use ahash::{AHashMap, AHashSet};
+use serde::Deserialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// The group every player is a member of, checked after all others.
+pub const DEFAULT_GROUP: &str = "default";
+
+/// Decides which permission nodes players have, implement this to use
+/// another permissions backend.
+pub trait PermissionsProvider: Send + Sync {
+    fn has_permission(&self, player: Uuid, node: &str) -> bool;
+}
+
+#[derive(Error, Debug)]
+pub enum PermissionsError {
+    #[error("Error while reading the permissions file")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid permissions file {0:?}: {1}")]
+    Toml(PathBuf, toml::de::Error),
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Group {
+    #[serde(default)]
+    inherits: Vec<String>,
+    #[serde(default)]
+    permissions: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PlayerEntry {
+    #[serde(default)]
+    groups: Vec<String>,
+    #[serde(default)]
+    permissions: Vec<String>,
+}
+
+/// Groups and players with permission nodes from a TOML file.
+#[derive(Debug, Default, Deserialize)]
+pub struct FilePermissions {
+    #[serde(default)]
+    groups: AHashMap<String, Group>,
+    #[serde(default)]
+    players: AHashMap<Uuid, PlayerEntry>,
+}
+
+impl FilePermissions {
+    /// Reads the permissions from `path`, a missing file grants nothing.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PermissionsError> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(contents) => FilePermissions::from_toml(&contents).map_err(|e| PermissionsError::Toml(path.to_path_buf(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(FilePermissions::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn from_toml(input: &str) -> Result<Self, toml::de::Error> { toml::from_str(input) }
+
+    /// Finds the most specific node matching `node`, `None` when neither
+    /// the player nor any of its groups mention it.
+    fn check(&self, player: Uuid, node: &str) -> Option<bool> {
+        let entry = self.players.get(&player);
+        if let Some(allowed) = entry.and_then(|entry| best_match(&entry.permissions, node)) {
+            return Some(allowed);
+        }
+        let mut groups: Vec<&str> = entry.map(|entry| entry.groups.iter().map(String::as_str).collect()).unwrap_or_default();
+        // breadth first, groups closer to the player win over inherited ones
+        let mut visited = AHashSet::new();
+        while !groups.is_empty() {
+            let level: Vec<&Group> = groups.drain(..).filter(|name| visited.insert(*name)).filter_map(|name| self.groups.get(name)).collect();
+            if let Some(allowed) = best_match(level.iter().flat_map(|group| group.permissions.iter()), node) {
+                return Some(allowed);
+            }
+            groups.extend(level.iter().flat_map(|group| group.inherits.iter().map(String::as_str)));
+        }
+        if visited.contains(DEFAULT_GROUP) {
+            return None;
+        }
+        self.groups.get(DEFAULT_GROUP).and_then(|group| best_match(&group.permissions, node))
+    }
+}
+
+impl PermissionsProvider for FilePermissions {
+    fn has_permission(&self, player: Uuid, node: &str) -> bool { self.check(player, node).unwrap_or(false) }
+}
+
+/// The permission of the most specific pattern matching `node`, negated
+/// patterns win over granted ones that are as specific.
+fn best_match<'a, I: IntoIterator<Item = &'a String>>(patterns: I, node: &str) -> Option<bool> {
+    patterns
+        .into_iter()
+        .filter_map(|pattern| {
+            let (allowed, pattern) = match pattern.strip_prefix('-') {
+                Some(pattern) => (false, pattern),
+                None => (true, pattern.as_str()),
+            };
+            let specificity = match pattern.strip_suffix('*') {
+                Some(prefix) if node.starts_with(prefix) => prefix.len(),
+                Some(_) => return None,
+                None if pattern == node => usize::MAX,
+                None => return None,
+            };
+            Some((specificity, !allowed))
+        })
+        .max()
+        .map(|(_, denied)| !denied)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_file_permissions() {
+        let admin = Uuid::from_u128(1);
+        let moderator = Uuid::from_u128(2);
+        let permissions = FilePermissions::from_toml(&format!(
+            r#"
+            [groups.default]
+            permissions = ["falcon.command.list"]
+
+            [groups.moderator]
+            inherits = ["default"]
+            permissions = ["falcon.command.*", "-falcon.command.stop"]
+
+            [groups.admin]
+            inherits = ["moderator"]
+            permissions = ["*"]
+
+            [players.{}]
+            groups = ["admin"]
+
+            [players.{}]
+            groups = ["moderator"]
+            permissions = ["-falcon.command.list"]
+            "#,
+            admin, moderator
+        ))
+        .unwrap();
+
+        let player = Uuid::from_u128(3);
+        assert!(permissions.has_permission(player, "falcon.command.list"));
+        assert!(!permissions.has_permission(player, "falcon.command.kick"));
+
+        assert!(permissions.has_permission(moderator, "falcon.command.kick"));
+        assert!(!permissions.has_permission(moderator, "falcon.command.stop"));
+        assert!(!permissions.has_permission(moderator, "falcon.command.list"));
+        assert!(!permissions.has_permission(moderator, "other.node"));
+
+        assert!(permissions.has_permission(admin, "falcon.command.stop"));
+        assert!(permissions.has_permission(admin, "other.node"));
+    }
+}
@@ -0,0 +1,266 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// The biomes a chunk can have, these decide the colors of grass, leaves,
+/// water and the sky.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Biome {
+    #[serde(rename = "minecraft:ocean")]
+    Ocean,
+    #[default]
+    #[serde(rename = "minecraft:plains")]
+    Plains,
+    #[serde(rename = "minecraft:desert")]
+    Desert,
+    #[serde(rename = "minecraft:mountains")]
+    Mountains,
+    #[serde(rename = "minecraft:forest")]
+    Forest,
+    #[serde(rename = "minecraft:taiga")]
+    Taiga,
+    #[serde(rename = "minecraft:swamp")]
+    Swamp,
+    #[serde(rename = "minecraft:river")]
+    River,
+    #[serde(rename = "minecraft:nether_wastes")]
+    NetherWastes,
+    #[serde(rename = "minecraft:the_end")]
+    TheEnd,
+    #[serde(rename = "minecraft:frozen_ocean")]
+    FrozenOcean,
+    #[serde(rename = "minecraft:snowy_tundra")]
+    SnowyTundra,
+    #[serde(rename = "minecraft:mushroom_fields")]
+    MushroomFields,
+    #[serde(rename = "minecraft:beach")]
+    Beach,
+    #[serde(rename = "minecraft:jungle")]
+    Jungle,
+    #[serde(rename = "minecraft:deep_ocean")]
+    DeepOcean,
+    #[serde(rename = "minecraft:birch_forest")]
+    BirchForest,
+    #[serde(rename = "minecraft:dark_forest")]
+    DarkForest,
+    #[serde(rename = "minecraft:snowy_taiga")]
+    SnowyTaiga,
+    #[serde(rename = "minecraft:savanna")]
+    Savanna,
+    #[serde(rename = "minecraft:badlands")]
+    Badlands,
+    #[serde(rename = "minecraft:warm_ocean")]
+    WarmOcean,
+}
+
+/// How a biome looks, as registered with 1.16.2+ clients.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BiomeProperties {
+    pub temperature: f32,
+    pub downfall: f32,
+    /// `rain`, `snow` or `none`.
+    pub precipitation: &'static str,
+    /// The biome category of 1.16.2 up to 1.18.
+    pub category: &'static str,
+    pub water_color: i32,
+    pub water_fog_color: i32,
+    pub sky_color: i32,
+    pub fog_color: i32,
+}
+
+const OVERWORLD_FOG: i32 = 12638463;
+const WATER: i32 = 4159204;
+const WATER_FOG: i32 = 329011;
+
+impl Biome {
+    /// Every biome, ordered by their id in the biome registry.
+    pub const ALL: [Biome; 22] = [
+        Biome::Ocean,
+        Biome::Plains,
+        Biome::Desert,
+        Biome::Mountains,
+        Biome::Forest,
+        Biome::Taiga,
+        Biome::Swamp,
+        Biome::River,
+        Biome::NetherWastes,
+        Biome::TheEnd,
+        Biome::FrozenOcean,
+        Biome::SnowyTundra,
+        Biome::MushroomFields,
+        Biome::Beach,
+        Biome::Jungle,
+        Biome::DeepOcean,
+        Biome::BirchForest,
+        Biome::DarkForest,
+        Biome::SnowyTaiga,
+        Biome::Savanna,
+        Biome::Badlands,
+        Biome::WarmOcean,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Biome::Ocean => "minecraft:ocean",
+            Biome::Plains => "minecraft:plains",
+            Biome::Desert => "minecraft:desert",
+            Biome::Mountains => "minecraft:mountains",
+            Biome::Forest => "minecraft:forest",
+            Biome::Taiga => "minecraft:taiga",
+            Biome::Swamp => "minecraft:swamp",
+            Biome::River => "minecraft:river",
+            Biome::NetherWastes => "minecraft:nether_wastes",
+            Biome::TheEnd => "minecraft:the_end",
+            Biome::FrozenOcean => "minecraft:frozen_ocean",
+            Biome::SnowyTundra => "minecraft:snowy_tundra",
+            Biome::MushroomFields => "minecraft:mushroom_fields",
+            Biome::Beach => "minecraft:beach",
+            Biome::Jungle => "minecraft:jungle",
+            Biome::DeepOcean => "minecraft:deep_ocean",
+            Biome::BirchForest => "minecraft:birch_forest",
+            Biome::DarkForest => "minecraft:dark_forest",
+            Biome::SnowyTaiga => "minecraft:snowy_taiga",
+            Biome::Savanna => "minecraft:savanna",
+            Biome::Badlands => "minecraft:badlands",
+            Biome::WarmOcean => "minecraft:warm_ocean",
+        }
+    }
+
+    /// The id in the biome registry that is sent to 1.16.2+ clients.
+    pub fn registry_id(&self) -> i32 { *self as i32 }
+
+    /// The fixed id of the biome before 1.16.2.
+    pub fn legacy_id(&self) -> i32 {
+        match self {
+            Biome::Ocean => 0,
+            Biome::Plains => 1,
+            Biome::Desert => 2,
+            Biome::Mountains => 3,
+            Biome::Forest => 4,
+            Biome::Taiga => 5,
+            Biome::Swamp => 6,
+            Biome::River => 7,
+            Biome::NetherWastes => 8,
+            Biome::TheEnd => 9,
+            Biome::FrozenOcean => 10,
+            Biome::SnowyTundra => 12,
+            Biome::MushroomFields => 14,
+            Biome::Beach => 16,
+            Biome::Jungle => 21,
+            Biome::DeepOcean => 24,
+            Biome::BirchForest => 27,
+            Biome::DarkForest => 29,
+            Biome::SnowyTaiga => 30,
+            Biome::Savanna => 35,
+            Biome::Badlands => 37,
+            Biome::WarmOcean => 44,
+        }
+    }
+
+    pub fn from_legacy_id(id: i32) -> Option<Biome> { Biome::ALL.into_iter().find(|biome| biome.legacy_id() == id) }
+
+    pub fn properties(&self) -> BiomeProperties {
+        let (temperature, downfall, precipitation, category) = match self {
+            Biome::Ocean | Biome::DeepOcean | Biome::WarmOcean => (0.5, 0.5, "rain", "ocean"),
+            Biome::Plains => (0.8, 0.4, "rain", "plains"),
+            Biome::Desert => (2.0, 0.0, "none", "desert"),
+            Biome::Mountains => (0.2, 0.3, "rain", "extreme_hills"),
+            Biome::Forest | Biome::DarkForest => (0.7, 0.8, "rain", "forest"),
+            Biome::BirchForest => (0.6, 0.6, "rain", "forest"),
+            Biome::Taiga => (0.25, 0.8, "rain", "taiga"),
+            Biome::SnowyTaiga => (-0.5, 0.4, "snow", "taiga"),
+            Biome::Swamp => (0.8, 0.9, "rain", "swamp"),
+            Biome::River => (0.5, 0.5, "rain", "river"),
+            Biome::NetherWastes => (2.0, 0.0, "none", "nether"),
+            Biome::TheEnd => (0.5, 0.5, "none", "the_end"),
+            Biome::FrozenOcean => (0.0, 0.5, "snow", "ocean"),
+            Biome::SnowyTundra => (0.0, 0.5, "snow", "icy"),
+            Biome::MushroomFields => (0.9, 1.0, "rain", "mushroom"),
+            Biome::Beach => (0.8, 0.4, "rain", "beach"),
+            Biome::Jungle => (0.95, 0.9, "rain", "jungle"),
+            Biome::Savanna => (1.2, 0.0, "none", "savanna"),
+            Biome::Badlands => (2.0, 0.0, "none", "mesa"),
+        };
+        let (water_color, water_fog_color) = match self {
+            Biome::Swamp => (6388580, 2302743),
+            Biome::FrozenOcean => (3750089, WATER_FOG),
+            Biome::SnowyTaiga => (4020182, WATER_FOG),
+            Biome::WarmOcean => (4445678, 270131),
+            _ => (WATER, WATER_FOG),
+        };
+        let (sky_color, fog_color) = match self {
+            Biome::NetherWastes => (7254527, 3344392),
+            Biome::TheEnd => (0, 10518688),
+            // the sky is bluer in colder biomes
+            _ => (sky_color(temperature), OVERWORLD_FOG),
+        };
+        BiomeProperties {
+            temperature,
+            downfall,
+            precipitation,
+            category,
+            water_color,
+            water_fog_color,
+            sky_color,
+            fog_color,
+        }
+    }
+}
+
+/// The sky color of vanilla overworld biomes, which only depends on the
+/// temperature.
+fn sky_color(temperature: f32) -> i32 {
+    let t = (temperature / 3.0).clamp(-1.0, 1.0);
+    let (hue, saturation, value) = (0.62222224 - t * 0.05, 0.5 + t * 0.1, 1.0f32);
+    // hsv to rgb, like Mth.hsvToRgb of vanilla
+    let h = (hue - hue.floor()) * 6.0;
+    let f = h - h.floor();
+    let (p, q, t) = (value * (1.0 - saturation), value * (1.0 - saturation * f), value * (1.0 - saturation * (1.0 - f)));
+    let (r, g, b) = match h as i32 {
+        0 => (value, t, p),
+        1 => (q, value, p),
+        2 => (p, value, t),
+        3 => (p, q, value),
+        4 => (t, p, value),
+        _ => (value, p, q),
+    };
+    let channel = |c: f32| (c * 255.0) as i32;
+    (channel(r) << 16) | (channel(g) << 8) | channel(b)
+}
+
+impl FromStr for Biome {
+    type Err = ();
+
+    /// Also knows the names biomes got in 1.18.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.strip_prefix("minecraft:").unwrap_or(s);
+        let biome = match name {
+            "windswept_hills" => Biome::Mountains,
+            "snowy_plains" => Biome::SnowyTundra,
+            _ => return Biome::ALL.into_iter().find(|biome| &biome.name()["minecraft:".len()..] == name).ok_or(()),
+        };
+        Ok(biome)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::Biome;
+
+    #[test]
+    fn test_biomes() {
+        for (id, biome) in Biome::ALL.into_iter().enumerate() {
+            assert_eq!(biome.registry_id(), id as i32);
+            assert_eq!(Biome::from_legacy_id(biome.legacy_id()), Some(biome));
+            assert_eq!(Biome::from_str(biome.name()), Ok(biome));
+        }
+        assert_eq!(Biome::from_str("snowy_plains"), Ok(Biome::SnowyTundra));
+        assert_eq!(Biome::from_str("minecraft:cherry_grove"), Err(()));
+        // the sky colors of vanilla
+        assert_eq!(Biome::Plains.properties().sky_color, 7907327);
+        assert_eq!(Biome::Desert.properties().sky_color, 7254527);
+        assert_eq!(Biome::SnowyTaiga.properties().sky_color, 8625919);
+    }
+}
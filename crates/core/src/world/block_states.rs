@@ -0,0 +1,102 @@
+//! The numeric ids of block states for every protocol version.
+//!
+//! Blocks are kept in one representation, [`Blocks`], whatever version the
+//! players have. The canonical form of a state is its namespaced id with
+//! its properties, e.g. `minecraft:oak_slab[type=top,waterlogged=false]`,
+//! see [`parse_state`] and the `Display` implementation of [`Blocks`]. The
+//! names are generated from `blocks.rs` by `falcon-codegen --block-names`.
+//!
+//! The registry covers the flattened ids of every version in
+//! [`ALLOWED_VERSIONS`](crate::server::config::FalconConfig::ALLOWED_VERSIONS),
+//! 1.13 to 1.17.1. Versions before the flattening are not supported by the
+//! server, so there are no `id:meta` ids. Newer versions are only accepted
+//! once their ids are added here, generated from the vanilla block report of
+//! the version:
+//! ```text
+//! falcon-codegen --block-states crates/core/src/world/blocks.rs --report blocks.json --data-version 3105 \
+//!     -o crates/core/src/world/block_ids_3105.rs
+//! ```
+
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use crate::world::blocks::{Blocks, ParseBlockError};
+use crate::world::palette::PaletteToI32;
+
+/// The ids of the block states as known by clients of `protocol`, `None`
+/// for versions the server does not support.
+pub fn state_ids(protocol: i32) -> Option<PaletteToI32<Blocks>> {
+    let ids: PaletteToI32<Blocks> = match protocol {
+        393 => Blocks::get_global_id_1519,
+        401 | 404 => Blocks::get_global_id_1631,
+        477..=498 => Blocks::get_global_id_1976,
+        573..=578 => Blocks::get_global_id_2230,
+        735 | 736 => Blocks::get_global_id_2567,
+        751..=754 => Blocks::get_global_id_2586,
+        755 | 756 => Blocks::get_global_id_2730,
+        _ => return None,
+    };
+    Some(ids)
+}
+
+/// The id of a block state for clients of `protocol`, `None` if the state
+/// does not exist in that version.
+pub fn state_id(block: &Blocks, protocol: i32) -> Option<i32> { state_ids(protocol).and_then(|ids| ids(block)) }
+
+/// The id of a state in its canonical form, e.g. `minecraft:stone` or
+/// `minecraft:oak_log[axis=x]`.
+pub fn state_id_by_name(state: &str, protocol: i32) -> Option<i32> { state_id(&parse_state(state).ok()?, protocol) }
+
+/// Reads a state in its canonical form, as found in worlds and schematics.
+/// The generated `FromStr` of [`Blocks`] names the `type` property `typed`
+/// as `type` is a keyword.
+pub fn parse_state(state: &str) -> Result<Blocks, ParseBlockError> {
+    let Some((name, properties)) = state.split_once('[') else {
+        return Blocks::from_str(state);
+    };
+    let properties: Vec<Cow<str>> = properties
+        .split(',')
+        .map(|property| match property.strip_prefix("type=") {
+            Some(value) => Cow::Owned(format!("typed={}", value)),
+            None => Cow::Borrowed(property),
+        })
+        .collect();
+    Blocks::from_str(&format!("{}[{}", name, properties.join(",")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_state, state_id, state_id_by_name, state_ids};
+    use crate::server::config::FalconConfig;
+    use crate::world::blocks::Blocks;
+
+    #[test]
+    fn test_state_ids() {
+        assert_eq!(state_id(&Blocks::Stone, 393), Some(1));
+        assert_eq!(state_id(&Blocks::Stone, 756), Some(1));
+        assert_eq!(state_id_by_name("minecraft:stone", 477), Some(1));
+        assert_eq!(state_id_by_name("minecraft:not_a_block", 477), None);
+        // blocks of 1.17 do not exist before
+        assert!(state_id(&Blocks::AmethystBlock, 756).is_some());
+        assert_eq!(state_id(&Blocks::AmethystBlock, 578), None);
+        // no legacy ids
+        assert!(state_ids(340).is_none());
+        assert_eq!(state_id(&Blocks::Stone, 47), None);
+    }
+
+    #[test]
+    fn test_canonical_names() {
+        for name in ["minecraft:stone", "minecraft:oak_slab[type=top,waterlogged=false]", "minecraft:piston_head[facing=up,short=true,type=sticky]"] {
+            assert_eq!(parse_state(name).unwrap().to_string(), name);
+        }
+        assert!(parse_state("minecraft:oak_slab[type=sideways]").is_err());
+    }
+
+    #[test]
+    fn test_allowed_versions() {
+        for protocol in FalconConfig::ALLOWED_VERSIONS {
+            assert!(state_ids(protocol as i32).is_some(), "No block states for {}", protocol);
+        }
+        assert!(state_ids(759).is_none());
+    }
+}
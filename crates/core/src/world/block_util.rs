@@ -209,3 +209,8 @@ pub fn blocks_movement(block: &Blocks) -> bool {
             | Blocks::FireCoral(_)
     )
 }
+
+pub fn is_air(block: &Blocks) -> bool { matches!(block, Blocks::Air | Blocks::CaveAir | Blocks::VoidAir) }
+
+/// Water and lava, waterlogged blocks are not counted.
+pub fn is_fluid(block: &Blocks) -> bool { matches!(block, Blocks::Water(_) | Blocks::Lava(_) | Blocks::BubbleColumn(_)) }
@@ -1,17 +1,26 @@
+use crate::world::biome::Biome;
 use crate::world::blocks::Blocks;
+use crate::world::heightmap::{Heightmap, HeightmapKind};
 use crate::world::palette::Palette;
 
 pub const SECTIONS_NUM: u16 = 16;
 pub const SECTION_WIDTH: u16 = 16;
 pub const SECTION_LENGTH: u16 = 16;
 pub const SECTION_HEIGHT: u16 = 16;
+/// Biomes are stored for cells of 4 by 4 by 4 blocks.
+pub const BIOME_CELL: u16 = 4;
+pub const BIOME_CELLS: usize = ((SECTION_WIDTH / BIOME_CELL) * (SECTION_LENGTH / BIOME_CELL) * (SECTIONS_NUM * SECTION_HEIGHT / BIOME_CELL)) as usize;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Chunk {
     sections: [Option<ChunkSection>; SECTIONS_NUM as usize],
     bitmask: i32,
     pos: ChunkPos,
     dirty: bool,
+    motion_blocking: Heightmap,
+    world_surface: Heightmap,
+    /// `None` if the chunk has the default biome of the world.
+    biomes: Option<Box<[Biome; BIOME_CELLS]>>,
 }
 
 impl Chunk {
@@ -21,6 +30,9 @@ impl Chunk {
             bitmask: 0,
             pos,
             dirty: true,
+            motion_blocking: Heightmap::empty(HeightmapKind::MotionBlocking),
+            world_surface: Heightmap::empty(HeightmapKind::WorldSurface),
+            biomes: None,
         }
     }
 
@@ -38,6 +50,57 @@ impl Chunk {
             self.sections[section_y as usize] = Some(section);
             self.bitmask ^= 1 << section_y;
         }
+        let sections = &self.sections;
+        let block_at = |y: u16| match &sections[(y / SECTION_HEIGHT) as usize] {
+            Some(section) => *section.block_at(x, y % SECTION_HEIGHT, z),
+            None => Blocks::Air,
+        };
+        self.motion_blocking.update(x, y, z, &block_state, block_at);
+        self.world_surface.update(x, y, z, &block_state, block_at);
+    }
+
+    /// The block at a position within this chunk, air for empty sections.
+    pub fn block_at(&self, x: u16, y: u16, z: u16) -> &Blocks {
+        match self.sections.get((y / SECTION_HEIGHT) as usize) {
+            Some(Some(section)) => section.block_at(x, y % SECTION_HEIGHT, z),
+            _ => &Blocks::Air,
+        }
+    }
+
+    pub fn heightmap(&self, kind: HeightmapKind) -> &Heightmap {
+        match kind {
+            HeightmapKind::MotionBlocking => &self.motion_blocking,
+            HeightmapKind::WorldSurface => &self.world_surface,
+        }
+    }
+
+    /// The height of the highest block that stops movement or is a fluid,
+    /// `None` if the column has no such blocks.
+    pub fn highest_block_at(&self, x: u16, z: u16) -> Option<u16> { self.motion_blocking.height(x, z).checked_sub(1) }
+
+    /// The biome at a block position within this chunk, `None` if the chunk
+    /// has the default biome of the world.
+    pub fn biome_at(&self, x: u16, y: u16, z: u16) -> Option<Biome> { self.biomes.as_ref().map(|biomes| biomes[Self::biome_index(x, y, z)]) }
+
+    /// The biomes of the cells ordered by y, then z, then x. `None` if the
+    /// chunk has the default biome of the world.
+    pub fn biomes(&self) -> Option<&[Biome; BIOME_CELLS]> { self.biomes.as_deref() }
+
+    /// Sets the biome of the cell around a block position. The other cells
+    /// become `default` if the chunk had no biomes yet.
+    pub fn set_biome(&mut self, x: u16, y: u16, z: u16, biome: Biome, default: Biome) {
+        let biomes = self.biomes.get_or_insert_with(|| Box::new([default; BIOME_CELLS]));
+        biomes[Self::biome_index(x, y, z)] = biome;
+    }
+
+    pub fn fill_biome(&mut self, biome: Biome) { self.biomes = Some(Box::new([biome; BIOME_CELLS])); }
+
+    /// `None` goes back to the default biome of the world.
+    pub fn set_biomes(&mut self, biomes: Option<Box<[Biome; BIOME_CELLS]>>) { self.biomes = biomes; }
+
+    pub fn biome_index(x: u16, y: u16, z: u16) -> usize {
+        let (x, y, z) = (x / BIOME_CELL, y / BIOME_CELL, z / BIOME_CELL);
+        (x + z * (SECTION_WIDTH / BIOME_CELL) + y * (SECTION_WIDTH / BIOME_CELL) * (SECTION_LENGTH / BIOME_CELL)) as usize
     }
 
     pub fn get_bit_mask(&self) -> i32 { self.bitmask }
@@ -49,6 +112,13 @@ impl Chunk {
     pub fn is_dirty(&self) -> bool { self.dirty }
 
     pub fn get_chunk_sections(&self) -> &[Option<ChunkSection>; SECTIONS_NUM as usize] { &self.sections }
+
+    /// An estimate of the memory used by this chunk in bytes.
+    pub fn memory_size(&self) -> usize {
+        std::mem::size_of::<Chunk>()
+            + self.sections.iter().flatten().map(ChunkSection::memory_size).sum::<usize>()
+            + self.biomes.as_ref().map_or(0, |_| BIOME_CELLS * std::mem::size_of::<Biome>())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -105,6 +175,10 @@ impl ChunkSection {
 
     pub fn get_block_data(&self) -> &Vec<u16> { &self.blocks }
 
+    /// An estimate of the memory used by this section outside of the
+    /// chunk in bytes.
+    pub fn memory_size(&self) -> usize { self.blocks.capacity() * std::mem::size_of::<u16>() + self.palette.len() * std::mem::size_of::<Blocks>() }
+
     pub fn calculate_index(x: u16, y: u16, z: u16) -> usize { (x + z * SECTION_WIDTH + y * SECTION_WIDTH * SECTION_LENGTH) as usize }
 }
 
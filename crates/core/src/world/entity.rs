@@ -0,0 +1,84 @@
+//! The entities of a world, other than players these are only shown to
+//! clients: the server does not move them.
+
+use crate::chat::ChatComponent;
+use crate::player::data::Hand;
+
+/// The kinds of entities the server knows about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EntityType {
+    Player,
+    ArmorStand,
+    ItemFrame,
+    /// Since 1.19.4, like the other displays.
+    BlockDisplay,
+    ItemDisplay,
+    TextDisplay,
+}
+
+impl EntityType {
+    /// The identifier of the type, e.g. `minecraft:armor_stand`.
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            EntityType::Player => "minecraft:player",
+            EntityType::ArmorStand => "minecraft:armor_stand",
+            EntityType::ItemFrame => "minecraft:item_frame",
+            EntityType::BlockDisplay => "minecraft:block_display",
+            EntityType::ItemDisplay => "minecraft:item_display",
+            EntityType::TextDisplay => "minecraft:text_display",
+        }
+    }
+
+    /// Living entities have health and are spawned with their own packet
+    /// before 1.19.
+    pub fn is_living(&self) -> bool { matches!(self, EntityType::Player | EntityType::ArmorStand) }
+
+    /// The width and height of the bounding box in blocks.
+    pub fn dimensions(&self) -> (f32, f32) {
+        match self {
+            EntityType::Player => (0.6, 1.8),
+            EntityType::ArmorStand => (0.5, 1.975),
+            EntityType::ItemFrame => (0.5, 0.5),
+            EntityType::BlockDisplay | EntityType::ItemDisplay | EntityType::TextDisplay => (0.0, 0.0),
+        }
+    }
+}
+
+/// The entity data shown to clients, only the fields this server sets.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EntityMetadata {
+    pub invisible: bool,
+    /// An outline that shows through blocks, since 1.9.
+    pub glowing: bool,
+    pub custom_name: Option<ChatComponent>,
+    pub custom_name_visible: bool,
+    /// Since 1.10.
+    pub no_gravity: bool,
+    /// The text of a [text display](EntityType::TextDisplay).
+    pub text: Option<ChatComponent>,
+    /// Crouching, since 1.14 this also changes the pose.
+    pub sneaking: bool,
+    pub sprinting: bool,
+}
+
+/// An animation of an entity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntityAnimation {
+    SwingMainArm = 0,
+    /// The red flash of a hit, a packet of its own since 1.19.4.
+    TakeDamage = 1,
+    /// Since 1.9.
+    SwingOffhand = 3,
+}
+
+/// What a player did to an entity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EntityInteraction {
+    /// A right click on the entity.
+    Interact(Hand),
+    /// A left click on the entity.
+    Attack,
+    /// A right click on the entity at a point relative to its position,
+    /// sent together with [`Interact`](EntityInteraction::Interact).
+    InteractAt(Hand, f32, f32, f32),
+}
@@ -0,0 +1,80 @@
+use crate::world::block_util::{blocks_movement, is_air, is_fluid};
+use crate::world::blocks::Blocks;
+use crate::world::chunks::{SECTION_LENGTH, SECTION_WIDTH};
+
+const COLUMNS: usize = (SECTION_WIDTH * SECTION_LENGTH) as usize;
+
+/// Which blocks a heightmap counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeightmapKind {
+    /// Blocks that stop movement and fluids.
+    MotionBlocking,
+    /// Every block but air.
+    WorldSurface,
+}
+
+impl HeightmapKind {
+    pub fn counts(&self, block: &Blocks) -> bool {
+        match self {
+            HeightmapKind::MotionBlocking => blocks_movement(block) || is_fluid(block),
+            HeightmapKind::WorldSurface => !is_air(block),
+        }
+    }
+}
+
+/// The height above the highest counted block of every column of a chunk,
+/// 0 for columns without such blocks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heightmap {
+    kind: HeightmapKind,
+    heights: [u16; COLUMNS],
+}
+
+impl Heightmap {
+    pub fn empty(kind: HeightmapKind) -> Self { Heightmap { kind, heights: [0; COLUMNS] } }
+
+    pub fn kind(&self) -> HeightmapKind { self.kind }
+
+    pub fn height(&self, x: u16, z: u16) -> u16 { self.heights[Self::index(x, z)] }
+
+    /// The heights ordered by z, then x.
+    pub fn heights(&self) -> &[u16] { &self.heights }
+
+    /// Updates the column after `block` was placed at `y`, `block_at`
+    /// looks up the blocks below when the highest block is removed.
+    pub(crate) fn update<F: Fn(u16) -> Blocks>(&mut self, x: u16, y: u16, z: u16, block: &Blocks, block_at: F) {
+        let index = Self::index(x, z);
+        if self.kind.counts(block) {
+            self.heights[index] = self.heights[index].max(y + 1);
+        } else if self.heights[index] == y + 1 {
+            self.heights[index] = (0..y).rev().find(|&y| self.kind.counts(&block_at(y))).map_or(0, |y| y + 1);
+        }
+    }
+
+    fn index(x: u16, z: u16) -> usize { (x + z * SECTION_WIDTH) as usize }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::world::blocks::Blocks;
+    use crate::world::chunks::{Chunk, ChunkPos};
+    use crate::world::heightmap::HeightmapKind;
+
+    #[test]
+    fn test_heightmaps() {
+        let mut chunk = Chunk::empty(ChunkPos::new(0, 0));
+        chunk.set_block_at(2, 10, 3, Blocks::Stone);
+        chunk.set_block_at(2, 40, 3, Blocks::Stone);
+        chunk.set_block_at(2, 41, 3, Blocks::Grass);
+        assert_eq!(chunk.highest_block_at(2, 3), Some(40));
+        assert_eq!(chunk.heightmap(HeightmapKind::WorldSurface).height(2, 3), 42);
+
+        // removing the highest block finds the one below, also in another section
+        chunk.set_block_at(2, 40, 3, Blocks::Air);
+        assert_eq!(chunk.highest_block_at(2, 3), Some(10));
+        assert_eq!(chunk.heightmap(HeightmapKind::WorldSurface).height(2, 3), 42);
+        chunk.set_block_at(2, 10, 3, Blocks::Air);
+        assert_eq!(chunk.highest_block_at(2, 3), None);
+        assert_eq!(chunk.highest_block_at(3, 2), None);
+    }
+}
@@ -1,10 +1,16 @@
 use crate::player::data::Position;
 
+pub mod biome;
+mod block_names;
+pub mod block_states;
 pub mod block_util;
 pub mod blocks;
 pub mod chunks;
 pub mod dimension;
+pub mod entity;
+pub mod heightmap;
 pub mod palette;
+pub mod seed;
 
 #[derive(Clone, Copy, Debug)]
 pub struct BlockPosition {
@@ -21,6 +21,10 @@ impl<T: PartialEq + Clone + Debug + 'static> Palette<T> {
 
     pub fn at(&self, index: usize) -> Option<&T> { self.items.get(index) }
 
+    pub fn len(&self) -> usize { self.items.len() }
+
+    pub fn is_empty(&self) -> bool { self.items.is_empty() }
+
     pub fn remove(&mut self, index: usize) -> usize {
         self.items.swap_remove(index);
         self.items.len()
@@ -0,0 +1,23 @@
+//! Since 1.15 the client receives the first 8 bytes of the SHA-256 hash of
+//! the world seed instead of the seed itself, it uses them for biome noise.
+
+use sha2::{Digest, Sha256};
+
+/// The hashed seed sent in the Join Game packet, vanilla hashes the
+/// little-endian bytes of the seed and reads the result the same way.
+pub fn hashed_seed(seed: i64) -> i64 {
+    let hash = Sha256::digest(seed.to_le_bytes());
+    i64::from_le_bytes(hash[..8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hashed_seed;
+
+    #[test]
+    fn test_hashed_seed() {
+        // the first 8 bytes of sha256(0i64.to_le_bytes()), af5570f5a1810b7a
+        assert_eq!(hashed_seed(0), i64::from_le_bytes([0xaf, 0x55, 0x70, 0xf5, 0xa1, 0x81, 0x0b, 0x7a]));
+        assert_ne!(hashed_seed(0), hashed_seed(1));
+    }
+}
@@ -0,0 +1,67 @@
+use falcon_packet_core::WriteError;
+
+use super::writer::SocketWrite;
+use crate::FalconConnection;
+
+/// The most packets a vanilla client accepts in one bundle.
+pub const MAX_BUNDLE_PACKETS: usize = 4096;
+
+type BundledPacket = Box<dyn FnOnce(&mut FalconConnection) -> Result<(), WriteError> + Send + Sync>;
+
+/// Packets that 1.19.4+ clients apply in the same tick, e.g. the spawn and
+/// metadata of an entity so it never shows up without its metadata. Older
+/// clients receive the packets one after another.
+///
+/// ```ignore
+/// let bundle = PacketBundle::new()
+///     .with(spawn, falcon_send::write_spawn_entity)
+///     .with(metadata, falcon_send::write_entity_metadata);
+/// player.connection().send_bundle(bundle);
+/// ```
+#[derive(Default)]
+pub struct PacketBundle {
+    packets: Vec<BundledPacket>,
+}
+
+impl PacketBundle {
+    pub fn new() -> Self { PacketBundle::default() }
+
+    pub fn with<T, F>(mut self, packet: T, write_fn: F) -> Self
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce(T, &mut SocketWrite, i32) -> Result<bool, WriteError> + Send + Sync + 'static,
+    {
+        self.push(packet, write_fn);
+        self
+    }
+
+    pub fn push<T, F>(&mut self, packet: T, write_fn: F)
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce(T, &mut SocketWrite, i32) -> Result<bool, WriteError> + Send + Sync + 'static,
+    {
+        self.packets.push(Box::new(move |connection: &mut FalconConnection| connection.send_packet(packet, write_fn)));
+    }
+
+    pub fn len(&self) -> usize { self.packets.len() }
+
+    pub fn is_empty(&self) -> bool { self.packets.is_empty() }
+
+    /// Writes the packets between two delimiters. Bundles that are too
+    /// large for the client are split.
+    pub(crate) fn write(self, connection: &mut FalconConnection) -> Result<(), WriteError> {
+        let mut packets = self.packets.into_iter().peekable();
+        while packets.peek().is_some() {
+            connection.send_packet((), falcon_send::write_bundle_delimiter)?;
+            for packet in packets.by_ref().take(MAX_BUNDLE_PACKETS) {
+                packet(connection)?;
+            }
+            connection.send_packet((), falcon_send::write_bundle_delimiter)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for PacketBundle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.debug_struct("PacketBundle").field("packets", &self.packets.len()).finish() }
+}
@@ -0,0 +1,108 @@
+use std::fmt::{Display, Formatter};
+
+use falcon_core::chat::{ChatColor, ChatComponent};
+use falcon_core::server::lang::{default_locale, MessageCatalog};
+
+/// Why the server closes a connection.
+pub enum DisconnectReason {
+    /// The server has reached its maximum amount of players.
+    ServerFull,
+    /// The player is banned, optionally with the reason of the ban.
+    Banned(Option<String>),
+    /// The player is not on the whitelist.
+    NotWhitelisted,
+    /// An operator kicked the player, optionally with a reason.
+    Kicked(Option<String>),
+    /// The player did nothing for longer than the idle timeout.
+    Idle,
+    /// The client did not answer a keep alive in time.
+    TimedOut,
+    /// The client sent a packet that could not be read or was not
+    /// expected.
+    InvalidPacket(String),
+    /// The protocol version of the client is not accepted.
+    UnsupportedVersion(String),
+    /// The address of the client tried to log in too often.
+    Throttled,
+    /// The login could not be finished, e.g. a login query was not
+    /// answered.
+    LoginFailed(String),
+    /// The name of the player is not a valid username.
+    InvalidName,
+    /// No profile has the name of the player while the server requires
+    /// one.
+    UnknownProfile,
+    /// The profile of the player could not be looked up while the server
+    /// requires one.
+    AuthUnavailable,
+    /// The server is shutting down.
+    Shutdown,
+    /// Handling the connection failed on the server side.
+    Internal(String),
+    /// A message that is shown as is.
+    Custom(ChatComponent),
+}
+
+impl DisconnectReason {
+    /// The message shown to a client with the given protocol version and
+    /// locale. Messages may use legacy `&` formatting codes, e.g. in the
+    /// reason of a kick.
+    pub fn to_component(self, protocol: i32, locale: &str) -> ChatComponent {
+        let component = match self {
+            DisconnectReason::Custom(component) => component,
+            reason => ChatComponent::from_legacy(&reason.message(locale), '&').color_if_absent(ChatColor::Red),
+        };
+        component.for_protocol(protocol)
+    }
+
+    /// The text of this reason in `locale`.
+    pub fn message(&self, locale: &str) -> String {
+        let catalog = MessageCatalog::global();
+        match self {
+            DisconnectReason::ServerFull => catalog.message(locale, "disconnect.server_full", &[]),
+            DisconnectReason::Banned(None) => catalog.message(locale, "disconnect.banned", &[]),
+            DisconnectReason::Banned(Some(reason)) => catalog.message(locale, "disconnect.banned_reason", &[reason]),
+            DisconnectReason::NotWhitelisted => catalog.message(locale, "disconnect.not_whitelisted", &[]),
+            DisconnectReason::Kicked(None) => catalog.message(locale, "disconnect.kicked", &[]),
+            DisconnectReason::Kicked(Some(reason)) => catalog.message(locale, "disconnect.kicked_reason", &[reason]),
+            DisconnectReason::Idle => catalog.message(locale, "disconnect.idle", &[]),
+            DisconnectReason::TimedOut => catalog.message(locale, "disconnect.timed_out", &[]),
+            DisconnectReason::InvalidPacket(error) => catalog.message(locale, "disconnect.invalid_packet", &[error]),
+            DisconnectReason::UnsupportedVersion(reason) => reason.clone(),
+            DisconnectReason::Throttled => catalog.message(locale, "disconnect.throttled", &[]),
+            DisconnectReason::LoginFailed(error) => catalog.message(locale, "disconnect.login_failed", &[error]),
+            DisconnectReason::InvalidName => catalog.message(locale, "disconnect.invalid_name", &[]),
+            DisconnectReason::UnknownProfile => catalog.message(locale, "disconnect.unknown_profile", &[]),
+            DisconnectReason::AuthUnavailable => catalog.message(locale, "disconnect.auth_unavailable", &[]),
+            DisconnectReason::Shutdown => catalog.message(locale, "disconnect.shutdown", &[]),
+            DisconnectReason::Internal(error) => catalog.message(locale, "disconnect.internal", &[error]),
+            DisconnectReason::Custom(_) => String::from("Custom message"),
+        }
+    }
+}
+
+impl Display for DisconnectReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{}", self.message(default_locale())) }
+}
+
+impl From<ChatComponent> for DisconnectReason {
+    fn from(component: ChatComponent) -> Self { DisconnectReason::Custom(component) }
+}
+
+#[cfg(test)]
+mod test {
+    use falcon_core::server::config::FalconConfig;
+
+    use super::DisconnectReason;
+
+    #[test]
+    fn test_messages() {
+        FalconConfig::init_default();
+        assert_eq!(DisconnectReason::ServerFull.to_string(), "The server is full!");
+        assert_eq!(DisconnectReason::Banned(None).to_string(), "You are banned from this server!");
+        assert_eq!(DisconnectReason::Banned(Some(String::from("griefing"))).to_string(), "You are banned from this server: griefing");
+        assert_eq!(DisconnectReason::InvalidPacket(String::from("Impossible next state!")).to_string(), "Invalid packet: Impossible next state!");
+        assert_eq!(DisconnectReason::InvalidName.to_string(), "Invalid characters in username!");
+        assert_eq!(DisconnectReason::UnknownProfile.to_string(), "No account was found for this name!");
+    }
+}
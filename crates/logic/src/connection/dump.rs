@@ -0,0 +1,84 @@
+//! Logging of every packet a connection sends or receives, configured in
+//! the `debug` section of [`FalconConfig`].
+//!
+//! Packets are logged on the [`TARGET`] tracing target at debug level, this
+//! makes it possible to follow the traffic of a connection while adding a
+//! new protocol version without touching the other logs.
+
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+
+use falcon_core::server::config::FalconConfig;
+use falcon_packet_core::{PacketRead, VarI32};
+use itertools::Itertools;
+use tracing::debug;
+
+/// The tracing target packets are logged on.
+pub const TARGET: &str = "falcon::packets";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Inbound => write!(f, "C->S"),
+            Direction::Outbound => write!(f, "S->C"),
+        }
+    }
+}
+
+/// Whether any packet of a connection from `address` may be dumped.
+pub(crate) fn dumps_connection(address: &SocketAddr) -> bool {
+    let settings = &FalconConfig::global().debug;
+    settings.packet_dump && (settings.dump_addresses.is_empty() || settings.dump_addresses.contains(&address.ip()))
+}
+
+/// Logs a packet, `data` is the uncompressed packet starting with its
+/// id as sent over the wire.
+pub(crate) fn dump_packet(address: &SocketAddr, state: &dyn Display, direction: Direction, name: Option<&str>, data: &[u8]) {
+    let settings = &FalconConfig::global().debug;
+    let mut body = data;
+    let Ok(packet_id) = VarI32::read(&mut body).map(|id| id.val()) else {
+        return debug!(target: TARGET, %address, %state, %direction, len = data.len(), "Packet without id");
+    };
+    if !settings.dumps(address.ip(), packet_id) {
+        return;
+    }
+    let id = format!("{:#04X}", packet_id);
+    let name = name.unwrap_or("unknown");
+    if settings.hex_dump {
+        let hex = format!("{:02x}", body.iter().format(" "));
+        debug!(target: TARGET, %address, %state, %direction, %id, name, len = body.len(), "{}", hex);
+    } else {
+        debug!(target: TARGET, %address, %state, %direction, %id, name, len = body.len());
+    }
+}
+
+/// Turns the type name of a `falcon_send` write function into the name of
+/// the packet it writes, e.g. `falcon_send::write_join_game<..>` into
+/// `join_game`.
+pub(crate) fn write_fn_name<F>() -> &'static str {
+    let name = std::any::type_name::<F>();
+    let name = name.split('<').next().unwrap_or(name).trim_end_matches("::");
+    let name = name.rsplit("::").next().unwrap_or(name);
+    name.strip_prefix("write_").unwrap_or(name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::write_fn_name;
+
+    fn write_join_game<T>(_: T) {}
+
+    #[test]
+    fn test_write_fn_name() {
+        fn name_of<F>(_: &F) -> &'static str { write_fn_name::<F>() }
+
+        assert_eq!(name_of(&write_join_game::<u8>), "join_game");
+        assert_eq!(name_of(&falcon_send::write_keep_alive::<super::super::writer::SocketWrite>), "keep_alive");
+    }
+}
@@ -0,0 +1,61 @@
+//! Encoding of expensive packets, like chunk data, off the server thread.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use anyhow::Result;
+use bytes::{Bytes, BytesMut};
+use falcon_packet_core::WriteError;
+use tokio::task::JoinHandle;
+
+use super::writer::PacketPriority;
+use super::{dump, FalconConnection, SyncFutConnectionTask};
+
+/// A packet that is being encoded on a blocking thread of the runtime, see
+/// [`EncodedPacket::spawn`].
+#[derive(Debug)]
+pub struct EncodedPacket {
+    name: &'static str,
+    priority: PacketPriority,
+    bytes: JoinHandle<Result<Option<Bytes>, WriteError>>,
+}
+
+impl EncodedPacket {
+    /// Starts encoding `packet` for the base `protocol` with `write_fn`, the
+    /// number of packets encoded at once is limited by the blocking threads
+    /// of the runtime.
+    pub fn spawn<T, F>(packet: T, protocol: i32, write_fn: F) -> Self
+    where
+        T: Send + 'static,
+        F: FnOnce(T, &mut BytesMut, i32) -> Result<bool, WriteError> + Send + 'static,
+    {
+        let bytes = tokio::task::spawn_blocking(move || {
+            let mut buffer = BytesMut::new();
+            Ok(write_fn(packet, &mut buffer, protocol)?.then(|| buffer.freeze()))
+        });
+        EncodedPacket {
+            name: dump::write_fn_name::<F>(),
+            priority: PacketPriority::Normal,
+            bytes,
+        }
+    }
+
+    /// Sends the packet in the lane of `priority` once it is encoded.
+    pub fn with_priority(mut self, priority: PacketPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// Waits for the packet and sends it, the connection handles no other
+/// tasks in the meantime so packets keep their order.
+impl SyncFutConnectionTask for EncodedPacket {
+    fn run(self: Box<Self>, connection: &mut FalconConnection) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async move {
+            if let Some(bytes) = self.bytes.await?? {
+                connection.with_priority(self.priority, |connection| connection.send_encoded(self.name, bytes))?;
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,257 @@
+//! Players that join from Bedrock Edition through Geyser with the Floodgate
+//! plugin on the proxy.
+//!
+//! Floodgate appends the encrypted identity of the Bedrock player to the
+//! address in the handshake. Bedrock players have no Java account, they
+//! join with a prefixed username and a uuid made from their Xbox id instead
+//! of a profile from the session server.
+//!
+//! The data is encrypted with the key Floodgate shares with its servers,
+//! [`AesCipher`] reads it like Floodgate does. Other ciphers can be used by
+//! implementing [`FloodgateCipher`].
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::str::FromStr;
+
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes128Gcm, KeyInit, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use falcon_core::server::config::FalconConfig;
+use once_cell::sync::OnceCell;
+use thiserror::Error;
+use uuid::Uuid;
+
+static INSTANCE: OnceCell<Floodgate> = OnceCell::new();
+
+/// Marks the Floodgate data in the address of a handshake.
+pub const FLOODGATE_IDENTIFIER: &str = "^Floodgate^";
+/// The longest username Java clients accept.
+const MAX_USERNAME_LENGTH: usize = 16;
+/// Follows [`FLOODGATE_IDENTIFIER`], the version of the data format plus
+/// `0x3E`. Only version 0 exists.
+const VERSION_0: char = '>';
+/// Separates the iv from the encrypted data.
+const IV_SEPARATOR: char = '!';
+const IV_LENGTH: usize = 12;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum FloodgateError {
+    #[error("Could not decrypt the Floodgate data: {0}")]
+    Decrypt(String),
+    #[error("The Floodgate data has no {0}")]
+    MissingField(&'static str),
+    #[error("Invalid {0} in the Floodgate data")]
+    InvalidField(&'static str),
+    #[error("Unsupported version of the Floodgate data")]
+    UnsupportedVersion,
+    #[error("A Floodgate key has 16 bytes, not {0}")]
+    InvalidKey(usize),
+}
+
+/// Decrypts the data Floodgate sends, e.g. with AES-GCM and the key of the
+/// Floodgate installation.
+pub trait FloodgateCipher: Send + Sync {
+    /// Decrypts `payload`, the data after [`FLOODGATE_IDENTIFIER`].
+    fn decrypt(&self, payload: &str) -> Result<String, FloodgateError>;
+}
+
+/// The AES-GCM cipher of Floodgate, using the `key.pem` Floodgate
+/// generated. Despite the name, that file holds the 16 bytes of the key.
+pub struct AesCipher {
+    cipher: Aes128Gcm,
+}
+
+impl AesCipher {
+    pub fn new(key: &[u8]) -> Result<Self, FloodgateError> {
+        let cipher = Aes128Gcm::new_from_slice(key).map_err(|_| FloodgateError::InvalidKey(key.len()))?;
+        Ok(AesCipher { cipher })
+    }
+
+    /// Reads the key from the file at `path`, e.g. the `key.pem` of the
+    /// Floodgate installation on the proxy.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let key = std::fs::read(path)?;
+        AesCipher::new(&key).map_err(|error| std::io::Error::new(ErrorKind::InvalidData, error))
+    }
+}
+
+impl FloodgateCipher for AesCipher {
+    fn decrypt(&self, payload: &str) -> Result<String, FloodgateError> {
+        let payload = payload.strip_prefix(VERSION_0).ok_or(FloodgateError::UnsupportedVersion)?;
+        let (iv, data) = payload.split_once(IV_SEPARATOR).ok_or(FloodgateError::MissingField("iv"))?;
+        let iv = STANDARD.decode(iv).ok().filter(|iv| iv.len() == IV_LENGTH).ok_or(FloodgateError::InvalidField("iv"))?;
+        let data = STANDARD.decode(data).map_err(|error| FloodgateError::Decrypt(error.to_string()))?;
+        let data = self
+            .cipher
+            .decrypt(Nonce::from_slice(&iv), data.as_slice())
+            .map_err(|_| FloodgateError::Decrypt(String::from("the key does not match or the data was changed")))?;
+        String::from_utf8(data).map_err(|error| FloodgateError::Decrypt(error.to_string()))
+    }
+}
+
+/// The identity of a Bedrock player as sent by Floodgate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BedrockData {
+    pub version: String,
+    /// The gamertag, without the prefix.
+    pub username: String,
+    pub xuid: i64,
+    pub device_os: i32,
+    pub language_code: String,
+    pub ui_profile: i32,
+    pub input_mode: i32,
+    /// The address the player connected to Geyser from.
+    pub ip: String,
+}
+
+impl BedrockData {
+    /// Reads the decrypted data, fields separated by `\0`. Fields Floodgate
+    /// sends after the ip are not needed.
+    pub fn parse(data: &str) -> Result<Self, FloodgateError> {
+        let mut fields = data.split('\0');
+        let mut next = |name| fields.next().ok_or(FloodgateError::MissingField(name));
+        Ok(BedrockData {
+            version: next("version")?.to_owned(),
+            username: next("username")?.to_owned(),
+            xuid: number("xuid", next("xuid")?)?,
+            device_os: number("device os", next("device os")?)?,
+            language_code: next("language code")?.to_owned(),
+            ui_profile: number("ui profile", next("ui profile")?)?,
+            input_mode: number("input mode", next("input mode")?)?,
+            ip: next("ip")?.to_owned(),
+        })
+    }
+
+    /// The uuid Floodgate gives a Bedrock player, the Xbox id in the lower
+    /// half.
+    pub fn uuid(&self) -> Uuid { Uuid::from_u64_pair(0, self.xuid as u64) }
+}
+
+fn number<T: FromStr>(name: &'static str, field: &str) -> Result<T, FloodgateError> { field.parse().map_err(|_| FloodgateError::InvalidField(name)) }
+
+/// Reads the Floodgate data of handshakes, see
+/// [`Floodgate::init_global`].
+pub struct Floodgate {
+    cipher: Box<dyn FloodgateCipher>,
+    username_prefix: String,
+    replace_spaces: bool,
+}
+
+impl Floodgate {
+    /// Handles Floodgate data with `cipher` and the usernames of the config.
+    pub fn from_config(cipher: Box<dyn FloodgateCipher>) -> Self {
+        let settings = &FalconConfig::global().floodgate;
+        Floodgate {
+            cipher,
+            username_prefix: settings.username_prefix.clone(),
+            replace_spaces: settings.replace_spaces,
+        }
+    }
+
+    /// The global Floodgate handler, `None` unless the server lets Bedrock
+    /// players in.
+    pub fn global() -> Option<&'static Floodgate> { INSTANCE.get() }
+
+    /// Lets Bedrock players in, returns this handler back when the global
+    /// handler was set already.
+    pub fn init_global(self) -> Result<(), Floodgate> { INSTANCE.set(self) }
+
+    /// The Bedrock player behind a handshake, `None` if the address has no
+    /// Floodgate data.
+    pub fn read_handshake(&self, address: &str) -> Option<Result<BedrockData, FloodgateError>> {
+        let payload = address.split('\0').find_map(|part| part.strip_prefix(FLOODGATE_IDENTIFIER))?;
+        Some(self.cipher.decrypt(payload).and_then(|data| BedrockData::parse(&data)))
+    }
+
+    /// The username of a Bedrock player, prefixed to not clash with the
+    /// Java players.
+    pub fn username(&self, data: &BedrockData) -> String {
+        let username = match self.replace_spaces {
+            true => data.username.replace(' ', "_"),
+            false => data.username.clone(),
+        };
+        format!("{}{}", self.username_prefix, username).chars().take(MAX_USERNAME_LENGTH).collect()
+    }
+}
+
+impl std::fmt::Debug for Floodgate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Floodgate")
+            .field("username_prefix", &self.username_prefix)
+            .field("replace_spaces", &self.replace_spaces)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Fields separated by `|` instead of encrypted.
+    struct Plain;
+
+    impl FloodgateCipher for Plain {
+        fn decrypt(&self, payload: &str) -> Result<String, FloodgateError> { Ok(payload.replace('|', "\0")) }
+    }
+
+    fn floodgate() -> Floodgate {
+        Floodgate {
+            cipher: Box::new(Plain),
+            username_prefix: String::from("."),
+            replace_spaces: true,
+        }
+    }
+
+    #[test]
+    fn test_read_handshake() {
+        let floodgate = floodgate();
+        assert_eq!(floodgate.read_handshake("localhost"), None);
+        assert_eq!(floodgate.read_handshake("localhost\0FML2\0"), None);
+
+        let address = "localhost\0^Floodgate^1.0|Some Gamer|2535400000000000|7|en_US|0|2|127.0.0.1|0";
+        let data = floodgate.read_handshake(address).unwrap().unwrap();
+        assert_eq!(data.xuid, 2535400000000000);
+        assert_eq!(data.ip, "127.0.0.1");
+        assert_eq!(data.uuid(), Uuid::from_u64_pair(0, 2535400000000000));
+        assert_eq!(floodgate.username(&data), ".Some_Gamer");
+
+        let address = "localhost\0^Floodgate^1.0|Some Gamer|xuid";
+        assert_eq!(floodgate.read_handshake(address), Some(Err(FloodgateError::InvalidField("xuid"))));
+    }
+
+    #[test]
+    fn test_aes_cipher() {
+        let key = [7u8; 16];
+        let iv = [3u8; IV_LENGTH];
+        let data = ["1.0", "Some Gamer", "2535400000000000", "7", "en_US", "0", "2", "127.0.0.1"].join("\0");
+        let encrypted = Aes128Gcm::new_from_slice(&key).unwrap().encrypt(Nonce::from_slice(&iv), data.as_bytes()).unwrap();
+        let payload = format!("{}{}{}{}", VERSION_0, STANDARD.encode(iv), IV_SEPARATOR, STANDARD.encode(encrypted));
+
+        let floodgate = Floodgate {
+            cipher: Box::new(AesCipher::new(&key).unwrap()),
+            ..floodgate()
+        };
+        let bedrock = floodgate.read_handshake(&format!("localhost\0{}{}", FLOODGATE_IDENTIFIER, payload)).unwrap().unwrap();
+        assert_eq!(bedrock.username, "Some Gamer");
+        assert_eq!(bedrock.xuid, 2535400000000000);
+
+        let path = std::env::temp_dir().join("falcon_floodgate_test_key.pem");
+        std::fs::write(&path, key).unwrap();
+        assert_eq!(AesCipher::load(&path).unwrap().decrypt(&payload).unwrap(), data);
+        std::fs::remove_file(&path).unwrap();
+
+        let other = AesCipher::new(&[8u8; 16]).unwrap();
+        assert!(matches!(other.decrypt(&payload), Err(FloodgateError::Decrypt(_))));
+        assert_eq!(other.decrypt(&payload[1..]), Err(FloodgateError::UnsupportedVersion));
+        assert_eq!(other.decrypt(">AAAA!AAAA"), Err(FloodgateError::InvalidField("iv")));
+        assert!(matches!(AesCipher::new(&[0u8; 5]), Err(FloodgateError::InvalidKey(5))));
+    }
+
+    #[test]
+    fn test_long_username() {
+        let data = BedrockData::parse(&["1.0", "AVeryLongGamertag", "1", "0", "en_US", "0", "0", "::1"].join("\0")).unwrap();
+        assert_eq!(floodgate().username(&data), ".AVeryLongGamert");
+    }
+}
@@ -0,0 +1,179 @@
+//! In-memory connections to test packet handlers without opening sockets.
+//!
+//! A [`MockConnection`] runs a real [`FalconConnection`] over a duplex byte
+//! channel. Tests script client packets with [`MockConnection::send_packet`],
+//! drive the server side with [`MockConnection::run_server_task`] and assert
+//! on the packets the server sent back with [`MockConnection::expect_packet`].
+//! Captured sessions can be checked with [`MockConnection::replay`].
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use bytes::{Bytes, BytesMut};
+use falcon_core::server::config::FalconConfig;
+use falcon_core::ShutdownHandle;
+use falcon_packet_core::special::{read_frame_async, MAX_FRAME_LENGTH};
+use falcon_packet_core::{PacketRead, PacketSize, PacketWrite, VarI32};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+use tokio::sync::mpsc::unbounded_channel;
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+use super::dump::Direction;
+use super::record::Recording;
+use super::ConnectionReceiver;
+use crate::server::ServerWrapper;
+use crate::{FalconConnection, FalconServer, FalconWorld};
+
+const PIPE_CAPACITY: usize = 64 * 1024;
+const TIMEOUT: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The client end of an in-memory connection to a [`FalconServer`].
+///
+/// All `expect` functions panic with a descriptive message when their
+/// expectation isn't met, which makes them suitable for use in tests.
+pub struct MockConnection {
+    client: BufReader<DuplexStream>,
+    server: FalconServer,
+    shutdown: ShutdownHandle,
+    connection: JoinHandle<()>,
+}
+
+impl MockConnection {
+    /// Starts a connection to a server with an empty world.
+    pub async fn start<R>(receiver: R) -> Self
+    where
+        R: ConnectionReceiver + Send + 'static,
+    {
+        Self::with_world(receiver, FalconWorld::new(0, 0, 0, 0, 0)).await
+    }
+
+    /// Starts a connection to a server with the given world, the global
+    /// config is initialized with default values if necessary.
+    pub async fn with_world<R>(receiver: R, world: FalconWorld) -> Self
+    where
+        R: ConnectionReceiver + Send + 'static,
+    {
+        FalconConfig::init_default();
+        let (shutdown, _) = ShutdownHandle::new();
+        let (_, console_rx) = unbounded_channel();
+        let (server_tx, server_rx) = unbounded_channel();
+        let server = FalconServer::new(shutdown.clone(), console_rx, server_rx, world);
+
+        let (client, socket) = tokio::io::duplex(PIPE_CAPACITY);
+        let addr = SocketAddr::from(([127, 0, 0, 1], 25565));
+        let connection = FalconConnection::new(shutdown.clone(), addr, ServerWrapper::new(server_tx, server.status().clone())).await;
+        let connection = tokio::spawn(connection.start(socket, receiver));
+        Self {
+            client: BufReader::new(client),
+            server,
+            shutdown,
+            connection,
+        }
+    }
+
+    /// The server this connection is linked to.
+    pub fn server(&mut self) -> &mut FalconServer { &mut self.server }
+
+    /// Sends a packet as if the client wrote it.
+    pub async fn send_packet<P: PacketWrite>(&mut self, packet_id: i32, packet: &P) {
+        let packet_id = VarI32::from(packet_id);
+        let length = packet_id.size() + packet.size();
+        let mut buffer = BytesMut::with_capacity(length + 3);
+        VarI32::from(length).write(&mut buffer).unwrap();
+        packet_id.write(&mut buffer).unwrap();
+        packet.write(&mut buffer).expect("Invalid client packet");
+        self.send_raw(&buffer).await;
+    }
+
+    /// Writes raw bytes to the connection, no framing is added.
+    pub async fn send_raw(&mut self, data: &[u8]) { self.client.write_all(data).await.expect("Connection closed while sending"); }
+
+    /// Runs the next task the connection handed to the server.
+    pub async fn run_server_task(&mut self) {
+        match timeout(TIMEOUT, self.server.run_next_task()).await {
+            Ok(true) => {},
+            Ok(false) => panic!("The connection was dropped before sending a server task"),
+            Err(_) => panic!("Timed out waiting for a server task"),
+        }
+    }
+
+    /// Returns the id and content of the next packet the server sent,
+    /// `None` if the connection was closed.
+    pub async fn next_packet(&mut self) -> Option<(i32, Bytes)> {
+        let frame = timeout(TIMEOUT, read_frame_async(&mut self.client, MAX_FRAME_LENGTH))
+            .await
+            .expect("Timed out waiting for a packet")
+            .expect("Invalid packet frame");
+        frame.map(|mut frame| {
+            let packet_id = VarI32::read(&mut frame).expect("Invalid packet id").val();
+            (packet_id, frame)
+        })
+    }
+
+    /// Asserts that the next packet has the given id and reads it as `P`.
+    pub async fn expect_packet<P: PacketRead>(&mut self, packet_id: i32) -> P {
+        match self.next_packet().await {
+            Some((id, mut frame)) => {
+                assert_eq!(id, packet_id, "Expected packet {:#04X} but received {:#04X}", packet_id, id);
+                P::read(&mut frame).unwrap_or_else(|e| panic!("Packet {:#04X} could not be read: {}", packet_id, e))
+            },
+            None => panic!("Connection closed while expecting packet {:#04X}", packet_id),
+        }
+    }
+
+    /// Feeds the inbound packets of `recording` to the server and asserts
+    /// that the server answers with packets with the same ids as recorded.
+    ///
+    /// Server tasks are run while waiting for outbound packets, timing is
+    /// not replicated.
+    pub async fn replay(&mut self, recording: &Recording) {
+        for (i, packet) in recording.packets().iter().enumerate() {
+            match packet.direction {
+                Direction::Inbound => {
+                    let mut buffer = BytesMut::with_capacity(packet.data.len() + 3);
+                    VarI32::from(packet.data.len()).write(&mut buffer).unwrap();
+                    buffer.extend_from_slice(&packet.data);
+                    self.send_raw(&buffer).await;
+                },
+                Direction::Outbound => {
+                    let expected = VarI32::read(&mut packet.data.clone()).expect("Invalid recorded packet id").val();
+                    self.wait_for_data().await;
+                    match self.next_packet().await {
+                        Some((id, _)) => assert_eq!(id, expected, "Packet {} of the recording: expected {:#04X} but received {:#04X}", i, expected, id),
+                        None => panic!("Connection closed while expecting packet {:#04X} (packet {} of the recording)", expected, i),
+                    }
+                },
+            }
+        }
+    }
+
+    /// Runs server tasks until the server wrote data to the client.
+    async fn wait_for_data(&mut self) {
+        let waiting = async {
+            loop {
+                while self.server.try_run_task().await {}
+                if timeout(POLL_INTERVAL, self.client.fill_buf()).await.is_ok() {
+                    return;
+                }
+            }
+        };
+        timeout(TIMEOUT, waiting).await.expect("Timed out waiting for a packet");
+    }
+
+    /// Asserts that the server closes the connection without sending any
+    /// further packets.
+    pub async fn expect_closed(&mut self) {
+        if let Some((id, _)) = self.next_packet().await {
+            panic!("Expected the connection to close but received packet {:#04X}", id);
+        }
+    }
+}
+
+impl Drop for MockConnection {
+    fn drop(&mut self) {
+        self.shutdown.send_shutdown();
+        self.connection.abort();
+    }
+}
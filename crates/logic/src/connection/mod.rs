@@ -6,21 +6,40 @@ use std::time::Duration;
 
 use anyhow::Result;
 use bytes::Bytes;
-use falcon_core::network::{ConnectionState, PacketHandlerState, UNKNOWN_PROTOCOL};
+use falcon_core::network::{ConnectionState, PacketHandlerState, VirtualHost, UNKNOWN_PROTOCOL};
+use falcon_core::server::config::FalconConfig;
+use falcon_core::server::lang::default_locale;
 use falcon_core::ShutdownHandle;
-use falcon_packet_core::WriteError;
-use mc_chat::ChatComponent;
+use falcon_packet_core::special::{write_packet, PacketPrepare, RawPacket};
+use falcon_packet_core::{PacketRead, PacketSize, PacketWrite, VarI32, WriteError};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
-use tokio::time::{interval, Interval, MissedTickBehavior};
-use tracing::{instrument, trace};
+use tokio::time::{interval, Instant, Interval, MissedTickBehavior};
+use tracing::{debug, instrument, trace, warn};
+pub use bundle::PacketBundle;
+pub use disconnect::DisconnectReason;
 pub use wrapper::ConnectionWrapper;
 
-use self::writer::SocketWrite;
+use self::dump::Direction;
+use self::floodgate::BedrockData;
+use self::query::LoginQueries;
+use self::reader::SocketRead;
+use self::record::PacketRecorder;
+use self::writer::{PacketPriority, SocketWrite};
 use crate::server::ServerWrapper;
 
+mod bundle;
 // mod codec;
+mod disconnect;
+pub mod dump;
+pub mod encode;
+pub mod floodgate;
 pub mod handler;
+#[cfg(any(feature = "testing", test))]
+pub mod mock;
+pub mod proxy;
+pub mod query;
 pub mod reader;
+pub mod record;
 mod tick;
 mod wrapper;
 pub mod writer;
@@ -40,6 +59,14 @@ pub enum ConnectionTask {
 
 pub trait ConnectionReceiver {
     fn receive(&mut self, packet_id: i32, bytes: &mut Bytes, connection: &mut FalconConnection) -> Result<bool>;
+
+    /// Called with the packets that were not handled by
+    /// [`receive`](ConnectionReceiver::receive) or whose id is not known to
+    /// the protocol, the id is the one the client sent. Returns whether the
+    /// packet was taken care of, e.g. forwarded with
+    /// [`FalconConnection::forward_packet`], unknown packets are then not
+    /// refused anymore.
+    fn receive_raw(&mut self, _packet: RawPacket, _connection: &mut FalconConnection) -> Result<bool> { Ok(false) }
 }
 
 #[derive(Debug)]
@@ -50,30 +77,117 @@ pub struct FalconConnection {
     wrapper: ConnectionWrapper,
     timeout: Interval,
     addr: SocketAddr,
+    read_buffer: SocketRead,
     write_buffer: SocketWrite,
     state: PacketHandlerState,
+    dump_packets: bool,
+    handled_packet: Option<&'static str>,
+    recorder: Option<PacketRecorder>,
+    locale: Option<String>,
+    keep_alive_sent: Option<Instant>,
+    latency: Option<Duration>,
+    online_mode: Option<bool>,
+    virtual_host: Option<VirtualHost>,
+    login_queries: LoginQueries,
+    bedrock: Option<BedrockData>,
 }
 
 impl FalconConnection {
     pub async fn new(shutdown: ShutdownHandle, addr: SocketAddr, server: ServerWrapper) -> Self {
-        let mut timeout = interval(Duration::from_secs(30));
+        let settings = &FalconConfig::global().network;
+        let mut timeout = interval(settings.keep_alive_timeout());
         timeout.set_missed_tick_behavior(MissedTickBehavior::Delay);
         timeout.tick().await;
         let (sender, receiver) = unbounded_channel();
+        let wrapper = ConnectionWrapper::new(sender);
         Self {
             shutdown,
-            server,
-            wrapper: ConnectionWrapper::new(sender),
+            server: server.with_origin(wrapper.clone()),
+            wrapper,
             task_rx: receiver,
             timeout,
             addr,
-            write_buffer: SocketWrite::new(-1),
+            read_buffer: SocketRead::new(-1)
+                .with_buffer_len(settings.read_buffer_size)
+                .with_max_packet_size(settings.packet_size_limit(ConnectionState::Handshake)),
+            write_buffer: SocketWrite::new(-1)
+                .with_buffer_len(settings.write_buffer_size)
+                .with_compression_level(settings.compression_level),
             state: PacketHandlerState::new(UNKNOWN_PROTOCOL),
+            dump_packets: dump::dumps_connection(&addr),
+            handled_packet: None,
+            recorder: record::recorder_for(&addr),
+            locale: None,
+            keep_alive_sent: None,
+            latency: None,
+            online_mode: None,
+            virtual_host: None,
+            login_queries: LoginQueries::default(),
+            bedrock: None,
+        }
+    }
+
+    /// Overrides the online mode of the server for this connection, as
+    /// configured for the listener it was accepted by.
+    pub fn with_online_mode(mut self, online_mode: Option<bool>) -> Self {
+        self.online_mode = online_mode;
+        self
+    }
+
+    /// Tells the client to compress packets of at least `threshold` bytes
+    /// from now on, both ways. Only valid during login.
+    pub fn enable_compression(&mut self, threshold: i32) -> Result<(), WriteError> {
+        if threshold < 0 {
+            return Ok(());
         }
+        self.send_packet(threshold, falcon_send::write_set_compression)?;
+        self.write_buffer.set_compression_threshold(threshold);
+        self.read_buffer.set_compression_threshold(threshold);
+        Ok(())
     }
 
     pub fn reset_keep_alive(&mut self) { self.timeout.reset(); }
 
+    /// Sends a keep alive with the given id, the round-trip time is measured
+    /// until [`FalconConnection::acknowledge_keep_alive`].
+    pub fn send_keep_alive(&mut self, id: u64) -> Result<(), WriteError> {
+        self.state.last_keep_alive = id;
+        self.keep_alive_sent = Some(Instant::now());
+        // a client busy downloading chunks must still get its keep alives
+        self.with_priority(PacketPriority::Urgent, |connection| connection.send_packet(id as i64, falcon_send::write_keep_alive))
+    }
+
+    /// Handles the answer to the last keep alive, the measured round-trip
+    /// time is passed on to the player.
+    pub fn acknowledge_keep_alive(&mut self) {
+        self.reset_keep_alive();
+        if let Some(sent) = self.keep_alive_sent.take() {
+            let latency = sent.elapsed();
+            self.latency = Some(latency);
+            if let Some(uuid) = self.state.uuid {
+                self.server.player_update_latency(uuid, latency);
+            }
+        }
+    }
+
+    /// The round-trip time of the last answered keep alive.
+    pub fn latency(&self) -> Option<Duration> { self.latency }
+
+    /// The online mode override of this connection, `None` follows the
+    /// server.
+    pub fn online_mode(&self) -> Option<bool> { self.online_mode }
+
+    /// The address the client connected to, known after the handshake.
+    pub fn virtual_host(&self) -> Option<&VirtualHost> { self.virtual_host.as_ref() }
+
+    pub fn set_virtual_host(&mut self, host: VirtualHost) { self.virtual_host = Some(host); }
+
+    /// The Bedrock player on this connection, as sent by Floodgate in the
+    /// handshake.
+    pub fn bedrock(&self) -> Option<&BedrockData> { self.bedrock.as_ref() }
+
+    pub fn set_bedrock(&mut self, data: BedrockData) { self.bedrock = Some(data); }
+
     pub fn server(&self) -> &ServerWrapper { &self.server }
 
     pub fn wrapper(&self) -> ConnectionWrapper { self.wrapper.clone() }
@@ -86,24 +200,126 @@ impl FalconConnection {
 
     pub fn state_mut(&mut self) -> &mut falcon_core::network::PacketHandlerState { &mut self.state }
 
-    #[instrument(level = "trace", skip_all)]
+    /// The locale of the client, the default locale until the client sent
+    /// its settings.
+    pub fn locale(&self) -> &str {
+        match &self.locale {
+            Some(locale) => locale,
+            None => default_locale(),
+        }
+    }
+
+    pub fn set_locale(&mut self, locale: String) { self.locale = Some(locale); }
+
+    /// Records the name of the packet that is being handled, this name is
+    /// used when the packet dump is enabled.
+    pub fn set_handled_packet(&mut self, name: &'static str) {
+        if self.dump_packets {
+            self.handled_packet = Some(name);
+        }
+    }
+
+    pub(crate) fn take_handled_packet(&mut self) -> Option<&'static str> { self.handled_packet.take() }
+
+    /// Whether packets are dumped or recorded, both need a copy of every
+    /// packet.
+    pub(crate) fn captures_packets(&self) -> bool { self.dump_packets || self.recorder.is_some() }
+
+    pub(crate) fn dumps_packets(&self) -> bool { self.dump_packets }
+
+    pub(crate) fn record_packet(&mut self, direction: Direction, data: &[u8]) {
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(error) = recorder.record(direction, data) {
+                warn!(%error, "Could not record packet, stopping the recording");
+                self.recorder = None;
+            }
+        }
+    }
+
+    /// Sends the packets of `send` in the lane of `priority`, see
+    /// [`PacketPriority`].
+    pub fn with_priority<R>(&mut self, priority: PacketPriority, send: impl FnOnce(&mut FalconConnection) -> R) -> R {
+        let previous = self.write_buffer.priority();
+        self.write_buffer.set_priority(priority);
+        let result = send(self);
+        self.write_buffer.set_priority(previous);
+        result
+    }
+
     pub fn send<F>(&mut self, write_fn: F) -> Result<(), WriteError>
+    where
+        F: FnOnce(&mut SocketWrite, i32) -> Result<(), WriteError>,
+    {
+        self.send_named(None, write_fn)
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    fn send_named<F>(&mut self, name: Option<&'static str>, write_fn: F) -> Result<(), WriteError>
     where
         F: FnOnce(&mut SocketWrite, i32) -> Result<(), WriteError>,
     {
         if self.state.connection_state == ConnectionState::Disconnected {
             return Ok(());
         }
-        write_fn(&mut self.write_buffer, self.state.protocol_id)?;
+        if self.captures_packets() {
+            self.write_buffer.start_capture();
+        }
+        if let Err(error) = write_fn(&mut self.write_buffer, self.state.client_protocol()) {
+            // a partial packet would corrupt every packet behind it
+            self.write_buffer.discard();
+            debug!(packet = name, fields = %error.field_path().join(" > "), error = %error.root(), "Dropping packet that could not be written");
+            return Err(error);
+        }
         self.write_buffer.finish();
+        if let Some(data) = self.write_buffer.take_capture().filter(|data| !data.is_empty()) {
+            if self.dump_packets {
+                dump::dump_packet(&self.addr, &self.state, Direction::Outbound, name, &data);
+            }
+            self.record_packet(Direction::Outbound, &data);
+        }
         Ok(())
     }
 
+    /// Sends a packet that was written already for the base protocol, e.g.
+    /// by an [`EncodedPacket`](encode::EncodedPacket). The packet id at the
+    /// start is mapped like the id of [`write_packet`](Self::write_packet).
+    pub fn send_encoded(&mut self, name: &'static str, mut packet: Bytes) -> Result<(), WriteError> {
+        let packet_id = self.wire_id(VarI32::read(&mut packet).map_err(|_| WriteError::EndOfBuffer)?.val());
+        self.send_named(Some(name), move |buffer, _| write_packet(buffer, packet_id, &packet[..]))
+    }
+
+    /// Sends `packet` with its id in the base protocol, the id is mapped
+    /// through the [`ProtocolRegistry`](falcon_core::network::registry::ProtocolRegistry)
+    /// for this connection. The frame is reserved from the exact size of
+    /// the packet and the packet is written into it directly.
+    pub fn write_packet<P: PacketWrite>(&mut self, packet_id: i32, packet: &P) -> Result<(), WriteError> {
+        let packet_id = self.wire_id(packet_id);
+        self.send_named(Some(dump::write_fn_name::<P>()), |buffer, _| write_packet(buffer, packet_id, packet))
+    }
+
+    fn wire_id(&self, packet_id: i32) -> i32 { self.state.packet_ids().map_or(packet_id, |ids| ids.clientbound.to_wire(packet_id)) }
+
+    /// Sends a packet verbatim, its id is not mapped for this connection.
+    /// Meant for packets that were received as a [`RawPacket`].
+    pub fn forward_packet(&mut self, packet: &RawPacket) -> Result<(), WriteError> {
+        self.send_named(Some("RawPacket"), |buffer, _| {
+            buffer.prepare(packet.size());
+            packet.write(buffer)
+        })
+    }
+
+    /// Sends bytes that are not a packet, like the legacy ping response.
+    pub fn send_raw(&mut self, data: &[u8]) {
+        if self.state.connection_state != ConnectionState::Disconnected {
+            self.write_buffer.write_raw(data);
+        }
+    }
+
     pub fn send_packet<T, F>(&mut self, packet: T, write_fn: F) -> Result<(), WriteError>
     where
         F: FnOnce(T, &mut SocketWrite, i32) -> Result<bool, WriteError>,
     {
-        self.send(move |buffer, protocol| {
+        self.send_named(Some(dump::write_fn_name::<F>()), move |buffer, protocol| {
             if !write_fn(packet, buffer, protocol)? {
                 // trace!("Unresolved packet");
             }
@@ -111,12 +327,13 @@ impl FalconConnection {
         })
     }
 
-    #[instrument(level = "trace", skip_all)]
-    pub fn disconnect(&mut self, reason: ChatComponent) {
-        match self.state.connection_state {
-            ConnectionState::Play => self.send_packet(reason, falcon_send::write_play_disconnect).ok(),
-            _ => self.send_packet(reason, falcon_send::write_login_disconnect).ok(),
-        };
+    #[instrument(level = "trace", skip_all, fields(%reason))]
+    pub fn disconnect(&mut self, reason: DisconnectReason) {
+        let reason = reason.to_component(self.state.protocol_id, self.locale());
+        self.with_priority(PacketPriority::Urgent, |connection| match connection.state.connection_state {
+            ConnectionState::Play => connection.send_packet(reason, falcon_send::write_play_disconnect).ok(),
+            _ => connection.send_packet(reason, falcon_send::write_login_disconnect).ok(),
+        });
         self.state.connection_state = ConnectionState::Disconnected;
         trace!("Player connection marked as disconnected");
     }
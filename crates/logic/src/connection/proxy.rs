@@ -0,0 +1,153 @@
+//! Support for the [PROXY protocol](https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt)
+//! header that TCP load balancers send ahead of the actual connection data.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+const V1_PREFIX: &[u8] = b"PROXY ";
+/// The longest possible v1 header, including `\r\n`.
+const V1_MAX_LEN: usize = 107;
+const V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+
+#[derive(Debug, Error)]
+pub enum ProxyHeaderError {
+    #[error("Connection did not start with a PROXY protocol header")]
+    MissingHeader,
+    #[error("Malformed PROXY protocol header: {0}")]
+    Malformed(&'static str),
+    #[error("Unsupported PROXY protocol version {0}")]
+    UnsupportedVersion(u8),
+    #[error("I/O error while reading the PROXY protocol header")]
+    Io(#[from] io::Error),
+}
+
+/// Reads a v1 or v2 PROXY protocol header and returns the address of the
+/// original client.
+///
+/// Exactly the header is consumed from `reader`. Headers that do not carry
+/// a TCP address (`LOCAL` or `UNKNOWN`) resolve to `peer`.
+pub async fn read_proxy_header<R>(reader: &mut R, peer: SocketAddr) -> Result<SocketAddr, ProxyHeaderError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut start = [0u8; 6];
+    reader.read_exact(&mut start).await?;
+    if start == V1_PREFIX {
+        read_v1(reader, peer).await
+    } else if start == V2_SIGNATURE[..6] {
+        let mut rest = [0u8; 6];
+        reader.read_exact(&mut rest).await?;
+        if rest != V2_SIGNATURE[6..] {
+            return Err(ProxyHeaderError::MissingHeader);
+        }
+        read_v2(reader, peer).await
+    } else {
+        Err(ProxyHeaderError::MissingHeader)
+    }
+}
+
+async fn read_v1<R: AsyncRead + Unpin>(reader: &mut R, peer: SocketAddr) -> Result<SocketAddr, ProxyHeaderError> {
+    // the header is read byte by byte to not consume any data behind it
+    let mut line = Vec::with_capacity(V1_MAX_LEN - V1_PREFIX.len());
+    while !line.ends_with(b"\r\n") {
+        if line.len() == V1_MAX_LEN - V1_PREFIX.len() {
+            return Err(ProxyHeaderError::Malformed("header is too long"));
+        }
+        line.push(reader.read_u8().await?);
+    }
+    let line = std::str::from_utf8(&line[..line.len() - 2]).map_err(|_| ProxyHeaderError::Malformed("header is not ascii"))?;
+
+    let mut parts = line.split(' ');
+    match parts.next() {
+        Some("TCP4") | Some("TCP6") => {},
+        Some("UNKNOWN") => return Ok(peer),
+        _ => return Err(ProxyHeaderError::Malformed("unknown protocol")),
+    }
+    let ip = parts.next().and_then(|ip| ip.parse::<IpAddr>().ok());
+    let port = parts.nth(1).and_then(|port| port.parse::<u16>().ok());
+    match (ip, port) {
+        (Some(ip), Some(port)) => Ok(SocketAddr::new(ip, port)),
+        _ => Err(ProxyHeaderError::Malformed("invalid source address")),
+    }
+}
+
+async fn read_v2<R: AsyncRead + Unpin>(reader: &mut R, peer: SocketAddr) -> Result<SocketAddr, ProxyHeaderError> {
+    let version_command = reader.read_u8().await?;
+    let family = reader.read_u8().await?;
+    let len = reader.read_u16().await? as usize;
+    let mut data = vec![0u8; len];
+    reader.read_exact(&mut data).await?;
+
+    if version_command >> 4 != 2 {
+        return Err(ProxyHeaderError::UnsupportedVersion(version_command >> 4));
+    }
+    match version_command & 0x0F {
+        0x0 => return Ok(peer),
+        0x1 => {},
+        _ => return Err(ProxyHeaderError::Malformed("unknown command")),
+    }
+    match family >> 4 {
+        0x1 if data.len() >= 12 => {
+            let ip = Ipv4Addr::new(data[0], data[1], data[2], data[3]);
+            Ok(SocketAddr::new(ip.into(), u16::from_be_bytes([data[8], data[9]])))
+        },
+        0x2 if data.len() >= 36 => {
+            let mut ip = [0u8; 16];
+            ip.copy_from_slice(&data[..16]);
+            Ok(SocketAddr::new(Ipv6Addr::from(ip).into(), u16::from_be_bytes([data[32], data[33]])))
+        },
+        0x1 | 0x2 => Err(ProxyHeaderError::Malformed("address block is too short")),
+        // unix sockets and unspecified families carry no usable address
+        _ => Ok(peer),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn peer() -> SocketAddr { "10.0.0.1:4000".parse().unwrap() }
+
+    async fn parse(mut data: &[u8]) -> (Result<SocketAddr, ProxyHeaderError>, &[u8]) {
+        let res = read_proxy_header(&mut data, peer()).await;
+        (res, data)
+    }
+
+    #[tokio::test]
+    async fn test_v1() {
+        let (addr, rest) = parse(b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 25565\r\n\x10\x00").await;
+        assert_eq!(addr.unwrap(), "192.168.0.1:56324".parse().unwrap());
+        assert_eq!(rest, b"\x10\x00");
+
+        let (addr, _) = parse(b"PROXY TCP6 ::1 ::1 56324 25565\r\n").await;
+        assert_eq!(addr.unwrap(), "[::1]:56324".parse().unwrap());
+
+        let (addr, _) = parse(b"PROXY UNKNOWN\r\n").await;
+        assert_eq!(addr.unwrap(), peer());
+
+        let (addr, _) = parse(b"PROXY TCP4 nonsense\r\n").await;
+        assert!(matches!(addr, Err(ProxyHeaderError::Malformed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_v2() {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend_from_slice(&[0x21, 0x11, 0x00, 0x0C, 192, 168, 0, 1, 192, 168, 0, 11, 0xDC, 0x04, 0x63, 0xDD, 0x10]);
+        let (addr, rest) = parse(&header).await;
+        assert_eq!(addr.unwrap(), "192.168.0.1:56324".parse().unwrap());
+        assert_eq!(rest, &[0x10]);
+
+        let mut local = V2_SIGNATURE.to_vec();
+        local.extend_from_slice(&[0x20, 0x00, 0x00, 0x00]);
+        assert_eq!(parse(&local).await.0.unwrap(), peer());
+    }
+
+    #[tokio::test]
+    async fn test_missing_header() {
+        let (addr, _) = parse(&[0x10, 0x00, 0xF7, 0x05, 0x09, 0x6C]).await;
+        assert!(matches!(addr, Err(ProxyHeaderError::MissingHeader)));
+    }
+}
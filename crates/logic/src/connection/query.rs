@@ -0,0 +1,203 @@
+//! Login plugin requests, custom queries the server sends to a client that
+//! is logging in, e.g. for the player data a proxy forwards.
+//!
+//! Handlers are registered by channel in the [`LoginQueryRegistry`], every
+//! client is asked all registered queries after its login start and the
+//! login only continues once each query was answered. Clients before 1.13
+//! cannot be queried, their queries count as not understood.
+
+use std::convert::Infallible;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use ahash::AHashMap;
+use bytes::Bytes;
+use falcon_core::network::ConnectionState;
+use falcon_packet_core::WriteError;
+use falcon_send::specs::login::LoginPluginRequestSpec;
+use once_cell::sync::OnceCell;
+use thiserror::Error;
+use tracing::{debug, trace};
+
+use super::{DisconnectReason, FalconConnection};
+
+static INSTANCE: OnceCell<LoginQueryRegistry> = OnceCell::new();
+
+/// The first protocol with login plugin requests, 1.13.
+const LOGIN_QUERY_PROTOCOL: i32 = 393;
+/// How long a client may take to answer a query by default.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug)]
+pub enum LoginQueryError {
+    #[error("No answer to the query on {0} in time")]
+    TimedOut(String),
+    #[error("The client does not understand the query on {0}")]
+    NotUnderstood(String),
+    #[error("Invalid answer to the query on {0}: {1}")]
+    InvalidAnswer(String, String),
+    #[error("Could not send the query on {0}")]
+    Write(String, #[source] WriteError),
+}
+
+/// Handles the answer to a query, `None` if the client did not understand
+/// the channel. An error refuses the login.
+pub type LoginQueryCallback = Box<dyn FnOnce(&mut FalconConnection, Option<Bytes>) -> Result<(), LoginQueryError> + Send + Sync>;
+
+/// Continues the login once every query was answered.
+type LoginContinuation = Box<dyn FnOnce(&mut FalconConnection) + Send + Sync>;
+
+/// A query every client is asked while logging in.
+pub trait LoginQueryHandler: Send + Sync {
+    fn channel(&self) -> &str;
+
+    /// The data sent with the query to `connection`.
+    fn request(&self, connection: &FalconConnection) -> Bytes;
+
+    /// Handles the answer of the client, `None` if the client did not
+    /// understand the channel. An error refuses the login.
+    fn response(&self, data: Option<Bytes>, connection: &mut FalconConnection) -> Result<(), LoginQueryError>;
+
+    /// How long the client may take to answer.
+    fn timeout(&self) -> Duration { QUERY_TIMEOUT }
+}
+
+/// The login query handlers, queried in the order they were registered.
+#[derive(Default)]
+pub struct LoginQueryRegistry {
+    handlers: Vec<Arc<dyn LoginQueryHandler>>,
+}
+
+impl LoginQueryRegistry {
+    /// The global registry, empty unless [`LoginQueryRegistry::init_global`]
+    /// was called.
+    pub fn global() -> &'static LoginQueryRegistry { INSTANCE.get_or_init(LoginQueryRegistry::default) }
+
+    /// Installs this registry as the global one, returns it back when the
+    /// global registry was already in use.
+    pub fn init_global(self) -> Result<(), LoginQueryRegistry> { INSTANCE.set(self) }
+
+    pub fn register(&mut self, handler: impl LoginQueryHandler + 'static) { self.handlers.push(Arc::new(handler)); }
+
+    pub fn channels(&self) -> impl Iterator<Item = &str> { self.handlers.iter().map(|handler| handler.channel()) }
+
+    pub fn len(&self) -> usize { self.handlers.len() }
+
+    pub fn is_empty(&self) -> bool { self.handlers.is_empty() }
+}
+
+impl Debug for LoginQueryRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { f.debug_list().entries(self.channels()).finish() }
+}
+
+struct PendingQuery {
+    channel: String,
+    callback: LoginQueryCallback,
+}
+
+/// The queries of a connection that were not answered yet.
+#[derive(Default)]
+pub(crate) struct LoginQueries {
+    next_id: i32,
+    pending: AHashMap<i32, PendingQuery>,
+    then: Option<LoginContinuation>,
+}
+
+impl Debug for LoginQueries {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoginQueries")
+            .field("next_id", &self.next_id)
+            .field("pending", &self.pending.values().map(|query| &query.channel).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl FalconConnection {
+    /// Asks the client a query on `channel`, `callback` gets the answer or
+    /// `None` if the client did not understand the channel. The login is
+    /// refused when the client takes longer than `timeout`.
+    pub fn send_login_query<F>(&mut self, channel: impl Into<String>, data: Bytes, timeout: Duration, callback: F)
+    where
+        F: FnOnce(&mut FalconConnection, Option<Bytes>) -> Result<(), LoginQueryError> + Send + Sync + 'static,
+    {
+        let channel = channel.into();
+        let message_id = self.login_queries.next_id;
+        self.login_queries.next_id = message_id.wrapping_add(1);
+        self.login_queries.pending.insert(
+            message_id,
+            PendingQuery {
+                channel: channel.clone(),
+                callback: Box::new(callback),
+            },
+        );
+        if self.state.protocol_id < LOGIN_QUERY_PROTOCOL {
+            return self.answer_login_query(message_id, None);
+        }
+
+        trace!(message_id, %channel, "Sending login query");
+        let request = LoginPluginRequestSpec::new(message_id, channel.clone(), data);
+        if let Err(error) = self.send_packet(request, falcon_send::write_login_plugin_request) {
+            self.login_queries.pending.remove(&message_id);
+            return self.fail_login(LoginQueryError::Write(channel, error));
+        }
+        let wrapper = self.wrapper();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            wrapper.execute(move |connection| {
+                if let Some(query) = connection.login_queries.pending.remove(&message_id) {
+                    connection.fail_login(LoginQueryError::TimedOut(query.channel));
+                }
+                Ok::<(), Infallible>(())
+            });
+        });
+    }
+
+    /// Handles the answer of the client to the query `message_id`, `None`
+    /// if the client did not understand it.
+    pub fn answer_login_query(&mut self, message_id: i32, data: Option<Bytes>) {
+        let Some(query) = self.login_queries.pending.remove(&message_id) else {
+            trace!(message_id, "Answer to an unknown login query, skipping!");
+            return;
+        };
+        if let Err(error) = (query.callback)(self, data) {
+            return self.fail_login(error);
+        }
+        if self.login_queries.pending.is_empty() {
+            if let Some(then) = self.login_queries.then.take() {
+                then(self);
+            }
+        }
+    }
+
+    /// Asks the queries of the global [`LoginQueryRegistry`] and runs `then`
+    /// once they were all answered.
+    pub fn start_login_queries<F>(&mut self, then: F)
+    where
+        F: FnOnce(&mut FalconConnection) + Send + Sync + 'static,
+    {
+        for handler in &LoginQueryRegistry::global().handlers {
+            if self.state.connection_state == ConnectionState::Disconnected {
+                return;
+            }
+            let (handler, data) = (handler.clone(), handler.request(self));
+            let (channel, timeout) = (handler.channel().to_owned(), handler.timeout());
+            self.send_login_query(channel, data, timeout, move |connection, data| handler.response(data, connection));
+        }
+        if self.state.connection_state == ConnectionState::Disconnected {
+            return;
+        }
+        // the queries of old clients were answered right away
+        match self.login_queries.pending.is_empty() {
+            true => then(self),
+            false => self.login_queries.then = Some(Box::new(then)),
+        }
+    }
+
+    fn fail_login(&mut self, error: LoginQueryError) {
+        debug!(%error, "Login query failed");
+        self.login_queries.pending.clear();
+        self.login_queries.then = None;
+        self.disconnect(DisconnectReason::LoginFailed(error.to_string()));
+    }
+}
@@ -1,53 +1,119 @@
+use std::io::ErrorKind;
 use std::mem::size_of;
 use std::ptr;
 
 use bytes::buf::UninitSlice;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
+use falcon_packet_core::ReadError;
 use flate2::{Decompress, FlushDecompress, Status};
 
 const COMPRESSION_BUF_LEN: usize = 4096;
+/// The longest frame header, the frame length and the data length of a
+/// compressed frame are at most 3 bytes each.
+const MAX_HEADER_LEN: usize = 6;
+/// The largest length that fits in a 3 byte frame length.
+const MAX_PACKET_SIZE: usize = 2097151;
+/// The first byte of a pre-1.7 server list ping.
+const LEGACY_PING: u8 = 0xFE;
+
+/// The kind of server list ping sent by pre-1.7 clients.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegacyPing {
+    /// Beta 1.8 up to 1.3, a single `0xFE`.
+    Beta,
+    /// 1.4 up to 1.6, `0xFE 0x01` followed by optional plugin data.
+    V1_4,
+}
 
 #[derive(Debug)]
 pub struct SocketRead {
-    decompress_buf: [u8; COMPRESSION_BUF_LEN],
+    decompress_buf: Box<[u8]>,
     decompress: Decompress,
     decompress_pos: usize,
     compression_threshold: i32,
     output_buf: BytesMut,
     next_is_compressed: bool,
+    /// The length of the packet that is being decompressed.
+    next_data_len: usize,
     ready_pos: usize,
     next_expected: usize,
+    max_packet_size: usize,
+    /// Why the data can not be read as packets, the buffered data is
+    /// dropped when this is set.
+    error: Option<ReadError>,
 }
 
 impl SocketRead {
     pub fn new(compression_threshold: i32) -> Self {
         Self {
-            decompress_buf: [0u8; COMPRESSION_BUF_LEN],
+            decompress_buf: vec![0u8; COMPRESSION_BUF_LEN].into_boxed_slice(),
             decompress: Decompress::new(true),
             decompress_pos: 0,
             compression_threshold,
             output_buf: BytesMut::with_capacity(COMPRESSION_BUF_LEN),
             next_is_compressed: false,
+            next_data_len: 0,
             ready_pos: 0,
             next_expected: 0,
+            max_packet_size: MAX_PACKET_SIZE,
+            error: None,
         }
     }
 
-    pub fn next_packet(&mut self) -> Option<Bytes> {
+    /// Reads incoming data in chunks of `len` bytes instead of the default
+    /// 4096, this must be done before any data is read. The chunks are at
+    /// least as long as a frame header.
+    pub fn with_buffer_len(mut self, len: usize) -> Self {
+        self.decompress_buf = vec![0u8; len.max(MAX_HEADER_LEN)].into_boxed_slice();
+        self
+    }
+
+    /// Refuses packets longer than `max_packet_size` bytes, compressed or
+    /// not.
+    pub fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = max_packet_size;
+        self
+    }
+
+    /// Changes the limit of [`with_max_packet_size`](SocketRead::with_max_packet_size)
+    /// for the data that was not read yet, e.g. when the protocol phase
+    /// changes.
+    pub fn set_max_packet_size(&mut self, max_packet_size: usize) { self.max_packet_size = max_packet_size; }
+
+    /// Changes the compression threshold for the data that was not read
+    /// yet, a negative threshold disables compression.
+    pub fn set_compression_threshold(&mut self, threshold: i32) { self.compression_threshold = threshold; }
+
+    /// Detects a legacy ping in data that has not been read as packets
+    /// yet, only meaningful for the first bytes of a connection.
+    pub fn legacy_ping(&self) -> Option<LegacyPing> {
+        match &self.decompress_buf[..self.decompress_pos] {
+            [LEGACY_PING] => Some(LegacyPing::Beta),
+            [LEGACY_PING, 0x01, ..] => Some(LegacyPing::V1_4),
+            _ => None,
+        }
+    }
+
+    /// The next complete packet, fails if the client sent a packet longer
+    /// than allowed or a frame that is malformed.
+    pub fn next_packet(&mut self) -> Result<Option<Bytes>, ReadError> {
         if self.decompress_pos > 0 {
             match self.flush_buffer() {
                 Some(n) => self.decompress_pos = n,
                 None => self.decompress_pos = 0,
             }
         }
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
 
         if self.ready_pos != 0 {
             let len = self.output_buf.get_i32();
             let data = self.output_buf.split_to(len as usize);
             self.ready_pos -= size_of::<i32>() + len as usize;
-            return Some(data.freeze());
+            return Ok(Some(data.freeze()));
         }
-        None
+        Ok(None)
     }
 
     fn flush_buffer(&mut self) -> Option<usize> {
@@ -56,16 +122,32 @@ impl SocketRead {
         while start != self.decompress_pos && success {
             if self.next_expected == 0 {
                 if let Some((length, cnt)) = read_varint_size(&self.decompress_buf[start..self.decompress_pos]) {
+                    if length as usize > self.max_packet_size {
+                        return self.fail(ReadError::FrameTooLong(self.max_packet_size, length as usize));
+                    }
+                    if length == 0 {
+                        return self.fail(ReadError::ZeroValue("frame length"));
+                    }
                     start += cnt;
                     if self.compression_threshold >= 0 {
                         if let Some((uncomp_len, cnt)) = read_varint_size(&self.decompress_buf[start..self.decompress_pos]) {
+                            // the data length is part of the frame
+                            if (length as usize) < cnt {
+                                return self.fail(ReadError::NotEnoughBytes {
+                                    expected: cnt,
+                                    remaining: length as usize,
+                                });
+                            }
                             self.next_expected = length as usize - cnt;
                             if uncomp_len == 0 {
                                 self.next_is_compressed = false;
-                                self.output_buf.reserve(length as usize + size_of::<i32>());
-                                self.output_buf.put_i32(length);
+                                self.output_buf.reserve(self.next_expected + size_of::<i32>());
+                                self.output_buf.put_i32(self.next_expected as i32);
+                            } else if uncomp_len as usize > self.max_packet_size {
+                                return self.fail(ReadError::FrameTooLong(self.max_packet_size, uncomp_len as usize));
                             } else {
                                 self.next_is_compressed = true;
+                                self.next_data_len = uncomp_len as usize;
                                 self.output_buf.reserve(uncomp_len as usize + size_of::<i32>());
                                 self.output_buf.put_i32(uncomp_len);
                             }
@@ -90,14 +172,19 @@ impl SocketRead {
             }
             if success && self.next_expected == 0 {
                 if self.next_is_compressed {
+                    if self.decompress.total_out() != self.next_data_len as u64 {
+                        let error = std::io::Error::new(ErrorKind::InvalidData, "Decompressed packet does not have the sent length");
+                        return self.fail(ReadError::IoError(error));
+                    }
                     self.decompress.reset(true);
                 }
                 self.ready_pos = self.output_buf.len();
 
-                if self.output_buf.len() < COMPRESSION_BUF_LEN {
+                let buf_len = self.decompress_buf.len();
+                if self.output_buf.len() < buf_len {
                     let capacity = self.output_buf.capacity();
-                    if capacity > COMPRESSION_BUF_LEN && capacity > 3 * self.output_buf.len() {
-                        let new_buffer = BytesMut::with_capacity(COMPRESSION_BUF_LEN);
+                    if capacity > buf_len && capacity > 3 * self.output_buf.len() {
+                        let new_buffer = BytesMut::with_capacity(buf_len);
                         let old_buffer = std::mem::replace(&mut self.output_buf, new_buffer);
                         self.output_buf.put(old_buffer);
                     }
@@ -119,17 +206,22 @@ impl SocketRead {
         Some(remaining)
     }
 
-    fn read_next(&mut self, mut start: usize) -> Option<usize> {
+    /// Drops the packet that is being read, the error is returned by the
+    /// next call to [`next_packet`](SocketRead::next_packet).
+    fn fail<T>(&mut self, error: ReadError) -> Option<T> {
+        self.error = Some(error);
+        self.next_expected = 0;
+        self.next_is_compressed = false;
+        self.decompress.reset(true);
+        self.output_buf.truncate(self.ready_pos);
+        None
+    }
+
+    fn read_next(&mut self, start: usize) -> Option<usize> {
         let end = self.decompress_pos.min(start + self.next_expected);
         let len = end - start;
         if self.next_is_compressed {
-            loop {
-                let n = self.read_compressed(start, end)?;
-                start += n;
-                if n == 0 {
-                    break;
-                }
-            }
+            self.read_compressed(start, end)?;
         } else {
             let buf = &self.decompress_buf[start..end];
             self.output_buf.put_slice(buf);
@@ -138,28 +230,36 @@ impl SocketRead {
         Some(len)
     }
 
-    fn read_compressed(&mut self, start: usize, end: usize) -> Option<usize> {
+    /// Decompresses the part of a packet between `start` and `end`, the
+    /// rest of the packet may arrive later.
+    fn read_compressed(&mut self, mut start: usize, end: usize) -> Option<()> {
         loop {
             let input = &self.decompress_buf[start..end];
-            let eof = input.is_empty();
             let before_out = self.decompress.total_out();
             let before_in = self.decompress.total_in();
-            let flush = if eof {
-                FlushDecompress::Finish
-            } else {
-                FlushDecompress::None
-            };
-            let ret = self.decompress.decompress(input, Self::output_mut(&mut self.output_buf), flush);
+            let ret = self.decompress.decompress(input, Self::output_mut(&mut self.output_buf), FlushDecompress::None);
             let read = (self.decompress.total_out() - before_out) as usize;
             let consumed = (self.decompress.total_in() - before_in) as usize;
             unsafe {
                 self.output_buf.advance_mut(read);
             }
+            start += consumed;
+            if self.decompress.total_out() > self.next_data_len as u64 {
+                return self.fail(ReadError::FrameTooLong(self.next_data_len, self.decompress.total_out() as usize));
+            }
 
             match ret {
-                Ok(Status::Ok) | Ok(Status::BufError) if read == 0 && !eof => continue,
-                Ok(Status::Ok) | Ok(Status::BufError) | Ok(Status::StreamEnd) => return Some(consumed),
-                Err(_) => return None,
+                Ok(Status::StreamEnd) => return Some(()),
+                Ok(Status::Ok) | Ok(Status::BufError) if read == 0 && consumed == 0 => {
+                    if start == end {
+                        return Some(());
+                    }
+                    // there is room for output, so the input can not be decompressed
+                    let error = std::io::Error::new(ErrorKind::InvalidData, "Compressed packet is malformed");
+                    return self.fail(ReadError::IoError(error));
+                },
+                Ok(Status::Ok) | Ok(Status::BufError) => {},
+                Err(error) => return self.fail(ReadError::IoError(std::io::Error::new(ErrorKind::InvalidData, error))),
             }
         }
     }
@@ -179,12 +279,9 @@ impl SocketRead {
 fn read_varint_size(buf: &[u8]) -> Option<(i32, usize)> {
     let mut result = 0i32;
     // packet lengths can only be 3 bytes
-    for (i, byte) in buf.iter().enumerate().take(3.min(buf.len()) + 1) {
-        if i > 2 {
-            return Some((result, i));
-        }
+    for (i, byte) in buf.iter().enumerate().take(3) {
         result |= ((byte & 0x7f) as i32) << (i * 7);
-        if byte & 0x80 == 0 {
+        if byte & 0x80 == 0 || i == 2 {
             return Some((result, i + 1));
         }
     }
@@ -199,7 +296,7 @@ unsafe impl BufMut for SocketRead {
     unsafe fn advance_mut(&mut self, cnt: usize) {
         self.decompress_pos += cnt;
 
-        if self.decompress_pos >= COMPRESSION_BUF_LEN {
+        if self.decompress_pos >= self.decompress_buf.len() {
             match self.flush_buffer() {
                 Some(n) => self.decompress_pos = n,
                 None => self.decompress_pos = 0,
@@ -209,16 +306,22 @@ unsafe impl BufMut for SocketRead {
 
     fn chunk_mut(&mut self) -> &mut bytes::buf::UninitSlice {
         // TODO: explain unsafe
-        unsafe { UninitSlice::from_raw_parts_mut(self.decompress_buf.as_mut_ptr().add(self.decompress_pos), COMPRESSION_BUF_LEN - self.decompress_pos) }
+        unsafe {
+            let len = self.decompress_buf.len() - self.decompress_pos;
+            UninitSlice::from_raw_parts_mut(self.decompress_buf.as_mut_ptr().add(self.decompress_pos), len)
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use bytes::BufMut;
+    use bytes::{Buf, BufMut};
+    use falcon_packet_core::special::PacketPrepare;
+    use falcon_packet_core::ReadError;
     use itertools::Itertools;
 
-    use super::SocketRead;
+    use super::{LegacyPing, SocketRead};
+    use crate::connection::writer::SocketWrite;
 
     /// Bad test, this needs some asserts
     #[test]
@@ -276,4 +379,122 @@ mod test {
         // println!("Content: {:02x}",
         // reader.output_buf.as_ref().iter().format(" "));
     }
+
+    #[test]
+    fn test_legacy_ping() {
+        let mut reader = SocketRead::new(-1);
+        reader.put_slice(&[0xFE]);
+        assert_eq!(reader.legacy_ping(), Some(LegacyPing::Beta));
+        reader.put_slice(&[0x01, 0xFA]);
+        assert_eq!(reader.legacy_ping(), Some(LegacyPing::V1_4));
+
+        let mut reader = SocketRead::new(-1);
+        reader.put_slice(&[0x10, 0x00, 0xFE]);
+        assert_eq!(reader.legacy_ping(), None);
+    }
+
+    #[test]
+    fn test_compression_roundtrip() {
+        let mut writer = SocketWrite::new(64).with_buffer_len(100).with_compression_level(9);
+        let mut reader = SocketRead::new(64).with_buffer_len(100);
+        for len in [10, 500, 3] {
+            writer.prepare(len);
+            writer.put_bytes(len as u8, len);
+            writer.finish();
+        }
+        while writer.has_remaining() {
+            let chunk = writer.chunk();
+            reader.put_slice(chunk);
+            let n = chunk.len();
+            writer.advance(n);
+        }
+        for len in [10, 500, 3] {
+            assert_eq!(reader.next_packet().unwrap().unwrap(), vec![len as u8; len]);
+        }
+        assert_eq!(reader.next_packet().unwrap(), None);
+    }
+
+    /// Compressed packets that arrive in pieces smaller than the buffer,
+    /// like a slow client sends them.
+    #[test]
+    fn test_split_compression() {
+        let mut writer = SocketWrite::new(8).with_compression_level(9);
+        let lens = [3, 80, 0, 1000, 9];
+        for (i, len) in lens.into_iter().enumerate() {
+            writer.prepare(len);
+            writer.put_bytes(i as u8, len);
+            writer.finish();
+        }
+        let data = writer.copy_to_bytes(writer.remaining());
+        for (buffer_len, chunk_len) in [(1, 1), (6, 5), (64, 7), (4096, 1), (4096, 100)] {
+            let mut reader = SocketRead::new(8).with_buffer_len(buffer_len);
+            let mut packets = Vec::new();
+            for chunk in data.chunks(chunk_len) {
+                reader.put_slice(chunk);
+                while let Some(packet) = reader.next_packet().unwrap() {
+                    packets.push(packet);
+                }
+            }
+            let expected = lens.into_iter().enumerate().map(|(i, len)| vec![i as u8; len]).collect::<Vec<_>>();
+            assert_eq!(packets, expected, "buffer of {} bytes, chunks of {} bytes", buffer_len, chunk_len);
+        }
+    }
+
+    #[test]
+    fn test_malformed_frames() {
+        // a frame without a packet id
+        let mut reader = SocketRead::new(0);
+        reader.put_slice(&[0x00, 0x00]);
+        assert!(matches!(reader.next_packet(), Err(ReadError::ZeroValue(_))));
+        let mut reader = SocketRead::new(-1);
+        reader.put_slice(&[0x00, 0x01, 0x00]);
+        assert!(matches!(reader.next_packet(), Err(ReadError::ZeroValue(_))));
+
+        // the data length is longer than the frame
+        let mut reader = SocketRead::new(0);
+        reader.put_slice(&[0x01, 0x80, 0x80, 0x01, 0x00]);
+        assert!(matches!(reader.next_packet(), Err(ReadError::NotEnoughBytes { expected: 3, remaining: 1 })));
+
+        // not zlib and a stream that is longer than its data length
+        let mut reader = SocketRead::new(0);
+        reader.put_slice(&[0x04, 0x05, 0x3b, 0xa3, 0x35]);
+        assert!(matches!(reader.next_packet(), Err(ReadError::IoError(_))));
+        let mut writer = SocketWrite::new(0);
+        writer.prepare(20);
+        writer.put_bytes(1, 20);
+        writer.finish();
+        let mut data = writer.copy_to_bytes(writer.remaining()).to_vec();
+        data[1] = 10;
+        let mut reader = SocketRead::new(0);
+        reader.put_slice(&data);
+        assert!(matches!(reader.next_packet(), Err(ReadError::FrameTooLong(10, _))));
+
+        // the reader starts over after an error
+        reader.put_slice(&[0x02, 0x00, 0x07]);
+        assert_eq!(reader.next_packet().unwrap().unwrap(), vec![0x07]);
+    }
+
+    #[test]
+    fn test_max_packet_size() {
+        let mut reader = SocketRead::new(-1).with_max_packet_size(100);
+        reader.put_slice(&[0x05, 0x00, 0x01, 0x02, 0x03, 0x04]);
+        assert!(reader.next_packet().unwrap().is_some());
+        reader.put_slice(&[0xC8, 0x01, 0x00]);
+        assert!(matches!(reader.next_packet(), Err(ReadError::FrameTooLong(100, 200))));
+    }
+
+    #[test]
+    fn test_set_max_packet_size() {
+        let mut reader = SocketRead::new(-1).with_max_packet_size(4);
+        reader.put_slice(&[0x03, 0x00, 0x01, 0x02]);
+        assert!(reader.next_packet().unwrap().is_some());
+        reader.set_max_packet_size(8);
+        reader.put_slice(&[0x06, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05]);
+        assert!(reader.next_packet().unwrap().is_some());
+        reader.set_max_packet_size(2);
+        // refused from the length alone, before the body arrived
+        reader.put_slice(&[0x03]);
+        assert!(matches!(reader.next_packet(), Err(ReadError::FrameTooLong(2, 3))));
+    }
 }
+
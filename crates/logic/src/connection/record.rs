@@ -0,0 +1,157 @@
+//! Recording of the packets a connection sends and receives.
+//!
+//! A recording starts with [`MAGIC`], followed by one entry per packet:
+//! the time since the start of the recording in microseconds (`u64`), the
+//! [`Direction`] (`u8`, 0 is inbound), the length (`u32`) and the
+//! uncompressed packet starting with its id as sent over the wire. All
+//! numbers are big endian.
+//!
+//! Recordings can be fed back into a server with `MockConnection::replay`
+//! of the `testing` feature.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use bytes::{Buf, Bytes};
+use falcon_core::server::config::FalconConfig;
+use tracing::{debug, warn};
+
+use super::dump::Direction;
+
+pub const MAGIC: &[u8; 8] = b"FLCNREC1";
+
+/// Creates a recorder for a new connection from `address` when the config
+/// has a `record_dir`.
+pub(crate) fn recorder_for(address: &SocketAddr) -> Option<PacketRecorder> {
+    let dir = FalconConfig::global().debug.record_dir.as_ref()?;
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let path = Path::new(dir).join(format!("{}_{}_{}.rec", address.ip().to_string().replace(':', "-"), address.port(), time));
+    match std::fs::create_dir_all(dir).and_then(|_| PacketRecorder::create(&path)) {
+        Ok(recorder) => {
+            debug!(path = %path.display(), "Recording connection");
+            Some(recorder)
+        },
+        Err(error) => {
+            warn!(%error, path = %path.display(), "Could not start recording the connection");
+            None
+        },
+    }
+}
+
+/// Writes the packets of a connection to a file.
+#[derive(Debug)]
+pub struct PacketRecorder {
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+impl PacketRecorder {
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        Ok(PacketRecorder {
+            writer,
+            start: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, direction: Direction, data: &[u8]) -> io::Result<()> {
+        let time = self.start.elapsed().as_micros() as u64;
+        let direction = match direction {
+            Direction::Inbound => 0u8,
+            Direction::Outbound => 1u8,
+        };
+        self.writer.write_all(&time.to_be_bytes())?;
+        self.writer.write_all(&[direction])?;
+        self.writer.write_all(&(data.len() as u32).to_be_bytes())?;
+        self.writer.write_all(data)?;
+        self.writer.flush()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedPacket {
+    /// The time since the start of the recording.
+    pub time: Duration,
+    pub direction: Direction,
+    /// The packet starting with its id.
+    pub data: Bytes,
+}
+
+/// The packets of a recorded connection, in the order they were recorded.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    packets: Vec<RecordedPacket>,
+}
+
+impl Recording {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> { Self::read_from(File::open(path)?) }
+
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::from_bytes(data.into())
+    }
+
+    pub fn from_bytes(mut data: Bytes) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_owned());
+        if data.len() < MAGIC.len() || &data[..MAGIC.len()] != MAGIC {
+            return Err(invalid("Not a packet recording"));
+        }
+        data.advance(MAGIC.len());
+
+        let mut packets = Vec::new();
+        while data.has_remaining() {
+            if data.remaining() < 13 {
+                return Err(invalid("Truncated packet header"));
+            }
+            let time = Duration::from_micros(data.get_u64());
+            let direction = match data.get_u8() {
+                0 => Direction::Inbound,
+                1 => Direction::Outbound,
+                _ => return Err(invalid("Invalid packet direction")),
+            };
+            let len = data.get_u32() as usize;
+            if data.remaining() < len {
+                return Err(invalid("Truncated packet"));
+            }
+            packets.push(RecordedPacket {
+                time,
+                direction,
+                data: data.split_to(len),
+            });
+        }
+        Ok(Recording { packets })
+    }
+
+    pub fn packets(&self) -> &[RecordedPacket] { &self.packets }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let path = std::env::temp_dir().join(format!("falcon-record-{}.rec", std::process::id()));
+        let mut recorder = PacketRecorder::create(&path).unwrap();
+        recorder.record(Direction::Inbound, &[0x00, 0x01, 0x02]).unwrap();
+        recorder.record(Direction::Outbound, &[0x02]).unwrap();
+        drop(recorder);
+
+        let recording = Recording::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let packets = recording.packets();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].direction, Direction::Inbound);
+        assert_eq!(packets[0].data.as_ref(), &[0x00, 0x01, 0x02]);
+        assert_eq!(packets[1].direction, Direction::Outbound);
+        assert!(packets[0].time <= packets[1].time);
+
+        assert!(Recording::from_bytes(Bytes::from_static(b"FLCNREC1\x00")).is_err());
+        assert!(Recording::from_bytes(Bytes::from_static(b"nonsense")).is_err());
+    }
+}
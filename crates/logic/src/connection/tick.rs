@@ -1,37 +1,51 @@
+use std::time::Duration;
+
 use anyhow::Result;
 use bytes::{Buf, Bytes};
 use falcon_core::error::FalconCoreError;
 use falcon_core::network::ConnectionState;
+use falcon_core::server::config::FalconConfig;
+use falcon_core::server::lang::MessageCatalog;
+use falcon_packet_core::special::RawPacket;
 use falcon_packet_core::{PacketRead, ReadError, VarI32};
-use mc_chat::{ChatColor, ChatComponent, ComponentStyle};
 use thiserror::Error;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
-use tracing::{debug_span, trace, trace_span};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::time::timeout;
+use tracing::{debug, debug_span, error, trace, trace_span};
 
-use super::reader::SocketRead;
-use super::ConnectionReceiver;
+use super::dump::{self, Direction};
+use super::{ConnectionReceiver, DisconnectReason};
 use crate::connection::ConnectionTask;
+use crate::panic::{catch_panic, CatchPanic, Panic};
 use crate::FalconConnection;
 
+/// How long a connection may take to send its last packets on shutdown.
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
 impl FalconConnection {
     #[tracing::instrument(name = "client", skip_all, fields(address = %self.address()))]
-    pub async fn start<R: ConnectionReceiver>(mut self, mut socket: TcpStream, mut receiver: R) {
-        let (mut socket_readhalf, mut socket_writehalf) = socket.split();
-        let mut socket_read = SocketRead::new(-1);
+    pub async fn start<S, R>(mut self, socket: S, mut receiver: R)
+    where
+        S: AsyncRead + AsyncWrite,
+        R: ConnectionReceiver,
+    {
+        let (mut socket_readhalf, mut socket_writehalf) = tokio::io::split(socket);
+        let mut first_read = true;
+        let mut legacy_ping = false;
 
         loop {
+            // the server holds back chunks while the client falls behind
+            self.wrapper.set_backlog(self.write_buffer.remaining());
             tokio::select! {
                 _ = self.shutdown.wait_for_shutdown() => {
+                    self.disconnect(DisconnectReason::Shutdown);
+                    // give the client a chance to see why it was disconnected
+                    timeout(SHUTDOWN_FLUSH_TIMEOUT, socket_writehalf.write_all_buf(&mut self.write_buffer)).await.ok();
                     break;
                 }
 
                 _ = self.timeout.tick() => {
-                    let style = ComponentStyle::with_version(self.state().protocol_id.unsigned_abs());
-                    self.disconnect(ChatComponent::from_text(
-                        "Did not receive Keep alive packet!",
-                        style
-                    ));
+                    self.disconnect(DisconnectReason::TimedOut);
                 }
 
                 task = self.task_rx.recv() => {
@@ -41,15 +55,17 @@ impl FalconConnection {
                     };
                     let span = debug_span!("connection_task", state = %self.state);
                     let _enter = span.enter();
-                    if let Err(error) = match task {
-                        ConnectionTask::Sync(task) => task.run(&mut self),
-                        ConnectionTask::Async(task) => task.run(&mut self).await,
+                    match match task {
+                        ConnectionTask::Sync(task) => catch_panic(|| task.run(&mut self)),
+                        ConnectionTask::Async(task) => CatchPanic(task.run(&mut self)).await,
                     } {
-                        self.disconnect(ChatComponent::from_text(format!("Task errored: {}", error), ComponentStyle::with_version(self.state.protocol_id.unsigned_abs())));
-                    };
+                        Ok(Ok(())) => {}
+                        Ok(Err(error)) => self.disconnect(DisconnectReason::Internal(error.to_string())),
+                        Err(panic) => self.disconnect_panicked(panic),
+                    }
                 }
 
-                n = socket_readhalf.read_buf(&mut socket_read) => {
+                n = socket_readhalf.read_buf(&mut self.read_buffer) => {
                     let span = debug_span!("incoming_data", state = %self.state);
                     let _enter = span.enter();
                     match n {
@@ -58,14 +74,42 @@ impl FalconConnection {
                                 self.state.connection_state = ConnectionState::Disconnected;
                                 break;
                             }
-                            while let Some(packet) = socket_read.next_packet() {
-                                if let Err(error) = process_packet(&mut self, packet, &mut receiver) {
-                                    self.disconnect(ChatComponent::from_text(format!("Error on read: {}", error), ComponentStyle::with_version(self.state.protocol_id.unsigned_abs())));
+                            if legacy_ping {
+                                // the rest of a legacy ping carries nothing we respond to
+                                continue;
+                            }
+                            if std::mem::take(&mut first_read) {
+                                if let Some(ping) = self.read_buffer.legacy_ping() {
+                                    trace!(?ping, "Legacy server list ping");
+                                    legacy_ping = true;
+                                    self.send_legacy_status(ping);
+                                    continue;
+                                }
+                            }
+                            // checked as the data arrives, the client may have
+                            // moved on since the previous data
+                            let limit = FalconConfig::global().network.packet_size_limit(self.state.connection_state);
+                            self.read_buffer.set_max_packet_size(limit);
+                            loop {
+                                match self.read_buffer.next_packet() {
+                                    Ok(Some(packet)) => match catch_panic(|| process_packet(&mut self, packet, &mut receiver)) {
+                                        Ok(Ok(())) => {}
+                                        Ok(Err(error)) => self.disconnect(DisconnectReason::InvalidPacket(error.to_string())),
+                                        Err(panic) => {
+                                            self.disconnect_panicked(panic);
+                                            break;
+                                        }
+                                    }
+                                    Ok(None) => break,
+                                    Err(error) => {
+                                        self.disconnect(DisconnectReason::InvalidPacket(error.to_string()));
+                                        break;
+                                    }
                                 }
                             }
                         }
                         Err(error) => {
-                            self.disconnect(ChatComponent::from_text(format!("Error on read: {}", error), ComponentStyle::with_version(self.state.protocol_id.unsigned_abs())));
+                            self.disconnect(DisconnectReason::InvalidPacket(error.to_string()));
                         }
                     }
                 }
@@ -86,17 +130,58 @@ impl FalconConnection {
             }
         }
     }
+
+    /// Logs a panic of a task or packet handler of this connection and
+    /// disconnects the client, its state may be broken.
+    fn disconnect_panicked(&mut self, panic: Panic) {
+        match panic.backtrace() {
+            Some(backtrace) => error!(%panic, "Connection panicked, disconnecting\n{}", backtrace),
+            None => error!(%panic, "Connection panicked, disconnecting"),
+        }
+        self.disconnect(DisconnectReason::Internal(String::from(panic.message())));
+    }
 }
 
 fn process_packet<R: ConnectionReceiver>(connection: &mut FalconConnection, mut packet: Bytes, receiver: &mut R) -> Result<()> {
-    let packet_id = VarI32::read(&mut packet)?.val();
+    // the state is captured up front, handling the packet may change it
+    // recorded before handling so responses follow the packet, the dump
+    // waits for the name but uses the state the packet was received in
+    connection.record_packet(Direction::Inbound, &packet);
+    let dump = connection.dumps_packets().then(|| (packet.clone(), connection.state().to_string()));
+    let res = handle_packet(connection, &mut packet, receiver);
+    if let Some((raw, state)) = dump {
+        let name = connection.take_handled_packet();
+        dump::dump_packet(connection.address(), &state, Direction::Inbound, name, &raw);
+    }
+    res
+}
+
+fn handle_packet<R: ConnectionReceiver>(connection: &mut FalconConnection, packet: &mut Bytes, receiver: &mut R) -> Result<()> {
+    let wire_id = VarI32::read(packet)?.val();
+    let body = packet.clone();
+    let mut packet_id = wire_id;
+    if let Some(ids) = connection.state().packet_ids() {
+        match ids.serverbound.from_wire(packet_id) {
+            Some(id) => packet_id = id,
+            None => {
+                if !receiver.receive_raw(RawPacket::new(wire_id, body), connection)? {
+                    trace!(packet_id, "Packet id is not mapped by the protocol table, skipping!");
+                }
+                return Ok(());
+            },
+        }
+    }
     let span = trace_span!("packet", packet_id = %format!("{:#04X}", packet_id));
     let _enter = span.enter();
-    if !receiver.receive(packet_id, &mut packet, connection)? {
+    let handled = receiver.receive(packet_id, packet, connection).inspect_err(|error| {
+        if let Some(error) = error.downcast_ref::<ReadError>() {
+            debug!(offset = ?error.offset(), fields = %error.field_path().join(" > "), error = %error.root(), "Dropping malformed packet");
+        }
+    })?;
+    if !handled && !receiver.receive_raw(RawPacket::new(wire_id, body), connection)? {
         let state = connection.state().connection_state;
         if state == ConnectionState::Login || state == ConnectionState::Status {
-            let style = ComponentStyle::with_version(connection.state().protocol_id.unsigned_abs()).color_if_absent(ChatColor::Red);
-            connection.disconnect(ChatComponent::from_text("Unsupported version!", style));
+            connection.disconnect(DisconnectReason::UnsupportedVersion(MessageCatalog::global().message(connection.locale(), "version.unknown_packet", &[])));
         }
         trace!("Unknown packet received, skipping!");
     }
@@ -1,22 +1,41 @@
 use std::convert::Infallible;
 use std::error::Error;
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use anyhow::Result;
 use falcon_packet_core::WriteError;
 use tokio::sync::mpsc::UnboundedSender;
 
-use super::writer::SocketWrite;
+use super::bundle::PacketBundle;
+use super::encode::EncodedPacket;
+use super::writer::{PacketPriority, SocketWrite};
 use super::{ConnectionTask, SyncConnectionTask};
 use crate::FalconConnection;
 
 #[derive(Debug)]
 pub struct ConnectionWrapper {
     link: UnboundedSender<ConnectionTask>,
+    backlog: Arc<AtomicUsize>,
 }
 
 impl ConnectionWrapper {
-    pub fn new(link: UnboundedSender<ConnectionTask>) -> Self { ConnectionWrapper { link } }
+    pub fn new(link: UnboundedSender<ConnectionTask>) -> Self {
+        ConnectionWrapper {
+            link,
+            backlog: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// The bytes the connection has queued but not written to the socket
+    /// yet, as of the last time it handled a task or wrote to the socket.
+    pub fn backlog(&self) -> usize { self.backlog.load(Ordering::Relaxed) }
+
+    pub(crate) fn set_backlog(&self, bytes: usize) { self.backlog.store(bytes, Ordering::Relaxed); }
+
+    /// Whether the connection has stopped, tasks sent to it are dropped.
+    pub fn is_closed(&self) -> bool { self.link.is_closed() }
 
     pub fn reset_keep_alive(&self) {
         self.execute(|connection| {
@@ -36,6 +55,34 @@ impl ConnectionWrapper {
         });
     }
 
+    /// Sends a packet in the lane of `priority`, see [`PacketPriority`].
+    pub fn send_packet_with_priority<T, F>(&self, priority: PacketPriority, packet: T, write_fn: F)
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce(T, &mut SocketWrite, i32) -> Result<bool, WriteError> + Send + Sync + 'static,
+    {
+        self.execute(move |connection| -> Result<(), WriteError> {
+            connection.with_priority(priority, |connection| connection.send_packet(packet, write_fn))?;
+            Ok(())
+        });
+    }
+
+    /// Sends the packets of `bundle` together, they cannot be split up by
+    /// packets that are sent at the same time.
+    pub fn send_bundle(&self, bundle: PacketBundle) {
+        if !bundle.is_empty() {
+            self.execute(move |connection| bundle.write(connection));
+        }
+    }
+
+    /// Sends `packet` once it is encoded, the packets sent after it wait
+    /// for it.
+    pub fn send_encoded(&self, packet: EncodedPacket) {
+        // SAFE: if this channel returns an error, then the client will have
+        // disconnected already.
+        self.link.send(ConnectionTask::Async(Box::new(packet))).ok();
+    }
+
     /// Do not pass a `Box` to this function.
     #[inline]
     pub fn send<T>(&self, task: T)
@@ -61,6 +108,7 @@ impl Clone for ConnectionWrapper {
     fn clone(&self) -> Self {
         ConnectionWrapper {
             link: self.link.clone(),
+            backlog: self.backlog.clone(),
         }
     }
 }
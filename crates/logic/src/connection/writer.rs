@@ -1,5 +1,7 @@
+use std::collections::VecDeque;
+
 use bytes::buf::UninitSlice;
-use bytes::{Buf, BufMut, BytesMut};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use falcon_packet_core::special::PacketPrepare;
 use falcon_packet_core::{PacketSize, VarI32};
 use flate2::{Compress, Compression, FlushCompress, Status};
@@ -9,66 +11,172 @@ const COMPRESSION_BUFFER_LEN: usize = 4096;
 /// This is header (= 2) + Adler checksum (= 4)
 const ZLIB_EXTRA_LEN: usize = 6;
 
+/// The lane a packet is sent in, urgent packets overtake the packets
+/// waiting in the normal lane. Packets of the same lane keep their order.
+///
+/// Chunk data is sent in the normal lane, packets about a chunk like block
+/// changes and spawns may never reach the client before the chunk itself.
+/// The chunk budget keeps the normal lane short instead, see
+/// [`budget`](crate::world::budget).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PacketPriority {
+    /// Packets that must not wait, like keep alives and kicks.
+    Urgent,
+    #[default]
+    Normal,
+}
+
+/// Packets are written into a buffer of their own and queued in their lane
+/// once finished, the socket is fed from the highest lane first. A packet
+/// that is partly sent is always sent in full first.
 #[derive(Debug)]
 pub struct SocketWrite {
-    compression_buffer: [u8; COMPRESSION_BUFFER_LEN],
+    compression_buffer: Box<[u8]>,
     compression_threshold: i32,
     compression: Compress,
     compression_position: usize,
     output_buffer: BytesMut,
     next_is_compressed: bool,
     next_len_size: usize,
-    ready_pos: usize,
+    priority: PacketPriority,
+    /// The packet that is being sent, empty only when every lane is.
+    sending: Bytes,
+    lanes: [VecDeque<Bytes>; 2],
+    queued_len: usize,
+    capture: Option<Vec<u8>>,
 }
 
 impl SocketWrite {
     pub fn new(threshold: i32) -> Self {
         Self {
-            compression_buffer: [0; COMPRESSION_BUFFER_LEN],
+            compression_buffer: vec![0; COMPRESSION_BUFFER_LEN].into_boxed_slice(),
             compression_threshold: threshold,
             compression: Compress::new(Compression::new(5), true),
             compression_position: 0,
             output_buffer: BytesMut::with_capacity(COMPRESSION_BUFFER_LEN),
             next_is_compressed: false,
             next_len_size: 0,
-            ready_pos: 0,
+            priority: PacketPriority::Normal,
+            sending: Bytes::new(),
+            lanes: Default::default(),
+            queued_len: 0,
+            capture: None,
         }
     }
 
+    /// Compresses packets from `len` bytes at a time instead of the default
+    /// 4096, this must be done before any packet is written.
+    pub fn with_buffer_len(mut self, len: usize) -> Self {
+        self.compression_buffer = vec![0; len.max(1)].into_boxed_slice();
+        self
+    }
+
+    /// Uses zlib compression `level`, from 0 (fastest) to 9 (smallest).
+    pub fn with_compression_level(mut self, level: u32) -> Self {
+        self.compression = Compress::new(Compression::new(level.min(9)), true);
+        self
+    }
+
+    /// Changes the compression threshold of the packets written next, a
+    /// negative threshold disables compression. This must not be called
+    /// while a packet is being written.
+    pub fn set_compression_threshold(&mut self, threshold: i32) {
+        // packets in the new format may not overtake those in the old one
+        self.barrier();
+        self.compression_threshold = threshold;
+    }
+
+    /// Sets the lane of the packets written next.
+    pub fn set_priority(&mut self, priority: PacketPriority) { self.priority = priority; }
+
+    pub fn priority(&self) -> PacketPriority { self.priority }
+
+    /// Packets written from now on are sent after every packet that was
+    /// written before, whatever their lane.
+    pub fn barrier(&mut self) {
+        let queued: VecDeque<Bytes> = self.lanes.iter_mut().flat_map(|lane| lane.drain(..)).collect();
+        self.lanes[PacketPriority::Urgent as usize] = queued;
+    }
+
+    /// Keeps a copy of the uncompressed data written from now on, until
+    /// [`take_capture`](Self::take_capture) is called.
+    pub fn start_capture(&mut self) { self.capture = Some(Vec::new()); }
+
+    pub fn take_capture(&mut self) -> Option<Vec<u8>> { self.capture.take() }
+
+    /// Writes bytes without packet framing, this must not be called while
+    /// a packet is being written.
+    pub fn write_raw(&mut self, data: &[u8]) { self.queue(Bytes::copy_from_slice(data)); }
+
+    /// Drops the packet that is being written, e.g. because one of its
+    /// fields could not be written. Finished packets are kept.
+    pub fn discard(&mut self) {
+        self.output_buffer.clear();
+        self.compression.reset();
+        self.compression_position = 0;
+        self.next_is_compressed = false;
+        self.capture = None;
+    }
+
     pub fn finish(&mut self) {
-        if self.ready_pos == self.output_buffer.len() {
+        if self.output_buffer.is_empty() {
             return;
         }
 
+        if !self.next_is_compressed {
+            if let Some(capture) = &mut self.capture {
+                capture.extend_from_slice(&self.output_buffer[self.next_len_size..]);
+            }
+        }
         self.flush();
 
         if self.compression_threshold >= 0 {
             if self.next_is_compressed {
                 let offset = VarI32::from(self.compression.total_in() as usize).size();
                 let overall_len = self.next_len_size - offset;
-                write_fixed_varint((self.output_buffer.len() - self.ready_pos - overall_len) as i32, overall_len, &mut self.output_buffer[self.ready_pos..]);
-                write_fixed_varint(self.compression.total_in() as i32, offset, &mut self.output_buffer[self.ready_pos + overall_len..]);
+                write_fixed_varint((self.output_buffer.len() - overall_len) as i32, overall_len, &mut self.output_buffer[..]);
+                write_fixed_varint(self.compression.total_in() as i32, offset, &mut self.output_buffer[overall_len..]);
             } else {
                 let overall_len = self.next_len_size - 1;
-                write_fixed_varint((self.output_buffer.len() - self.ready_pos - overall_len) as i32, overall_len, &mut self.output_buffer[self.ready_pos..]);
+                write_fixed_varint((self.output_buffer.len() - overall_len) as i32, overall_len, &mut self.output_buffer[..]);
             }
         } else {
             let overall_len = self.next_len_size;
-            write_fixed_varint((self.output_buffer.len() - self.ready_pos - overall_len) as i32, self.next_len_size, &mut self.output_buffer[self.ready_pos..]);
+            write_fixed_varint((self.output_buffer.len() - overall_len) as i32, self.next_len_size, &mut self.output_buffer[..]);
         }
 
         // TODO: do encryption
 
         self.compression.reset();
-        self.ready_pos = self.output_buffer.len();
-
-        if self.output_buffer.len() < COMPRESSION_BUFFER_LEN {
-            let capacity = self.output_buffer.capacity();
-            if capacity > COMPRESSION_BUFFER_LEN && capacity > 3 * self.output_buffer.len() {
-                let new_buffer = BytesMut::with_capacity(COMPRESSION_BUFFER_LEN);
-                let old_buffer = std::mem::replace(&mut self.output_buffer, new_buffer);
-                self.output_buffer.put(old_buffer);
-            }
+        let packet = self.output_buffer.split().freeze();
+        self.queue(packet);
+
+        // the rest of a large allocation would stay alive with the packet
+        let buffer_len = self.compression_buffer.len();
+        if self.output_buffer.capacity() > 4 * buffer_len {
+            self.output_buffer = BytesMut::with_capacity(buffer_len);
+        }
+    }
+
+    fn queue(&mut self, packet: Bytes) {
+        if self.sending.is_empty() {
+            self.sending = packet;
+        } else {
+            self.queued_len += packet.len();
+            self.lanes[self.priority as usize].push_back(packet);
+        }
+    }
+
+    /// Moves on to the next packet of the highest lane, returns whether
+    /// there was one.
+    fn next_sending(&mut self) -> bool {
+        match self.lanes.iter_mut().find_map(VecDeque::pop_front) {
+            Some(packet) => {
+                self.queued_len -= packet.len();
+                self.sending = packet;
+                true
+            },
+            None => false,
         }
     }
 
@@ -94,6 +202,9 @@ impl SocketWrite {
     }
 
     fn write_all(&mut self) {
+        if let Some(capture) = &mut self.capture {
+            capture.extend_from_slice(&self.compression_buffer[..self.compression_position]);
+        }
         if self.next_is_compressed {
             let mut n = 0;
             while !&self.compression_buffer[n..self.compression_position].is_empty() {
@@ -186,48 +297,146 @@ impl PacketPrepare for SocketWrite {
 }
 
 // TODO: explain unsafe code
+/// Packets that are not compressed are written to the output directly,
+/// only compressed packets go through the compression buffer.
 unsafe impl BufMut for SocketWrite {
     fn remaining_mut(&self) -> usize { self.output_buffer.remaining_mut() }
 
     // TODO: explain unsafe
     unsafe fn advance_mut(&mut self, cnt: usize) {
+        if !self.next_is_compressed {
+            self.output_buffer.advance_mut(cnt);
+            return;
+        }
         self.compression_position += cnt;
 
-        if self.compression_position >= COMPRESSION_BUFFER_LEN {
+        let buffer_len = self.compression_buffer.len();
+        if self.compression_position >= buffer_len {
             self.write_all();
-            self.compression_position -= COMPRESSION_BUFFER_LEN;
+            self.compression_position -= buffer_len;
         }
     }
 
     fn chunk_mut(&mut self) -> &mut UninitSlice {
+        if !self.next_is_compressed {
+            return self.output_buffer.chunk_mut();
+        }
         // TODO: explain unsafe
         unsafe {
             UninitSlice::from_raw_parts_mut(
                 self.compression_buffer.as_mut_ptr().add(self.compression_position),
-                COMPRESSION_BUFFER_LEN - self.compression_position,
+                self.compression_buffer.len() - self.compression_position,
             )
         }
     }
 }
 
+/// The finished packets, in the order they are sent.
 impl Buf for SocketWrite {
-    fn remaining(&self) -> usize { self.ready_pos.min(self.output_buffer.remaining()) }
+    fn remaining(&self) -> usize { self.sending.len() + self.queued_len }
 
-    fn chunk(&self) -> &[u8] { &self.output_buffer[..self.remaining()] }
+    fn chunk(&self) -> &[u8] { &self.sending }
 
-    fn advance(&mut self, cnt: usize) {
-        self.output_buffer.advance(cnt);
-        self.ready_pos -= cnt;
+    fn advance(&mut self, mut cnt: usize) {
+        loop {
+            let n = cnt.min(self.sending.len());
+            self.sending.advance(n);
+            cnt -= n;
+            if self.sending.is_empty() && !self.next_sending() {
+                assert_eq!(cnt, 0, "cannot advance past `remaining`");
+                return;
+            }
+            if cnt == 0 {
+                return;
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use bytes::{Buf, BufMut};
-    use falcon_packet_core::special::PacketPrepare;
+    use falcon_packet_core::special::{write_packet, PacketPrepare};
     use itertools::Itertools;
 
-    use super::SocketWrite;
+    use super::{PacketPriority, SocketWrite};
+
+    #[test]
+    fn test_discard() {
+        let mut writer = SocketWrite::new(-1);
+        writer.prepare(2);
+        writer.put_slice(&[1, 2]);
+        writer.finish();
+        writer.prepare(3);
+        writer.put_slice(&[9]);
+        writer.discard();
+        writer.prepare(1);
+        writer.put_slice(&[3]);
+        writer.finish();
+        assert_eq!(writer.copy_to_bytes(writer.remaining()), &[2, 1, 2, 1, 3][..]);
+    }
+
+    #[test]
+    fn test_priority() {
+        let mut writer = SocketWrite::new(-1);
+        for (priority, byte) in [(PacketPriority::Normal, 1), (PacketPriority::Normal, 2), (PacketPriority::Normal, 3), (PacketPriority::Urgent, 4)] {
+            writer.set_priority(priority);
+            write_packet(&mut writer, byte, &[][..]).unwrap();
+            writer.finish();
+        }
+        // the first packet was already being sent
+        assert_eq!(writer.copy_to_bytes(writer.remaining()), &[1, 1, 1, 4, 1, 2, 1, 3][..]);
+
+        writer.set_priority(PacketPriority::Normal);
+        write_packet(&mut writer, 5, &[][..]).unwrap();
+        writer.finish();
+        write_packet(&mut writer, 6, &[][..]).unwrap();
+        writer.finish();
+        writer.barrier();
+        writer.set_priority(PacketPriority::Urgent);
+        write_packet(&mut writer, 7, &[][..]).unwrap();
+        writer.finish();
+        assert_eq!(writer.copy_to_bytes(writer.remaining()), &[1, 5, 1, 6, 1, 7][..]);
+    }
+
+    #[test]
+    fn test_chunk_order() {
+        let mut writer = SocketWrite::new(-1);
+        write_packet(&mut writer, 0x01, &[][..]).unwrap();
+        writer.finish();
+        // the chunk waits behind the packet that is being sent
+        write_packet(&mut writer, 0x22, &[0u8; 8][..]).unwrap();
+        writer.finish();
+        write_packet(&mut writer, 0x0B, &[1u8][..]).unwrap();
+        writer.finish();
+        writer.set_priority(PacketPriority::Urgent);
+        write_packet(&mut writer, 0x21, &[][..]).unwrap();
+        writer.finish();
+
+        let mut ids = Vec::new();
+        while writer.has_remaining() {
+            let len = writer.get_u8() as usize;
+            ids.push(writer.get_u8());
+            writer.advance(len - 1);
+        }
+        // only the keep alive overtakes, the block change stays behind its chunk
+        assert_eq!(ids, vec![0x01, 0x21, 0x22, 0x0B]);
+    }
+
+    #[test]
+    fn test_write_packet_capture() {
+        for threshold in [-1, 0, 256] {
+            let mut writer = SocketWrite::new(threshold);
+            writer.start_capture();
+            write_packet(&mut writer, 0x21, &[5u8, 6][..]).unwrap();
+            writer.finish();
+            assert_eq!(writer.take_capture().unwrap(), vec![0x21, 5, 6]);
+        }
+        let mut writer = SocketWrite::new(-1);
+        write_packet(&mut writer, 0x21, &[5u8, 6][..]).unwrap();
+        writer.finish();
+        assert_eq!(writer.chunk(), &[3, 0x21, 5, 6]);
+    }
 
     /// Bad test, this needs some asserts
     #[test]
@@ -243,7 +452,7 @@ mod test {
         println!("Capacity: {}", writer.output_buffer.capacity());
         println!("CompPos: {}", writer.compression_position);
         println!("Length: {}", writer.output_buffer.len());
-        println!("ReadyPos: {}", writer.ready_pos);
+        println!("Remaining: {}", writer.remaining());
         println!("Content: {:02x}", writer.output_buffer.as_ref().iter().format(" "));
 
         writer.prepare(220);
@@ -252,7 +461,7 @@ mod test {
         println!("Capacity: {}", writer.output_buffer.capacity());
         println!("CompPos: {}", writer.compression_position);
         println!("Length: {}", writer.output_buffer.len());
-        println!("ReadyPos: {}", writer.ready_pos);
+        println!("Remaining: {}", writer.remaining());
         println!("Content: {:02x}", writer.output_buffer.as_ref().iter().format(" "));
 
         let mut read = [0u8; 10];
@@ -261,7 +470,7 @@ mod test {
         println!("Capacity: {}", writer.output_buffer.capacity());
         println!("CompPos: {}", writer.compression_position);
         println!("Length: {}", writer.output_buffer.len());
-        println!("ReadyPos: {}", writer.ready_pos);
+        println!("Remaining: {}", writer.remaining());
         println!("Content: {:02x}", writer.output_buffer.as_ref().iter().format(" "));
 
         writer.put_bytes(1, 110);
@@ -270,7 +479,7 @@ mod test {
         println!("Capacity: {}", writer.output_buffer.capacity());
         println!("CompPos: {}", writer.compression_position);
         println!("Length: {}", writer.output_buffer.len());
-        println!("ReadyPos: {}", writer.ready_pos);
+        println!("Remaining: {}", writer.remaining());
         println!("Content: {:02x}", writer.output_buffer.as_ref().iter().format(" "));
     }
 }
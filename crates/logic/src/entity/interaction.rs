@@ -0,0 +1,22 @@
+use falcon_core::world::entity::EntityInteraction;
+use uuid::Uuid;
+
+use crate::server::FalconServer;
+
+/// A player that clicked on an entity or another player.
+#[derive(Clone, Debug)]
+pub struct InteractEvent {
+    pub player: Uuid,
+    /// The id of the entity.
+    pub target: i32,
+    pub interaction: EntityInteraction,
+    /// Since 1.16, always `false` before.
+    pub sneaking: bool,
+    /// Skips the built-in handling, e.g. the hits of
+    /// [`pvp`](falcon_core::server::config::PlayerSettings::pvp).
+    pub cancelled: bool,
+}
+
+/// Sees every interaction before the server handles it, e.g. to build a
+/// minigame on top of the hits of players.
+pub type InteractListener = Box<dyn FnMut(&mut FalconServer, &mut InteractEvent) + Send>;
@@ -0,0 +1,128 @@
+//! Every entity of the server, players included. Entity ids are handed out
+//! in order and never reused, so a client cannot mistake a new entity for
+//! one that was removed.
+
+use ahash::AHashMap;
+use falcon_core::world::entity::EntityType;
+use uuid::Uuid;
+
+pub mod interaction;
+pub mod tracker;
+
+/// What the registry knows of an entity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntityInfo {
+    pub id: i32,
+    pub uuid: Uuid,
+    pub entity_type: EntityType,
+}
+
+/// A change to the entities of the registry.
+#[derive(Debug)]
+pub enum EntityEvent<'a> {
+    Spawned(&'a EntityInfo),
+    Removed(&'a EntityInfo),
+}
+
+/// Sees every entity that is spawned or removed, after the registry
+/// changed.
+pub type EntityListener = Box<dyn FnMut(&EntityEvent) + Send>;
+
+/// Hands out entity ids and maps them to the uuid and type of their
+/// entity.
+pub struct EntityRegistry {
+    next_id: i32,
+    entities: AHashMap<i32, EntityInfo>,
+    uuids: AHashMap<Uuid, i32>,
+    listeners: Vec<EntityListener>,
+}
+
+impl EntityRegistry {
+    pub fn new() -> Self {
+        EntityRegistry {
+            next_id: 0,
+            entities: AHashMap::new(),
+            uuids: AHashMap::new(),
+            listeners: Vec::new(),
+        }
+    }
+
+    pub fn add_listener(&mut self, listener: EntityListener) { self.listeners.push(listener); }
+
+    /// Registers a new entity and returns its id, the uuid now points to
+    /// this entity.
+    pub fn spawn(&mut self, entity_type: EntityType, uuid: Uuid) -> i32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.uuids.insert(uuid, id);
+        let info = self.entities.entry(id).or_insert(EntityInfo { id, uuid, entity_type });
+        for listener in &mut self.listeners {
+            listener(&EntityEvent::Spawned(info));
+        }
+        id
+    }
+
+    /// Returns `None` if no entity has this id.
+    pub fn remove(&mut self, id: i32) -> Option<EntityInfo> {
+        let info = self.entities.remove(&id)?;
+        if self.uuids.get(&info.uuid) == Some(&id) {
+            self.uuids.remove(&info.uuid);
+        }
+        for listener in &mut self.listeners {
+            listener(&EntityEvent::Removed(&info));
+        }
+        Some(info)
+    }
+
+    /// The id the next entity gets.
+    pub fn next_id(&self) -> i32 { self.next_id }
+
+    pub fn get(&self, id: i32) -> Option<&EntityInfo> { self.entities.get(&id) }
+
+    pub fn by_uuid(&self, uuid: Uuid) -> Option<&EntityInfo> { self.uuids.get(&uuid).and_then(|id| self.entities.get(id)) }
+
+    pub fn len(&self) -> usize { self.entities.len() }
+
+    pub fn is_empty(&self) -> bool { self.entities.is_empty() }
+
+    pub fn iter(&self) -> impl Iterator<Item = &EntityInfo> { self.entities.values() }
+}
+
+impl Default for EntityRegistry {
+    fn default() -> Self { EntityRegistry::new() }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use falcon_core::world::entity::EntityType;
+    use uuid::Uuid;
+
+    use super::{EntityEvent, EntityRegistry};
+
+    #[test]
+    fn test_registry() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = EntityRegistry::new();
+        let seen = events.clone();
+        registry.add_listener(Box::new(move |event| {
+            let (spawned, info) = match event {
+                EntityEvent::Spawned(info) => (true, info),
+                EntityEvent::Removed(info) => (false, info),
+            };
+            seen.lock().unwrap().push((spawned, info.id));
+        }));
+
+        let player = registry.spawn(EntityType::Player, Uuid::from_u128(1));
+        let stand = registry.spawn(EntityType::ArmorStand, Uuid::from_u128(2));
+        assert_eq!((player, stand), (0, 1));
+        assert_eq!(registry.by_uuid(Uuid::from_u128(2)).unwrap().entity_type, EntityType::ArmorStand);
+
+        assert!(registry.remove(player).is_some());
+        assert!(registry.remove(player).is_none());
+        assert!(registry.by_uuid(Uuid::from_u128(1)).is_none());
+        assert_eq!(registry.spawn(EntityType::Player, Uuid::from_u128(1)), 2);
+        assert_eq!(*events.lock().unwrap(), [(true, 0), (true, 1), (false, 0), (true, 2)]);
+    }
+}
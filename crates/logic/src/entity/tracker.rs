@@ -0,0 +1,182 @@
+use ahash::{AHashMap, AHashSet};
+use falcon_core::player::data::{LookAngles, Position};
+use falcon_core::world::entity::{EntityMetadata, EntityType};
+use falcon_send::specs::play::{EntityMetadataSpec, SpawnEntitySpec};
+use uuid::Uuid;
+
+use crate::connection::PacketBundle;
+use crate::player::FalconPlayer;
+
+/// A non-player entity, shown to the players that have its chunk in view.
+#[derive(Clone, Debug)]
+pub struct TrackedEntity {
+    pub id: i32,
+    pub uuid: Uuid,
+    pub entity_type: EntityType,
+    pub position: Position,
+    pub look: LookAngles,
+    pub metadata: EntityMetadata,
+}
+
+impl TrackedEntity {
+    pub fn new(id: i32, uuid: Uuid, entity_type: EntityType, position: Position) -> Self {
+        TrackedEntity {
+            id,
+            uuid,
+            entity_type,
+            position,
+            look: LookAngles::default(),
+            metadata: EntityMetadata::default(),
+        }
+    }
+
+    /// Whether the chunk of this entity is within the view distance of
+    /// `player`.
+    pub fn in_view_of(&self, player: &FalconPlayer) -> bool { in_view(player, &self.position) }
+
+    fn spawn_for(&self, player: &FalconPlayer) {
+        let protocol = player.protocol_version();
+        let spawn = SpawnEntitySpec::new(self.id, self.uuid, self.entity_type, self.position, self.look, 0, protocol);
+        let metadata = EntityMetadataSpec::new(self.id, self.entity_type, self.metadata.clone(), protocol);
+        let bundle = PacketBundle::new()
+            .with(spawn, falcon_send::write_spawn_entity)
+            .with(metadata, falcon_send::write_entity_metadata);
+        player.connection().send_bundle(bundle);
+    }
+
+    fn send_metadata(&self, player: &FalconPlayer) {
+        let metadata = EntityMetadataSpec::new(self.id, self.entity_type, self.metadata.clone(), player.protocol_version());
+        player.connection().send_packet(metadata, falcon_send::write_entity_metadata);
+    }
+}
+
+/// Whether the chunk of `position` is within the view distance of `player`.
+fn in_view(player: &FalconPlayer, position: &Position) -> bool {
+    let (chunk_x, chunk_z) = player.position().chunk_coords();
+    let (x, z) = position.chunk_coords();
+    let view_distance = player.view_distance() as u32;
+    chunk_x.abs_diff(x) <= view_distance && chunk_z.abs_diff(z) <= view_distance
+}
+
+/// Keeps track of which entities and other players each player was sent,
+/// so they are spawned and destroyed as players move around.
+#[derive(Debug, Default)]
+pub struct EntityTracker {
+    entities: AHashMap<i32, TrackedEntity>,
+    /// The entities and players that were spawned for each player.
+    viewers: AHashMap<Uuid, AHashSet<i32>>,
+}
+
+impl EntityTracker {
+    pub fn get(&self, id: i32) -> Option<&TrackedEntity> { self.entities.get(&id) }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TrackedEntity> { self.entities.values() }
+
+    /// Starts tracking an entity and spawns it for the players that have it
+    /// in view.
+    pub fn add(&mut self, entity: TrackedEntity, players: &AHashMap<Uuid, FalconPlayer>) {
+        for player in players.values().filter(|player| entity.in_view_of(player)) {
+            entity.spawn_for(player);
+            self.viewers.entry(player.uuid()).or_default().insert(entity.id);
+        }
+        self.entities.insert(entity.id, entity);
+    }
+
+    /// Destroys an entity for every player that sees it.
+    pub fn remove(&mut self, id: i32, players: &AHashMap<Uuid, FalconPlayer>) -> Option<TrackedEntity> {
+        let entity = self.entities.remove(&id)?;
+        for (uuid, seen) in &mut self.viewers {
+            if seen.remove(&id) {
+                if let Some(player) = players.get(uuid) {
+                    player.connection().send_packet(id, falcon_send::write_destroy_entity);
+                }
+            }
+        }
+        Some(entity)
+    }
+
+    /// Replaces the metadata of an entity, returns `false` if the entity is
+    /// not tracked.
+    pub fn set_metadata(&mut self, id: i32, metadata: EntityMetadata, players: &AHashMap<Uuid, FalconPlayer>) -> bool {
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return false;
+        };
+        entity.metadata = metadata;
+        for (uuid, seen) in &self.viewers {
+            if let Some(player) = players.get(uuid).filter(|_| seen.contains(&id)) {
+                entity.send_metadata(player);
+            }
+        }
+        true
+    }
+
+    /// The players that were sent the entity or player `id`.
+    pub fn viewers_of<'a>(&'a self, id: i32, players: &'a AHashMap<Uuid, FalconPlayer>) -> impl Iterator<Item = &'a FalconPlayer> {
+        self.viewers
+            .iter()
+            .filter(move |(_, seen)| seen.contains(&id))
+            .filter_map(|(uuid, _)| players.get(uuid))
+    }
+
+    /// Spawns the entities and players that came into view of a player and
+    /// destroys the ones that left it, e.g. after joining or moving to
+    /// another chunk. The player is also shown to or hidden from the others.
+    pub fn update_player(&mut self, player: &FalconPlayer, players: &AHashMap<Uuid, FalconPlayer>) {
+        let seen = self.viewers.entry(player.uuid()).or_default();
+        for entity in self.entities.values() {
+            match (entity.in_view_of(player), seen.contains(&entity.id)) {
+                (true, false) => {
+                    entity.spawn_for(player);
+                    seen.insert(entity.id);
+                },
+                (false, true) => {
+                    player.connection().send_packet(entity.id, falcon_send::write_destroy_entity);
+                    seen.remove(&entity.id);
+                },
+                _ => {},
+            }
+        }
+        for other in players.values().filter(|other| other.uuid() != player.uuid()) {
+            self.update_viewer(player, other);
+            self.update_viewer(other, player);
+        }
+    }
+
+    /// Spawns or destroys `target` for `viewer`, depending on whether it is
+    /// in view.
+    fn update_viewer(&mut self, viewer: &FalconPlayer, target: &FalconPlayer) {
+        let seen = self.viewers.entry(viewer.uuid()).or_default();
+        let id = target.entity_id();
+        match (in_view(viewer, target.position()), seen.contains(&id)) {
+            (true, false) => {
+                // not part of the spawn packet since 1.15
+                let metadata = EntityMetadataSpec::new(id, EntityType::Player, target.metadata().clone(), viewer.protocol_version());
+                let bundle = PacketBundle::new()
+                    .with(target.spawn_spec(viewer.protocol_version()), falcon_send::write_spawn_player)
+                    .with(metadata, falcon_send::write_entity_metadata)
+                    .with((id, target.look_angles().yaw), falcon_send::write_entity_head_look);
+                viewer.connection().send_bundle(bundle);
+                seen.insert(id);
+            },
+            (false, true) => {
+                viewer.connection().send_packet(id, falcon_send::write_destroy_entity);
+                seen.remove(&id);
+            },
+            _ => {},
+        }
+    }
+
+    /// Forgets what a player that left was sent and destroys it for the
+    /// players that saw it.
+    pub fn remove_player(&mut self, player: &FalconPlayer, players: &AHashMap<Uuid, FalconPlayer>) {
+        self.viewers.remove(&player.uuid());
+        let id = player.entity_id();
+        for (uuid, seen) in &mut self.viewers {
+            if seen.remove(&id) {
+                if let Some(viewer) = players.get(uuid) {
+                    viewer.connection().send_packet(id, falcon_send::write_destroy_entity);
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 pub mod connection;
+pub mod entity;
+pub mod panic;
 pub mod player;
 pub mod server;
 pub mod world;
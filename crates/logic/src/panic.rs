@@ -0,0 +1,105 @@
+//! Keeps a panic in one connection or task from taking down the server.
+//!
+//! Panics are caught with [`catch_panic`] and [`CatchPanic`], the panic
+//! hook of [`install_hook`] keeps the backtrace of these for logging
+//! instead of reporting them as a crash.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use thiserror::Error;
+
+thread_local! {
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static CAUGHT: RefCell<Option<(String, Backtrace)>> = const { RefCell::new(None) };
+}
+
+/// A panic that was caught.
+#[derive(Error, Debug)]
+#[error("panicked at {location}: {message}")]
+pub struct Panic {
+    message: String,
+    location: String,
+    backtrace: Option<Backtrace>,
+}
+
+impl Panic {
+    fn new(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => payload.downcast_ref::<&str>().map(|message| message.to_string()).unwrap_or_else(|| String::from("Box<dyn Any>")),
+        };
+        let (location, backtrace) = match CAUGHT.take() {
+            Some((location, backtrace)) => (location, Some(backtrace)),
+            None => (String::from("<unknown>"), None),
+        };
+        Panic { message, location, backtrace }
+    }
+
+    pub fn message(&self) -> &str { &self.message }
+
+    /// The backtrace of the panic, only when the hook of [`install_hook`]
+    /// is installed.
+    pub fn backtrace(&self) -> Option<&Backtrace> { self.backtrace.as_ref() }
+}
+
+/// Installs a panic hook that captures the backtrace of the panics that
+/// are caught, other panics are passed to the previous hook.
+pub fn install_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if CATCHING.get() {
+            let location = info.location().map(|location| location.to_string()).unwrap_or_default();
+            CAUGHT.set(Some((location, Backtrace::force_capture())));
+        } else {
+            previous(info);
+        }
+    }));
+}
+
+/// Runs `f`, a panic is returned as an error.
+pub fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, Panic> {
+    let catching = CATCHING.replace(true);
+    let res = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(catching);
+    res.map_err(Panic::new)
+}
+
+/// A future that returns a panic while it is polled as an error.
+pub struct CatchPanic<F>(pub F);
+
+impl<F: Future + Unpin> Future for CatchPanic<F> {
+    type Output = Result<F::Output, Panic>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match catch_panic(|| Pin::new(&mut self.0).poll(cx)) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(panic) => Poll::Ready(Err(panic)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{catch_panic, install_hook, CatchPanic};
+
+    #[tokio::test]
+    async fn test_catch() {
+        install_hook();
+        assert_eq!(catch_panic(|| 1).unwrap(), 1);
+
+        let panic = catch_panic(|| panic!("oops {}", 1)).unwrap_err();
+        assert_eq!(panic.message(), "oops 1");
+        assert!(panic.backtrace().is_some());
+        assert!(panic.to_string().contains("panic.rs"));
+
+        let panic = CatchPanic(Box::pin(async { panic!("async oops") })).await.unwrap_err();
+        assert_eq!(panic.message(), "async oops");
+    }
+}
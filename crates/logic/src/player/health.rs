@@ -0,0 +1,139 @@
+//! The health and food of players. Players only take damage when the
+//! `players.damage` setting of the config is on.
+
+use uuid::Uuid;
+
+pub const MAX_HEALTH: f32 = 20.0;
+pub const MAX_FOOD: i32 = 20;
+/// The saturation of a player that just joined or respawned.
+const START_SATURATION: f32 = 5.0;
+/// Ticks after a hit in which a player takes no more damage, like vanilla.
+const INVULNERABLE_TICKS: u32 = 10;
+/// Falls up to this many blocks do no damage.
+const SAFE_FALL_DISTANCE: f64 = 3.0;
+
+/// What hurt a player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageCause {
+    Fall,
+    /// Being below the bottom of the world.
+    Void,
+    /// A hit by another player.
+    Attack(Uuid),
+    /// Damage dealt by a plugin.
+    Generic,
+}
+
+impl DamageCause {
+    /// The key of the death message in the message catalog.
+    pub fn death_message(&self) -> &'static str {
+        match self {
+            DamageCause::Fall => "death.fall",
+            DamageCause::Void => "death.void",
+            DamageCause::Attack(_) => "death.attack",
+            DamageCause::Generic => "death.generic",
+        }
+    }
+}
+
+/// The health, food and fall distance of a player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HealthState {
+    health: f32,
+    food: i32,
+    saturation: f32,
+    /// Blocks fallen since the player last stood on the ground.
+    fall_distance: f64,
+    invulnerable_ticks: u32,
+}
+
+impl HealthState {
+    pub fn health(&self) -> f32 { self.health }
+
+    pub fn food(&self) -> i32 { self.food }
+
+    pub fn saturation(&self) -> f32 { self.saturation }
+
+    pub fn is_dead(&self) -> bool { self.health <= 0.0 }
+
+    /// Lowers the health by `amount`, returns the damage that was taken.
+    /// Dead players and players that were hit less than half a second ago
+    /// take no damage.
+    pub fn damage(&mut self, amount: f32) -> f32 {
+        if self.is_dead() || self.invulnerable_ticks > 0 || amount <= 0.0 {
+            return 0.0;
+        }
+        let taken = amount.min(self.health);
+        self.health -= taken;
+        self.invulnerable_ticks = INVULNERABLE_TICKS;
+        taken
+    }
+
+    pub fn kill(&mut self) { self.health = 0.0; }
+
+    /// Does nothing for dead players.
+    pub fn heal(&mut self, amount: f32) {
+        if !self.is_dead() {
+            self.health = (self.health + amount).min(MAX_HEALTH);
+        }
+    }
+
+    pub fn set_food(&mut self, food: i32, saturation: f32) {
+        self.food = food.clamp(0, MAX_FOOD);
+        self.saturation = saturation.clamp(0.0, self.food as f32);
+    }
+
+    /// Adds a move from height `from_y` to `to_y` to the fall distance and
+    /// returns the fall damage once the player lands.
+    pub fn fall(&mut self, from_y: f64, to_y: f64, on_ground: bool) -> f32 {
+        if to_y < from_y {
+            self.fall_distance += from_y - to_y;
+        }
+        if !on_ground {
+            return 0.0;
+        }
+        let damage = (self.fall_distance - SAFE_FALL_DISTANCE).ceil().max(0.0);
+        self.fall_distance = 0.0;
+        damage as f32
+    }
+
+    /// Forgets the fall so far, e.g. after a teleport or while flying.
+    pub fn reset_fall(&mut self) { self.fall_distance = 0.0; }
+
+    pub(crate) fn tick(&mut self) { self.invulnerable_ticks = self.invulnerable_ticks.saturating_sub(1); }
+}
+
+impl Default for HealthState {
+    fn default() -> Self {
+        HealthState {
+            health: MAX_HEALTH,
+            food: MAX_FOOD,
+            saturation: START_SATURATION,
+            fall_distance: 0.0,
+            invulnerable_ticks: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_damage_and_fall() {
+        let mut health = HealthState::default();
+        assert_eq!(health.fall(70.0, 68.0, false), 0.0);
+        assert_eq!(health.fall(68.0, 64.5, true), 3.0);
+        assert_eq!(health.fall(64.5, 64.5, true), 0.0);
+
+        assert_eq!(health.damage(3.0), 3.0);
+        assert_eq!(health.damage(3.0), 0.0);
+        for _ in 0..INVULNERABLE_TICKS {
+            health.tick();
+        }
+        assert_eq!(health.damage(30.0), 17.0);
+        assert!(health.is_dead());
+        health.heal(5.0);
+        assert!(health.is_dead());
+    }
+}
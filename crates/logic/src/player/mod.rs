@@ -1,21 +1,36 @@
 use std::convert::Infallible;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use falcon_core::player::data::{GameMode, LookAngles, PlayerAbilityFlags, Position};
+use ahash::AHashMap;
+use falcon_core::chat::ChatComponent;
+use falcon_core::player::advancements::{Advancement, AdvancementFrame};
+use falcon_core::player::data::{GameMode, LookAngles, PlayerAbilityFlags, Position, RecipeBookSettings};
+use falcon_core::player::profile::GameProfile;
+use falcon_core::player::stats::PlayerStatistics;
 use falcon_core::server::config::FalconConfig;
 use falcon_core::server::data::Difficulty;
-use falcon_packet_core::WriteError;
-use falcon_send::specs::play::JoinGameSpec;
-use mc_chat::ChatComponent;
+use falcon_core::server::lang::default_locale;
+use falcon_core::world::entity::EntityMetadata;
+use falcon_send::specs::play::{AdvancementsSpec, JoinGameSpec, PlayerInfoEntry, SpawnPlayerSpec, SpawnPositionSpec, UnlockRecipesAction, UnlockRecipesSpec, UpdateHealthSpec};
 use tokio::time::Instant;
 use uuid::Uuid;
 
-use crate::connection::ConnectionWrapper;
+use self::health::HealthState;
+use self::movement::MovementState;
+use crate::connection::{ConnectionWrapper, DisconnectReason};
+
+pub mod health;
+pub mod movement;
+pub mod profile;
+pub mod void;
+
+/// The advancement that shows toasts, it is removed right after.
+const TOAST_ID: &str = "falcon:toast";
 
 #[derive(Debug)]
 pub struct FalconPlayer {
     // identity
-    username: String,
-    uuid: Uuid,
+    profile: GameProfile,
     // in-game
     eid: i32,
     gamemode: GameMode,
@@ -23,39 +38,76 @@ pub struct FalconPlayer {
     abilities: PlayerAbilityFlags,
     position: Position,
     facing: LookAngles,
+    spawn_point: (Position, LookAngles),
     view_distance: u8,
+    locale: String,
+    statistics: PlayerStatistics,
+    /// The time each advancement was granted, in milliseconds since the Unix
+    /// epoch.
+    advancements: AHashMap<String, i64>,
+    recipe_book: RecipeBookSettings,
+    /// What other players see of this player, e.g. sneaking.
+    metadata: EntityMetadata,
+    movement: MovementState,
+    health: HealthState,
     // network
     time: Instant,
+    last_action: Instant,
+    ping: u32,
     protocol: i32,
     connection: ConnectionWrapper,
 }
 
 impl FalconPlayer {
-    pub fn new(username: String, uuid: Uuid, eid: i32, pos: Position, facing: LookAngles, protocol: i32, connection: ConnectionWrapper) -> Self {
+    pub fn new(profile: GameProfile, eid: i32, pos: Position, facing: LookAngles, protocol: i32, connection: ConnectionWrapper) -> Self {
         FalconPlayer {
-            username,
-            uuid,
+            profile,
             eid,
             gamemode: GameMode::Creative,
             dimension: 0,
             abilities: PlayerAbilityFlags::new(false, true, true, true),
             position: pos,
             facing,
+            spawn_point: (pos, facing),
             view_distance: 5,
+            locale: String::from(default_locale()),
+            statistics: PlayerStatistics::default(),
+            advancements: AHashMap::new(),
+            recipe_book: RecipeBookSettings::default(),
+            metadata: EntityMetadata::default(),
+            movement: MovementState::default(),
+            health: HealthState::default(),
             time: Instant::now(),
+            last_action: Instant::now(),
+            ping: 0,
             protocol,
             connection,
         }
     }
 
-    pub fn username(&self) -> &str { &self.username }
+    pub fn username(&self) -> &str { &self.profile.name }
 
-    pub fn uuid(&self) -> Uuid { self.uuid }
+    pub fn uuid(&self) -> Uuid { self.profile.uuid }
+
+    /// The profile as sent to clients, including e.g. the skin textures.
+    pub fn profile(&self) -> &GameProfile { &self.profile }
+
+    pub fn with_statistics(mut self, statistics: PlayerStatistics) -> Self {
+        self.statistics = statistics;
+        self
+    }
 
     pub fn entity_id(&self) -> i32 { self.eid }
 
     pub fn gamemode(&self) -> GameMode { self.gamemode }
 
+    /// Changes the game mode and the abilities that come with it, this
+    /// does not notify any client, see [`FalconServer::set_game_mode`](crate::FalconServer::set_game_mode).
+    pub fn set_game_mode(&mut self, game_mode: GameMode) {
+        self.gamemode = game_mode;
+        self.abilities = PlayerAbilityFlags::for_game_mode(game_mode, self.abilities.flying);
+    }
+
     pub fn dimension(&self) -> i32 { self.dimension }
 
     pub fn ability_flags(&self) -> PlayerAbilityFlags { self.abilities }
@@ -68,36 +120,172 @@ impl FalconPlayer {
 
     pub fn look_angles_mut(&mut self) -> &mut LookAngles { &mut self.facing }
 
+    /// Where the player respawns, the place it joined at unless changed.
+    pub fn spawn_point(&self) -> (Position, LookAngles) { self.spawn_point }
+
+    /// Changes where the player respawns and points its compass there.
+    pub fn set_spawn_point(&mut self, position: Position, look: LookAngles) {
+        self.spawn_point = (position, look);
+        self.send_spawn_position();
+    }
+
+    pub fn send_spawn_position(&self) {
+        let (position, look) = self.spawn_point;
+        self.connection
+            .send_packet(SpawnPositionSpec::new(position.into(), look.yaw), falcon_send::write_spawn_position);
+    }
+
     pub fn view_distance(&self) -> u8 { self.view_distance }
 
     pub fn set_view_distance(&mut self, distance: u8) {
         self.view_distance = std::cmp::max(0, std::cmp::min(distance, FalconConfig::global().players.max_view_distance));
     }
 
+    /// The locale of the client, used for messages sent to this player.
+    pub fn locale(&self) -> &str { &self.locale }
+
+    pub fn set_locale(&mut self, locale: String) { self.locale = locale; }
+
+    pub fn statistics(&self) -> &PlayerStatistics { &self.statistics }
+
+    pub fn statistics_mut(&mut self) -> &mut PlayerStatistics { &mut self.statistics }
+
+    pub fn has_advancement(&self, id: &str) -> bool { self.advancements.contains_key(id) }
+
+    pub fn recipe_book(&self) -> &RecipeBookSettings { &self.recipe_book }
+
+    pub fn recipe_book_mut(&mut self) -> &mut RecipeBookSettings { &mut self.recipe_book }
+
+    pub fn metadata(&self) -> &EntityMetadata { &self.metadata }
+
+    pub fn metadata_mut(&mut self) -> &mut EntityMetadata { &mut self.metadata }
+
+    pub fn movement_mut(&mut self) -> &mut MovementState { &mut self.movement }
+
+    pub fn health(&self) -> &HealthState { &self.health }
+
+    /// Changes the health without notifying the client, see
+    /// [`send_health`](FalconPlayer::send_health).
+    pub fn health_mut(&mut self) -> &mut HealthState { &mut self.health }
+
+    pub fn send_health(&self) {
+        let health = UpdateHealthSpec::new(self.health.health(), self.health.food(), self.health.saturation());
+        self.connection.send_packet(health, falcon_send::write_update_health);
+    }
+
     pub fn protocol_version(&self) -> i32 { self.protocol }
 
+    /// The latency of this player in milliseconds, averaged over the last
+    /// keep alives like the vanilla server does. Zero until the first keep
+    /// alive is answered.
+    pub fn ping(&self) -> u32 { self.ping }
+
+    /// Adds the round-trip time of a keep alive to the latency.
+    pub fn update_ping(&mut self, latency: Duration) {
+        let latency = latency.as_millis().min(u32::MAX as u128) as u32;
+        self.ping = if self.ping == 0 { latency } else { (self.ping * 3 + latency) / 4 };
+    }
+
+    /// The entry of this player in the tab list.
+    pub fn player_info(&self) -> PlayerInfoEntry {
+        PlayerInfoEntry::new(self.profile.clone(), self.gamemode, self.ping as i32)
+    }
+
+    /// Spawns this player for a client with another `protocol_version`.
+    pub fn spawn_spec(&self, protocol_version: i32) -> SpawnPlayerSpec {
+        SpawnPlayerSpec::new(self.eid, self.profile.uuid, self.position, self.facing, self.metadata.clone(), protocol_version)
+    }
+
+    /// How long ago the player last moved, chatted or used a command.
+    pub fn idle_time(&self) -> Duration { self.last_action.elapsed() }
+
+    /// Resets the idle time of the player.
+    pub fn mark_active(&mut self) { self.last_action = Instant::now(); }
+
     pub fn connection(&self) -> &ConnectionWrapper { &self.connection }
 }
 
 impl FalconPlayer {
-    pub fn disconnect(&mut self, reason: ChatComponent) {
+    /// Disconnects this player, the player is removed from the server once
+    /// the connection has closed.
+    pub fn kick(&self, reason: DisconnectReason) {
         self.connection.execute(move |connection| {
             connection.disconnect(reason);
             Ok::<(), Infallible>(())
         });
     }
 
+    /// Shows `message` in the chat of this player.
+    pub fn send_message(&self, message: impl Into<ChatComponent>) {
+        self.connection.send_packet(message.into().for_protocol(self.protocol), falcon_send::write_system_chat);
+    }
+
+    /// Marks an advancement as done, the client shows a toast when its
+    /// display asks for one. Returns `false` if it was done already.
+    pub fn grant_advancement(&mut self, id: &str) -> bool {
+        if self.advancements.contains_key(id) {
+            return false;
+        }
+        let time = unix_millis();
+        self.advancements.insert(id.to_owned(), time);
+        self.send_advancements(false, Vec::new(), Vec::new(), vec![(id.to_owned(), Some(time))]);
+        true
+    }
+
+    /// Returns `false` if the advancement was not done.
+    pub fn revoke_advancement(&mut self, id: &str) -> bool {
+        if self.advancements.remove(id).is_none() {
+            return false;
+        }
+        self.send_advancements(false, Vec::new(), Vec::new(), vec![(id.to_owned(), None)]);
+        true
+    }
+
+    /// Replaces the advancements of the client by `tab`, with the progress
+    /// of this player.
+    pub fn send_advancement_tab(&self, tab: &[Advancement]) {
+        let added = tab
+            .iter()
+            .cloned()
+            .map(|mut advancement| {
+                if let Some(display) = &mut advancement.display {
+                    display.title = display.title.clone().for_protocol(self.protocol);
+                    display.description = display.description.clone().for_protocol(self.protocol);
+                }
+                advancement
+            })
+            .collect();
+        let progress = tab.iter().map(|advancement| (advancement.id.clone(), self.advancements.get(&advancement.id).copied())).collect();
+        self.send_advancements(true, added, Vec::new(), progress);
+    }
+
+    /// Pops up a toast with `title` and the item `icon`, the advancements
+    /// of the player do not change.
+    pub fn show_toast(&self, title: impl Into<ChatComponent>, icon: &str, frame: AdvancementFrame) {
+        let toast = Advancement::toast(TOAST_ID, title.into().for_protocol(self.protocol), icon, frame);
+        self.send_advancements(false, vec![toast], Vec::new(), vec![(String::from(TOAST_ID), Some(unix_millis()))]);
+        self.send_advancements(false, Vec::new(), vec![String::from(TOAST_ID)], Vec::new());
+    }
+
+    fn send_advancements(&self, reset: bool, added: Vec<Advancement>, removed: Vec<String>, progress: Vec<(String, Option<i64>)>) {
+        let spec = AdvancementsSpec::new(reset, added, removed, progress, self.protocol);
+        self.connection.send_packet(spec, falcon_send::write_advancements);
+    }
+
+    /// Sends the settings of the recipe book, without recipes as none are
+    /// declared.
+    pub fn send_recipe_book(&self) {
+        let spec = UnlockRecipesSpec::new(UnlockRecipesAction::Init, self.recipe_book, Vec::new(), self.protocol);
+        self.connection.send_packet(spec, falcon_send::write_unlock_recipes);
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn send_keep_alive(&self) {
         let elapsed = self.time.elapsed().as_secs();
-        self.connection.execute(move |connection| -> Result<(), WriteError> {
-            connection.state_mut().last_keep_alive = elapsed;
-            connection.send_packet(elapsed as i64, falcon_send::write_keep_alive)?;
-            Ok(())
-        });
+        self.connection.execute(move |connection| connection.send_keep_alive(elapsed));
     }
 
-    pub fn join_spec(&self, difficulty: Difficulty, max_players: u8, level_type: String, seed: i64, reduced_debug: bool, enable_respawn: bool) -> JoinGameSpec {
+    pub fn join_spec(&self, difficulty: Difficulty, max_players: u8, level_type: String, hashed_seed: i64, reduced_debug: bool, enable_respawn: bool) -> JoinGameSpec {
         JoinGameSpec::new(
             self.eid,
             self.gamemode,
@@ -105,10 +293,12 @@ impl FalconPlayer {
             difficulty,
             max_players,
             level_type,
-            seed,
+            hashed_seed,
             self.view_distance as i32,
             reduced_debug,
             enable_respawn,
         )
     }
 }
+
+fn unix_millis() -> i64 { SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_millis() as i64).unwrap_or(0) }
@@ -0,0 +1,137 @@
+//! Sanity checks on the position updates of players, configured by the
+//! movement settings of the config.
+
+use falcon_core::player::data::Position;
+use falcon_core::server::config::MovementSettings;
+use falcon_core::world::chunks::ChunkPos;
+use uuid::Uuid;
+
+use crate::server::FalconServer;
+
+/// Why a position update was refused.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MovementViolation {
+    /// Moved further than the maximum distance of one update, in blocks.
+    TooFast(f64),
+    /// Moved into a chunk that is not loaded yet.
+    UnloadedChunk(ChunkPos),
+    /// Stayed in the air too long without being allowed to fly.
+    Flying,
+    /// Sent a coordinate or angle that is NaN or infinite.
+    NonFinite,
+}
+
+/// A position update that was refused, the player is sent back to `from`.
+#[derive(Clone, Debug)]
+pub struct MovementEvent {
+    pub player: Uuid,
+    pub violation: MovementViolation,
+    pub from: Position,
+    pub to: Position,
+}
+
+/// Sees every refused position update, e.g. to kick players that keep
+/// failing the checks.
+pub type MovementListener = Box<dyn FnMut(&mut FalconServer, &MovementEvent) + Send>;
+
+/// What the checks remember of a player between two updates.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MovementState {
+    /// Updates in the air without falling.
+    air_updates: u32,
+}
+
+impl MovementState {
+    /// Checks a move from `from` to `to`, `is_loaded` tells whether a chunk
+    /// is loaded.
+    pub fn check<F>(&mut self, settings: &MovementSettings, from: &Position, to: &Position, on_ground: bool, may_fly: bool, is_loaded: F) -> Result<(), MovementViolation>
+    where
+        F: Fn(ChunkPos) -> bool,
+    {
+        // NaN passes every comparison below and would be stored as position
+        if !(to.x.is_finite() && to.y.is_finite() && to.z.is_finite()) {
+            return Err(MovementViolation::NonFinite);
+        }
+        let distance = ((to.x - from.x).powi(2) + (to.y - from.y).powi(2) + (to.z - from.z).powi(2)).sqrt();
+        if settings.max_distance > 0.0 && distance > settings.max_distance {
+            return Err(MovementViolation::TooFast(distance));
+        }
+        let chunk = ChunkPos::from(to.chunk_coords());
+        if settings.check_unloaded_chunks && to.chunk_coords() != from.chunk_coords() && !is_loaded(chunk) {
+            return Err(MovementViolation::UnloadedChunk(chunk));
+        }
+        if on_ground || may_fly || to.y < from.y {
+            self.air_updates = 0;
+        } else {
+            self.air_updates += 1;
+            if settings.max_air_updates > 0 && self.air_updates > settings.max_air_updates {
+                self.air_updates = 0;
+                return Err(MovementViolation::Flying);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the angles of a look update.
+    pub fn check_look(yaw: f32, pitch: f32) -> Result<(), MovementViolation> {
+        match yaw.is_finite() && pitch.is_finite() {
+            true => Ok(()),
+            false => Err(MovementViolation::NonFinite),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use falcon_core::player::data::Position;
+    use falcon_core::server::config::MovementSettings;
+    use falcon_core::world::chunks::ChunkPos;
+
+    use super::{MovementState, MovementViolation};
+
+    #[test]
+    fn test_movement_checks() {
+        let settings = MovementSettings {
+            max_distance: 10.0,
+            check_unloaded_chunks: true,
+            max_air_updates: 3,
+        };
+        let mut state = MovementState::default();
+        let from = Position::new(8.0, 64.0, 8.0);
+        let loaded = |pos: ChunkPos| pos.x == 0;
+
+        assert_eq!(state.check(&settings, &from, &Position::new(8.0, 64.0, 18.0), true, false, loaded), Ok(()));
+        assert_eq!(state.check(&settings, &from, &Position::new(8.0, 64.0, 17.0), true, false, |_| false), Err(MovementViolation::UnloadedChunk(ChunkPos::new(0, 1))));
+        assert!(matches!(state.check(&settings, &from, &Position::new(20.0, 64.0, 8.0), true, false, loaded), Err(MovementViolation::TooFast(_))));
+
+        for _ in 0..3 {
+            assert_eq!(state.check(&settings, &from, &from, false, false, loaded), Ok(()));
+        }
+        assert_eq!(state.check(&settings, &from, &from, false, false, loaded), Err(MovementViolation::Flying));
+        for _ in 0..4 {
+            assert_eq!(state.check(&settings, &from, &from, false, true, loaded), Ok(()));
+        }
+        // falling is not flying
+        for _ in 0..4 {
+            assert_eq!(state.check(&settings, &from, &Position::new(8.0, 63.0, 8.0), false, false, loaded), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_non_finite_moves() {
+        let mut state = MovementState::default();
+        let from = Position::new(8.0, 64.0, 8.0);
+        // also refused with every check turned off
+        let settings = MovementSettings {
+            max_distance: 0.0,
+            check_unloaded_chunks: false,
+            max_air_updates: 0,
+        };
+        for to in [Position::new(f64::NAN, 64.0, 8.0), Position::new(8.0, f64::INFINITY, 8.0), Position::new(8.0, 64.0, f64::NEG_INFINITY)] {
+            assert_eq!(state.check(&settings, &from, &to, true, false, |_| true), Err(MovementViolation::NonFinite));
+        }
+        assert_eq!(MovementState::check_look(f32::NAN, 0.0), Err(MovementViolation::NonFinite));
+        assert_eq!(MovementState::check_look(90.0, f32::INFINITY), Err(MovementViolation::NonFinite));
+        assert_eq!(MovementState::check_look(90.0, -45.0), Ok(()));
+    }
+}
@@ -0,0 +1,226 @@
+//! Lookup of the Mojang profile behind a username, this lets players on an
+//! offline mode server see each other's skins.
+//!
+//! Lookups are cached and spaced out to stay within the rate limits of the
+//! Mojang API, a lookup that would have to wait too long is given up. The
+//! urls can point to another Yggdrasil server, see [`ProfileSettings`].
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use ahash::AHashMap;
+use falcon_core::player::profile::{is_valid_username, ProfileProperty};
+use falcon_core::server::config::{FalconConfig, ProfileSettings};
+use once_cell::sync::Lazy;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// The time between two lookups, Mojang allows about 600 requests per 10
+/// minutes per endpoint.
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+/// Lookups that would have to wait longer than this are given up.
+const MAX_WAIT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// The wait before the first retry of a failed lookup, doubled for every
+/// retry after.
+const RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+static INSTANCE: Lazy<ProfileResolver> = Lazy::new(ProfileResolver::from_config);
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("Profile request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Too many profile lookups are waiting")]
+    RateLimited,
+}
+
+impl ProfileError {
+    /// Whether the lookup may succeed when tried again, the server could
+    /// not be reached or had an error of its own.
+    fn is_transient(&self) -> bool {
+        match self {
+            ProfileError::Request(error) => {
+                let overloaded = |status: StatusCode| status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS;
+                error.is_timeout() || error.is_connect() || error.status().is_some_and(overloaded)
+            },
+            ProfileError::RateLimited => false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ProfileId {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct SessionProfile {
+    #[serde(default)]
+    properties: Vec<SessionProperty>,
+}
+
+#[derive(Deserialize)]
+struct SessionProperty {
+    name: String,
+    value: String,
+    signature: Option<String>,
+}
+
+/// When a profile was looked up and its properties, `None` when no account
+/// has the name.
+type CachedProfile = (Instant, Option<Vec<ProfileProperty>>);
+
+/// Resolves usernames to the properties of their Mojang profile.
+#[derive(Debug)]
+pub struct ProfileResolver {
+    client: Client,
+    profile_url: String,
+    session_url: String,
+    cache_ttl: Duration,
+    retries: u32,
+    cache: Mutex<AHashMap<String, CachedProfile>>,
+    next_request: Mutex<Instant>,
+}
+
+impl Default for ProfileResolver {
+    fn default() -> Self { Self::new() }
+}
+
+impl ProfileResolver {
+    /// A resolver that looks up profiles at Mojang.
+    pub fn new() -> Self { ProfileResolver::from_settings(&ProfileSettings::default()) }
+
+    pub fn from_config() -> Self { ProfileResolver::from_settings(&FalconConfig::global().profiles) }
+
+    pub fn from_settings(settings: &ProfileSettings) -> Self {
+        ProfileResolver {
+            client: Client::builder().timeout(REQUEST_TIMEOUT).build().unwrap_or_default(),
+            profile_url: settings.api_url.clone(),
+            session_url: settings.session_url.clone(),
+            cache_ttl: settings.cache_ttl(),
+            retries: settings.retries,
+            cache: Mutex::new(AHashMap::new()),
+            next_request: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn global() -> &'static ProfileResolver { &INSTANCE }
+
+    /// The profile properties of `username`, empty when no account has
+    /// this name.
+    pub async fn properties(&self, username: &str) -> Result<Vec<ProfileProperty>, ProfileError> {
+        Ok(self.profile(username).await?.unwrap_or_default())
+    }
+
+    /// The profile properties of `username`, `None` when no account has
+    /// this name.
+    pub async fn profile(&self, username: &str) -> Result<Option<Vec<ProfileProperty>>, ProfileError> {
+        let key = username.to_lowercase();
+        if let Some(properties) = self.cached(&key) {
+            return Ok(properties);
+        }
+        let mut backoff = RETRY_BACKOFF;
+        let mut retries = self.retries;
+        let properties = loop {
+            self.wait_for_slot().await?;
+            match self.lookup(username).await.map_err(ProfileError::from) {
+                Err(error) if retries > 0 && error.is_transient() => {
+                    debug!(%error, %username, "Profile lookup failed, trying again");
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    retries -= 1;
+                },
+                result => break result?,
+            }
+        };
+        self.cache.lock().unwrap().insert(key, (Instant::now(), properties.clone()));
+        Ok(properties)
+    }
+
+    fn cached(&self, key: &str) -> Option<Option<Vec<ProfileProperty>>> {
+        let mut cache = self.cache.lock().unwrap();
+        match cache.get(key) {
+            Some((time, properties)) if time.elapsed() < self.cache_ttl => Some(properties.clone()),
+            Some(_) => {
+                cache.remove(key);
+                None
+            },
+            None => None,
+        }
+    }
+
+    /// Reserves the next free request slot and waits for it.
+    async fn wait_for_slot(&self) -> Result<(), ProfileError> {
+        let slot = {
+            let mut next_request = self.next_request.lock().unwrap();
+            let now = Instant::now();
+            let slot = (*next_request).max(now);
+            if slot - now > MAX_WAIT {
+                return Err(ProfileError::RateLimited);
+            }
+            *next_request = slot + REQUEST_INTERVAL;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+        Ok(())
+    }
+
+    async fn lookup(&self, username: &str) -> Result<Option<Vec<ProfileProperty>>, reqwest::Error> {
+        // the name becomes part of the url, no account can have another name
+        if !is_valid_username(username) {
+            return Ok(None);
+        }
+        let response = self.client.get(format!("{}{}", self.profile_url, username)).send().await?;
+        if matches!(response.status(), StatusCode::NO_CONTENT | StatusCode::NOT_FOUND) {
+            return Ok(None);
+        }
+        let profile: ProfileId = response.error_for_status()?.json().await?;
+        let session: SessionProfile = self
+            .client
+            .get(format!("{}{}?unsigned=false", self.session_url, profile.id))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(Some(
+            session
+                .properties
+                .into_iter()
+                .map(|property| ProfileProperty {
+                    name: property.name,
+                    value: property.value,
+                    signature: property.signature,
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_and_rate_limit() {
+        let resolver = ProfileResolver::new();
+        let textures = vec![ProfileProperty {
+            name: String::from("textures"),
+            value: String::from("e30="),
+            signature: None,
+        }];
+        resolver.cache.lock().unwrap().insert(String::from("notch"), (Instant::now(), Some(textures.clone())));
+        resolver.cache.lock().unwrap().insert(String::from("nobody"), (Instant::now(), None));
+        assert_eq!(resolver.properties("Notch").await.unwrap(), textures);
+        assert_eq!(resolver.profile("Nobody").await.unwrap(), None);
+        assert_eq!(resolver.properties("Nobody").await.unwrap(), Vec::new());
+        assert_eq!(resolver.profile("../Notch").await.unwrap(), None);
+
+        *resolver.next_request.lock().unwrap() = Instant::now() + MAX_WAIT * 2;
+        assert!(matches!(resolver.properties("jeb_").await, Err(ProfileError::RateLimited)));
+    }
+}
@@ -0,0 +1,22 @@
+//! Players that fall below the world, handled as the void settings of the
+//! config say.
+
+use falcon_core::player::data::Position;
+use falcon_core::server::config::VoidAction;
+use uuid::Uuid;
+
+use crate::server::FalconServer;
+
+/// A player moved below the void level.
+#[derive(Clone, Debug)]
+pub struct VoidEvent {
+    pub player: Uuid,
+    pub position: Position,
+    /// What happens to the player once every listener ran, starts as the
+    /// action of the config.
+    pub action: VoidAction,
+}
+
+/// Sees every position update below the void level, the ones of players
+/// that keep falling included.
+pub type VoidListener = Box<dyn FnMut(&mut FalconServer, &mut VoidEvent) + Send>;
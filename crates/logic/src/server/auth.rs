@@ -0,0 +1,56 @@
+//! Authentication of logins by a plugin, e.g. to link web accounts, gate
+//! players behind 2FA or ask for a password on an offline mode server.
+
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+
+use falcon_core::network::VirtualHost;
+use falcon_core::player::profile::GameProfile;
+
+use crate::connection::floodgate::BedrockData;
+use crate::connection::DisconnectReason;
+
+/// The connection a profile logs in with.
+#[derive(Clone, Debug)]
+pub struct LoginInfo {
+    pub address: IpAddr,
+    /// `None` when the client did not send a handshake this server
+    /// understood.
+    pub host: Option<VirtualHost>,
+    pub protocol: i32,
+    /// The Bedrock player behind the connection, see
+    /// [`Floodgate`](crate::connection::floodgate::Floodgate).
+    pub bedrock: Option<BedrockData>,
+}
+
+/// Whether a profile may log in.
+pub enum AuthResult {
+    /// The player joins with this profile, which may differ from the one
+    /// that was authenticated, e.g. in uuid or skin.
+    Allow(GameProfile),
+    /// The player may not join.
+    Deny(DisconnectReason),
+    /// The decision needs time, e.g. a web request. The future runs on the
+    /// connection so the server is not held up.
+    Defer(Pin<Box<dyn Future<Output = AuthResult> + Send>>),
+}
+
+/// Decides whether a player may join, see [`FalconServer::with_auth`].
+///
+/// Providers run once the username and uuid of the player are known and
+/// before bans, the whitelist and the router are consulted, so those apply
+/// to the profile the provider allowed.
+///
+/// [`FalconServer::with_auth`]: crate::FalconServer::with_auth
+pub trait AuthProvider: Send + Sync {
+    fn authenticate(&self, profile: GameProfile, login: &LoginInfo) -> AuthResult;
+}
+
+/// Lets every player join with its own profile.
+#[derive(Debug, Default)]
+pub struct AllowAll;
+
+impl AuthProvider for AllowAll {
+    fn authenticate(&self, profile: GameProfile, _login: &LoginInfo) -> AuthResult { AuthResult::Allow(profile) }
+}
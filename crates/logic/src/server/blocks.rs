@@ -0,0 +1,25 @@
+use falcon_core::world::blocks::Blocks;
+use falcon_core::world::BlockPosition;
+use falcon_send::specs::play::BlockChangeSpec;
+
+use crate::server::FalconServer;
+
+impl FalconServer {
+    /// Places a block and sends it to the players that have its chunk in
+    /// view, every player receives the id of their own version. Returns
+    /// the block that was there, `None` if the position is outside of the
+    /// world.
+    pub fn set_block(&mut self, position: BlockPosition, block: Blocks) -> Option<Blocks> {
+        let old = self.world.set_block(position, block)?;
+        let (chunk_x, chunk_z) = (position.get_x().div_euclid(16), position.get_z().div_euclid(16));
+        for player in self.players.values() {
+            let (x, z) = player.position().chunk_coords();
+            let view_distance = player.view_distance() as u32;
+            if x.abs_diff(chunk_x) <= view_distance && z.abs_diff(chunk_z) <= view_distance {
+                let spec = BlockChangeSpec::new(position, &block, player.protocol_version());
+                player.connection().send_packet(spec, falcon_send::write_block_change);
+            }
+        }
+        Some(old)
+    }
+}
@@ -0,0 +1,335 @@
+//! The way of a chat message from a player to everyone else: the mute
+//! list, the filters, the listeners and finally the format.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use ahash::{AHashMap, AHashSet};
+use falcon_core::chat::{escape_markup, ChatComponent};
+use falcon_core::server::config::FalconConfig;
+use falcon_core::server::lang::MessageCatalog;
+use regex::{Regex, RegexBuilder};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::server::command::CommandSender;
+
+/// The name of the world, the file name of the world file without its
+/// extension.
+pub fn world_name() -> String {
+    FalconConfig::global()
+        .world_file()
+        .and_then(|file| Path::new(file).file_stem())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| String::from("world"))
+}
+
+/// Who receives a message, see [`FalconServer::send_message`].
+///
+/// [`FalconServer::send_message`]: crate::FalconServer::send_message
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MessageTarget {
+    Player(Uuid),
+    Players(Vec<Uuid>),
+    /// The server log.
+    Console,
+    /// Every player and the console.
+    Broadcast,
+    /// The players with at least this operator level.
+    Ops(u8),
+    /// The players with this permission node.
+    Permission(String),
+    /// The players in the world with this name, see [`world_name`].
+    World(String),
+}
+
+impl MessageTarget {
+    /// Whether the message is written to the server log.
+    pub fn includes_console(&self) -> bool { matches!(self, MessageTarget::Console | MessageTarget::Broadcast) }
+}
+
+impl From<CommandSender> for MessageTarget {
+    fn from(sender: CommandSender) -> Self {
+        match sender {
+            CommandSender::Console => MessageTarget::Console,
+            CommandSender::Player(uuid) => MessageTarget::Player(uuid),
+        }
+    }
+}
+
+/// A chat message that is about to be broadcast, listeners may change it
+/// or cancel it.
+#[derive(Debug)]
+pub struct ChatEvent {
+    pub sender: Uuid,
+    pub username: String,
+    pub locale: String,
+    pub message: String,
+    /// Markup shown in front of the name, see [`ChatSettings::format`].
+    ///
+    /// [`ChatSettings::format`]: falcon_core::server::config::ChatSettings::format
+    pub prefix: String,
+    pub world: String,
+    /// The format of this message, the one of the config by default.
+    pub format: String,
+    cancelled: bool,
+}
+
+impl ChatEvent {
+    pub fn new(sender: Uuid, username: String, locale: String, message: String) -> Self {
+        ChatEvent {
+            sender,
+            username,
+            locale,
+            message,
+            prefix: String::new(),
+            world: world_name(),
+            format: FalconConfig::global().chat.format.clone(),
+            cancelled: false,
+        }
+    }
+
+    /// Drops the message without telling the sender.
+    pub fn cancel(&mut self) { self.cancelled = true; }
+
+    pub fn is_cancelled(&self) -> bool { self.cancelled }
+
+    /// Fills in the format, the message and the name are shown as is.
+    pub fn to_component(&self) -> ChatComponent {
+        let text = self
+            .format
+            .replace("{prefix}", &self.prefix)
+            .replace("{world}", &escape_markup(&self.world))
+            .replace("{name}", &escape_markup(&self.username))
+            .replace("{message}", &escape_markup(&self.message));
+        ChatComponent::from_markup(&text)
+    }
+}
+
+/// Whether a filter lets a message through.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FilterResult {
+    Allow,
+    /// Blocks the message, the sender is told why.
+    Deny(String),
+}
+
+/// Checks or changes chat messages before the listeners see them.
+pub trait ChatFilter: Send {
+    fn filter(&mut self, event: &mut ChatEvent, now: Instant) -> FilterResult;
+}
+
+/// Sees every chat message that passed the filters, it may rewrite or
+/// cancel it.
+pub type ChatListener = Box<dyn FnMut(&mut ChatEvent) + Send>;
+
+/// Replaces words that match a regular expression by `*`.
+#[derive(Debug, Default)]
+pub struct WordFilter {
+    patterns: Vec<Regex>,
+}
+
+impl WordFilter {
+    /// Patterns that are not valid are skipped with a warning.
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        let patterns = patterns
+            .iter()
+            .filter_map(|pattern| {
+                RegexBuilder::new(pattern.as_ref())
+                    .case_insensitive(true)
+                    .build()
+                    .inspect_err(|error| warn!(pattern = pattern.as_ref(), %error, "Invalid blocked word"))
+                    .ok()
+            })
+            .collect();
+        WordFilter { patterns }
+    }
+}
+
+impl ChatFilter for WordFilter {
+    fn filter(&mut self, event: &mut ChatEvent, _now: Instant) -> FilterResult {
+        for pattern in &self.patterns {
+            if pattern.is_match(&event.message) {
+                event.message = pattern.replace_all(&event.message, |captures: &regex::Captures| "*".repeat(captures[0].chars().count())).into_owned();
+            }
+        }
+        FilterResult::Allow
+    }
+}
+
+/// Blocks messages sent too soon after the previous message of a player.
+#[derive(Debug)]
+pub struct SpamCooldown {
+    cooldown: Duration,
+    last: AHashMap<Uuid, Instant>,
+}
+
+impl SpamCooldown {
+    pub fn new(cooldown: Duration) -> Self {
+        SpamCooldown {
+            cooldown,
+            last: AHashMap::new(),
+        }
+    }
+}
+
+impl ChatFilter for SpamCooldown {
+    fn filter(&mut self, event: &mut ChatEvent, now: Instant) -> FilterResult {
+        match self.last.get(&event.sender) {
+            Some(last) if now.saturating_duration_since(*last) < self.cooldown => {
+                FilterResult::Deny(MessageCatalog::global().message(&event.locale, "chat.cooldown", &[]))
+            },
+            _ => {
+                self.last.insert(event.sender, now);
+                FilterResult::Allow
+            },
+        }
+    }
+}
+
+/// Runs chat messages through the mute list, the filters and the
+/// listeners, in that order.
+pub struct ChatPipeline {
+    muted: AHashSet<Uuid>,
+    filters: Vec<Box<dyn ChatFilter>>,
+    listeners: Vec<ChatListener>,
+    /// Who `/reply` answers, the last one a sender talked with privately.
+    reply_targets: AHashMap<CommandSender, CommandSender>,
+}
+
+impl ChatPipeline {
+    /// No filters or listeners.
+    pub fn new() -> Self {
+        ChatPipeline {
+            muted: AHashSet::new(),
+            filters: Vec::new(),
+            listeners: Vec::new(),
+            reply_targets: AHashMap::new(),
+        }
+    }
+
+    /// The word filter and cooldown of the config.
+    pub fn from_config() -> Self {
+        let settings = &FalconConfig::global().chat;
+        let mut pipeline = ChatPipeline::new();
+        if !settings.blocked_words.is_empty() {
+            pipeline = pipeline.with_filter(Box::new(WordFilter::new(&settings.blocked_words)));
+        }
+        if settings.cooldown > 0 {
+            pipeline = pipeline.with_filter(Box::new(SpamCooldown::new(Duration::from_millis(settings.cooldown))));
+        }
+        pipeline
+    }
+
+    pub fn with_filter(mut self, filter: Box<dyn ChatFilter>) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    pub fn with_listener(mut self, listener: ChatListener) -> Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    /// Mutes a player, returns `false` if the player was muted already.
+    pub fn mute(&mut self, uuid: Uuid) -> bool { self.muted.insert(uuid) }
+
+    /// Returns `false` if the player was not muted.
+    pub fn unmute(&mut self, uuid: Uuid) -> bool { self.muted.remove(&uuid) }
+
+    pub fn is_muted(&self, uuid: Uuid) -> bool { self.muted.contains(&uuid) }
+
+    /// Runs the mute list and the filters only, e.g. for private messages.
+    /// Returns why the sender may not send the message.
+    pub fn check(&mut self, event: &mut ChatEvent, now: Instant) -> Result<(), String> {
+        if self.is_muted(event.sender) {
+            return Err(MessageCatalog::global().message(&event.locale, "chat.muted", &[]));
+        }
+        for filter in &mut self.filters {
+            if let FilterResult::Deny(reason) = filter.filter(event, now) {
+                return Err(reason);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the message to broadcast, `None` if a listener cancelled it,
+    /// or why the sender may not send it.
+    pub fn process(&mut self, mut event: ChatEvent, now: Instant) -> Result<Option<ChatComponent>, String> {
+        self.check(&mut event, now)?;
+        for listener in &mut self.listeners {
+            listener(&mut event);
+        }
+        Ok((!event.is_cancelled()).then(|| event.to_component()))
+    }
+
+    /// Remembers a private message so both sides can `/reply`.
+    pub fn set_conversation(&mut self, sender: CommandSender, receiver: CommandSender) {
+        self.reply_targets.insert(sender, receiver);
+        self.reply_targets.insert(receiver, sender);
+    }
+
+    /// Who `sender` talked with privately last.
+    pub fn reply_target(&self, sender: CommandSender) -> Option<CommandSender> { self.reply_targets.get(&sender).copied() }
+
+    /// Forgets the conversations of a player that left.
+    pub fn forget(&mut self, sender: CommandSender) {
+        self.reply_targets.remove(&sender);
+        self.reply_targets.retain(|_, receiver| *receiver != sender);
+    }
+}
+
+impl Default for ChatPipeline {
+    fn default() -> Self { ChatPipeline::new() }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use falcon_core::server::config::FalconConfig;
+    use uuid::Uuid;
+
+    use super::{ChatEvent, ChatPipeline, SpamCooldown, WordFilter};
+    use crate::server::command::CommandSender;
+
+    fn event(message: &str) -> ChatEvent { ChatEvent::new(Uuid::nil(), String::from("Steve"), String::from("en_us"), String::from(message)) }
+
+    #[test]
+    fn test_pipeline() {
+        FalconConfig::init_default();
+        let mut pipeline = ChatPipeline::new()
+            .with_filter(Box::new(WordFilter::new(&["dar+n"])))
+            .with_filter(Box::new(SpamCooldown::new(Duration::from_secs(1))))
+            .with_listener(Box::new(|event| {
+                event.prefix = String::from("<red>[OP]</red> ");
+                if event.message == "cancel" {
+                    event.cancel();
+                }
+            }));
+        let start = Instant::now();
+
+        let message = pipeline.process(event("<b>DARRN</b> it"), start).unwrap().unwrap();
+        assert_eq!(message.to_plain(), "[OP] <Steve> <b>*****</b> it");
+        assert!(pipeline.process(event("again"), start + Duration::from_millis(500)).is_err());
+        assert_eq!(pipeline.process(event("cancel"), start + Duration::from_secs(2)), Ok(None));
+
+        pipeline.mute(Uuid::nil());
+        assert!(pipeline.process(event("muted"), start + Duration::from_secs(4)).is_err());
+    }
+
+    #[test]
+    fn test_reply_targets() {
+        let mut pipeline = ChatPipeline::new();
+        let (alex, steve) = (CommandSender::Player(Uuid::from_u128(1)), CommandSender::Player(Uuid::from_u128(2)));
+        pipeline.set_conversation(alex, steve);
+        pipeline.set_conversation(CommandSender::Console, alex);
+        assert_eq!(pipeline.reply_target(alex), Some(CommandSender::Console));
+        assert_eq!(pipeline.reply_target(steve), Some(alex));
+
+        pipeline.forget(alex);
+        assert_eq!(pipeline.reply_target(alex), None);
+        assert_eq!(pipeline.reply_target(steve), None);
+        assert_eq!(pipeline.reply_target(CommandSender::Console), None);
+    }
+}
@@ -0,0 +1,330 @@
+//! The commands every server has.
+
+use std::net::IpAddr;
+use std::time::Instant;
+
+use falcon_core::chat::{ChatColor, ChatComponent};
+use falcon_core::player::data::{GameMode, Position};
+use falcon_core::server::config::FalconConfig;
+use falcon_core::server::lang::MessageCatalog;
+use falcon_core::server::lists::{BanEntry, IpBanEntry, WhitelistEntry};
+use falcon_send::specs::play::CommandParser;
+use tracing::info;
+use uuid::Uuid;
+
+use super::{Command, CommandContext, CommandError, CommandNode, CommandRegistry, CommandResult, CommandSender, Executor};
+use crate::connection::DisconnectReason;
+use crate::server::chat::ChatEvent;
+use crate::FalconServer;
+
+pub(super) fn register(registry: &mut CommandRegistry) {
+    registry.register(Command::new(4, CommandNode::literal("stop").executes(stop)));
+    registry.register(Command::new(0, CommandNode::literal("list").executes(list)));
+    registry.register(Command::new(2, CommandNode::literal("tps").executes(tps)));
+    registry.register(Command::new(
+        3,
+        CommandNode::literal("kick").then(
+            CommandNode::argument("player", CommandParser::Player)
+                .executes(kick)
+                .then(CommandNode::argument("reason", CommandParser::Greedy).executes(kick)),
+        ),
+    ));
+    registry.register(Command::new(2, CommandNode::literal("say").then(CommandNode::argument("message", CommandParser::Greedy).executes(say))));
+    for name in ["msg", "tell", "w"] {
+        registry.register(Command::new(
+            0,
+            CommandNode::literal(name)
+                .then(CommandNode::argument("player", CommandParser::Player).then(CommandNode::argument("message", CommandParser::Greedy).executes(message))),
+        ));
+    }
+    for name in ["reply", "r"] {
+        registry.register(Command::new(0, CommandNode::literal(name).then(CommandNode::argument("message", CommandParser::Greedy).executes(reply))));
+    }
+    registry.register(Command::new(
+        2,
+        CommandNode::literal("tp").then(position(teleport)).then(
+            CommandNode::argument("target", CommandParser::Player)
+                .executes(teleport)
+                .then(position(teleport))
+                .then(CommandNode::argument("destination", CommandParser::Player).executes(teleport)),
+        ),
+    ));
+    registry.register(Command::new(0, CommandNode::literal("spawn").executes(spawn)));
+    let mut gamemode = CommandNode::literal("gamemode");
+    for game_mode in [GameMode::Survival, GameMode::Creative, GameMode::Adventure, GameMode::Spectator] {
+        gamemode = gamemode.then(
+            CommandNode::literal(game_mode.name())
+                .executes(set_game_mode)
+                .then(CommandNode::argument("player", CommandParser::Player).executes(set_game_mode)),
+        );
+    }
+    registry.register(Command::new(2, gamemode));
+    registry.register(Command::new(
+        3,
+        CommandNode::literal("whitelist")
+            .then(CommandNode::literal("on").executes(whitelist_toggle))
+            .then(CommandNode::literal("off").executes(whitelist_toggle))
+            .then(CommandNode::literal("list").executes(whitelist_list))
+            .then(CommandNode::literal("add").then(profile().executes(whitelist_add)))
+            .then(CommandNode::literal("remove").then(profile().executes(whitelist_remove)))
+            .then(CommandNode::literal("reload").executes(whitelist_reload)),
+    ));
+    registry.register(Command::new(
+        3,
+        CommandNode::literal("ban").then(
+            profile()
+                .executes(ban)
+                .then(CommandNode::argument("reason", CommandParser::Greedy).executes(ban)),
+        ),
+    ));
+    registry.register(Command::new(3, CommandNode::literal("pardon").then(profile().executes(pardon))));
+    registry.register(Command::new(
+        3,
+        CommandNode::literal("ban-ip").then(
+            CommandNode::argument("address", CommandParser::Word)
+                .executes(ban_ip)
+                .then(CommandNode::argument("reason", CommandParser::Greedy).executes(ban_ip)),
+        ),
+    ));
+    registry.register(Command::new(3, CommandNode::literal("pardon-ip").then(CommandNode::argument("address", CommandParser::Word).executes(pardon_ip))));
+    registry.register(Command::new(3, CommandNode::literal("op").then(profile().executes(op))));
+    registry.register(Command::new(3, CommandNode::literal("deop").then(profile().executes(op))));
+}
+
+/// `<x> <y> <z>`, complete after the last coordinate.
+fn position(executor: Executor) -> CommandNode {
+    CommandNode::argument("x", CommandParser::Double)
+        .then(CommandNode::argument("y", CommandParser::Double).then(CommandNode::argument("z", CommandParser::Double).executes(executor)))
+}
+
+fn profile() -> CommandNode { CommandNode::argument("player", CommandParser::GameProfile) }
+
+/// The uuid of the online player called `name`.
+fn online_player(server: &FalconServer, name: &str) -> Result<Uuid, CommandError> {
+    server.usernames.get(name).copied().ok_or_else(|| CommandError::PlayerNotFound(name.to_owned()))
+}
+
+/// The uuid of the player called `name`, who does not have to be online.
+fn any_player(server: &FalconServer, name: &str) -> Uuid { server.usernames.get(name).copied().unwrap_or_else(|| FalconServer::offline_uuid(name)) }
+
+fn sender_player(context: &CommandContext) -> Result<Uuid, CommandError> { context.sender.uuid().ok_or(CommandError::PlayerOnly) }
+
+fn sender_name(server: &FalconServer, sender: CommandSender) -> String {
+    sender
+        .uuid()
+        .and_then(|uuid| server.players.get(&uuid))
+        .map(|player| player.username().to_owned())
+        .unwrap_or_else(|| String::from("Server"))
+}
+
+fn player_name(server: &FalconServer, uuid: Uuid) -> &str { server.players.get(&uuid).map(|player| player.username()).unwrap_or_default() }
+
+fn stop(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    info!("Shutting down server! (Stop command executed)");
+    server.should_stop = true;
+    server.shutdown_handle().send_shutdown();
+    Ok(Some(context.message("commands.stop.success", &[])))
+}
+
+fn list(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let mut names: Vec<&str> = server.players.values().map(|player| player.username()).collect();
+    names.sort_unstable();
+    Ok(Some(context.message("commands.list.players", &[&names.len(), &names.join(", ")])))
+}
+
+fn tps(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let report = server.tick_report();
+    let [tps, mean, p50, p95, p99, max] = [report.tps, report.mspt_mean, report.mspt_p50, report.mspt_p95, report.mspt_p99, report.mspt_max].map(|value| format!("{:.2}", value));
+    Ok(Some(context.message("commands.tps.report", &[&tps, &mean, &p50, &p95, &p99, &max])))
+}
+
+fn kick(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let name = context.string("player").unwrap_or_default();
+    let uuid = online_player(server, name)?;
+    let reason = context.string("reason").map(str::to_owned);
+    let feedback = match &reason {
+        Some(reason) => context.message("commands.kick.success_reason", &[&name, reason]),
+        None => context.message("commands.kick.success", &[&name]),
+    };
+    if let Some(player) = server.players.get(&uuid) {
+        player.kick(DisconnectReason::Kicked(reason));
+    }
+    Ok(Some(feedback))
+}
+
+fn say(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let sender = sender_name(server, context.sender);
+    let message = context.string("message").unwrap_or_default();
+    server.broadcast_message(|locale| MessageCatalog::global().message(locale, "commands.say.format", &[&sender, &message]));
+    Ok(None)
+}
+
+fn message(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let receiver = online_player(server, context.string("player").unwrap_or_default())?;
+    whisper(server, context, CommandSender::Player(receiver))
+}
+
+fn reply(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let receiver = server.chat.reply_target(context.sender).ok_or(CommandError::NoReplyTarget)?;
+    whisper(server, context, receiver)
+}
+
+/// Sends the `message` argument to `receiver` only, players go through the
+/// mute list and the chat filters first.
+fn whisper(server: &mut FalconServer, context: &CommandContext, receiver: CommandSender) -> CommandResult {
+    let sender = sender_name(server, context.sender);
+    let mut message = context.string("message").unwrap_or_default().to_owned();
+    if let Some(uuid) = context.sender.uuid() {
+        let mut event = ChatEvent::new(uuid, sender.clone(), context.locale.clone(), message);
+        server.chat.check(&mut event, Instant::now()).map_err(CommandError::Denied)?;
+        message = event.message;
+    }
+    let receiver_name = sender_name(server, receiver);
+    let catalog = MessageCatalog::global();
+    let whisper = |text: String| ChatComponent::text(text).color(ChatColor::Gray).italic();
+    server.send_message(&receiver.into(), |locale| whisper(catalog.message(locale, "commands.msg.incoming", &[&sender, &message])));
+    server.send_message(&context.sender.into(), |locale| whisper(catalog.message(locale, "commands.msg.outgoing", &[&receiver_name, &message])));
+    server.chat.set_conversation(context.sender, receiver);
+    Ok(None)
+}
+
+fn teleport(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let position = match (context.double("x"), context.double("y"), context.double("z")) {
+        (Some(x), Some(y), Some(z)) => Some(Position::new(x, y, z)),
+        _ => None,
+    };
+    // `tp <target>` moves the sender to the target, otherwise the target moves
+    let (player, destination) = match (context.string("target"), context.string("destination")) {
+        (Some(target), Some(destination)) => (online_player(server, target)?, Some(online_player(server, destination)?)),
+        (Some(target), None) if position.is_none() => (sender_player(context)?, Some(online_player(server, target)?)),
+        (Some(target), None) => (online_player(server, target)?, None),
+        (None, _) => (sender_player(context)?, None),
+    };
+    let (position, destination) = match (destination.and_then(|uuid| server.players.get(&uuid)), position) {
+        (Some(other), _) => (*other.position(), other.username().to_owned()),
+        (None, Some(position)) => (position, format!("{:.2}, {:.2}, {:.2}", position.x, position.y, position.z)),
+        (None, None) => return Err(CommandError::Syntax(String::from("tp"))),
+    };
+    server.teleport(player, position);
+    Ok(Some(context.message("commands.tp.success", &[&player_name(server, player), &destination])))
+}
+
+fn spawn(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let uuid = sender_player(context)?;
+    let (position, look) = server.world_spawn();
+    let position = server.world.safe_spawn(position);
+    if let Some(player) = server.players.get_mut(&uuid) {
+        *player.look_angles_mut() = look;
+    }
+    server.teleport(uuid, position);
+    Ok(Some(context.message("commands.spawn.success", &[])))
+}
+
+fn set_game_mode(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let game_mode = context.literal(1).and_then(GameMode::from_name).unwrap_or(GameMode::Survival);
+    let uuid = match context.string("player") {
+        Some(name) => online_player(server, name)?,
+        None => sender_player(context)?,
+    };
+    server.set_game_mode(uuid, game_mode);
+    Ok(Some(context.message("commands.gamemode.success", &[&player_name(server, uuid), &game_mode.name()])))
+}
+
+fn whitelist_toggle(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let enable = context.literal(1) == Some("on");
+    if server.whitelist_enabled == enable {
+        return Ok(Some(context.message(if enable { "commands.whitelist.already_on" } else { "commands.whitelist.already_off" }, &[])));
+    }
+    server.whitelist_enabled = enable;
+    Ok(Some(context.message(if enable { "commands.whitelist.enabled" } else { "commands.whitelist.disabled" }, &[])))
+}
+
+fn whitelist_list(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let mut names: Vec<&str> = server.whitelist.entries().map(|entry| entry.name.as_str()).collect();
+    names.sort_unstable();
+    Ok(Some(context.message("commands.whitelist.list", &[&names.len(), &names.join(", ")])))
+}
+
+fn whitelist_add(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let name = context.string("player").unwrap_or_default();
+    let uuid = any_player(server, name);
+    if server.whitelist.contains(uuid) {
+        return Ok(Some(context.message("commands.whitelist.add_unchanged", &[&name])));
+    }
+    server.whitelist.insert(WhitelistEntry { uuid, name: name.to_owned() })?;
+    Ok(Some(context.message("commands.whitelist.add_success", &[&name])))
+}
+
+fn whitelist_remove(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let name = context.string("player").unwrap_or_default();
+    let uuid = server.whitelist.get_by_name(name).map(|entry| entry.uuid).unwrap_or_else(|| any_player(server, name));
+    match server.whitelist.remove(uuid)? {
+        Some(_) => Ok(Some(context.message("commands.whitelist.remove_success", &[&name]))),
+        None => Ok(Some(context.message("commands.whitelist.remove_unchanged", &[&name]))),
+    }
+}
+
+fn whitelist_reload(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    server.whitelist.reload()?;
+    Ok(Some(context.message("commands.whitelist.reloaded", &[])))
+}
+
+fn ban(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let name = context.string("player").unwrap_or_default();
+    let uuid = any_player(server, name);
+    if server.bans.contains(uuid) {
+        return Ok(Some(context.message("commands.ban.unchanged", &[&name])));
+    }
+    let reason = context.string("reason").map(str::to_owned).unwrap_or_else(|| context.message("commands.ban.default_reason", &[]));
+    let source = sender_name(server, context.sender);
+    server.bans.insert(BanEntry::new(uuid, name.to_owned(), source, reason.clone()))?;
+    if let Some(player) = server.players.get(&uuid) {
+        player.kick(DisconnectReason::Banned(Some(reason.clone())));
+    }
+    Ok(Some(context.message("commands.ban.success", &[&name, &reason])))
+}
+
+fn pardon(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let name = context.string("player").unwrap_or_default();
+    let uuid = server.bans.get_by_name(name).map(|entry| entry.uuid).unwrap_or_else(|| any_player(server, name));
+    match server.bans.remove(uuid)? {
+        Some(_) => Ok(Some(context.message("commands.pardon.success", &[&name]))),
+        None => Ok(Some(context.message("commands.pardon.unchanged", &[&name]))),
+    }
+}
+
+fn address(context: &CommandContext) -> Result<IpAddr, CommandError> {
+    let address = context.string("address").unwrap_or_default();
+    address.parse().map_err(|_| CommandError::InvalidAddress(address.to_owned()))
+}
+
+fn ban_ip(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let address = address(context)?;
+    if server.ip_bans.contains(address) {
+        return Ok(Some(context.message("commands.ban_ip.unchanged", &[&address])));
+    }
+    let reason = context.string("reason").map(str::to_owned).unwrap_or_else(|| context.message("commands.ban.default_reason", &[]));
+    let source = sender_name(server, context.sender);
+    server.ip_bans.insert(IpBanEntry::new(address, source, reason.clone()))?;
+    Ok(Some(context.message("commands.ban_ip.success", &[&address, &reason])))
+}
+
+fn pardon_ip(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let address = address(context)?;
+    match server.ip_bans.remove(address)? {
+        Some(_) => Ok(Some(context.message("commands.pardon_ip.success", &[&address]))),
+        None => Ok(Some(context.message("commands.pardon_ip.unchanged", &[&address]))),
+    }
+}
+
+/// `op <player>` and `deop <player>`.
+fn op(server: &mut FalconServer, context: &CommandContext) -> CommandResult {
+    let command = context.literal(0).unwrap_or_default();
+    let name = context.string("player").unwrap_or_default();
+    let uuid = any_player(server, name);
+    let level = if command == "op" { FalconConfig::global().server.op_permission_level } else { 0 };
+    match server.set_op_level(uuid, name.to_owned(), level)? {
+        true => Ok(Some(context.message(&format!("commands.{}.success", command), &[&name]))),
+        false => Ok(Some(context.message(&format!("commands.{}.unchanged", command), &[&name]))),
+    }
+}
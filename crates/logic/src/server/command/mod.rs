@@ -0,0 +1,433 @@
+//! Commands typed in the console or by players.
+//!
+//! Like brigadier, a command is a tree of literals and arguments built from
+//! [`CommandNode`]s. The same tree parses commands on the server and is sent
+//! to 1.13+ clients, which use it to complete and check commands while
+//! they are typed.
+//!
+//! Using a command needs either the op level of the command or the
+//! `falcon.command.<name>` permission node, the console may use every
+//! command.
+
+use ahash::AHashMap;
+use falcon_core::server::lang::{default_locale, MessageCatalog};
+use falcon_core::server::lists::PlayerListError;
+use falcon_send::specs::play::{CommandNodeKind, CommandNodeSpec, CommandParser, DeclareCommandsSpec};
+use thiserror::Error;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::FalconServer;
+
+mod builtin;
+
+/// Runs a command once it is parsed, returns the feedback for the sender.
+pub type Executor = fn(&mut FalconServer, &CommandContext) -> CommandResult;
+
+pub type CommandResult = Result<Option<String>, CommandError>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CommandSender {
+    Console,
+    Player(Uuid),
+}
+
+impl CommandSender {
+    pub fn uuid(&self) -> Option<Uuid> {
+        match self {
+            CommandSender::Console => None,
+            CommandSender::Player(uuid) => Some(*uuid),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CommandError {
+    #[error("Unknown command {0}")]
+    Unknown(String),
+    #[error("Invalid syntax, usage: {0}")]
+    Syntax(String),
+    #[error("Missing permission")]
+    NoPermission,
+    #[error("Only players can use this command")]
+    PlayerOnly,
+    #[error("No player named {0} is online")]
+    PlayerNotFound(String),
+    #[error("Invalid IP address {0}")]
+    InvalidAddress(String),
+    #[error("Nobody to reply to")]
+    NoReplyTarget,
+    /// The chat pipeline refused a message, with the reason for the sender.
+    #[error("Message refused: {0}")]
+    Denied(String),
+    #[error("Could not save a player list")]
+    PlayerList(#[from] PlayerListError),
+}
+
+impl CommandError {
+    /// The text of this error in `locale`.
+    pub fn message(&self, locale: &str) -> String {
+        let catalog = MessageCatalog::global();
+        match self {
+            CommandError::Unknown(name) => catalog.message(locale, "commands.unknown", &[name]),
+            CommandError::Syntax(usage) => catalog.message(locale, "commands.usage", &[usage]),
+            CommandError::NoPermission => catalog.message(locale, "commands.no_permission", &[]),
+            CommandError::PlayerOnly => catalog.message(locale, "commands.player_only", &[]),
+            CommandError::PlayerNotFound(name) => catalog.message(locale, "commands.player_not_found", &[name]),
+            CommandError::InvalidAddress(address) => catalog.message(locale, "commands.invalid_address", &[address]),
+            CommandError::NoReplyTarget => catalog.message(locale, "commands.reply.none", &[]),
+            CommandError::Denied(reason) => reason.clone(),
+            CommandError::PlayerList(_) => catalog.message(locale, "commands.failed", &[]),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgumentValue {
+    String(String),
+    Double(f64),
+}
+
+/// The parsed arguments of a command in order.
+type Arguments = Vec<(&'static str, ArgumentValue)>;
+
+/// A parsed command.
+pub struct CommandContext {
+    pub sender: CommandSender,
+    pub locale: String,
+    literals: Vec<&'static str>,
+    arguments: AHashMap<&'static str, ArgumentValue>,
+}
+
+impl CommandContext {
+    /// The n-th literal of the command, the name of the command is the
+    /// first.
+    pub fn literal(&self, index: usize) -> Option<&'static str> { self.literals.get(index).copied() }
+
+    pub fn string(&self, name: &str) -> Option<&str> {
+        match self.arguments.get(name) {
+            Some(ArgumentValue::String(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn double(&self, name: &str) -> Option<f64> {
+        match self.arguments.get(name) {
+            Some(ArgumentValue::Double(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The message for `key` in the locale of the sender.
+    pub fn message(&self, key: &str, args: &[&dyn std::fmt::Display]) -> String { MessageCatalog::global().message(&self.locale, key, args) }
+}
+
+enum NodeKind {
+    Literal(&'static str),
+    Argument(&'static str, CommandParser),
+}
+
+/// A literal or argument of a command with the nodes that may follow it.
+pub struct CommandNode {
+    kind: NodeKind,
+    executor: Option<Executor>,
+    children: Vec<CommandNode>,
+}
+
+impl CommandNode {
+    /// A word that has to be typed as is.
+    pub fn literal(name: &'static str) -> Self {
+        CommandNode {
+            kind: NodeKind::Literal(name),
+            executor: None,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn argument(name: &'static str, parser: CommandParser) -> Self {
+        CommandNode {
+            kind: NodeKind::Argument(name, parser),
+            executor: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Adds a node that may follow this one, children are tried in order.
+    pub fn then(mut self, child: CommandNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Makes the command complete at this node.
+    pub fn executes(mut self, executor: Executor) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    fn parse(&self, tokens: &[&str], literals: &mut Vec<&'static str>, arguments: &mut Arguments) -> Option<Executor> {
+        let (token, rest) = tokens.split_first()?;
+        match self.kind {
+            NodeKind::Literal(name) if token.eq_ignore_ascii_case(name) => literals.push(name),
+            NodeKind::Literal(_) => return None,
+            NodeKind::Argument(name, CommandParser::Greedy) => {
+                arguments.push((name, ArgumentValue::String(tokens.join(" "))));
+                return self.executor;
+            },
+            NodeKind::Argument(name, CommandParser::Double) => {
+                let value = token.parse::<f64>().ok().filter(|value| value.is_finite())?;
+                arguments.push((name, ArgumentValue::Double(value)));
+            },
+            NodeKind::Argument(name, _) => arguments.push((name, ArgumentValue::String(token.to_string()))),
+        }
+        if rest.is_empty() {
+            return self.executor;
+        }
+        self.children.iter().find_map(|child| {
+            let (literal_count, argument_count) = (literals.len(), arguments.len());
+            let executor = child.parse(rest, literals, arguments);
+            if executor.is_none() {
+                literals.truncate(literal_count);
+                arguments.truncate(argument_count);
+            }
+            executor
+        })
+    }
+
+    fn token(&self) -> String {
+        match self.kind {
+            NodeKind::Literal(name) => name.to_owned(),
+            NodeKind::Argument(name, _) => format!("<{}>", name),
+        }
+    }
+
+    /// The usage of this node, only following the children while there is
+    /// no choice between them.
+    fn usage(&self) -> String {
+        let children = match self.children.as_slice() {
+            [] => return self.token(),
+            [child] => child.usage(),
+            children => format!("({})", children.iter().map(CommandNode::token).collect::<Vec<_>>().join("|")),
+        };
+        match self.executor {
+            Some(_) => format!("{} [{}]", self.token(), children.trim_start_matches('(').trim_end_matches(')')),
+            None => format!("{} {}", self.token(), children),
+        }
+    }
+
+    fn flatten(&self, nodes: &mut Vec<CommandNodeSpec>) -> i32 {
+        let index = nodes.len();
+        let kind = match self.kind {
+            NodeKind::Literal(name) => CommandNodeKind::Literal(name.to_owned()),
+            NodeKind::Argument(name, parser) => CommandNodeKind::Argument(name.to_owned(), parser),
+        };
+        nodes.push(CommandNodeSpec {
+            kind,
+            executable: self.executor.is_some(),
+            children: Vec::new(),
+        });
+        let children = self.children.iter().map(|child| child.flatten(nodes)).collect();
+        nodes[index].children = children;
+        index as i32
+    }
+}
+
+/// A command with the op level that may use it.
+pub struct Command {
+    node: CommandNode,
+    level: u8,
+}
+
+impl Command {
+    /// # Panics
+    /// When `node` is not a literal.
+    pub fn new(level: u8, node: CommandNode) -> Self {
+        assert!(matches!(node.kind, NodeKind::Literal(_)), "Commands start with a literal");
+        Command { node, level }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self.node.kind {
+            NodeKind::Literal(name) => name,
+            NodeKind::Argument(name, _) => name,
+        }
+    }
+
+    pub fn level(&self) -> u8 { self.level }
+
+    /// The permission node that allows using this command without the op
+    /// level.
+    pub fn permission(&self) -> String { format!("falcon.command.{}", self.name()) }
+
+    pub fn usage(&self) -> String { self.node.usage() }
+
+    fn parse(&self, tokens: &[&str]) -> Result<(Executor, Vec<&'static str>, Arguments), CommandError> {
+        let (mut literals, mut arguments) = (Vec::new(), Vec::new());
+        match self.node.parse(tokens, &mut literals, &mut arguments) {
+            Some(executor) => Ok((executor, literals, arguments)),
+            None => Err(CommandError::Syntax(self.usage())),
+        }
+    }
+}
+
+/// Every command of the server.
+#[derive(Default)]
+pub struct CommandRegistry {
+    commands: Vec<Command>,
+}
+
+impl CommandRegistry {
+    /// A registry with the built-in commands.
+    pub fn builtin() -> Self {
+        let mut registry = CommandRegistry::default();
+        builtin::register(&mut registry);
+        registry
+    }
+
+    /// Adds a command, replacing the command with the same name.
+    pub fn register(&mut self, command: Command) {
+        self.commands.retain(|other| other.name() != command.name());
+        self.commands.push(command);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Command> { self.commands.iter().find(|command| command.name().eq_ignore_ascii_case(name)) }
+
+    pub fn commands(&self) -> impl Iterator<Item = &Command> { self.commands.iter() }
+
+    /// The tree of the commands for which `filter` returns true.
+    pub fn declare_spec<F: Fn(&Command) -> bool>(&self, filter: F) -> DeclareCommandsSpec {
+        let mut nodes = vec![CommandNodeSpec {
+            kind: CommandNodeKind::Root,
+            executable: false,
+            children: Vec::new(),
+        }];
+        let children = self.commands.iter().filter(|command| filter(command)).map(|command| command.node.flatten(&mut nodes)).collect();
+        nodes[0].children = children;
+        DeclareCommandsSpec::new(nodes, 0)
+    }
+}
+
+impl FalconServer {
+    pub fn commands(&self) -> &CommandRegistry { &self.commands }
+
+    pub fn commands_mut(&mut self) -> &mut CommandRegistry { &mut self.commands }
+
+    /// Whether `sender` may use `command`, either with its op level or the
+    /// permission node of the command.
+    pub fn can_use(&self, sender: CommandSender, command: &Command) -> bool {
+        match sender {
+            CommandSender::Console => true,
+            CommandSender::Player(uuid) => self.has_op_level(uuid, command.level()) || self.has_permission(uuid, &command.permission()),
+        }
+    }
+
+    /// Parses and runs `input`, returns the feedback for the sender.
+    pub fn execute_command(&mut self, sender: CommandSender, input: &str, locale: &str) -> Option<String> {
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+        let name = tokens.first()?;
+        let parsed = match self.commands.get(name) {
+            Some(command) if !self.can_use(sender, command) => Err(CommandError::NoPermission),
+            Some(command) => command.parse(&tokens),
+            None => Err(CommandError::Unknown(name.to_string())),
+        };
+        let result = parsed.and_then(|(executor, literals, arguments)| {
+            let context = CommandContext {
+                sender,
+                locale: locale.to_owned(),
+                literals,
+                arguments: arguments.into_iter().collect(),
+            };
+            executor(self, &context)
+        });
+        match result {
+            Ok(feedback) => feedback,
+            Err(error) => {
+                if let CommandError::PlayerList(error) = &error {
+                    error!(%error, "Command failed");
+                }
+                Some(error.message(locale))
+            },
+        }
+    }
+
+    /// Runs a line typed in the console.
+    pub(crate) fn console_command(&mut self, command: &str) {
+        info!(cmd = %command, "Console command execution");
+        if let Some(feedback) = self.execute_command(CommandSender::Console, command, default_locale()) {
+            info!("{}", feedback);
+        }
+    }
+
+    /// Runs a command typed by a player, without the leading `/`.
+    pub fn player_command(&mut self, uuid: Uuid, command: String) {
+        let Some(player) = self.players.get_mut(&uuid) else {
+            return;
+        };
+        player.mark_active();
+        info!(name = player.username(), cmd = %command, "Player command execution");
+        let locale = player.locale().to_owned();
+        if let Some(feedback) = self.execute_command(CommandSender::Player(uuid), &command, &locale) {
+            if let Some(player) = self.players.get(&uuid) {
+                player.send_message(feedback);
+            }
+        }
+    }
+
+    /// Sends the commands `uuid` may use to the player.
+    pub fn send_commands(&self, uuid: Uuid) {
+        if let Some(player) = self.players.get(&uuid) {
+            let spec = self.commands.declare_spec(|command| self.can_use(CommandSender::Player(uuid), command));
+            player.connection().send_packet(spec, falcon_send::write_declare_commands);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn noop(_: &mut FalconServer, _: &CommandContext) -> CommandResult { Ok(None) }
+
+    fn teleport() -> Command {
+        Command::new(
+            2,
+            CommandNode::literal("tp")
+                .then(CommandNode::argument("x", CommandParser::Double).then(CommandNode::argument("y", CommandParser::Double).executes(noop)))
+                .then(
+                    CommandNode::argument("target", CommandParser::Player)
+                        .executes(noop)
+                        .then(CommandNode::argument("reason", CommandParser::Greedy).executes(noop)),
+                ),
+        )
+    }
+
+    #[test]
+    fn test_parse() {
+        let command = teleport();
+        let (_, literals, arguments) = command.parse(&["TP", "1", "2.5"]).unwrap();
+        assert_eq!(literals, vec!["tp"]);
+        assert_eq!(arguments, vec![("x", ArgumentValue::Double(1.0)), ("y", ArgumentValue::Double(2.5))]);
+
+        // not a complete position, so the player argument is used instead
+        let (_, _, arguments) = command.parse(&["tp", "1"]).unwrap();
+        assert_eq!(arguments, vec![("target", ArgumentValue::String(String::from("1")))]);
+
+        let (_, _, arguments) = command.parse(&["tp", "Steve", "was", "here"]).unwrap();
+        assert_eq!(arguments[1], ("reason", ArgumentValue::String(String::from("was here"))));
+
+        assert!(matches!(command.parse(&["tp"]), Err(CommandError::Syntax(_))));
+        assert_eq!(command.usage(), "tp (<x>|<target>)");
+    }
+
+    #[test]
+    fn test_declare_spec() {
+        let mut registry = CommandRegistry::default();
+        registry.register(teleport());
+        let spec = registry.declare_spec(|_| true);
+        assert_eq!(spec.root, 0);
+        assert_eq!(spec.nodes.len(), 6);
+        assert_eq!(spec.nodes[0].children, vec![1]);
+        assert_eq!(spec.nodes[1].children, vec![2, 4]);
+        assert!(spec.nodes[3].executable && !spec.nodes[2].executable);
+        assert!(registry.declare_spec(|_| false).nodes[0].children.is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+use falcon_core::player::data::GameMode;
+use falcon_core::player::stats::Statistic;
+use falcon_core::server::config::FalconConfig;
+use falcon_core::server::lang::MessageCatalog;
+use falcon_core::world::entity::EntityAnimation;
+use falcon_send::specs::play::{EntityAnimationSpec, EntityStatusSpec};
+use uuid::Uuid;
+
+use crate::player::health::DamageCause;
+use crate::server::FalconServer;
+
+/// 64 blocks below the bottom of the world, where vanilla starts hurting
+/// players.
+const VOID_LEVEL: f64 = -64.0;
+const VOID_DAMAGE: f32 = 4.0;
+
+impl FalconServer {
+    /// Hurts a player in survival or adventure mode and kills it once its
+    /// health runs out. Returns whether the player took damage, which is
+    /// never the case when the [`damage`](falcon_core::server::config::PlayerSettings::damage)
+    /// setting is off.
+    pub fn damage_player(&mut self, uuid: Uuid, amount: f32, cause: DamageCause) -> bool {
+        if !FalconConfig::global().players.damage {
+            return false;
+        }
+        let Some(player) = self.players.get_mut(&uuid) else {
+            return false;
+        };
+        if matches!(player.gamemode(), GameMode::Creative | GameMode::Spectator) || player.health_mut().damage(amount) == 0.0 {
+            return false;
+        }
+        let player = &self.players[&uuid];
+        let animation = EntityAnimationSpec::new(player.entity_id(), EntityAnimation::TakeDamage);
+        for viewer in self.tracker.viewers_of(player.entity_id(), &self.players).chain(std::iter::once(player)) {
+            viewer.connection().send_packet(animation.clone(), falcon_send::write_entity_animation);
+        }
+        if player.health().is_dead() {
+            self.kill_player(uuid, cause);
+        } else {
+            player.send_health();
+        }
+        true
+    }
+
+    /// Kills a player, also when players cannot take damage. Everyone that
+    /// sees the player gets the death animation and a message how the
+    /// player died, the client shows its death screen until it respawns.
+    pub fn kill_player(&mut self, uuid: Uuid, cause: DamageCause) {
+        let Some(player) = self.players.get_mut(&uuid) else {
+            return;
+        };
+        player.health_mut().kill();
+        player.statistics_mut().increment(Statistic::Deaths, 1);
+        let player = &self.players[&uuid];
+        player.send_health();
+        let death = EntityStatusSpec::death(player.entity_id());
+        for viewer in self.tracker.viewers_of(player.entity_id(), &self.players) {
+            viewer.connection().send_packet(death.clone(), falcon_send::write_entity_status);
+        }
+        let name = player.username().to_owned();
+        let attacker = match cause {
+            DamageCause::Attack(attacker) => self.players.get(&attacker).map(|attacker| attacker.username().to_owned()),
+            _ => None,
+        }
+        .unwrap_or_default();
+        self.broadcast_message(|locale| MessageCatalog::global().message(locale, cause.death_message(), &[&name, &attacker]));
+    }
+
+    /// Counts down the invulnerability after a hit and hurts the players
+    /// below the world.
+    pub(crate) fn tick_health(&mut self) {
+        if !FalconConfig::global().players.damage {
+            return;
+        }
+        let mut in_void = Vec::new();
+        for player in self.players.values_mut() {
+            player.health_mut().tick();
+            if player.position().y < VOID_LEVEL && !player.health().is_dead() {
+                in_void.push(player.uuid());
+            }
+        }
+        for uuid in in_void {
+            self.damage_player(uuid, VOID_DAMAGE, DamageCause::Void);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use falcon_core::player::data::Position;
+use falcon_core::server::config::FalconConfig;
+use falcon_core::world::entity::{EntityAnimation, EntityInteraction, EntityMetadata, EntityType};
+use falcon_send::specs::play::{EntityAnimationSpec, EntityVelocitySpec};
+use uuid::Uuid;
+
+use crate::entity::interaction::{InteractEvent, InteractListener};
+use crate::entity::tracker::TrackedEntity;
+use crate::player::health::DamageCause;
+use crate::server::chat::world_name;
+use crate::server::FalconServer;
+
+impl FalconServer {
+    /// Spawns a non-player entity in the world named `world`, see
+    /// [`world_name`], and shows it to the players nearby. Returns the id of
+    /// the entity, `None` if there is no such world or `entity_type` is a
+    /// player.
+    pub fn spawn_entity(&mut self, world: &str, entity_type: EntityType, position: Position) -> Option<i32> {
+        if world != world_name() || entity_type == EntityType::Player {
+            return None;
+        }
+        // unique as long as the server runs, like the entity id
+        let uuid = Uuid::new_v3(&Uuid::NAMESPACE_OID, format!("falcon:entity/{}", self.entities.next_id()).as_bytes());
+        let id = self.entities.spawn(entity_type, uuid);
+        self.tracker.add(TrackedEntity::new(id, uuid, entity_type, position), &self.players);
+        Some(id)
+    }
+
+    /// Changes what players see of an entity, returns `false` if no
+    /// non-player entity has this id.
+    pub fn set_entity_metadata(&mut self, id: i32, metadata: EntityMetadata) -> bool { self.tracker.set_metadata(id, metadata, &self.players) }
+
+    /// Removes a non-player entity, returns `false` if no non-player entity
+    /// has this id.
+    pub fn remove_entity(&mut self, id: i32) -> bool {
+        if self.tracker.remove(id, &self.players).is_none() {
+            return false;
+        }
+        self.entities.remove(id);
+        true
+    }
+
+    pub fn add_interact_listener(&mut self, listener: InteractListener) { self.interact_listeners.push(listener); }
+
+    /// Runs the interact listeners, an attack on another player that is
+    /// not cancelled is a hit when [`pvp`](falcon_core::server::config::PlayerSettings::pvp)
+    /// is enabled.
+    pub fn player_interact_entity(&mut self, uuid: Uuid, target: i32, interaction: EntityInteraction, sneaking: bool) {
+        let Some(player) = self.players.get_mut(&uuid) else {
+            return;
+        };
+        player.mark_active();
+        let mut event = InteractEvent {
+            player: uuid,
+            target,
+            interaction,
+            sneaking,
+            cancelled: false,
+        };
+        // listeners added by a listener run from the next interaction on
+        let mut listeners = std::mem::take(&mut self.interact_listeners);
+        for listener in &mut listeners {
+            listener(self, &mut event);
+        }
+        listeners.append(&mut self.interact_listeners);
+        self.interact_listeners = listeners;
+
+        if !event.cancelled && event.interaction == EntityInteraction::Attack && FalconConfig::global().players.pvp {
+            if let Some(target) = self.entities.get(target).filter(|info| info.entity_type == EntityType::Player).map(|info| info.uuid) {
+                self.hit_player(uuid, target);
+            }
+        }
+    }
+
+    /// Shows `attacker` hitting `target` to everyone that sees the target
+    /// and knocks the target back, the target also takes damage when the
+    /// [`damage`](falcon_core::server::config::PlayerSettings::damage)
+    /// setting is on. Returns `false` if either player is not online or the
+    /// target is out of reach.
+    pub fn hit_player(&mut self, attacker: Uuid, target: Uuid) -> bool {
+        let (Some(attacking), Some(hit)) = (self.players.get(&attacker), self.players.get(&target)) else {
+            return false;
+        };
+        let (from, to) = (attacking.position(), hit.position());
+        if attacker == target || (to.x - from.x).hypot(to.z - from.z).hypot(to.y - from.y) > REACH {
+            return false;
+        }
+        let id = hit.entity_id();
+        // like the vanilla knockback, away from the attacker
+        let yaw = attacking.look_angles().yaw.to_radians() as f64;
+        let knockback = EntityVelocitySpec::new(id, -yaw.sin() * KNOCKBACK, KNOCKBACK, yaw.cos() * KNOCKBACK);
+        for player in self.tracker.viewers_of(id, &self.players).chain(std::iter::once(hit)) {
+            player.connection().send_packet(knockback.clone(), falcon_send::write_entity_velocity);
+        }
+        // a hit that does damage already shows the hurt animation
+        if !self.damage_player(target, ATTACK_DAMAGE, DamageCause::Attack(attacker)) {
+            let animation = EntityAnimationSpec::new(id, EntityAnimation::TakeDamage);
+            for player in self.tracker.viewers_of(id, &self.players).chain(self.players.get(&target)) {
+                player.connection().send_packet(animation.clone(), falcon_send::write_entity_animation);
+            }
+        }
+        true
+    }
+}
+
+/// How far players can hit, a bit more than the client allows to account
+/// for latency.
+const REACH: f64 = 6.0;
+
+/// The damage of a hit with the fist.
+const ATTACK_DAMAGE: f32 = 1.0;
+
+/// The knockback of a hit in blocks per tick.
+const KNOCKBACK: f64 = 0.4;
@@ -0,0 +1,164 @@
+//! The packets that bring a player into the world once logged in, in the
+//! order the client expects them.
+//!
+//! The steps of every version are declared here by protocol range and not
+//! by the version modules of `falcon_send`, that crate does not know the
+//! server logic. A server replaces them with
+//! [`FalconServer::with_join_sequence`](crate::FalconServer::with_join_sequence).
+
+use thiserror::Error;
+
+/// The first protocol with the recipe book and advancements, 1.12.
+const RECIPE_BOOK_PROTOCOL: i32 = 335;
+/// The first protocol with tags, declared recipes and commands, 1.13.
+const FLATTENING_PROTOCOL: i32 = 393;
+
+/// A part of the join of a player, most steps send a single packet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinStep {
+    JoinGame,
+    /// The operator permission level, as an entity status.
+    OpLevel,
+    Difficulty,
+    Abilities,
+    Recipes,
+    Tags,
+    Commands,
+    RecipeBook,
+    /// The chunks in view distance. The chunks around the player are sent
+    /// right away, the others follow with the chunk budget or once they
+    /// are loaded.
+    Chunks,
+    SpawnPosition,
+    Health,
+    Position,
+    /// The tab list of the player and the player in the tab list of the
+    /// others.
+    PlayerInfo,
+    /// Shows the player to the others and the others to the player.
+    Entities,
+    Advancements,
+    WelcomeToast,
+}
+
+/// Steps that must come before another step when both are part of a
+/// sequence. The packets of the steps share a lane of the connection, so
+/// they also reach the client in this order.
+const ORDER: [(JoinStep, JoinStep); 3] = [
+    // players would fall through the world that is not there yet
+    (JoinStep::Chunks, JoinStep::Position),
+    // the skin of a player is only known through its tab list entry
+    (JoinStep::PlayerInfo, JoinStep::Entities),
+    // the toast is an advancement that would be reset by the tab
+    (JoinStep::Advancements, JoinStep::WelcomeToast),
+];
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum JoinSequenceError {
+    #[error("The join sequence does not start with the join game packet")]
+    MissingJoinGame,
+    #[error("{0:?} is part of the join sequence more than once")]
+    Duplicate(JoinStep),
+    #[error("{1:?} is sent before {0:?}")]
+    OutOfOrder(JoinStep, JoinStep),
+}
+
+/// The steps to join a player in a version, built with
+/// [`JoinSequence::then`] and executed by the server in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JoinSequence {
+    steps: Vec<JoinStep>,
+}
+
+impl JoinSequence {
+    pub fn new() -> Self { JoinSequence::default() }
+
+    /// The steps of the versions up to 1.11 and the ones added since.
+    pub fn for_protocol(protocol: i32) -> Self {
+        let mut sequence = JoinSequence::new()
+            .then(JoinStep::JoinGame)
+            .then(JoinStep::OpLevel)
+            .then(JoinStep::Difficulty)
+            .then(JoinStep::Abilities);
+        if protocol >= FLATTENING_PROTOCOL {
+            sequence = sequence.then(JoinStep::Recipes).then(JoinStep::Tags).then(JoinStep::Commands);
+        }
+        if protocol >= RECIPE_BOOK_PROTOCOL {
+            sequence = sequence.then(JoinStep::RecipeBook);
+        }
+        sequence = sequence
+            .then(JoinStep::Chunks)
+            .then(JoinStep::SpawnPosition)
+            .then(JoinStep::Health)
+            .then(JoinStep::Position)
+            .then(JoinStep::PlayerInfo)
+            .then(JoinStep::Entities);
+        if protocol >= RECIPE_BOOK_PROTOCOL {
+            sequence = sequence.then(JoinStep::Advancements).then(JoinStep::WelcomeToast);
+        }
+        sequence
+    }
+
+    /// Adds `step` at the end.
+    pub fn then(mut self, step: JoinStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Leaves `step` out, e.g. for a version that does not know it.
+    pub fn without(mut self, step: JoinStep) -> Self {
+        self.steps.retain(|s| *s != step);
+        self
+    }
+
+    pub fn steps(&self) -> &[JoinStep] { &self.steps }
+
+    /// Checks that the sequence starts with joining the game, has every
+    /// step at most once and sends the steps in an order the client
+    /// accepts.
+    pub fn validate(&self) -> Result<(), JoinSequenceError> {
+        if self.steps.first() != Some(&JoinStep::JoinGame) {
+            return Err(JoinSequenceError::MissingJoinGame);
+        }
+        for (i, step) in self.steps.iter().enumerate() {
+            if self.steps[..i].contains(step) {
+                return Err(JoinSequenceError::Duplicate(*step));
+            }
+        }
+        let position = |step| self.steps.iter().position(|s| *s == step);
+        for (before, after) in ORDER {
+            if let (Some(first), Some(second)) = (position(before), position(after)) {
+                if second < first {
+                    return Err(JoinSequenceError::OutOfOrder(before, after));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{JoinSequence, JoinSequenceError, JoinStep};
+
+    #[test]
+    fn test_for_protocol() {
+        for protocol in [47, 340, 404, 763] {
+            assert_eq!(JoinSequence::for_protocol(protocol).validate(), Ok(()));
+        }
+        assert!(!JoinSequence::for_protocol(47).steps().contains(&JoinStep::RecipeBook));
+        assert!(!JoinSequence::for_protocol(340).steps().contains(&JoinStep::Tags));
+        assert!(JoinSequence::for_protocol(763).steps().contains(&JoinStep::Commands));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(JoinSequence::new().then(JoinStep::Chunks).validate(), Err(JoinSequenceError::MissingJoinGame));
+        let sequence = JoinSequence::new().then(JoinStep::JoinGame).then(JoinStep::Health).then(JoinStep::Health);
+        assert_eq!(sequence.validate(), Err(JoinSequenceError::Duplicate(JoinStep::Health)));
+        let sequence = JoinSequence::new().then(JoinStep::JoinGame).then(JoinStep::Position).then(JoinStep::Chunks);
+        assert_eq!(sequence.validate(), Err(JoinSequenceError::OutOfOrder(JoinStep::Chunks, JoinStep::Position)));
+        let sequence = JoinSequence::for_protocol(763).without(JoinStep::Chunks).then(JoinStep::Chunks);
+        assert_eq!(sequence.validate(), Err(JoinSequenceError::OutOfOrder(JoinStep::Chunks, JoinStep::Position)));
+    }
+}
@@ -1,18 +1,55 @@
 use std::error::Error;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 
 use ahash::AHashMap;
 use anyhow::Result;
+use falcon_core::data::tags::ProtocolTags;
+use falcon_core::player::advancements::Advancement;
+use falcon_core::player::data::{LookAngles, Position};
+use falcon_core::server::config::FalconConfig;
+use falcon_core::server::lists::{BanList, IpBanList, Whitelist};
+use falcon_core::server::ops::OpList;
+use falcon_core::server::permissions::{FilePermissions, PermissionsProvider};
 use falcon_core::ShutdownHandle;
 use tokio::sync::mpsc::UnboundedReceiver;
 use uuid::Uuid;
 pub use wrapper::ServerWrapper;
 
+use crate::connection::ConnectionWrapper;
+use crate::entity::interaction::InteractListener;
+use crate::entity::tracker::EntityTracker;
+use crate::player::movement::MovementListener;
+use crate::player::void::VoidListener;
+use crate::entity::EntityRegistry;
 use crate::player::FalconPlayer;
+use crate::server::auth::{AllowAll, AuthProvider};
+use crate::server::chat::ChatPipeline;
+use crate::server::command::CommandRegistry;
+use crate::server::join::JoinSequence;
+use crate::server::report::{TickReport, TickTimes};
+use crate::server::routing::{ForcedHosts, HostRouter};
+use crate::server::status::ServerStatus;
+use crate::server::throttle::LoginThrottle;
 use crate::world::FalconWorld;
 
+pub mod auth;
+mod blocks;
+pub mod chat;
+pub mod command;
+mod damage;
+mod entities;
+pub mod join;
 mod network;
+pub mod report;
+pub mod routing;
+mod snapshot;
+mod spawn;
+mod statistics;
+pub mod status;
+pub mod throttle;
 mod tick;
 mod wrapper;
 
@@ -24,20 +61,54 @@ pub trait SyncFutServerTask: Send + Sync {
     fn run(self: Box<Self>, server: &mut FalconServer) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>>;
 }
 
-pub enum ServerTask {
+pub enum TaskKind {
     Sync(Box<dyn SyncServerTask>),
     Async(Box<dyn SyncFutServerTask>),
 }
 
+/// A task for the server and the connection it was sent from, that client
+/// is disconnected when the task panics as its player may be left half
+/// updated.
+pub struct ServerTask {
+    kind: TaskKind,
+    origin: Option<ConnectionWrapper>,
+}
+
+impl ServerTask {
+    pub fn new(kind: TaskKind, origin: Option<ConnectionWrapper>) -> Self { ServerTask { kind, origin } }
+}
+
 pub struct FalconServer {
     shutdown: ShutdownHandle,
     should_stop: bool,
     console_rx: UnboundedReceiver<String>,
     receiver: UnboundedReceiver<ServerTask>,
-    eid_count: i32,
+    entities: EntityRegistry,
+    tracker: EntityTracker,
+    interact_listeners: Vec<InteractListener>,
+    movement_listeners: Vec<MovementListener>,
+    void_listeners: Vec<VoidListener>,
     players: AHashMap<Uuid, FalconPlayer>,
     usernames: AHashMap<String, Uuid>,
+    ops: OpList,
+    permissions: Box<dyn PermissionsProvider>,
+    whitelist_enabled: bool,
+    whitelist: Whitelist,
+    bans: BanList,
+    ip_bans: IpBanList,
+    login_throttle: LoginThrottle,
+    router: Box<dyn HostRouter>,
+    auth: Arc<dyn AuthProvider>,
+    commands: CommandRegistry,
+    chat: ChatPipeline,
+    advancements: Vec<Advancement>,
+    tags: ProtocolTags,
     world: FalconWorld,
+    world_spawn: (Position, LookAngles),
+    tick_count: u64,
+    tick_times: TickTimes,
+    status: Arc<ServerStatus>,
+    join_sequence: fn(i32) -> JoinSequence,
 }
 
 impl FalconServer {
@@ -47,17 +118,124 @@ impl FalconServer {
             should_stop: false,
             console_rx,
             receiver,
-            eid_count: 0,
+            entities: EntityRegistry::new(),
+            tracker: EntityTracker::default(),
+            interact_listeners: Vec::new(),
+            movement_listeners: Vec::new(),
+            void_listeners: Vec::new(),
             players: AHashMap::new(),
             usernames: AHashMap::new(),
+            ops: OpList::default(),
+            permissions: Box::<FilePermissions>::default(),
+            whitelist_enabled: FalconConfig::global().server.whitelist,
+            whitelist: Whitelist::default(),
+            bans: BanList::default(),
+            ip_bans: IpBanList::default(),
+            login_throttle: LoginThrottle::new(Duration::from_millis(FalconConfig::global().connection.login_throttle)),
+            router: Box::new(ForcedHosts::from_config()),
+            auth: Arc::new(AllowAll),
+            commands: CommandRegistry::builtin(),
+            chat: ChatPipeline::from_config(),
+            advancements: Advancement::tab_from_config(),
+            tags: ProtocolTags::load_dir(&FalconConfig::global().server.tags_dir),
             world,
+            world_spawn: (FalconConfig::global().players.spawn_position, FalconConfig::global().players.spawn_look),
+            tick_count: 0,
+            tick_times: TickTimes::default(),
+            status: Arc::default(),
+            join_sequence: JoinSequence::for_protocol,
         }
     }
 
+    pub fn with_ops(mut self, ops: OpList) -> Self {
+        self.ops = ops;
+        self
+    }
+
+    /// Replaces the permissions provider, the default one grants nothing.
+    pub fn with_permissions(mut self, permissions: Box<dyn PermissionsProvider>) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Replaces the router of logins, the default one uses the forced hosts
+    /// of the config.
+    pub fn with_router(mut self, router: Box<dyn HostRouter>) -> Self {
+        self.router = router;
+        self
+    }
+
+    /// Replaces the authentication of logins, the default one lets every
+    /// player join.
+    pub fn with_auth(mut self, auth: Arc<dyn AuthProvider>) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Replaces the steps a player of a protocol joins with, the default
+    /// is [`JoinSequence::for_protocol`].
+    pub fn with_join_sequence(mut self, join_sequence: fn(i32) -> JoinSequence) -> Self {
+        self.join_sequence = join_sequence;
+        self
+    }
+
+    /// Replaces the chat pipeline, the default one has the filters of the
+    /// config.
+    pub fn with_chat(mut self, chat: ChatPipeline) -> Self {
+        self.chat = chat;
+        self
+    }
+
+    /// Replaces the advancement tab of the config.
+    pub fn with_advancements(mut self, advancements: Vec<Advancement>) -> Self {
+        self.advancements = advancements;
+        self
+    }
+
+    /// Replaces the tags of the tag directory of the config.
+    pub fn with_tags(mut self, tags: ProtocolTags) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_whitelist(mut self, whitelist: Whitelist) -> Self {
+        self.whitelist = whitelist;
+        self
+    }
+
+    pub fn with_bans(mut self, bans: BanList) -> Self {
+        self.bans = bans;
+        self
+    }
+
+    pub fn with_ip_bans(mut self, ip_bans: IpBanList) -> Self {
+        self.ip_bans = ip_bans;
+        self
+    }
+
     pub fn shutdown_handle(&mut self) -> &mut ShutdownHandle { &mut self.shutdown }
 
     pub fn online_count(&self) -> usize { self.players.len() }
 
+    /// The server list as answered by the connections, pass it to the
+    /// [`ServerWrapper`] of this server.
+    pub fn status(&self) -> &Arc<ServerStatus> { &self.status }
+
+    /// The chat pipeline, e.g. to mute a player.
+    pub fn chat_mut(&mut self) -> &mut ChatPipeline { &mut self.chat }
+
+    pub fn advancements(&self) -> &[Advancement] { &self.advancements }
+
+    pub fn entities(&self) -> &EntityRegistry { &self.entities }
+
+    /// The entity registry, e.g. to add a listener.
+    pub fn entities_mut(&mut self) -> &mut EntityRegistry { &mut self.entities }
+
+    /// The entities and players each player sees.
+    pub fn tracker(&self) -> &EntityTracker { &self.tracker }
+
+    pub fn tags(&self) -> &ProtocolTags { &self.tags }
+
     pub fn player(&self, uuid: Uuid) -> Option<&FalconPlayer> { self.players.get(&uuid) }
 
     pub fn player_mut(&mut self, uuid: Uuid) -> Option<&mut FalconPlayer> { self.players.get_mut(&uuid) }
@@ -66,6 +244,31 @@ impl FalconServer {
 
     pub fn username_mut(&mut self, username: &String) -> Option<&mut FalconPlayer> { self.usernames.get(username).and_then(|x| self.players.get_mut(x)) }
 
+    pub fn ops(&self) -> &OpList { &self.ops }
+
+    /// The operator level of `uuid`, 0 for regular players.
+    pub fn permission_level(&self, uuid: Uuid) -> u8 { self.ops.level(uuid) }
+
+    pub fn has_op_level(&self, uuid: Uuid, level: u8) -> bool { self.permission_level(uuid) >= level }
+
+    /// Whether the permissions provider gives `uuid` the permission `node`.
+    pub fn has_permission(&self, uuid: Uuid, node: &str) -> bool { self.permissions.has_permission(uuid, node) }
+
+    pub fn whitelist(&self) -> &Whitelist { &self.whitelist }
+
+    /// Whether only players on the whitelist and operators may join.
+    pub fn whitelist_enabled(&self) -> bool { self.whitelist_enabled }
+
+    pub fn bans(&self) -> &BanList { &self.bans }
+
+    pub fn ip_bans(&self) -> &IpBanList { &self.ip_bans }
+
+    /// The number of ticks since the server started.
+    pub fn tick_count(&self) -> u64 { self.tick_count }
+
+    /// The tick rate and tick durations of the last ticks.
+    pub fn tick_report(&self) -> TickReport { self.tick_times.report() }
+
     pub fn world(&mut self) -> &mut FalconWorld { &mut self.world }
 }
 
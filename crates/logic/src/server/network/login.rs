@@ -1,64 +1,287 @@
+use std::convert::Infallible;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use falcon_core::chat::ChatComponent;
 use falcon_core::network::ConnectionState;
+use falcon_core::player::advancements::AdvancementFrame;
+use falcon_core::player::data::{LookAngles, Position, TeleportFlags};
+use falcon_core::player::profile::{is_valid_username, GameProfile, ProfileProperty};
 use falcon_core::server::config::FalconConfig;
 use falcon_core::server::data::Difficulty;
+use falcon_core::server::lang::MessageCatalog;
+use falcon_core::world::entity::EntityType;
+use falcon_core::world::seed::hashed_seed;
 use falcon_packet_core::WriteError;
 use falcon_send::specs::login::LoginSuccessSpec;
-use falcon_send::specs::play::{PlayerAbilitiesSpec, PositionAndLookSpec, ServerDifficultySpec};
+use falcon_send::specs::play::{DeclareRecipesSpec, EntityStatusSpec, PlayerAbilitiesSpec, PlayerInfoSpec, PositionAndLookSpec, ServerDifficultySpec, TagsSpec};
 use tracing::{debug, error, info};
-use uuid::Uuid;
+use md5::{Digest, Md5};
+use uuid::{Builder, Uuid};
 
-use crate::connection::ConnectionWrapper;
+use crate::connection::floodgate::Floodgate;
+use crate::connection::{ConnectionWrapper, DisconnectReason};
+use crate::player::profile::ProfileResolver;
 use crate::player::FalconPlayer;
+use crate::server::auth::{AuthResult, LoginInfo};
+use crate::server::join::JoinStep;
+use crate::server::routing::LoginRoute;
 use crate::server::FalconServer;
 
 impl FalconServer {
-    pub fn player_login(&mut self, username: String, protocol: i32, connection: ConnectionWrapper) {
+    pub fn player_login(&mut self, username: String, login: LoginInfo, connection: ConnectionWrapper) {
         debug!(player_name = %username);
-        // TODO: create minecraft uuids
-        let player_uuid = Uuid::new_v3(&Uuid::NAMESPACE_DNS, username.as_bytes());
-        let username2 = username.clone();
+        if self.login_throttle.throttle(login.address, Instant::now()) {
+            debug!(address = %login.address, "Login throttled");
+            return connection.execute(|connection| {
+                connection.disconnect(DisconnectReason::Throttled);
+                Ok::<(), Infallible>(())
+            });
+        }
+        if let Some(bedrock) = &login.bedrock {
+            // Bedrock players have no Java profile to look up
+            let username = Floodgate::global().map_or_else(|| bedrock.username.clone(), |floodgate| floodgate.username(bedrock));
+            let profile = GameProfile::new(bedrock.uuid(), username, Vec::new());
+            return self.authenticate(profile, login, connection);
+        }
+        if !is_valid_username(&username) {
+            debug!(%username, "Refusing invalid username");
+            return connection.execute(|connection| {
+                connection.disconnect(DisconnectReason::InvalidName);
+                Ok::<(), Infallible>(())
+            });
+        }
+        let config = FalconConfig::global();
+        let require_profile = config.profiles.require_profile;
+        if !config.players.fetch_skins && !require_profile {
+            return self.player_login_with_profile(username, Vec::new(), login, connection);
+        }
+        // the lookup runs on the connection so the server is not held up
+        connection.execute(move |connection| {
+            let server = connection.server().clone();
+            let wrapper = connection.wrapper();
+            tokio::spawn(async move {
+                let properties = match ProfileResolver::global().profile(&username).await {
+                    Ok(Some(properties)) => properties,
+                    Ok(None) if require_profile => return deny_login(DisconnectReason::UnknownProfile, &wrapper),
+                    Err(error) if require_profile => {
+                        debug!(%error, %username, "Refusing player without profile");
+                        return deny_login(DisconnectReason::AuthUnavailable, &wrapper);
+                    },
+                    Ok(None) => Vec::new(),
+                    Err(error) => {
+                        debug!(%error, %username, "Joining without profile properties");
+                        Vec::new()
+                    },
+                };
+                server.player_login_with_profile(username, properties, login, wrapper);
+            });
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    /// Authenticates the player once the profile properties are known.
+    pub fn player_login_with_profile(&mut self, username: String, properties: Vec<ProfileProperty>, login: LoginInfo, connection: ConnectionWrapper) {
+        if connection.is_closed() {
+            return;
+        }
+        let profile = GameProfile::new(FalconServer::offline_uuid(&username), username, properties);
+        self.authenticate(profile, login, connection);
+    }
+
+    /// Asks the auth provider whether `profile` may join, see
+    /// [`FalconServer::with_auth`].
+    fn authenticate(&mut self, profile: GameProfile, login: LoginInfo, connection: ConnectionWrapper) {
+        match self.auth.authenticate(profile, &login) {
+            AuthResult::Allow(profile) => self.player_login_authenticated(profile, login, connection),
+            AuthResult::Deny(reason) => deny_login(reason, &connection),
+            AuthResult::Defer(future) => {
+                // the future only has to be Send, the mutex makes the task Sync
+                let future = Mutex::new(future);
+                connection.execute(move |connection| {
+                    let (server, wrapper) = (connection.server().clone(), connection.wrapper());
+                    let mut future = future.into_inner().unwrap();
+                    tokio::spawn(async move {
+                        loop {
+                            match future.await {
+                                AuthResult::Allow(profile) => break server.player_login_authenticated(profile, login, wrapper),
+                                AuthResult::Deny(reason) => break deny_login(reason, &wrapper),
+                                AuthResult::Defer(next) => future = next,
+                            }
+                        }
+                    });
+                    Ok::<(), Infallible>(())
+                });
+            },
+        }
+    }
+
+    /// Finishes the login of an authenticated player, the router decides
+    /// where the player spawns.
+    pub fn player_login_authenticated(&mut self, profile: GameProfile, login: LoginInfo, connection: ConnectionWrapper) {
+        if connection.is_closed() {
+            return;
+        }
+        let player_uuid = profile.uuid;
+        let spawn = match self.refuse_reason(player_uuid, login.address).map(LoginRoute::Refuse).unwrap_or_else(|| self.router.route(login.host.as_ref(), &profile.name)) {
+            LoginRoute::Default => self.world_spawn,
+            LoginRoute::Spawn(position, look) => (position, look),
+            LoginRoute::Refuse(reason) => return deny_login(reason, &connection),
+        };
+        let success = LoginSuccessSpec::new(profile.clone());
         connection.execute(move |connection| -> Result<(), WriteError> {
-            connection.send_packet(LoginSuccessSpec::new(player_uuid, username2), falcon_send::write_login_success)?;
+            connection.enable_compression(FalconConfig::global().network.compression_threshold)?;
+            connection.send_packet(success, falcon_send::write_login_success)?;
             let handler_state = connection.state_mut();
             handler_state.connection_state = ConnectionState::Play;
             handler_state.uuid = Some(player_uuid);
             Ok(())
         });
-        self.login_success(username, player_uuid, protocol, connection);
+        self.login_success(profile, login.protocol, spawn, connection);
+    }
+
+    /// Why the player `uuid` may not join from `address`, if at all.
+    fn refuse_reason(&self, uuid: Uuid, address: IpAddr) -> Option<DisconnectReason> {
+        let ban_reason = self.bans.get(uuid).map(|ban| &ban.reason).or_else(|| self.ip_bans.get(address).map(|ban| &ban.reason));
+        if let Some(reason) = ban_reason {
+            return Some(DisconnectReason::Banned(Some(reason.clone()).filter(|reason| !reason.is_empty())));
+        }
+        let is_op = self.ops.contains(uuid);
+        if self.whitelist_enabled && !is_op && !self.whitelist.contains(uuid) {
+            return Some(DisconnectReason::NotWhitelisted);
+        }
+        let max_players = FalconConfig::global().server.max_players;
+        if max_players >= 0 && self.online_count() >= max_players as usize && !self.ops.bypasses_player_limit(uuid) {
+            return Some(DisconnectReason::ServerFull);
+        }
+        None
     }
 
-    pub fn login_success(&mut self, username: String, uuid: Uuid, protocol: i32, connection: ConnectionWrapper) {
+    /// The uuid vanilla gives `username` on an offline mode server, a v3
+    /// uuid of the md5 of `OfflinePlayer:<username>`.
+    pub fn offline_uuid(username: &str) -> Uuid {
+        let hash = Md5::new().chain_update("OfflinePlayer:").chain_update(username).finalize();
+        Builder::from_md5_bytes(hash.into()).into_uuid()
+    }
+
+    pub fn login_success(&mut self, profile: GameProfile, protocol: i32, (spawn_pos, spawn_look): (Position, LookAngles), connection: ConnectionWrapper) {
+        let sequence = (self.join_sequence)(protocol);
+        if let Err(error) = sequence.validate() {
+            error!(%error, protocol, "Invalid join sequence");
+            return connection.execute(move |connection| {
+                connection.disconnect(DisconnectReason::Internal(error.to_string()));
+                Ok::<(), Infallible>(())
+            });
+        }
+        let (uuid, username) = (profile.uuid, profile.name.clone());
         if self.players.contains_key(&uuid) {
             // TODO: Kick duplicated players
             error!(%uuid, %username, "Duplicate player joining");
         }
-        info!(name = %username, "Player joined the game!");
-        let (spawn_pos, spawn_look) = (FalconConfig::global().players.spawn_position, FalconConfig::global().players.spawn_look);
-        let player = FalconPlayer::new(username.clone(), uuid, self.eid_count, spawn_pos, spawn_look, protocol, connection);
-
-        self.eid_count += 1;
+        info!(name = %username, "{}", MessageCatalog::global().default_message("player.joined", &[&username]));
+        // a static height can leave players falling through the world
+        let spawn_pos = self.world.safe_spawn(spawn_pos);
+        let entity_id = self.entities.spawn(EntityType::Player, uuid);
+        let player = FalconPlayer::new(profile, entity_id, spawn_pos, spawn_look, protocol, connection).with_statistics(FalconServer::load_statistics(uuid));
 
         self.players.insert(uuid, player);
+        self.status.set_online(self.players.len());
         self.usernames.insert(username, uuid);
-        if let Some(player) = self.players.get(&uuid) {
-            let join_game_spec =
-                player.join_spec(Difficulty::Peaceful, FalconConfig::global().server.max_players as u8, String::from("customized"), 0, false, false);
-            player.connection().send_packet(join_game_spec, falcon_send::write_join_game);
+        for step in sequence.steps() {
+            self.join_step(uuid, *step);
+        }
+    }
+
+    /// Sends the packets of one step of the join of the player `uuid`.
+    fn join_step(&mut self, uuid: Uuid, step: JoinStep) {
+        let Some(player) = self.players.get(&uuid) else {
+            return;
+        };
+        match step {
+            JoinStep::JoinGame => {
+                let join_game_spec = player.join_spec(
+                    Difficulty::Peaceful,
+                    FalconConfig::global().server.max_players as u8,
+                    String::from("customized"),
+                    hashed_seed(FalconConfig::global().server.seed),
+                    false,
+                    // players that can die see the death screen
+                    FalconConfig::global().players.damage,
+                );
+                player.connection().send_packet(join_game_spec, falcon_send::write_join_game);
+            },
+            JoinStep::OpLevel => {
+                let op_level = EntityStatusSpec::op_level(player.entity_id(), self.ops.level(uuid));
+                player.connection().send_packet(op_level, falcon_send::write_entity_status);
+            },
+            JoinStep::Difficulty => {
+                let server_difficulty = ServerDifficultySpec::new(Difficulty::Peaceful, false);
+                player
+                    .connection()
+                    .send_packet(server_difficulty, falcon_send::write_server_difficulty);
+            },
+            JoinStep::Abilities => {
+                let player_abilities = PlayerAbilitiesSpec::new(player.ability_flags(), 0.05, 0.1);
+                player
+                    .connection()
+                    .send_packet(player_abilities, falcon_send::write_player_abilities);
+            },
+            JoinStep::Recipes => player.connection().send_packet(DeclareRecipesSpec, falcon_send::write_declare_recipes),
+            JoinStep::Tags => {
+                let tags = TagsSpec::new(self.tags.get(player.protocol_version()).clone(), player.protocol_version());
+                player.connection().send_packet(tags, falcon_send::write_tags);
+            },
+            JoinStep::Commands => self.send_commands(uuid),
+            JoinStep::RecipeBook => player.send_recipe_book(),
+            JoinStep::Chunks => self.world.send_chunks_for_player(player),
+            JoinStep::SpawnPosition => player.send_spawn_position(),
+            JoinStep::Health => player.send_health(),
+            JoinStep::Position => {
+                let position_look = PositionAndLookSpec::new(player.position(), player.look_angles(), TeleportFlags::empty(), 1);
+                player.connection().send_packet(position_look, falcon_send::write_position_look);
+            },
+            JoinStep::PlayerInfo => {
+                let tab_list = self.players.values().map(FalconPlayer::player_info).collect();
+                player
+                    .connection()
+                    .send_packet(PlayerInfoSpec::AddPlayers(tab_list), falcon_send::write_player_info);
+                let entry = PlayerInfoSpec::AddPlayers(vec![player.player_info()]);
+                for other in self.players.values().filter(|other| other.uuid() != uuid) {
+                    other.connection().send_packet(entry.clone(), falcon_send::write_player_info);
+                }
+            },
+            JoinStep::Entities => self.tracker.update_player(player, &self.players),
+            JoinStep::Advancements => {
+                if !self.advancements.is_empty() {
+                    player.send_advancement_tab(&self.advancements);
+                }
+            },
+            JoinStep::WelcomeToast => {
+                if let Some(toast) = &FalconConfig::global().advancements.welcome_toast {
+                    player.show_toast(ChatComponent::from_markup(toast), "minecraft:nether_star", AdvancementFrame::Goal);
+                }
+            },
+        }
+    }
+}
 
-            let server_difficulty = ServerDifficultySpec::new(Difficulty::Peaceful, false);
-            player
-                .connection()
-                .send_packet(server_difficulty, falcon_send::write_server_difficulty);
+fn deny_login(reason: DisconnectReason, connection: &ConnectionWrapper) {
+    connection.execute(|connection| {
+        connection.disconnect(reason);
+        Ok::<(), Infallible>(())
+    });
+}
 
-            let player_abilities = PlayerAbilitiesSpec::new(player.ability_flags(), 0.05, 0.1);
-            player
-                .connection()
-                .send_packet(player_abilities, falcon_send::write_player_abilities);
+#[cfg(test)]
+mod test {
+    use uuid::uuid;
 
-            self.world.send_chunks_for_player(player);
+    use crate::server::FalconServer;
 
-            let position_look = PositionAndLookSpec::new(player.position(), player.look_angles(), 0, 1);
-            player.connection().send_packet(position_look, falcon_send::write_position_look);
-        }
+    #[test]
+    fn test_offline_uuid() {
+        assert_eq!(FalconServer::offline_uuid("Notch"), uuid!("b50ad385-829d-3141-a216-7e7d7539ba7f"));
+        assert_eq!(FalconServer::offline_uuid("jeb_"), uuid!("a762f560-4fce-3236-812a-b80efff0b62b"));
     }
 }
@@ -1,3 +1,2 @@
 mod login;
 mod play;
-mod status;
@@ -1,30 +1,121 @@
-use falcon_core::player::data::Position;
-use tracing::info;
+use std::time::{Duration, Instant};
+
+use falcon_core::chat::ChatComponent;
+use falcon_core::player::data::{GameMode, Position, RecipeBookType, TeleportFlags};
+use falcon_core::player::stats::Statistic;
+use falcon_core::server::config::FalconConfig;
+use falcon_core::server::lang::{default_locale, MessageCatalog};
+use falcon_core::server::lists::PlayerListError;
+use falcon_core::world::chunks::ChunkPos;
+use falcon_core::world::entity::{EntityAnimation, EntityType};
+use falcon_send::specs::play::{
+    ChangeGameStateSpec, EntityAnimationSpec, EntityMetadataSpec, EntityStatusSpec, EntityTeleportSpec, PlayerAbilitiesSpec, PlayerInfoSpec, PositionAndLookSpec,
+};
+use tracing::{debug, error, info};
 use uuid::Uuid;
 
+use crate::server::chat::{world_name, ChatEvent, MessageTarget};
+use crate::player::health::DamageCause;
+use crate::player::movement::{MovementEvent, MovementListener, MovementState};
+use crate::server::command::CommandSender;
 use crate::server::FalconServer;
 
 impl FalconServer {
     pub fn player_leave(&mut self, uuid: Uuid) {
         if let Some(player) = self.players.remove(&uuid) {
+            self.status.set_online(self.players.len());
             self.usernames.remove(player.username());
-            info!(%uuid, username = player.username(), "Player disconnected!");
+            self.entities.remove(player.entity_id());
+            self.tracker.remove_player(&player, &self.players);
+            self.world.forget_player(uuid);
+            self.chat.forget(CommandSender::Player(uuid));
+            if let Err(error) = player.statistics().save() {
+                error!(%uuid, %error, "Could not save statistics");
+            }
+            for other in self.players.values() {
+                other.connection().send_packet(vec![uuid], falcon_send::write_player_info_remove);
+            }
+            info!(%uuid, username = player.username(), "{}", MessageCatalog::global().default_message("player.left", &[&player.username()]));
+        }
+    }
+
+    /// Moves a player as the client asks, unless the move fails a check of
+    /// the movement settings. The player is then sent back and the movement
+    /// listeners are told.
+    pub fn player_update_pos_look(&mut self, uuid: Uuid, pos: Option<Position>, facing: Option<(f32, f32)>, on_ground: bool) {
+        let Some(player) = self.players.get_mut(&uuid) else {
+            return;
+        };
+        let from = *player.position();
+        let look = match facing {
+            Some((yaw, pitch)) => MovementState::check_look(yaw, pitch),
+            None => Ok(()),
+        };
+        let checked = match pos {
+            Some(to) => look.and_then(|()| {
+                let may_fly = player.ability_flags().allow_flying || FalconConfig::global().players.allow_flight;
+                let world = &self.world;
+                player.movement_mut().check(&FalconConfig::global().movement, &from, &to, on_ground, may_fly, |chunk| world.is_loaded(chunk))
+            }),
+            None => look,
+        };
+        if let Err(violation) = checked {
+            let position_look = PositionAndLookSpec::new(player.position(), player.look_angles(), TeleportFlags::empty(), 0);
+            player.connection().send_packet(position_look, falcon_send::write_position_look);
+            debug!(%uuid, ?violation, "Refused a movement");
+            let event = MovementEvent {
+                player: uuid,
+                violation,
+                from,
+                to: pos.unwrap_or(from),
+            };
+            let mut listeners = std::mem::take(&mut self.movement_listeners);
+            for listener in &mut listeners {
+                listener(self, &event);
+            }
+            listeners.append(&mut self.movement_listeners);
+            self.movement_listeners = listeners;
+            return;
+        }
+        let mut fall_damage = 0.0;
+        if let (Some(player), Some(to)) = (self.players.get_mut(&uuid), pos) {
+            // players that may fly do not fall
+            if player.ability_flags().allow_flying {
+                player.health_mut().reset_fall();
+            } else {
+                let from_y = player.position().y;
+                fall_damage = player.health_mut().fall(from_y, to.y, on_ground);
+            }
+        }
+        self.move_player(uuid, pos, facing, on_ground);
+        if fall_damage > 0.0 {
+            self.damage_player(uuid, fall_damage, DamageCause::Fall);
         }
+        self.check_void(uuid);
     }
 
-    pub fn player_update_pos_look(&mut self, uuid: Uuid, pos: Option<Position>, facing: Option<(f32, f32)>, _on_ground: bool) {
+    pub fn add_movement_listener(&mut self, listener: MovementListener) { self.movement_listeners.push(listener); }
+
+    fn move_player(&mut self, uuid: Uuid, pos: Option<Position>, facing: Option<(f32, f32)>, on_ground: bool) {
         let mut update_position = false;
         let mut update_viewpos = false;
+        let mut moved = false;
         let (old_x, old_z, x, z) = match self.players.get_mut(&uuid) {
             Some(player) => {
                 let look_angles = player.look_angles_mut();
                 if let Some((yaw, pitch)) = facing {
+                    moved |= look_angles.yaw != yaw || look_angles.pitch != pitch;
                     look_angles.yaw = yaw;
                     look_angles.pitch = pitch;
                 }
                 let position = player.position_mut();
                 let (old_chunk_x, old_chunk_z) = position.chunk_coords();
+                let mut walked = 0.0;
                 if let Some(pos) = pos {
+                    moved |= position.x != pos.x || position.y != pos.y || position.z != pos.z;
+                    if on_ground {
+                        walked = (pos.x - position.x).hypot(pos.z - position.z);
+                    }
                     position.x = pos.x;
                     position.z = pos.z;
                     if pos.y as i32 != position.y as i32 {
@@ -34,6 +125,12 @@ impl FalconServer {
                 }
 
                 let (chunk_x, chunk_z) = (position.chunk_x(), position.chunk_z());
+                if walked > 0.0 {
+                    player.statistics_mut().increment(Statistic::WalkOneCm, (walked * 100.0).round() as i32);
+                }
+                if moved {
+                    player.mark_active();
+                }
                 if chunk_x != old_chunk_x || chunk_z != old_chunk_z {
                     update_viewpos = true;
                     update_position = true;
@@ -43,7 +140,17 @@ impl FalconServer {
             None => (0, 0, 0, 0),
         };
         if update_position {
-            self.world.update_player_pos(self.players.get(&uuid).unwrap(), old_x, old_z, x, z);
+            let player = self.players.get(&uuid).unwrap();
+            self.world.update_player_pos(player, old_x, old_z, x, z);
+            self.tracker.update_player(player, &self.players);
+        }
+        if moved {
+            let player = self.players.get(&uuid).unwrap();
+            let teleport = EntityTeleportSpec::new(player.entity_id(), *player.position(), *player.look_angles(), on_ground);
+            for viewer in self.tracker.viewers_of(player.entity_id(), &self.players) {
+                viewer.connection().send_packet(teleport.clone(), falcon_send::write_entity_teleport);
+                viewer.connection().send_packet((player.entity_id(), player.look_angles().yaw), falcon_send::write_entity_head_look);
+            }
         }
         if update_viewpos {
             self.players
@@ -54,10 +161,181 @@ impl FalconServer {
         }
     }
 
+    /// Moves a player to `position`, returns `false` if the player is not
+    /// online.
+    pub fn teleport(&mut self, uuid: Uuid, position: Position) -> bool {
+        if !self.players.contains_key(&uuid) {
+            return false;
+        }
+        self.move_player(uuid, Some(position), None, false);
+        if let Some(player) = self.players.get_mut(&uuid) {
+            player.health_mut().reset_fall();
+            let position_look = PositionAndLookSpec::new(player.position(), player.look_angles(), TeleportFlags::empty(), 0);
+            player.connection().send_packet(position_look, falcon_send::write_position_look);
+        }
+        true
+    }
+
+    /// Shows a message to every player, `message` gives the text in the
+    /// locale of a player.
+    pub fn broadcast_message<F: Fn(&str) -> String>(&self, message: F) { self.send_message(&MessageTarget::Broadcast, message) }
+
+    /// Shows a message to the receivers of `target`, `message` gives the
+    /// text in the locale of a receiver.
+    pub fn send_message<M: Into<ChatComponent>, F: Fn(&str) -> M>(&self, target: &MessageTarget, message: F) {
+        if target.includes_console() {
+            info!("{}", message(default_locale()).into().to_plain());
+        }
+        let world = matches!(target, MessageTarget::World(_)).then(world_name);
+        for player in self.players.values() {
+            let uuid = player.uuid();
+            let receives = match target {
+                MessageTarget::Player(target) => *target == uuid,
+                MessageTarget::Players(targets) => targets.contains(&uuid),
+                MessageTarget::Console => false,
+                MessageTarget::Broadcast => true,
+                MessageTarget::Ops(level) => self.has_op_level(uuid, *level),
+                MessageTarget::Permission(node) => self.has_permission(uuid, node),
+                MessageTarget::World(name) => world.as_ref() == Some(name),
+            };
+            if receives {
+                player.send_message(message(player.locale()));
+            }
+        }
+    }
+
+    /// Sends a chunk that finished loading, unless the player left or moved
+    /// away from it in the meantime.
+    pub(crate) fn send_loaded_chunk(&mut self, uuid: Uuid, pos: ChunkPos) {
+        if let Some(player) = self.players.get(&uuid) {
+            let (chunk_x, chunk_z) = player.position().chunk_coords();
+            let view_distance = player.view_distance() as u32;
+            if chunk_x.abs_diff(pos.x) <= view_distance && chunk_z.abs_diff(pos.z) <= view_distance {
+                self.world.send_loaded(player, pos.x, pos.z);
+            }
+        }
+    }
+
     pub fn player_update_view_distance(&mut self, uuid: Uuid, view_distance: u8) {
         if let Some(player) = self.players.get_mut(&uuid) {
             self.world.update_view_distance(player, view_distance);
             player.set_view_distance(view_distance);
         }
+        if let Some(player) = self.players.get(&uuid) {
+            self.tracker.update_player(player, &self.players);
+        }
+    }
+
+    /// Changes the game mode of a player and updates the abilities and the
+    /// tab list entry, returns `false` if the player is not online.
+    pub fn set_game_mode(&mut self, uuid: Uuid, game_mode: GameMode) -> bool {
+        let Some(player) = self.players.get_mut(&uuid) else {
+            return false;
+        };
+        player.set_game_mode(game_mode);
+        let connection = player.connection();
+        connection.send_packet(ChangeGameStateSpec::game_mode(game_mode), falcon_send::write_change_game_state);
+        connection.send_packet(PlayerAbilitiesSpec::new(player.ability_flags(), 0.05, 0.1), falcon_send::write_player_abilities);
+
+        let update = PlayerInfoSpec::UpdateGameMode(vec![(uuid, game_mode)]);
+        for player in self.players.values() {
+            player.connection().send_packet(update.clone(), falcon_send::write_player_info);
+        }
+        true
+    }
+
+    /// Makes `uuid` an operator with `level`, or no operator for level 0,
+    /// and sends the new level to the player when online. Returns whether
+    /// anything changed.
+    pub fn set_op_level(&mut self, uuid: Uuid, name: String, level: u8) -> Result<bool, PlayerListError> {
+        let changed = if level == 0 { self.ops.deop(uuid)? } else { self.ops.op(uuid, name, level)? };
+        if let (true, Some(player)) = (changed, self.players.get(&uuid)) {
+            let status = EntityStatusSpec::op_level(player.entity_id(), self.ops.level(uuid));
+            player.connection().send_packet(status, falcon_send::write_entity_status);
+            self.send_commands(uuid);
+        }
+        Ok(changed)
+    }
+
+    pub fn player_update_latency(&mut self, uuid: Uuid, latency: Duration) {
+        if let Some(player) = self.players.get_mut(&uuid) {
+            player.update_ping(latency);
+        }
+    }
+
+    pub fn player_update_locale(&mut self, uuid: Uuid, locale: String) {
+        if let Some(player) = self.players.get_mut(&uuid) {
+            player.set_locale(locale);
+        }
+    }
+
+    pub fn player_update_recipe_book(&mut self, uuid: Uuid, book: RecipeBookType, open: bool, filtering: bool) {
+        if let Some(player) = self.players.get_mut(&uuid) {
+            player.recipe_book_mut().set(book, open, filtering);
+        }
+    }
+
+    /// Shows the arm swing of a player to the players that see it.
+    pub fn player_swing_arm(&mut self, uuid: Uuid, offhand: bool) {
+        let Some(player) = self.players.get_mut(&uuid) else {
+            return;
+        };
+        player.mark_active();
+        let animation = if offhand { EntityAnimation::SwingOffhand } else { EntityAnimation::SwingMainArm };
+        let spec = EntityAnimationSpec::new(player.entity_id(), animation);
+        for viewer in self.tracker.viewers_of(spec.entity_id, &self.players) {
+            viewer.connection().send_packet(spec.clone(), falcon_send::write_entity_animation);
+        }
+    }
+
+    pub fn player_set_sneaking(&mut self, uuid: Uuid, sneaking: bool) {
+        if let Some(player) = self.players.get_mut(&uuid) {
+            player.metadata_mut().sneaking = sneaking;
+            self.send_player_metadata(uuid);
+        }
+    }
+
+    pub fn player_set_sprinting(&mut self, uuid: Uuid, sprinting: bool) {
+        if let Some(player) = self.players.get_mut(&uuid) {
+            player.metadata_mut().sprinting = sprinting;
+            self.send_player_metadata(uuid);
+        }
+    }
+
+    /// Sends the metadata of a player to the players that see it.
+    fn send_player_metadata(&self, uuid: Uuid) {
+        if let Some(player) = self.players.get(&uuid) {
+            for viewer in self.tracker.viewers_of(player.entity_id(), &self.players) {
+                let spec = EntityMetadataSpec::new(player.entity_id(), EntityType::Player, player.metadata().clone(), viewer.protocol_version());
+                viewer.connection().send_packet(spec, falcon_send::write_entity_metadata);
+            }
+        }
+    }
+
+    /// Runs a chat message through the chat pipeline and shows it to every
+    /// player, or tells the sender why it was blocked.
+    pub fn player_chat(&mut self, uuid: Uuid, message: String) {
+        let Some(player) = self.players.get_mut(&uuid) else {
+            return;
+        };
+        player.mark_active();
+        let mut event = ChatEvent::new(uuid, player.username().to_string(), player.locale().to_string(), message);
+        if self.ops.contains(uuid) {
+            event.prefix = FalconConfig::global().chat.op_prefix.clone();
+        }
+        match self.chat.process(event, Instant::now()) {
+            Ok(Some(message)) => {
+                info!("{}", message.to_plain());
+                for player in self.players.values() {
+                    player.send_message(message.clone());
+                }
+            },
+            Ok(None) => {},
+            Err(reason) => {
+                if let Some(player) = self.players.get(&uuid) {
+                    player.send_message(reason);
+                }
+            },
+        }
     }
 }
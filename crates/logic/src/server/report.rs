@@ -0,0 +1,102 @@
+//! Timing of the server ticks.
+
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// How many of the last ticks a [`TickReport`] covers, 5 seconds at full
+/// speed.
+pub const REPORT_TICKS: usize = 100;
+
+/// The tick rate and tick durations of the last [`REPORT_TICKS`] ticks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TickReport {
+    /// Ticks per second, 20 when the server keeps up.
+    pub tps: f64,
+    /// The average milliseconds per tick.
+    pub mspt_mean: f64,
+    pub mspt_p50: f64,
+    pub mspt_p95: f64,
+    pub mspt_p99: f64,
+    pub mspt_max: f64,
+    /// The number of ticks this report is made of.
+    pub ticks: usize,
+}
+
+impl Display for TickReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.1} TPS, {:.2} MSPT (p50 {:.2}, p95 {:.2}, p99 {:.2}, max {:.2})",
+            self.tps, self.mspt_mean, self.mspt_p50, self.mspt_p95, self.mspt_p99, self.mspt_max
+        )
+    }
+}
+
+/// Keeps the start and duration of the last ticks.
+#[derive(Debug, Default)]
+pub(crate) struct TickTimes {
+    ticks: VecDeque<(Instant, Duration)>,
+}
+
+impl TickTimes {
+    pub fn record(&mut self, start: Instant, duration: Duration) {
+        if self.ticks.len() == REPORT_TICKS {
+            self.ticks.pop_front();
+        }
+        self.ticks.push_back((start, duration));
+    }
+
+    pub fn report(&self) -> TickReport {
+        let ticks = self.ticks.len();
+        if ticks == 0 {
+            return TickReport::default();
+        }
+        let mut mspt: Vec<f64> = self.ticks.iter().map(|(_, duration)| duration.as_secs_f64() * 1000.0).collect();
+        mspt.sort_unstable_by(f64::total_cmp);
+        let percentile = |p: usize| mspt[((ticks * p).div_ceil(100)).clamp(1, ticks) - 1];
+
+        let (first, _) = self.ticks[0];
+        let (last, duration) = self.ticks[ticks - 1];
+        // the last tick counts until it ended, a single tick has no rate yet
+        let span = (last + duration).duration_since(first).as_secs_f64();
+        let tps = if ticks > 1 && span > 0.0 { (ticks as f64 / span).min(super::tick::TPS as f64) } else { super::tick::TPS as f64 };
+        TickReport {
+            tps,
+            mspt_mean: mspt.iter().sum::<f64>() / ticks as f64,
+            mspt_p50: percentile(50),
+            mspt_p95: percentile(95),
+            mspt_p99: percentile(99),
+            mspt_max: mspt[ticks - 1],
+            ticks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::TickTimes;
+
+    #[test]
+    fn test_report() {
+        let mut times = TickTimes::default();
+        assert_eq!(times.report().ticks, 0);
+
+        let start = Instant::now();
+        for i in 0..150u64 {
+            // every tenth tick takes 100ms and overruns
+            let duration = if i % 10 == 9 { Duration::from_millis(100) } else { Duration::from_millis(i % 10) };
+            times.record(start + Duration::from_millis(50 * i), duration);
+        }
+        let report = times.report();
+        assert_eq!(report.ticks, 100);
+        assert_eq!(report.mspt_p50, 4.0);
+        assert_eq!(report.mspt_p95, 100.0);
+        assert_eq!(report.mspt_max, 100.0);
+        assert!((report.mspt_mean - 13.6).abs() < 1e-9);
+        // 100 ticks in 4.95s + 100ms
+        assert!((report.tps - 100.0 / 5.05).abs() < 1e-9);
+    }
+}
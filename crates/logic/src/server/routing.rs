@@ -0,0 +1,79 @@
+//! Routing of logins by the host name the client connected with, e.g. to
+//! implement forced hosts.
+
+use ahash::AHashMap;
+use falcon_core::network::VirtualHost;
+use falcon_core::player::data::{LookAngles, Position};
+use falcon_core::server::config::FalconConfig;
+
+use crate::connection::DisconnectReason;
+
+/// Where a player joins.
+pub enum LoginRoute {
+    /// At the spawn point of the config.
+    Default,
+    /// At the given spawn point.
+    Spawn(Position, LookAngles),
+    /// The player may not join.
+    Refuse(DisconnectReason),
+}
+
+/// Decides where players join, see [`FalconServer::with_router`].
+///
+/// [`FalconServer::with_router`]: crate::FalconServer::with_router
+pub trait HostRouter: Send + Sync {
+    /// Routes the login of `username`, `host` is `None` when the client
+    /// did not send a handshake this server understood.
+    fn route(&self, host: Option<&VirtualHost>, username: &str) -> LoginRoute;
+}
+
+/// Routes by the forced hosts of the config, players that connect with
+/// another host name join at the default spawn point.
+#[derive(Debug, Default)]
+pub struct ForcedHosts {
+    spawns: AHashMap<String, (Position, LookAngles)>,
+}
+
+impl ForcedHosts {
+    pub fn from_config() -> Self {
+        let mut hosts = ForcedHosts::default();
+        for forced in &FalconConfig::global().connection.forced_hosts {
+            hosts = hosts.with_host(&forced.host, forced.spawn_position, forced.spawn_look);
+        }
+        hosts
+    }
+
+    pub fn with_host(mut self, host: &str, position: Position, look: LookAngles) -> Self {
+        self.spawns.insert(VirtualHost::from_handshake(host, 0).host, (position, look));
+        self
+    }
+}
+
+impl HostRouter for ForcedHosts {
+    fn route(&self, host: Option<&VirtualHost>, _username: &str) -> LoginRoute {
+        match host.and_then(|host| self.spawns.get(&host.host)) {
+            Some(&(position, look)) => LoginRoute::Spawn(position, look),
+            None => LoginRoute::Default,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use falcon_core::network::VirtualHost;
+    use falcon_core::player::data::{LookAngles, Position};
+
+    use super::{ForcedHosts, HostRouter, LoginRoute};
+
+    #[test]
+    fn test_forced_hosts() {
+        let hosts = ForcedHosts::default().with_host("Lobby.example.com", Position::new(1.0, 2.0, 3.0), LookAngles::default());
+        let route = |address: &str| hosts.route(Some(&VirtualHost::from_handshake(address, 25565)), "Steve");
+
+        assert!(matches!(route("lobby.example.com"), LoginRoute::Spawn(position, _) if position.x == 1.0));
+        // trailing dot of a fully qualified name and Forge's marker
+        assert!(matches!(route("LOBBY.example.com.\0FML2\0"), LoginRoute::Spawn(..)));
+        assert!(matches!(route("example.com"), LoginRoute::Default));
+        assert!(matches!(hosts.route(None, "Steve"), LoginRoute::Default));
+    }
+}
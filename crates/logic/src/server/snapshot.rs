@@ -0,0 +1,19 @@
+use crate::server::FalconServer;
+use crate::world::snapshot::WorldSnapshot;
+
+impl FalconServer {
+    /// Puts back the chunks of a snapshot and sends them again to the
+    /// players that have them in view.
+    pub fn restore_snapshot(&mut self, snapshot: &WorldSnapshot) {
+        self.world.restore(snapshot);
+        for player in self.players.values() {
+            let (chunk_x, chunk_z) = player.position().chunk_coords();
+            let view_distance = player.view_distance() as u32;
+            for pos in snapshot.positions() {
+                if chunk_x.abs_diff(pos.x) <= view_distance && chunk_z.abs_diff(pos.z) <= view_distance {
+                    self.world.send_chunk(player, pos.x, pos.z);
+                }
+            }
+        }
+    }
+}
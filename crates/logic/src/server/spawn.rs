@@ -0,0 +1,110 @@
+use falcon_core::player::data::{LookAngles, Position, TeleportFlags};
+use falcon_core::server::config::{FalconConfig, VoidAction};
+use falcon_core::server::data::Difficulty;
+use falcon_core::world::seed::hashed_seed;
+use falcon_send::specs::play::{PlayerAbilitiesSpec, PositionAndLookSpec, RespawnSpec};
+use uuid::Uuid;
+
+use crate::player::health::{DamageCause, HealthState};
+use crate::player::void::{VoidEvent, VoidListener};
+use crate::server::FalconServer;
+
+impl FalconServer {
+    /// Where players join unless the router picks another place, set by
+    /// the `players.spawn_position` of the config.
+    pub fn world_spawn(&self) -> (Position, LookAngles) { self.world_spawn }
+
+    /// Moves the spawn for players that join after this, the spawn points
+    /// of online players stay.
+    pub fn set_world_spawn(&mut self, position: Position, look: LookAngles) { self.world_spawn = (position, look); }
+
+    pub fn add_void_listener(&mut self, listener: VoidListener) { self.void_listeners.push(listener); }
+
+    /// Runs the void listeners for a player below the void level of the
+    /// config, then teleports the player to its spawn point or kills it as
+    /// the event says.
+    pub(crate) fn check_void(&mut self, uuid: Uuid) {
+        let settings = &FalconConfig::global().void;
+        let Some(player) = self.players.get(&uuid).filter(|player| player.position().y < settings.level && !player.health().is_dead()) else {
+            return;
+        };
+        let mut event = VoidEvent {
+            player: uuid,
+            position: *player.position(),
+            action: settings.action,
+        };
+        let mut listeners = std::mem::take(&mut self.void_listeners);
+        for listener in &mut listeners {
+            listener(self, &mut event);
+        }
+        listeners.append(&mut self.void_listeners);
+        self.void_listeners = listeners;
+
+        match event.action {
+            VoidAction::Teleport => {
+                let Some(player) = self.players.get_mut(&uuid) else {
+                    return;
+                };
+                let (position, look) = player.spawn_point();
+                *player.look_angles_mut() = look;
+                self.teleport(uuid, position);
+            },
+            VoidAction::Kill => self.kill_player(uuid, DamageCause::Void),
+            VoidAction::Nothing => {},
+        }
+    }
+
+    /// The client asks to respawn from its death screen, players that are
+    /// alive stay where they are.
+    pub fn player_respawn(&mut self, uuid: Uuid) {
+        if self.players.get(&uuid).is_some_and(|player| player.health().is_dead()) {
+            self.respawn(uuid);
+        }
+    }
+
+    /// Resets a player to its spawn point with full health: the client gets
+    /// a fresh world with the chunks, entities and players around the spawn
+    /// point.
+    /// Returns `false` if the player is not online.
+    pub fn respawn(&mut self, uuid: Uuid) -> bool {
+        let Some(player) = self.players.get_mut(&uuid) else {
+            return false;
+        };
+        let respawn = RespawnSpec::new(
+            player.dimension(),
+            Difficulty::Peaceful,
+            player.gamemode(),
+            String::from("customized"),
+            hashed_seed(FalconConfig::global().server.seed),
+        );
+        player.connection().send_packet(respawn, falcon_send::write_respawn);
+        player
+            .connection()
+            .send_packet(PlayerAbilitiesSpec::new(player.ability_flags(), 0.05, 0.1), falcon_send::write_player_abilities);
+
+        let (old_x, old_z) = player.position().chunk_coords();
+        let (position, look) = player.spawn_point();
+        *player.position_mut() = position;
+        *player.look_angles_mut() = look;
+        *player.health_mut() = HealthState::default();
+        player.mark_active();
+
+        let player = self.players.get(&uuid).unwrap();
+        let (x, z) = position.chunk_coords();
+        // only 1.16+ clients throw their world away when respawning in the same dimension
+        if player.protocol_version() >= 735 {
+            self.world.send_chunks_for_player(player);
+        } else {
+            self.world.update_player_pos(player, old_x, old_z, x, z);
+        }
+        player.connection().send_packet((x, z), falcon_send::write_update_viewpos);
+        let position_look = PositionAndLookSpec::new(player.position(), player.look_angles(), TeleportFlags::empty(), 0);
+        player.connection().send_packet(position_look, falcon_send::write_position_look);
+        player.send_spawn_position();
+        player.send_health();
+
+        self.tracker.remove_player(player, &self.players);
+        self.tracker.update_player(player, &self.players);
+        true
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use falcon_core::player::stats::{PlayerStatistics, Statistic};
+use falcon_core::server::config::FalconConfig;
+use falcon_send::specs::play::StatisticsSpec;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::server::FalconServer;
+
+impl FalconServer {
+    /// Reads the statistics of `uuid` from the statistics directory of the
+    /// config. A file that cannot be read is left alone and the player
+    /// starts without statistics.
+    pub(crate) fn load_statistics(uuid: Uuid) -> PlayerStatistics {
+        let path = Path::new(&FalconConfig::global().players.statistics_dir).join(format!("{}.json", uuid));
+        PlayerStatistics::load(path)
+            .inspect_err(|error| warn!(%uuid, %error, "Could not load statistics"))
+            .unwrap_or_default()
+    }
+
+    /// Saves the statistics of every online player.
+    pub fn save_statistics(&self) {
+        for player in self.players.values() {
+            if let Err(error) = player.statistics().save() {
+                error!(uuid = %player.uuid(), %error, "Could not save statistics");
+            }
+        }
+    }
+
+    pub fn increment_statistic(&mut self, uuid: Uuid, statistic: Statistic, amount: i32) {
+        if let Some(player) = self.players.get_mut(&uuid) {
+            player.statistics_mut().increment(statistic, amount);
+        }
+    }
+
+    /// Sends the statistics of a player to its statistics screen.
+    pub fn player_request_statistics(&mut self, uuid: Uuid) {
+        if let Some(player) = self.players.get(&uuid) {
+            let statistics = player.statistics().iter().map(|(statistic, value)| (statistic.clone(), value)).collect();
+            let spec = StatisticsSpec::new(statistics, player.protocol_version());
+            player.connection().send_packet(spec, falcon_send::write_statistics);
+        }
+    }
+
+    /// Counts a tick of play time for every player.
+    pub(crate) fn tick_play_time(&mut self) {
+        for player in self.players.values_mut() {
+            player.statistics_mut().increment(Statistic::PlayTime, 1);
+        }
+    }
+}
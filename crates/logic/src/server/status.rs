@@ -0,0 +1,184 @@
+//! The server list, answered by the connections themselves.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use ahash::AHashMap;
+use bytes::{Bytes, BytesMut};
+use falcon_core::chat::ChatComponent;
+use falcon_core::network::registry::{ProtocolRegistry, ProtocolTable};
+use falcon_core::network::ConnectionState;
+use falcon_core::server::config::FalconConfig;
+use falcon_core::server::data::{supported_versions, ServerVersion};
+use falcon_packet_core::WriteError;
+use falcon_send::specs::status::{PlayerData, StatusResponseSpec};
+use tracing::debug;
+
+use crate::connection::dump;
+use crate::connection::reader::LegacyPing;
+use crate::FalconConnection;
+
+/// The most protocols a response is kept for, clients can announce any
+/// protocol they like.
+const MAX_CACHED_PROTOCOLS: usize = 64;
+
+/// The encoded status responses per protocol, a flood of server list pings
+/// then only costs a clone of the bytes instead of serializing the JSON.
+/// The responses are dropped once the number of online players changes,
+/// the rest of the response comes from the config.
+#[derive(Debug, Default)]
+struct StatusCache {
+    online: usize,
+    responses: AHashMap<i32, (&'static str, Bytes)>,
+    /// The cached protocols, oldest first.
+    order: VecDeque<i32>,
+}
+
+impl StatusCache {
+    fn get(&self, protocol: i32, online: usize) -> Option<(&'static str, Bytes)> {
+        if self.online != online {
+            return None;
+        }
+        self.responses.get(&protocol).cloned()
+    }
+
+    /// Caches `response`, evicting the oldest protocol when full.
+    fn insert(&mut self, protocol: i32, online: usize, response: (&'static str, Bytes)) {
+        if self.online != online {
+            self.online = online;
+            self.responses.clear();
+            self.order.clear();
+        }
+        if self.responses.contains_key(&protocol) {
+            return;
+        }
+        if self.order.len() >= MAX_CACHED_PROTOCOLS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.responses.remove(&oldest);
+            }
+        }
+        self.responses.insert(protocol, response);
+        self.order.push_back(protocol);
+    }
+}
+
+/// What the server list shows, shared by the server and its connections so
+/// connections answer server list pings without waking up the server.
+#[derive(Debug, Default)]
+pub struct ServerStatus {
+    online: AtomicUsize,
+    cache: Mutex<StatusCache>,
+}
+
+impl ServerStatus {
+    /// The number of players online, as last told by the server.
+    pub fn online(&self) -> usize { self.online.load(Ordering::Relaxed) }
+
+    pub(crate) fn set_online(&self, online: usize) { self.online.store(online, Ordering::Relaxed); }
+
+    /// The encoded status response for `protocol`, the JSON is encoded
+    /// without holding the cache so other connections are not blocked.
+    pub fn response(&self, protocol: i32) -> Result<(&'static str, Bytes), WriteError> {
+        let online = self.online();
+        if let Some(response) = self.cache().get(protocol, online) {
+            return Ok(response);
+        }
+        let version = ServerVersion::new(supported_versions(), protocol);
+        let player_data = PlayerData::new(FalconConfig::global().server.max_players, online as i32);
+        let description = ChatComponent::from_markup(&FalconConfig::global().server.description).for_protocol(protocol);
+        // encoded packets carry the ids of the base protocol
+        let base = ProtocolRegistry::global().get(protocol).map_or(protocol, ProtocolTable::base);
+        let response = encode(StatusResponseSpec::new(version, player_data, description), base, falcon_send::write_status_response)?;
+        self.cache().insert(protocol, online, response.clone());
+        Ok(response)
+    }
+
+    /// The cache only holds finished responses, it can be used even if a
+    /// thread panicked while holding it.
+    fn cache(&self) -> MutexGuard<'_, StatusCache> { self.cache.lock().unwrap_or_else(PoisonError::into_inner) }
+
+    /// The kick packet pre-1.7 clients expect as answer to a server list
+    /// ping.
+    pub fn legacy_response(&self, ping: LegacyPing) -> Vec<u8> {
+        let config = &FalconConfig::global().server;
+        let (online, max) = (self.online(), config.max_players);
+        let description = ChatComponent::from_markup(&config.description);
+        let response = match ping {
+            // the beta format uses § as separator
+            LegacyPing::Beta => format!("{}§{}§{}", description.to_plain().replace('§', ""), online, max),
+            // protocol 127 is not used by any legacy client, they will show the version as incompatible
+            LegacyPing::V1_4 => format!("§1\0127\0{}\0{}\0{}\0{}", supported_versions(), description.to_legacy(), online, max),
+        };
+        legacy_kick(&response)
+    }
+}
+
+impl FalconConnection {
+    /// Answers a status request, the server is not involved.
+    pub fn send_status(&mut self) {
+        match self.server().status().response(self.state().client_protocol()) {
+            Ok((name, bytes)) => {
+                if let Err(error) = self.send_encoded(name, bytes) {
+                    debug!(%error, "Could not send the status response");
+                }
+            },
+            Err(error) => debug!(%error, "Could not encode the status response"),
+        }
+    }
+
+    /// Answers a pre-1.7 server list ping, the connection is closed
+    /// afterwards.
+    pub fn send_legacy_status(&mut self, ping: LegacyPing) {
+        let response = self.server().status().legacy_response(ping);
+        self.send_raw(&response);
+        self.state_mut().connection_state = ConnectionState::Disconnected;
+    }
+}
+
+/// Writes a packet for `protocol` up front, to be sent with
+/// [`FalconConnection::send_encoded`](crate::FalconConnection::send_encoded).
+fn encode<T, F>(packet: T, protocol: i32, write_fn: F) -> Result<(&'static str, Bytes), WriteError>
+where
+    F: FnOnce(T, &mut BytesMut, i32) -> Result<bool, WriteError>,
+{
+    let mut buffer = BytesMut::new();
+    write_fn(packet, &mut buffer, protocol)?;
+    Ok((dump::write_fn_name::<F>(), buffer.freeze()))
+}
+
+/// Encodes a legacy kick packet, a UTF-16BE string prefixed by its length.
+fn legacy_kick(reason: &str) -> Vec<u8> {
+    let chars: Vec<u16> = reason.encode_utf16().collect();
+    let mut data = Vec::with_capacity(3 + chars.len() * 2);
+    data.push(0xFF);
+    data.extend_from_slice(&(chars.len() as u16).to_be_bytes());
+    chars.iter().for_each(|c| data.extend_from_slice(&c.to_be_bytes()));
+    data
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::Bytes;
+
+    use super::{StatusCache, MAX_CACHED_PROTOCOLS};
+
+    fn response(protocol: i32) -> (&'static str, Bytes) { ("status_response", Bytes::from(protocol.to_string())) }
+
+    #[test]
+    fn test_cache_eviction() {
+        let mut cache = StatusCache::default();
+        for protocol in 0..MAX_CACHED_PROTOCOLS as i32 {
+            cache.insert(protocol, 1, response(protocol));
+        }
+        cache.insert(-1, 1, response(-1));
+        assert_eq!(cache.get(0, 1), None);
+        assert_eq!(cache.get(1, 1), Some(response(1)));
+        assert_eq!(cache.get(-1, 1), Some(response(-1)));
+        assert_eq!(cache.responses.len(), MAX_CACHED_PROTOCOLS);
+
+        assert_eq!(cache.get(1, 2), None);
+        cache.insert(1, 2, response(1));
+        assert_eq!(cache.responses.len(), 1);
+    }
+}
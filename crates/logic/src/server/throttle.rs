@@ -0,0 +1,62 @@
+//! Limits how often an address may try to log in.
+
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use ahash::AHashMap;
+
+/// Refuses login attempts that follow the previous attempt of the same
+/// address within an interval. Loopback addresses are never throttled,
+/// those are usually a proxy on the same machine.
+#[derive(Debug)]
+pub struct LoginThrottle {
+    interval: Duration,
+    attempts: AHashMap<IpAddr, Instant>,
+}
+
+impl LoginThrottle {
+    /// An interval of zero throttles nothing.
+    pub fn new(interval: Duration) -> Self {
+        LoginThrottle {
+            interval,
+            attempts: AHashMap::new(),
+        }
+    }
+
+    /// Records a login attempt of `address`, returns whether it should be
+    /// refused. Refused attempts count too, spamming keeps an address
+    /// throttled.
+    pub fn throttle(&mut self, address: IpAddr, now: Instant) -> bool {
+        if self.interval.is_zero() || address.is_loopback() {
+            return false;
+        }
+        let interval = self.interval;
+        self.attempts.retain(|_, last| now.saturating_duration_since(*last) < interval);
+        self.attempts.insert(address, now).is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::time::{Duration, Instant};
+
+    use super::LoginThrottle;
+
+    #[test]
+    fn test_throttle() {
+        let mut throttle = LoginThrottle::new(Duration::from_secs(4));
+        let (a, b): (IpAddr, IpAddr) = ("10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap());
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        assert!(!throttle.throttle(a, at(0)));
+        assert!(!throttle.throttle(b, at(1)));
+        assert!(throttle.throttle(a, at(3)));
+        // the refused attempt restarted the interval
+        assert!(throttle.throttle(a, at(6)));
+        assert!(!throttle.throttle(a, at(10)));
+        assert!(!throttle.throttle("127.0.0.1".parse().unwrap(), at(10)));
+        assert!(!throttle.throttle("127.0.0.1".parse().unwrap(), at(10)));
+    }
+}
@@ -1,37 +1,66 @@
+use std::convert::Infallible;
 use std::time::Duration;
 
+use falcon_core::server::config::FalconConfig;
+use falcon_send::specs::play::PlayerInfoSpec;
 use tokio::runtime::Builder;
-use tokio::time::MissedTickBehavior;
-use tracing::{debug, debug_span, error, info};
+use tokio::time::{sleep_until, Instant};
+use tracing::{debug, debug_span, error, warn};
 
-use super::ServerTask;
+use super::{ServerTask, TaskKind};
+use crate::connection::DisconnectReason;
+use crate::panic::{catch_panic, CatchPanic};
 use crate::FalconServer;
 
+/// The ticks per second the server aims for.
+pub(crate) const TPS: u32 = 20;
+const TICK_DURATION: Duration = Duration::from_millis(1000 / TPS as u64);
+/// Overrun ticks are caught up on by running the next ticks right away,
+/// unless the server fell further behind than this.
+const MAX_CATCH_UP: Duration = Duration::from_secs(2);
+/// How often the tab list latencies are updated, the same as vanilla.
+const LATENCY_UPDATE_INTERVAL: Duration = Duration::from_secs(30);
+/// How often players are checked against the idle timeout.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 impl FalconServer {
     #[tracing::instrument(name = "server", skip(self))]
     pub fn start(&mut self) {
         debug!("Starting server logic!");
-        let rt = Builder::new_current_thread().enable_all().build().unwrap();
+        // chunk packets are encoded on the blocking threads
+        let rt = Builder::new_current_thread()
+            .max_blocking_threads(FalconConfig::global().network.chunk_workers())
+            .thread_name("Chunk Encoder")
+            .enable_all()
+            .build()
+            .unwrap();
 
         rt.block_on(async move {
-            let mut tick_interval = tokio::time::interval(Duration::from_millis(50));
-            tick_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
-            let mut keep_alive_interval = tokio::time::interval(Duration::from_secs(12));
-            keep_alive_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
-
+            let mut next_tick = Instant::now();
             while !self.should_stop {
+                let start = Instant::now();
+                self.tick().await;
+                self.tick_times.record(start.into_std(), start.elapsed());
+                self.tick_count += 1;
+
+                next_tick += TICK_DURATION;
+                let behind = Instant::now().saturating_duration_since(next_tick);
+                if behind > MAX_CATCH_UP {
+                    warn!(
+                        "Can't keep up! Is the server overloaded? Running {}ms or {} ticks behind",
+                        behind.as_millis(),
+                        behind.as_millis() / TICK_DURATION.as_millis()
+                    );
+                    next_tick = Instant::now();
+                }
                 tokio::select! {
-                    _ = tick_interval.tick() => {
-                        self.tick().await;
-                    }
-                    _ = keep_alive_interval.tick() => {
-                        self.keep_alive();
-                    }
+                    _ = sleep_until(next_tick) => {}
                     _ = self.shutdown_handle().wait_for_shutdown() => {
                         break;
                     }
                 }
             }
+            self.save_statistics();
             debug!("Stopping server logic!");
         });
     }
@@ -40,27 +69,126 @@ impl FalconServer {
     #[tracing::instrument(skip(self), fields(player_count = self.online_count()))]
     async fn tick(&mut self) {
         while let Ok(task) = self.receiver.try_recv() {
-            let span = debug_span!("server_task");
-            let _enter = span.enter();
-            if let Err(error) = match task {
-                ServerTask::Sync(task) => task.run(self),
-                ServerTask::Async(task) => task.run(self).await,
-            } {
-                error!(%error);
-            }
+            self.run_task(task).await;
         }
         while let Ok(command) = self.console_rx.try_recv() {
-            info!(cmd = %command.trim(), "Console command execution");
-            // TODO: better commands
-            if command.trim() == "stop" {
-                info!("Shutting down server! (Stop command executed)");
-                self.should_stop = true;
-                self.shutdown_handle().send_shutdown();
+            if let Err(panic) = catch_panic(|| self.console_command(command.trim())) {
+                error!(%panic, "Console command panicked");
+            }
+            if self.should_stop {
                 return;
             }
         }
+        self.poll_chunk_loads();
+        self.world.send_queued(&self.players);
+        self.tick_play_time();
+        self.tick_health();
+        if self.every(FalconConfig::global().network.keep_alive_interval()) {
+            self.keep_alive();
+        }
+        if self.every(LATENCY_UPDATE_INTERVAL) {
+            self.update_latency();
+        }
+        if self.every(IDLE_CHECK_INTERVAL) {
+            self.kick_idle_players();
+        }
+    }
+
+    /// Whether the current tick is one of every `interval`, this includes
+    /// the first tick.
+    fn every(&self, interval: Duration) -> bool {
+        let ticks = (interval.as_millis() / TICK_DURATION.as_millis()).max(1) as u64;
+        self.tick_count.is_multiple_of(ticks)
+    }
+
+    /// Waits for the next task and runs it, returns `false` if no task can
+    /// be received anymore.
+    pub(crate) async fn run_next_task(&mut self) -> bool {
+        match self.receiver.recv().await {
+            Some(task) => {
+                self.run_task(task).await;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Runs the next task if one is ready, returns `false` otherwise.
+    pub(crate) async fn try_run_task(&mut self) -> bool {
+        match self.receiver.try_recv() {
+            Ok(task) => {
+                self.run_task(task).await;
+                true
+            },
+            Err(_) => false,
+        }
+    }
+
+    async fn run_task(&mut self, task: ServerTask) {
+        let span = debug_span!("server_task");
+        let _enter = span.enter();
+        // a panicking task is dropped, the rest of the server keeps running
+        let panic = match match task.kind {
+            TaskKind::Sync(run) => catch_panic(|| run.run(self)),
+            TaskKind::Async(run) => CatchPanic(run.run(self)).await,
+        } {
+            Ok(Ok(())) => return,
+            Ok(Err(error)) => {
+                error!(%error);
+                return;
+            },
+            Err(panic) => panic,
+        };
+        match panic.backtrace() {
+            Some(backtrace) => error!(%panic, "Server task panicked\n{}", backtrace),
+            None => error!(%panic, "Server task panicked"),
+        }
+        // the connection leaves the server once disconnected, which removes
+        // its player in whatever state the task left it
+        if let Some(origin) = task.origin {
+            let reason = DisconnectReason::Internal(String::from(panic.message()));
+            origin.execute(move |connection| {
+                connection.disconnect(reason);
+                Ok::<(), Infallible>(())
+            });
+        }
+    }
+
+    /// Runs the callbacks of the chunks that finished loading.
+    fn poll_chunk_loads(&mut self) {
+        for (pos, callbacks) in self.world.poll_loaded() {
+            for callback in callbacks {
+                callback(self, pos);
+            }
+        }
     }
 
     #[tracing::instrument(skip(self), fields(player_count = self.players.len()))]
     fn keep_alive(&mut self) { self.players.values().for_each(|player| player.send_keep_alive()); }
+
+    /// Kicks the players that were idle for longer than the idle timeout of
+    /// the config.
+    fn kick_idle_players(&mut self) {
+        let minutes = FalconConfig::global().players.idle_timeout;
+        if minutes == 0 {
+            return;
+        }
+        let timeout = Duration::from_secs(minutes as u64 * 60);
+        for player in self.players.values().filter(|player| player.idle_time() >= timeout) {
+            debug!(name = player.username(), "Kicking idle player");
+            player.kick(DisconnectReason::Idle);
+        }
+    }
+
+    /// Sends the latency of every player to the tab list of every player.
+    #[tracing::instrument(skip(self), fields(player_count = self.players.len()))]
+    fn update_latency(&mut self) {
+        if self.players.is_empty() {
+            return;
+        }
+        let update = PlayerInfoSpec::UpdateLatency(self.players.values().map(|player| (player.uuid(), player.ping() as i32)).collect());
+        for player in self.players.values() {
+            player.connection().send_packet(update.clone(), falcon_send::write_player_info);
+        }
+    }
 }
@@ -1,22 +1,42 @@
 use std::convert::Infallible;
 use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
-use falcon_core::player::data::Position;
+use falcon_core::player::data::{Position, RecipeBookType};
+use falcon_core::world::entity::EntityInteraction;
+use falcon_core::player::profile::{GameProfile, ProfileProperty};
 use tokio::sync::mpsc::UnboundedSender;
 use uuid::Uuid;
 
-use super::{ServerTask, SyncServerTask};
+use super::auth::LoginInfo;
+use super::status::ServerStatus;
+use super::{ServerTask, SyncServerTask, TaskKind};
 use crate::connection::ConnectionWrapper;
 use crate::FalconServer;
 
 #[derive(Debug)]
 pub struct ServerWrapper {
     link: UnboundedSender<ServerTask>,
+    status: Arc<ServerStatus>,
+    origin: Option<ConnectionWrapper>,
 }
 
 impl ServerWrapper {
-    pub fn new(link: UnboundedSender<ServerTask>) -> Self { Self { link } }
+    /// Links to the server behind `link`, `status` is the one of that
+    /// server, see [`FalconServer::status`].
+    pub fn new(link: UnboundedSender<ServerTask>, status: Arc<ServerStatus>) -> Self { Self { link, status, origin: None } }
+
+    /// Sends the tasks on behalf of `connection`, it is disconnected when
+    /// one of them panics.
+    pub fn with_origin(mut self, connection: ConnectionWrapper) -> Self {
+        self.origin = Some(connection);
+        self
+    }
+
+    /// The server list, answered without the server.
+    pub fn status(&self) -> &ServerStatus { &self.status }
 
     /// Do not pass a `Box` to this function.
     #[inline]
@@ -26,7 +46,7 @@ impl ServerWrapper {
     {
         // SAFE: if this channel returns an error, the server will have shut down
         // already.
-        self.link.send(ServerTask::Sync(Box::new(task))).ok();
+        self.link.send(ServerTask::new(TaskKind::Sync(Box::new(task)), self.origin.clone())).ok();
     }
 
     /// Do not pass a `Box` to this function.
@@ -40,16 +60,23 @@ impl ServerWrapper {
 }
 
 impl ServerWrapper {
-    pub fn request_status(&self, protocol: i32, connection: ConnectionWrapper) {
+    pub fn player_login(&self, username: String, login: LoginInfo, connection: ConnectionWrapper) {
+        self.execute(move |server| {
+            server.player_login(username, login, connection);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_login_with_profile(&self, username: String, properties: Vec<ProfileProperty>, login: LoginInfo, connection: ConnectionWrapper) {
         self.execute(move |server| {
-            server.request_status(protocol, connection);
+            server.player_login_with_profile(username, properties, login, connection);
             Ok::<(), Infallible>(())
         });
     }
 
-    pub fn player_login(&self, username: String, protocol: i32, connection: ConnectionWrapper) {
+    pub fn player_login_authenticated(&self, profile: GameProfile, login: LoginInfo, connection: ConnectionWrapper) {
         self.execute(move |server| {
-            server.player_login(username, protocol, connection);
+            server.player_login_authenticated(profile, login, connection);
             Ok::<(), Infallible>(())
         });
     }
@@ -68,6 +95,83 @@ impl ServerWrapper {
         });
     }
 
+    pub fn player_update_latency(&self, uuid: Uuid, latency: Duration) {
+        self.execute(move |server| {
+            server.player_update_latency(uuid, latency);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_update_locale(&self, uuid: Uuid, locale: String) {
+        self.execute(move |server| {
+            server.player_update_locale(uuid, locale);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_update_recipe_book(&self, uuid: Uuid, book: RecipeBookType, open: bool, filtering: bool) {
+        self.execute(move |server| {
+            server.player_update_recipe_book(uuid, book, open, filtering);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_swing_arm(&self, uuid: Uuid, offhand: bool) {
+        self.execute(move |server| {
+            server.player_swing_arm(uuid, offhand);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_set_sneaking(&self, uuid: Uuid, sneaking: bool) {
+        self.execute(move |server| {
+            server.player_set_sneaking(uuid, sneaking);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_set_sprinting(&self, uuid: Uuid, sprinting: bool) {
+        self.execute(move |server| {
+            server.player_set_sprinting(uuid, sprinting);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_interact_entity(&self, uuid: Uuid, target: i32, interaction: EntityInteraction, sneaking: bool) {
+        self.execute(move |server| {
+            server.player_interact_entity(uuid, target, interaction, sneaking);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_chat(&self, uuid: Uuid, message: String) {
+        self.execute(move |server| {
+            server.player_chat(uuid, message);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_command(&self, uuid: Uuid, command: String) {
+        self.execute(move |server| {
+            server.player_command(uuid, command);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_respawn(&self, uuid: Uuid) {
+        self.execute(move |server| {
+            server.player_respawn(uuid);
+            Ok::<(), Infallible>(())
+        });
+    }
+
+    pub fn player_request_statistics(&self, uuid: Uuid) {
+        self.execute(move |server| {
+            server.player_request_statistics(uuid);
+            Ok::<(), Infallible>(())
+        });
+    }
+
     pub fn player_leave(&self, uuid: Uuid) {
         self.execute(move |server| {
             server.player_leave(uuid);
@@ -80,6 +184,8 @@ impl Clone for ServerWrapper {
     fn clone(&self) -> Self {
         Self {
             link: self.link.clone(),
+            status: self.status.clone(),
+            origin: self.origin.clone(),
         }
     }
 }
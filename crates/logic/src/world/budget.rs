@@ -0,0 +1,125 @@
+//! Sending chunks at the pace of the connection of each player.
+//!
+//! Chunks wait in a queue per player and a few are sent each tick. The
+//! number grows by one each tick the connection keeps up and is halved once
+//! it falls behind, so slow clients do not pile up hundreds of chunks while
+//! fast ones load the terrain quickly.
+
+use ahash::AHashSet;
+use falcon_core::world::chunks::ChunkPos;
+
+/// The bytes a connection may have queued before it falls behind.
+const TARGET_BACKLOG: usize = 256 * 1024;
+/// The chunks sent each tick to a player that just joined.
+const INITIAL_CHUNKS: usize = 4;
+
+/// The number of chunks sent to a player each tick.
+#[derive(Debug)]
+pub struct ChunkBudget {
+    chunks: usize,
+    max: usize,
+}
+
+impl ChunkBudget {
+    pub fn new(max: usize) -> Self {
+        let max = max.max(1);
+        ChunkBudget {
+            chunks: INITIAL_CHUNKS.min(max),
+            max,
+        }
+    }
+
+    /// The chunks sent each tick while the connection keeps up.
+    pub fn chunks(&self) -> usize { self.chunks }
+
+    /// Adapts the budget to the bytes the connection still has queued and
+    /// returns the chunks to send this tick, none while it is behind.
+    pub fn update(&mut self, backlog: usize) -> usize {
+        if backlog > TARGET_BACKLOG {
+            self.chunks = (self.chunks / 2).max(1);
+            return 0;
+        }
+        self.chunks = (self.chunks + 1).min(self.max);
+        self.chunks
+    }
+}
+
+/// The chunks that still have to be sent to a player.
+#[derive(Debug)]
+pub(crate) struct ChunkQueue {
+    pending: AHashSet<ChunkPos>,
+    budget: ChunkBudget,
+}
+
+impl ChunkQueue {
+    pub fn new(max_chunks: usize) -> Self {
+        ChunkQueue {
+            pending: AHashSet::new(),
+            budget: ChunkBudget::new(max_chunks),
+        }
+    }
+
+    pub fn push(&mut self, pos: ChunkPos) { self.pending.insert(pos); }
+
+    pub fn is_empty(&self) -> bool { self.pending.is_empty() }
+
+    /// Takes the chunks to send this tick, the nearest to `center` first.
+    /// Chunks further than `view_distance` are dropped, the player moved
+    /// away from them.
+    pub fn next(&mut self, center: (i32, i32), view_distance: u8, backlog: usize) -> Vec<ChunkPos> {
+        let distance = |pos: &ChunkPos| center.0.abs_diff(pos.x).max(center.1.abs_diff(pos.z));
+        self.pending.retain(|pos| distance(pos) <= view_distance as u32);
+        if self.pending.is_empty() {
+            return Vec::new();
+        }
+        let count = self.budget.update(backlog);
+        let mut chunks: Vec<ChunkPos> = self.pending.iter().copied().collect();
+        chunks.sort_unstable_by_key(distance);
+        chunks.truncate(count);
+        for pos in &chunks {
+            self.pending.remove(pos);
+        }
+        chunks
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_budget() {
+        let mut budget = ChunkBudget::new(8);
+        assert_eq!(budget.update(0), 5);
+        assert_eq!(budget.update(TARGET_BACKLOG), 6);
+        for _ in 0..10 {
+            budget.update(0);
+        }
+        assert_eq!(budget.chunks(), 8);
+
+        // a slow connection gets nothing until it caught up
+        assert_eq!(budget.update(TARGET_BACKLOG + 1), 0);
+        assert_eq!(budget.chunks(), 4);
+        budget.update(TARGET_BACKLOG + 1);
+        budget.update(TARGET_BACKLOG + 1);
+        budget.update(TARGET_BACKLOG + 1);
+        assert_eq!(budget.chunks(), 1);
+        assert_eq!(budget.update(0), 2);
+    }
+
+    #[test]
+    fn test_queue() {
+        let mut queue = ChunkQueue::new(32);
+        for x in -3..=3 {
+            queue.push(ChunkPos::new(x, 0));
+        }
+        queue.push(ChunkPos::new(0, 0));
+
+        let chunks = queue.next((0, 0), 2, 0);
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(chunks[0], ChunkPos::new(0, 0));
+        assert!(chunks.iter().all(|pos| pos.x.abs() <= 2));
+        // the chunks out of view are dropped
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+//! The chunks of a world that are in memory.
+
+use std::cell::Cell;
+
+use ahash::AHashMap;
+use falcon_core::world::chunks::{Chunk, ChunkPos};
+use tracing::debug;
+
+/// The hits and misses of a [`ChunkCache`] and its memory use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub chunks: usize,
+    /// The estimated memory of the chunks in bytes.
+    pub memory: usize,
+    /// The memory budget in bytes, if any.
+    pub budget: Option<usize>,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// The share of lookups that found the chunk in memory, 0 to 1.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 1.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CachedChunk {
+    chunk: Chunk,
+    memory: usize,
+    last_used: Cell<u64>,
+}
+
+/// Keeps chunks within a memory budget, the least recently used chunks
+/// that are not dirty are evicted first. Dirty chunks are never evicted,
+/// they cannot be loaded again.
+#[derive(Debug, Default)]
+pub struct ChunkCache {
+    chunks: AHashMap<ChunkPos, CachedChunk>,
+    budget: Option<usize>,
+    memory: usize,
+    clock: Cell<u64>,
+    hits: Cell<u64>,
+    misses: Cell<u64>,
+    evictions: u64,
+}
+
+impl ChunkCache {
+    pub fn with_capacity(capacity: usize) -> Self {
+        ChunkCache {
+            chunks: AHashMap::with_capacity(capacity),
+            ..Default::default()
+        }
+    }
+
+    /// Evicts chunks once they use more than `budget` bytes, `None` keeps
+    /// every chunk.
+    pub fn set_budget(&mut self, budget: Option<usize>) {
+        self.budget = budget;
+        self.evict();
+    }
+
+    /// Looks up a chunk without counting it as a hit or a miss.
+    pub fn peek(&self, pos: ChunkPos) -> Option<&Chunk> { self.chunks.get(&pos).map(|cached| &cached.chunk) }
+
+    /// Looks up a chunk, this counts as a hit or a miss.
+    pub fn get(&self, pos: ChunkPos) -> Option<&Chunk> {
+        match self.chunks.get(&pos) {
+            Some(cached) => {
+                self.hits.set(self.hits.get() + 1);
+                cached.last_used.set(self.tick());
+                Some(&cached.chunk)
+            },
+            None => {
+                self.misses.set(self.misses.get() + 1);
+                None
+            },
+        }
+    }
+
+    /// The chunk at `pos`, an empty one is added if there is none. The
+    /// chunk is marked dirty.
+    pub fn get_mut(&mut self, pos: ChunkPos) -> &mut Chunk {
+        let last_used = self.tick();
+        let cached = self.chunks.entry(pos).or_insert_with(|| CachedChunk {
+            chunk: Chunk::empty(pos),
+            memory: 0,
+            last_used: Cell::new(last_used),
+        });
+        cached.last_used.set(last_used);
+        // the size may change, it is updated when chunks are evicted
+        cached.chunk.mark_dirty(true);
+        &mut cached.chunk
+    }
+
+    pub fn contains(&self, pos: ChunkPos) -> bool { self.chunks.contains_key(&pos) }
+
+    pub fn insert(&mut self, chunk: Chunk) {
+        let pos = *chunk.get_position();
+        let memory = chunk.memory_size();
+        let cached = CachedChunk {
+            chunk,
+            memory,
+            last_used: Cell::new(self.tick()),
+        };
+        self.memory += memory;
+        if let Some(old) = self.chunks.insert(pos, cached) {
+            self.memory -= old.memory;
+        }
+        self.evict();
+    }
+
+    /// The chunks in memory, this does not count as using them.
+    pub fn iter(&self) -> impl Iterator<Item = &Chunk> { self.chunks.values().map(|cached| &cached.chunk) }
+
+    /// Forgets a chunk, a provider loads it again when it is needed.
+    pub fn remove(&mut self, pos: ChunkPos) -> Option<Chunk> {
+        let cached = self.chunks.remove(&pos)?;
+        self.memory -= cached.memory;
+        Some(cached.chunk)
+    }
+
+    pub fn len(&self) -> usize { self.chunks.len() }
+
+    pub fn is_empty(&self) -> bool { self.chunks.is_empty() }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            chunks: self.chunks.len(),
+            memory: self
+                .chunks
+                .values()
+                .map(|cached| if cached.chunk.is_dirty() { cached.chunk.memory_size() } else { cached.memory })
+                .sum(),
+            budget: self.budget,
+            hits: self.hits.get(),
+            misses: self.misses.get(),
+            evictions: self.evictions,
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        let now = self.clock.get() + 1;
+        self.clock.set(now);
+        now
+    }
+
+    fn evict(&mut self) {
+        let Some(budget) = self.budget else {
+            return;
+        };
+        self.update_memory();
+        if self.memory <= budget {
+            return;
+        }
+        let mut clean: Vec<(u64, ChunkPos)> = self
+            .chunks
+            .iter()
+            .filter(|(_, cached)| !cached.chunk.is_dirty())
+            .map(|(pos, cached)| (cached.last_used.get(), *pos))
+            .collect();
+        clean.sort_unstable_by_key(|(last_used, _)| *last_used);
+        let before = self.chunks.len();
+        for (_, pos) in clean {
+            if self.memory <= budget {
+                break;
+            }
+            if let Some(cached) = self.chunks.remove(&pos) {
+                self.memory -= cached.memory;
+                self.evictions += 1;
+            }
+        }
+        debug!(evicted = before - self.chunks.len(), memory = self.memory, budget, hit_rate = self.stats().hit_rate(), "Evicted chunks");
+    }
+
+    /// Updates the memory of the dirty chunks, those may have changed.
+    fn update_memory(&mut self) {
+        for cached in self.chunks.values_mut().filter(|cached| cached.chunk.is_dirty()) {
+            let memory = cached.chunk.memory_size();
+            self.memory = self.memory + memory - cached.memory;
+            cached.memory = memory;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use falcon_core::world::blocks::Blocks;
+    use falcon_core::world::chunks::{Chunk, ChunkPos};
+
+    use super::ChunkCache;
+
+    fn clean_chunk(x: i32) -> Chunk {
+        let mut chunk = Chunk::empty(ChunkPos::new(x, 0));
+        chunk.set_block_at(0, 0, 0, Blocks::Stone);
+        chunk.mark_dirty(false);
+        chunk
+    }
+
+    #[test]
+    fn test_eviction() {
+        let size = clean_chunk(0).memory_size();
+        let mut cache = ChunkCache::default();
+        cache.set_budget(Some(size * 3));
+        cache.get_mut(ChunkPos::new(-1, 0)).set_block_at(0, 0, 0, Blocks::Stone);
+        cache.insert(clean_chunk(0));
+        cache.insert(clean_chunk(1));
+        assert!(cache.get(ChunkPos::new(0, 0)).is_some());
+        cache.insert(clean_chunk(2));
+
+        // the dirty chunk stays, 1 was used least recently
+        assert!(cache.contains(ChunkPos::new(-1, 0)));
+        assert!(cache.contains(ChunkPos::new(0, 0)));
+        assert!(!cache.contains(ChunkPos::new(1, 0)));
+        assert!(cache.contains(ChunkPos::new(2, 0)));
+        assert!(cache.get(ChunkPos::new(1, 0)).is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.chunks, stats.memory, stats.hits, stats.misses, stats.evictions), (3, size * 3, 1, 1, 1));
+    }
+}
@@ -0,0 +1,156 @@
+//! Loading of chunks that are not in memory, without blocking the tick.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use ahash::{AHashMap, AHashSet};
+use falcon_core::error::FalconCoreError;
+use falcon_core::world::chunks::{Chunk, ChunkPos};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tracing::warn;
+
+use crate::FalconServer;
+
+/// Where the chunks of a world come from when they are not in memory.
+pub trait ChunkProvider: Send + Sync {
+    /// Loads the chunk at `pos`, `None` if there is no such chunk. This runs
+    /// on a blocking thread.
+    fn load_chunk(&self, pos: ChunkPos) -> Result<Option<Chunk>, FalconCoreError>;
+}
+
+/// Runs once a requested chunk is in memory.
+pub type ChunkCallback = Box<dyn FnOnce(&mut FalconServer, ChunkPos) + Send>;
+
+type LoadResult = (ChunkPos, Result<Option<Chunk>, FalconCoreError>);
+
+/// Loads chunks from a [`ChunkProvider`] on the blocking threads of the
+/// runtime, the requests with the lowest priority first.
+pub struct ChunkLoader {
+    provider: Arc<dyn ChunkProvider>,
+    max_loading: usize,
+    queue: BinaryHeap<(Reverse<u32>, i32, i32)>,
+    pending: AHashMap<ChunkPos, Vec<ChunkCallback>>,
+    loading: AHashSet<ChunkPos>,
+    loaded_tx: UnboundedSender<LoadResult>,
+    loaded_rx: UnboundedReceiver<LoadResult>,
+}
+
+impl ChunkLoader {
+    /// Loads at most `max_loading` chunks at once.
+    pub fn new(provider: Arc<dyn ChunkProvider>, max_loading: usize) -> Self {
+        let (loaded_tx, loaded_rx) = unbounded_channel();
+        ChunkLoader {
+            provider,
+            max_loading: max_loading.max(1),
+            queue: BinaryHeap::new(),
+            pending: AHashMap::new(),
+            loading: AHashSet::new(),
+            loaded_tx,
+            loaded_rx,
+        }
+    }
+
+    /// Queues the chunk at `pos`, e.g. with the distance to the player
+    /// that needs it as `priority`. A chunk that was requested already
+    /// only gets another callback and the lowest priority of both.
+    pub fn request(&mut self, pos: ChunkPos, priority: u32, callback: ChunkCallback) {
+        self.pending.entry(pos).or_default().push(callback);
+        if !self.loading.contains(&pos) {
+            self.queue.push((Reverse(priority), pos.x, pos.z));
+        }
+    }
+
+    /// Whether the chunk at `pos` was requested and did not finish loading.
+    pub fn is_pending(&self, pos: ChunkPos) -> bool { self.pending.contains_key(&pos) }
+
+    /// The number of requested chunks that did not finish loading.
+    pub fn pending_count(&self) -> usize { self.pending.len() }
+
+    /// Returns the chunks that finished loading with their callbacks, and
+    /// starts loading the next chunks. Chunks that could not be loaded are
+    /// `None`.
+    pub fn poll(&mut self) -> Vec<(ChunkPos, Option<Chunk>, Vec<ChunkCallback>)> {
+        let mut loaded = Vec::new();
+        while let Ok((pos, result)) = self.loaded_rx.try_recv() {
+            self.loading.remove(&pos);
+            let chunk = result.unwrap_or_else(|error| {
+                warn!(x = pos.x, z = pos.z, %error, "Could not load chunk");
+                None
+            });
+            loaded.push((pos, chunk, self.pending.remove(&pos).unwrap_or_default()));
+        }
+        self.start_loading();
+        loaded
+    }
+
+    fn start_loading(&mut self) {
+        while self.loading.len() < self.max_loading {
+            let Some((_, x, z)) = self.queue.pop() else {
+                return;
+            };
+            let pos = ChunkPos::new(x, z);
+            // requested more than once or loaded in the meantime
+            if !self.pending.contains_key(&pos) || !self.loading.insert(pos) {
+                continue;
+            }
+            let provider = self.provider.clone();
+            let loaded_tx = self.loaded_tx.clone();
+            tokio::task::spawn_blocking(move || {
+                loaded_tx.send((pos, provider.load_chunk(pos))).ok();
+            });
+        }
+    }
+}
+
+impl Debug for ChunkLoader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkLoader")
+            .field("pending", &self.pending.len())
+            .field("loading", &self.loading.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use falcon_core::error::FalconCoreError;
+    use falcon_core::world::chunks::{Chunk, ChunkPos};
+
+    use super::{ChunkLoader, ChunkProvider};
+
+    /// Has every chunk with a positive x and remembers the load order.
+    #[derive(Default)]
+    struct TestProvider(Mutex<Vec<i32>>);
+
+    impl ChunkProvider for TestProvider {
+        fn load_chunk(&self, pos: ChunkPos) -> Result<Option<Chunk>, FalconCoreError> {
+            self.0.lock().unwrap().push(pos.x);
+            Ok((pos.x > 0).then(|| Chunk::empty(pos)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_order() {
+        let provider = Arc::new(TestProvider::default());
+        let mut loader = ChunkLoader::new(provider.clone(), 1);
+        for (x, priority) in [(5, 5), (-1, 1), (3, 3), (3, 0)] {
+            loader.request(ChunkPos::new(x, 0), priority, Box::new(|_, _| {}));
+        }
+        assert_eq!(loader.pending_count(), 3);
+
+        let mut loaded = Vec::new();
+        while loader.pending_count() > 0 {
+            for (pos, chunk, callbacks) in loader.poll() {
+                loaded.push((pos.x, chunk.is_some(), callbacks.len()));
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(*provider.0.lock().unwrap(), vec![3, -1, 5]);
+        assert_eq!(loaded, vec![(3, true, 2), (-1, false, 1), (5, true, 1)]);
+    }
+}
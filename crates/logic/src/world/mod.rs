@@ -1,14 +1,36 @@
+use std::sync::Arc;
+
 use ahash::AHashMap;
 use falcon_core::error::FalconCoreError;
-use falcon_core::network::util::read_var_i32_from_iter;
+use falcon_core::player::data::Position;
 use falcon_core::schematic::SchematicData;
+use falcon_core::server::config::FalconConfig;
+use falcon_core::world::biome::Biome;
+use falcon_core::world::block_util::blocks_movement;
 use falcon_core::world::blocks::Blocks;
-use falcon_core::world::chunks::{Chunk, ChunkPos, SECTION_LENGTH, SECTION_WIDTH};
-use falcon_send::specs::play::ChunkDataSpec;
-use itertools::Itertools;
+use falcon_core::world::chunks::{Chunk, ChunkPos, SECTIONS_NUM, SECTION_HEIGHT};
+use falcon_core::world::BlockPosition;
+use falcon_send::specs::play::{ChunkDataSpec, LightUpdateSpec};
 use tracing::debug;
+use uuid::Uuid;
 
+use self::budget::ChunkQueue;
+use self::cache::{CacheStats, ChunkCache};
+use self::loader::{ChunkLoader, ChunkProvider};
+use self::provider::MemoryProvider;
+use crate::connection::encode::EncodedPacket;
 use crate::player::FalconPlayer;
+use crate::FalconServer;
+
+pub mod budget;
+pub mod cache;
+pub mod loader;
+pub mod provider;
+pub mod snapshot;
+
+/// The chunks around a player that are sent without waiting for the chunk
+/// budget, the player would fall through the world without them.
+const SPAWN_RADIUS: u32 = 1;
 
 #[derive(Debug)]
 pub struct FalconWorld {
@@ -16,7 +38,10 @@ pub struct FalconWorld {
     min_z: i32,
     max_x: i32,
     max_z: i32,
-    chunks: AHashMap<ChunkPos, Chunk>,
+    chunks: ChunkCache,
+    loader: Option<ChunkLoader>,
+    default_biome: Biome,
+    queues: AHashMap<Uuid, ChunkQueue>,
 }
 
 impl FalconWorld {
@@ -26,30 +51,213 @@ impl FalconWorld {
             min_z,
             max_x,
             max_z,
-            chunks: AHashMap::with_capacity(capacity),
+            chunks: ChunkCache::with_capacity(capacity),
+            loader: None,
+            default_biome: Biome::default(),
+            queues: AHashMap::new(),
         }
     }
 
+    /// Loads the chunks that are not in memory from `provider`, this
+    /// removes the bounds of the world. Chunks that were not changed are
+    /// unloaded when the chunk cache of the config is full.
+    pub fn with_provider(mut self, provider: Arc<dyn ChunkProvider>) -> Self {
+        (self.min_x, self.min_z, self.max_x, self.max_z) = (i32::MIN, i32::MIN, i32::MAX, i32::MAX);
+        self.loader = Some(ChunkLoader::new(provider, FalconConfig::global().network.chunk_workers()));
+        self.chunks.set_budget(FalconConfig::global().server.chunk_cache_budget());
+        self
+    }
+
+    /// The biome of chunks that have no biomes of their own.
+    pub fn with_default_biome(mut self, biome: Biome) -> Self {
+        self.default_biome = biome;
+        self
+    }
+
+    /// The hits and misses of the chunks in memory, only chunks that are
+    /// loaded from a provider can miss.
+    pub fn cache_stats(&self) -> CacheStats { self.chunks.stats() }
+
     pub fn get_chunk(&self, pos: ChunkPos) -> Option<&Chunk> {
         if pos.x > self.max_x || pos.x < self.min_x || pos.z > self.max_z || pos.z < self.min_z {
             return None;
         }
-        self.chunks.get(&pos)
+        self.chunks.peek(pos)
     }
 
-    pub fn get_chunk_mut(&mut self, pos: ChunkPos) -> &mut Chunk { self.chunks.entry(pos).or_insert_with(|| Chunk::empty(pos)) }
+    /// Whether a chunk can be sent right away. Without a provider every
+    /// chunk can, the ones outside the world are empty.
+    pub fn is_loaded(&self, pos: ChunkPos) -> bool { self.loader.is_none() || self.chunks.peek(pos).is_some() }
 
+    /// The chunks that are in memory, e.g. to export them.
+    pub fn chunks(&self) -> impl Iterator<Item = &Chunk> { self.chunks.iter() }
+
+    pub fn get_chunk_mut(&mut self, pos: ChunkPos) -> &mut Chunk { self.chunks.get_mut(pos) }
+
+    /// The block at a position, air in chunks that are not loaded.
+    pub fn block_at(&self, position: BlockPosition) -> Blocks {
+        let Some(y) = u16::try_from(position.get_y()).ok().filter(|&y| y < SECTIONS_NUM * SECTION_HEIGHT) else {
+            return Blocks::Air;
+        };
+        let (x, z) = (position.get_x(), position.get_z());
+        self.get_chunk(ChunkPos::new(x.div_euclid(16), z.div_euclid(16)))
+            .map_or(Blocks::Air, |chunk| *chunk.block_at(x.rem_euclid(16) as u16, y, z.rem_euclid(16) as u16))
+    }
+
+    /// Places a block and returns the block that was there, `None` if the
+    /// position is outside of the world. Players are not sent the change,
+    /// see [`FalconServer::set_block`](crate::FalconServer::set_block).
+    pub fn set_block(&mut self, position: BlockPosition, block: Blocks) -> Option<Blocks> {
+        let y = u16::try_from(position.get_y()).ok().filter(|&y| y < SECTIONS_NUM * SECTION_HEIGHT)?;
+        let pos = ChunkPos::new(position.get_x().div_euclid(16), position.get_z().div_euclid(16));
+        if pos.x > self.max_x || pos.x < self.min_x || pos.z > self.max_z || pos.z < self.min_z {
+            return None;
+        }
+        let (x, z) = (position.get_x().rem_euclid(16) as u16, position.get_z().rem_euclid(16) as u16);
+        let chunk = self.get_chunk_mut(pos);
+        let old = *chunk.block_at(x, y, z);
+        chunk.set_block_at(x, y, z, block);
+        Some(old)
+    }
+
+    /// The height of the highest block at a column that stops movement or
+    /// is a fluid, `None` if the chunk is not loaded or the column has no
+    /// such blocks.
+    pub fn highest_block_at(&self, x: i32, z: i32) -> Option<i32> {
+        let chunk = self.get_chunk(ChunkPos::new(x.div_euclid(16), z.div_euclid(16)))?;
+        chunk.highest_block_at(x.rem_euclid(16) as u16, z.rem_euclid(16) as u16).map(i32::from)
+    }
+
+    /// The biome at a block position, the default biome of the world for
+    /// chunks that are not loaded.
+    pub fn biome_at(&self, x: i32, y: i32, z: i32) -> Biome {
+        let Some(chunk) = self.get_chunk(ChunkPos::new(x.div_euclid(16), z.div_euclid(16))) else {
+            return self.default_biome;
+        };
+        let y = y.clamp(0, (SECTIONS_NUM * SECTION_HEIGHT) as i32 - 1) as u16;
+        chunk.biome_at(x.rem_euclid(16) as u16, y, z.rem_euclid(16) as u16).unwrap_or(self.default_biome)
+    }
+
+    /// Changes the biome of the 4 by 4 by 4 cell around a block position,
+    /// players only see it once the chunk is sent again.
+    pub fn set_biome(&mut self, x: i32, y: i32, z: i32, biome: Biome) {
+        let y = y.clamp(0, (SECTIONS_NUM * SECTION_HEIGHT) as i32 - 1) as u16;
+        let default = self.default_biome;
+        self.get_chunk_mut(ChunkPos::new(x.div_euclid(16), z.div_euclid(16)))
+            .set_biome(x.rem_euclid(16) as u16, y, z.rem_euclid(16) as u16, biome, default);
+    }
+
+    /// Where a player can stand at the column of `position`: `position`
+    /// itself if there is room for the player on solid ground, otherwise
+    /// on top of the highest block of the column. Positions in chunks that
+    /// are not loaded or in columns without blocks are kept.
+    pub fn safe_spawn(&self, position: Position) -> Position {
+        let Some(chunk) = self.get_chunk(position.chunk_coords().into()) else {
+            return position;
+        };
+        let (x, z) = (position.x.floor().rem_euclid(16.0) as u16, position.z.floor().rem_euclid(16.0) as u16);
+        let height = SECTIONS_NUM * SECTION_HEIGHT;
+        let solid = |y: u16| blocks_movement(chunk.block_at(x, y, z));
+        let feet = position.y.floor();
+        if feet >= 1.0 && feet < (height - 1) as f64 {
+            let feet = feet as u16;
+            if solid(feet - 1) && !solid(feet) && !solid(feet + 1) {
+                return position;
+            }
+        }
+        match chunk.highest_block_at(x, z) {
+            Some(y) => Position::new(position.x, (y + 1) as f64, position.z),
+            None => position,
+        }
+    }
+
+    /// Sends the light and block data of a chunk, empty if it is not part of
+    /// the world. Chunks that are not in memory are sent once they are
+    /// loaded, the nearest to the player first.
+    pub(crate) fn send_chunk(&mut self, player: &FalconPlayer, x: i32, z: i32) {
+        let pos = ChunkPos::new(x, z);
+        if let Some(loader) = self.loader.as_mut().filter(|_| self.chunks.get(pos).is_none()) {
+            let (chunk_x, chunk_z) = player.position().chunk_coords();
+            let distance = chunk_x.abs_diff(x).max(chunk_z.abs_diff(z));
+            let uuid = player.uuid();
+            loader.request(pos, distance, Box::new(move |server: &mut FalconServer, pos| server.send_loaded_chunk(uuid, pos)));
+            return;
+        }
+        self.send_loaded(player, x, z);
+    }
+
+    /// Queues a chunk that is in memory, it is sent once the connection of
+    /// the player has room for it, see [`budget`].
+    pub(crate) fn send_loaded(&mut self, player: &FalconPlayer, x: i32, z: i32) {
+        self.queues
+            .entry(player.uuid())
+            .or_insert_with(|| ChunkQueue::new(FalconConfig::global().network.max_chunks_per_tick))
+            .push(ChunkPos::new(x, z));
+    }
+
+    /// Sends the players the queued chunks their connections have room for.
+    pub(crate) fn send_queued(&mut self, players: &AHashMap<Uuid, FalconPlayer>) {
+        let mut queues = std::mem::take(&mut self.queues);
+        queues.retain(|uuid, queue| {
+            let Some(player) = players.get(uuid) else {
+                return false;
+            };
+            if !queue.is_empty() {
+                let chunks = queue.next(player.position().chunk_coords(), player.view_distance(), player.connection().backlog());
+                for pos in chunks {
+                    self.write_chunk(player, pos.x, pos.z);
+                }
+            }
+            true
+        });
+        self.queues = queues;
+    }
+
+    /// Forgets the queued chunks of a player that left.
+    pub(crate) fn forget_player(&mut self, uuid: Uuid) { self.queues.remove(&uuid); }
+
+    fn write_chunk(&self, player: &FalconPlayer, x: i32, z: i32) {
+        let spec = match self.get_chunk((x, z).into()) {
+            Some(chunk) => ChunkDataSpec::new(chunk, player.protocol_version(), self.default_biome),
+            None => ChunkDataSpec::empty(x, z, self.default_biome),
+        };
+        // 1.18+ clients receive the light as part of the chunk data
+        player.connection().send_packet(LightUpdateSpec::new(x, z), falcon_send::write_update_light);
+        let chunk_data = EncodedPacket::spawn(spec, player.protocol_version(), falcon_send::write_chunk_data);
+        player.connection().send_encoded(chunk_data);
+    }
+
+    /// Keeps the chunks that finished loading and returns their callbacks.
+    pub(crate) fn poll_loaded(&mut self) -> Vec<(ChunkPos, Vec<loader::ChunkCallback>)> {
+        let Some(loader) = &mut self.loader else {
+            return Vec::new();
+        };
+        let loaded = loader.poll();
+        let mut callbacks = Vec::with_capacity(loaded.len());
+        for (pos, chunk, waiting) in loaded {
+            let mut chunk = chunk.unwrap_or_else(|| Chunk::empty(pos));
+            chunk.mark_dirty(false);
+            self.chunks.insert(chunk);
+            callbacks.push((pos, waiting));
+        }
+        callbacks
+    }
+
+    /// Sends the chunks in view distance of a player. The chunk the player
+    /// stands in and its neighbours are written right away so they reach
+    /// the client before the position of the player, the others are queued.
     pub fn send_chunks_for_player(&mut self, player: &FalconPlayer) {
         let (chunk_x, chunk_z) = player.position().chunk_coords();
         let view_distance = player.view_distance();
 
         for x in chunk_x - view_distance as i32..=chunk_x + view_distance as i32 {
             for z in chunk_z - view_distance as i32..=chunk_z + view_distance as i32 {
-                let spec = match self.get_chunk((x, z).into()) {
-                    Some(chunk) => ChunkDataSpec::new(chunk, player.protocol_version()),
-                    None => ChunkDataSpec::empty(x, z),
-                };
-                player.connection().send_packet(spec, falcon_send::write_chunk_data);
+                let spawn = chunk_x.abs_diff(x) <= SPAWN_RADIUS && chunk_z.abs_diff(z) <= SPAWN_RADIUS;
+                if spawn && self.is_loaded(ChunkPos::new(x, z)) {
+                    self.write_chunk(player, x, z);
+                } else {
+                    self.send_chunk(player, x, z);
+                }
             }
         }
     }
@@ -69,11 +277,7 @@ impl FalconWorld {
         for x in chunk_x - view_distance as i32..=chunk_x + view_distance as i32 {
             for z in chunk_z - view_distance as i32..=chunk_z + view_distance as i32 {
                 if old_chunk_x.abs_diff(x) > view_distance as u32 || old_chunk_z.abs_diff(z) > view_distance as u32 {
-                    let spec = match self.get_chunk((x, z).into()) {
-                        Some(chunk) => ChunkDataSpec::new(chunk, player.protocol_version()),
-                        None => ChunkDataSpec::empty(x, z),
-                    };
-                    player.connection().send_packet(spec, falcon_send::write_chunk_data);
+                    self.send_chunk(player, x, z);
                 }
             }
         }
@@ -88,11 +292,7 @@ impl FalconWorld {
                 for x in -(view_distance as i8)..=view_distance as i8 {
                     for z in -(view_distance as i8)..=view_distance as i8 {
                         if x.unsigned_abs() > old_view_distance || z.unsigned_abs() > old_view_distance {
-                            let spec = match self.get_chunk((chunk_x + x as i32, chunk_z + z as i32).into()) {
-                                Some(chunk) => ChunkDataSpec::new(chunk, player.protocol_version()),
-                                None => ChunkDataSpec::empty(chunk_x + x as i32, chunk_z + z as i32),
-                            };
-                            player.connection().send_packet(spec, falcon_send::write_chunk_data);
+                            self.send_chunk(player, chunk_x + x as i32, chunk_z + z as i32);
                         }
                     }
                 }
@@ -124,44 +324,54 @@ impl<'a> TryFrom<SchematicData<'a>> for FalconWorld {
         let count_z = ((schematic.length - rest_z) / 16) as usize + usize::from(rest_z > 0);
         debug!(x = count_x, z = count_z, "World size");
 
-        let air_value = schematic
-            .palette
-            .iter()
-            .find(|(_, value)| *value == &Blocks::Air)
-            .map(|(index, _)| *index);
-        let mut schematic_blocks = schematic
-            .block_data
-            .iter()
-            .map(|b| b as u8)
-            .batching(|iter| read_var_i32_from_iter(iter));
-
         let mut world = FalconWorld::new(count_x * count_z, 0, 0, count_x as i32, count_z as i32);
-        for y in 0..schematic.height as usize {
-            for z in 0..schematic.length as usize {
-                for x in 0..schematic.width as usize {
-                    let schematic_block = schematic_blocks
-                        .next()
-                        .ok_or_else(|| FalconCoreError::InvalidData(String::from("Invalid world data, fewer blocks than size given!!")))?;
-                    match air_value {
-                        Some(value) if value == schematic_block => {},
-                        _ => {
-                            let chunk_pos = ChunkPos::new((x / SECTION_WIDTH as usize) as i32, (z / SECTION_LENGTH as usize) as i32);
-                            let palette_entry = *schematic.palette.get(&schematic_block).ok_or_else(|| {
-                                FalconCoreError::InvalidData(String::from("Invalid schematic data, could not find corresponding palette entry!!"))
-                            })?;
-                            let chunk = world.get_chunk_mut(chunk_pos);
-                            chunk.set_block_at(
-                                (x as i32 - (chunk_pos.x * SECTION_WIDTH as i32)) as u16,
-                                y as u16,
-                                (z as i32 - (chunk_pos.z * SECTION_LENGTH as i32)) as u16,
-                                palette_entry,
-                            );
-                        },
-                    }
-                }
-            }
+        for chunk in MemoryProvider::try_from(schematic)?.into_chunks() {
+            world.chunks.insert(chunk);
         }
         debug!(count = world.chunks.len(), "Loaded chunks.");
         Ok(world)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use falcon_core::player::data::Position;
+    use falcon_core::world::blocks::Blocks;
+    use falcon_core::world::chunks::ChunkPos;
+    use falcon_core::world::BlockPosition;
+
+    use super::FalconWorld;
+
+    #[test]
+    fn test_safe_spawn() {
+        let mut world = FalconWorld::new(4, 0, 0, 0, 0);
+        let chunk = world.get_chunk_mut(ChunkPos::new(0, 0));
+        chunk.set_block_at(3, 40, 3, Blocks::Stone);
+        chunk.set_block_at(3, 64, 3, Blocks::Stone);
+        chunk.set_block_at(3, 66, 3, Blocks::Stone);
+
+        // on solid ground with room for the player
+        assert_eq!(world.safe_spawn(Position::new(3.5, 41.0, 3.5)).y, 41.0);
+        // the head would be inside a block, and floating above the column
+        assert_eq!(world.safe_spawn(Position::new(3.5, 65.0, 3.5)).y, 67.0);
+        assert_eq!(world.safe_spawn(Position::new(3.5, 100.0, 3.5)).y, 67.0);
+        // columns without blocks and positions outside the world are kept
+        assert_eq!(world.safe_spawn(Position::new(5.5, 70.0, 5.5)).y, 70.0);
+        assert_eq!(world.safe_spawn(Position::new(20.0, 70.0, 3.5)).y, 70.0);
+    }
+
+    #[test]
+    fn test_set_block() {
+        let mut world = FalconWorld::new(4, -1, 0, 0, 0);
+        let position = BlockPosition::new(-3, 70, 5);
+        assert_eq!(world.set_block(position, Blocks::Stone), Some(Blocks::Air));
+        assert_eq!(world.set_block(position, Blocks::Dirt), Some(Blocks::Stone));
+        assert_eq!(world.block_at(position), Blocks::Dirt);
+        assert_eq!(world.get_chunk(ChunkPos::new(-1, 0)).unwrap().block_at(13, 70, 5), &Blocks::Dirt);
+
+        // outside of the world
+        assert_eq!(world.set_block(BlockPosition::new(-3, 256, 5), Blocks::Stone), None);
+        assert_eq!(world.set_block(BlockPosition::new(16, 70, 5), Blocks::Stone), None);
+        assert_eq!(world.block_at(BlockPosition::new(16, 70, 5)), Blocks::Air);
+    }
+}
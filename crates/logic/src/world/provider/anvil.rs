@@ -0,0 +1,68 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use falcon_core::error::FalconCoreError;
+use falcon_core::world::chunks::{Chunk, ChunkPos};
+use flate2::read::{GzDecoder, ZlibDecoder};
+
+use super::{chunk_nbt, ChunkProvider};
+
+/// The size of a sector of a region file.
+const SECTOR: u64 = 4096;
+
+/// Reads the chunks of a vanilla world directory from its region files,
+/// worlds of 1.13 and later. Sections outside of heights 0 to 255 are left
+/// out.
+#[derive(Clone, Debug)]
+pub struct AnvilProvider {
+    region_dir: PathBuf,
+}
+
+impl AnvilProvider {
+    /// `world_dir` is the directory with the `region` directory.
+    pub fn new<P: AsRef<Path>>(world_dir: P) -> Self {
+        AnvilProvider {
+            region_dir: world_dir.as_ref().join("region"),
+        }
+    }
+
+    /// The NBT of a chunk, `None` if its region file does not have it.
+    fn read_nbt(&self, pos: ChunkPos) -> Result<Option<Vec<u8>>, FalconCoreError> {
+        let path = self.region_dir.join(format!("r.{}.{}.mca", pos.x >> 5, pos.z >> 5));
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut location = [0; 4];
+        file.seek(SeekFrom::Start(4 * ((pos.x & 31) + (pos.z & 31) * 32) as u64))?;
+        file.read_exact(&mut location)?;
+        let offset = (u32::from_be_bytes(location) >> 8) as u64;
+        if offset == 0 {
+            return Ok(None);
+        }
+        let mut header = [0; 5];
+        file.seek(SeekFrom::Start(offset * SECTOR))?;
+        file.read_exact(&mut header)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]).saturating_sub(1) as u64;
+        let mut compressed = file.take(length);
+        let mut nbt = Vec::new();
+        match header[4] {
+            1 => GzDecoder::new(compressed).read_to_end(&mut nbt)?,
+            2 => ZlibDecoder::new(compressed).read_to_end(&mut nbt)?,
+            3 => compressed.read_to_end(&mut nbt)?,
+            compression => return Err(FalconCoreError::InvalidData(format!("Unknown chunk compression {}", compression))),
+        };
+        Ok(Some(nbt))
+    }
+}
+
+impl ChunkProvider for AnvilProvider {
+    fn load_chunk(&self, pos: ChunkPos) -> Result<Option<Chunk>, FalconCoreError> {
+        match self.read_nbt(pos)? {
+            Some(nbt) => chunk_nbt::read_chunk(pos, &nbt).map(Some),
+            None => Ok(None),
+        }
+    }
+}
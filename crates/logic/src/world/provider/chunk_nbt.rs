@@ -0,0 +1,341 @@
+//! The NBT of vanilla chunks, shared by the region file formats.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use falcon_core::error::FalconCoreError;
+use falcon_core::world::biome::Biome;
+use falcon_core::world::block_states::parse_state;
+use falcon_core::world::blocks::Blocks;
+use falcon_core::world::chunks::{Chunk, ChunkPos, BIOME_CELLS, SECTIONS_NUM, SECTION_HEIGHT, SECTION_LENGTH, SECTION_WIDTH};
+use fastnbt::{IntArray, LongArray};
+use serde::{Deserialize, Serialize};
+
+/// The first data version that does not spread block indices over two
+/// longs, 20w17a.
+const NO_SPANNING_VERSION: i32 = 2529;
+/// The data version of the chunks that are written, 1.17.1 like the blocks.
+const DATA_VERSION: i32 = 2730;
+const SECTION_BLOCKS: usize = (SECTION_WIDTH * SECTION_LENGTH * SECTION_HEIGHT) as usize;
+const SECTION_CELLS: usize = BIOME_CELLS / SECTIONS_NUM as usize;
+const COLUMNS: usize = (SECTION_WIDTH * SECTION_LENGTH) as usize;
+
+/// Reads the blocks and biomes of a chunk from its NBT, worlds of 1.13 and
+/// later. Sections outside of heights 0 to 255 are left out.
+pub(super) fn read_chunk(pos: ChunkPos, nbt: &[u8]) -> Result<Chunk, FalconCoreError> {
+    let data: ChunkNbt = fastnbt::from_bytes(nbt).map_err(|e| FalconCoreError::InvalidData(format!("Invalid chunk NBT: {}", e)))?;
+    let spanning = data.data_version < NO_SPANNING_VERSION;
+    let mut chunk = Chunk::empty(pos);
+    let sections = match data.level {
+        // before 1.18 the sections and biomes are part of the level
+        Some(level) => {
+            chunk.set_biomes(level.biomes.as_deref().and_then(read_legacy_biomes));
+            level.sections.into_iter().map(|s| (s.y, s.palette, s.block_states)).collect()
+        },
+        None => {
+            chunk.set_biomes(read_biomes(&data.sections));
+            data
+                .sections
+                .into_iter()
+                .filter_map(|s| s.block_states.map(|states| (s.y, Some(states.palette), states.data)))
+                .collect::<Vec<_>>()
+        },
+    };
+    for (y, palette, states) in sections {
+        match palette {
+            Some(palette) if (0..SECTIONS_NUM as i8).contains(&y) => read_section(&mut chunk, y as u16, &palette, states.as_deref(), spanning),
+            _ => {},
+        }
+    }
+    chunk.mark_dirty(false);
+    Ok(chunk)
+}
+
+/// The NBT of a chunk in the format of 1.17.1.
+pub(super) fn write_chunk(chunk: &Chunk) -> Result<Vec<u8>, FalconCoreError> {
+    let sections = chunk
+        .get_chunk_sections()
+        .iter()
+        .enumerate()
+        .filter_map(|(y, section)| section.as_ref().map(|section| (y, section)))
+        .map(|(y, section)| {
+            let palette = section.get_palette();
+            let blocks = (0..palette.len()).filter_map(|i| palette.at(i)).map(BlockStateNbt::from).collect::<Vec<_>>();
+            let states = pack(section.get_block_data(), bits_per_block(blocks.len()));
+            LevelSectionNbt {
+                y: y as i8,
+                palette: Some(blocks),
+                block_states: Some(LongArray::new(states)),
+            }
+        })
+        .collect();
+    let pos = chunk.get_position();
+    let data = ChunkNbtOut {
+        data_version: DATA_VERSION,
+        level: LevelNbtOut {
+            x_pos: pos.x,
+            z_pos: pos.z,
+            status: "full",
+            sections,
+            biomes: chunk.biomes().map(|biomes| IntArray::new(biomes.iter().map(Biome::legacy_id).collect())),
+        },
+    };
+    fastnbt::to_bytes(&data).map_err(|e| FalconCoreError::InvalidData(format!("Could not write chunk NBT: {}", e)))
+}
+
+/// Copies the blocks of a section into `chunk`.
+fn read_section(chunk: &mut Chunk, section_y: u16, palette: &[BlockStateNbt], states: Option<&[i64]>, spanning: bool) {
+    // blocks that this version does not know become air
+    let blocks: Vec<Blocks> = palette.iter().map(|state| state.block().unwrap_or(Blocks::Air)).collect();
+    let indices = match states {
+        Some(states) if blocks.len() > 1 => unpack(states, bits_per_block(blocks.len()), spanning),
+        _ => vec![0; SECTION_BLOCKS],
+    };
+    for (i, index) in indices.into_iter().enumerate() {
+        let block = blocks.get(index as usize).copied().unwrap_or(Blocks::Air);
+        if block != Blocks::Air {
+            let (x, z, y) = (i as u16 % SECTION_WIDTH, i as u16 / SECTION_WIDTH % SECTION_LENGTH, i as u16 / (SECTION_WIDTH * SECTION_LENGTH));
+            chunk.set_block_at(x, section_y * SECTION_HEIGHT + y, z, block);
+        }
+    }
+}
+
+/// The biomes of 1.15 up to 1.17 with a legacy id for every cell, or of
+/// 1.13 and 1.14 with one for every column.
+fn read_legacy_biomes(ids: &[i32]) -> Option<Box<[Biome; BIOME_CELLS]>> {
+    let biome = |id: i32| Biome::from_legacy_id(id).unwrap_or_default();
+    let mut biomes = Box::new([Biome::default(); BIOME_CELLS]);
+    match ids.len() {
+        BIOME_CELLS => biomes.iter_mut().zip(ids).for_each(|(cell, &id)| *cell = biome(id)),
+        COLUMNS => {
+            for (i, cell) in biomes.iter_mut().enumerate() {
+                // the column at the corner of the cell
+                let (x, z) = (i % 4 * 4, i / 4 % 4 * 4);
+                *cell = biome(ids[x + z * SECTION_WIDTH as usize]);
+            }
+        },
+        _ => return None,
+    }
+    Some(biomes)
+}
+
+/// The biomes of 1.18+ which every section has in a palette of names.
+fn read_biomes(sections: &[SectionNbt]) -> Option<Box<[Biome; BIOME_CELLS]>> {
+    let mut biomes = Box::new([Biome::default(); BIOME_CELLS]);
+    let mut found = false;
+    for section in sections.iter().filter(|s| (0..SECTIONS_NUM as i8).contains(&s.y)) {
+        let Some(section_biomes) = &section.biomes else {
+            continue;
+        };
+        // biomes that this version does not know become plains
+        let palette: Vec<Biome> = section_biomes.palette.iter().map(|name| Biome::from_str(name).unwrap_or_default()).collect();
+        let indices = match &section_biomes.data {
+            Some(data) if palette.len() > 1 => unpack_cells(data, usize::BITS - (palette.len() - 1).leading_zeros()),
+            _ => vec![0; SECTION_CELLS],
+        };
+        let offset = section.y as usize * SECTION_CELLS;
+        for (i, index) in indices.into_iter().enumerate() {
+            biomes[offset + i] = palette.get(index as usize).copied().unwrap_or_default();
+        }
+        found = true;
+    }
+    found.then_some(biomes)
+}
+
+fn unpack_cells(data: &[i64], bits: u32) -> Vec<u16> {
+    let per_long = 64 / bits as usize;
+    let mask = (1u64 << bits) - 1;
+    (0..SECTION_CELLS).map(|i| (data.get(i / per_long).map_or(0, |&l| l as u64 >> ((i % per_long) * bits as usize)) & mask) as u16).collect()
+}
+
+fn bits_per_block(palette_len: usize) -> u32 { (usize::BITS - palette_len.saturating_sub(1).leading_zeros()).max(4) }
+
+/// The palette indices of a section, `spanning` indices can be split over
+/// two longs.
+fn unpack(states: &[i64], bits: u32, spanning: bool) -> Vec<u16> {
+    let mask = (1u64 << bits) - 1;
+    let per_long = 64 / bits as usize;
+    (0..SECTION_BLOCKS)
+        .map(|i| {
+            let (long, shift) = match spanning {
+                true => ((i * bits as usize) / 64, (i * bits as usize) % 64),
+                false => (i / per_long, (i % per_long) * bits as usize),
+            };
+            let mut value = states.get(long).map_or(0, |&l| l as u64 >> shift);
+            if spanning && shift + bits as usize > 64 {
+                value |= states.get(long + 1).map_or(0, |&l| (l as u64) << (64 - shift));
+            }
+            (value & mask) as u16
+        })
+        .collect()
+}
+
+/// Packs palette indices without spanning, the inverse of [`unpack`].
+fn pack(indices: &[u16], bits: u32) -> Vec<i64> {
+    let per_long = 64 / bits as usize;
+    indices
+        .chunks(per_long)
+        .map(|long| long.iter().enumerate().fold(0u64, |packed, (i, &index)| packed | (index as u64) << (i * bits as usize)) as i64)
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct ChunkNbt {
+    #[serde(rename = "DataVersion", default)]
+    data_version: i32,
+    #[serde(rename = "Level")]
+    level: Option<LevelNbt>,
+    #[serde(default)]
+    sections: Vec<SectionNbt>,
+}
+
+#[derive(Deserialize)]
+struct LevelNbt {
+    #[serde(rename = "Sections", default)]
+    sections: Vec<LevelSectionNbt>,
+    #[serde(rename = "Biomes")]
+    biomes: Option<IntArray>,
+}
+
+#[derive(Serialize)]
+struct ChunkNbtOut {
+    #[serde(rename = "DataVersion")]
+    data_version: i32,
+    #[serde(rename = "Level")]
+    level: LevelNbtOut,
+}
+
+#[derive(Serialize)]
+struct LevelNbtOut {
+    #[serde(rename = "xPos")]
+    x_pos: i32,
+    #[serde(rename = "zPos")]
+    z_pos: i32,
+    #[serde(rename = "Status")]
+    status: &'static str,
+    #[serde(rename = "Sections")]
+    sections: Vec<LevelSectionNbt>,
+    #[serde(rename = "Biomes", skip_serializing_if = "Option::is_none")]
+    biomes: Option<IntArray>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LevelSectionNbt {
+    #[serde(rename = "Y")]
+    y: i8,
+    #[serde(rename = "Palette")]
+    palette: Option<Vec<BlockStateNbt>>,
+    #[serde(rename = "BlockStates")]
+    block_states: Option<LongArray>,
+}
+
+#[derive(Deserialize)]
+struct SectionNbt {
+    #[serde(rename = "Y")]
+    y: i8,
+    block_states: Option<BlockStatesNbt>,
+    biomes: Option<BiomesNbt>,
+}
+
+#[derive(Deserialize)]
+struct BiomesNbt {
+    palette: Vec<String>,
+    data: Option<LongArray>,
+}
+
+#[derive(Deserialize)]
+struct BlockStatesNbt {
+    palette: Vec<BlockStateNbt>,
+    data: Option<LongArray>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct BlockStateNbt {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Properties", default, skip_serializing_if = "BTreeMap::is_empty")]
+    properties: BTreeMap<String, String>,
+}
+
+impl BlockStateNbt {
+    fn block(&self) -> Option<Blocks> {
+        if self.properties.is_empty() {
+            return parse_state(&self.name).ok();
+        }
+        let properties: Vec<String> = self.properties.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        parse_state(&format!("{}[{}]", self.name, properties.join(","))).ok()
+    }
+}
+
+impl From<&Blocks> for BlockStateNbt {
+    fn from(block: &Blocks) -> Self {
+        let block = block.to_string();
+        let (name, properties) = block.trim_end_matches(']').split_once('[').unwrap_or((&block, ""));
+        BlockStateNbt {
+            name: name.to_owned(),
+            properties: properties
+                .split(',')
+                .filter_map(|property| property.split_once('='))
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use falcon_core::world::blocks::{Blocks, SlabType};
+
+    use falcon_core::world::biome::Biome;
+    use falcon_core::world::chunks::{Chunk, BIOME_CELLS};
+
+    use super::{bits_per_block, pack, read_legacy_biomes, unpack, BlockStateNbt};
+
+    #[test]
+    fn test_unpack() {
+        assert_eq!((bits_per_block(2), bits_per_block(16), bits_per_block(17)), (4, 4, 5));
+
+        // 5 bits: 12 indices per long without spanning
+        let packed = [1 | 2 << 5 | 31 << 55, 3];
+        let indices = unpack(&packed, 5, false);
+        assert_eq!(indices[..3], [1, 2, 0]);
+        assert_eq!(indices[11..14], [31, 3, 0]);
+        assert_eq!(pack(&indices, 5)[..2], packed);
+
+        // the 13th index starts at bit 60 and ends in the next long
+        let packed = [0b1011 << 60, 0b1];
+        let indices = unpack(&packed, 5, true);
+        assert_eq!(indices[12], 0b11011);
+        assert_eq!(indices[13], 0);
+    }
+
+    #[test]
+    fn test_block_state() {
+        let mut slab = Blocks::OakSlab(Default::default());
+        if let Blocks::OakSlab(state) = &mut slab {
+            state.with_typed(SlabType::Top);
+        }
+        let state = BlockStateNbt::from(&slab);
+        assert_eq!(state.name, "minecraft:oak_slab");
+        assert_eq!(state.properties["type"], "top");
+        assert_eq!(state.block(), Some(slab));
+        assert_eq!(BlockStateNbt::from(&Blocks::Stone).block(), Some(Blocks::Stone));
+    }
+
+    #[test]
+    fn test_legacy_biomes() {
+        let mut columns = [Biome::Plains.legacy_id(); 256];
+        // the column at the corner decides the biome of the cell
+        columns[4 + 4 * 16] = Biome::Desert.legacy_id();
+        columns[5 + 8 * 16] = Biome::Desert.legacy_id();
+        let biomes = read_legacy_biomes(&columns).unwrap();
+        assert_eq!(biomes[Chunk::biome_index(7, 200, 6)], Biome::Desert);
+        assert_eq!(biomes[Chunk::biome_index(5, 0, 8)], Biome::Plains);
+
+        let mut cells = [Biome::Swamp.legacy_id(); BIOME_CELLS];
+        cells[Chunk::biome_index(0, 64, 0)] = -1;
+        let biomes = read_legacy_biomes(&cells).unwrap();
+        assert_eq!((biomes[0], biomes[Chunk::biome_index(0, 64, 0)]), (Biome::Swamp, Biome::Plains));
+        assert!(read_legacy_biomes(&[0; 3]).is_none());
+    }
+}
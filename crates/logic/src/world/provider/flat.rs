@@ -0,0 +1,43 @@
+use falcon_core::error::FalconCoreError;
+use falcon_core::world::block_states::parse_state;
+use falcon_core::world::blocks::Blocks;
+use falcon_core::world::chunks::{Chunk, ChunkPos, SECTIONS_NUM, SECTION_HEIGHT, SECTION_LENGTH, SECTION_WIDTH};
+
+use super::{ChunkProvider, ProviderError};
+
+/// Generates a flat world, the same column of blocks everywhere.
+#[derive(Clone, Debug)]
+pub struct FlatGenerator {
+    /// From the bottom up, higher layers than the world are left out.
+    layers: Vec<Blocks>,
+}
+
+impl FlatGenerator {
+    pub fn new(mut layers: Vec<Blocks>) -> Self {
+        layers.truncate((SECTIONS_NUM * SECTION_HEIGHT) as usize);
+        FlatGenerator { layers }
+    }
+
+    /// The layers as block states like `minecraft:grass_block[snowy=false]`.
+    pub fn from_names<S: AsRef<str>>(names: &[S]) -> Result<Self, ProviderError> {
+        let layers = names
+            .iter()
+            .map(|name| parse_state(name.as_ref()).map_err(|e| ProviderError::Block(name.as_ref().to_owned(), e)))
+            .collect::<Result<_, _>>()?;
+        Ok(FlatGenerator::new(layers))
+    }
+}
+
+impl ChunkProvider for FlatGenerator {
+    fn load_chunk(&self, pos: ChunkPos) -> Result<Option<Chunk>, FalconCoreError> {
+        let mut chunk = Chunk::empty(pos);
+        for (y, block) in self.layers.iter().enumerate().filter(|(_, block)| **block != Blocks::Air) {
+            for z in 0..SECTION_LENGTH {
+                for x in 0..SECTION_WIDTH {
+                    chunk.set_block_at(x, y as u16, z, *block);
+                }
+            }
+        }
+        Ok(Some(chunk))
+    }
+}
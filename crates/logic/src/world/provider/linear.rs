@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ahash::AHashMap;
+use falcon_core::error::FalconCoreError;
+use falcon_core::world::chunks::{Chunk, ChunkPos};
+
+use super::{chunk_nbt, ChunkProvider};
+
+const SIGNATURE: u64 = 0xc3ff_1318_3cca_9d9a;
+const VERSION: u8 = 1;
+const HEADER_LENGTH: usize = 32;
+const COMPRESSION_LEVEL: i8 = 6;
+/// The chunks of a region.
+const REGION_CHUNKS: usize = 32 * 32;
+
+/// Reads the chunks of a world in the Linear format, which keeps the Anvil
+/// chunks of a region in one zstd frame. Regions stay in memory once they
+/// are read, these worlds are meant to be small like minigame maps.
+#[derive(Debug)]
+pub struct LinearProvider {
+    region_dir: PathBuf,
+    regions: Mutex<AHashMap<(i32, i32), Arc<LinearRegion>>>,
+}
+
+impl LinearProvider {
+    /// `world_dir` is the directory with the `region` directory.
+    pub fn new<P: AsRef<Path>>(world_dir: P) -> Self {
+        LinearProvider {
+            region_dir: world_dir.as_ref().join("region"),
+            regions: Mutex::new(AHashMap::new()),
+        }
+    }
+
+    /// Writes `chunks` to `.linear` region files in the `region` directory
+    /// of `world_dir`, replacing the region files that are there. Returns
+    /// the number of region files.
+    pub fn export<'a, P, I>(world_dir: P, chunks: I) -> Result<usize, FalconCoreError>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = &'a Chunk>,
+    {
+        let region_dir = world_dir.as_ref().join("region");
+        std::fs::create_dir_all(&region_dir)?;
+        let mut regions: BTreeMap<(i32, i32), Vec<&Chunk>> = BTreeMap::new();
+        for chunk in chunks {
+            let pos = chunk.get_position();
+            regions.entry((pos.x >> 5, pos.z >> 5)).or_default().push(chunk);
+        }
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+        for ((x, z), chunks) in &regions {
+            let file = write_region(chunks, timestamp)?;
+            std::fs::write(region_dir.join(format!("r.{}.{}.linear", x, z)), file)?;
+        }
+        Ok(regions.len())
+    }
+
+    fn region(&self, x: i32, z: i32) -> Result<Arc<LinearRegion>, FalconCoreError> {
+        if let Some(region) = self.regions.lock().unwrap().get(&(x, z)) {
+            return Ok(region.clone());
+        }
+        let region = match std::fs::read(self.region_dir.join(format!("r.{}.{}.linear", x, z))) {
+            Ok(file) => LinearRegion::read(&file)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => LinearRegion::default(),
+            Err(e) => return Err(e.into()),
+        };
+        let region = Arc::new(region);
+        self.regions.lock().unwrap().insert((x, z), region.clone());
+        Ok(region)
+    }
+}
+
+impl ChunkProvider for LinearProvider {
+    fn load_chunk(&self, pos: ChunkPos) -> Result<Option<Chunk>, FalconCoreError> {
+        let region = self.region(pos.x >> 5, pos.z >> 5)?;
+        match region.chunks.get(chunk_index(pos)) {
+            Some(Some(nbt)) => chunk_nbt::read_chunk(pos, nbt).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// The chunk NBT of a region file, empty if there is no file.
+#[derive(Debug, Default)]
+struct LinearRegion {
+    chunks: Vec<Option<Vec<u8>>>,
+}
+
+impl LinearRegion {
+    fn read(file: &[u8]) -> Result<Self, FalconCoreError> {
+        let invalid = |reason: &str| FalconCoreError::InvalidData(format!("Invalid linear region file: {}", reason));
+        if file.len() < HEADER_LENGTH + 8 || read_u64(file, 0) != SIGNATURE || read_u64(file, file.len() - 8) != SIGNATURE {
+            return Err(invalid("missing signature"));
+        }
+        if file[8] != VERSION {
+            return Err(invalid(&format!("unsupported version {}", file[8])));
+        }
+        let length = u32::from_be_bytes([file[20], file[21], file[22], file[23]]) as usize;
+        let compressed = file.get(HEADER_LENGTH..HEADER_LENGTH + length).ok_or_else(|| invalid("truncated"))?;
+        let mut data = Vec::new();
+        zstd::Decoder::new(compressed)?.read_to_end(&mut data)?;
+        if data.len() < REGION_CHUNKS * 8 {
+            return Err(invalid("truncated chunk table"));
+        }
+        let mut offset = REGION_CHUNKS * 8;
+        let mut chunks = Vec::with_capacity(REGION_CHUNKS);
+        for i in 0..REGION_CHUNKS {
+            let size = u32::from_be_bytes([data[i * 8], data[i * 8 + 1], data[i * 8 + 2], data[i * 8 + 3]]) as usize;
+            let nbt = match size {
+                0 => None,
+                _ => Some(data.get(offset..offset + size).ok_or_else(|| invalid("truncated chunk"))?.to_vec()),
+            };
+            offset += size;
+            chunks.push(nbt);
+        }
+        Ok(LinearRegion { chunks })
+    }
+}
+
+fn write_region(chunks: &[&Chunk], timestamp: u64) -> Result<Vec<u8>, FalconCoreError> {
+    let mut nbt = vec![Vec::new(); REGION_CHUNKS];
+    for chunk in chunks {
+        nbt[chunk_index(*chunk.get_position())] = chunk_nbt::write_chunk(chunk)?;
+    }
+    let mut data = Vec::with_capacity(REGION_CHUNKS * 8 + nbt.iter().map(Vec::len).sum::<usize>());
+    for chunk in &nbt {
+        data.extend_from_slice(&(chunk.len() as u32).to_be_bytes());
+        data.extend_from_slice(&(timestamp as u32).to_be_bytes());
+    }
+    nbt.iter().for_each(|chunk| data.extend_from_slice(chunk));
+    let compressed = zstd::encode_all(&data[..], COMPRESSION_LEVEL as i32)?;
+
+    let mut file = Vec::with_capacity(HEADER_LENGTH + compressed.len() + 8);
+    file.extend_from_slice(&SIGNATURE.to_be_bytes());
+    file.push(VERSION);
+    file.extend_from_slice(&timestamp.to_be_bytes());
+    file.push(COMPRESSION_LEVEL as u8);
+    file.extend_from_slice(&(chunks.len() as u16).to_be_bytes());
+    file.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+    file.extend_from_slice(&0u64.to_be_bytes());
+    file.extend_from_slice(&compressed);
+    file.extend_from_slice(&SIGNATURE.to_be_bytes());
+    Ok(file)
+}
+
+fn chunk_index(pos: ChunkPos) -> usize { ((pos.x & 31) + (pos.z & 31) * 32) as usize }
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    let mut value = [0; 8];
+    value.copy_from_slice(&bytes[offset..offset + 8]);
+    u64::from_be_bytes(value)
+}
+
+#[cfg(test)]
+mod test {
+    use falcon_core::world::chunks::{Chunk, ChunkPos};
+
+    use super::{write_region, LinearRegion, REGION_CHUNKS};
+
+    #[test]
+    fn test_region_file() {
+        let chunks = [Chunk::empty(ChunkPos::new(-1, 0)), Chunk::empty(ChunkPos::new(2, 3))];
+        let file = write_region(&chunks.iter().collect::<Vec<_>>(), 1_700_000_000).unwrap();
+        let region = LinearRegion::read(&file).unwrap();
+        assert_eq!(region.chunks.len(), REGION_CHUNKS);
+        // empty chunks still have their NBT
+        assert!(region.chunks[31].is_some());
+        assert!(region.chunks[2 + 3 * 32].is_some());
+        assert_eq!(region.chunks.iter().flatten().count(), 2);
+
+        assert!(LinearRegion::read(&file[..file.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use std::io::Read;
+use std::path::Path;
+
+use ahash::AHashMap;
+use falcon_core::error::FalconCoreError;
+use falcon_core::schematic::{SchematicData, SchematicVersionedRaw};
+use falcon_core::world::blocks::Blocks;
+use falcon_core::world::chunks::{Chunk, ChunkPos, SECTION_LENGTH, SECTION_WIDTH};
+use falcon_packet_core::{PacketRead, VarI32};
+use flate2::read::GzDecoder;
+
+use super::{ChunkProvider, ProviderError};
+
+/// Chunks kept in memory, e.g. from a schematic or made by a plugin.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryProvider {
+    chunks: AHashMap<ChunkPos, Chunk>,
+}
+
+impl MemoryProvider {
+    pub fn new() -> Self { MemoryProvider::default() }
+
+    /// Reads a gzipped sponge schematic, its corner is at chunk 0, 0.
+    pub fn read_schematic<P: AsRef<Path>>(path: P) -> Result<Self, ProviderError> {
+        let path = path.as_ref();
+        let file = std::fs::read(path).map_err(|e| ProviderError::Io(path.to_path_buf(), e))?;
+        let mut nbt = Vec::new();
+        GzDecoder::new(&file[..])
+            .read_to_end(&mut nbt)
+            .map_err(|e| ProviderError::Io(path.to_path_buf(), e))?;
+        let schematic: SchematicVersionedRaw = fastnbt::from_bytes(&nbt).map_err(|e| ProviderError::Nbt(path.to_path_buf(), e.to_string()))?;
+        SchematicData::try_from(schematic)
+            .and_then(MemoryProvider::try_from)
+            .map_err(|e| ProviderError::Schematic(path.to_path_buf(), e))
+    }
+
+    /// Replaces the chunk at the same position.
+    pub fn insert(&mut self, chunk: Chunk) { self.chunks.insert(*chunk.get_position(), chunk); }
+
+    /// The chunk at `pos`, an empty chunk is added if there is none.
+    pub fn get_mut(&mut self, pos: ChunkPos) -> &mut Chunk { self.chunks.entry(pos).or_insert_with(|| Chunk::empty(pos)) }
+
+    pub fn len(&self) -> usize { self.chunks.len() }
+
+    pub fn is_empty(&self) -> bool { self.chunks.is_empty() }
+
+    pub fn into_chunks(self) -> impl Iterator<Item = Chunk> { self.chunks.into_iter().map(|(_, chunk)| chunk) }
+}
+
+impl ChunkProvider for MemoryProvider {
+    fn load_chunk(&self, pos: ChunkPos) -> Result<Option<Chunk>, FalconCoreError> { Ok(self.chunks.get(&pos).cloned()) }
+}
+
+impl<'a> TryFrom<SchematicData<'a>> for MemoryProvider {
+    type Error = FalconCoreError;
+
+    fn try_from(schematic: SchematicData<'a>) -> Result<Self, Self::Error> {
+        let air_value = schematic
+            .palette
+            .iter()
+            .find(|(_, value)| *value == &Blocks::Air)
+            .map(|(index, _)| *index);
+        let block_data: Vec<u8> = schematic.block_data.iter().map(|b| b as u8).collect();
+        let mut schematic_blocks = &block_data[..];
+
+        let mut chunks = MemoryProvider::new();
+        for y in 0..schematic.height as usize {
+            for z in 0..schematic.length as usize {
+                for x in 0..schematic.width as usize {
+                    let schematic_block = VarI32::read(&mut schematic_blocks)
+                        .map_err(|_| FalconCoreError::InvalidData(String::from("Invalid world data, fewer blocks than size given!!")))?
+                        .val();
+                    match air_value {
+                        Some(value) if value == schematic_block => {},
+                        _ => {
+                            let chunk_pos = ChunkPos::new((x / SECTION_WIDTH as usize) as i32, (z / SECTION_LENGTH as usize) as i32);
+                            let palette_entry = *schematic.palette.get(&schematic_block).ok_or_else(|| {
+                                FalconCoreError::InvalidData(String::from("Invalid schematic data, could not find corresponding palette entry!!"))
+                            })?;
+                            let chunk = chunks.get_mut(chunk_pos);
+                            chunk.set_block_at(
+                                (x as i32 - (chunk_pos.x * SECTION_WIDTH as i32)) as u16,
+                                y as u16,
+                                (z as i32 - (chunk_pos.z * SECTION_LENGTH as i32)) as u16,
+                                palette_entry,
+                            );
+                        },
+                    }
+                }
+            }
+        }
+        Ok(chunks)
+    }
+}
@@ -0,0 +1,111 @@
+//! The [`ChunkProvider`]s the world can be loaded from, picked by the world
+//! sources of the config.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use falcon_core::error::FalconCoreError;
+use falcon_core::server::config::{ChunkSource, FalconConfig};
+use falcon_core::world::blocks::ParseBlockError;
+use falcon_core::world::chunks::{Chunk, ChunkPos};
+use thiserror::Error;
+
+pub use self::anvil::AnvilProvider;
+pub use self::flat::FlatGenerator;
+pub use self::linear::LinearProvider;
+pub use self::memory::MemoryProvider;
+pub use super::loader::ChunkProvider;
+
+mod anvil;
+mod chunk_nbt;
+mod flat;
+mod linear;
+mod memory;
+
+#[derive(Error, Debug)]
+pub enum ProviderError {
+    #[error("Could not read {0:?}")]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error("Invalid NBT in {0:?}: {1}")]
+    Nbt(PathBuf, String),
+    #[error("Invalid schematic {0:?}")]
+    Schematic(PathBuf, #[source] FalconCoreError),
+    #[error("Unknown block {0:?} in the layers of a flat world")]
+    Block(String, #[source] ParseBlockError),
+}
+
+/// Asks each provider in turn until one has the chunk, e.g. a world on disk
+/// with a generator for the chunks it does not have.
+pub struct LayeredProvider {
+    providers: Vec<Arc<dyn ChunkProvider>>,
+}
+
+impl LayeredProvider {
+    pub fn new(providers: Vec<Arc<dyn ChunkProvider>>) -> Self { LayeredProvider { providers } }
+
+    /// Asks `provider` after the providers that are already there.
+    pub fn then(mut self, provider: Arc<dyn ChunkProvider>) -> Self {
+        self.providers.push(provider);
+        self
+    }
+}
+
+impl ChunkProvider for LayeredProvider {
+    fn load_chunk(&self, pos: ChunkPos) -> Result<Option<Chunk>, FalconCoreError> {
+        for provider in &self.providers {
+            if let Some(chunk) = provider.load_chunk(pos)? {
+                return Ok(Some(chunk));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The provider of the world sources of the config, `None` if there are
+/// no sources.
+pub fn from_config() -> Result<Option<Arc<dyn ChunkProvider>>, ProviderError> {
+    let mut providers = Vec::new();
+    for source in &FalconConfig::global().world.sources {
+        let provider: Arc<dyn ChunkProvider> = match source {
+            ChunkSource::Anvil { path } => Arc::new(AnvilProvider::new(path)),
+            ChunkSource::Linear { path } => Arc::new(LinearProvider::new(path)),
+            ChunkSource::Schematic { path } => Arc::new(MemoryProvider::read_schematic(path)?),
+            ChunkSource::Flat { layers } => Arc::new(FlatGenerator::from_names(layers)?),
+        };
+        providers.push(provider);
+    }
+    Ok(match providers.len() {
+        0 => None,
+        1 => providers.pop(),
+        _ => Some(Arc::new(LayeredProvider::new(providers))),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use falcon_core::world::blocks::Blocks;
+    use falcon_core::world::chunks::{Chunk, ChunkPos};
+
+    use super::{ChunkProvider, FlatGenerator, LayeredProvider, MemoryProvider};
+
+    #[test]
+    fn test_layered_provider() {
+        let mut memory = MemoryProvider::new();
+        let mut chunk = Chunk::empty(ChunkPos::new(1, 1));
+        chunk.set_block_at(0, 0, 0, Blocks::Stone);
+        memory.insert(chunk);
+        let layered = LayeredProvider::new(vec![Arc::new(memory)]);
+        assert!(layered.load_chunk(ChunkPos::new(0, 0)).unwrap().is_none());
+
+        let layered = layered.then(Arc::new(FlatGenerator::new(vec![Blocks::Bedrock, Blocks::Dirt])));
+        let stored = layered.load_chunk(ChunkPos::new(1, 1)).unwrap().unwrap();
+        assert_eq!(stored.block_at(0, 0, 0), &Blocks::Stone);
+        assert_eq!(stored.block_at(0, 1, 0), &Blocks::Air);
+        let generated = layered.load_chunk(ChunkPos::new(-7, 3)).unwrap().unwrap();
+        assert_eq!(*generated.get_position(), ChunkPos::new(-7, 3));
+        assert_eq!((generated.block_at(15, 0, 15), generated.block_at(15, 1, 15)), (&Blocks::Bedrock, &Blocks::Dirt));
+        assert_eq!(generated.block_at(15, 2, 15), &Blocks::Air);
+    }
+}
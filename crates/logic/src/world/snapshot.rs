@@ -0,0 +1,80 @@
+//! Copies of parts of the world that can be put back later, e.g. to reset
+//! a minigame arena between rounds.
+
+use falcon_core::world::chunks::{Chunk, ChunkPos};
+
+use super::FalconWorld;
+
+/// The chunks of a rectangle of the world as they were when the snapshot
+/// was taken.
+#[derive(Clone, Debug)]
+pub struct WorldSnapshot {
+    /// `None` for chunks that were not in memory, those are loaded again
+    /// from the provider of the world, or empty without one.
+    chunks: Vec<(ChunkPos, Option<Chunk>)>,
+}
+
+impl WorldSnapshot {
+    /// The positions of the chunks in the snapshot.
+    pub fn positions(&self) -> impl Iterator<Item = ChunkPos> + '_ { self.chunks.iter().map(|(pos, _)| *pos) }
+
+    pub fn len(&self) -> usize { self.chunks.len() }
+
+    pub fn is_empty(&self) -> bool { self.chunks.is_empty() }
+
+    /// An estimate of the memory used by the copied chunks in bytes.
+    pub fn memory_size(&self) -> usize { self.chunks.iter().flat_map(|(_, chunk)| chunk).map(Chunk::memory_size).sum() }
+}
+
+impl FalconWorld {
+    /// Copies the chunks from `from` to `to`, both included.
+    pub fn snapshot(&self, from: ChunkPos, to: ChunkPos) -> WorldSnapshot {
+        let mut chunks = Vec::new();
+        for x in from.x.min(to.x)..=from.x.max(to.x) {
+            for z in from.z.min(to.z)..=from.z.max(to.z) {
+                let pos = ChunkPos::new(x, z);
+                chunks.push((pos, self.chunks.peek(pos).cloned()));
+            }
+        }
+        WorldSnapshot { chunks }
+    }
+
+    /// Puts back the chunks of a snapshot, this does not send them to the
+    /// players. See [`FalconServer::restore_snapshot`](crate::FalconServer::restore_snapshot).
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) {
+        for (pos, chunk) in &snapshot.chunks {
+            match chunk {
+                Some(chunk) => self.chunks.insert(chunk.clone()),
+                None => {
+                    self.chunks.remove(*pos);
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use falcon_core::world::blocks::Blocks;
+    use falcon_core::world::chunks::ChunkPos;
+
+    use crate::world::FalconWorld;
+
+    #[test]
+    fn test_snapshot() {
+        let mut world = FalconWorld::new(4, -1, -1, 1, 1);
+        world.get_chunk_mut(ChunkPos::new(0, 0)).set_block_at(1, 1, 1, Blocks::Stone);
+        let snapshot = world.snapshot(ChunkPos::new(1, 1), ChunkPos::new(-1, 0));
+        assert_eq!(snapshot.len(), 6);
+
+        world.get_chunk_mut(ChunkPos::new(0, 0)).set_block_at(1, 1, 1, Blocks::Air);
+        world.get_chunk_mut(ChunkPos::new(1, 1)).set_block_at(2, 2, 2, Blocks::Dirt);
+        world.get_chunk_mut(ChunkPos::new(1, -1)).set_block_at(2, 2, 2, Blocks::Dirt);
+        world.restore(&snapshot);
+
+        assert_eq!(world.get_chunk(ChunkPos::new(0, 0)).unwrap().block_at(1, 1, 1), &Blocks::Stone);
+        assert!(world.get_chunk(ChunkPos::new(1, 1)).is_none());
+        // outside of the snapshot
+        assert_eq!(world.get_chunk(ChunkPos::new(1, -1)).unwrap().block_at(2, 2, 2), &Blocks::Dirt);
+    }
+}
@@ -3,8 +3,12 @@ use std::io::ErrorKind::NotFound;
 use std::path::Path;
 
 use anyhow::{Context, Error, Result};
+use falcon_core::data::items::ProtocolItems;
+use falcon_core::network::registry::ProtocolRegistry;
 use falcon_core::server::config::FalconConfig;
+use falcon_core::server::lang::MessageCatalog;
 use falcon_core::ShutdownHandle;
+use falcon_logic::connection::floodgate::{AesCipher, Floodgate};
 use tracing::metadata::LevelFilter;
 use tracing::{debug, info};
 use tracing_subscriber::layer::SubscriberExt;
@@ -18,6 +22,7 @@ mod server;
 #[tokio::main]
 async fn main() -> Result<()> {
     human_panic::setup_panic!();
+    falcon_logic::panic::install_hook();
 
     let log_file = load_log_file().context("Could not load log file")?;
 
@@ -52,6 +57,27 @@ async fn main() -> Result<()> {
         handle_stdout.modify(|l| {
             *l.filter_mut() = filter_level;
         })?;
+
+        if let Some(path) = &FalconConfig::global().versions.protocol_tables {
+            let registry = ProtocolRegistry::load_path(path).with_context(|| format!("The protocol tables at \"{}\" could not be loaded!", path))?;
+            info!("Loaded {} protocol table(s)", registry.len());
+            let _ = registry.init_global();
+        }
+        let items = ProtocolItems::load_dir(&FalconConfig::global().server.items_dir);
+        if !items.is_empty() {
+            info!("Loaded the items of {} protocol(s)", items.len());
+        }
+        let _ = items.init_global();
+        if let Some(path) = &FalconConfig::global().server.language_dir {
+            let catalog = MessageCatalog::load_dir(path).with_context(|| format!("The language files at \"{}\" could not be loaded!", path))?;
+            let _ = catalog.init_global();
+        }
+        let floodgate = &FalconConfig::global().floodgate;
+        if floodgate.enabled {
+            let cipher = AesCipher::load(&floodgate.key_file).with_context(|| format!("The Floodgate key at \"{}\" could not be loaded!", floodgate.key_file))?;
+            let _ = Floodgate::from_config(Box::new(cipher)).init_global();
+            info!("Letting Bedrock players in through Floodgate");
+        }
         Ok::<(), Error>(())
     }() {
         print_error!(e);
@@ -1,12 +1,22 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::{anyhow, Context};
-use falcon_core::server::config::FalconConfig;
+use falcon_core::server::config::{FalconConfig, ListenerSettings};
 use falcon_core::ShutdownHandle;
+use falcon_logic::connection::proxy::read_proxy_header;
 use falcon_logic::connection::ConnectionReceiver;
 use falcon_logic::server::ServerWrapper;
 use falcon_logic::FalconConnection;
-use tokio::net::TcpListener;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tokio::time::timeout;
 use tracing::{debug, info};
 
+/// How long a connection may take to send its PROXY protocol header.
+const PROXY_HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct NetworkListener {
     shutdown_handle: ShutdownHandle,
     /// Used to clone for every client handler per connection
@@ -27,19 +37,35 @@ impl NetworkListener {
     }
 
     #[tracing::instrument(name = "network", skip(self))]
-    async fn start_listening(mut self) {
-        let listener = match TcpListener::bind(FalconConfig::global().server_socket_addrs())
-            .await
-            .with_context(|| "Could not bind to the address!")
-        {
-            Ok(listener) => listener,
-            Err(ref error) => {
-                print_error!(error);
-                return self.shutdown_handle.send_shutdown();
-            },
-        };
-        info!("Network bound to {}", listener.local_addr().unwrap());
+    async fn start_listening(self) {
+        let mut listeners = JoinSet::new();
+        for settings in FalconConfig::global().listeners() {
+            let listener = match TcpListener::bind(settings.address)
+                .await
+                .with_context(|| format!("Could not bind to {}!", settings.address))
+            {
+                Ok(listener) => listener,
+                Err(ref error) => {
+                    print_error!(error);
+                    continue;
+                },
+            };
+            info!("Network bound to {}", listener.local_addr().unwrap());
+            let network_listener = NetworkListener {
+                shutdown_handle: self.shutdown_handle.clone(),
+                server: self.server.clone(),
+            };
+            listeners.spawn(network_listener.accept_connections(listener, Arc::new(settings)));
+        }
+        if listeners.is_empty() {
+            return self.shutdown_handle.send_shutdown();
+        }
+        while listeners.join_next().await.is_some() {}
+        info!("Stopped network listening!");
+    }
 
+    #[tracing::instrument(name = "listener", skip_all, fields(address = %settings.address))]
+    async fn accept_connections(mut self, listener: TcpListener, settings: Arc<ListenerSettings>) {
         loop {
             tokio::select! {
                 _ = self.shutdown_handle.wait_for_shutdown() => {
@@ -49,13 +75,8 @@ impl NetworkListener {
                     match connection {
                         Ok((socket, addr)) => {
                             debug!(address = %addr, "Accepted connection");
-                            socket.set_nodelay(true).ok();
-                            let connection = FalconConnection::new(
-                                self.shutdown_handle.clone(),
-                                addr,
-                                self.server.clone(),
-                            ).await;
-                            tokio::spawn(connection.start(socket, FalconReceiver));
+                            socket.set_nodelay(FalconConfig::global().network.tcp_nodelay).ok();
+                            tokio::spawn(Self::handle_connection(socket, addr, settings.clone(), self.shutdown_handle.clone(), self.server.clone()));
                         },
                         Err(e) => {
                             print_error!(anyhow!("Connection broke due to {}", e));
@@ -64,7 +85,29 @@ impl NetworkListener {
                 }
             }
         }
-        info!("Stopped network listening!");
+    }
+
+    async fn handle_connection(
+        mut socket: TcpStream,
+        mut addr: SocketAddr,
+        settings: Arc<ListenerSettings>,
+        shutdown_handle: ShutdownHandle,
+        server: ServerWrapper,
+    ) {
+        if settings.proxy_protocol {
+            match timeout(PROXY_HEADER_TIMEOUT, read_proxy_header(&mut socket, addr)).await {
+                Ok(Ok(client)) => {
+                    debug!(proxy = %addr, address = %client, "Read PROXY protocol header");
+                    addr = client;
+                },
+                Ok(Err(error)) => return debug!(address = %addr, %error, "Dropping connection"),
+                Err(_) => return debug!(address = %addr, "Dropping connection, no PROXY protocol header received"),
+            }
+        }
+        let connection = FalconConnection::new(shutdown_handle, addr, server)
+            .await
+            .with_online_mode(settings.online_mode);
+        connection.start(socket, FalconReceiver).await;
     }
 }
 
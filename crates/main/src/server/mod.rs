@@ -4,6 +4,9 @@ use std::thread;
 use anyhow::{Context, Result};
 use falcon_core::schematic::{SchematicData, SchematicVersionedRaw};
 use falcon_core::server::config::FalconConfig;
+use falcon_core::server::lists::{BanList, IpBanList, Whitelist};
+use falcon_core::server::ops::OpList;
+use falcon_core::server::permissions::FilePermissions;
 use falcon_core::ShutdownHandle;
 use falcon_logic::server::ServerWrapper;
 use falcon_logic::{FalconServer, FalconWorld};
@@ -19,8 +22,13 @@ pub mod console;
 pub(crate) fn start_server(shutdown_handle: ShutdownHandle) -> Result<()> {
     info!("Starting server thread...");
 
-    let world = match FalconConfig::global().world_file() {
-        Some(file_name) => {
+    let provider = falcon_logic::world::provider::from_config().context("Could not load the world sources, stopping launch")?;
+    let world = match (provider, FalconConfig::global().world_file()) {
+        (Some(provider), _) => {
+            info!("Loading the world from its sources");
+            FalconWorld::new(0, 0, 0, 0, 0).with_provider(provider)
+        },
+        (None, Some(file_name)) => {
             let world_file = std::fs::read(file_name).with_context(|| format!("Could not load \"{}\", stopping launch", file_name))?;
             let mut gz = GzDecoder::new(&world_file[..]);
             let mut decompressed_world = Vec::new();
@@ -37,14 +45,30 @@ pub(crate) fn start_server(shutdown_handle: ShutdownHandle) -> Result<()> {
 
             FalconWorld::try_from(data)?
         },
-        None => FalconWorld::new(0, 0, 0, 0, 0),
+        (None, None) => FalconWorld::new(0, 0, 0, 0, 0),
     };
+    let world = world.with_default_biome(FalconConfig::global().world.default_biome);
 
     let console_rx = ConsoleListener::start_console(shutdown_handle.clone())?;
     let (server_tx, server_rx) = unbounded_channel();
-    let mut server = FalconServer::new(shutdown_handle, console_rx, server_rx, world);
+    let ops_file = &FalconConfig::global().server.ops_file;
+    let ops = OpList::load(ops_file).with_context(|| format!("Could not load the operators from \"{}\"", ops_file))?;
+    let permissions_file = &FalconConfig::global().server.permissions_file;
+    let permissions = FilePermissions::load(permissions_file).with_context(|| format!("Could not load the permissions from \"{}\"", permissions_file))?;
+    let whitelist_file = &FalconConfig::global().server.whitelist_file;
+    let whitelist = Whitelist::load(whitelist_file).with_context(|| format!("Could not load the whitelist from \"{}\"", whitelist_file))?;
+    let bans_file = &FalconConfig::global().server.banned_players_file;
+    let bans = BanList::load(bans_file).with_context(|| format!("Could not load the banned players from \"{}\"", bans_file))?;
+    let ip_bans_file = &FalconConfig::global().server.banned_ips_file;
+    let ip_bans = IpBanList::load(ip_bans_file).with_context(|| format!("Could not load the banned addresses from \"{}\"", ip_bans_file))?;
+    let mut server = FalconServer::new(shutdown_handle, console_rx, server_rx, world)
+        .with_ops(ops)
+        .with_permissions(Box::new(permissions))
+        .with_whitelist(whitelist)
+        .with_bans(bans)
+        .with_ip_bans(ip_bans);
 
-    tokio::spawn(NetworkListener::start_network_listening(server.shutdown_handle().clone(), ServerWrapper::new(server_tx)));
+    tokio::spawn(NetworkListener::start_network_listening(server.shutdown_handle().clone(), ServerWrapper::new(server_tx, server.status().clone())));
 
     thread::Builder::new()
         .name(String::from("Main Server Thread"))
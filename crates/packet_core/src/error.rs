@@ -1,4 +1,7 @@
-use std::string::FromUtf8Error;
+use alloc::boxed::Box;
+use alloc::string::{FromUtf8Error, String};
+use alloc::vec::Vec;
+use alloc::{format, vec};
 
 use thiserror::Error;
 
@@ -6,10 +9,54 @@ use thiserror::Error;
 pub enum WriteError {
     #[error("String was longer than allowed: {1} > {0}")]
     StringTooLong(usize, usize),
+    #[cfg(feature = "std")]
     #[error("Couldn't serialize to NBT")]
     FastNbtError(#[from] fastnbt::error::Error),
     #[error("Buffer ran out of space")]
     EndOfBuffer,
+    #[error("Couldn't serialize: {0}")]
+    Serde(String),
+    /// Which field could not be written, added by the derive macros.
+    #[error("{error} (in {})", .path.join(" > "))]
+    Context {
+        error: Box<WriteError>,
+        /// The fields that were being written, outermost first, formatted
+        /// as `Struct.field`.
+        path: Vec<String>,
+    },
+}
+
+impl WriteError {
+    /// Adds the field that was being written to the context of this error.
+    pub fn in_field(self, struct_name: &str, field: &str) -> WriteError {
+        let segment = format!("{}.{}", struct_name, field);
+        match self {
+            WriteError::Context { error, mut path } => {
+                path.insert(0, segment);
+                WriteError::Context { error, path }
+            },
+            error => WriteError::Context {
+                error: Box::new(error),
+                path: vec![segment],
+            },
+        }
+    }
+
+    /// The error without its context.
+    pub fn root(&self) -> &WriteError {
+        match self {
+            WriteError::Context { error, .. } => error.root(),
+            error => error,
+        }
+    }
+
+    /// The chain of fields that were being written, outermost first.
+    pub fn field_path(&self) -> &[String] {
+        match self {
+            WriteError::Context { path, .. } => path,
+            _ => &[],
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -17,13 +64,85 @@ pub enum ReadError {
     #[error("Invalid UTF-8 received")]
     FromUTF8Error(#[from] FromUtf8Error),
     #[error("Invalid StrUuid received")]
-    UuidError(#[from] uuid::Error),
+    UuidError(#[cfg_attr(feature = "std", source)] uuid::Error),
+    #[cfg(feature = "std")]
     #[error("Couldn't deserialize from NBT")]
     FastNbtError(#[from] fastnbt::error::Error),
     #[error("String was longer than allowed: {1} > {0}")]
     StringTooLong(usize, usize),
+    #[error("Invalid identifier received")]
+    InvalidIdentifier(#[from] crate::IdentifierError),
+    #[error("Expected a non-zero {0} but received 0")]
+    ZeroValue(&'static str),
     #[error("VarInt was longer than allowed")]
     VarTooLong,
     #[error("Reached EOF of input buffer")]
     NoMoreBytes,
+    #[error("Expected {expected} more bytes but only {remaining} remain")]
+    NotEnoughBytes { expected: usize, remaining: usize },
+    #[error("Frame was longer than allowed: {1} > {0}")]
+    FrameTooLong(usize, usize),
+    #[error("Couldn't deserialize: {0}")]
+    Serde(String),
+    #[cfg(feature = "std")]
+    #[error("Couldn't read from the input stream")]
+    IoError(#[from] std::io::Error),
+    /// Where an error happened, added by the derive macros.
+    #[error("{error} (at byte {offset} in {})", .path.join(" > "))]
+    Context {
+        error: Box<ReadError>,
+        /// The fields that were being read, outermost first, formatted
+        /// as `Struct.field`.
+        path: Vec<String>,
+        /// The offset from the start of the outermost struct.
+        offset: usize,
+    },
+}
+
+// uuid only implements the error trait with std
+impl From<uuid::Error> for ReadError {
+    fn from(error: uuid::Error) -> Self { ReadError::UuidError(error) }
+}
+
+impl ReadError {
+    /// Adds the field that was being read to the context of this error,
+    /// `offset` is counted from the start of `struct_name`.
+    pub fn in_field(self, struct_name: &str, field: &str, offset: usize) -> ReadError {
+        let segment = format!("{}.{}", struct_name, field);
+        match self {
+            ReadError::Context { error, mut path, .. } => {
+                path.insert(0, segment);
+                ReadError::Context { error, path, offset }
+            },
+            error => ReadError::Context {
+                error: Box::new(error),
+                path: vec![segment],
+                offset,
+            },
+        }
+    }
+
+    /// The error without its context.
+    pub fn root(&self) -> &ReadError {
+        match self {
+            ReadError::Context { error, .. } => error.root(),
+            error => error,
+        }
+    }
+
+    /// The offset at which reading failed, if known.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            ReadError::Context { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// The chain of fields that were being read, outermost first.
+    pub fn field_path(&self) -> &[String] {
+        match self {
+            ReadError::Context { path, .. } => path,
+            _ => &[],
+        }
+    }
 }
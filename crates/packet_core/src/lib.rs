@@ -30,6 +30,15 @@
 //! generally need to know their length from some other field when reading.
 //! - ...
 //!
+//! ## **Protocol versions**
+//! Fields marked with `#[falcon(since = 477)]` or `#[falcon(until = 340)]`
+//! are only present starting from, respectively up to and including, the
+//! given protocol version. Structs with such fields derive
+//! [`PacketReadVersioned`], [`PacketWriteVersioned`] and
+//! [`PacketSizeVersioned`] instead of the plain traits, absent fields are
+//! read as their [`Default`] value. [`PacketVersioned`] is the seed that
+//! carries the protocol version.
+//!
 //! ## **How to implement**
 //! For user implementations, it is highly encouraged to use the following
 //! derive macros:
@@ -37,6 +46,32 @@
 //! - [`PacketWrite`](falcon_packet_core_derive::PacketWrite)
 //! - [`PacketSize`](falcon_packet_core_derive::PacketSize)
 //!
+//! Packets that belong to the protocol are best declared using the
+//! [`packet!`] macro. It derives the three traits for every packet, maps
+//! each packet to its ids per protocol version (see [`protocol::PacketId`])
+//! and generates an enum that can read any of the declared packets given
+//! the state of the connection and a packet id:
+//! ```ignore
+//! falcon_packet_core::packet! {
+//!     pub enum LoginPackets;
+//!
+//!     #[falcon_packet(state = login, direction = serverbound, versions = { -1 = 0x00 })]
+//!     pub struct LoginStartPacket {
+//!         #[falcon(string = 16)]
+//!         pub username: String,
+//!     }
+//! }
+//! ```
+//! Versions can also be given as ranges, `393..=404 = 0x22` or the
+//! open-ended `477.. = 0x23` (mind the space before `=`).
+//!
+//! The enum is optional, without `pub enum Name;` only the packets and their
+//! ids are generated. Structs without `#[falcon_packet]` and any other items
+//! are left as they are, and packets that derive some of the three traits
+//! themselves only get those. All packets of Falcon are declared this way:
+//! the client's in `falcon_client`, the server's in `falcon_send` and
+//! `falcon_receive`.
+//!
 //! ## **Provided implementations**:
 //! Numerous implementations are provided for most basic types:
 //! - **Primitive types**:
@@ -44,33 +79,95 @@
 //!     - i8, i16, i32, i64, i128
 //!     - u8, u16, u32, u64, u128
 //!     - f32, f64
+//!     - NonZero wrappers of the integers, zero is rejected when read
 //! - **Arrays**:
-//!     - [T; N] for all N
-//!     - [u8; N] for all N
+//!     - [T; N] for all N and any element type, `#[falcon(array)]`
+//!     - [u8; N] for all N, without an attribute
 //! - **String**:
 //!     - AsRef<str> for writing
 //!     - From<String> for reading
 //! - **Byte sequences**:
 //!     - AsRef<\[u8]> for writing
 //!     - From<Vec\<u8>> for reading
+//!     - The rest of a packet: `#[falcon(rest)]`, From<bytes::Bytes> for reading
+//! - **Collections**: `HashMap`, `BTreeMap`, `HashSet`, `VecDeque` and `Vec`
+//!   prefixed by their length, `#[falcon(collection)]`
 //! - **Iterators**
 //!     - IntoIterator for writing
 //!     - FromIterator for reading
 //! - **Extra**
 //!     - Uuid
 //!     - StrUuid (string representation of uuid)
+//!     - [`Identifier`] (`namespace:location`, validated when read)
+//!     - [`PacketBitSet`] (long array backed bit set, 1.17+) and
+//!       [`PacketFixedBitSet`] (bytes without a length, 1.19.3+)
+//!     - [`Angle`] (1/256 of a turn) and [`FixedPoint`] (fraction bits of the
+//!       positions before 1.9), both convert from and to floats
+//!     - `PacketFlags`, types of the `bitflags` crate as their bits with
+//!       `#[falcon(bitflags)]`, needs the `bitflags` feature
+//!
+//! ## **Testing**
+//! The `testing` feature exposes [`testing::assert_roundtrip`] to check
+//! implementations for consistency, the `proptest` feature adds property
+//! test support on top of that.
+//!
+//! The `check-size` feature makes [`special::write_packet`] panic when a
+//! packet writes a different number of bytes than [`PacketSize`] reported,
+//! which would otherwise corrupt every packet after it. Enable it in the
+//! dev-dependencies to check all packets a test sends.
+//!
+//! ## **Fuzzing**
+//! Reading never panics, malformed input gives a [`ReadError`]. The enums
+//! of [`packet!`] have a `decode_frame` function that decodes a whole frame
+//! from arbitrary bytes, meant as the entry point of fuzz targets:
+//! ```ignore
+//! fuzz_target!(|data: &[u8]| {
+//!     let _ = LoginPackets::decode_frame(ProtocolState::Login, PacketDirection::Serverbound, 763, data);
+//! });
+//! ```
+//!
+//! ## **Serde**
+//! The `serde` feature adds [`serde`](mod@crate::serde), a serializer and
+//! deserializer for quick prototypes that map serde's data model onto the
+//! conventions of the protocol.
+//!
+//! ## **`no_std`**
+//! The crate only needs `alloc` when the default `std` feature is disabled.
+//! What is left out without `std`: the `HashMap` and `HashSet` collections,
+//! the `io` adapters in [`special`] and with them NBT fields, and the
+//! `tokio` and `proptest` features which enable `std` themselves.
+//!
+//! ## **Async streams**
+//! With the `tokio` feature enabled, [`special::read_frame_async`] and
+//! [`special::read_packet_async`] read length-prefixed frames directly from
+//! any [`AsyncRead`](tokio::io::AsyncRead), taking care of partial reads.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 extern crate self as falcon_packet_core;
 use bytes::{Buf, BufMut};
 pub use error::{ReadError, WriteError};
-pub use falcon_packet_core_derive::{PacketRead, PacketSize, PacketWrite};
+pub use falcon_packet_core_derive::{packet, PacketRead, PacketSize, PacketWrite};
 pub use primitives::*;
 
 mod error;
 pub mod special;
+#[cfg(feature = "std")]
 mod test;
+#[cfg(any(feature = "testing", test))]
+pub mod testing;
 
 mod primitives;
+pub mod protocol;
+#[cfg(any(feature = "serde", test))]
+pub mod serde;
+
+/// Used by the derive macros, not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::ToString;
+}
 
 /// A data structure that can be read from a minecraft connection without
 /// needing external input; aimed to be highly modular.
@@ -152,3 +249,30 @@ pub trait PacketSizeSeed<'a> {
 
     fn size(self, value: &'a Self::Value) -> usize;
 }
+
+/// A data structure whose layout depends on the protocol version of the
+/// connection, usually because some fields only exist in a range of
+/// versions.
+///
+/// Every [`PacketRead`] type implements this trait by ignoring the version.
+/// Derived structs with `since` or `until` fields implement this trait
+/// instead of [`PacketRead`], use [`PacketVersioned`] to read them as a seed.
+pub trait PacketReadVersioned {
+    /// Read self from the buffer according to the given protocol version.
+    fn read_versioned<B>(buffer: &mut B, protocol: i32) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized;
+}
+
+/// The versioned counterpart of [`PacketWrite`], see [`PacketReadVersioned`].
+pub trait PacketWriteVersioned: PacketSizeVersioned {
+    fn write_versioned<B>(&self, buffer: &mut B, protocol: i32) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized;
+}
+
+/// The versioned counterpart of [`PacketSize`], see [`PacketReadVersioned`].
+pub trait PacketSizeVersioned {
+    fn size_versioned(&self, protocol: i32) -> usize;
+}
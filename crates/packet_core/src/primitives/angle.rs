@@ -0,0 +1,108 @@
+use bytes::{Buf, BufMut};
+
+use crate::error::{ReadError, WriteError};
+use crate::{PacketRead, PacketSize, PacketWrite};
+
+/// A rotation in steps of 1/256 of a turn, written as one byte.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Angle(pub u8);
+
+impl Angle {
+    /// Any angle in degrees, it is wrapped to a single turn first.
+    pub fn from_degrees(degrees: f32) -> Self { Angle((degrees.rem_euclid(360.0) * 256.0 / 360.0) as u8) }
+
+    /// The angle in degrees, from 0 up to 360.
+    pub fn degrees(self) -> f32 { self.0 as f32 * 360.0 / 256.0 }
+}
+
+impl From<f32> for Angle {
+    fn from(degrees: f32) -> Self { Angle::from_degrees(degrees) }
+}
+
+impl From<f64> for Angle {
+    fn from(degrees: f64) -> Self { Angle::from_degrees(degrees.rem_euclid(360.0) as f32) }
+}
+
+impl From<Angle> for f32 {
+    fn from(angle: Angle) -> Self { angle.degrees() }
+}
+
+impl From<Angle> for f64 {
+    fn from(angle: Angle) -> Self { angle.degrees() as f64 }
+}
+
+impl PacketRead for Angle {
+    fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized,
+    {
+        Ok(Angle(u8::read(buffer)?))
+    }
+}
+
+impl PacketWrite for Angle {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        self.0.write(buffer)
+    }
+}
+
+impl PacketSize for Angle {
+    #[inline]
+    fn size(&self) -> usize { 1 }
+}
+
+/// A number with `FRACTION` fraction bits, like the entity positions before
+/// 1.9 that are written as `FixedPoint<i32, 5>` and the relative moves as
+/// `FixedPoint<i8, 5>`. Converting a float rounds towards negative infinity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FixedPoint<T, const FRACTION: u32>(pub T);
+
+macro_rules! impl_fixed_point {
+    ($($num:ident),*) => {$(
+        impl<const FRACTION: u32> From<f64> for FixedPoint<$num, FRACTION> {
+            fn from(value: f64) -> Self { FixedPoint((value * (1u64 << FRACTION) as f64).floor() as $num) }
+        }
+
+        impl<const FRACTION: u32> From<f32> for FixedPoint<$num, FRACTION> {
+            fn from(value: f32) -> Self { FixedPoint::from(value as f64) }
+        }
+
+        impl<const FRACTION: u32> From<FixedPoint<$num, FRACTION>> for f64 {
+            fn from(value: FixedPoint<$num, FRACTION>) -> Self { value.0 as f64 / (1u64 << FRACTION) as f64 }
+        }
+
+        impl<const FRACTION: u32> From<FixedPoint<$num, FRACTION>> for f32 {
+            fn from(value: FixedPoint<$num, FRACTION>) -> Self { f64::from(value) as f32 }
+        }
+    )*}
+}
+
+impl_fixed_point!(i8, i16, i32, i64);
+
+impl<T: PacketRead, const FRACTION: u32> PacketRead for FixedPoint<T, FRACTION> {
+    fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized,
+    {
+        Ok(FixedPoint(T::read(buffer)?))
+    }
+}
+
+impl<T: PacketWrite, const FRACTION: u32> PacketWrite for FixedPoint<T, FRACTION> {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        self.0.write(buffer)
+    }
+}
+
+impl<T: PacketSize, const FRACTION: u32> PacketSize for FixedPoint<T, FRACTION> {
+    #[inline]
+    fn size(&self) -> usize { self.0.size() }
+}
@@ -1,5 +1,5 @@
-use std::marker::PhantomData;
-use std::mem::MaybeUninit;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
 
 use bytes::{Buf, BufMut};
 
@@ -95,7 +95,10 @@ impl<const N: usize> PacketRead for [u8; N] {
         Self: Sized,
     {
         if buffer.remaining() < N {
-            return Err(ReadError::NoMoreBytes);
+            return Err(ReadError::NotEnoughBytes {
+                expected: N,
+                remaining: buffer.remaining(),
+            });
         }
         let mut buf = [0u8; N];
         buffer.copy_to_slice(&mut buf);
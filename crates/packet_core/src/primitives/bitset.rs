@@ -0,0 +1,202 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use bytes::{Buf, BufMut};
+
+use crate::{PacketRead, PacketSize, PacketWrite, ReadError, VarI32, WriteError};
+
+/// Java's `BitSet` as sent since 1.17: a VarInt-prefixed array of longs
+/// where bit `n` is bit `n % 64` of long `n / 64`.
+///
+/// Trailing empty longs are never written, like `BitSet::toLongArray`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PacketBitSet {
+    words: Vec<u64>,
+}
+
+impl PacketBitSet {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn from_words(words: Vec<u64>) -> Self {
+        let mut set = PacketBitSet { words };
+        set.trim();
+        set
+    }
+
+    pub fn get(&self, index: usize) -> bool { self.words.get(index / 64).map(|word| word & (1 << (index % 64)) != 0).unwrap_or(false) }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        let word = index / 64;
+        if value {
+            if word >= self.words.len() {
+                self.words.resize(word + 1, 0);
+            }
+            self.words[word] |= 1 << (index % 64);
+        } else if let Some(bits) = self.words.get_mut(word) {
+            *bits &= !(1 << (index % 64));
+            self.trim();
+        }
+    }
+
+    pub fn clear(&mut self, index: usize) { self.set(index, false) }
+
+    /// The indices of the set bits, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ { set_bits(self.words.iter().copied(), 64) }
+
+    /// The number of set bits.
+    pub fn count(&self) -> usize { self.words.iter().map(|word| word.count_ones() as usize).sum() }
+
+    pub fn words(&self) -> &[u64] { &self.words }
+
+    pub fn is_empty(&self) -> bool { self.words.is_empty() }
+
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+}
+
+impl FromIterator<usize> for PacketBitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = PacketBitSet::new();
+        iter.into_iter().for_each(|index| set.set(index, true));
+        set
+    }
+}
+
+impl From<u64> for PacketBitSet {
+    fn from(bits: u64) -> Self { PacketBitSet::from_words(vec![bits]) }
+}
+
+impl PacketSize for PacketBitSet {
+    fn size(&self) -> usize { VarI32::from(self.words.len()).size() + self.words.len() * core::mem::size_of::<u64>() }
+}
+
+impl PacketWrite for PacketBitSet {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        VarI32::from(self.words.len()).write(buffer)?;
+        for word in &self.words {
+            word.write(buffer)?;
+        }
+        Ok(())
+    }
+}
+
+impl PacketRead for PacketBitSet {
+    fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized,
+    {
+        let len = VarI32::read(buffer)?.as_usize();
+        let expected = len.saturating_mul(core::mem::size_of::<u64>());
+        if buffer.remaining() < expected {
+            return Err(ReadError::NotEnoughBytes {
+                expected,
+                remaining: buffer.remaining(),
+            });
+        }
+        let words = (0..len).map(|_| u64::read(buffer)).collect::<Result<Vec<u64>, ReadError>>()?;
+        Ok(PacketBitSet::from_words(words))
+    }
+}
+
+/// Java's `FixedBitSet` as sent since 1.19.3, e.g. the acknowledged chat
+/// messages: `BYTES` bytes without a length where bit `n` is bit `n % 8`
+/// of byte `n / 8`. The 20 acknowledgements fit in a `PacketFixedBitSet<3>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PacketFixedBitSet<const BYTES: usize> {
+    bytes: [u8; BYTES],
+}
+
+impl<const BYTES: usize> PacketFixedBitSet<BYTES> {
+    /// The number of bits in the set.
+    pub const BITS: usize = BYTES * 8;
+
+    pub fn new() -> Self { Self::default() }
+
+    pub fn from_bytes(bytes: [u8; BYTES]) -> Self { PacketFixedBitSet { bytes } }
+
+    /// # Panics
+    /// When `index` is not below [`Self::BITS`].
+    pub fn get(&self, index: usize) -> bool { self.bytes[index / 8] & (1 << (index % 8)) != 0 }
+
+    /// # Panics
+    /// When `index` is not below [`Self::BITS`].
+    pub fn set(&mut self, index: usize, value: bool) {
+        if value {
+            self.bytes[index / 8] |= 1 << (index % 8);
+        } else {
+            self.bytes[index / 8] &= !(1 << (index % 8));
+        }
+    }
+
+    /// # Panics
+    /// When `index` is not below [`Self::BITS`].
+    pub fn clear(&mut self, index: usize) { self.set(index, false) }
+
+    /// The indices of the set bits, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ { set_bits(self.bytes.iter().map(|&byte| byte as u64), 8) }
+
+    /// The number of set bits.
+    pub fn count(&self) -> usize { self.bytes.iter().map(|byte| byte.count_ones() as usize).sum() }
+
+    pub fn bytes(&self) -> &[u8; BYTES] { &self.bytes }
+
+    pub fn is_empty(&self) -> bool { self.bytes.iter().all(|&byte| byte == 0) }
+}
+
+impl<const BYTES: usize> Default for PacketFixedBitSet<BYTES> {
+    fn default() -> Self { PacketFixedBitSet { bytes: [0; BYTES] } }
+}
+
+impl<const BYTES: usize> PacketSize for PacketFixedBitSet<BYTES> {
+    #[inline]
+    fn size(&self) -> usize { BYTES }
+}
+
+impl<const BYTES: usize> PacketWrite for PacketFixedBitSet<BYTES> {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        self.bytes[..].write(buffer)
+    }
+}
+
+impl<const BYTES: usize> PacketRead for PacketFixedBitSet<BYTES> {
+    fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized,
+    {
+        if buffer.remaining() < BYTES {
+            return Err(ReadError::NotEnoughBytes {
+                expected: BYTES,
+                remaining: buffer.remaining(),
+            });
+        }
+        let mut bytes = [0; BYTES];
+        buffer.copy_to_slice(&mut bytes);
+        Ok(PacketFixedBitSet { bytes })
+    }
+}
+
+/// The indices of the set bits in `words` of `width` bits each, skipping
+/// over the unset bits a word at a time.
+fn set_bits(words: impl Iterator<Item = u64>, width: usize) -> impl Iterator<Item = usize> {
+    words.enumerate().flat_map(move |(i, mut word)| {
+        core::iter::from_fn(move || {
+            if word == 0 {
+                return None;
+            }
+            let bit = word.trailing_zeros() as usize;
+            word &= word - 1;
+            Some(i * width + bit)
+        })
+    })
+}
@@ -1,6 +1,7 @@
-use std::borrow::Cow;
-use std::marker::PhantomData;
-use std::ops::Deref;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::Deref;
 
 use bytes::BufMut;
 
@@ -46,6 +47,41 @@ impl<'a, T: AsRef<[u8]>> PacketSizeSeed<'a> for AsRefU8<T> {
     fn size(self, value: &Self::Value) -> usize { value.as_ref().len() }
 }
 
+/// Reads all remaining bytes of a packet and writes them verbatim, the
+/// length is never written to the connection.
+///
+/// Reading a [`bytes::Bytes`] from a [`bytes::Bytes`] buffer does not copy.
+#[derive(Default)]
+pub struct PacketRest<T>(PhantomData<T>);
+
+impl<T: From<bytes::Bytes>> PacketReadSeed for PacketRest<T> {
+    type Value = T;
+
+    fn read<B>(self, buffer: &mut B) -> Result<Self::Value, ReadError>
+    where
+        B: bytes::Buf + ?Sized,
+    {
+        Ok(buffer.copy_to_bytes(buffer.remaining()).into())
+    }
+}
+
+impl<'a, T: AsRef<[u8]>> PacketWriteSeed<'a> for PacketRest<T> {
+    #[inline]
+    fn write<B>(self, value: &Self::Value, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        value.as_ref().write(buffer)
+    }
+}
+
+impl<'a, T: AsRef<[u8]>> PacketSizeSeed<'a> for PacketRest<T> {
+    type Value = T;
+
+    #[inline]
+    fn size(self, value: &Self::Value) -> usize { value.as_ref().len() }
+}
+
 pub struct Bytes<T> {
     size: usize,
     _marker: PhantomData<T>,
@@ -68,7 +104,10 @@ impl<T: From<Vec<u8>>> PacketReadSeed for Bytes<T> {
         B: bytes::Buf + ?Sized,
     {
         if buffer.remaining() < self.size {
-            return Err(ReadError::NoMoreBytes);
+            return Err(ReadError::NotEnoughBytes {
+                expected: self.size,
+                remaining: buffer.remaining(),
+            });
         }
         let mut buf = Vec::with_capacity(self.size);
         buf.extend_from_slice(buffer.copy_to_bytes(self.size).as_ref());
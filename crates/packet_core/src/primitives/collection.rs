@@ -0,0 +1,190 @@
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+use core::iter;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+use bytes::{Buf, BufMut};
+
+use crate::error::{ReadError, WriteError};
+use crate::{PacketRead, PacketReadSeed, PacketSize, PacketSizeSeed, PacketWrite, PacketWriteSeed, VarI32};
+
+/// Reads and writes a collection prefixed by its length as a [`VarI32`],
+/// maps are written as key/value pairs.
+pub struct PacketCollection<C>(PhantomData<C>);
+
+impl<C> Default for PacketCollection<C> {
+    fn default() -> Self { Self(PhantomData) }
+}
+
+/// Reads the length of a collection. Every element takes at least one byte,
+/// longer collections are rejected before anything is allocated.
+fn read_len<B>(buffer: &mut B) -> Result<usize, ReadError>
+where
+    B: Buf + ?Sized,
+{
+    let len = VarI32::read(buffer)?;
+    if *len < 0 || len.as_usize() > buffer.remaining() {
+        return Err(ReadError::NotEnoughBytes {
+            expected: len.as_usize(),
+            remaining: buffer.remaining(),
+        });
+    }
+    Ok(len.as_usize())
+}
+
+fn read_elements<T, C, B>(buffer: &mut B) -> Result<C, ReadError>
+where
+    T: PacketRead,
+    C: FromIterator<T>,
+    B: Buf + ?Sized,
+{
+    let len = read_len(buffer)?;
+    iter::repeat_with(|| T::read(buffer)).take(len).collect()
+}
+
+fn read_pairs<K, V, C, B>(buffer: &mut B) -> Result<C, ReadError>
+where
+    K: PacketRead,
+    V: PacketRead,
+    C: FromIterator<(K, V)>,
+    B: Buf + ?Sized,
+{
+    let len = read_len(buffer)?;
+    iter::repeat_with(|| Ok((K::read(buffer)?, V::read(buffer)?))).take(len).collect()
+}
+
+fn write_elements<'a, T, I, B>(len: usize, mut elements: I, buffer: &mut B) -> Result<(), WriteError>
+where
+    T: PacketWrite + 'a,
+    I: Iterator<Item = &'a T>,
+    B: BufMut + ?Sized,
+{
+    VarI32::from(len).write(buffer)?;
+    elements.try_for_each(|element| element.write(buffer))
+}
+
+fn write_pairs<'a, K, V, I, B>(len: usize, mut pairs: I, buffer: &mut B) -> Result<(), WriteError>
+where
+    K: PacketWrite + 'a,
+    V: PacketWrite + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+    B: BufMut + ?Sized,
+{
+    VarI32::from(len).write(buffer)?;
+    pairs.try_for_each(|(key, value)| {
+        key.write(buffer)?;
+        value.write(buffer)
+    })
+}
+
+fn elements_size<'a, T: PacketSize + 'a, I: Iterator<Item = &'a T>>(len: usize, elements: I) -> usize {
+    VarI32::from(len).size() + elements.map(|element| element.size()).sum::<usize>()
+}
+
+fn pairs_size<'a, K: PacketSize + 'a, V: PacketSize + 'a, I: Iterator<Item = (&'a K, &'a V)>>(len: usize, pairs: I) -> usize {
+    VarI32::from(len).size() + pairs.map(|(key, value)| key.size() + value.size()).sum::<usize>()
+}
+
+/// A collection that can be read by [`PacketCollection`].
+pub trait PacketCollectionRead {
+    fn read_collection<B>(buffer: &mut B) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized;
+}
+
+/// A collection that can be written by [`PacketCollection`].
+pub trait PacketCollectionWrite {
+    fn write_collection<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized;
+
+    fn collection_size(&self) -> usize;
+}
+
+impl<C: PacketCollectionRead> PacketReadSeed for PacketCollection<C> {
+    type Value = C;
+
+    fn read<B>(self, buffer: &mut B) -> Result<Self::Value, ReadError>
+    where
+        B: Buf + ?Sized,
+    {
+        C::read_collection(buffer)
+    }
+}
+
+impl<'a, C: PacketCollectionWrite> PacketWriteSeed<'a> for PacketCollection<C> {
+    fn write<B>(self, value: &'a Self::Value, buffer: &'a mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        value.write_collection(buffer)
+    }
+}
+
+impl<'a, C: PacketCollectionWrite> PacketSizeSeed<'a> for PacketCollection<C> {
+    type Value = C;
+
+    fn size(self, value: &'a Self::Value) -> usize { value.collection_size() }
+}
+
+macro_rules! impl_collection {
+    ($($(#[$attr:meta])* $collection:ident<$($param:ident),+> read { $($read_bounds:tt)* } write { $($write_bounds:tt)* } => $read:ident, $write:ident, $size:ident);*$(;)?) => {$(
+        $(#[$attr])*
+        impl<$($param),+> PacketCollectionRead for $collection<$($param),+>
+        where
+            $($read_bounds)*
+        {
+            fn read_collection<B>(buffer: &mut B) -> Result<Self, ReadError>
+            where
+                B: Buf + ?Sized,
+            {
+                $read(buffer)
+            }
+        }
+
+        $(#[$attr])*
+        impl<$($param),+> PacketCollectionWrite for $collection<$($param),+>
+        where
+            $($write_bounds)*
+        {
+            fn write_collection<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+            where
+                B: BufMut + ?Sized,
+            {
+                $write(self.len(), self.iter(), buffer)
+            }
+
+            fn collection_size(&self) -> usize { $size(self.len(), self.iter()) }
+        }
+    )*}
+}
+
+impl_collection! {
+    #[cfg(feature = "std")]
+    HashMap<K, V, S>
+        read { K: PacketRead + Eq + Hash, V: PacketRead, S: BuildHasher + Default }
+        write { K: PacketWrite, V: PacketWrite }
+        => read_pairs, write_pairs, pairs_size;
+    BTreeMap<K, V>
+        read { K: PacketRead + Ord, V: PacketRead }
+        write { K: PacketWrite, V: PacketWrite }
+        => read_pairs, write_pairs, pairs_size;
+    #[cfg(feature = "std")]
+    HashSet<T, S>
+        read { T: PacketRead + Eq + Hash, S: BuildHasher + Default }
+        write { T: PacketWrite }
+        => read_elements, write_elements, elements_size;
+    VecDeque<T>
+        read { T: PacketRead }
+        write { T: PacketWrite }
+        => read_elements, write_elements, elements_size;
+    Vec<T>
+        read { T: PacketRead }
+        write { T: PacketWrite }
+        => read_elements, write_elements, elements_size;
+}
@@ -0,0 +1,53 @@
+use core::marker::PhantomData;
+
+use bitflags::Flags;
+use bytes::{Buf, BufMut};
+
+use crate::error::{ReadError, WriteError};
+use crate::{PacketRead, PacketReadSeed, PacketSize, PacketSizeSeed, PacketWrite, PacketWriteSeed};
+
+/// Reads and writes a type generated by the `bitflags` crate as its bits,
+/// unknown bits are kept when read.
+pub struct PacketFlags<T>(PhantomData<T>);
+
+impl<T> Default for PacketFlags<T> {
+    fn default() -> Self { Self(PhantomData) }
+}
+
+impl<T> PacketReadSeed for PacketFlags<T>
+where
+    T: Flags,
+    T::Bits: PacketRead,
+{
+    type Value = T;
+
+    fn read<B>(self, buffer: &mut B) -> Result<Self::Value, ReadError>
+    where
+        B: Buf + ?Sized,
+    {
+        Ok(T::from_bits_retain(T::Bits::read(buffer)?))
+    }
+}
+
+impl<'a, T> PacketWriteSeed<'a> for PacketFlags<T>
+where
+    T: Flags,
+    T::Bits: PacketWrite,
+{
+    fn write<B>(self, value: &'a Self::Value, buffer: &'a mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        value.bits().write(buffer)
+    }
+}
+
+impl<'a, T> PacketSizeSeed<'a> for PacketFlags<T>
+where
+    T: Flags,
+    T::Bits: PacketSize,
+{
+    type Value = T;
+
+    fn size(self, value: &'a Self::Value) -> usize { value.bits().size() }
+}
@@ -0,0 +1,216 @@
+use alloc::string::String;
+use core::fmt::{Debug, Display, Formatter};
+use core::str::FromStr;
+
+use bytes::{Buf, BufMut, BytesMut};
+use thiserror::Error;
+
+use crate::error::{ReadError, WriteError};
+use crate::{PacketRead, PacketSize, PacketWrite, VarI32};
+
+/// The longest identifier the protocol allows.
+pub const MAX_IDENTIFIER_LENGTH: usize = 32767;
+
+const DEFAULT_NAMESPACE: &str = "minecraft";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IdentifierError {
+    #[error("Identifier was longer than allowed: {0} > 32767")]
+    TooLong(usize),
+    #[error("Invalid character in identifier at byte {0}")]
+    InvalidCharacter(usize),
+}
+
+/// A namespaced id like `minecraft:stone`, also called a resource location.
+///
+/// The namespace may contain `a-z`, `0-9`, `_`, `-` and `.`, the location
+/// also `/`. Ids without a namespace are in the `minecraft` namespace. The
+/// id is kept as one buffer so clones are cheap and ids read from a packet
+/// share the memory of that packet.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Identifier {
+    /// `namespace:location`, only ASCII.
+    inner: bytes::Bytes,
+    colon: usize,
+}
+
+impl Identifier {
+    /// An empty namespace is the `minecraft` namespace.
+    pub fn new(namespace: &str, location: &str) -> Result<Self, IdentifierError> {
+        let namespace = if namespace.is_empty() { DEFAULT_NAMESPACE } else { namespace };
+        check(namespace.as_bytes(), 0, is_namespace_char)?;
+        check(location.as_bytes(), namespace.len() + 1, is_location_char)?;
+        Self::join(namespace, location.as_bytes()).limit_length()
+    }
+
+    /// # Panics
+    /// When the namespace or location contains invalid characters.
+    pub fn from_static(namespace: &'static str, location: &'static str) -> Self {
+        Self::new(namespace, location).unwrap_or_else(|e| panic!("Invalid identifier {}:{}: {}", namespace, location, e))
+    }
+
+    /// # Panics
+    /// When the location contains invalid characters.
+    pub fn location_static(location: &'static str) -> Self { Self::from_static(DEFAULT_NAMESPACE, location) }
+
+    /// Parses an id without copying it when it has a namespace.
+    pub fn parse_static(input: &'static str) -> Result<Self, IdentifierError> { Self::parse(bytes::Bytes::from_static(input.as_bytes())) }
+
+    pub fn namespace(&self) -> &str { &self.as_str()[..self.colon] }
+
+    pub fn location(&self) -> &str { &self.as_str()[self.colon + 1..] }
+
+    /// The id in its `namespace:location` form.
+    pub fn as_str(&self) -> &str {
+        // only ever contains ASCII
+        core::str::from_utf8(&self.inner).unwrap()
+    }
+
+    fn parse(input: bytes::Bytes) -> Result<Self, IdentifierError> {
+        match input.iter().position(|&b| b == b':') {
+            // an empty namespace is the default one as well
+            Some(colon) if colon > 0 => {
+                check(&input[..colon], 0, is_namespace_char)?;
+                check(&input[colon + 1..], colon + 1, is_location_char)?;
+                Ok(Identifier { inner: input, colon })
+            },
+            colon => {
+                let start = colon.map_or(0, |colon| colon + 1);
+                check(&input[start..], start, is_location_char)?;
+                Ok(Self::join(DEFAULT_NAMESPACE, &input[start..]))
+            },
+        }
+        .and_then(Identifier::limit_length)
+    }
+
+    fn limit_length(self) -> Result<Self, IdentifierError> {
+        match self.inner.len() {
+            len if len > MAX_IDENTIFIER_LENGTH => Err(IdentifierError::TooLong(len)),
+            _ => Ok(self),
+        }
+    }
+
+    fn join(namespace: &str, location: &[u8]) -> Self {
+        let mut inner = BytesMut::with_capacity(namespace.len() + 1 + location.len());
+        inner.put_slice(namespace.as_bytes());
+        inner.put_u8(b':');
+        inner.put_slice(location);
+        Identifier {
+            inner: inner.freeze(),
+            colon: namespace.len(),
+        }
+    }
+}
+
+fn is_namespace_char(c: u8) -> bool { matches!(c, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.') }
+
+fn is_location_char(c: u8) -> bool { is_namespace_char(c) || c == b'/' }
+
+/// Checks every byte of `part`, `offset` is where it starts in the id.
+fn check(part: &[u8], offset: usize, allowed: fn(u8) -> bool) -> Result<(), IdentifierError> {
+    match part.iter().position(|&c| !allowed(c)) {
+        Some(position) => Err(IdentifierError::InvalidCharacter(offset + position)),
+        None => Ok(()),
+    }
+}
+
+impl Display for Identifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result { f.write_str(self.as_str()) }
+}
+
+impl Debug for Identifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result { f.debug_tuple("Identifier").field(&self.as_str()).finish() }
+}
+
+impl<'a> TryFrom<&'a str> for Identifier {
+    type Error = IdentifierError;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> { Self::parse(bytes::Bytes::copy_from_slice(input.as_bytes())) }
+}
+
+impl TryFrom<String> for Identifier {
+    type Error = IdentifierError;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> { Self::parse(bytes::Bytes::from(input)) }
+}
+
+impl FromStr for Identifier {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Identifier::try_from(s) }
+}
+
+impl PacketWrite for Identifier {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        VarI32::from(self.inner.len()).write(buffer)?;
+        self.inner[..].write(buffer)
+    }
+}
+
+impl PacketSize for Identifier {
+    fn size(&self) -> usize { VarI32::from(self.inner.len()).size() + self.inner.len() }
+}
+
+impl PacketRead for Identifier {
+    fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized,
+    {
+        let len = VarI32::read(buffer)?.as_usize();
+        if len > MAX_IDENTIFIER_LENGTH {
+            return Err(IdentifierError::TooLong(len).into());
+        }
+        if buffer.remaining() < len {
+            return Err(ReadError::NotEnoughBytes {
+                expected: len,
+                remaining: buffer.remaining(),
+            });
+        }
+        Ok(Self::parse(buffer.copy_to_bytes(len))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+
+    use super::{Identifier, IdentifierError};
+
+    #[test]
+    fn it_works() {
+        let test1 = "minecraft:test";
+        let test2 = "minecraft:test/test/123.done";
+        let test3 = Identifier::from_static("minecraft", "test");
+        let test5 = "falcon:test/test/123.done";
+
+        assert_eq!(format!("{}", Identifier::from_static("minecraft", "test")), test1);
+        assert_eq!(format!("{}", Identifier::new("minecraft", "test").unwrap()), test1);
+        assert_eq!(format!("{}", Identifier::try_from("test").unwrap()), test1);
+        assert_eq!(format!("{}", Identifier::location_static("test")), test1);
+
+        assert_eq!(format!("{}", Identifier::from_static("minecraft", "test/test/123.done")), test2);
+        assert_eq!(format!("{}", Identifier::new("minecraft", "test/test/123.done").unwrap()), test2);
+        assert_eq!(format!("{}", Identifier::try_from("test/test/123.done").unwrap()), test2);
+        assert_eq!(format!("{}", Identifier::location_static("test/test/123.done")), test2);
+
+        assert_eq!(format!("{}", Identifier::from_static("falcon", "test/test/123.done")), test5);
+        assert_eq!(format!("{}", Identifier::new("falcon", "test/test/123.done").unwrap()), test5);
+
+        assert_eq!(Identifier::location_static("test"), test3);
+        assert_eq!(Identifier::try_from(":test").unwrap(), test3);
+        assert_eq!(test3.namespace(), "minecraft");
+        assert_eq!(test3.location(), "test");
+
+        assert_eq!(Identifier::parse_static("minecraft:test").unwrap(), test3);
+        assert_eq!(Identifier::parse_static("minecraft:te*st"), Err(IdentifierError::InvalidCharacter(12)));
+        assert_eq!(Identifier::try_from("minecraft:test").unwrap(), test3);
+        assert_eq!(Identifier::try_from("mine*craft:test"), Err(IdentifierError::InvalidCharacter(4)));
+        assert_eq!(Identifier::try_from("Minecraft:test"), Err(IdentifierError::InvalidCharacter(0)));
+        assert_eq!(Identifier::new("falcon", "a:b"), Err(IdentifierError::InvalidCharacter(8)));
+        assert_eq!(Identifier::try_from("a".repeat(32758)), Err(IdentifierError::TooLong(32768)));
+    }
+}
@@ -1,27 +1,42 @@
+mod angle;
 mod array;
+mod bitset;
 mod bytes;
+mod collection;
+#[cfg(feature = "bitflags")]
+mod flags;
+mod identifier;
 mod iter;
 mod num;
 mod str;
 mod vec;
+mod version;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests;
 
+pub use self::angle::{Angle, FixedPoint};
 pub use self::array::PacketArray;
-pub use self::bytes::{AsRefU8, Bytes};
+pub use self::bitset::{PacketBitSet, PacketFixedBitSet};
+pub use self::bytes::{AsRefU8, Bytes, PacketRest};
+pub use self::collection::{PacketCollection, PacketCollectionRead, PacketCollectionWrite};
+#[cfg(feature = "bitflags")]
+pub use self::flags::PacketFlags;
+pub use self::identifier::{Identifier, IdentifierError, MAX_IDENTIFIER_LENGTH};
 pub use self::iter::PacketIter;
 pub use self::str::PacketString;
 pub use self::vec::PacketVec;
+pub use self::version::PacketVersioned;
 
 macro_rules! impl_var_int {
     ($($var:ident: $base:ident => $($in:ident),+ + $($out_ty:ident = $out:ident),+);*$(;)?) => {$(
         #[repr(transparent)]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
         pub struct $var {
             val: $base,
         }
 
-        impl std::ops::Deref for $var {
+        impl core::ops::Deref for $var {
             type Target = $base;
 
             fn deref(&self) -> &Self::Target {
@@ -29,7 +44,7 @@ macro_rules! impl_var_int {
             }
         }
 
-        impl std::ops::DerefMut for $var {
+        impl core::ops::DerefMut for $var {
             fn deref_mut(&mut self) -> &mut Self::Target {
                 &mut self.val
             }
@@ -11,7 +11,7 @@ impl PacketRead for bool {
         Self: Sized,
     {
         if !buffer.has_remaining() {
-            return Err(ReadError::NoMoreBytes);
+            return Err(ReadError::NotEnoughBytes { expected: 1, remaining: 0 });
         }
         Ok(buffer.get_u8() != 0)
     }
@@ -45,8 +45,8 @@ macro_rules! impl_num {
                 B: Buf + ?Sized,
                 Self: Sized
             {
-                if buffer.remaining() < ::std::mem::size_of::<$num>() {
-                    return Err(ReadError::NoMoreBytes);
+                if buffer.remaining() < ::core::mem::size_of::<$num>() {
+                    return Err(ReadError::NotEnoughBytes { expected: ::core::mem::size_of::<$num>(), remaining: buffer.remaining() });
                 }
                 Ok(buffer.$get())
             }
@@ -58,7 +58,7 @@ macro_rules! impl_num {
             where
                 B: BufMut + ?Sized
             {
-                if buffer.remaining_mut() < ::std::mem::size_of::<$num>() {
+                if buffer.remaining_mut() < ::core::mem::size_of::<$num>() {
                     return Err(WriteError::EndOfBuffer);
                 }
                 Ok(buffer.$put(*self))
@@ -68,7 +68,7 @@ macro_rules! impl_num {
         impl PacketSize for $num {
             #[inline]
             fn size(&self) -> usize {
-                std::mem::size_of::<$num>()
+                core::mem::size_of::<$num>()
             }
         }
     )*}
@@ -89,6 +89,52 @@ impl_num! {
     f64, get_f64, put_f64;
 }
 
+macro_rules! impl_non_zero {
+    ($($non_zero:ident = $num:ident),*$(,)?) => {$(
+        impl PacketRead for ::core::num::$non_zero {
+            #[inline]
+            fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+            where
+                B: Buf + ?Sized,
+                Self: Sized
+            {
+                ::core::num::$non_zero::new($num::read(buffer)?).ok_or(ReadError::ZeroValue(stringify!($num)))
+            }
+        }
+
+        impl PacketWrite for ::core::num::$non_zero {
+            #[inline]
+            fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+            where
+                B: BufMut + ?Sized
+            {
+                self.get().write(buffer)
+            }
+        }
+
+        impl PacketSize for ::core::num::$non_zero {
+            #[inline]
+            fn size(&self) -> usize {
+                core::mem::size_of::<$num>()
+            }
+        }
+    )*}
+}
+
+// encoded like the base type, but zero is rejected when read
+impl_non_zero! {
+    NonZeroI8 = i8,
+    NonZeroU8 = u8,
+    NonZeroI16 = i16,
+    NonZeroU16 = u16,
+    NonZeroI32 = i32,
+    NonZeroU32 = u32,
+    NonZeroI64 = i64,
+    NonZeroU64 = u64,
+    NonZeroI128 = i128,
+    NonZeroU128 = u128,
+}
+
 const fn var_max<const BITS: u32>() -> usize { (BITS as usize + 6) / 7 }
 
 macro_rules! impl_var {
@@ -124,17 +170,15 @@ macro_rules! impl_var {
                 Self: Sized
             {
                 let mut result: $num = 0;
-                for i in 0..=(var_max::<{ $num::BITS }>()) {
-                    if i > var_max::<{ $num::BITS }>() {
-                        return Err(ReadError::VarTooLong);
-                    }
+                for i in 0..var_max::<{ $num::BITS }>() {
                     let byte = u8::read(buffer)?;
                     result |= ((byte & 0x7f) as $num) << (i * 7);
                     if byte & 0x80 == 0 {
-                        break;
+                        return Ok($var::from(result));
                     }
                 }
-                Ok($var::from(result))
+                // one more byte would shift past the width of the number
+                Err(ReadError::VarTooLong)
             }
         }
     )*}
@@ -157,4 +201,13 @@ mod test {
         let num = VarI32::from(4000);
         assert_eq!(num.size(), 2);
     }
+    #[test]
+    fn test_var_too_long() {
+        let mut max = &[0xff, 0xff, 0xff, 0xff, 0x0f][..];
+        assert_eq!(VarI32::read(&mut max).unwrap().val(), -1);
+        let mut long = &[0xff, 0xff, 0xff, 0xff, 0xff, 0x01][..];
+        assert!(matches!(VarI32::read(&mut long), Err(ReadError::VarTooLong)));
+        let mut long = &[0x80; 11][..];
+        assert!(matches!(VarI64::read(&mut long), Err(ReadError::VarTooLong)));
+    }
 }
@@ -20,6 +20,18 @@ mod num {
         test_buffers([&zero, &small[4..], &medium[4..], &large[4..]], [0i32, 127i32, 123456789i32, 1912277059i32]);
         test_buffers([&zero, &small, &medium, &large], [0u64, 127u64, 123456789u64, 1234567891011u64]);
         test_buffers([&zero, &small, &medium, &large], [0u64, 127u64, 123456789u64, 1234567891011u64]);
+        test_buffers([&[0u8; 16], &[0xff; 16]], [0u128, u128::MAX]);
+        test_buffers([&[0u8; 16], &[0xff; 16]], [0i128, -1i128]);
+    }
+
+    #[test]
+    fn test_non_zero_read() {
+        use std::num::{NonZeroI32, NonZeroU8};
+
+        test_buffers([&[0u8, 0, 0, 5], &[0xff; 4]], [NonZeroI32::new(5).unwrap(), NonZeroI32::new(-1).unwrap()]);
+        let error = NonZeroU8::read(&mut Bytes::from_static(&[0])).unwrap_err();
+        assert!(matches!(error, ReadError::ZeroValue("u8")));
+        assert_eq!(error.to_string(), "Expected a non-zero u8 but received 0");
     }
 
     fn test_buffers<const N: usize, T>(buffers: [&[u8]; N], values: [T; N])
@@ -31,3 +43,229 @@ mod num {
         }
     }
 }
+
+mod roundtrip {
+    use proptest::prelude::*;
+
+    use crate::testing::{assert_roundtrip, assert_roundtrip_seed};
+    use crate::*;
+
+    proptest! {
+        #[test]
+        fn test_num_roundtrip(a: bool, b: i8, c: u16, d: i32, e: u64, f: i128, g in proptest::num::f64::NORMAL) {
+            assert_roundtrip(&a);
+            assert_roundtrip(&b);
+            assert_roundtrip(&c);
+            assert_roundtrip(&d);
+            assert_roundtrip(&e);
+            assert_roundtrip(&f);
+            assert_roundtrip(&g);
+        }
+
+        #[test]
+        fn test_non_zero_roundtrip(a: std::num::NonZeroU16, b: std::num::NonZeroI64, c: std::num::NonZeroU128) {
+            assert_roundtrip(&a);
+            assert_roundtrip(&b);
+            assert_roundtrip(&c);
+        }
+
+        #[test]
+        fn test_angle_roundtrip(a: u8, b: i32, c: i8) {
+            assert_roundtrip(&Angle(a));
+            assert_roundtrip(&FixedPoint::<i32, 5>(b));
+            assert_roundtrip(&FixedPoint::<i8, 5>(c));
+        }
+
+        #[test]
+        fn test_var_roundtrip(a: i32, b: i64) {
+            assert_roundtrip(&VarI32::from(a));
+            assert_roundtrip(&VarI64::from(b));
+        }
+
+        #[test]
+        fn test_string_roundtrip(s in "\\PC{0,40}") {
+            assert_roundtrip_seed(&s, || PacketString::new(40));
+        }
+
+        #[test]
+        fn test_identifier_roundtrip(s in "[a-z0-9_.-]{1,16}:[a-z0-9_./-]{0,32}") {
+            assert_roundtrip(&Identifier::try_from(s).unwrap());
+        }
+
+        #[test]
+        fn test_uuid_roundtrip(a: u128) {
+            assert_roundtrip(&uuid::Uuid::from_u128(a));
+        }
+
+        #[test]
+        fn test_collection_roundtrip(
+            a in proptest::collection::hash_map(any::<i32>(), any::<u8>(), 0..16),
+            b in proptest::collection::hash_set(any::<u64>(), 0..16),
+            c in proptest::collection::vec_deque(any::<bool>(), 0..16),
+        ) {
+            assert_roundtrip_seed(&a, PacketCollection::default);
+            assert_roundtrip_seed(&b, PacketCollection::default);
+            assert_roundtrip_seed(&c, PacketCollection::default);
+        }
+
+        #[test]
+        fn test_bitset_roundtrip(words in proptest::collection::vec(any::<u64>(), 0..8)) {
+            assert_roundtrip(&PacketBitSet::from_words(words));
+        }
+    }
+}
+
+mod angle {
+    use crate::*;
+
+    #[test]
+    fn test_angle() {
+        assert_eq!(Angle::from(90.0f32), Angle(64));
+        assert_eq!(Angle::from(-90.0f32), Angle(192));
+        assert_eq!(Angle::from(720.0f64), Angle(0));
+        assert_eq!(f32::from(Angle(128)), 180.0);
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        let x: FixedPoint<i32, 5> = 10.5f64.into();
+        assert_eq!(x, FixedPoint(336));
+        assert_eq!(f64::from(x), 10.5);
+        // rounded down, also when negative
+        assert_eq!(FixedPoint::<i32, 5>::from(-0.01f64), FixedPoint(-1));
+        assert_eq!(FixedPoint::<i8, 5>::from(1.0f32).size(), 1);
+        assert_eq!(f32::from(FixedPoint::<i16, 12>(4096)), 1.0);
+    }
+}
+
+mod collection {
+    use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+    use bytes::{BufMut, Bytes, BytesMut};
+
+    use crate::*;
+
+    #[test]
+    fn test_map_layout() {
+        let map = BTreeMap::from([(2u8, 300i16), (1u8, -1i16)]);
+        let mut buffer = BytesMut::new();
+        PacketWriteSeed::write(PacketCollection::default(), &map, &mut buffer).unwrap();
+        assert_eq!(&buffer[..], &[2, 1, 0xff, 0xff, 2, 0x01, 0x2c]);
+        assert_eq!(PacketSizeSeed::size(PacketCollection::default(), &map), buffer.len());
+        let read: BTreeMap<u8, i16> = PacketCollection::default().read(&mut buffer.freeze()).unwrap();
+        assert_eq!(read, map);
+    }
+
+    #[test]
+    fn test_collection_too_long() {
+        let mut buffer = BytesMut::new();
+        VarI32::from(i32::MAX).write(&mut buffer).unwrap();
+        buffer.put_u8(1);
+        let read: Result<HashMap<u8, u8>, _> = PacketCollection::default().read(&mut Bytes::from(buffer));
+        assert!(matches!(read, Err(ReadError::NotEnoughBytes { remaining: 1, .. })));
+        let read: Result<VecDeque<u8>, _> = PacketCollection::default().read(&mut Bytes::from_static(&[0xff, 0xff, 0xff, 0xff, 0x0f]));
+        assert!(read.is_err());
+        let read: HashSet<u8> = PacketCollection::default().read(&mut Bytes::from_static(&[0])).unwrap();
+        assert!(read.is_empty());
+    }
+}
+
+#[cfg(feature = "bitflags")]
+mod flags {
+    use bytes::Bytes;
+
+    use crate::testing::assert_roundtrip;
+    use crate::*;
+
+    bitflags::bitflags! {
+        #[derive(Debug, PartialEq, Eq)]
+        struct SkinParts: u8 {
+            const CAPE = 0x01;
+            const JACKET = 0x02;
+            const HAT = 0x40;
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite, PacketRead, Debug, PartialEq)]
+    struct Settings {
+        #[falcon(bitflags)]
+        parts: SkinParts,
+        #[falcon(var32)]
+        hand: i32,
+    }
+
+    #[test]
+    fn test_flags() {
+        let settings = Settings {
+            parts: SkinParts::CAPE | SkinParts::HAT,
+            hand: 1,
+        };
+        assert_roundtrip(&settings);
+        assert_eq!(settings.size(), 2);
+        // unknown bits are kept
+        let settings = Settings::read(&mut Bytes::from_static(&[0x83, 0])).unwrap();
+        assert_eq!(settings.parts.bits(), 0x83);
+        assert!(settings.parts.contains(SkinParts::CAPE | SkinParts::JACKET));
+    }
+}
+
+mod bitset {
+    use bytes::{BufMut, Bytes, BytesMut};
+
+    use crate::*;
+
+    #[test]
+    fn test_bitset_bits() {
+        let mut set = PacketBitSet::new();
+        set.set(3, true);
+        set.set(70, true);
+        assert!(set.get(3) && set.get(70) && !set.get(4));
+        assert_eq!(set.words(), &[1 << 3, 1 << 6]);
+        set.set(70, false);
+        assert_eq!(set.words(), &[1 << 3]);
+
+        let mut buffer = BytesMut::new();
+        set.write(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), set.size());
+        assert_eq!(&buffer[..], &[1, 0, 0, 0, 0, 0, 0, 0, 8]);
+    }
+
+    #[test]
+    fn test_bitset_truncated() {
+        let mut buffer = BytesMut::new();
+        buffer.put_u8(4);
+        buffer.put_u64(1);
+        assert!(matches!(PacketBitSet::read(&mut Bytes::from(buffer)), Err(ReadError::NotEnoughBytes { expected: 32, remaining: 8 })));
+    }
+
+    #[test]
+    fn test_bitset_iter() {
+        let mut set: PacketBitSet = [0, 63, 64, 130].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 63, 64, 130]);
+        assert_eq!(set.count(), 4);
+        set.clear(130);
+        assert_eq!(set.words().len(), 2);
+    }
+
+    #[test]
+    fn test_fixed_bitset() {
+        let mut set = PacketFixedBitSet::<3>::new();
+        assert_eq!(PacketFixedBitSet::<3>::BITS, 24);
+        set.set(0, true);
+        set.set(9, true);
+        set.set(19, true);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 9, 19]);
+        set.clear(0);
+        assert_eq!(set.count(), 2);
+
+        let mut buffer = BytesMut::new();
+        set.write(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), set.size());
+        assert_eq!(&buffer[..], &[0, 2, 8]);
+        assert_eq!(PacketFixedBitSet::<3>::read(&mut Bytes::from(buffer)).unwrap(), set);
+        assert!(matches!(
+            PacketFixedBitSet::<3>::read(&mut Bytes::from_static(&[1])),
+            Err(ReadError::NotEnoughBytes { expected: 3, remaining: 1 })
+        ));
+    }
+}
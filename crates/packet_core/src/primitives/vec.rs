@@ -1,5 +1,5 @@
-use std::iter::{self, FromIterator};
-use std::marker::PhantomData;
+use core::iter::{self, FromIterator};
+use core::marker::PhantomData;
 
 use super::iter::PacketIter;
 use crate::{PacketRead, PacketReadSeed, PacketSizeSeed, PacketWrite, PacketWriteSeed};
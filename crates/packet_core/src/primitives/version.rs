@@ -0,0 +1,70 @@
+use core::marker::PhantomData;
+
+use bytes::{Buf, BufMut};
+
+use crate::error::{ReadError, WriteError};
+use crate::{PacketRead, PacketReadSeed, PacketReadVersioned, PacketSize, PacketSizeSeed, PacketSizeVersioned, PacketWrite, PacketWriteSeed, PacketWriteVersioned};
+
+/// Seed that reads and writes `T` according to a specific protocol version.
+pub struct PacketVersioned<T> {
+    protocol: i32,
+    _marker: PhantomData<T>,
+}
+
+impl<T> PacketVersioned<T> {
+    pub fn new(protocol: i32) -> Self {
+        Self {
+            protocol,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: PacketReadVersioned> PacketReadSeed for PacketVersioned<T> {
+    type Value = T;
+
+    fn read<B>(self, buffer: &mut B) -> Result<Self::Value, ReadError>
+    where
+        B: Buf + ?Sized,
+    {
+        T::read_versioned(buffer, self.protocol)
+    }
+}
+
+impl<'a, T: PacketWriteVersioned> PacketWriteSeed<'a> for PacketVersioned<T> {
+    fn write<B>(self, value: &'a Self::Value, buffer: &'a mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        value.write_versioned(buffer, self.protocol)
+    }
+}
+
+impl<'a, T: PacketSizeVersioned> PacketSizeSeed<'a> for PacketVersioned<T> {
+    type Value = T;
+
+    fn size(self, value: &'a Self::Value) -> usize { value.size_versioned(self.protocol) }
+}
+
+impl<T: PacketRead> PacketReadVersioned for T {
+    fn read_versioned<B>(buffer: &mut B, _protocol: i32) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized,
+    {
+        T::read(buffer)
+    }
+}
+
+impl<T: PacketWrite> PacketWriteVersioned for T {
+    fn write_versioned<B>(&self, buffer: &mut B, _protocol: i32) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        self.write(buffer)
+    }
+}
+
+impl<T: PacketSize> PacketSizeVersioned for T {
+    fn size_versioned(&self, _protocol: i32) -> usize { self.size() }
+}
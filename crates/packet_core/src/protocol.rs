@@ -0,0 +1,33 @@
+//! Types describing where a packet belongs in the protocol, used by the
+//! [`packet!`](crate::packet) macro.
+
+/// The state of a connection, which determines the meaning of packet ids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProtocolState {
+    Handshake,
+    Status,
+    Login,
+    Play,
+}
+
+/// The direction in which a packet is sent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PacketDirection {
+    /// Sent from the client to the server.
+    Serverbound,
+    /// Sent from the server to the client.
+    Clientbound,
+}
+
+/// A packet type with known packet ids, implemented by the
+/// [`packet!`](crate::packet) macro.
+pub trait PacketId {
+    /// The connection state this packet is sent in.
+    const STATE: ProtocolState;
+    /// The direction this packet is sent in.
+    const DIRECTION: PacketDirection;
+
+    /// Returns the id of this packet in the given protocol version, `None`
+    /// if the packet doesn't exist in that version.
+    fn packet_id(protocol: i32) -> Option<i32>;
+}
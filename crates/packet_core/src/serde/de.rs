@@ -0,0 +1,193 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use bytes::Buf;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+
+use crate::{PacketRead, PacketReadSeed, ReadError, VarI32};
+
+/// Reads serde types as described in [the module docs](super).
+#[derive(Debug)]
+pub struct Deserializer<'a, B: ?Sized> {
+    buffer: &'a mut B,
+}
+
+impl<'a, B: Buf + ?Sized> Deserializer<'a, B> {
+    pub fn new(buffer: &'a mut B) -> Self { Deserializer { buffer } }
+
+    fn read_len(&mut self) -> Result<usize, ReadError> {
+        let len = VarI32::read(self.buffer)?.val();
+        usize::try_from(len).map_err(|_| ReadError::Serde(format!("negative length {}", len)))
+    }
+
+    fn read_bytes(&mut self) -> Result<Vec<u8>, ReadError> {
+        let len = self.read_len()?;
+        crate::Bytes::new(len).read(self.buffer)
+    }
+
+    fn read_string(&mut self) -> Result<String, ReadError> { Ok(String::from_utf8(self.read_bytes()?)?) }
+}
+
+impl de::Error for ReadError {
+    fn custom<T: core::fmt::Display>(msg: T) -> Self { ReadError::Serde(msg.to_string()) }
+}
+
+macro_rules! deserialize_num {
+    ($($fn:ident: $ty:ty => $visit:ident),*$(,)?) => {$(
+        fn $fn<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReadError> { visitor.$visit(<$ty>::read(self.buffer)?) }
+    )*}
+}
+
+macro_rules! unsupported {
+    ($($fn:ident),*$(,)?) => {$(
+        fn $fn<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ReadError> {
+            Err(ReadError::Serde(String::from("the format is not self-describing")))
+        }
+    )*}
+}
+
+impl<'de, 'a, 'b, B: Buf + ?Sized> de::Deserializer<'de> for &'b mut Deserializer<'a, B> {
+    type Error = ReadError;
+
+    deserialize_num! {
+        deserialize_bool: bool => visit_bool,
+        deserialize_i8: i8 => visit_i8,
+        deserialize_i16: i16 => visit_i16,
+        deserialize_i32: i32 => visit_i32,
+        deserialize_i64: i64 => visit_i64,
+        deserialize_i128: i128 => visit_i128,
+        deserialize_u8: u8 => visit_u8,
+        deserialize_u16: u16 => visit_u16,
+        deserialize_u32: u32 => visit_u32,
+        deserialize_u64: u64 => visit_u64,
+        deserialize_u128: u128 => visit_u128,
+        deserialize_f32: f32 => visit_f32,
+        deserialize_f64: f64 => visit_f64,
+    }
+
+    unsupported! {
+        deserialize_any,
+        deserialize_identifier,
+        deserialize_ignored_any,
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReadError> {
+        let string = self.read_string()?;
+        let mut chars = string.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(ReadError::Serde(format!("expected a single character, received {:?}", string))),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReadError> { self.deserialize_string(visitor) }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReadError> { visitor.visit_string(self.read_string()?) }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReadError> { self.deserialize_byte_buf(visitor) }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReadError> { visitor.visit_byte_buf(self.read_bytes()?) }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReadError> {
+        if bool::read(self.buffer)? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReadError> { visitor.visit_unit() }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, ReadError> { visitor.visit_unit() }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, ReadError> { visitor.visit_newtype_struct(self) }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReadError> {
+        let len = self.read_len()?;
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, ReadError> { visitor.visit_seq(Access { de: self, len }) }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value, ReadError> {
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ReadError> {
+        let len = self.read_len()?;
+        visitor.visit_map(Access { de: self, len })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, ReadError> {
+        visitor.visit_seq(Access { de: self, len: fields.len() })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, ReadError> {
+        visitor.visit_enum(self)
+    }
+
+    fn is_human_readable(&self) -> bool { false }
+}
+
+/// Reads the elements of a sequence or the entries of a map, `len` counts
+/// the ones that are left.
+struct Access<'a, 'b, B: ?Sized> {
+    de: &'b mut Deserializer<'a, B>,
+    len: usize,
+}
+
+impl<'de, 'a, 'b, B: Buf + ?Sized> de::SeqAccess<'de> for Access<'a, 'b, B> {
+    type Error = ReadError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, ReadError> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> { Some(self.len) }
+}
+
+impl<'de, 'a, 'b, B: Buf + ?Sized> de::MapAccess<'de> for Access<'a, 'b, B> {
+    type Error = ReadError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, ReadError> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, ReadError> { seed.deserialize(&mut *self.de) }
+
+    fn size_hint(&self) -> Option<usize> { Some(self.len) }
+}
+
+impl<'de, 'a, 'b, B: Buf + ?Sized> de::EnumAccess<'de> for &'b mut Deserializer<'a, B> {
+    type Error = ReadError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), ReadError> {
+        let index = VarI32::read(self.buffer)?.as_u32();
+        let value = seed.deserialize(IntoDeserializer::<ReadError>::into_deserializer(index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, 'a, 'b, B: Buf + ?Sized> de::VariantAccess<'de> for &'b mut Deserializer<'a, B> {
+    type Error = ReadError;
+
+    fn unit_variant(self) -> Result<(), ReadError> { Ok(()) }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, ReadError> { seed.deserialize(self) }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, ReadError> { de::Deserializer::deserialize_tuple(self, len, visitor) }
+
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, ReadError> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
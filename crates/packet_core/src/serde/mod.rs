@@ -0,0 +1,95 @@
+//! A fallback that reads and writes any type implementing serde's
+//! `Serialize` and `Deserialize` according to the conventions of the
+//! minecraft protocol.
+//!
+//! This is meant for prototypes, packets that are used for real should
+//! derive [`PacketRead`], [`PacketWrite`] and [`PacketSize`] instead. The
+//! data model of serde is mapped as follows:
+//! - **Numbers and bool**: like their [`PacketWrite`] implementation, so
+//!   big-endian with a fixed size, VarInt fields need the derives
+//! - **Strings, chars and bytes**: prefixed by their length as a VarInt,
+//!   strings are not limited in length
+//! - **Options**: prefixed by a bool telling whether the value is present
+//! - **Sequences and maps**: prefixed by their length as a VarInt
+//! - **Structs and tuples**: their fields in order, without any prefix
+//! - **Enums**: the index of the variant as a VarInt, followed by its
+//!   fields
+//! - **Unit types**: nothing
+//!
+//! The format is not self-describing, `deserialize_any` is not supported.
+
+use ::serde::de::DeserializeOwned;
+use ::serde::Serialize;
+use bytes::{Buf, BufMut, BytesMut};
+
+pub use self::de::Deserializer;
+pub use self::ser::{Output, Serializer, SizeCounter};
+use crate::{PacketRead, PacketSize, PacketWrite, ReadError, WriteError};
+
+mod de;
+mod ser;
+#[cfg(all(test, feature = "std"))]
+mod tests;
+
+/// Writes `value` to `buffer`.
+pub fn to_buffer<T, B>(value: &T, buffer: &mut B) -> Result<(), WriteError>
+where
+    T: Serialize + ?Sized,
+    B: BufMut + ?Sized,
+{
+    value.serialize(&mut Serializer::new(buffer))
+}
+
+/// Writes `value` to a new buffer of exactly the right size.
+pub fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Result<BytesMut, WriteError> {
+    let mut buffer = BytesMut::with_capacity(serialized_size(value)?);
+    to_buffer(value, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// The number of bytes [`to_buffer`] would write.
+pub fn serialized_size<T: Serialize + ?Sized>(value: &T) -> Result<usize, WriteError> {
+    let mut serializer = Serializer::new(SizeCounter::default());
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_output().size())
+}
+
+/// Reads a `T` from `buffer`, bytes that remain afterwards are left in the
+/// buffer.
+pub fn from_buffer<T, B>(buffer: &mut B) -> Result<T, ReadError>
+where
+    T: DeserializeOwned,
+    B: Buf + ?Sized,
+{
+    T::deserialize(&mut Deserializer::new(buffer))
+}
+
+/// Lets a serde type be used wherever a packet type is expected.
+///
+/// A value that fails to serialize has a size of 0, writing it returns the
+/// error.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SerdePacket<T>(pub T);
+
+impl<T: DeserializeOwned> PacketRead for SerdePacket<T> {
+    fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized,
+    {
+        from_buffer(buffer).map(SerdePacket)
+    }
+}
+
+impl<T: Serialize> PacketWrite for SerdePacket<T> {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        to_buffer(&self.0, buffer)
+    }
+}
+
+impl<T: Serialize> PacketSize for SerdePacket<T> {
+    fn size(&self) -> usize { serialized_size(&self.0).unwrap_or(0) }
+}
@@ -0,0 +1,194 @@
+use alloc::string::{String, ToString};
+
+use bytes::BufMut;
+use serde::ser::{self, Serialize};
+
+use crate::{PacketWrite, VarI32, WriteError};
+
+/// Where a [`Serializer`] puts the values it writes.
+pub trait Output {
+    fn put<T: PacketWrite + ?Sized>(&mut self, value: &T) -> Result<(), WriteError>;
+}
+
+impl<B: BufMut + ?Sized> Output for &mut B {
+    fn put<T: PacketWrite + ?Sized>(&mut self, value: &T) -> Result<(), WriteError> { value.write(*self) }
+}
+
+/// An [`Output`] that only counts the bytes that would be written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SizeCounter {
+    size: usize,
+}
+
+impl SizeCounter {
+    pub fn size(&self) -> usize { self.size }
+}
+
+impl Output for SizeCounter {
+    fn put<T: PacketWrite + ?Sized>(&mut self, value: &T) -> Result<(), WriteError> {
+        self.size += value.size();
+        Ok(())
+    }
+}
+
+/// Writes serde types as described in [the module docs](super).
+#[derive(Debug)]
+pub struct Serializer<O> {
+    output: O,
+}
+
+impl<O: Output> Serializer<O> {
+    pub fn new(output: O) -> Self { Serializer { output } }
+
+    pub fn into_output(self) -> O { self.output }
+
+    fn put_len(&mut self, len: usize) -> Result<(), WriteError> { self.output.put(&VarI32::from(len)) }
+}
+
+impl ser::Error for WriteError {
+    fn custom<T: core::fmt::Display>(msg: T) -> Self { WriteError::Serde(msg.to_string()) }
+}
+
+macro_rules! serialize_num {
+    ($($fn:ident: $ty:ty),*$(,)?) => {$(
+        fn $fn(self, v: $ty) -> Result<(), WriteError> { self.output.put(&v) }
+    )*}
+}
+
+impl<O: Output> ser::Serializer for &mut Serializer<O> {
+    type Error = WriteError;
+    type Ok = ();
+    type SerializeMap = Self;
+    type SerializeSeq = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+
+    serialize_num! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), WriteError> { self.serialize_str(v.encode_utf8(&mut [0; 4])) }
+
+    fn serialize_str(self, v: &str) -> Result<(), WriteError> { self.serialize_bytes(v.as_bytes()) }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), WriteError> {
+        self.put_len(v.len())?;
+        self.output.put(v)
+    }
+
+    fn serialize_none(self) -> Result<(), WriteError> { self.output.put(&false) }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), WriteError> {
+        self.output.put(&true)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), WriteError> { Ok(()) }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), WriteError> { Ok(()) }
+
+    fn serialize_unit_variant(self, _name: &'static str, variant_index: u32, _variant: &'static str) -> Result<(), WriteError> {
+        self.output.put(&VarI32::from(variant_index))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<(), WriteError> { value.serialize(self) }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, variant_index: u32, _variant: &'static str, value: &T) -> Result<(), WriteError> {
+        self.output.put(&VarI32::from(variant_index))?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, WriteError> {
+        let len = len.ok_or_else(|| WriteError::Serde(String::from("sequences need a known length")))?;
+        self.put_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, WriteError> { Ok(self) }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, WriteError> { Ok(self) }
+
+    fn serialize_tuple_variant(self, _name: &'static str, variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self, WriteError> {
+        self.output.put(&VarI32::from(variant_index))?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, WriteError> {
+        let len = len.ok_or_else(|| WriteError::Serde(String::from("maps need a known length")))?;
+        self.put_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, WriteError> { Ok(self) }
+
+    fn serialize_struct_variant(self, _name: &'static str, variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self, WriteError> {
+        self.output.put(&VarI32::from(variant_index))?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool { false }
+}
+
+macro_rules! serialize_compound {
+    ($($trait:ident::$fn:ident),*$(,)?) => {$(
+        impl<O: Output> ser::$trait for &mut Serializer<O> {
+            type Error = WriteError;
+            type Ok = ();
+
+            fn $fn<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), WriteError> { value.serialize(&mut **self) }
+
+            fn end(self) -> Result<(), WriteError> { Ok(()) }
+        }
+    )*}
+}
+
+serialize_compound! {
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+}
+
+impl<O: Output> ser::SerializeMap for &mut Serializer<O> {
+    type Error = WriteError;
+    type Ok = ();
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), WriteError> { key.serialize(&mut **self) }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), WriteError> { value.serialize(&mut **self) }
+
+    fn end(self) -> Result<(), WriteError> { Ok(()) }
+}
+
+impl<O: Output> ser::SerializeStruct for &mut Serializer<O> {
+    type Error = WriteError;
+    type Ok = ();
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _key: &'static str, value: &T) -> Result<(), WriteError> { value.serialize(&mut **self) }
+
+    fn end(self) -> Result<(), WriteError> { Ok(()) }
+}
+
+impl<O: Output> ser::SerializeStructVariant for &mut Serializer<O> {
+    type Error = WriteError;
+    type Ok = ();
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _key: &'static str, value: &T) -> Result<(), WriteError> { value.serialize(&mut **self) }
+
+    fn end(self) -> Result<(), WriteError> { Ok(()) }
+}
@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Prototype {
+    name: String,
+    id: i32,
+    flag: Option<u8>,
+    entries: Vec<(bool, i16)>,
+    scores: BTreeMap<String, i64>,
+    kinds: [Kind; 3],
+    initial: char,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Kind {
+    Empty,
+    Pair(u8, u8),
+    Named { value: f32 },
+}
+
+#[test]
+fn test_layout() {
+    let value = (String::from("ab"), Some(7u8), None::<u8>, vec![1u16], Kind::Pair(3, 4));
+    let buffer = to_bytes(&value).unwrap();
+    assert_eq!(&buffer[..], &[2, b'a', b'b', 1, 7, 0, 1, 0, 1, 1, 3, 4]);
+    assert_eq!(serialized_size(&value).unwrap(), buffer.len());
+}
+
+#[test]
+fn test_roundtrip() {
+    let value = SerdePacket(Prototype {
+        name: String::from("falcon"),
+        id: -5,
+        flag: None,
+        entries: vec![(true, 300), (false, -1)],
+        scores: BTreeMap::from([(String::from("a"), 1), (String::from("b"), i64::MAX)]),
+        kinds: [Kind::Empty, Kind::Pair(1, 2), Kind::Named { value: 0.5 }],
+        initial: 'é',
+    });
+    crate::testing::assert_roundtrip(&value);
+}
+
+#[test]
+fn test_invalid_input() {
+    assert!(matches!(from_buffer::<Vec<u8>, _>(&mut Bytes::from_static(&[0xff, 0xff, 0xff, 0xff, 0x0f])), Err(ReadError::Serde(_))));
+    assert!(matches!(from_buffer::<String, _>(&mut Bytes::from_static(&[0x05, b'a'])), Err(ReadError::NotEnoughBytes { .. })));
+    assert!(matches!(from_buffer::<Kind, _>(&mut Bytes::from_static(&[0x09])), Err(ReadError::Serde(_))));
+}
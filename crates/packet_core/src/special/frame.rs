@@ -0,0 +1,51 @@
+use bytes::{Buf, Bytes};
+
+use crate::{PacketRead, ReadError, VarI32};
+
+/// The largest frame length the minecraft protocol allows, this is the
+/// largest number that fits in a 3-byte VarInt.
+pub const MAX_FRAME_LENGTH: usize = 2097151;
+
+/// Splits the uncompressed frame at the start of `buffer` into its packet
+/// id and body, the bytes after the frame are left in `buffer`.
+///
+/// Every input either gives a frame or an error, this never panics. It is
+/// the first step of the `decode_frame` function of the enums declared with
+/// [`packet!`](crate::packet), the entry point for fuzz targets.
+pub fn split_frame<B>(buffer: &mut B) -> Result<(i32, Bytes), ReadError>
+where
+    B: Buf + ?Sized,
+{
+    let length = VarI32::read(buffer)?.as_usize();
+    if length > MAX_FRAME_LENGTH {
+        return Err(ReadError::FrameTooLong(MAX_FRAME_LENGTH, length));
+    }
+    if buffer.remaining() < length {
+        return Err(ReadError::NotEnoughBytes {
+            expected: length,
+            remaining: buffer.remaining(),
+        });
+    }
+    let mut frame = buffer.copy_to_bytes(length);
+    let packet_id = VarI32::read(&mut frame)?.val();
+    Ok((packet_id, frame))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_frame() {
+        let mut buffer = &[0x03, 0x82, 0x01, 0x05, 0x01, 0x7f][..];
+        let (packet_id, body) = split_frame(&mut buffer).unwrap();
+        assert_eq!(packet_id, 0x82);
+        assert_eq!(&body[..], &[0x05]);
+        assert_eq!(split_frame(&mut buffer).unwrap(), (0x7f, Bytes::new()));
+
+        // negative and truncated lengths, an empty frame has no id
+        assert!(matches!(split_frame(&mut &[0xff, 0xff, 0xff, 0xff, 0x0f][..]), Err(ReadError::FrameTooLong(..))));
+        assert!(matches!(split_frame(&mut &[0x05, 0x00][..]), Err(ReadError::NotEnoughBytes { expected: 5, remaining: 1 })));
+        assert!(split_frame(&mut &[0x00][..]).is_err());
+    }
+}
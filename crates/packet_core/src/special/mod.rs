@@ -1,11 +1,25 @@
+#[cfg(feature = "std")]
 mod counter;
+mod frame;
 mod packet;
+mod raw;
+#[cfg(feature = "std")]
 mod reader;
+#[cfg(any(feature = "tokio", all(test, feature = "std")))]
+mod stream;
 mod uuid;
+#[cfg(feature = "std")]
 mod writer;
 
+#[cfg(feature = "std")]
 pub use self::counter::Counter;
-pub use self::packet::PacketPrepare;
+pub use self::frame::{split_frame, MAX_FRAME_LENGTH};
+pub use self::packet::{write_packet, PacketPrepare};
+pub use self::raw::RawPacket;
+#[cfg(feature = "std")]
 pub use self::reader::Reader;
+#[cfg(any(feature = "tokio", all(test, feature = "std")))]
+pub use self::stream::{read_frame_async, read_frame_length_async, read_packet_async};
 pub use self::uuid::StrUuid;
+#[cfg(feature = "std")]
 pub use self::writer::Writer;
@@ -1,5 +1,7 @@
 use bytes::{BufMut, BytesMut};
 
+use crate::{PacketSize, PacketWrite, VarI32, WriteError};
+
 pub trait PacketPrepare: BufMut {
     fn prepare(&mut self, additional: usize);
 }
@@ -7,3 +9,79 @@ pub trait PacketPrepare: BufMut {
 impl PacketPrepare for BytesMut {
     fn prepare(&mut self, additional: usize) { self.reserve(additional); }
 }
+
+/// Writes the id and the body of a packet, the frame is prepared in one go
+/// from the exact size of both so the packet can be written in place.
+pub fn write_packet<B, P>(buffer: &mut B, packet_id: i32, packet: &P) -> Result<(), WriteError>
+where
+    B: PacketPrepare + ?Sized,
+    P: PacketWrite + ?Sized,
+{
+    let packet_id = VarI32::from(packet_id);
+    buffer.prepare(packet_id.size() + packet.size());
+    packet_id.write(buffer)?;
+    #[cfg(feature = "check-size")]
+    {
+        let mut checked = SizeChecked { inner: buffer, written: 0 };
+        packet.write(&mut checked)?;
+        assert_eq!(
+            packet.size(),
+            checked.written,
+            "Size of {} (id {:#04X}) does not match the written length",
+            core::any::type_name::<P>(),
+            packet_id.val()
+        );
+        Ok(())
+    }
+    #[cfg(not(feature = "check-size"))]
+    packet.write(buffer)
+}
+
+/// Counts the bytes a packet writes, a frame prepared from a wrong size
+/// corrupts every packet behind it.
+#[cfg(feature = "check-size")]
+struct SizeChecked<'a, B: ?Sized> {
+    inner: &'a mut B,
+    written: usize,
+}
+
+#[cfg(feature = "check-size")]
+unsafe impl<B: BufMut + ?Sized> BufMut for SizeChecked<'_, B> {
+    fn remaining_mut(&self) -> usize { self.inner.remaining_mut() }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.written += cnt;
+        self.inner.advance_mut(cnt);
+    }
+
+    fn chunk_mut(&mut self) -> &mut bytes::buf::UninitSlice { self.inner.chunk_mut() }
+}
+
+#[cfg(all(test, feature = "check-size"))]
+mod test {
+    use bytes::BytesMut;
+
+    use super::*;
+
+    /// Claims one byte less than it writes.
+    struct Short;
+
+    impl PacketSize for Short {
+        fn size(&self) -> usize { 1 }
+    }
+
+    impl PacketWrite for Short {
+        fn write<B: BufMut + ?Sized>(&self, buffer: &mut B) -> Result<(), WriteError> { [1u8, 2].write(buffer) }
+    }
+
+    #[test]
+    fn test_size_checked() {
+        let mut buffer = BytesMut::new();
+        write_packet(&mut buffer, 0x05, &7u32).unwrap();
+        assert_eq!(&buffer[..], &[0x05, 0, 0, 0, 7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the written length")]
+    fn test_size_mismatch() { write_packet(&mut BytesMut::new(), 0x05, &Short).unwrap(); }
+}
@@ -0,0 +1,71 @@
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::{PacketRead, PacketSize, PacketWrite, ReadError, VarI32, WriteError};
+
+/// A packet that is not decoded, only its id is read.
+///
+/// Reading consumes the rest of the frame as the body, writing puts the id
+/// and the body back verbatim. This is enough to pass packets on to another
+/// connection or to keep packets that have no known layout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawPacket {
+    pub id: i32,
+    pub body: Bytes,
+}
+
+impl RawPacket {
+    pub fn new<B: Into<Bytes>>(id: i32, body: B) -> Self { RawPacket { id, body: body.into() } }
+}
+
+impl PacketRead for RawPacket {
+    fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+    where
+        B: Buf + ?Sized,
+        Self: Sized,
+    {
+        let id = VarI32::read(buffer)?.val();
+        Ok(RawPacket {
+            id,
+            body: buffer.copy_to_bytes(buffer.remaining()),
+        })
+    }
+}
+
+impl PacketSize for RawPacket {
+    #[inline]
+    fn size(&self) -> usize { VarI32::from(self.id).size() + self.body.len() }
+}
+
+impl PacketWrite for RawPacket {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        VarI32::from(self.id).write(buffer)?;
+        self.body[..].write(buffer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use bytes::BytesMut;
+
+    use super::*;
+
+    #[test]
+    fn test_raw_packet() {
+        let mut frame = Bytes::from_static(&[0x80, 0x01, 0x05, 0x06]);
+        let packet = RawPacket::read(&mut frame).unwrap();
+        assert_eq!(packet, RawPacket::new(128, vec![0x05, 0x06]));
+        assert!(frame.is_empty());
+
+        let mut buffer = BytesMut::new();
+        packet.write(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), packet.size());
+        assert_eq!(&buffer[..], &[0x80, 0x01, 0x05, 0x06]);
+
+        assert!(RawPacket::read(&mut Bytes::new()).is_err());
+    }
+}
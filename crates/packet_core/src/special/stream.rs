@@ -0,0 +1,104 @@
+use bytes::{Bytes, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::MAX_FRAME_LENGTH;
+use crate::{PacketRead, ReadError};
+
+/// Reads the VarInt length prefix of the next frame from an async stream.
+///
+/// Returns `Ok(None)` if the stream ended cleanly before the first byte
+/// of the prefix.
+pub async fn read_frame_length_async<R>(reader: &mut R) -> Result<Option<usize>, ReadError>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    let mut result = 0usize;
+    for i in 0..3 {
+        let byte = match reader.read_u8().await {
+            Ok(byte) => byte,
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof && i == 0 => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        result |= ((byte & 0x7f) as usize) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Ok(Some(result));
+        }
+    }
+    Err(ReadError::VarTooLong)
+}
+
+/// Reads the next length-prefixed frame from an async stream.
+///
+/// Partial reads are handled internally, the returned buffer always
+/// contains the complete frame without its length prefix. `max_length`
+/// is capped to [`MAX_FRAME_LENGTH`].
+///
+/// Returns `Ok(None)` if the stream ended cleanly in between frames.
+pub async fn read_frame_async<R>(reader: &mut R, max_length: usize) -> Result<Option<Bytes>, ReadError>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    let length = match read_frame_length_async(reader).await? {
+        Some(length) => length,
+        None => return Ok(None),
+    };
+    let max_length = max_length.min(MAX_FRAME_LENGTH);
+    if length > max_length {
+        return Err(ReadError::FrameTooLong(max_length, length));
+    }
+    let mut frame = BytesMut::zeroed(length);
+    reader.read_exact(&mut frame).await?;
+    Ok(Some(frame.freeze()))
+}
+
+/// Reads the next uncompressed frame from an async stream and decodes it
+/// as `P`.
+///
+/// This does not deal with compression or encryption, the stream should
+/// already take care of the latter. Bytes left in the frame after reading
+/// `P` are discarded.
+pub async fn read_packet_async<P, R>(reader: &mut R) -> Result<Option<P>, ReadError>
+where
+    P: PacketRead,
+    R: AsyncRead + Unpin + ?Sized,
+{
+    match read_frame_async(reader, MAX_FRAME_LENGTH).await? {
+        Some(mut frame) => P::read(&mut frame).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::io::AsyncWriteExt;
+
+    use super::*;
+    use crate::VarI32;
+
+    #[tokio::test]
+    async fn test_read_split_frames() {
+        let (mut client, mut server) = tokio::io::duplex(2);
+        tokio::spawn(async move {
+            client.write_all(&[0x03, 0x82, 0x01, 0x05]).await.unwrap();
+            client.write_all(&[0x01, 0x7f]).await.unwrap();
+        });
+
+        let first = read_frame_async(&mut server, MAX_FRAME_LENGTH).await.unwrap().unwrap();
+        assert_eq!(first.as_ref(), &[0x82, 0x01, 0x05]);
+        let second: VarI32 = read_packet_async(&mut server).await.unwrap().unwrap();
+        assert_eq!(second.val(), 127);
+        assert!(read_frame_async(&mut server, MAX_FRAME_LENGTH).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_errors() {
+        let mut too_long: &[u8] = &[0x05, 0x00, 0x00, 0x00, 0x00, 0x00];
+        assert!(matches!(read_frame_async(&mut too_long, 4).await, Err(ReadError::FrameTooLong(4, 5))));
+
+        let mut bad_prefix: &[u8] = &[0xff, 0xff, 0xff, 0x01];
+        assert!(matches!(read_frame_async(&mut bad_prefix, MAX_FRAME_LENGTH).await, Err(ReadError::VarTooLong)));
+
+        let mut truncated: &[u8] = &[0x03, 0x00];
+        assert!(matches!(read_frame_async(&mut truncated, MAX_FRAME_LENGTH).await, Err(ReadError::IoError(_))));
+    }
+}
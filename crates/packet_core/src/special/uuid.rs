@@ -1,5 +1,6 @@
-use std::ops::Deref;
-use std::str::FromStr;
+use alloc::string::String;
+use core::ops::Deref;
+use core::str::FromStr;
 
 use bytes::{Buf, BufMut};
 use uuid::Uuid;
@@ -7,6 +7,8 @@ use crate::ReadError;
 pub struct TestPacket {
     #[falcon(array)]
     array: [u8; 2],
+    #[falcon(collection)]
+    counts: std::collections::BTreeMap<u8, i32>,
     // #[falcon(var32)]
     id: i32,
     #[falcon(var32)]
@@ -54,3 +56,425 @@ impl From<String> for TestStrWrapper {
 impl AsRef<str> for TestStrWrapper {
     fn as_ref(&self) -> &str { &self.content }
 }
+
+#[cfg(test)]
+mod generic {
+    use crate::testing::assert_roundtrip;
+    use crate::{PacketRead, PacketSize, PacketWrite};
+
+    #[derive(PacketSize, PacketWrite, PacketRead, Debug, PartialEq)]
+    struct WithLength<T> {
+        #[falcon(var32)]
+        length: i32,
+        first: T,
+        second: T,
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    struct BorrowedPacket<'a, T>
+    where
+        T: AsRef<str>,
+    {
+        #[falcon(string = 16)]
+        name: T,
+        #[falcon(bytes)]
+        data: &'a [u8],
+    }
+
+    #[test]
+    fn test_generic_roundtrip() {
+        assert_roundtrip(&WithLength {
+            length: 3,
+            first: 7u16,
+            second: 1u16,
+        });
+        assert_roundtrip(&WithLength {
+            length: -1,
+            first: WithLength {
+                length: 0,
+                first: true,
+                second: false,
+            },
+            second: WithLength {
+                length: 1,
+                first: true,
+                second: true,
+            },
+        });
+    }
+
+    #[test]
+    fn test_borrowed_size() {
+        let packet = BorrowedPacket {
+            name: "falcon",
+            data: &[1, 2, 3],
+        };
+        let mut buffer = Vec::new();
+        packet.write(&mut buffer).unwrap();
+        assert_eq!(packet.size(), buffer.len());
+        assert_eq!(buffer.len(), 10);
+    }
+}
+
+#[cfg(test)]
+mod array {
+    use bytes::Bytes;
+
+    use crate::testing::assert_roundtrip;
+    use crate::{PacketRead, PacketSize, PacketWrite, ReadError, VarI32};
+
+    #[derive(PacketSize, PacketWrite, PacketRead, Debug, PartialEq)]
+    struct Fixed {
+        #[falcon(array)]
+        heightmap: [i64; 4],
+        #[falcon(array)]
+        biomes: [i32; 16],
+        #[falcon(array)]
+        ids: [VarI32; 2],
+        hash: [u8; 3],
+    }
+
+    #[test]
+    fn test_array_roundtrip() {
+        let packet = Fixed {
+            heightmap: [1, -2, i64::MAX, 0],
+            biomes: [7; 16],
+            ids: [VarI32::from(300), VarI32::from(-1)],
+            hash: [1, 2, 3],
+        };
+        assert_roundtrip(&packet);
+        assert_eq!(packet.size(), 32 + 64 + 2 + 5 + 3);
+    }
+
+    #[test]
+    fn test_array_truncated() {
+        let error = Fixed::read(&mut Bytes::from_static(&[0; 40])).unwrap_err();
+        assert!(matches!(error.root(), ReadError::NotEnoughBytes { expected: 4, remaining: 0 }));
+        assert_eq!(error.field_path(), ["Fixed.biomes"]);
+    }
+}
+
+#[cfg(test)]
+mod tuple {
+    use crate::testing::assert_roundtrip;
+    use crate::{PacketRead, PacketSize, PacketWrite};
+
+    #[derive(PacketSize, PacketWrite, PacketRead, Debug, PartialEq)]
+    struct EntityId(i32);
+
+    #[derive(PacketSize, PacketWrite, PacketRead, Debug, PartialEq)]
+    struct Named(#[falcon(var32)] i32, #[falcon(string = 16)] String, EntityId);
+
+    #[test]
+    fn test_newtype() {
+        assert_roundtrip(&EntityId(-12));
+        assert_eq!(EntityId(5).size(), 4);
+    }
+
+    #[test]
+    fn test_tuple_attributes() {
+        let packet = Named(300, String::from("falcon"), EntityId(1));
+        assert_roundtrip(&packet);
+        assert_eq!(packet.size(), 2 + 7 + 4);
+    }
+}
+
+#[cfg(test)]
+mod versioned {
+    use bytes::BytesMut;
+
+    use crate::testing::assert_roundtrip_seed;
+    use crate::{PacketRead, PacketReadSeed, PacketSize, PacketSizeSeed, PacketVersioned, PacketWrite, PacketWriteSeed};
+
+    #[derive(PacketSize, PacketWrite, PacketRead, Debug, Default, PartialEq)]
+    struct Inner {
+        #[falcon(var32)]
+        value: i32,
+    }
+
+    #[derive(PacketSize, PacketWrite, PacketRead, Debug, PartialEq)]
+    struct JoinGame {
+        entity_id: i32,
+        #[falcon(until = 340)]
+        difficulty: u8,
+        #[falcon(since = 477, var32)]
+        view_distance: i32,
+        #[falcon(since = 335, until = 498)]
+        inner: Inner,
+        hardcore: bool,
+    }
+
+    #[derive(PacketSize, PacketWrite, PacketRead, Debug, PartialEq)]
+    struct Wrapper {
+        #[falcon(until = 340)]
+        legacy: u8,
+        packet: JoinGame,
+    }
+
+    fn packet() -> JoinGame {
+        JoinGame {
+            entity_id: 5,
+            difficulty: 2,
+            view_distance: 10,
+            inner: Inner { value: 300 },
+            hardcore: true,
+        }
+    }
+
+    #[test]
+    fn test_versioned_sizes() {
+        assert_eq!(PacketVersioned::new(47).size(&packet()), 4 + 1 + 1);
+        assert_eq!(PacketVersioned::new(340).size(&packet()), 4 + 1 + 2 + 1);
+        assert_eq!(PacketVersioned::new(477).size(&packet()), 4 + 1 + 2 + 1);
+        assert_eq!(PacketVersioned::new(578).size(&packet()), 4 + 1 + 1);
+    }
+
+    #[test]
+    fn test_versioned_roundtrip() {
+        let legacy = JoinGame {
+            view_distance: 0,
+            ..packet()
+        };
+        assert_roundtrip_seed(&legacy, || PacketVersioned::new(340));
+        let modern = JoinGame {
+            difficulty: 0,
+            ..packet()
+        };
+        assert_roundtrip_seed(&modern, || PacketVersioned::new(477));
+        let wrapper = Wrapper {
+            legacy: 0,
+            packet: JoinGame {
+                difficulty: 0,
+                inner: Inner::default(),
+                ..packet()
+            },
+        };
+        assert_roundtrip_seed(&wrapper, || PacketVersioned::new(578));
+    }
+
+    #[test]
+    fn test_absent_fields_default() {
+        let mut buffer = BytesMut::new();
+        PacketWriteSeed::write(PacketVersioned::new(578), &packet(), &mut buffer).unwrap();
+        let read = PacketVersioned::<JoinGame>::new(578).read(&mut buffer.freeze()).unwrap();
+        assert_eq!(read.difficulty, 0);
+        assert_eq!(read.inner, Inner::default());
+        assert_eq!(read.view_distance, 10);
+    }
+}
+
+#[cfg(test)]
+mod rest {
+    use bytes::{Buf, Bytes};
+
+    use crate::testing::assert_roundtrip;
+    use crate::{PacketRead, PacketSize, PacketWrite};
+
+    #[derive(PacketSize, PacketWrite, PacketRead, Debug, PartialEq)]
+    struct PluginMessage {
+        #[falcon(string = 32)]
+        channel: String,
+        #[falcon(rest)]
+        data: Bytes,
+    }
+
+    #[test]
+    fn test_rest_roundtrip() {
+        assert_roundtrip(&PluginMessage {
+            channel: String::from("minecraft:brand"),
+            data: Bytes::from_static(b"\x06falcon"),
+        });
+        assert_roundtrip(&PluginMessage {
+            channel: String::new(),
+            data: Bytes::new(),
+        });
+    }
+
+    #[test]
+    fn test_rest_consumes_packet() {
+        let mut buffer = Bytes::from_static(b"\x02mc\x01\x02\x03");
+        let packet = PluginMessage::read(&mut buffer).unwrap();
+        assert_eq!(packet.channel, "mc");
+        assert_eq!(packet.data.as_ref(), &[1, 2, 3]);
+        assert!(!buffer.has_remaining());
+    }
+}
+
+#[cfg(test)]
+mod packet_macro {
+    use bytes::Bytes;
+
+    use crate::protocol::{PacketDirection, PacketId, ProtocolState};
+
+    crate::packet! {
+        enum TestPackets;
+
+        #[falcon_packet(state = login, direction = serverbound, versions = { -1 = 0x00 })]
+        #[derive(Debug, PartialEq)]
+        struct LoginStart {
+            #[falcon(string = 16)]
+            name: String,
+        }
+
+        #[falcon_packet(state = play, direction = serverbound, versions = {
+            47 = 0x00;
+            340, 404 = 0x0B;
+        })]
+        #[derive(Debug, PartialEq)]
+        struct KeepAlive {
+            id: i64,
+        }
+
+        #[falcon_packet(state = play, direction = clientbound, versions = { 47, 340 = 0x00 })]
+        #[derive(Debug, PartialEq)]
+        struct KeepAliveOut {
+            id: i64,
+        }
+
+        #[falcon_packet(state = play, direction = clientbound, versions = {
+            393..=404 = 0x22;
+            477.. = 0x23;
+        })]
+        #[derive(Debug, PartialEq)]
+        struct Ranged {
+            id: i32,
+        }
+    }
+
+    #[test]
+    fn test_packet_ids() {
+        assert_eq!(LoginStart::packet_id(1000), Some(0x00));
+        assert_eq!(KeepAlive::packet_id(47), Some(0x00));
+        assert_eq!(KeepAlive::packet_id(404), Some(0x0B));
+        assert_eq!(KeepAlive::packet_id(578), None);
+        assert_eq!(KeepAlive::STATE, ProtocolState::Play);
+        assert_eq!(KeepAliveOut::DIRECTION, PacketDirection::Clientbound);
+        assert_eq!(TestPackets::from(KeepAlive { id: 1 }).packet_id(340), Some(0x0B));
+    }
+
+    #[test]
+    fn test_version_ranges() {
+        assert_eq!(Ranged::packet_id(340), None);
+        assert_eq!(Ranged::packet_id(393), Some(0x22));
+        assert_eq!(Ranged::packet_id(404), Some(0x22));
+        assert_eq!(Ranged::packet_id(405), None);
+        assert_eq!(Ranged::packet_id(477), Some(0x23));
+        assert_eq!(Ranged::packet_id(763), Some(0x23));
+
+        let packet = TestPackets::read(ProtocolState::Play, PacketDirection::Clientbound, 401, 0x22, &mut Bytes::from_static(&[0, 0, 0, 3])).unwrap();
+        assert!(matches!(packet, Some(TestPackets::Ranged(Ranged { id: 3 }))));
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let read = |state, direction, protocol, id, data: &'static [u8]| TestPackets::read(state, direction, protocol, id, &mut Bytes::from_static(data)).unwrap();
+
+        let packet = read(ProtocolState::Play, PacketDirection::Serverbound, 340, 0x0B, &[0, 0, 0, 0, 0, 0, 0, 7]);
+        assert!(matches!(packet, Some(TestPackets::KeepAlive(KeepAlive { id: 7 }))));
+        let packet = read(ProtocolState::Play, PacketDirection::Clientbound, 47, 0x00, &[0, 0, 0, 0, 0, 0, 0, 7]);
+        assert!(matches!(packet, Some(TestPackets::KeepAliveOut(KeepAliveOut { id: 7 }))));
+        let packet = read(ProtocolState::Login, PacketDirection::Serverbound, 47, 0x00, b"\x02mc");
+        assert!(matches!(packet, Some(TestPackets::LoginStart(LoginStart { name })) if name == "mc"));
+
+        assert!(read(ProtocolState::Play, PacketDirection::Serverbound, 47, 0x0B, &[]).is_none());
+        assert!(read(ProtocolState::Status, PacketDirection::Serverbound, 47, 0x00, &[]).is_none());
+    }
+
+    #[test]
+    fn test_decode_frame() {
+        let decode = |protocol, bytes| TestPackets::decode_frame(ProtocolState::Play, PacketDirection::Serverbound, protocol, bytes);
+        let packet = decode(340, &[0x09, 0x0B, 0, 0, 0, 0, 0, 0, 0, 7]).unwrap();
+        assert!(matches!(packet, Some(TestPackets::KeepAlive(KeepAlive { id: 7 }))));
+        assert!(decode(578, &[0x09, 0x0B, 0, 0, 0, 0, 0, 0, 0, 7]).unwrap().is_none());
+        assert!(decode(340, &[0x09, 0x0B, 0, 0]).is_err());
+        assert!(decode(340, &[0x03, 0x0B, 0, 0]).is_err());
+    }
+
+    mod headerless {
+        crate::packet! {
+            use crate::{PacketSize, PacketWrite};
+
+            #[derive(PacketSize, PacketWrite)]
+            #[falcon_packet(state = status, direction = clientbound, versions = { -1 = 0x01 })]
+            pub struct Pong {
+                payload: i64,
+            }
+
+            impl From<i64> for Pong {
+                fn from(payload: i64) -> Self { Pong { payload } }
+            }
+        }
+    }
+
+    #[test]
+    fn test_headerless() {
+        use crate::PacketWrite;
+
+        assert_eq!(headerless::Pong::packet_id(47), Some(0x01));
+        assert_eq!(headerless::Pong::STATE, ProtocolState::Status);
+        let mut buffer = bytes::BytesMut::new();
+        headerless::Pong::from(7).write(&mut buffer).unwrap();
+        assert_eq!(&buffer[..], &[0, 0, 0, 0, 0, 0, 0, 7]);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_decode_never_panics(protocol in 0..1000i32, bytes in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..64)) {
+            for state in [ProtocolState::Handshake, ProtocolState::Status, ProtocolState::Login, ProtocolState::Play] {
+                for direction in [PacketDirection::Serverbound, PacketDirection::Clientbound] {
+                    let _ = TestPackets::decode_frame(state, direction, protocol, &bytes);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod context {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::{PacketRead, PacketWrite};
+
+    #[derive(PacketRead)]
+    struct Inner {
+        _flag: u8,
+        value: u32,
+    }
+
+    #[derive(PacketRead)]
+    struct Outer {
+        _id: u16,
+        inner: Inner,
+    }
+
+    #[test]
+    fn test_error_context() {
+        let error = match Outer::read(&mut Bytes::from_static(&[0, 1, 2, 0, 0])) {
+            Ok(outer) => panic!("Read a truncated packet: {}", outer.inner.value),
+            Err(error) => error,
+        };
+        assert_eq!(error.field_path(), ["Outer.inner", "Inner.value"]);
+        assert_eq!(error.offset(), Some(3));
+        assert!(matches!(error.root(), ReadError::NotEnoughBytes { expected: 4, remaining: 2 }));
+        assert_eq!(error.to_string(), "Expected 4 more bytes but only 2 remain (at byte 3 in Outer.inner > Inner.value)");
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    struct Named {
+        #[falcon(string = 4)]
+        name: String,
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    struct Message(u8, Named);
+
+    #[test]
+    fn test_write_error_context() {
+        let message = Message(1, Named { name: String::from("too long") });
+        let error = message.write(&mut bytes::BytesMut::new()).unwrap_err();
+        assert_eq!(error.field_path(), ["Message.1", "Named.name"]);
+        assert!(matches!(error.root(), crate::WriteError::StringTooLong(4, 8)));
+        assert_eq!(error.to_string(), "String was longer than allowed: 8 > 4 (in Message.1 > Named.name)");
+    }
+}
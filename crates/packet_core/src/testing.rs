@@ -0,0 +1,115 @@
+//! Helpers to check that packet types are consistent.
+//!
+//! A correct implementation always satisfies two properties:
+//! - `read(write(x)) == x`
+//! - `size(x)` equals the number of bytes `write(x)` produced
+//!
+//! [`assert_roundtrip`] checks both for a single value. With the `proptest`
+//! feature enabled, [`proptest_roundtrip!`](crate::proptest_roundtrip) turns
+//! this into a property test over arbitrary values in one line.
+
+use core::fmt::Debug;
+
+use bytes::{Buf, BytesMut};
+
+use crate::{PacketRead, PacketReadSeed, PacketWrite, PacketWriteSeed};
+
+/// Asserts that `value` survives a write followed by a read and that
+/// its reported size matches the written length.
+///
+/// # Panics
+/// When writing or reading fails or any of the properties is violated.
+pub fn assert_roundtrip<T>(value: &T)
+where
+    T: PacketRead + PacketWrite + PartialEq + Debug,
+{
+    let size = value.size();
+    let mut buffer = BytesMut::with_capacity(size);
+    value.write(&mut buffer).unwrap_or_else(|e| panic!("Writing {:?} failed: {}", value, e));
+    assert_eq!(size, buffer.len(), "Size of {:?} does not match the written length", value);
+
+    let mut buffer = buffer.freeze();
+    let read = T::read(&mut buffer).unwrap_or_else(|e| panic!("Reading {:?} failed: {}", value, e));
+    assert_eq!(&read, value, "Value changed after a roundtrip");
+    assert!(!buffer.has_remaining(), "Reading {:?} left {} byte(s) unread", value, buffer.remaining());
+}
+
+/// Same as [`assert_roundtrip`] but for types that need a seed, `seed` is
+/// called once for each operation.
+///
+/// # Panics
+/// When writing or reading fails or any of the properties is violated.
+pub fn assert_roundtrip_seed<T, S, F>(value: &T, seed: F)
+where
+    T: PartialEq + Debug,
+    S: for<'a> PacketWriteSeed<'a, Value = T> + PacketReadSeed<Value = T>,
+    F: Fn() -> S,
+{
+    let size = seed().size(value);
+    let mut buffer = BytesMut::with_capacity(size);
+    PacketWriteSeed::write(seed(), value, &mut buffer).unwrap_or_else(|e| panic!("Writing {:?} failed: {}", value, e));
+    assert_eq!(size, buffer.len(), "Size of {:?} does not match the written length", value);
+
+    let mut buffer = buffer.freeze();
+    let read = PacketReadSeed::read(seed(), &mut buffer).unwrap_or_else(|e| panic!("Reading {:?} failed: {}", value, e));
+    assert_eq!(&read, value, "Value changed after a roundtrip");
+    assert!(!buffer.has_remaining(), "Reading {:?} left {} byte(s) unread", value, buffer.remaining());
+}
+
+#[cfg(feature = "proptest")]
+#[doc(hidden)]
+pub use proptest;
+
+#[cfg(feature = "proptest")]
+mod strategies {
+    use proptest::prelude::*;
+
+    use crate::{VarI32, VarI64};
+
+    impl Arbitrary for VarI32 {
+        type Parameters = ();
+        type Strategy = prop::strategy::Map<prop::num::i32::Any, fn(i32) -> VarI32>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy { any::<i32>().prop_map(VarI32::from) }
+    }
+
+    impl Arbitrary for VarI64 {
+        type Parameters = ();
+        type Strategy = prop::strategy::Map<prop::num::i64::Any, fn(i64) -> VarI64>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy { any::<i64>().prop_map(VarI64::from) }
+    }
+
+    /// Generates strings of at most `max_length` characters, to be used with
+    /// [`PacketString`](crate::PacketString).
+    pub fn packet_string(max_length: usize) -> impl Strategy<Value = String> {
+        prop::collection::vec(any::<char>(), 0..=max_length).prop_map(|chars| chars.into_iter().collect())
+    }
+}
+
+#[cfg(feature = "proptest")]
+pub use strategies::packet_string;
+
+/// Generates a property test per type that checks [`assert_roundtrip`] for
+/// arbitrary values, every type must implement
+/// [`Arbitrary`](proptest::arbitrary::Arbitrary).
+///
+/// ```ignore
+/// falcon_packet_core::proptest_roundtrip! {
+///     entity_id_roundtrip: EntityIdPacket,
+/// }
+/// ```
+#[cfg(feature = "proptest")]
+#[macro_export]
+macro_rules! proptest_roundtrip {
+    ($($name:ident: $ty:ty),* $(,)?) => {
+        $crate::testing::proptest::proptest! {
+            $(
+                #[test]
+                fn $name(value in $crate::testing::proptest::prelude::any::<$ty>()) {
+                    $crate::testing::assert_roundtrip(&value);
+                }
+            )*
+        }
+    };
+}
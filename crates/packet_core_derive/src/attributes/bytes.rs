@@ -19,6 +19,23 @@ impl BytesAttribute {
     pub fn span(&self) -> Span { self.ident.span }
 }
 
+#[derive(Derivative)]
+#[derivative(Debug, Hash, PartialEq, Eq)]
+pub struct RestAttribute {
+    pub ident: kw::rest,
+}
+
+impl RestAttribute {
+    pub fn span(&self) -> Span { self.ident.span }
+}
+
+impl Parse for RestAttribute {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ident = input.parse::<kw::rest>()?;
+        Ok(Self { ident })
+    }
+}
+
 impl Parse for BytesAttribute {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let ident = input.parse::<kw::bytes>()?;
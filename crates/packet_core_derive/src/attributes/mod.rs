@@ -2,13 +2,13 @@
 
 use proc_macro2::Span;
 
-use self::bytes::BytesAttribute;
+use self::bytes::{BytesAttribute, RestAttribute};
 use self::convert::{ConvertAttribute, FromAttribute, IntoAttribute};
 use self::link::LinkAttribute;
 use self::nbt::NBTAttribute;
 use self::string::{StringAttribute, ToStringAttribute};
 use self::varint::{VarI32Attribute, VarI64Attribute};
-use self::vec::{ArrayAttribute, VecAttribute};
+use self::vec::{ArrayAttribute, CollectionAttribute, FlagsAttribute, VecAttribute};
 use self::PacketAttribute::*;
 
 pub mod bytes;
@@ -18,6 +18,7 @@ pub mod nbt;
 pub mod string;
 pub mod varint;
 pub mod vec;
+pub mod version;
 
 #[macro_use]
 pub mod macros;
@@ -26,11 +27,14 @@ pub mod macros;
 pub enum PacketAttribute {
     Array(ArrayAttribute),
     Bytes(BytesAttribute),
+    Collection(CollectionAttribute),
     Convert(ConvertAttribute),
+    Flags(FlagsAttribute),
     From(FromAttribute),
     Into(IntoAttribute),
     Link(LinkAttribute),
     Nbt(NBTAttribute),
+    Rest(RestAttribute),
     String(StringAttribute),
     ToString(ToStringAttribute),
     VarI32(VarI32Attribute),
@@ -52,7 +56,10 @@ impl PacketAttribute {
             From(data) => data.span(),
             Convert(data) => data.span(),
             Array(data) => data.span(),
+            Collection(data) => data.span(),
+            Flags(data) => data.span(),
             Nbt(data) => data.span(),
+            Rest(data) => data.span(),
         }
     }
 }
@@ -60,11 +67,14 @@ impl PacketAttribute {
 impl_parse! {
     Array = (ArrayAttribute as crate::kw::array),
     Bytes = (BytesAttribute as crate::kw::bytes),
+    Collection = (CollectionAttribute as crate::kw::collection),
     Convert = (ConvertAttribute as crate::kw::convert),
+    Flags = (FlagsAttribute as crate::kw::bitflags),
     Into = (IntoAttribute as crate::kw::into),
     From = (FromAttribute as crate::kw::from),
     Link = (LinkAttribute as crate::kw::link),
     Nbt = (NBTAttribute as crate::kw::nbt),
+    Rest = (RestAttribute as crate::kw::rest),
     String = (StringAttribute as crate::kw::string),
     ToString = (ToStringAttribute as crate::kw::to_string),
     VarI32 = (VarI32Attribute as crate::kw::var32),
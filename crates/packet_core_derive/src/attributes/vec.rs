@@ -23,6 +23,42 @@ impl Parse for ArrayAttribute {
     }
 }
 
+#[derive(Derivative)]
+#[derivative(Debug, Hash, PartialEq, Eq)]
+pub struct CollectionAttribute {
+    pub ident: kw::collection,
+}
+
+impl CollectionAttribute {
+    pub fn span(&self) -> Span { self.ident.span }
+}
+
+impl Parse for CollectionAttribute {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            ident: input.parse::<kw::collection>()?,
+        })
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug, Hash, PartialEq, Eq)]
+pub struct FlagsAttribute {
+    pub ident: kw::bitflags,
+}
+
+impl FlagsAttribute {
+    pub fn span(&self) -> Span { self.ident.span }
+}
+
+impl Parse for FlagsAttribute {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            ident: input.parse::<kw::bitflags>()?,
+        })
+    }
+}
+
 #[derive(Derivative)]
 #[derivative(Debug, Hash, PartialEq, Eq)]
 pub struct VecAttribute {
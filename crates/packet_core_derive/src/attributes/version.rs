@@ -0,0 +1,103 @@
+use derivative::Derivative;
+use proc_macro2::Span;
+use syn::parse::Parse;
+use syn::{parse_quote_spanned, Expr, Ident, LitInt, Token};
+
+use super::PacketAttribute;
+use crate::kw;
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct SinceAttribute {
+    pub ident: kw::since,
+    pub eq: Token![=],
+    pub version: LitInt,
+}
+
+impl SinceAttribute {
+    pub fn span(&self) -> Span { self.ident.span }
+}
+
+impl Parse for SinceAttribute {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            ident: input.parse()?,
+            eq: input.parse()?,
+            version: input.parse()?,
+        })
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct UntilAttribute {
+    pub ident: kw::until,
+    pub eq: Token![=],
+    pub version: LitInt,
+}
+
+impl UntilAttribute {
+    pub fn span(&self) -> Span { self.ident.span }
+}
+
+impl Parse for UntilAttribute {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            ident: input.parse()?,
+            eq: input.parse()?,
+            version: input.parse()?,
+        })
+    }
+}
+
+/// Any attribute that can be put on a field, version attributes
+/// determine whether a field is present rather than how it is encoded.
+pub enum FieldAttribute {
+    Packet(PacketAttribute),
+    Since(SinceAttribute),
+    Until(UntilAttribute),
+}
+
+impl Parse for FieldAttribute {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(kw::since) {
+            Ok(Self::Since(input.parse()?))
+        } else if input.peek(kw::until) {
+            Ok(Self::Until(input.parse()?))
+        } else {
+            Ok(Self::Packet(input.parse()?))
+        }
+    }
+}
+
+/// The protocol versions in which a field is present, both bounds are
+/// inclusive.
+#[derive(Default)]
+pub struct VersionBounds {
+    pub since: Option<SinceAttribute>,
+    pub until: Option<UntilAttribute>,
+}
+
+impl VersionBounds {
+    pub fn is_empty(&self) -> bool { self.since.is_none() && self.until.is_none() }
+
+    /// The condition for the field to be present given the protocol
+    /// version in `protocol`, `None` if it is always present.
+    pub fn condition(&self, protocol: &Ident) -> Option<Expr> {
+        match (&self.since, &self.until) {
+            (Some(since), Some(until)) => {
+                let (since, until) = (&since.version, &until.version);
+                Some(parse_quote_spanned! {since.span()=> (#since..=#until).contains(&#protocol)})
+            },
+            (Some(since), None) => {
+                let since = &since.version;
+                Some(parse_quote_spanned! {since.span()=> #protocol >= #since})
+            },
+            (None, Some(until)) => {
+                let until = &until.version;
+                Some(parse_quote_spanned! {until.span()=> #protocol <= #until})
+            },
+            (None, None) => None,
+        }
+    }
+}
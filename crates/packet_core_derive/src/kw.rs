@@ -1,6 +1,8 @@
 use syn::custom_keyword;
 
 custom_keyword!(array);
+custom_keyword!(collection);
+custom_keyword!(bitflags);
 custom_keyword!(string);
 custom_keyword!(to_string);
 custom_keyword!(var32);
@@ -13,3 +15,7 @@ custom_keyword!(from);
 custom_keyword!(link);
 custom_keyword!(with);
 custom_keyword!(nbt);
+custom_keyword!(since);
+custom_keyword!(until);
+custom_keyword!(rest);
+custom_keyword!(versions);
@@ -2,10 +2,12 @@ use read::implement_read;
 use size::implement_size;
 use syn::{parse_macro_input, ItemStruct};
 
+use crate::packet::data::PacketListing;
 use crate::write::implement_write;
 
 pub(crate) mod attributes;
 pub(crate) mod kw;
+mod packet;
 mod read;
 mod size;
 pub(crate) mod util;
@@ -40,3 +42,15 @@ pub fn derive_packet_read(item: proc_macro::TokenStream) -> proc_macro::TokenStr
         Err(error) => error.to_compile_error().into(),
     }
 }
+
+/// Declares packets together with their ids, see the documentation of
+/// `falcon_packet_core` for an example.
+#[proc_macro]
+pub fn packet(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let listing = parse_macro_input!(item as PacketListing);
+
+    match packet::implement_packet(listing) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
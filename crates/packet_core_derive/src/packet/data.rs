@@ -0,0 +1,140 @@
+use falcon_proc_util::ErrorCatcher;
+use falcon_protocol_util::{PacketDirection, PacketPhase, PacketState, PacketVersionMappings, PhaseAttribute, VersionsToID};
+use proc_macro2::Span;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{braced, parse_quote, Attribute, Error, Ident, Item, ItemStruct, Path, Token, Visibility};
+
+use crate::kw;
+
+/// The contents of a `packet!` invocation.
+pub struct PacketListing {
+    pub header: Option<ListingHeader>,
+    pub items: Vec<Item>,
+}
+
+/// The optional `pub enum Name;` that starts a listing.
+pub struct ListingHeader {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    pub name: Ident,
+}
+
+impl Parse for PacketListing {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let header = if is_header(input) {
+            let attrs = input.call(Attribute::parse_outer)?;
+            let vis = input.parse()?;
+            input.parse::<Token![enum]>()?;
+            let name = input.parse()?;
+            input.parse::<Token![;]>()?;
+            Some(ListingHeader { attrs, vis, name })
+        } else {
+            None
+        };
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            items.push(input.parse()?);
+        }
+        Ok(Self { header, items })
+    }
+}
+
+fn is_header(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.call(Attribute::parse_outer).is_ok() && fork.parse::<Visibility>().is_ok() && fork.peek(Token![enum]) && fork.peek3(Token![;])
+}
+
+/// Whether the struct is declared as a packet, other items of a listing are
+/// left as they are.
+pub fn is_packet(item: &ItemStruct) -> bool { item.attrs.iter().any(|attr| attr.path.is_ident("falcon_packet")) }
+
+/// The mappings of a single packet declared in `packet!`.
+pub struct PacketData {
+    pub struct_name: Ident,
+    pub state: PacketState,
+    pub direction: PacketDirection,
+    pub versions: PacketVersionMappings,
+}
+
+impl PacketData {
+    pub fn parse_packet(item: &mut ItemStruct) -> syn::Result<PacketData> {
+        let mut error = ErrorCatcher::new();
+        let mut phase = PacketPhase::new();
+        let mut versions = PacketVersionMappings::new();
+        let mut found = false;
+
+        for attr in &item.attrs {
+            if attr.path.is_ident("falcon_packet") {
+                found = true;
+                let args = attr.parse_args_with(Punctuated::<PacketAttribute, Token![,]>::parse_terminated)?;
+                for arg in args {
+                    match arg {
+                        PacketAttribute::Phase(p) => error.extend_error(phase.add_attribute(p)),
+                        PacketAttribute::Versions(v) => error.extend_error(versions.add_versions(v.into_iter())),
+                    }
+                }
+            }
+        }
+
+        item.attrs.retain(|attr| !attr.path.is_ident("falcon_packet"));
+        if !found {
+            return Err(Error::new(item.ident.span(), "missing \"falcon_packet\" attribute"));
+        }
+        if versions.versions().next().is_none() && versions.is_exclude().is_none() {
+            error.add_error(Error::new(item.ident.span(), "no version mappings provided on a \"falcon_packet\" struct"));
+        }
+        if phase.state.is_none() {
+            error.add_error(Error::new(item.ident.span(), "missing \"falcon_packet\" attribute \"state\""));
+        }
+        if phase.direction.is_none() {
+            error.add_error(Error::new(item.ident.span(), "missing \"falcon_packet\" attribute \"direction\""));
+        }
+        error.emit()?;
+
+        Ok(PacketData {
+            struct_name: item.ident.clone(),
+            state: phase.state.unwrap(),
+            direction: phase.direction.unwrap(),
+            versions,
+        })
+    }
+
+    pub fn phase(&self) -> PacketPhase {
+        PacketPhase {
+            state: Some(self.state),
+            direction: Some(self.direction),
+        }
+    }
+
+    pub fn state_path(&self) -> Path {
+        let variant = Ident::new(self.state.variant_name(), Span::call_site());
+        parse_quote!(::falcon_packet_core::protocol::ProtocolState::#variant)
+    }
+
+    pub fn direction_path(&self) -> Path {
+        let variant = Ident::new(self.direction.variant_name(), Span::call_site());
+        parse_quote!(::falcon_packet_core::protocol::PacketDirection::#variant)
+    }
+}
+
+enum PacketAttribute {
+    Phase(PhaseAttribute),
+    Versions(Punctuated<VersionsToID, Token![;]>),
+}
+
+impl Parse for PacketAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if PhaseAttribute::peek(input) {
+            Ok(Self::Phase(input.parse()?))
+        } else if input.peek(kw::versions) {
+            input.parse::<kw::versions>()?;
+            input.parse::<Token![=]>()?;
+            let content;
+            braced!(content in input);
+            Ok(Self::Versions(content.parse_terminated(VersionsToID::parse)?))
+        } else {
+            Err(Error::new(input.span(), "Unexpected attribute argument!"))
+        }
+    }
+}
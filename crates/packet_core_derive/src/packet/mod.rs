@@ -0,0 +1,202 @@
+use falcon_proc_util::ErrorCatcher;
+use falcon_protocol_util::check_collisions;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, parse_quote_spanned, Arm, ImplItemMethod, Item, ItemEnum, ItemImpl, ItemStruct, Path, Token};
+
+use self::data::{is_packet, ListingHeader, PacketData, PacketListing};
+
+pub(crate) mod data;
+
+pub(crate) fn implement_packet(mut listing: PacketListing) -> syn::Result<TokenStream> {
+    let mut error = ErrorCatcher::new();
+    let mut packets = Vec::new();
+    let mut result = TokenStream::new();
+    for item in &mut listing.items {
+        match item {
+            Item::Struct(item) if is_packet(item) => {
+                match PacketData::parse_packet(item) {
+                    Ok(data) => packets.push(data),
+                    Err(e) => error.add_error(e),
+                }
+                // packets that pick their own derives only get those
+                if !derives_packet_traits(item) {
+                    result.extend(quote! {
+                        #[derive(::falcon_packet_core::PacketRead, ::falcon_packet_core::PacketWrite, ::falcon_packet_core::PacketSize)]
+                    });
+                }
+                result.extend(item.to_token_stream());
+            },
+            item => result.extend(item.to_token_stream()),
+        }
+    }
+    error.emit()?;
+    check_collisions(packets.iter().map(|packet| (&packet.struct_name, packet.phase(), &packet.versions)))?;
+
+    for packet in &packets {
+        result.extend(generate_packet_id(packet).into_token_stream());
+    }
+    if let Some(header) = &listing.header {
+        result.extend(generate_dispatch(header, &packets));
+    }
+    Ok(result)
+}
+
+fn derives_packet_traits(item: &ItemStruct) -> bool {
+    item.attrs.iter().filter(|attr| attr.path.is_ident("derive")).any(|attr| {
+        let Ok(paths) = attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated) else {
+            return false;
+        };
+        paths
+            .into_iter()
+            .filter_map(|path| path.segments.into_iter().last())
+            .any(|segment| ["PacketRead", "PacketWrite", "PacketSize"].iter().any(|name| segment.ident == name))
+    })
+}
+
+fn generate_packet_id(packet: &PacketData) -> ItemImpl {
+    let name = &packet.struct_name;
+    let state = packet.state_path();
+    let direction = packet.direction_path();
+    let arms: Vec<Arm> = packet
+        .versions
+        .versions()
+        .map(|(id, versions)| {
+            parse_quote_spanned! {id.span()=>
+                #(#versions)|* => ::core::option::Option::Some(#id)
+            }
+        })
+        .collect();
+    let fallback = match packet.versions.is_exclude() {
+        Some(id) => quote!(::core::option::Option::Some(#id)),
+        None => quote!(::core::option::Option::None),
+    };
+    let body = if arms.is_empty() {
+        fallback
+    } else {
+        quote! {
+            match _protocol {
+                #(#arms,)*
+                _ => #fallback,
+            }
+        }
+    };
+
+    parse_quote_spanned! {name.span()=>
+        #[automatically_derived]
+        impl ::falcon_packet_core::protocol::PacketId for #name {
+            const STATE: ::falcon_packet_core::protocol::ProtocolState = #state;
+            const DIRECTION: ::falcon_packet_core::protocol::PacketDirection = #direction;
+
+            // protocol lists like `759, 760, 761` are clearer than ranges
+            #[allow(clippy::manual_range_patterns)]
+            fn packet_id(_protocol: i32) -> ::core::option::Option<i32> {
+                #body
+            }
+        }
+    }
+}
+
+fn generate_dispatch(header: &ListingHeader, packets: &[PacketData]) -> TokenStream {
+    let attrs = &header.attrs;
+    let vis = &header.vis;
+    let name = &header.name;
+    let variants = packets.iter().map(|packet| &packet.struct_name);
+
+    let mut read_arms: Vec<Arm> = Vec::new();
+    for packet in packets {
+        let packet_name = &packet.struct_name;
+        let state = packet.state_path();
+        let direction = packet.direction_path();
+        let read = quote! {
+            ::core::result::Result::Ok(::core::option::Option::Some(#name::#packet_name(
+                ::falcon_packet_core::PacketReadVersioned::read_versioned(buffer, protocol)?,
+            )))
+        };
+        for (id, versions) in packet.versions.versions() {
+            read_arms.push(parse_quote_spanned! {packet_name.span()=>
+                (#state, #direction, #id) if matches!(protocol, #(#versions)|*) => #read
+            });
+        }
+        if let Some(id) = packet.versions.is_exclude() {
+            read_arms.push(parse_quote_spanned! {packet_name.span()=>
+                (#state, #direction, #id) => #read
+            });
+        }
+    }
+
+    let id_arms = packets.iter().map(|packet| {
+        let packet_name = &packet.struct_name;
+        quote!(#name::#packet_name(_) => <#packet_name as ::falcon_packet_core::protocol::PacketId>::packet_id(protocol))
+    });
+    let from_impls = packets.iter().map(|packet| {
+        let packet_name = &packet.struct_name;
+        quote! {
+            impl ::core::convert::From<#packet_name> for #name {
+                fn from(packet: #packet_name) -> Self { #name::#packet_name(packet) }
+            }
+        }
+    });
+
+    let item: ItemEnum = parse_quote! {
+        #(#attrs)*
+        #vis enum #name {
+            #(#variants(#variants),)*
+        }
+    };
+    let read: ImplItemMethod = parse_quote! {
+        /// Reads the packet with the given id, returns `None` if no packet
+        /// with that id exists for the state, direction and protocol version.
+        pub fn read<B>(
+            state: ::falcon_packet_core::protocol::ProtocolState,
+            direction: ::falcon_packet_core::protocol::PacketDirection,
+            protocol: i32,
+            packet_id: i32,
+            buffer: &mut B,
+        ) -> ::core::result::Result<::core::option::Option<Self>, ::falcon_packet_core::ReadError>
+        where
+            B: ::bytes::Buf + ?Sized,
+        {
+            #[allow(unreachable_patterns)]
+            match (state, direction, packet_id) {
+                #(#read_arms,)*
+                _ => ::core::result::Result::Ok(::core::option::Option::None),
+            }
+        }
+    };
+
+    quote! {
+        #item
+
+        #[automatically_derived]
+        impl #name {
+            #read
+
+            /// Decodes an uncompressed frame as sent over the connection: its
+            /// length, the packet id and the packet. Returns `None` if no packet
+            /// with that id exists, the bytes after the frame are ignored.
+            ///
+            /// This never panics, whatever `bytes` holds, which makes it the
+            /// entry point for fuzz targets.
+            pub fn decode_frame(
+                state: ::falcon_packet_core::protocol::ProtocolState,
+                direction: ::falcon_packet_core::protocol::PacketDirection,
+                protocol: i32,
+                mut bytes: &[u8],
+            ) -> ::core::result::Result<::core::option::Option<Self>, ::falcon_packet_core::ReadError> {
+                let (packet_id, mut body) = ::falcon_packet_core::special::split_frame(&mut bytes)?;
+                Self::read(state, direction, protocol, packet_id, &mut body)
+            }
+
+            /// Returns the id of this packet in the given protocol version.
+            pub fn packet_id(&self, protocol: i32) -> ::core::option::Option<i32> {
+                match self {
+                    #(#id_arms,)*
+                }
+            }
+        }
+
+        #(#from_impls)*
+    }
+}
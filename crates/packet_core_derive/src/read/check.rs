@@ -1,7 +1,7 @@
 use falcon_proc_util::ErrorCatcher;
 use syn::Error;
 
-use crate::attributes::PacketAttribute::{self, Array, Bytes, Convert, From, Into, Link, Nbt, String, ToString, VarI32, VarI64, Vec as PacketVec};
+use crate::attributes::PacketAttribute::{self, Array, Bytes, Collection, Convert, Flags, From, Into, Link, Nbt, Rest, String, ToString, VarI32, VarI64, Vec as PacketVec};
 
 pub fn is_outer(attribute: &PacketAttribute) -> bool {
     match attribute {
@@ -16,7 +16,10 @@ pub fn is_outer(attribute: &PacketAttribute) -> bool {
         Link(_) => true,
         Convert(_) => false,
         Array(_) => true,
+        Collection(_) => true,
+        Flags(_) => true,
         Nbt(_) => true,
+        Rest(_) => true,
     }
 }
 
@@ -51,8 +54,11 @@ where
         Convert(_) => all_except!(Into(_) | From(_), others, "`convert`").emit(),
         Link(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`link`").emit(),
         Array(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`array`").emit(),
+        Collection(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`collection`").emit(),
+        Flags(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`bitflags`").emit(),
         Bytes(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`bytes`").emit(),
         Nbt(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`nbt`").emit(),
+        Rest(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`rest`").emit(),
         From(_) => Ok(()),
     }
 }
@@ -40,6 +40,18 @@ pub fn to_begin(attribute: &PacketAttribute, span: Span) -> Option<Expr> {
                 buffer,
             )?
         }),
+        Collection(_) => Some(parse_quote_spanned! {span=>
+            ::falcon_packet_core::PacketReadSeed::read(
+                ::falcon_packet_core::PacketCollection::default(),
+                buffer,
+            )?
+        }),
+        Flags(_) => Some(parse_quote_spanned! {span=>
+            ::falcon_packet_core::PacketReadSeed::read(
+                ::falcon_packet_core::PacketFlags::default(),
+                buffer,
+            )?
+        }),
         Bytes(data) => Some(match data.target.as_ref() {
             Some(target) => parse_quote_spanned! {span=>
                 ::falcon_packet_core::PacketReadSeed::read(
@@ -66,6 +78,12 @@ pub fn to_begin(attribute: &PacketAttribute, span: Span) -> Option<Expr> {
                 },
             })
         },
+        Rest(_) => Some(parse_quote_spanned! {span=>
+            ::falcon_packet_core::PacketReadSeed::read(
+                ::falcon_packet_core::PacketRest::default(),
+                buffer,
+            )?
+        }),
         Nbt(_) => Some(parse_quote_spanned! {span=>
             {
                 let reader = ::falcon_packet_core::special::Reader::new(buffer);
@@ -80,24 +98,24 @@ pub fn to_tokenstream(attribute: &PacketAttribute, field: Expr, field_ty: &Type)
     match attribute {
         VarI32(_) => {
             parse_quote_spanned! {field.span()=>
-                <#field_ty as ::std::convert::From<::falcon_packet_core::VarI32>>::from(#field)
+                <#field_ty as ::core::convert::From<::falcon_packet_core::VarI32>>::from(#field)
             }
         },
         VarI64(_) => {
             parse_quote_spanned! {field.span()=>
-                <#field_ty as ::std::convert::From<::falcon_packet_core::VarI64>>::from(#field)
+                <#field_ty as ::core::convert::From<::falcon_packet_core::VarI64>>::from(#field)
             }
         },
         From(data) => {
             let target = &data.target;
             parse_quote_spanned! {field.span()=>
-                <#field_ty as ::std::convert::From<#target>>::from(#field)
+                <#field_ty as ::core::convert::From<#target>>::from(#field)
             }
         },
         Convert(data) => {
             let target = &data.target;
             parse_quote_spanned! {field.span()=>
-                <#field_ty as ::std::convert::From<#target>>::from(#field)
+                <#field_ty as ::core::convert::From<#target>>::from(#field)
             }
         },
         _ => field,
@@ -1,37 +1,34 @@
-use falcon_proc_util::ErrorCatcher;
 use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn::spanned::Spanned;
-use syn::{parse_quote_spanned, Error, Expr, Fields, ItemImpl, ItemStruct, Stmt};
+use syn::{parse_quote, parse_quote_spanned, Expr, Fields, Ident, ItemImpl, ItemStruct, Stmt};
 
 use self::check::validate;
 use self::generate::{to_begin, to_tokenstream};
-use crate::util::ParsedFields;
+use crate::util::{binding, protocol_ident, ParsedFields};
 
 mod check;
 mod generate;
 
 pub(crate) fn implement_read(item: ItemStruct) -> syn::Result<TokenStream> {
-    let mut error = ErrorCatcher::new();
-
-    match &item.fields {
-        Fields::Named(fields) => {
-            let fields = error.critical(ParsedFields::new(&fields.named, validate))?;
-            return Ok(generate_tokens(&item, fields).into_token_stream());
-        },
-        _ => error.add_error(Error::new(item.fields.span(), "Only named fields are supported currently")),
-    }
-
-    error.emit()?;
-    Ok(TokenStream::new())
+    let fields = ParsedFields::new(&item.fields, validate)?;
+    Ok(generate_tokens(&item, fields).into_token_stream())
 }
 
 fn generate_tokens(item: &ItemStruct, parsed: ParsedFields) -> ItemImpl {
+    let versioned = parsed.is_versioned();
+    let protocol = protocol_ident();
+    let generics = if versioned {
+        parsed.bounded_generics(&item.generics, parse_quote!(::falcon_packet_core::PacketReadVersioned))
+    } else {
+        parsed.bounded_generics(&item.generics, parse_quote!(::falcon_packet_core::PacketRead))
+    };
     let mut temps: Vec<Stmt> = Vec::with_capacity(parsed.fields.len());
-    let mut reads: Vec<Expr> = Vec::with_capacity(parsed.fields.len());
+    let mut reads: Vec<Ident> = Vec::with_capacity(parsed.fields.len());
+    let struct_name = item.ident.to_string();
 
-    for (field, data) in parsed.fields {
-        let ident = field.ident.as_ref().unwrap();
+    for (index, ((field, data), bounds)) in parsed.fields.into_iter().zip(parsed.versions).enumerate() {
+        let ident = binding(index, field);
         let field_ty = &field.ty;
 
         let should_skip;
@@ -39,6 +36,14 @@ fn generate_tokens(item: &ItemStruct, parsed: ParsedFields) -> ItemImpl {
         if let Some(field) = data.first().and_then(|attr| to_begin(attr, field.span())) {
             should_skip = 1;
             tokens = field;
+        } else if versioned {
+            should_skip = 0;
+            tokens = parse_quote_spanned! {field.span()=>
+                ::falcon_packet_core::PacketReadVersioned::read_versioned(
+                    buffer,
+                    #protocol,
+                )?
+            };
         } else {
             should_skip = 0;
             tokens = parse_quote_spanned! {field.span()=>
@@ -52,29 +57,69 @@ fn generate_tokens(item: &ItemStruct, parsed: ParsedFields) -> ItemImpl {
             tokens = to_tokenstream(attribute, tokens, field_ty);
         }
 
+        if let Some(condition) = bounds.condition(&protocol) {
+            tokens = parse_quote_spanned! {tokens.span()=>
+                if #condition {
+                    #tokens
+                } else {
+                    ::core::default::Default::default()
+                }
+            };
+        }
+
+        // the read is wrapped in a closure so every error of this field
+        // can be given the field as context
+        let field_name = match &field.ident {
+            Some(name) => name.to_string(),
+            None => index.to_string(),
+        };
         temps.push(parse_quote_spanned! {tokens.span()=>
-            let #ident: #field_ty = #tokens;
+            let #ident: #field_ty = {
+                let result = (|| -> ::core::result::Result<#field_ty, ::falcon_packet_core::ReadError> { Ok(#tokens) })();
+                match result {
+                    Ok(value) => value,
+                    Err(error) => return Err(error.in_field(#struct_name, #field_name, __falcon_start - buffer.remaining())),
+                }
+            };
         });
-        reads.push(parse_quote_spanned! {tokens.span()=>
-            #ident
-        })
+        reads.push(ident);
     }
 
     let ident = &item.ident;
-    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    let construct: Expr = match item.fields {
+        Fields::Unnamed(_) => parse_quote_spanned! {item.ident.span()=> #ident(#(#reads),*)},
+        _ => parse_quote_spanned! {item.ident.span()=> #ident { #(#reads),* }},
+    };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    if versioned {
+        return parse_quote_spanned! {item.ident.span()=>
+            #[allow(clippy::useless_conversion, clippy::redundant_closure_call, clippy::needless_question_mark)]
+            #[automatically_derived]
+            impl #impl_generics ::falcon_packet_core::PacketReadVersioned for #ident #ty_generics #where_clause {
+                fn read_versioned<B>(buffer: &mut B, #protocol: i32) -> ::core::result::Result<Self, ::falcon_packet_core::ReadError>
+                where
+                    B: ::bytes::Buf + ?Sized,
+                    Self: Sized
+                {
+                    let __falcon_start = buffer.remaining();
+                    #(#temps)*
+                    Ok(#construct)
+                }
+            }
+        };
+    }
     parse_quote_spanned! {item.ident.span()=>
-        #[allow(clippy::useless_conversion)]
+        #[allow(clippy::useless_conversion, clippy::redundant_closure_call, clippy::needless_question_mark)]
         #[automatically_derived]
         impl #impl_generics ::falcon_packet_core::PacketRead for #ident #ty_generics #where_clause {
-            fn read<B>(buffer: &mut B) -> ::std::result::Result<Self, ::falcon_packet_core::ReadError>
+            fn read<B>(buffer: &mut B) -> ::core::result::Result<Self, ::falcon_packet_core::ReadError>
             where
                 B: ::bytes::Buf + ?Sized,
                 Self: Sized
             {
+                let __falcon_start = buffer.remaining();
                 #(#temps)*
-                Ok(#ident {
-                    #(#reads),*
-                })
+                Ok(#construct)
             }
         }
     }
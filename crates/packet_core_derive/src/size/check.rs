@@ -3,7 +3,7 @@ use std::collections::HashSet;
 use falcon_proc_util::ErrorCatcher;
 use syn::{Error, Field, Ident};
 
-use crate::attributes::PacketAttribute::{self, Array, Bytes, Convert, From, Into, Link, Nbt, String, ToString, VarI32, VarI64, Vec as PacketVec};
+use crate::attributes::PacketAttribute::{self, Array, Bytes, Collection, Convert, Flags, From, Into, Link, Nbt, Rest, String, ToString, VarI32, VarI64, Vec as PacketVec};
 
 pub fn get_replaced(attributes: &[(&Field, Vec<PacketAttribute>)]) -> HashSet<Ident> {
     attributes
@@ -30,7 +30,10 @@ pub fn is_outer(attribute: &PacketAttribute) -> bool {
         From(_) => false,
         Convert(_) => false,
         Array(_) => true,
+        Collection(_) => true,
+        Flags(_) => true,
         Nbt(_) => true,
+        Rest(_) => true,
         ToString(_) => true,
     }
 }
@@ -66,8 +69,11 @@ where
         Into(_) => all_except!(Convert(_), others, "`into`").emit(),
         Convert(_) => all_except!(Into(_) | From(_), others, "`convert`").emit(),
         Array(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`array`").emit(),
+        Collection(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`collection`").emit(),
+        Flags(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`bitflags`").emit(),
         Bytes(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`bytes`").emit(),
         Nbt(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`nbt`").emit(),
+        Rest(_) => none_except!(Into(_) | From(_) | Convert(_), others, "`rest`").emit(),
         From(_) => Ok(()),
     }
 }
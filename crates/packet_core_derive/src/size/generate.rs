@@ -59,7 +59,7 @@ pub fn to_end(attribute: &PacketAttribute, field: Expr) -> Option<Expr> {
             Some(parse_quote_spanned! {field.span()=>
                 ::falcon_packet_core::PacketSizeSeed::size(
                     ::falcon_packet_core::PacketString::new(#len),
-                    &::std::string::ToString::to_string(&#field),
+                    &::falcon_packet_core::__private::ToString::to_string(&#field),
                 )
             })
         },
@@ -75,6 +75,18 @@ pub fn to_end(attribute: &PacketAttribute, field: Expr) -> Option<Expr> {
                 &#field,
             )
         }),
+        Collection(_) => Some(parse_quote_spanned! {field.span()=>
+            ::falcon_packet_core::PacketSizeSeed::size(
+                ::falcon_packet_core::PacketCollection::default(),
+                &#field,
+            )
+        }),
+        Flags(_) => Some(parse_quote_spanned! {field.span()=>
+            ::falcon_packet_core::PacketSizeSeed::size(
+                ::falcon_packet_core::PacketFlags::default(),
+                &#field,
+            )
+        }),
         Bytes(_) => Some(parse_quote_spanned! {field.span()=>
             ::falcon_packet_core::PacketSizeSeed::size(
                 ::falcon_packet_core::AsRefU8::default(),
@@ -87,12 +99,19 @@ pub fn to_end(attribute: &PacketAttribute, field: Expr) -> Option<Expr> {
                 #prefix(&#field)
             })
         },
+        Rest(_) => Some(parse_quote_spanned! {field.span()=>
+            ::falcon_packet_core::PacketSizeSeed::size(
+                ::falcon_packet_core::PacketRest::default(),
+                &#field,
+            )
+        }),
+        // parenthesized, a block cannot start the sum of the field sizes
         Nbt(_) => Some(parse_quote_spanned! {field.span()=>
-            {
+            ({
                 let mut writer = ::falcon_packet_core::special::Counter::new();
                 ::fastnbt::to_writer(&mut writer, &#field).expect("Invalid NBT to be sent!!");
                 writer.count()
-            }
+            })
         }),
         _ => None,
     }
@@ -113,13 +132,13 @@ pub fn to_tokenstream(attribute: &PacketAttribute, field: Expr, field_ty: &Type)
         Into(data) => {
             let target = &data.target;
             parse_quote_spanned! {field.span()=>
-                <#field_ty as ::std::convert::Into<#target>>::into(::std::clone::Clone::clone(&#field))
+                <#field_ty as ::core::convert::Into<#target>>::into(::core::clone::Clone::clone(&#field))
             }
         },
         Convert(data) => {
             let target = &data.target;
             parse_quote_spanned! {field.span()=>
-                <#field_ty as ::std::convert::Into<#target>>::into(::std::clone::Clone::clone(&#field))
+                <#field_ty as ::core::convert::Into<#target>>::into(::core::clone::Clone::clone(&#field))
             }
         },
         _ => field,
@@ -1,44 +1,41 @@
-use falcon_proc_util::ErrorCatcher;
 use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn::spanned::Spanned;
-use syn::{parse_quote_spanned, Error, Expr, Fields, ItemImpl, ItemStruct, Stmt};
+use syn::{parse_quote, parse_quote_spanned, Expr, ItemImpl, ItemStruct, Stmt};
 
 use self::check::{get_replaced, validate};
 use self::generate::{to_end, to_preprocess, to_tokenstream};
-use crate::util::ParsedFields;
+use crate::util::{member, protocol_ident, ParsedFields};
 
 mod check;
 mod generate;
 
 pub(crate) fn implement_size(item: ItemStruct) -> syn::Result<TokenStream> {
-    let mut error = ErrorCatcher::new();
-
-    match &item.fields {
-        Fields::Named(fields) => {
-            let fields = error.critical(ParsedFields::new(&fields.named, validate))?;
-            return Ok(generate_tokens(&item, fields).into_token_stream());
-        },
-        _ => error.add_error(Error::new(item.fields.span(), "Only named fields are supported currently")),
-    }
-
-    error.emit()?;
-    Ok(TokenStream::new())
+    let fields = ParsedFields::new(&item.fields, validate)?;
+    Ok(generate_tokens(&item, fields).into_token_stream())
 }
 
 fn generate_tokens(item: &ItemStruct, parsed: ParsedFields) -> ItemImpl {
+    let versioned = parsed.is_versioned();
+    let protocol = protocol_ident();
+    let generics = if versioned {
+        parsed.bounded_generics(&item.generics, parse_quote!(::falcon_packet_core::PacketSizeVersioned))
+    } else {
+        parsed.bounded_generics(&item.generics, parse_quote!(::falcon_packet_core::PacketSize))
+    };
     let mut preprocess: Vec<Stmt> = Vec::new();
     let mut writes: Vec<Expr> = Vec::with_capacity(parsed.fields.len());
 
     let replace = get_replaced(&parsed.fields);
 
-    for (field, data) in parsed.fields {
-        let ident = field.ident.as_ref().unwrap();
+    for (index, ((field, data), bounds)) in parsed.fields.into_iter().zip(parsed.versions).enumerate() {
+        let member = member(index, field);
         let field_ty = &field.ty;
-        let mut field: Expr = if replace.contains(ident) {
-            parse_quote_spanned! {field.span()=> <#field_ty as ::std::convert::From<usize>>::from(#ident)}
-        } else {
-            parse_quote_spanned! {field.span()=> self.#ident}
+        let mut field: Expr = match &field.ident {
+            Some(ident) if replace.contains(ident) => {
+                parse_quote_spanned! {field.span()=> <#field_ty as ::core::convert::From<usize>>::from(#ident)}
+            },
+            _ => parse_quote_spanned! {field.span()=> self.#member},
         };
 
         let mut end = None;
@@ -53,17 +50,47 @@ fn generate_tokens(item: &ItemStruct, parsed: ParsedFields) -> ItemImpl {
             }
         }
 
-        writes.push(end.unwrap_or_else(|| {
-            parse_quote_spanned! {field.span()=>
-                ::falcon_packet_core::PacketSize::size(
-                    &#field,
-                )
+        let size = end.unwrap_or_else(|| {
+            if versioned {
+                parse_quote_spanned! {field.span()=>
+                    ::falcon_packet_core::PacketSizeVersioned::size_versioned(
+                        &#field,
+                        #protocol,
+                    )
+                }
+            } else {
+                parse_quote_spanned! {field.span()=>
+                    ::falcon_packet_core::PacketSize::size(
+                        &#field,
+                    )
+                }
             }
-        }));
+        });
+        writes.push(match bounds.condition(&protocol) {
+            Some(condition) => parse_quote_spanned! {field.span()=>
+                (if #condition { #size } else { 0 })
+            },
+            None => size,
+        });
+    }
+    if writes.is_empty() {
+        writes.push(parse_quote_spanned! {item.ident.span()=> 0});
     }
 
     let ident = &item.ident;
-    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    if versioned {
+        return parse_quote_spanned! {item.ident.span()=>
+            #[allow(clippy::useless_conversion)]
+            #[automatically_derived]
+            impl #impl_generics ::falcon_packet_core::PacketSizeVersioned for #ident #ty_generics #where_clause {
+                fn size_versioned(&self, #protocol: i32) -> usize {
+                    #(#preprocess)*
+                    #(#writes)+*
+                }
+            }
+        };
+    }
     parse_quote_spanned! {item.ident.span()=>
         #[allow(clippy::useless_conversion)]
         #[automatically_derived]
@@ -1,45 +1,155 @@
 use falcon_proc_util::ErrorCatcher;
 use indexmap::IndexSet;
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, ToTokens};
 use syn::punctuated::Punctuated;
-use syn::{Field, Token};
+use syn::spanned::Spanned;
+use syn::{parse_quote, Error, Field, Fields, Generics, Ident, Index, Member, Path, Token, Type};
 
+use crate::attributes::version::{FieldAttribute, VersionBounds};
 use crate::attributes::PacketAttribute;
 
 pub struct ParsedFields<'a> {
     pub fields: Vec<(&'a Field, Vec<PacketAttribute>)>,
+    pub versions: Vec<VersionBounds>,
 }
 
 impl<'a> ParsedFields<'a> {
-    pub fn new<F>(fields: &'a Punctuated<Field, Token![,]>, validate: F) -> syn::Result<Self>
+    pub fn new<F>(fields: &'a Fields, validate: F) -> syn::Result<Self>
     where
         F: FnOnce(Vec<PacketAttribute>) -> syn::Result<Vec<PacketAttribute>> + Copy,
     {
+        let mut error = ErrorCatcher::new();
         let mut result = Vec::with_capacity(fields.len());
-        for field in fields {
-            result.push((field, to_attributes(field, validate)?));
+        let mut versions = Vec::with_capacity(fields.len());
+        match fields {
+            Fields::Named(fields) => {
+                for field in &fields.named {
+                    let (attributes, bounds) = to_attributes(field, validate)?;
+                    result.push((field, attributes));
+                    versions.push(bounds);
+                }
+            },
+            Fields::Unnamed(fields) => {
+                for field in &fields.unnamed {
+                    let (attributes, bounds) = to_attributes(field, validate)?;
+                    for attribute in attributes.iter().filter(|a| refers_to_field(a)) {
+                        error.add_error(Error::new(attribute.span(), "Attributes referring to other fields are not supported on tuple structs"));
+                    }
+                    result.push((field, attributes));
+                    versions.push(bounds);
+                }
+            },
+            Fields::Unit => error.add_error(Error::new(fields.span(), "Unit structs are not supported")),
+        }
+        for (_, attributes) in result.iter().rev().skip(1) {
+            for attribute in attributes.iter().filter(|a| matches!(a, PacketAttribute::Rest(_))) {
+                error.add_error(Error::new(attribute.span(), "`rest` can only be used on the last field"));
+            }
         }
-        Ok(Self { fields: result })
+        error.emit()?;
+        Ok(Self { fields: result, versions })
+    }
+
+    /// Whether any field is only present in some protocol versions, such
+    /// structs implement the versioned packet traits.
+    pub fn is_versioned(&self) -> bool { self.versions.iter().any(|bounds| !bounds.is_empty()) }
+}
+
+/// The name of the protocol version argument in versioned implementations.
+pub fn protocol_ident() -> Ident { format_ident!("__protocol") }
+
+/// How `field` is accessed on the struct, by name or by position.
+pub fn member(index: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index {
+            index: index as u32,
+            span: field.span(),
+        }),
+    }
+}
+
+/// The name of a local variable holding the value of `field`.
+pub fn binding(index: usize, field: &Field) -> Ident {
+    match &field.ident {
+        Some(ident) => ident.clone(),
+        None => format_ident!("field_{}", index, span = field.span()),
+    }
+}
+
+fn refers_to_field(attribute: &PacketAttribute) -> bool {
+    match attribute {
+        PacketAttribute::Vec(_) | PacketAttribute::Link(_) => true,
+        PacketAttribute::Bytes(data) => data.target.is_some(),
+        _ => false,
+    }
+}
+
+impl<'a> ParsedFields<'a> {
+    /// Returns `generics` with an extra `field_ty: bound` predicate for
+    /// every field without attributes whose type uses a type parameter.
+    ///
+    /// Fields with attributes are left alone, their bounds depend on the
+    /// attribute and should be written on the struct itself.
+    pub fn bounded_generics(&self, generics: &Generics, bound: Path) -> Generics {
+        let params: Vec<&Ident> = generics.type_params().map(|param| &param.ident).collect();
+        let mut generics = generics.clone();
+        if params.is_empty() {
+            return generics;
+        }
+        let where_clause = generics.make_where_clause();
+        for (field, attributes) in &self.fields {
+            let ty = &field.ty;
+            if attributes.is_empty() && uses_params(ty, &params) {
+                where_clause.predicates.push(parse_quote!(#ty: #bound));
+            }
+        }
+        generics
+    }
+}
+
+fn uses_params(ty: &Type, params: &[&Ident]) -> bool {
+    fn contains(tokens: TokenStream, params: &[&Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => params.iter().any(|param| **param == ident),
+            TokenTree::Group(group) => contains(group.stream(), params),
+            _ => false,
+        })
     }
+    contains(ty.to_token_stream(), params)
 }
 
-fn to_attributes<F>(field: &Field, validate: F) -> syn::Result<Vec<PacketAttribute>>
+fn to_attributes<F>(field: &Field, validate: F) -> syn::Result<(Vec<PacketAttribute>, VersionBounds)>
 where
     F: FnOnce(Vec<PacketAttribute>) -> syn::Result<Vec<PacketAttribute>>,
 {
     let mut error = ErrorCatcher::new();
+    let mut bounds = VersionBounds::default();
 
     let attributes: Vec<PacketAttribute> = field
         .attrs
         .iter()
         .filter(|a| a.path.is_ident("falcon"))
-        .map(|a| a.parse_args_with(Punctuated::<PacketAttribute, Token![,]>::parse_terminated))
+        .map(|a| a.parse_args_with(Punctuated::<FieldAttribute, Token![,]>::parse_terminated))
         .fold(IndexSet::new(), |mut result, attrs| {
             let attrs = attrs.map(|attrs| {
                 for attr in attrs {
-                    if result.contains(&attr) {
-                        error.add_error(syn::Error::new(attr.span(), "Attribute already defined earlier"));
-                    } else {
-                        result.insert(attr);
+                    match attr {
+                        FieldAttribute::Packet(attr) if result.contains(&attr) => {
+                            error.add_error(syn::Error::new(attr.span(), "Attribute already defined earlier"));
+                        },
+                        FieldAttribute::Packet(attr) => {
+                            result.insert(attr);
+                        },
+                        FieldAttribute::Since(attr) if bounds.since.is_some() => {
+                            error.add_error(syn::Error::new(attr.span(), "Attribute already defined earlier"));
+                        },
+                        FieldAttribute::Since(attr) => bounds.since = Some(attr),
+                        FieldAttribute::Until(attr) if bounds.until.is_some() => {
+                            error.add_error(syn::Error::new(attr.span(), "Attribute already defined earlier"));
+                        },
+                        FieldAttribute::Until(attr) => bounds.until = Some(attr),
                     }
                 }
             });
@@ -51,5 +161,5 @@ where
 
     error.emit()?;
 
-    validate(attributes)
+    Ok((validate(attributes)?, bounds))
 }
@@ -60,7 +60,7 @@ pub fn to_end(attribute: &PacketAttribute, field: Expr) -> Option<Stmt> {
             Some(parse_quote_spanned! {field.span()=>
                 ::falcon_packet_core::PacketWriteSeed::write(
                     ::falcon_packet_core::PacketString::new(#len),
-                    &::std::string::ToString::to_string(&#field),
+                    &::falcon_packet_core::__private::ToString::to_string(&#field),
                     buffer,
                 )?;
             })
@@ -79,6 +79,20 @@ pub fn to_end(attribute: &PacketAttribute, field: Expr) -> Option<Stmt> {
                 buffer,
             )?;
         }),
+        Collection(_) => Some(parse_quote_spanned! {field.span()=>
+            ::falcon_packet_core::PacketWriteSeed::write(
+                ::falcon_packet_core::PacketCollection::default(),
+                &#field,
+                buffer,
+            )?;
+        }),
+        Flags(_) => Some(parse_quote_spanned! {field.span()=>
+            ::falcon_packet_core::PacketWriteSeed::write(
+                ::falcon_packet_core::PacketFlags::default(),
+                &#field,
+                buffer,
+            )?;
+        }),
         Bytes(_) => Some(parse_quote_spanned! {field.span()=>
             ::falcon_packet_core::PacketWriteSeed::write(
                 ::falcon_packet_core::AsRefU8::default(),
@@ -92,6 +106,13 @@ pub fn to_end(attribute: &PacketAttribute, field: Expr) -> Option<Stmt> {
                 #prefix(&#field, buffer)?;
             })
         },
+        Rest(_) => Some(parse_quote_spanned! {field.span()=>
+            ::falcon_packet_core::PacketWriteSeed::write(
+                ::falcon_packet_core::PacketRest::default(),
+                &#field,
+                buffer,
+            )?;
+        }),
         Nbt(_) => Some(parse_quote_spanned! {field.span()=>
             {
                 let writer = ::falcon_packet_core::special::Writer::new(buffer);
@@ -117,13 +138,13 @@ pub fn to_tokenstream(attribute: &PacketAttribute, field: Expr, field_ty: &Type)
         Into(data) => {
             let target = &data.target;
             parse_quote_spanned! {field.span()=>
-                <#field_ty as ::std::convert::Into<#target>>::into(::std::clone::Clone::clone(&#field))
+                <#field_ty as ::core::convert::Into<#target>>::into(::core::clone::Clone::clone(&#field))
             }
         },
         Convert(data) => {
             let target = &data.target;
             parse_quote_spanned! {field.span()=>
-                <#field_ty as ::std::convert::Into<#target>>::into(::std::clone::Clone::clone(&#field))
+                <#field_ty as ::core::convert::Into<#target>>::into(::core::clone::Clone::clone(&#field))
             }
         },
         _ => field,
@@ -1,5 +1,5 @@
-mod fn_name;
 mod packet_versions;
+mod phase;
 
-pub use fn_name::SendFnName;
-pub use packet_versions::{PacketVersionMappings, VersionsToID};
+pub use packet_versions::{PacketVersionMappings, ProtocolVersion, VersionsToID};
+pub use phase::{check_collisions, PacketDirection, PacketPhase, PacketState, PhaseAttribute};
@@ -2,11 +2,13 @@ use std::str::FromStr;
 
 use falcon_proc_util::ErrorCatcher;
 use itertools::Itertools;
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
-use syn::{Error, LitInt, Token};
+use syn::{Error, LitInt, RangeLimits, Token};
 
-type Versions = Vec<(LitInt, Vec<(LitInt, bool)>)>;
+type Versions = Vec<(LitInt, Vec<(ProtocolVersion, bool)>)>;
 
 #[derive(Debug)]
 pub struct PacketVersionMappings {
@@ -45,7 +47,7 @@ impl PacketVersionMappings {
                     entry = Some(i);
                 }
                 for version in &new_version.versions {
-                    if let Some((old_version, has_errored)) = versions.iter_mut().find(|(v, _)| v.base10_digits() == version.base10_digits()) {
+                    if let Some((old_version, has_errored)) = versions.iter_mut().find(|(v, _)| v.overlaps(version)) {
                         if !*has_errored {
                             *has_errored = true;
                             error.add_error(Error::new(old_version.span(), "duplicate protocol version"));
@@ -75,50 +77,120 @@ impl PacketVersionMappings {
         Ok(())
     }
 
-    pub fn versions(&self) -> impl Iterator<Item = (&LitInt, Vec<&LitInt>)> {
+    pub fn versions(&self) -> impl Iterator<Item = (&LitInt, Vec<&ProtocolVersion>)> {
         self.versions
             .iter()
             .map(|(id, versions)| (id, versions.iter().map(|(v, _)| v).collect()))
     }
 
     pub fn is_exclude(&self) -> Option<&LitInt> { self.is_exclude.as_ref() }
-
-    pub fn to_inner(&self) -> (Option<LitInt>, Versions) { (self.is_exclude.clone(), self.versions.clone()) }
 }
 
 impl Default for PacketVersionMappings {
     fn default() -> Self { Self::new() }
 }
 
+/// A single protocol version or a range of versions, written as
+/// `393..=404` or `477..`. Open ranges must be followed by a space before
+/// the `=` of the mapping.
+#[derive(Debug, Clone)]
+pub struct ProtocolVersion {
+    start: LitInt,
+    range: Option<(RangeLimits, Option<LitInt>)>,
+}
+
+impl ProtocolVersion {
+    /// The inclusive bounds of this version range, `None` as upper bound
+    /// means there is no upper limit.
+    pub fn bounds(&self) -> (i64, Option<i64>) {
+        let start = self.start.base10_parse().unwrap_or_default();
+        match &self.range {
+            None => (start, Some(start)),
+            Some((_, None)) => (start, None),
+            Some((RangeLimits::Closed(_), Some(end))) => (start, end.base10_parse().ok()),
+            Some((RangeLimits::HalfOpen(_), Some(end))) => (start, end.base10_parse::<i64>().ok().map(|end| end - 1)),
+        }
+    }
+
+    /// Whether both ranges have at least one version in common.
+    pub fn overlaps(&self, other: &ProtocolVersion) -> bool {
+        let (start, end) = self.bounds();
+        let (other_start, other_end) = other.bounds();
+        end.map(|end| other_start <= end).unwrap_or(true) && other_end.map(|other_end| start <= other_end).unwrap_or(true)
+    }
+
+    /// Returns the version if this is not a range.
+    pub fn single(&self) -> Option<&LitInt> {
+        match self.range {
+            None => Some(&self.start),
+            Some(_) => None,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match &self.range {
+            Some((_, Some(end))) => self.start.span().join(end.span()).unwrap_or_else(|| self.start.span()),
+            _ => self.start.span(),
+        }
+    }
+}
+
+impl Parse for ProtocolVersion {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let start = input.parse::<LitInt>()?;
+        let range = if input.peek(Token![..=]) || input.peek(Token![..]) {
+            let limits = input.parse::<RangeLimits>()?;
+            let end = if input.peek(LitInt) { Some(input.parse::<LitInt>()?) } else { None };
+            match (&limits, &end) {
+                (RangeLimits::Closed(limit), None) => return Err(Error::new(limit.spans[0], "inclusive ranges must have an upper bound")),
+                (_, Some(end)) if end.base10_parse::<i64>()? < start.base10_parse::<i64>()? => {
+                    return Err(Error::new(end.span(), "empty protocol version range"))
+                },
+                _ => {},
+            }
+            Some((limits, end))
+        } else {
+            None
+        };
+        Ok(Self { start, range })
+    }
+}
+
+impl ToTokens for ProtocolVersion {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.start.to_tokens(tokens);
+        if let Some((limits, end)) = &self.range {
+            limits.to_tokens(tokens);
+            end.to_tokens(tokens);
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct VersionsToID {
-    versions: Punctuated<LitInt, Token![,]>,
+    versions: Punctuated<ProtocolVersion, Token![,]>,
     id: LitInt,
 }
 
 impl VersionsToID {
     pub(crate) fn check_duplicates(self) -> syn::Result<Self> {
-        let versions: Punctuated<LitInt, Token![,]> = self
+        let versions: Punctuated<ProtocolVersion, Token![,]> = self
             .versions
             .into_iter()
-            .sorted_by(|v1, v2| v1.base10_digits().cmp(v2.base10_digits()))
+            .sorted_by_key(|v| v.bounds().0)
             .collect();
 
         let mut error = ErrorCatcher::new();
 
-        let mut started = false;
-        {
-            let mut iterator = versions.iter().peekable();
-            while let Some(element) = iterator.next() {
-                if let Some(other) = iterator.peek() {
-                    if element.base10_digits() == other.base10_digits() {
-                        if !started {
-                            started = true;
-                            error.add_error(Error::new(element.span(), "duplicate protocol version"))
+        let mut errored = vec![false; versions.len()];
+        for (i, element) in versions.iter().enumerate() {
+            for (j, other) in versions.iter().enumerate().skip(i + 1) {
+                if element.overlaps(other) {
+                    for (k, version) in [(i, element), (j, other)] {
+                        if !errored[k] {
+                            errored[k] = true;
+                            error.add_error(Error::new(version.span(), "duplicate protocol version"))
                         }
-                        error.add_error(Error::new(other.span(), "duplicate protocol version"))
-                    } else {
-                        started = false;
                     }
                 }
             }
@@ -139,13 +211,14 @@ impl VersionsToID {
     {
         self.versions
             .iter()
+            .filter_map(ProtocolVersion::single)
             .find(|v| v.base10_parse::<N>().ok().map(|n| n == version).unwrap_or(false))
     }
 }
 
 impl Parse for VersionsToID {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let versions = Punctuated::<LitInt, Token![,]>::parse_separated_nonempty(input)?;
+        let versions = Punctuated::<ProtocolVersion, Token![,]>::parse_separated_nonempty(input)?;
         input.parse::<Token![=]>()?;
         let id = input.parse::<LitInt>()?;
         Ok(VersionsToID { versions, id })
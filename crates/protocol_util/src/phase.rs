@@ -0,0 +1,159 @@
+use falcon_proc_util::ErrorCatcher;
+use proc_macro2::{Ident, Span};
+use syn::parse::{Parse, ParseStream};
+use syn::{Error, LitInt, Token};
+
+use crate::{PacketVersionMappings, ProtocolVersion};
+
+mod kw {
+    syn::custom_keyword!(state);
+    syn::custom_keyword!(direction);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PacketState {
+    Handshake,
+    Status,
+    Login,
+    Play,
+}
+
+impl PacketState {
+    /// The name of the matching variant of the state enums.
+    pub fn variant_name(self) -> &'static str {
+        match self {
+            PacketState::Handshake => "Handshake",
+            PacketState::Status => "Status",
+            PacketState::Login => "Login",
+            PacketState::Play => "Play",
+        }
+    }
+}
+
+impl Parse for PacketState {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse::<Ident>()?;
+        match ident.to_string().as_str() {
+            "handshake" => Ok(PacketState::Handshake),
+            "status" => Ok(PacketState::Status),
+            "login" => Ok(PacketState::Login),
+            "play" => Ok(PacketState::Play),
+            _ => Err(Error::new(ident.span(), "expected one of `handshake`, `status`, `login` or `play`")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PacketDirection {
+    Serverbound,
+    Clientbound,
+}
+
+impl PacketDirection {
+    /// The name of the matching variant of the direction enums.
+    pub fn variant_name(self) -> &'static str {
+        match self {
+            PacketDirection::Serverbound => "Serverbound",
+            PacketDirection::Clientbound => "Clientbound",
+        }
+    }
+}
+
+impl Parse for PacketDirection {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse::<Ident>()?;
+        match ident.to_string().as_str() {
+            "serverbound" => Ok(PacketDirection::Serverbound),
+            "clientbound" => Ok(PacketDirection::Clientbound),
+            _ => Err(Error::new(ident.span(), "expected either `serverbound` or `clientbound`")),
+        }
+    }
+}
+
+/// A `state = ...` or `direction = ...` argument of a packet attribute.
+#[derive(Debug)]
+pub enum PhaseAttribute {
+    State(Span, PacketState),
+    Direction(Span, PacketDirection),
+}
+
+impl PhaseAttribute {
+    pub fn peek(input: ParseStream) -> bool { input.peek(kw::state) || input.peek(kw::direction) }
+}
+
+impl Parse for PhaseAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(kw::state) {
+            let span = input.parse::<kw::state>()?.span;
+            input.parse::<Token![=]>()?;
+            Ok(Self::State(span, input.parse()?))
+        } else {
+            let span = input.parse::<kw::direction>()?.span;
+            input.parse::<Token![=]>()?;
+            Ok(Self::Direction(span, input.parse()?))
+        }
+    }
+}
+
+/// The connection state and direction a packet is sent in, both are
+/// optional. Packet ids only have to be unique within the same phase.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PacketPhase {
+    pub state: Option<PacketState>,
+    pub direction: Option<PacketDirection>,
+}
+
+impl PacketPhase {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn add_attribute(&mut self, attribute: PhaseAttribute) -> syn::Result<()> {
+        match attribute {
+            PhaseAttribute::State(span, _) if self.state.is_some() => Err(Error::new(span, "duplicate state")),
+            PhaseAttribute::State(_, state) => {
+                self.state = Some(state);
+                Ok(())
+            },
+            PhaseAttribute::Direction(span, _) if self.direction.is_some() => Err(Error::new(span, "duplicate direction")),
+            PhaseAttribute::Direction(_, direction) => {
+                self.direction = Some(direction);
+                Ok(())
+            },
+        }
+    }
+}
+
+/// Reports packet ids that are used by more than one packet for the same
+/// protocol version in the same phase.
+pub fn check_collisions<'a, I>(packets: I) -> syn::Result<()>
+where
+    I: IntoIterator<Item = (&'a Ident, PacketPhase, &'a PacketVersionMappings)>,
+{
+    let mut error = ErrorCatcher::new();
+    let mut seen: Vec<(&Ident, PacketPhase, &LitInt, Option<&ProtocolVersion>)> = Vec::new();
+
+    for (name, phase, mappings) in packets {
+        let mut entries: Vec<(&LitInt, Option<&ProtocolVersion>)> = mappings.is_exclude().map(|id| (id, None)).into_iter().collect();
+        for (id, versions) in mappings.versions() {
+            entries.extend(versions.into_iter().map(|v| (id, Some(v))));
+        }
+
+        for (id, version) in entries {
+            let collision = seen.iter().find(|(_, other_phase, other_id, other_version)| {
+                *other_phase == phase
+                    && other_id.base10_digits() == id.base10_digits()
+                    && match (version, other_version) {
+                        (Some(v1), Some(v2)) => v1.overlaps(v2),
+                        _ => true,
+                    }
+            });
+            if let Some((other, _, other_id, _)) = collision {
+                let message = format!("packet id used by both `{}` and `{}`", other, name);
+                error.add_error(Error::new(other_id.span(), &message));
+                error.add_error(Error::new(version.map(ProtocolVersion::span).unwrap_or_else(|| id.span()), message));
+            }
+            seen.push((name, phase, id, version));
+        }
+    }
+
+    error.emit()
+}
@@ -1,11 +1,20 @@
+use falcon_logic::connection::handler::PacketHandler;
+use falcon_logic::FalconConnection;
 use thiserror::Error;
 
 mod macros;
+#[cfg(test)]
+mod tests;
 
 packet_modules! {
     extern pub mod v1_8_9;
     extern pub mod v1_12_2;
+    extern pub mod v1_13;
+    extern pub mod v1_14;
+    extern pub mod v1_16;
     extern pub mod v1_9;
+    extern pub mod v1_17;
+    extern pub mod v1_19;
 }
 
 #[derive(Error, Debug)]
@@ -13,3 +22,14 @@ pub enum ReceiveError {
     #[error("The player could not be found")]
     PlayerNotFound,
 }
+
+/// Hands a packet to its handler, the name of the packet is kept by the
+/// connection for its errors.
+pub(crate) fn handle_packet<P: PacketHandler>(packet: P, connection: &mut FalconConnection) -> anyhow::Result<()> {
+    let packet_name = packet.get_name();
+    let span = tracing::trace_span!("handle_packet", %packet_name);
+    let _enter = span.enter();
+    connection.set_handled_packet(packet_name);
+    packet.handle_packet(connection)?;
+    Ok(())
+}
@@ -63,3 +63,26 @@ macro_rules! packet_modules {
         }
     }
 }
+
+/// Generates `falcon_process_packet` for the packets of one module: the
+/// enum of [`falcon_packet_core::packet!`] reads the packet for the state
+/// of the connection and every variant is handed to its handler.
+#[macro_export]
+macro_rules! packet_handlers {
+    ($packets:ident { $($packet:ident),* $(,)? }) => {
+        pub fn falcon_process_packet<B>(packet_id: i32, buffer: &mut B, connection: &mut ::falcon_logic::connection::FalconConnection) -> ::anyhow::Result<bool>
+        where
+            B: ::bytes::Buf,
+        {
+            let Some(state) = connection.state().connection_state.protocol_state() else {
+                return Ok(false);
+            };
+            let protocol_id = connection.state().protocol_id;
+            match $packets::read(state, ::falcon_packet_core::protocol::PacketDirection::Serverbound, protocol_id, packet_id, buffer)? {
+                $(Some($packets::$packet(packet)) => $crate::handle_packet(packet, connection)?,)*
+                None => return Ok(false),
+            }
+            Ok(true)
+        }
+    }
+}
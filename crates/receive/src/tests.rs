@@ -0,0 +1,212 @@
+use std::sync::Once;
+
+use bytes::Bytes;
+use falcon_client::packets::*;
+use falcon_core::network::registry::ProtocolRegistry;
+use falcon_logic::connection::mock::MockConnection;
+use falcon_logic::connection::record::Recording;
+use falcon_logic::connection::ConnectionReceiver;
+use falcon_logic::server::command::CommandSender;
+use falcon_logic::FalconConnection;
+use falcon_packet_core::{PacketSize, PacketWrite};
+use uuid::uuid;
+
+struct TestReceiver;
+
+impl ConnectionReceiver for TestReceiver {
+    fn receive(&mut self, packet_id: i32, bytes: &mut Bytes, connection: &mut FalconConnection) -> anyhow::Result<bool> {
+        crate::falcon_process_packet(packet_id, bytes, connection)
+    }
+}
+
+/// Connects to a new server, the global protocol registry aliases
+/// protocol 900 to 578 with moved status packets.
+async fn connect() -> MockConnection {
+    static REGISTRY: Once = Once::new();
+    REGISTRY.call_once(|| {
+        let mut registry = ProtocolRegistry::default();
+        registry
+            .load_toml(
+                r#"
+                [[protocols]]
+                version = 900
+                base = 578
+
+                [protocols.status]
+                serverbound = { remap = [[0x01, 0x05]] }
+                clientbound = { remap = [[0x01, 0x06]] }
+                "#,
+            )
+            .unwrap();
+        registry.init_global().unwrap();
+    });
+    MockConnection::start(TestReceiver).await
+}
+
+async fn handshake(connection: &mut MockConnection, version: i32, next_state: i32) {
+    let packet = HandshakePacket {
+        version,
+        address: String::from("localhost"),
+        port: 25565,
+        next_state,
+    };
+    connection.send_packet(0x00, &packet).await;
+}
+
+#[tokio::test]
+async fn test_status() {
+    let mut connection = connect().await;
+    handshake(&mut connection, 578, NEXT_STATE_STATUS).await;
+    connection.send_packet(0x00, &StatusRequestPacket {}).await;
+
+    let status: StatusResponsePacket = connection.expect_packet(0x00).await;
+    assert!(status.response.contains("\"protocol\":578"));
+
+    connection.send_packet(0x01, &StatusPingPacket { payload: 42 }).await;
+    let pong: StatusPongPacket = connection.expect_packet(0x01).await;
+    assert_eq!(pong.payload, 42);
+    connection.expect_closed().await;
+}
+
+/// Replays a status request captured from `falcon-client`.
+#[tokio::test]
+async fn test_replay_status() {
+    let recording = Recording::from_bytes(Bytes::from_static(include_bytes!("../captures/status_578.rec"))).unwrap();
+    let mut connection = connect().await;
+    connection.replay(&recording).await;
+    connection.expect_closed().await;
+}
+
+#[tokio::test]
+async fn test_login() {
+    let mut connection = connect().await;
+    handshake(&mut connection, 736, NEXT_STATE_LOGIN).await;
+    let login = LoginStartPacket {
+        username: String::from("Tester"),
+    };
+    connection.send_packet(0x00, &login).await;
+    connection.run_server_task().await;
+
+    let success: LoginSuccessPacket = connection.expect_packet(0x02).await;
+    assert_eq!(success.username, "Tester");
+    assert_eq!(success.uuid, uuid!("f3d28cb0-7225-3cb1-baeb-2dadd2be89ae"));
+    assert_eq!(connection.server().online_count(), 1);
+}
+
+/// The chunks around the spawn must arrive before the position of the
+/// player, the rest of the view distance follows on later ticks.
+#[tokio::test]
+async fn test_join_order() {
+    const CHUNK_DATA: i32 = 0x21;
+    const POSITION_LOOK: i32 = 0x35;
+
+    let mut connection = connect().await;
+    handshake(&mut connection, 736, NEXT_STATE_LOGIN).await;
+    let login = LoginStartPacket {
+        username: String::from("Joiner"),
+    };
+    connection.send_packet(0x00, &login).await;
+    connection.run_server_task().await;
+
+    let mut chunks = 0;
+    loop {
+        match connection.next_packet().await {
+            Some((CHUNK_DATA, _)) => chunks += 1,
+            Some((POSITION_LOOK, _)) => break,
+            Some(_) => {},
+            None => panic!("Connection closed before the position was sent"),
+        }
+    }
+    assert_eq!(chunks, 9, "The spawn chunk and its neighbours are sent before the position");
+}
+
+/// Login plugin response as sent since 1.13.
+#[derive(PacketSize, PacketWrite)]
+struct LoginPluginResponsePacket {
+    #[falcon(var32)]
+    message_id: i32,
+    successful: bool,
+}
+
+/// Bans apply to the address of the client, which is the one from the
+/// PROXY protocol header behind a proxy.
+#[tokio::test]
+async fn test_ip_ban() {
+    let mut connection = connect().await;
+    let feedback = connection.server().execute_command(CommandSender::Console, "ban-ip 127.0.0.1 spam", "en_us");
+    assert_eq!(feedback.as_deref(), Some("Banned IP 127.0.0.1: spam"));
+    handshake(&mut connection, 736, NEXT_STATE_LOGIN).await;
+    let login = LoginStartPacket {
+        username: String::from("Banned"),
+    };
+    connection.send_packet(0x00, &login).await;
+    connection.run_server_task().await;
+
+    let disconnect: LoginDisconnectPacket = connection.expect_packet(0x00).await;
+    assert!(disconnect.reason.contains("You are banned from this server: spam"));
+    assert_eq!(connection.server().online_count(), 0);
+}
+
+#[tokio::test]
+async fn test_unknown_login_query() {
+    let mut connection = connect().await;
+    handshake(&mut connection, 736, NEXT_STATE_LOGIN).await;
+    let response = LoginPluginResponsePacket {
+        message_id: 7,
+        successful: false,
+    };
+    connection.send_packet(0x02, &response).await;
+    let login = LoginStartPacket {
+        username: String::from("Queried"),
+    };
+    connection.send_packet(0x00, &login).await;
+    connection.run_server_task().await;
+
+    let success: LoginSuccessPacket = connection.expect_packet(0x02).await;
+    assert_eq!(success.username, "Queried");
+}
+
+#[tokio::test]
+async fn test_invalid_next_state() {
+    let mut connection = connect().await;
+    handshake(&mut connection, 578, 5).await;
+    let disconnect: LoginDisconnectPacket = connection.expect_packet(0x00).await;
+    assert!(disconnect.reason.contains("Impossible next state!"));
+    connection.expect_closed().await;
+}
+
+#[tokio::test]
+async fn test_unsupported_status() {
+    let mut connection = connect().await;
+    handshake(&mut connection, 800, NEXT_STATE_STATUS).await;
+    connection.send_packet(0x00, &StatusRequestPacket {}).await;
+
+    let status: StatusResponsePacket = connection.expect_packet(0x00).await;
+    assert!(status.response.contains("\"name\":\"Outdated server, still on 1.13-1.17.1\",\"protocol\":756"));
+}
+
+#[tokio::test]
+async fn test_outdated_versions() {
+    for (version, reason) in [(340, "Outdated client! Please use 1.13-1.17.1"), (800, "Outdated server! I'm still on 1.13-1.17.1")] {
+        let mut connection = connect().await;
+        handshake(&mut connection, version, NEXT_STATE_LOGIN).await;
+        let disconnect: LoginDisconnectPacket = connection.expect_packet(0x00).await;
+        assert!(disconnect.reason.contains(reason), "{}", disconnect.reason);
+        connection.expect_closed().await;
+    }
+}
+
+#[tokio::test]
+async fn test_protocol_alias() {
+    let mut connection = connect().await;
+    handshake(&mut connection, 900, NEXT_STATE_STATUS).await;
+    connection.send_packet(0x00, &StatusRequestPacket {}).await;
+
+    let status: StatusResponsePacket = connection.expect_packet(0x00).await;
+    assert!(status.response.contains("\"protocol\":900"));
+
+    connection.send_packet(0x05, &StatusPingPacket { payload: 42 }).await;
+    let pong: StatusPongPacket = connection.expect_packet(0x06).await;
+    assert_eq!(pong.payload, 42);
+    connection.expect_closed().await;
+}
@@ -1,17 +1,26 @@
-#[falcon_receive_derive::falcon_receive]
-mod inner {
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
     use std::convert::Infallible;
 
+    use bytes::Buf;
+    use falcon_core::player::data::RecipeBookType;
     use falcon_logic::{FalconConnection, connection::handler::PacketHandler};
-    use falcon_packet_core::PacketRead;
-    use mc_chat::{ChatComponent, ComponentStyle};
+    use falcon_logic::connection::DisconnectReason;
+    use falcon_packet_core::{PacketRead, ReadError, VarI32};
+
+    use crate::ReceiveError;
 
     #[derive(PacketRead)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = serverbound, versions = {
         340 = 0x0B;
         393, 401, 404 = 0x0E;
-        477, 480, 485, 490, 498, 573, 575, 578 = 0x0F;
+        477, 480, 485, 490, 498, 573, 575, 578, 755, 756 = 0x0F;
         735, 736 = 0x10;
+        759, 761 = 0x11;
+        760, 763 = 0x12;
     })]
     pub struct KeepAlivePacket {
         id: i64,
@@ -22,10 +31,9 @@ mod inner {
 
         fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Infallible> {
             if connection.state().last_keep_alive != self.id as u64 {
-                let version = connection.state().protocol_id;
-                connection.disconnect(ChatComponent::from_text("Received invalid Keep Alive id!", ComponentStyle::with_version(version.unsigned_abs())));
+                connection.disconnect(DisconnectReason::InvalidPacket(String::from("Received invalid Keep Alive id!")));
             } else {
-                connection.reset_keep_alive();
+                connection.acknowledge_keep_alive();
             }
             Ok(())
         }
@@ -34,4 +42,87 @@ mod inner {
             "Keep alive (1.12.2)"
         }
     }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        340, 393, 401, 404 = 0x02;
+        477, 480, 485, 490, 498, 573, 575, 578, 735, 736, 755, 756 = 0x03;
+    })]
+    pub struct ChatMessagePacket {
+        #[falcon(string = 256)]
+        message: String,
+    }
+
+    impl PacketHandler for ChatMessagePacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            match self.message.strip_prefix('/') {
+                Some(command) => connection.server().player_command(uuid, command.to_owned()),
+                None => connection.server().player_chat(uuid, self.message),
+            }
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Chat Message (1.12.2)"
+        }
+    }
+
+    /// The type of a `Recipe Book Data` packet with the state of the books.
+    const BOOK_STATES: i32 = 1;
+
+    /// The books of a `Recipe Book Data` packet before 1.16.2, `None` when
+    /// the client only tells which recipe it looked at.
+    pub(crate) struct LegacyBookStates<const BOOKS: usize>(Option<[(bool, bool); BOOKS]>);
+
+    impl<const BOOKS: usize> PacketRead for LegacyBookStates<BOOKS> {
+        fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+        where
+            B: Buf + ?Sized,
+            Self: Sized,
+        {
+            if VarI32::read(buffer)?.val() != BOOK_STATES {
+                return Ok(LegacyBookStates(None));
+            }
+            let mut books = [(false, false); BOOKS];
+            for book in &mut books {
+                *book = (bool::read(buffer)?, bool::read(buffer)?);
+            }
+            Ok(LegacyBookStates(Some(books)))
+        }
+    }
+
+    impl<const BOOKS: usize> LegacyBookStates<BOOKS> {
+        pub(crate) fn handle(self, connection: &mut FalconConnection) -> Result<(), ReceiveError> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            for (id, (open, filtering)) in self.0.into_iter().flatten().enumerate() {
+                if let Some(book) = RecipeBookType::from_id(id as i32) {
+                    connection.server().player_update_recipe_book(uuid, book, open, filtering);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        335, 338, 340 = 0x17;
+    })]
+    pub struct RecipeBookDataPacket {
+        books: LegacyBookStates<1>,
+    }
+
+    impl PacketHandler for RecipeBookDataPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> { self.books.handle(connection) }
+
+        fn get_name(&self) -> &'static str {
+            "Recipe Book Data (1.12.2)"
+        }
+    }
 }
+
+packet_handlers!(Packets { KeepAlivePacket, ChatMessagePacket, RecipeBookDataPacket });
@@ -0,0 +1,38 @@
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use falcon_packet_core::PacketRead;
+    use falcon_logic::connection::{FalconConnection, handler::PacketHandler};
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = login, direction = serverbound, versions = {
+        393, 401, 404, 477, 480, 485, 490, 498, 573, 575, 578, 735, 736, 755, 756, 759, 760, 761, 763 = 0x02
+    })]
+    pub struct LoginPluginResponsePacket {
+        #[falcon(var32)]
+        message_id: i32,
+        successful: bool,
+        #[falcon(rest)]
+        data: Bytes,
+    }
+
+    impl PacketHandler for LoginPluginResponsePacket {
+        type Error = Infallible;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
+            connection.answer_login_query(self.message_id, self.successful.then_some(self.data));
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Login Plugin Response (1.13)"
+        }
+    }
+}
+
+packet_handlers!(Packets { LoginPluginResponsePacket });
@@ -0,0 +1,10 @@
+use crate::packet_modules;
+
+packet_modules! {
+    type Login => {
+        pub mod login;
+    }
+    type Play => {
+        pub mod play;
+    }
+}
@@ -0,0 +1,31 @@
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
+    use falcon_logic::{FalconConnection, connection::handler::PacketHandler};
+    use falcon_packet_core::PacketRead;
+
+    use crate::ReceiveError;
+    use crate::v1_12_2::play::LegacyBookStates;
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        393, 401, 404 = 0x1B;
+    })]
+    pub struct RecipeBookDataPacket {
+        books: LegacyBookStates<2>,
+    }
+
+    impl PacketHandler for RecipeBookDataPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> { self.books.handle(connection) }
+
+        fn get_name(&self) -> &'static str {
+            "Recipe Book Data (1.13)"
+        }
+    }
+}
+
+packet_handlers!(Packets { RecipeBookDataPacket });
@@ -0,0 +1,32 @@
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
+    use falcon_logic::{FalconConnection, connection::handler::PacketHandler};
+    use falcon_packet_core::PacketRead;
+
+    use crate::ReceiveError;
+    use crate::v1_12_2::play::LegacyBookStates;
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        477, 480, 485, 490, 498, 573, 575, 578 = 0x1D;
+        735, 736 = 0x1E;
+    })]
+    pub struct RecipeBookDataPacket {
+        books: LegacyBookStates<4>,
+    }
+
+    impl PacketHandler for RecipeBookDataPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> { self.books.handle(connection) }
+
+        fn get_name(&self) -> &'static str {
+            "Recipe Book Data (1.14)"
+        }
+    }
+}
+
+packet_handlers!(Packets { RecipeBookDataPacket });
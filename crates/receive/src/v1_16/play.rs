@@ -0,0 +1,37 @@
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
+    use falcon_logic::{FalconConnection, connection::handler::PacketHandler};
+    use falcon_packet_core::PacketRead;
+
+    use crate::ReceiveError;
+    use crate::v1_8_9::play::Interaction;
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        735, 736 = 0x0E;
+        755, 756 = 0x0D;
+        759, 761 = 0x0F;
+        760, 763 = 0x10;
+    })]
+    pub struct InteractEntityPacket {
+        #[falcon(var32)]
+        target: i32,
+        interaction: Interaction<true>,
+        sneaking: bool,
+    }
+
+    impl PacketHandler for InteractEntityPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> { self.interaction.handle(connection, self.target, self.sneaking) }
+
+        fn get_name(&self) -> &'static str {
+            "Interact Entity (1.16)"
+        }
+    }
+}
+
+packet_handlers!(Packets { InteractEntityPacket });
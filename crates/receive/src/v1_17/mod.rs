@@ -0,0 +1,7 @@
+use crate::packet_modules;
+
+packet_modules! {
+    type Play => {
+        pub mod play;
+    }
+}
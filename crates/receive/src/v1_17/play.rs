@@ -0,0 +1,76 @@
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
+    use falcon_core::player::data::RecipeBookType;
+    use falcon_logic::{FalconConnection, connection::handler::PacketHandler};
+    use falcon_packet_core::PacketRead;
+
+    use crate::ReceiveError;
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        755, 756 = 0x05;
+    })]
+    pub struct ClientSettingsPacket {
+        #[falcon(string = 16)]
+        locale: String,
+        view_distance: u8,
+        #[falcon(var32)]
+        _chat_mode: i32,
+        _chat_colors: bool,
+        _skin_parts: u8,
+        #[falcon(var32)]
+        _main_hand: i32,
+        _disable_text_filtering: bool,
+    }
+
+    impl PacketHandler for ClientSettingsPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            connection.server().player_update_view_distance(uuid, self.view_distance);
+            let locale = self.locale.to_lowercase();
+            connection.set_locale(locale.clone());
+            connection.server().player_update_locale(uuid, locale);
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Client Settings (1.17)"
+        }
+    }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        755, 756 = 0x1E;
+        759 = 0x20;
+        760, 761, 763 = 0x21;
+    })]
+    pub struct SetRecipeBookStatePacket {
+        #[falcon(var32)]
+        book_id: i32,
+        open: bool,
+        filtering: bool,
+    }
+
+    impl PacketHandler for SetRecipeBookStatePacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            if let Some(book) = RecipeBookType::from_id(self.book_id) {
+                connection.server().player_update_recipe_book(uuid, book, self.open, self.filtering);
+            }
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Set Recipe Book State (1.17)"
+        }
+    }
+}
+
+packet_handlers!(Packets { ClientSettingsPacket, SetRecipeBookStatePacket });
@@ -0,0 +1,109 @@
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
+    use bytes::Bytes;
+    use falcon_logic::{FalconConnection, connection::handler::PacketHandler};
+    use falcon_packet_core::PacketRead;
+
+    use crate::ReceiveError;
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        759, 761 = 0x07;
+        760, 763 = 0x08;
+    })]
+    pub struct ClientSettingsPacket {
+        #[falcon(string = 16)]
+        locale: String,
+        view_distance: u8,
+        #[falcon(var32)]
+        _chat_mode: i32,
+        _chat_colors: bool,
+        _skin_parts: u8,
+        #[falcon(var32)]
+        _main_hand: i32,
+        _disable_text_filtering: bool,
+        _allow_server_listings: bool,
+    }
+
+    impl PacketHandler for ClientSettingsPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            connection.server().player_update_view_distance(uuid, self.view_distance);
+            let locale = self.locale.to_lowercase();
+            connection.set_locale(locale.clone());
+            connection.server().player_update_locale(uuid, locale);
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Client Settings (1.19)"
+        }
+    }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        759 = 0x04;
+        760, 761, 763 = 0x05;
+    })]
+    pub struct ChatMessagePacket {
+        #[falcon(string = 256)]
+        message: String,
+        _timestamp: i64,
+        _salt: i64,
+        /// The message signature and acknowledgements, not verified in
+        /// offline mode.
+        #[falcon(rest)]
+        _signature_data: Bytes,
+    }
+
+    impl PacketHandler for ChatMessagePacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            connection.server().player_chat(uuid, self.message);
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Chat Message (1.19)"
+        }
+    }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        759 = 0x03;
+        760, 761, 763 = 0x04;
+    })]
+    pub struct ChatCommandPacket {
+        #[falcon(string = 256)]
+        command: String,
+        _timestamp: i64,
+        _salt: i64,
+        /// The argument signatures and acknowledgements, not verified in
+        /// offline mode.
+        #[falcon(rest)]
+        _signature_data: Bytes,
+    }
+
+    impl PacketHandler for ChatCommandPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            connection.server().player_command(uuid, self.command);
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Chat Command (1.19)"
+        }
+    }
+}
+
+packet_handlers!(Packets { ClientSettingsPacket, ChatMessagePacket, ChatCommandPacket });
@@ -1,14 +1,19 @@
-#[falcon_receive_derive::falcon_receive]
-mod inner {
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
     use std::convert::Infallible;
 
     use falcon_logic::{FalconConnection, connection::handler::PacketHandler};
+    use falcon_logic::connection::DisconnectReason;
+    use falcon_logic::connection::floodgate::Floodgate;
     use falcon_packet_core::PacketRead;
-    use mc_chat::{ChatComponent, ComponentStyle};
-    use falcon_core::network::ConnectionState;
+    use falcon_core::network::{ConnectionState, VirtualHost};
+    use falcon_core::server::data::ProtocolSupport;
 
     #[derive(PacketRead)]
-    #[falcon_packet(versions = { -1 = 0x00 })]
+    #[falcon_packet(state = handshake, direction = serverbound, versions = { -1 = 0x00 })]
     pub struct HandshakePacket {
         #[falcon(var32)]
         version: i32,
@@ -27,10 +32,22 @@ mod inner {
                 1 => connection.state_mut().connection_state = ConnectionState::Status,
                 2 => connection.state_mut().connection_state = ConnectionState::Login,
                 _ => {
-                    connection.disconnect(ChatComponent::from_text("Impossible next state!", ComponentStyle::with_version(self.version.unsigned_abs())));
+                    connection.disconnect(DisconnectReason::InvalidPacket(String::from("Impossible next state!")));
+                }
+            }
+            connection.state_mut().set_protocol(self.version);
+            connection.set_virtual_host(VirtualHost::from_handshake(&self.address, self.port));
+            if connection.state().connection_state == ConnectionState::Login {
+                // Bedrock players behind Geyser, their proxy sends who they are
+                match Floodgate::global().and_then(|floodgate| floodgate.read_handshake(&self.address)) {
+                    Some(Ok(data)) => connection.set_bedrock(data),
+                    Some(Err(error)) => connection.disconnect(DisconnectReason::LoginFailed(error.to_string())),
+                    None => {},
+                }
+                if let Some(reason) = ProtocolSupport::of(self.version).kick_reason() {
+                    connection.disconnect(DisconnectReason::UnsupportedVersion(reason));
                 }
             }
-            connection.state_mut().protocol_id = self.version;
             Ok(())
         }
 
@@ -39,3 +56,5 @@ mod inner {
         }
     }
 }
+
+packet_handlers!(Packets { HandshakePacket });
@@ -1,42 +1,43 @@
-#[falcon_receive_derive::falcon_receive]
-mod inner {
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
     use std::convert::Infallible;
 
+    use bytes::Bytes;
     use falcon_packet_core::PacketRead;
-    use mc_chat::{ChatColor, ChatComponent, ComponentStyle};
     use falcon_logic::connection::{FalconConnection, handler::PacketHandler};
-    use falcon_core::server::config::FalconConfig;
+    use falcon_logic::server::auth::LoginInfo;
 
     #[derive(PacketRead)]
-    #[falcon_packet(versions = {
-        47, 393, 401, 404, 477, 480, 485, 490, 498, 573, 575, 578, 735, 736 = 0x00
+    #[falcon_packet(state = login, direction = serverbound, versions = {
+        47, 393, 401, 404, 477, 480, 485, 490, 498, 573, 575, 578, 735, 736, 755, 756, 759, 760, 761, 763 = 0x00
     })]
     pub struct LoginStartPacket {
         #[falcon(string = 16)]
         name: String,
+        /// Signature data and uuid sent since 1.19, not needed in offline
+        /// mode.
+        #[falcon(rest)]
+        _signature_data: Bytes,
     }
 
     impl PacketHandler for LoginStartPacket {
         type Error = Infallible;
 
         fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
-            let version = connection.state().protocol_id;
-
-            if !FalconConfig::ALLOWED_VERSIONS.contains(&version.unsigned_abs()) {
-                 connection.disconnect(ChatComponent::from_text(
-                     "Incompatible version",
-                     ComponentStyle::with_version(version.unsigned_abs()).color_if_absent(ChatColor::Red)
-                ));
-            } 
-            if FalconConfig::global().versions.excluded.contains(&version.unsigned_abs()) {
-                connection.disconnect(ChatComponent::from_text(
-                    "Disabled version",
-                    ComponentStyle::with_version(version.unsigned_abs()).color_if_absent(ChatColor::Red)
-                ));
-            } else {
-                let wrapper = connection.wrapper();
-                connection.server().player_login(self.name, version, wrapper);
-            }
+            // the login continues once the login queries were answered
+            connection.start_login_queries(move |connection| {
+                // unsupported versions are disconnected during the handshake
+                let login = LoginInfo {
+                    address: connection.address().ip(),
+                    host: connection.virtual_host().cloned(),
+                    protocol: connection.state().protocol_id,
+                    bedrock: connection.bedrock().cloned(),
+                };
+                connection.server().player_login(self.name, login, connection.wrapper());
+            });
             Ok(())
         }
 
@@ -45,3 +46,5 @@ mod inner {
         }
     }
 }
+
+packet_handlers!(Packets { LoginStartPacket });
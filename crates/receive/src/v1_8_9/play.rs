@@ -1,20 +1,27 @@
-#[falcon_receive_derive::falcon_receive]
-mod inner {
-    use falcon_core::player::data::Position;
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
+    use bytes::Buf;
+    use falcon_core::player::data::{Hand, Position};
+    use falcon_core::world::entity::EntityInteraction;
     use falcon_logic::{FalconConnection, connection::handler::PacketHandler};
-    use falcon_packet_core::PacketRead;
+    use falcon_packet_core::{PacketRead, ReadError, VarI32};
 
     use crate::ReceiveError;
 
     #[derive(PacketRead)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = serverbound, versions = {
         47 = 0x04;
         107, 108, 109, 110, 210, 315, 316 = 0x0C;
         335 = 0x0E;
         338, 340 = 0x0D;
         393, 401, 404 = 0x10;
-        477, 480, 485, 490, 498, 573, 575, 578 = 0x11;
+        477, 480, 485, 490, 498, 573, 575, 578, 755, 756 = 0x11;
         735, 736 = 0x12;
+        759, 761 = 0x13;
+        760, 763 = 0x14;
     })]
     pub struct PlayerPositionPacket {
         x: f64,
@@ -24,14 +31,16 @@ mod inner {
     }
 
     #[derive(PacketRead)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = serverbound, versions = {
         47 = 0x05;
         107, 108, 109, 110, 210, 315, 316 = 0x0E;
         335 = 0x10;
         338, 340 = 0x0F;
         393, 401, 404 = 0x12;
-        477, 480, 485, 490, 498, 573, 575, 578 = 0x13;
+        477, 480, 485, 490, 498, 573, 575, 578, 755, 756 = 0x13;
         735, 736 = 0x14;
+        759, 761 = 0x15;
+        760, 763 = 0x16;
     })]
     pub struct PlayerLookPacket {
         yaw: f32,
@@ -40,14 +49,16 @@ mod inner {
     }
 
     #[derive(PacketRead)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = serverbound, versions = {
         47 = 0x06;
         107, 108, 109, 110, 210, 315, 316 = 0x0D;
         335 = 0x0F;
         338, 340 = 0x0E;
         393, 401, 404 = 0x11;
-        477, 480, 485, 490, 498, 573, 575, 578 = 0x12;
+        477, 480, 485, 490, 498, 573, 575, 578, 755, 756 = 0x12;
         735, 736 = 0x13;
+        759, 761 = 0x14;
+        760, 763 = 0x15;
     })]
     pub struct PositionLookPacket {
         x: f64,
@@ -58,6 +69,19 @@ mod inner {
         on_ground: bool,
     }
 
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        47 = 0x16;
+        107, 108, 109, 110, 210, 315, 316, 338, 340, 393, 401, 404 = 0x03;
+        335, 477, 480, 485, 490, 498, 573, 575, 578, 735, 736, 755, 756 = 0x04;
+        759, 761 = 0x06;
+        760, 763 = 0x07;
+    })]
+    pub struct ClientStatusPacket {
+        #[falcon(var32)]
+        action: i32,
+    }
+
     impl PacketHandler for PlayerPositionPacket {
         type Error = ReceiveError;
 
@@ -99,4 +123,137 @@ mod inner {
             "Position And Look (1.8.9)"
         }
     }
+
+    /// Action 0 respawns after death, action 1 requests the statistics.
+    const PERFORM_RESPAWN: i32 = 0;
+    const REQUEST_STATISTICS: i32 = 1;
+
+    impl PacketHandler for ClientStatusPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            match self.action {
+                PERFORM_RESPAWN => connection.server().player_respawn(uuid),
+                REQUEST_STATISTICS => connection.server().player_request_statistics(uuid),
+                _ => {},
+            }
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Client Status (1.8.9)"
+        }
+    }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        47 = 0x0A;
+    })]
+    pub struct AnimationPacket {}
+
+    impl PacketHandler for AnimationPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            connection.server().player_swing_arm(uuid, false);
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Animation (1.8.9)"
+        }
+    }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        47 = 0x0B;
+    })]
+    pub struct EntityActionPacket {
+        #[falcon(var32)]
+        _entity_id: i32,
+        action: u8,
+        #[falcon(var32)]
+        _jump_boost: i32,
+    }
+
+    /// Updates the sneaking or sprinting of a player, other actions like
+    /// leaving a bed are ignored.
+    pub(crate) fn entity_action(connection: &mut FalconConnection, action: i32) -> Result<(), ReceiveError> {
+        let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+        match action {
+            0 => connection.server().player_set_sneaking(uuid, true),
+            1 => connection.server().player_set_sneaking(uuid, false),
+            3 => connection.server().player_set_sprinting(uuid, true),
+            4 => connection.server().player_set_sprinting(uuid, false),
+            _ => {},
+        }
+        Ok(())
+    }
+
+    impl PacketHandler for EntityActionPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> { entity_action(connection, self.action as i32) }
+
+        fn get_name(&self) -> &'static str {
+            "Entity Action (1.8.9)"
+        }
+    }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        47 = 0x02;
+    })]
+    pub struct UseEntityPacket {
+        #[falcon(var32)]
+        target: i32,
+        interaction: Interaction<false>,
+    }
+
+    /// The type of an interaction, followed by the point of an interact at
+    /// and since 1.9 the hand of anything but an attack.
+    pub(crate) struct Interaction<const HAND: bool>(Option<EntityInteraction>);
+
+    impl<const HAND: bool> PacketRead for Interaction<HAND> {
+        fn read<B>(buffer: &mut B) -> Result<Self, ReadError>
+        where
+            B: Buf + ?Sized,
+            Self: Sized,
+        {
+            let kind = VarI32::read(buffer)?.val();
+            let point = if kind == 2 { Some((f32::read(buffer)?, f32::read(buffer)?, f32::read(buffer)?)) } else { None };
+            let hand = if HAND && kind != 1 { Hand::from_id(VarI32::read(buffer)?.val()) } else { Some(Hand::Main) };
+            Ok(Interaction(match (kind, hand, point) {
+                (0, Some(hand), _) => Some(EntityInteraction::Interact(hand)),
+                (1, ..) => Some(EntityInteraction::Attack),
+                (2, Some(hand), Some((x, y, z))) => Some(EntityInteraction::InteractAt(hand, x, y, z)),
+                _ => None,
+            }))
+        }
+    }
+
+    impl<const HAND: bool> Interaction<HAND> {
+        /// Unknown interactions are ignored.
+        pub(crate) fn handle(self, connection: &mut FalconConnection, target: i32, sneaking: bool) -> Result<(), ReceiveError> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            if let Some(interaction) = self.0 {
+                connection.server().player_interact_entity(uuid, target, interaction, sneaking);
+            }
+            Ok(())
+        }
+    }
+
+    impl PacketHandler for UseEntityPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> { self.interaction.handle(connection, self.target, false) }
+
+        fn get_name(&self) -> &'static str {
+            "Use Entity (1.8.9)"
+        }
+    }
 }
+
+packet_handlers!(Packets { PlayerPositionPacket, PlayerLookPacket, PositionLookPacket, ClientStatusPacket, AnimationPacket, EntityActionPacket, UseEntityPacket });
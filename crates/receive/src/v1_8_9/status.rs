@@ -1,5 +1,8 @@
-#[falcon_receive_derive::falcon_receive]
-mod inner {
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
     use std::convert::Infallible;
 
     use falcon_logic::{FalconConnection, connection::handler::PacketHandler};
@@ -8,11 +11,11 @@ mod inner {
     use falcon_core::network::ConnectionState;
 
     #[derive(PacketRead)]
-    #[falcon_packet(versions = { -1 = 0x00 })]
+    #[falcon_packet(state = status, direction = serverbound, versions = { -1 = 0x00 })]
     pub struct StatusRequestPacket {}
 
     #[derive(PacketRead)]
-    #[falcon_packet(versions = { -1 = 0x01 })]
+    #[falcon_packet(state = status, direction = serverbound, versions = { -1 = 0x01 })]
     pub struct StatusPingPacket {
         payload: i64,
     }
@@ -22,9 +25,7 @@ mod inner {
 
         fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Infallible> {
             trace!("Status requested");
-            let version = connection.state().protocol_id;
-            let wrapper = connection.wrapper();
-            connection.server().request_status(version, wrapper);
+            connection.send_status();
             Ok(())
         }
 
@@ -48,3 +49,5 @@ mod inner {
         }
     }
 }
+
+packet_handlers!(Packets { StatusRequestPacket, StatusPingPacket });
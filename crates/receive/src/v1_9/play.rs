@@ -1,18 +1,22 @@
-#[falcon_receive_derive::falcon_receive]
-mod inner {
+use crate::packet_handlers;
+
+falcon_packet_core::packet! {
+    pub enum Packets;
+
     use falcon_logic::{FalconConnection, connection::handler::PacketHandler};
     use falcon_packet_core::PacketRead;
 
+    use crate::v1_8_9::play::{entity_action, Interaction};
     use crate::ReceiveError;
 
     #[derive(PacketRead)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = serverbound, versions = {
         107, 108, 109, 110, 210, 315, 316, 393, 338, 340, 401, 404 = 0x04;
         335, 477, 480, 485, 490, 498, 573, 575, 578, 735, 736 = 0x05;
     })]
     pub struct ClientSettingsPacket {
         #[falcon(string = 16)]
-        _locale: String,
+        locale: String,
         view_distance: u8,
         #[falcon(var32)]
         _chat_mode: i32,
@@ -28,6 +32,9 @@ mod inner {
         fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
             let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
             connection.server().player_update_view_distance(uuid, self.view_distance);
+            let locale = self.locale.to_lowercase();
+            connection.set_locale(locale.clone());
+            connection.server().player_update_locale(uuid, locale);
             Ok(())
         }
 
@@ -35,4 +42,88 @@ mod inner {
             "Client Settings (1.9)"
         }
     }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        107, 108, 109, 110, 210, 315, 316 = 0x1A;
+        335, 338, 340 = 0x1D;
+        393, 401, 404 = 0x27;
+        477, 480, 485, 490, 498, 573, 575, 578 = 0x2A;
+        735, 736 = 0x2B;
+        755, 756 = 0x2C;
+        759 = 0x2E;
+        760, 761, 763 = 0x2F;
+    })]
+    pub struct AnimationPacket {
+        #[falcon(var32)]
+        hand: i32,
+    }
+
+    impl PacketHandler for AnimationPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> {
+            let uuid = connection.state().uuid.ok_or(ReceiveError::PlayerNotFound)?;
+            connection.server().player_swing_arm(uuid, self.hand == 1);
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Animation (1.9)"
+        }
+    }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        107, 108, 109, 110, 210, 315, 316 = 0x14;
+        335, 338, 340 = 0x15;
+        393, 401, 404 = 0x19;
+        477, 480, 485, 490, 498, 573, 575, 578, 755, 756 = 0x1B;
+        735, 736 = 0x1C;
+        759, 761 = 0x1D;
+        760, 763 = 0x1E;
+    })]
+    pub struct EntityActionPacket {
+        #[falcon(var32)]
+        _entity_id: i32,
+        #[falcon(var32)]
+        action: i32,
+        #[falcon(var32)]
+        _jump_boost: i32,
+    }
+
+    impl PacketHandler for EntityActionPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> { entity_action(connection, self.action) }
+
+        fn get_name(&self) -> &'static str {
+            "Entity Action (1.9)"
+        }
+    }
+
+    #[derive(PacketRead)]
+    #[falcon_packet(state = play, direction = serverbound, versions = {
+        107, 108, 109, 110, 210, 315, 316, 338, 340 = 0x0A;
+        335 = 0x0B;
+        393, 401, 404 = 0x0D;
+        477, 480, 485, 490, 498, 573, 575, 578 = 0x0E;
+    })]
+    pub struct UseEntityPacket {
+        #[falcon(var32)]
+        target: i32,
+        interaction: Interaction<true>,
+    }
+
+    impl PacketHandler for UseEntityPacket {
+        type Error = ReceiveError;
+
+        fn handle_packet(self, connection: &mut FalconConnection) -> Result<(), Self::Error> { self.interaction.handle(connection, self.target, false) }
+
+        fn get_name(&self) -> &'static str {
+            "Use Entity (1.9)"
+        }
+    }
 }
+
+packet_handlers!(Packets { ClientSettingsPacket, AnimationPacket, EntityActionPacket, UseEntityPacket });
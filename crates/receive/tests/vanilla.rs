@@ -0,0 +1,246 @@
+//! Drives a server on an ephemeral port of the loopback interface with the
+//! bytes vanilla clients sent, see `tests/corpus/README.md` on capturing
+//! them.
+//!
+//! Every recording in the corpus is replayed, the handshake at its start
+//! tells the protocol and whether it pings the server list or logs in.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::{Bytes, BytesMut};
+use falcon_client::packets::*;
+use falcon_core::server::config::FalconConfig;
+use falcon_core::ShutdownHandle;
+use falcon_logic::connection::dump::Direction;
+use falcon_logic::connection::record::Recording;
+use falcon_logic::connection::ConnectionReceiver;
+use falcon_logic::server::status::ServerStatus;
+use falcon_logic::server::ServerWrapper;
+use falcon_logic::{FalconConnection, FalconServer, FalconWorld};
+use falcon_packet_core::special::{read_frame_async, MAX_FRAME_LENGTH};
+use falcon_packet_core::{PacketRead, PacketWrite, VarI32};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::unbounded_channel;
+use tokio::time::timeout;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Packets the server may send between two recorded packets, e.g. the
+/// packets of the join that depend on the config.
+const MAX_SKIPPED: usize = 64;
+/// The first protocol that sends the uuid of the login success as two
+/// longs, 1.16.
+const BINARY_UUID_PROTOCOL: i32 = 735;
+
+struct FalconReceiver;
+
+impl ConnectionReceiver for FalconReceiver {
+    fn receive(&mut self, packet_id: i32, bytes: &mut Bytes, connection: &mut FalconConnection) -> anyhow::Result<bool> {
+        falcon_receive::falcon_process_packet(packet_id, bytes, connection)
+    }
+}
+
+/// A server with an empty world that accepts connections like the network
+/// listener of the server does.
+struct TestServer {
+    address: SocketAddr,
+    status: Arc<ServerStatus>,
+    shutdown: ShutdownHandle,
+}
+
+impl TestServer {
+    async fn start() -> Self {
+        let mut config = FalconConfig::default();
+        // every recording logs in from the same address
+        config.connection.login_throttle = 0;
+        config.players.statistics_dir = std::env::temp_dir().join("falcon-vanilla-stats").to_string_lossy().into_owned();
+        FalconConfig::init_with(config);
+
+        let (shutdown, _) = ShutdownHandle::new();
+        let (_, console_rx) = unbounded_channel();
+        let (server_tx, server_rx) = unbounded_channel();
+        let mut server = FalconServer::new(shutdown.clone(), console_rx, server_rx, FalconWorld::new(0, 0, 0, 0, 0));
+        let status = server.status().clone();
+        let wrapper = ServerWrapper::new(server_tx, status.clone());
+        std::thread::spawn(move || server.start());
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.expect("Could not bind to an ephemeral port");
+        let address = listener.local_addr().unwrap();
+        let mut accept_shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = accept_shutdown.wait_for_shutdown() => break,
+                    Ok((socket, addr)) = listener.accept() => {
+                        let connection = FalconConnection::new(accept_shutdown.clone(), addr, wrapper.clone()).await;
+                        tokio::spawn(connection.start(socket, FalconReceiver));
+                    }
+                }
+            }
+        });
+        TestServer { address, status, shutdown }
+    }
+
+    async fn connect(&self) -> TcpStream { TcpStream::connect(self.address).await.expect("Could not connect to the server") }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) { self.shutdown.send_shutdown(); }
+}
+
+/// A recording of the corpus and the handshake it starts with.
+struct Capture {
+    name: String,
+    recording: Recording,
+    handshake: HandshakePacket,
+}
+
+impl Capture {
+    /// The inbound packets of the recording after the handshake.
+    fn inbound(&self) -> impl Iterator<Item = Bytes> + '_ {
+        self.recording
+            .packets()
+            .iter()
+            .filter(|packet| packet.direction == Direction::Inbound)
+            .skip(1)
+            .map(|packet| packet.data.clone())
+    }
+}
+
+/// The recordings of the corpus that use `next_state`, sorted by name.
+fn corpus(next_state: i32) -> Vec<Capture> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "rec"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let recording = Recording::open(&path).unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+            let mut first = recording.packets().first().unwrap_or_else(|| panic!("{} is empty", path.display())).data.clone();
+            let id = VarI32::read(&mut first).expect("Invalid recorded packet id").val();
+            assert_eq!(id, 0x00, "{} does not start with a handshake", path.display());
+            let handshake = HandshakePacket::read(&mut first).unwrap_or_else(|e| panic!("Invalid handshake in {}: {}", path.display(), e));
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            Capture { name, recording, handshake }
+        })
+        .filter(|capture| capture.handshake.next_state == next_state)
+        .collect()
+}
+
+/// The captures of `next_state`, the corpus may not be empty.
+fn captures(next_state: i32) -> Vec<Capture> {
+    let captures = corpus(next_state);
+    assert!(!captures.is_empty(), "No captures with next state {} in tests/corpus, see tests/corpus/README.md", next_state);
+    captures
+}
+
+/// The id and content of the next packet the server sent, `None` once the
+/// connection is closed.
+async fn next_packet(socket: &mut TcpStream) -> Option<(i32, Bytes)> {
+    let frame = timeout(TIMEOUT, read_frame_async(socket, MAX_FRAME_LENGTH))
+        .await
+        .expect("Timed out waiting for a packet")
+        .expect("Invalid packet frame");
+    frame.map(|mut frame| (VarI32::read(&mut frame).expect("Invalid packet id").val(), frame))
+}
+
+/// Sends the inbound packets of `recording` and asserts that the server
+/// sends the outbound packets in the same order, returns the content of
+/// the outbound packets.
+async fn replay(socket: &mut TcpStream, recording: &Recording, name: &str) -> Vec<Bytes> {
+    let mut received = Vec::new();
+    for (i, packet) in recording.packets().iter().enumerate() {
+        match packet.direction {
+            Direction::Inbound => {
+                let mut buffer = BytesMut::with_capacity(packet.data.len() + 3);
+                VarI32::from(packet.data.len()).write(&mut buffer).unwrap();
+                buffer.extend_from_slice(&packet.data);
+                socket.write_all(&buffer).await.expect("Connection closed while sending");
+            },
+            Direction::Outbound => {
+                let expected = VarI32::read(&mut packet.data.clone()).expect("Invalid recorded packet id").val();
+                let mut skipped = 0;
+                loop {
+                    match next_packet(socket).await {
+                        Some((id, data)) if id == expected => {
+                            received.push(data);
+                            break;
+                        },
+                        Some((id, _)) => {
+                            skipped += 1;
+                            assert!(skipped <= MAX_SKIPPED, "{} packet {}: expected {:#04X}, last received {:#04X}", name, i, expected, id);
+                        },
+                        None => panic!("{} packet {}: connection closed while expecting {:#04X}", name, i, expected),
+                    }
+                }
+            },
+        }
+    }
+    received
+}
+
+/// Every protocol the server allows has a status and a login capture.
+#[test]
+fn test_corpus_coverage() {
+    for (next_state, kind) in [(NEXT_STATE_STATUS, "status"), (NEXT_STATE_LOGIN, "login")] {
+        let protocols: Vec<i32> = corpus(next_state).iter().map(|capture| capture.handshake.version).collect();
+        let missing: Vec<u32> = FalconConfig::ALLOWED_VERSIONS.into_iter().filter(|protocol| !protocols.contains(&(*protocol as i32))).collect();
+        assert!(missing.is_empty(), "No {} captures of the protocols {:?} in tests/corpus, see tests/corpus/README.md", kind, missing);
+    }
+}
+
+#[tokio::test]
+async fn test_status_corpus() {
+    let server = TestServer::start().await;
+    for capture in captures(NEXT_STATE_STATUS) {
+        let (name, protocol) = (&capture.name, capture.handshake.version);
+        let mut socket = server.connect().await;
+        let received = replay(&mut socket, &capture.recording, name).await;
+
+        let status = StatusResponsePacket::read(&mut received[0].clone()).unwrap();
+        assert!(status.response.contains(&format!("\"protocol\":{}", protocol)), "{}: {}", name, status.response);
+        let mut ping = capture.inbound().nth(1).unwrap_or_else(|| panic!("{}: no ping", name));
+        VarI32::read(&mut ping).unwrap();
+        let ping = StatusPingPacket::read(&mut ping).unwrap();
+        let pong = StatusPongPacket::read(&mut received[1].clone()).unwrap();
+        assert_eq!(pong.payload, ping.payload, "{}", name);
+        assert!(next_packet(&mut socket).await.is_none(), "{}: the connection stays open after the pong", name);
+    }
+}
+
+#[tokio::test]
+async fn test_login_corpus() {
+    let server = TestServer::start().await;
+    for capture in captures(NEXT_STATE_LOGIN) {
+        let (name, protocol) = (&capture.name, capture.handshake.version);
+        let mut login = capture.inbound().next().unwrap_or_else(|| panic!("{}: no login start", name));
+        VarI32::read(&mut login).unwrap();
+        let login = LoginStartPacket::read(&mut login).unwrap();
+        let mut socket = server.connect().await;
+        let received = replay(&mut socket, &capture.recording, name).await;
+
+        // older versions send the uuid as a string
+        let username = if protocol >= BINARY_UUID_PROTOCOL {
+            LoginSuccessPacket::read(&mut received[0].clone()).unwrap().username
+        } else {
+            LoginSuccessStrPacket::read(&mut received[0].clone()).unwrap().username
+        };
+        assert_eq!(username, login.username, "{}", name);
+        assert_eq!(server.status.online(), 1, "{}", name);
+
+        drop(socket);
+        let left = async {
+            while server.status.online() != 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        timeout(TIMEOUT, left).await.unwrap_or_else(|_| panic!("{}: the player did not leave", name));
+    }
+}
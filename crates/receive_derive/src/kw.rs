@@ -1 +0,0 @@
-syn::custom_keyword!(versions);
@@ -0,0 +1,97 @@
+//! Resolves outgoing packets through the
+//! [`ProtocolRegistry`](falcon_core::network::registry::ProtocolRegistry).
+//!
+//! Every spec lists the [`Layout`]s it can be written as, the packets of
+//! [`packet!`](falcon_packet_core::packet) with their ids. A client is
+//! served the layout of the version it is handled as, versions of the
+//! registry with the ids of their table. A version that only moves ids
+//! needs no layout of its own.
+
+use falcon_core::network::registry::{ProtocolRegistry, ProtocolTable};
+use falcon_packet_core::protocol::{PacketId, ProtocolState};
+use falcon_packet_core::special::{write_packet, PacketPrepare};
+use falcon_packet_core::{PacketWrite, WriteError};
+
+/// A packet a spec of type `T` can be written as.
+pub struct Layout<T, B> {
+    packet_id: fn(i32) -> Option<i32>,
+    state: ProtocolState,
+    write: fn(T, i32, &mut B) -> Result<(), WriteError>,
+}
+
+impl<T, B> Layout<T, B>
+where
+    B: PacketPrepare,
+{
+    pub fn of<P>() -> Self
+    where
+        P: PacketId + PacketWrite + From<T>,
+    {
+        Layout {
+            packet_id: P::packet_id,
+            state: P::STATE,
+            write: |packet, packet_id, buffer| write_packet(buffer, packet_id, &P::from(packet)),
+        }
+    }
+}
+
+/// The first of `layouts` that exists in the version a client of `protocol`
+/// is handled as, together with the id to send it with.
+pub fn resolve<'a, T, B>(registry: &ProtocolRegistry, layouts: &'a [Layout<T, B>], protocol: i32) -> Option<(&'a Layout<T, B>, i32)> {
+    let table = registry.get(protocol);
+    let base = table.map_or(protocol, ProtocolTable::base);
+    layouts.iter().find_map(|layout| {
+        let packet_id = (layout.packet_id)(base)?;
+        Some((layout, table.map_or(packet_id, |table| table.state_ids(layout.state).clientbound.to_wire(packet_id))))
+    })
+}
+
+/// Writes `packet` for a client of `protocol`, nothing is written if none
+/// of `layouts` exists in its version.
+pub fn write<T, B>(packet: T, buffer: &mut B, protocol: i32, layouts: &[Layout<T, B>]) -> Result<bool, WriteError> {
+    match resolve(ProtocolRegistry::global(), layouts, protocol) {
+        Some((layout, packet_id)) => (layout.write)(packet, packet_id, buffer).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::BytesMut;
+    use falcon_core::network::registry::ProtocolRegistry;
+
+    use super::{resolve, Layout};
+    use crate::specs::play::StatisticsSpec;
+    use crate::{v1_13, v1_8_9};
+
+    #[test]
+    fn test_resolve() {
+        let mut registry = ProtocolRegistry::default();
+        registry
+            .load_toml(
+                r#"
+                [[protocols]]
+                version = 900
+                base = 736
+
+                [protocols.play.clientbound]
+                shift = [{ from = 0x05, by = 2 }]
+
+                [[protocols]]
+                version = 901
+                base = 900
+                "#,
+            )
+            .unwrap();
+        let layouts: [Layout<StatisticsSpec, BytesMut>; 2] = [Layout::of::<v1_8_9::play::StatisticsPacket>(), Layout::of::<v1_13::play::StatisticsPacket>()];
+        let resolve = |protocol| resolve(&registry, &layouts, protocol).map(|(layout, id)| (std::ptr::eq(layout, &layouts[0]), id));
+
+        assert_eq!(resolve(47), Some((true, 0x37)));
+        assert_eq!(resolve(404), Some((false, 0x07)));
+        assert_eq!(resolve(736), Some((false, 0x06)));
+        // the layout of the base with the ids of the table
+        assert_eq!(resolve(900), Some((false, 0x08)));
+        assert_eq!(resolve(901), Some((false, 0x08)));
+        assert_eq!(resolve(12), None);
+    }
+}
@@ -1,9 +1,14 @@
-use mc_chat::ChatComponent;
+use falcon_core::chat::ChatComponent;
+use falcon_core::world::entity::EntityAnimation;
+use layout::Layout;
 use specs::login::*;
 use specs::play::*;
 use specs::status::*;
+use uuid::Uuid;
 
+pub mod layout;
 pub mod macros;
+mod section_cache;
 pub mod specs;
 pub mod util;
 pub mod v1_12_2;
@@ -11,6 +16,11 @@ pub mod v1_13;
 pub mod v1_14;
 pub mod v1_15;
 pub mod v1_16;
+pub mod v1_17;
+pub mod v1_19;
+pub mod v1_19_1;
+pub mod v1_19_3;
+pub mod v1_20;
 pub mod v1_8_9;
 pub mod v1_9;
 pub mod v1_9_1;
@@ -18,59 +28,200 @@ pub mod v1_9_1;
 // Status packets
 packet_write_fn! {
     StatusResponseSpec => write_status_response {
-        mod v1_8_9::status::status_response;
+        v1_8_9::status::StatusResponsePacket;
     }
     i64 => write_status_pong {
-        mod v1_8_9::status::status_pong;
+        v1_8_9::status::StatusPongPacket;
     }
 }
 
 // Login packets
 packet_write_fn! {
     ChatComponent => write_login_disconnect {
-        mod v1_8_9::login::disconnect;
+        v1_8_9::login::DisconnectPacket;
+    }
+    i32 => write_set_compression {
+        v1_8_9::login::SetCompressionPacket;
     }
     LoginSuccessSpec => write_login_success {
-        mod v1_8_9::login::login_success;
-        mod v1_16::login::login_success;
+        v1_8_9::login::LoginSuccess;
+        v1_16::login::LoginSuccessPacket;
+        v1_19::login::LoginSuccessPacket;
+    }
+    LoginPluginRequestSpec => write_login_plugin_request {
+        v1_13::login::LoginPluginRequestPacket;
     }
 }
 
 // Play packets
 packet_write_fn! {
     ChatComponent => write_play_disconnect {
-        mod v1_8_9::play::disconnect;
+        v1_8_9::play::DisconnectPacket;
     }
     JoinGameSpec => write_join_game {
-        mod v1_8_9::play::join_game;
-        mod v1_9_1::play::join_game;
-        mod v1_14::play::join_game;
-        mod v1_15::play::join_game;
-        mod v1_16::play::join_game;
+        v1_8_9::play::JoinGamePacket;
+        v1_9_1::play::JoinGamePacket;
+        v1_14::play::JoinGamePacket;
+        v1_15::play::JoinGamePacket;
+        v1_16::play::JoinGamePacket;
+        v1_17::play::JoinGamePacket;
+        v1_19::play::JoinGamePacket;
+        v1_19_1::play::JoinGamePacket;
+        v1_20::play::JoinGamePacket;
     }
     ServerDifficultySpec => write_server_difficulty {
-        mod v1_8_9::play::difficulty;
-        mod v1_14::play::difficulty;
+        v1_8_9::play::ServerDifficultyPacket;
+        v1_14::play::ServerDifficultyPacket;
+    }
+    RespawnSpec => write_respawn {
+        v1_8_9::play::RespawnPacket;
+        v1_14::play::RespawnPacket;
+        v1_15::play::RespawnPacket;
+        v1_16::play::RespawnPacket;
+        v1_17::play::RespawnPacket;
+        v1_19::play::RespawnPacket;
+        v1_20::play::RespawnPacket;
+    }
+    UpdateHealthSpec => write_update_health {
+        v1_8_9::play::UpdateHealthPacket;
+    }
+    SpawnPositionSpec => write_spawn_position {
+        v1_8_9::play::SpawnPositionPacket;
+        v1_14::play::SpawnPositionPacket;
+        v1_17::play::SpawnPositionPacket;
     }
     PlayerAbilitiesSpec => write_player_abilities {
-        mod v1_8_9::play::player_abilities;
+        v1_8_9::play::PlayerAbilityPacket;
+    }
+    ChangeGameStateSpec => write_change_game_state {
+        v1_8_9::play::ChangeGameStatePacket;
+    }
+    EntityStatusSpec => write_entity_status {
+        v1_8_9::play::EntityStatusPacket;
+    }
+    ChatComponent => write_system_chat {
+        v1_8_9::play::SystemChatPacket;
+        v1_16::play::SystemChatPacket;
+        v1_19::play::SystemChatPacket;
+        v1_19_1::play::SystemChatPacket;
+    }
+    DeclareCommandsSpec => write_declare_commands {
+        v1_13::play::DeclareCommandsPacket;
+        v1_19::play::DeclareCommandsPacket;
+    }
+    AdvancementsSpec => write_advancements {
+        v1_12_2::play::AdvancementsPacket;
+    }
+    DeclareRecipesSpec => write_declare_recipes {
+        v1_13::play::DeclareRecipesPacket;
+    }
+    UnlockRecipesSpec => write_unlock_recipes {
+        v1_12_2::play::UnlockRecipesPacket;
+    }
+    TagsSpec => write_tags {
+        v1_13::play::TagsPacket;
+        v1_17::play::TagsPacket;
+    }
+    StatisticsSpec => write_statistics {
+        v1_8_9::play::StatisticsPacket;
+        v1_13::play::StatisticsPacket;
+    }
+    () => write_bundle_delimiter {
+        v1_20::play::BundleDelimiterPacket;
     }
     i64 => write_keep_alive {
-        mod v1_12_2::play::keep_alive;
+        v1_12_2::play::KeepAlivePacket;
     }
     PositionAndLookSpec => write_position_look {
-        mod v1_9::play::position_look;
+        v1_9::play::PositionLookPacket;
+        v1_17::play::PositionLookPacket;
     }
     ChunkDataSpec => write_chunk_data {
-        mod v1_13::play::chunk_data;
-        mod v1_14::play::chunk_data;
-        mod v1_15::play::chunk_data;
-        mod v1_16::play::chunk_data;
+        v1_13::play::ChunkDataPacket;
+        v1_14::play::ChunkDataPacket;
+        v1_15::play::ChunkDataPacket;
+        v1_16::play::ChunkDataPacket;
+        v1_17::play::ChunkDataPacket;
+        v1_19::play::ChunkDataPacket;
+        v1_20::play::ChunkDataPacket;
+    }
+    BlockChangeSpec => write_block_change {
+        v1_13::play::BlockChangePacket;
+        v1_14::play::BlockChangePacket;
+    }
+    LightUpdateSpec => write_update_light {
+        v1_14::play::UpdateLightPacket;
+        v1_16::play::UpdateLightPacket;
+        v1_17::play::UpdateLightPacket;
     }
     (i32, i32) => write_unload_chunk {
-        mod v1_9::play::unload_chunk;
+        v1_9::play::UnloadChunkPacket;
     }
     (i32, i32) => write_update_viewpos {
-        mod v1_14::play::update_viewpos;
+        v1_14::play::UpdateViewPosition;
+    }
+    PlayerInfoSpec => write_player_info {
+        v1_8_9::play::PlayerInfoPacket;
+        v1_19::play::PlayerInfoPacket;
+        v1_19_3::play::PlayerInfoUpdatePacket;
+    }
+    EntityMetadataSpec => write_entity_metadata {
+        v1_8_9::play::EntityMetadataPacket;
+    }
+    SpawnPlayerSpec => write_spawn_player {
+        v1_8_9::play::SpawnPlayerPacket;
+        v1_9::play::SpawnPlayerPacket;
+        v1_15::play::SpawnPlayerPacket;
+    }
+    EntityTeleportSpec => write_entity_teleport {
+        v1_8_9::play::EntityTeleportPacket;
+        v1_9::play::EntityTeleportPacket;
+    }
+    (i32, f32) => write_entity_head_look {
+        v1_8_9::play::EntityHeadLookPacket;
+    }
+    EntityVelocitySpec => write_entity_velocity {
+        v1_8_9::play::EntityVelocityPacket;
     }
+    i32 => write_destroy_entity {
+        v1_8_9::play::DestroyEntitiesPacket;
+        v1_17::play::DestroyEntityPacket;
+    }
+    Vec<Uuid> => write_player_info_remove {
+        v1_8_9::play::PlayerInfoPacket;
+        v1_19::play::PlayerInfoPacket;
+        v1_19_3::play::PlayerInfoRemovePacket;
+    }
+}
+
+/// Spawns a non-player entity, nothing is written if the version does not
+/// have its type. Living entities have a packet of their own from 1.14 to
+/// 1.18.
+pub fn write_spawn_entity<B>(spec: SpawnEntitySpec, buffer: &mut B, protocol: i32) -> Result<bool, falcon_packet_core::WriteError>
+where
+    B: falcon_packet_core::special::PacketPrepare,
+{
+    if spec.type_id().is_none() {
+        return Ok(false);
+    }
+    let layouts = [
+        Layout::of::<v1_14::play::SpawnLivingEntityPacket>(),
+        Layout::of::<v1_8_9::play::SpawnObjectPacket>(),
+        Layout::of::<v1_9::play::SpawnObjectPacket>(),
+        Layout::of::<v1_14::play::SpawnEntityPacket>(),
+        Layout::of::<v1_19::play::SpawnEntityPacket>(),
+    ];
+    let layouts = if spec.entity_type.is_living() { &layouts[..] } else { &layouts[1..] };
+    layout::write(spec, buffer, protocol, layouts)
+}
+
+/// Plays an animation of an entity, the damage animation has a packet of
+/// its own since 1.19.4.
+pub fn write_entity_animation<B>(spec: EntityAnimationSpec, buffer: &mut B, protocol: i32) -> Result<bool, falcon_packet_core::WriteError>
+where
+    B: falcon_packet_core::special::PacketPrepare,
+{
+    let layouts = [Layout::of::<v1_20::play::HurtAnimationPacket>(), Layout::of::<v1_8_9::play::EntityAnimationPacket>()];
+    let layouts = if spec.animation == EntityAnimation::TakeDamage { &layouts[..] } else { &layouts[1..] };
+    layout::write(spec, buffer, protocol, layouts)
 }
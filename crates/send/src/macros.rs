@@ -23,11 +23,13 @@ macro_rules! define_spec {
     }
 }
 
+/// Generates a function per spec that writes it as the listed packets, see
+/// [`layout`](crate::layout).
 #[macro_export]
 macro_rules! packet_write_fn {
     (
         $($spec_name:ty => $fn_name:ident {
-            $(mod $mod_name:path;)+
+            $($packet:path;)+
         }$(,)?)*
     ) => {
         $(
@@ -39,13 +41,7 @@ macro_rules! packet_write_fn {
         where
             B: ::falcon_packet_core::special::PacketPrepare,
         {
-            let mut packet = Some(packet);
-            $(
-            if $mod_name(&mut packet, buffer, protocol)? {
-                return Ok(true);
-            }
-            )+
-            Ok(false)
+            $crate::layout::write(packet, buffer, protocol, &[$($crate::layout::Layout::of::<$packet>()),+])
         }
         )*
     }
@@ -0,0 +1,77 @@
+//! Chunk sections that were serialized before, many worlds repeat the same
+//! sections over and over, e.g. the air above flat plains.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use falcon_core::world::block_states::state_ids;
+use falcon_packet_core::{PacketSize, PacketWrite, WriteError};
+
+use crate::specs::play::ChunkSectionDataSpec;
+
+/// The cache is emptied once it holds this many sections, so worlds
+/// without repetition do not keep every section they ever sent.
+const MAX_SECTIONS: usize = 1024;
+
+static SECTIONS: OnceLock<Mutex<HashMap<(usize, u64), Bytes>>> = OnceLock::new();
+
+/// A section in the format of a version, shared between every chunk and
+/// version that encodes the same section the same way.
+#[derive(Clone, Debug)]
+pub(crate) struct EncodedSection(Bytes);
+
+impl PacketSize for EncodedSection {
+    fn size(&self) -> usize { self.0.len() }
+}
+
+impl PacketWrite for EncodedSection {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        buffer.put_slice(&self.0);
+        Ok(())
+    }
+}
+
+/// Serializes a section with `encode`, or reuses the bytes of an earlier
+/// section with the same content.
+///
+/// Sections are told apart by the ids their blocks have in the version, so
+/// versions that agree on those ids share the bytes as well.
+pub(crate) fn encode_section<T>(spec: ChunkSectionDataSpec, encode: fn(ChunkSectionDataSpec) -> T) -> EncodedSection
+where
+    T: PacketSize + PacketWrite,
+{
+    let key = (encode as usize, content_hash(&spec));
+    let sections = SECTIONS.get_or_init(Default::default);
+    if let Some(bytes) = sections.lock().unwrap().get(&key) {
+        return EncodedSection(bytes.clone());
+    }
+
+    let section = encode(spec);
+    let mut buffer = BytesMut::with_capacity(section.size());
+    section.write(&mut buffer).expect("Sections are always written in full");
+    let bytes = buffer.freeze();
+    let mut sections = sections.lock().unwrap();
+    if sections.len() >= MAX_SECTIONS {
+        sections.clear();
+    }
+    sections.insert(key, bytes.clone());
+    EncodedSection(bytes)
+}
+
+/// Hashes what the serialized section depends on, the palette as ids of
+/// the version and the palette index of every block.
+fn content_hash(spec: &ChunkSectionDataSpec) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    match state_ids(spec.protocol_version) {
+        Some(ids) => (0..spec.palette.len()).for_each(|i| spec.palette.at(i).and_then(ids).hash(&mut hasher)),
+        None => spec.protocol_version.hash(&mut hasher),
+    }
+    spec.blocks.hash(&mut hasher);
+    hasher.finish()
+}
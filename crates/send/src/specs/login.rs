@@ -1,10 +1,18 @@
-use uuid::Uuid;
+use bytes::Bytes;
+use falcon_core::player::profile::GameProfile;
 
 use crate::define_spec;
 
 define_spec! {
     LoginSuccessSpec {
-        uuid: Uuid,
-        username: String,
+        profile: GameProfile,
+    }, Debug
+}
+
+define_spec! {
+    LoginPluginRequestSpec {
+        message_id: i32,
+        channel: String,
+        data: Bytes,
     }, Debug
 }
@@ -1,8 +1,18 @@
-use falcon_core::player::data::{GameMode, LookAngles, PlayerAbilityFlags, Position};
+use falcon_core::data::tags::TagRegistry;
+use falcon_core::player::advancements::Advancement;
+use falcon_core::player::data::{GameMode, LookAngles, PlayerAbilityFlags, Position, RecipeBookSettings, TeleportFlags};
+use falcon_core::player::profile::GameProfile;
+use falcon_core::player::stats::Statistic;
 use falcon_core::server::data::Difficulty;
+use falcon_core::world::biome::Biome;
+use falcon_core::world::block_states::state_id;
 use falcon_core::world::blocks::Blocks;
-use falcon_core::world::chunks::{Chunk, ChunkSection};
+use falcon_core::world::chunks::{Chunk, ChunkSection, BIOME_CELLS};
+use falcon_core::world::entity::{EntityAnimation, EntityMetadata, EntityType};
+use falcon_core::world::heightmap::HeightmapKind;
 use falcon_core::world::palette::Palette;
+use falcon_core::world::BlockPosition;
+use uuid::Uuid;
 
 use crate::define_spec;
 
@@ -21,6 +31,31 @@ define_spec! {
     }
 }
 
+define_spec! {
+    RespawnSpec {
+        dimension: i32,
+        difficulty: Difficulty,
+        game_mode: GameMode,
+        level_type: String,
+        hashed_seed: i64;
+    }
+}
+
+define_spec! {
+    UpdateHealthSpec {
+        health: f32,
+        food: i32,
+        saturation: f32;
+    }
+}
+
+define_spec! {
+    SpawnPositionSpec {
+        position: BlockPosition,
+        angle: f32;
+    }
+}
+
 define_spec! {
     PlayerAbilitiesSpec {
         flags: PlayerAbilityFlags,
@@ -31,7 +66,7 @@ define_spec! {
 
 define_spec! {
     PositionAndLookSpec => pos: &Position, look: &LookAngles {
-        flags: u8,
+        flags: TeleportFlags,
         teleport_id: i32;
         let x: f64 = pos.x,
         let y: f64 = pos.y,
@@ -41,6 +76,34 @@ define_spec! {
     }
 }
 
+define_spec! {
+    ChangeGameStateSpec {
+        reason: u8,
+        value: f32,
+    }
+}
+
+impl ChangeGameStateSpec {
+    /// Reason 3, switches the game mode of the client.
+    pub fn game_mode(game_mode: GameMode) -> Self { ChangeGameStateSpec::new(3, game_mode as u8 as f32) }
+}
+
+define_spec! {
+    EntityStatusSpec {
+        entity_id: i32,
+        status: i8,
+    }, Clone
+}
+
+impl EntityStatusSpec {
+    /// Statuses 24 to 28, the operator permission level (0 to 4) of the
+    /// player with this entity id.
+    pub fn op_level(entity_id: i32, level: u8) -> Self { EntityStatusSpec::new(entity_id, 24 + level.min(4) as i8) }
+
+    /// Status 3, the death animation of a living entity.
+    pub fn death(entity_id: i32) -> Self { EntityStatusSpec::new(entity_id, 3) }
+}
+
 define_spec! {
     ServerDifficultySpec {
         difficulty: Difficulty,
@@ -49,11 +112,14 @@ define_spec! {
 }
 
 define_spec! {
-    ChunkDataSpec => chunk: &Chunk, protocol_version: i32 {
+    ChunkDataSpec => chunk: &Chunk, protocol_version: i32, default_biome: Biome {
         ;let chunk_x: i32 = chunk_pos.x,
         let chunk_z: i32 = chunk_pos.z,
         let bitmask: i32 = bit_mask,
-        let sections: Vec<ChunkSectionDataSpec> = chunk_sections;
+        let sections: Vec<ChunkSectionDataSpec> = chunk_sections,
+        let motion_blocking: Vec<u16> = chunk.heightmap(HeightmapKind::MotionBlocking).heights().to_vec(),
+        let world_surface: Vec<u16> = chunk.heightmap(HeightmapKind::WorldSurface).heights().to_vec(),
+        let biomes: Vec<Biome> = chunk.biomes().map_or_else(|| vec![default_biome; BIOME_CELLS], |biomes| biomes.to_vec());
         {
             let chunk_pos = chunk.get_position()
             let bit_mask = chunk.get_bit_mask()
@@ -66,16 +132,34 @@ define_spec! {
 }
 
 impl ChunkDataSpec {
-    pub fn empty(x: i32, z: i32) -> Self {
+    pub fn empty(x: i32, z: i32, biome: Biome) -> Self {
         ChunkDataSpec {
             chunk_x: x,
             chunk_z: z,
             bitmask: 0,
             sections: vec![],
+            motion_blocking: vec![0; 16 * 16],
+            world_surface: vec![0; 16 * 16],
+            biomes: vec![biome; BIOME_CELLS],
         }
     }
 }
 
+define_spec! {
+    BlockChangeSpec => position: BlockPosition, block: &Blocks, protocol_version: i32 {
+        ;let position: BlockPosition = position,
+        // blocks that the version does not have become air
+        let block_id: i32 = state_id(block, protocol_version).unwrap_or(0),
+    }
+}
+
+define_spec! {
+    LightUpdateSpec {
+        chunk_x: i32,
+        chunk_z: i32,
+    }
+}
+
 define_spec! {
     ChunkSectionDataSpec => section: &ChunkSection {
         section_index: usize,
@@ -84,3 +168,303 @@ define_spec! {
         let blocks: Vec<u16> = section.get_block_data().clone(),
     }
 }
+
+/// The statistics of a player, sent when the statistics screen opens.
+#[derive(Clone, Debug)]
+pub struct StatisticsSpec {
+    pub statistics: Vec<(Statistic, i32)>,
+    /// Custom statistics have other ids in every version.
+    pub protocol_version: i32,
+}
+
+impl StatisticsSpec {
+    pub fn new(statistics: Vec<(Statistic, i32)>, protocol_version: i32) -> Self { StatisticsSpec { statistics, protocol_version } }
+}
+
+/// Changes to the advancements of a player, since 1.12.
+#[derive(Clone, Debug)]
+pub struct AdvancementsSpec {
+    /// Removes every advancement first.
+    pub reset: bool,
+    pub added: Vec<Advancement>,
+    pub removed: Vec<String>,
+    /// When each advancement was granted in milliseconds since the Unix
+    /// epoch, `None` revokes it.
+    pub progress: Vec<(String, Option<i64>)>,
+    /// The icon and the fields of an advancement differ between versions.
+    pub protocol_version: i32,
+}
+
+impl AdvancementsSpec {
+    pub fn new(reset: bool, added: Vec<Advancement>, removed: Vec<String>, progress: Vec<(String, Option<i64>)>, protocol_version: i32) -> Self {
+        AdvancementsSpec {
+            reset,
+            added,
+            removed,
+            progress,
+            protocol_version,
+        }
+    }
+}
+
+/// The tags of every registry, 1.13+ clients need them after joining.
+#[derive(Clone, Debug)]
+pub struct TagsSpec {
+    pub tags: TagRegistry,
+    /// Before 1.17 only blocks, items, fluids and, since 1.14, entity types
+    /// have tags.
+    pub protocol_version: i32,
+}
+
+impl TagsSpec {
+    pub fn new(tags: TagRegistry, protocol_version: i32) -> Self { TagsSpec { tags, protocol_version } }
+}
+
+/// The recipes of the crafting blocks, 1.13+ clients only know the recipes
+/// the server declares. Recipes need item ids, so none are declared yet.
+#[derive(Clone, Debug, Default)]
+pub struct DeclareRecipesSpec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnlockRecipesAction {
+    /// Replaces the recipes of the book, sent after joining.
+    Init = 0,
+    /// Shows a toast for the new recipes.
+    Add,
+    Remove,
+}
+
+/// Changes to the recipe book of a player.
+#[derive(Clone, Debug)]
+pub struct UnlockRecipesSpec {
+    pub action: UnlockRecipesAction,
+    pub settings: RecipeBookSettings,
+    /// The identifiers of declared recipes, clients before 1.13 number their
+    /// built-in recipes instead so these are not sent to them.
+    pub recipes: Vec<String>,
+    /// The number of books and the recipe format differ between versions.
+    pub protocol_version: i32,
+}
+
+impl UnlockRecipesSpec {
+    pub fn new(action: UnlockRecipesAction, settings: RecipeBookSettings, recipes: Vec<String>, protocol_version: i32) -> Self {
+        UnlockRecipesSpec {
+            action,
+            settings,
+            recipes,
+            protocol_version,
+        }
+    }
+}
+
+/// A non-player entity that comes into view of a player.
+#[derive(Clone, Debug)]
+pub struct SpawnEntitySpec {
+    pub entity_id: i32,
+    pub uuid: Uuid,
+    pub entity_type: EntityType,
+    pub position: Position,
+    pub look: LookAngles,
+    /// Depends on the type, e.g. the facing of an item frame.
+    pub data: i32,
+    /// Entity types have other ids in every version.
+    pub protocol_version: i32,
+}
+
+impl SpawnEntitySpec {
+    pub fn new(entity_id: i32, uuid: Uuid, entity_type: EntityType, position: Position, look: LookAngles, data: i32, protocol_version: i32) -> Self {
+        SpawnEntitySpec {
+            entity_id,
+            uuid,
+            entity_type,
+            position,
+            look,
+            data,
+            protocol_version,
+        }
+    }
+
+    /// The id of the type in this version, object ids before 1.14. `None`
+    /// if the version does not have this type.
+    pub(crate) fn type_id(&self) -> Option<i32> {
+        match (self.entity_type, self.protocol_version) {
+            (EntityType::ArmorStand, ..=404) => Some(78),
+            (EntityType::ArmorStand, 477..=756) => Some(1),
+            (EntityType::ArmorStand, 759..) => Some(2),
+            (EntityType::ItemFrame, ..=404) => Some(71),
+            (EntityType::ItemFrame, 477..=498) => Some(35),
+            (EntityType::ItemFrame, 573..=578) => Some(36),
+            (EntityType::ItemFrame, 735..=736) => Some(38),
+            (EntityType::ItemFrame, 755..=756) => Some(42),
+            (EntityType::ItemFrame, 759..=760) => Some(45),
+            (EntityType::ItemFrame, 761) => Some(53),
+            (EntityType::ItemFrame, 763) => Some(56),
+            (EntityType::BlockDisplay, 763) => Some(8),
+            (EntityType::ItemDisplay, 763) => Some(55),
+            (EntityType::TextDisplay, 763) => Some(100),
+            _ => None,
+        }
+    }
+}
+
+/// The metadata of an entity, every field is sent.
+#[derive(Clone, Debug)]
+pub struct EntityMetadataSpec {
+    pub entity_id: i32,
+    pub entity_type: EntityType,
+    pub metadata: EntityMetadata,
+    /// The indices and types of the fields differ between versions.
+    pub protocol_version: i32,
+}
+
+impl EntityMetadataSpec {
+    pub fn new(entity_id: i32, entity_type: EntityType, metadata: EntityMetadata, protocol_version: i32) -> Self {
+        EntityMetadataSpec {
+            entity_id,
+            entity_type,
+            metadata,
+            protocol_version,
+        }
+    }
+}
+
+/// Another player, the client looks up its name and skin in the tab list.
+#[derive(Clone, Debug)]
+pub struct SpawnPlayerSpec {
+    pub entity_id: i32,
+    pub uuid: Uuid,
+    pub position: Position,
+    pub look: LookAngles,
+    /// Part of the packet before 1.15.
+    pub metadata: EntityMetadata,
+    pub protocol_version: i32,
+}
+
+impl SpawnPlayerSpec {
+    pub fn new(entity_id: i32, uuid: Uuid, position: Position, look: LookAngles, metadata: EntityMetadata, protocol_version: i32) -> Self {
+        SpawnPlayerSpec {
+            entity_id,
+            uuid,
+            position,
+            look,
+            metadata,
+            protocol_version,
+        }
+    }
+}
+
+/// Moves an entity to an absolute position.
+#[derive(Clone, Debug)]
+pub struct EntityTeleportSpec {
+    pub entity_id: i32,
+    pub position: Position,
+    pub look: LookAngles,
+    pub on_ground: bool,
+}
+
+impl EntityTeleportSpec {
+    pub fn new(entity_id: i32, position: Position, look: LookAngles, on_ground: bool) -> Self {
+        EntityTeleportSpec {
+            entity_id,
+            position,
+            look,
+            on_ground,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EntityAnimationSpec {
+    pub entity_id: i32,
+    pub animation: EntityAnimation,
+}
+
+impl EntityAnimationSpec {
+    pub fn new(entity_id: i32, animation: EntityAnimation) -> Self { EntityAnimationSpec { entity_id, animation } }
+}
+
+/// The velocity of an entity in blocks per tick, players are pushed by
+/// their own velocity.
+#[derive(Clone, Debug)]
+pub struct EntityVelocitySpec {
+    pub entity_id: i32,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl EntityVelocitySpec {
+    pub fn new(entity_id: i32, x: f64, y: f64, z: f64) -> Self { EntityVelocitySpec { entity_id, x, y, z } }
+}
+
+/// A player as shown in the tab list.
+#[derive(Clone, Debug)]
+pub struct PlayerInfoEntry {
+    pub(crate) profile: GameProfile,
+    pub(crate) game_mode: GameMode,
+    /// The latency in milliseconds.
+    pub(crate) ping: i32,
+}
+
+impl PlayerInfoEntry {
+    pub fn new(profile: GameProfile, game_mode: GameMode, ping: i32) -> Self {
+        PlayerInfoEntry {
+            profile,
+            game_mode,
+            ping,
+        }
+    }
+}
+
+/// A change to the tab list, removing players is done with
+/// [`write_player_info_remove`](crate::write_player_info_remove).
+#[derive(Clone, Debug)]
+pub enum PlayerInfoSpec {
+    AddPlayers(Vec<PlayerInfoEntry>),
+    UpdateGameMode(Vec<(Uuid, GameMode)>),
+    /// The latency in milliseconds of every player.
+    UpdateLatency(Vec<(Uuid, i32)>),
+}
+
+/// How the client parses and completes an argument of a command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandParser {
+    /// A single word.
+    Word,
+    /// Everything up to the end of the command.
+    Greedy,
+    Double,
+    /// The name of an online player.
+    Player,
+    /// The name of any player, online or not.
+    GameProfile,
+}
+
+#[derive(Clone, Debug)]
+pub enum CommandNodeKind {
+    Root,
+    Literal(String),
+    Argument(String, CommandParser),
+}
+
+/// A node of the command tree, the children are indices into the nodes
+/// of the [`DeclareCommandsSpec`].
+#[derive(Clone, Debug)]
+pub struct CommandNodeSpec {
+    pub kind: CommandNodeKind,
+    /// Whether the command is complete at this node.
+    pub executable: bool,
+    pub children: Vec<i32>,
+}
+
+/// The commands a player may use, 1.13+ clients complete and check
+/// commands with these.
+#[derive(Clone, Debug)]
+pub struct DeclareCommandsSpec {
+    pub nodes: Vec<CommandNodeSpec>,
+    pub root: i32,
+}
+
+impl DeclareCommandsSpec {
+    pub fn new(nodes: Vec<CommandNodeSpec>, root: i32) -> Self { DeclareCommandsSpec { nodes, root } }
+}
@@ -1,3 +1,4 @@
+use falcon_core::chat::ChatComponent;
 use falcon_core::server::data::ServerVersion;
 use serde::Serialize;
 
@@ -7,7 +8,7 @@ define_spec! {
     StatusResponseSpec {
         version: ServerVersion,
         players: PlayerData,
-        description: String,
+        description: ChatComponent,
     }, Debug, Serialize
 }
 
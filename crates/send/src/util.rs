@@ -1,50 +1,191 @@
-use falcon_core::world::block_util::blocks_movement;
-use falcon_core::world::blocks::Blocks;
-use falcon_core::world::chunks::ChunkSection;
-use falcon_core::world::palette::PaletteToI32;
+use bytes::BufMut;
+use falcon_core::data::items::ProtocolItems;
+use falcon_core::world::biome::Biome;
+use falcon_core::world::chunks::{Chunk, BIOME_CELL, BIOME_CELLS, SECTION_HEIGHT, SECTION_LENGTH, SECTION_WIDTH};
+use falcon_core::world::BlockPosition;
+use falcon_packet_core::{PacketIter, PacketSize, PacketVec, PacketWrite, PacketWriteSeed, VarI32, WriteError};
 
-use crate::ChunkSectionDataSpec;
+use crate::specs::play::ChunkDataSpec;
 
 pub struct HeightMap {
     motion_blocking: Vec<u16>,
+    world_surface: Vec<u16>,
 }
 
 impl HeightMap {
-    pub fn from_sections(sections: &[ChunkSectionDataSpec], to_i32: PaletteToI32<Blocks>) -> HeightMap {
-        match sections.len() {
-            0 => HeightMap {
-                motion_blocking: vec![0; 16 * 16],
-            },
-            _ => {
-                let mut heightmap = Vec::with_capacity(256);
-                for z in 0..16 {
-                    for x in 0..16 {
-                        let mut found = false;
-                        for section in sections.iter().rev() {
-                            let top_y = section.section_index * 16;
-                            for y in (0..16).rev() {
-                                let block = section
-                                    .palette
-                                    .at(section.blocks[ChunkSection::calculate_index(x as u16, y as u16, z as u16)] as usize)
-                                    .unwrap();
-                                if to_i32(block).is_some() && blocks_movement(block) {
-                                    heightmap.push((top_y + y + 1) as u16);
-                                    found = true;
-                                    break;
-                                }
-                            }
-                            if found {
-                                break;
-                            }
-                        }
-                    }
-                }
-                HeightMap {
-                    motion_blocking: heightmap,
-                }
+    pub fn new(spec: &ChunkDataSpec) -> HeightMap {
+        HeightMap {
+            motion_blocking: spec.motion_blocking.clone(),
+            world_surface: spec.world_surface.clone(),
+        }
+    }
+
+    pub fn motion_blocking(&self) -> &[u16] { &self.motion_blocking }
+
+    pub fn world_surface(&self) -> &[u16] { &self.world_surface }
+}
+
+/// The legacy id of the biome of every column at sea level, before 1.15.
+pub(crate) fn biomes_2d(biomes: &[Biome]) -> [i32; (SECTION_WIDTH * SECTION_LENGTH) as usize] {
+    let mut columns = [0; (SECTION_WIDTH * SECTION_LENGTH) as usize];
+    for (i, column) in columns.iter_mut().enumerate() {
+        let (x, z) = (i as u16 % SECTION_WIDTH, i as u16 / SECTION_WIDTH);
+        *column = biomes[Chunk::biome_index(x, SEA_LEVEL, z)].legacy_id();
+    }
+    columns
+}
+
+const SEA_LEVEL: u16 = 64;
+
+/// The legacy id of the biome of every cell, 1.15 up to 1.16.1.
+pub(crate) fn biomes_3d(biomes: &[Biome]) -> [i32; BIOME_CELLS] {
+    let mut cells = [0; BIOME_CELLS];
+    cells.iter_mut().zip(biomes).for_each(|(cell, biome)| *cell = biome.legacy_id());
+    cells
+}
+
+/// The biomes of the cells of a section in the paletted format of 1.18+.
+pub(crate) struct BiomeSection {
+    bits: u8,
+    /// The registry ids of the biomes, `None` for the direct palette.
+    palette: Option<Vec<i32>>,
+    longs: Vec<u64>,
+}
+
+impl BiomeSection {
+    pub(crate) fn new(biomes: &[Biome], section: usize) -> Self {
+        let per_section = ((SECTION_WIDTH / BIOME_CELL) * (SECTION_LENGTH / BIOME_CELL) * (SECTION_HEIGHT / BIOME_CELL)) as usize;
+        let cells = &biomes[section * per_section..(section + 1) * per_section];
+        let mut palette: Vec<Biome> = Vec::new();
+        for biome in cells {
+            if !palette.contains(biome) {
+                palette.push(*biome);
+            }
+        }
+        if palette.len() == 1 {
+            return BiomeSection {
+                bits: 0,
+                palette: Some(vec![palette[0].registry_id()]),
+                longs: Vec::new(),
+            };
+        }
+        // an indirect palette is used up to 3 bits
+        let indirect = palette.len() <= 8;
+        let bits = bits_for(if indirect { palette.len() } else { Biome::ALL.len() });
+        let index = |biome: &Biome| match indirect {
+            true => palette.iter().position(|b| b == biome).unwrap_or(0) as u64,
+            false => biome.registry_id() as u64,
+        };
+        let per_long = (64 / bits) as usize;
+        let longs = cells
+            .chunks(per_long)
+            .map(|long| long.iter().enumerate().fold(0, |packed, (i, biome)| packed | index(biome) << (i * bits as usize)))
+            .collect();
+        BiomeSection {
+            bits: bits as u8,
+            palette: indirect.then(|| palette.iter().map(Biome::registry_id).collect()),
+            longs,
+        }
+    }
+}
+
+impl PacketSize for BiomeSection {
+    fn size(&self) -> usize {
+        let palette = match &self.palette {
+            // a single value has no length
+            Some(palette) if self.bits == 0 => VarI32::from(palette[0]).size(),
+            Some(palette) => VarI32::from(palette.len()).size() + palette.iter().map(|&id| VarI32::from(id).size()).sum::<usize>(),
+            None => 0,
+        };
+        self.bits.size() + palette + VarI32::from(self.longs.len()).size() + self.longs.len() * std::mem::size_of::<u64>()
+    }
+}
+
+impl PacketWrite for BiomeSection {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        self.bits.write(buffer)?;
+        match &self.palette {
+            Some(palette) if self.bits == 0 => VarI32::from(palette[0]).write(buffer)?,
+            Some(palette) => {
+                VarI32::from(palette.len()).write(buffer)?;
+                PacketIter::new(palette.iter().map(|&id| VarI32::from(id))).write_owned(buffer)?;
             },
+            None => {},
+        }
+        VarI32::from(self.longs.len()).write(buffer)?;
+        PacketWriteSeed::write(PacketVec::default(), &self.longs, buffer)
+    }
+}
+
+fn bits_for(entries: usize) -> u32 { (usize::BITS - (entries - 1).leading_zeros()).max(1) }
+
+/// The first protocol without the damage of an item, 1.13.
+const NO_DAMAGE_PROTOCOL: i32 = 393;
+/// The first protocol with a boolean in front of an item, 1.13.2.
+const ITEM_PRESENT_PROTOCOL: i32 = 404;
+
+/// An item stack without NBT in the format of a version.
+pub(crate) struct Slot {
+    item: Option<(i32, u8)>,
+    protocol: i32,
+}
+
+impl Slot {
+    /// Empty if the version has no id for `item`, see [`ProtocolItems`].
+    pub(crate) fn new(item: &str, count: u8, protocol: i32) -> Self {
+        Slot {
+            item: ProtocolItems::global().item_id(item, protocol).map(|id| (id, count)),
+            protocol,
+        }
+    }
+}
+
+impl PacketSize for Slot {
+    fn size(&self) -> usize {
+        let Some((id, count)) = self.item else {
+            return if self.protocol < ITEM_PRESENT_PROTOCOL { (-1i16).size() } else { false.size() };
+        };
+        let id = if self.protocol < ITEM_PRESENT_PROTOCOL { (id as i16).size() } else { true.size() + VarI32::from(id).size() };
+        let damage = if self.protocol < NO_DAMAGE_PROTOCOL { 0i16.size() } else { 0 };
+        id + count.size() + damage + TAG_END.size()
+    }
+}
+
+impl PacketWrite for Slot {
+    fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        let Some((id, count)) = self.item else {
+            return if self.protocol < ITEM_PRESENT_PROTOCOL { (-1i16).write(buffer) } else { false.write(buffer) };
+        };
+        if self.protocol < ITEM_PRESENT_PROTOCOL {
+            (id as i16).write(buffer)?;
+        } else {
+            true.write(buffer)?;
+            VarI32::from(id).write(buffer)?;
         }
+        count.write(buffer)?;
+        if self.protocol < NO_DAMAGE_PROTOCOL {
+            0i16.write(buffer)?;
+        }
+        // no NBT
+        TAG_END.write(buffer)
     }
+}
+
+const TAG_END: u8 = 0;
+
+/// A block position packed in a long, before 1.14.
+pub(crate) fn position_xyz(position: BlockPosition) -> i64 {
+    ((position.get_x() as i64 & 0x3FFFFFF) << 38) | ((position.get_y() as i64 & 0xFFF) << 26) | (position.get_z() as i64 & 0x3FFFFFF)
+}
 
-    pub fn motion_blocking(self) -> Vec<u16> { self.motion_blocking }
+/// A block position packed in a long, the y coordinate moved to the end in
+/// 1.14.
+pub(crate) fn position_xzy(position: BlockPosition) -> i64 {
+    ((position.get_x() as i64 & 0x3FFFFFF) << 38) | ((position.get_z() as i64 & 0x3FFFFFF) << 12) | (position.get_y() as i64 & 0xFFF)
 }
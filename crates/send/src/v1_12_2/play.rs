@@ -1,13 +1,22 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
-    use falcon_packet_core::{PacketSize, PacketWrite};
+falcon_packet_core::packet! {
+    use bytes::BufMut;
+    use falcon_core::player::advancements::{Advancement, AdvancementFrame, CRITERION};
+    use falcon_packet_core::{PacketSize, PacketSizeSeed, PacketString, PacketWrite, PacketWriteSeed, VarI32, WriteError};
+
+    use crate::specs::play::{AdvancementsSpec, UnlockRecipesAction, UnlockRecipesSpec};
+    use crate::util::Slot;
 
     #[derive(PacketSize, PacketWrite)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         340 = 0x1F;
         393, 401, 404, 573, 575, 578 = 0x21;
         477, 480, 485, 490, 498, 735, 736 = 0x20;
-    }, name = "keep_alive")]
+        755, 756 = 0x21;
+        759 = 0x1E;
+        760 = 0x20;
+        761 = 0x1F;
+        763 = 0x23;
+    })]
     pub struct KeepAlivePacket {
         id: i64,
     }
@@ -15,4 +24,275 @@ mod inner {
     impl From<i64> for KeepAlivePacket {
         fn from(id: i64) -> Self { KeepAlivePacket { id } }
     }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        335 = 0x4C;
+        338, 340 = 0x4D;
+        393, 401, 404 = 0x51;
+        477, 480, 485, 490, 498, 735, 736 = 0x57;
+        573, 575, 578 = 0x58;
+        755, 756 = 0x62;
+        759 = 0x64;
+        760 = 0x67;
+        761 = 0x65;
+        763 = 0x69;
+    })]
+    pub struct AdvancementsPacket {
+        reset: bool,
+        changes: AdvancementChanges,
+    }
+
+    impl From<AdvancementsSpec> for AdvancementsPacket {
+        fn from(spec: AdvancementsSpec) -> Self {
+            AdvancementsPacket {
+                reset: spec.reset,
+                changes: AdvancementChanges {
+                    added: spec.added.iter().map(|advancement| EncodedAdvancement::new(advancement, spec.protocol_version)).collect(),
+                    removed: spec.removed,
+                    progress: spec.progress,
+                    protocol: spec.protocol_version,
+                },
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        335 = 0x30;
+        338, 340 = 0x31;
+        393, 401, 404 = 0x34;
+        477, 480, 485, 490, 498, 735, 736 = 0x36;
+        573, 575, 578 = 0x37;
+        755, 756 = 0x39;
+        759 = 0x37;
+        760 = 0x3A;
+        761 = 0x39;
+        763 = 0x3D;
+    })]
+    pub struct UnlockRecipesPacket {
+        #[falcon(var32)]
+        action: i32,
+        changes: RecipeBookChanges,
+    }
+
+    impl From<UnlockRecipesSpec> for UnlockRecipesPacket {
+        fn from(spec: UnlockRecipesSpec) -> Self {
+            // furnaces since 1.13, blast furnaces and smokers since 1.16.2
+            let books = match spec.protocol_version {
+                ..=340 => 1,
+                341..=736 => 2,
+                _ => 4,
+            };
+            UnlockRecipesPacket {
+                action: spec.action as i32,
+                changes: RecipeBookChanges {
+                    books: spec.settings.books()[..books].to_vec(),
+                    recipes: if spec.protocol_version < IDENTIFIER_RECIPE_PROTOCOL { Vec::new() } else { spec.recipes },
+                    init: spec.action == UnlockRecipesAction::Init,
+                },
+            }
+        }
+    }
+
+    /// The first protocol that names recipes instead of numbering them,
+    /// 1.13.
+    const IDENTIFIER_RECIPE_PROTOCOL: i32 = 393;
+
+    /// The settings of the books followed by the recipes, clients before
+    /// 1.13 get empty VarInt lists.
+    pub(crate) struct RecipeBookChanges {
+        books: Vec<(bool, bool)>,
+        recipes: Vec<String>,
+        /// Adds a second list, the recipes to highlight as new.
+        init: bool,
+    }
+
+    impl PacketSize for RecipeBookChanges {
+        fn size(&self) -> usize {
+            let recipes = VarI32::from(self.recipes.len()).size() + self.recipes.iter().map(|recipe| PacketString::new(32767).size(recipe)).sum::<usize>();
+            let highlighted = if self.init { VarI32::from(0).size() } else { 0 };
+            self.books.len() * 2 + recipes + highlighted
+        }
+    }
+
+    impl PacketWrite for RecipeBookChanges {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            for (open, filtering) in &self.books {
+                open.write(buffer)?;
+                filtering.write(buffer)?;
+            }
+            VarI32::from(self.recipes.len()).write(buffer)?;
+            for recipe in &self.recipes {
+                PacketString::new(32767).write(recipe, buffer)?;
+            }
+            if self.init {
+                VarI32::from(0).write(buffer)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// The first protocol that tells whether an advancement sends
+    /// telemetry, 1.20.
+    const TELEMETRY_PROTOCOL: i32 = 763;
+
+    const FLAG_BACKGROUND: i32 = 0x01;
+    const FLAG_SHOW_TOAST: i32 = 0x02;
+    const FLAG_HIDDEN: i32 = 0x04;
+
+    pub(crate) struct AdvancementChanges {
+        added: Vec<EncodedAdvancement>,
+        removed: Vec<String>,
+        progress: Vec<(String, Option<i64>)>,
+        protocol: i32,
+    }
+
+    /// An advancement with its display text as JSON.
+    struct EncodedAdvancement {
+        id: String,
+        parent: Option<String>,
+        display: Option<EncodedDisplay>,
+    }
+
+    struct EncodedDisplay {
+        title: String,
+        description: String,
+        /// Empty if the item has no id in the version.
+        icon: Slot,
+        frame: i32,
+        flags: i32,
+        background: Option<String>,
+        x: f32,
+        y: f32,
+    }
+
+    impl EncodedAdvancement {
+        fn new(advancement: &Advancement, protocol: i32) -> Self {
+            let display = advancement.display.as_ref().map(|display| {
+                let flags = [(display.background.is_some(), FLAG_BACKGROUND), (display.show_toast, FLAG_SHOW_TOAST), (display.hidden, FLAG_HIDDEN)]
+                    .into_iter()
+                    .filter(|(set, _)| *set)
+                    .fold(0, |flags, (_, flag)| flags | flag);
+                EncodedDisplay {
+                    title: serde_json::to_string(&display.title).expect("Invalid title data"),
+                    description: serde_json::to_string(&display.description).expect("Invalid description data"),
+                    icon: Slot::new(&display.icon, 1, protocol),
+                    frame: match display.frame {
+                        AdvancementFrame::Task => 0,
+                        AdvancementFrame::Challenge => 1,
+                        AdvancementFrame::Goal => 2,
+                    },
+                    flags,
+                    background: display.background.clone(),
+                    x: display.x,
+                    y: display.y,
+                }
+            });
+            EncodedAdvancement {
+                id: advancement.id.clone(),
+                parent: advancement.parent.clone(),
+                display,
+            }
+        }
+    }
+
+    impl AdvancementChanges {
+        fn advancement_size(&self, advancement: &EncodedAdvancement) -> usize {
+            let parent = 1 + advancement.parent.as_ref().map(|parent| PacketString::new(32767).size(parent)).unwrap_or(0);
+            let display = 1 + advancement
+                .display
+                .as_ref()
+                .map(|display| {
+                    PacketString::new(262144).size(&display.title)
+                        + PacketString::new(262144).size(&display.description)
+                        + display.icon.size()
+                        + VarI32::from(display.frame).size()
+                        + display.flags.size()
+                        + display.background.as_ref().map(|background| PacketString::new(32767).size(background)).unwrap_or(0)
+                        + display.x.size()
+                        + display.y.size()
+                })
+                .unwrap_or(0);
+            // one criterion that is the only requirement
+            let criteria = VarI32::from(1).size() + PacketString::new(32767).size(&CRITERION) + VarI32::from(1).size() + VarI32::from(1).size() + PacketString::new(32767).size(&CRITERION);
+            let telemetry = if self.protocol >= TELEMETRY_PROTOCOL { false.size() } else { 0 };
+            PacketString::new(32767).size(&advancement.id) + parent + display + criteria + telemetry
+        }
+
+        fn write_advancement<B: BufMut + ?Sized>(&self, advancement: &EncodedAdvancement, buffer: &mut B) -> Result<(), WriteError> {
+            PacketString::new(32767).write(&advancement.id, buffer)?;
+            advancement.parent.is_some().write(buffer)?;
+            if let Some(parent) = &advancement.parent {
+                PacketString::new(32767).write(parent, buffer)?;
+            }
+            advancement.display.is_some().write(buffer)?;
+            if let Some(display) = &advancement.display {
+                PacketString::new(262144).write(&display.title, buffer)?;
+                PacketString::new(262144).write(&display.description, buffer)?;
+                display.icon.write(buffer)?;
+                VarI32::from(display.frame).write(buffer)?;
+                display.flags.write(buffer)?;
+                if let Some(background) = &display.background {
+                    PacketString::new(32767).write(background, buffer)?;
+                }
+                display.x.write(buffer)?;
+                display.y.write(buffer)?;
+            }
+            VarI32::from(1).write(buffer)?;
+            PacketString::new(32767).write(&CRITERION, buffer)?;
+            VarI32::from(1).write(buffer)?;
+            VarI32::from(1).write(buffer)?;
+            PacketString::new(32767).write(&CRITERION, buffer)?;
+            if self.protocol >= TELEMETRY_PROTOCOL {
+                false.write(buffer)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl PacketSize for AdvancementChanges {
+        fn size(&self) -> usize {
+            VarI32::from(self.added.len()).size()
+                + self.added.iter().map(|advancement| self.advancement_size(advancement)).sum::<usize>()
+                + VarI32::from(self.removed.len()).size()
+                + self.removed.iter().map(|id| PacketString::new(32767).size(id)).sum::<usize>()
+                + VarI32::from(self.progress.len()).size()
+                + self
+                    .progress
+                    .iter()
+                    .map(|(id, granted)| PacketString::new(32767).size(id) + VarI32::from(1).size() + PacketString::new(32767).size(&CRITERION) + 1 + granted.map(|time| time.size()).unwrap_or(0))
+                    .sum::<usize>()
+        }
+    }
+
+    impl PacketWrite for AdvancementChanges {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            VarI32::from(self.added.len()).write(buffer)?;
+            for advancement in &self.added {
+                self.write_advancement(advancement, buffer)?;
+            }
+            VarI32::from(self.removed.len()).write(buffer)?;
+            for id in &self.removed {
+                PacketString::new(32767).write(id, buffer)?;
+            }
+            VarI32::from(self.progress.len()).write(buffer)?;
+            for (id, granted) in &self.progress {
+                PacketString::new(32767).write(id, buffer)?;
+                VarI32::from(1).write(buffer)?;
+                PacketString::new(32767).write(&CRITERION, buffer)?;
+                granted.is_some().write(buffer)?;
+                if let Some(time) = granted {
+                    time.write(buffer)?;
+                }
+            }
+            Ok(())
+        }
+    }
 }
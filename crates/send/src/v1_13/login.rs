@@ -0,0 +1,29 @@
+falcon_packet_core::packet! {
+    use bytes::Bytes;
+    use falcon_packet_core::{PacketSize, PacketWrite};
+
+    use crate::specs::login::LoginPluginRequestSpec;
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = login, direction = clientbound, versions = {
+        393, 401, 404, 477, 480, 485, 490, 498, 573, 575, 578, 735, 736, 755, 756, 759, 760, 761, 763 = 0x04;
+    })]
+    pub struct LoginPluginRequestPacket {
+        #[falcon(var32)]
+        message_id: i32,
+        #[falcon(string = 32767)]
+        channel: String,
+        #[falcon(rest)]
+        data: Bytes,
+    }
+
+    impl From<LoginPluginRequestSpec> for LoginPluginRequestPacket {
+        fn from(spec: LoginPluginRequestSpec) -> Self {
+            LoginPluginRequestPacket {
+                message_id: spec.message_id,
+                channel: spec.channel,
+                data: spec.data,
+            }
+        }
+    }
+}
@@ -1,53 +1,74 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
     use bytes::BufMut;
-    use derive_from_ext::From;
+    use falcon_core::data::tags::{TagRegistry, BLOCK, ENTITY_TYPE, FLUID, ITEM};
+    use falcon_core::player::stats::Statistic;
+    use falcon_core::world::block_states::state_ids;
     use falcon_core::world::blocks::Blocks;
     use falcon_core::world::chunks::{SECTION_HEIGHT, SECTION_LENGTH, SECTION_WIDTH};
-    use falcon_packet_core::{PacketArray, PacketIter, PacketSize, PacketVec, PacketWrite, PacketWriteSeed, VarI32, WriteError};
+    use falcon_packet_core::{PacketArray, PacketIter, PacketSize, PacketSizeSeed, PacketString, PacketVec, PacketWrite, PacketWriteSeed, VarI32, WriteError};
 
-    use crate::specs::play::{ChunkDataSpec, ChunkSectionDataSpec};
+    use crate::section_cache::{encode_section, EncodedSection};
+    use crate::specs::play::{
+        BlockChangeSpec, ChunkDataSpec, ChunkSectionDataSpec, CommandNodeKind, CommandNodeSpec, CommandParser, DeclareCommandsSpec, DeclareRecipesSpec,
+        StatisticsSpec, TagsSpec,
+    };
+    use crate::util::{biomes_2d, position_xyz};
 
     const MAX_BITS_PER_BLOCK: u8 = 14;
 
     const BIOME_COUNT: u16 = SECTION_WIDTH * SECTION_LENGTH;
     const LIGHT_COUNT: usize = ((SECTION_WIDTH * SECTION_HEIGHT * SECTION_LENGTH) / 2) as usize;
-    const BIOMES: [i32; BIOME_COUNT as usize] = [0; BIOME_COUNT as usize];
     const MAX_LIGHT: [u8; LIGHT_COUNT] = [0xFF; LIGHT_COUNT];
 
-    #[derive(PacketSize, PacketWrite, From)]
-    #[from(ChunkDataSpec)]
-    #[falcon_packet(versions = {
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         393, 401, 404 = 0x22;
-    }, name = "chunk_data")]
+    })]
     pub struct ChunkDataPacket {
         chunk_x: i32,
         chunk_z: i32,
-        #[from(skip, default = "true")]
-        full_chunk: bool,
+        full_chunk: bool, // default true
         #[falcon(var32)]
         bitmask: i32,
-        #[from(skip)]
         #[falcon(var32)]
         size: usize, // filled in by sections field
-        #[from(map = "data_map")]
         #[falcon(link = "size with data")]
-        sections: Vec<ChunkSectionData>,
-        #[from(skip)]
+        sections: ChunkColumn<EncodedSection>,
         #[falcon(var32)]
         block_entity_num: i32, // default 0
     }
 
-    fn data_map(sections: Vec<ChunkSectionDataSpec>) -> Vec<ChunkSectionData> { sections.into_iter().map(|s| s.into()).collect() }
+    /// The sections of a chunk followed by the biome of every column.
+    pub(crate) struct ChunkColumn<T> {
+        pub(crate) sections: Vec<T>,
+        pub(crate) biomes: [i32; BIOME_COUNT as usize],
+    }
+
+    impl From<ChunkDataSpec> for ChunkDataPacket {
+        fn from(spec: ChunkDataSpec) -> Self {
+            ChunkDataPacket {
+                chunk_x: spec.chunk_x,
+                chunk_z: spec.chunk_z,
+                full_chunk: true,
+                bitmask: spec.bitmask,
+                size: 0,
+                sections: ChunkColumn {
+                    biomes: biomes_2d(&spec.biomes),
+                    sections: spec.sections.into_iter().map(|section| encode_section(section, ChunkSectionData::from)).collect(),
+                },
+                block_entity_num: 0,
+            }
+        }
+    }
 
     #[inline(always)]
-    fn data_value(field: &[ChunkSectionData]) -> usize { data_size(field) }
+    pub(crate) fn data_value<T: PacketSize>(field: &ChunkColumn<T>) -> usize { data_size(field) }
 
-    fn data_size(field: &[ChunkSectionData]) -> usize { PacketIter::new(field.iter()).size_ref() + BIOME_COUNT as usize * 4 }
+    pub(crate) fn data_size<T: PacketSize>(field: &ChunkColumn<T>) -> usize { PacketIter::new(field.sections.iter()).size_ref() + BIOME_COUNT as usize * 4 }
 
-    fn data_write<B: BufMut + ?Sized>(field: &[ChunkSectionData], buffer: &mut B) -> Result<(), WriteError> {
-        PacketIter::new(field.iter()).write_ref(buffer)?;
-        PacketWriteSeed::write(PacketArray::default(), &BIOMES, buffer)
+    pub(crate) fn data_write<T: PacketWrite, B: BufMut + ?Sized>(field: &ChunkColumn<T>, buffer: &mut B) -> Result<(), WriteError> {
+        PacketIter::new(field.sections.iter()).write_ref(buffer)?;
+        PacketWriteSeed::write(PacketArray::default(), &field.biomes, buffer)
     }
 
     struct ChunkSectionData {
@@ -91,10 +112,7 @@ mod inner {
 
     impl From<ChunkSectionDataSpec> for ChunkSectionData {
         fn from(spec: ChunkSectionDataSpec) -> Self {
-            let block_to_int = match spec.protocol_version {
-                401 | 404 => Blocks::get_global_id_1631,
-                _ => Blocks::get_global_id_1519,
-            };
+            let block_to_int = state_ids(spec.protocol_version).unwrap_or(Blocks::get_global_id_1519);
             let bits_per_block = {
                 let actual = spec.palette.calculate_bits_per_entry(block_to_int);
                 if actual < 4 {
@@ -133,6 +151,25 @@ mod inner {
         }
     }
 
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        393, 401, 404 = 0x0B;
+    })]
+    pub struct BlockChangePacket {
+        position: i64,
+        #[falcon(var32)]
+        block_id: i32,
+    }
+
+    impl From<BlockChangeSpec> for BlockChangePacket {
+        fn from(spec: BlockChangeSpec) -> Self {
+            BlockChangePacket {
+                position: position_xyz(spec.position),
+                block_id: spec.block_id,
+            }
+        }
+    }
+
     pub fn build_compacted_data_array<E: Iterator<Item = u64>>(bits_per_element: u8, capacity: u32, elements: E) -> Vec<u64> {
         let mut compacted_data = Vec::with_capacity(capacity as usize);
         let mut current_long = 0u64;
@@ -159,4 +196,290 @@ mod inner {
 
         compacted_data
     }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        393, 401, 404, 477, 480, 485, 490, 498, 735, 736 = 0x11;
+        573, 575, 578, 755, 756 = 0x12;
+    })]
+    pub struct DeclareCommandsPacket {
+        nodes: CommandNodes,
+        #[falcon(var32)]
+        root: i32,
+    }
+
+    impl From<DeclareCommandsSpec> for DeclareCommandsPacket {
+        fn from(spec: DeclareCommandsSpec) -> Self {
+            DeclareCommandsPacket {
+                nodes: CommandNodes(spec.nodes, false),
+                root: spec.root,
+            }
+        }
+    }
+
+    /// The nodes of a declare commands packet, the flag writes the parsers
+    /// as the numeric ids of 1.19 instead of their identifiers.
+    pub(crate) struct CommandNodes(pub(crate) Vec<CommandNodeSpec>, pub(crate) bool);
+
+    const FLAG_LITERAL: u8 = 0x01;
+    const FLAG_ARGUMENT: u8 = 0x02;
+    const FLAG_EXECUTABLE: u8 = 0x04;
+
+    fn parser_identifier(parser: CommandParser) -> &'static str {
+        match parser {
+            CommandParser::Word | CommandParser::Greedy => "brigadier:string",
+            CommandParser::Double => "brigadier:double",
+            CommandParser::Player => "minecraft:entity",
+            CommandParser::GameProfile => "minecraft:game_profile",
+        }
+    }
+
+    fn parser_id(parser: CommandParser) -> i32 {
+        match parser {
+            CommandParser::Word | CommandParser::Greedy => 5,
+            CommandParser::Double => 2,
+            CommandParser::Player => 6,
+            CommandParser::GameProfile => 7,
+        }
+    }
+
+    /// The properties of the parser, written as a single byte or var int.
+    fn parser_properties(parser: CommandParser) -> Option<u8> {
+        match parser {
+            // single word and greedy phrase
+            CommandParser::Word => Some(0),
+            CommandParser::Greedy => Some(2),
+            // no minimum or maximum
+            CommandParser::Double => Some(0),
+            // a single player
+            CommandParser::Player => Some(0x01 | 0x02),
+            CommandParser::GameProfile => None,
+        }
+    }
+
+    impl CommandNodes {
+        fn parser_size(&self, parser: CommandParser) -> usize {
+            let id = if self.1 { VarI32::from(parser_id(parser)).size() } else { PacketString::new(32767).size(&parser_identifier(parser)) };
+            id + parser_properties(parser).map(|_| 1).unwrap_or(0)
+        }
+    }
+
+    impl PacketSize for CommandNodes {
+        fn size(&self) -> usize {
+            VarI32::from(self.0.len()).size()
+                + self
+                    .0
+                    .iter()
+                    .map(|node| {
+                        let kind = match &node.kind {
+                            CommandNodeKind::Root => 0,
+                            CommandNodeKind::Literal(name) => PacketString::new(32767).size(name),
+                            CommandNodeKind::Argument(name, parser) => PacketString::new(32767).size(name) + self.parser_size(*parser),
+                        };
+                        1 + VarI32::from(node.children.len()).size() + node.children.iter().map(|i| VarI32::from(*i).size()).sum::<usize>() + kind
+                    })
+                    .sum::<usize>()
+        }
+    }
+
+    impl PacketWrite for CommandNodes {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            VarI32::from(self.0.len()).write(buffer)?;
+            for node in &self.0 {
+                let flags = match node.kind {
+                    CommandNodeKind::Root => 0,
+                    CommandNodeKind::Literal(_) => FLAG_LITERAL,
+                    CommandNodeKind::Argument(..) => FLAG_ARGUMENT,
+                };
+                (flags | if node.executable { FLAG_EXECUTABLE } else { 0 }).write(buffer)?;
+                VarI32::from(node.children.len()).write(buffer)?;
+                for child in &node.children {
+                    VarI32::from(*child).write(buffer)?;
+                }
+                match &node.kind {
+                    CommandNodeKind::Root => {},
+                    CommandNodeKind::Literal(name) => PacketString::new(32767).write(name, buffer)?,
+                    CommandNodeKind::Argument(name, parser) => {
+                        PacketString::new(32767).write(name, buffer)?;
+                        if self.1 {
+                            VarI32::from(parser_id(*parser)).write(buffer)?;
+                        } else {
+                            PacketString::new(32767).write(&parser_identifier(*parser), buffer)?;
+                        }
+                        if let Some(properties) = parser_properties(*parser) {
+                            properties.write(buffer)?;
+                        }
+                    },
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        393, 401, 404, 477, 480, 485, 490, 498, 573, 575, 578, 755, 756 = 0x07;
+        735, 736 = 0x06;
+        759..=761 = 0x04;
+        763 = 0x05;
+    })]
+    pub struct StatisticsPacket {
+        statistics: Statistics,
+    }
+
+    impl From<StatisticsSpec> for StatisticsPacket {
+        fn from(spec: StatisticsSpec) -> Self {
+            let statistics = spec
+                .statistics
+                .iter()
+                .filter_map(|(statistic, value)| custom_statistic_id(statistic, spec.protocol_version).map(|id| (CATEGORY_CUSTOM, id, *value)))
+                .collect();
+            StatisticsPacket {
+                statistics: Statistics(statistics),
+            }
+        }
+    }
+
+    /// The `minecraft:custom` category, blocks and items are not sent
+    /// until their registries are known.
+    const CATEGORY_CUSTOM: i32 = 8;
+
+    /// The id of a custom statistic, the registry grew in 1.14
+    /// (`time_since_rest`), 1.16 (`strider_one_cm`) and 1.17
+    /// (`total_world_time`).
+    fn custom_statistic_id(statistic: &Statistic, protocol: i32) -> Option<i32> {
+        let (id, after_strider) = match statistic {
+            Statistic::PlayTime => return Some(1),
+            Statistic::WalkOneCm => (4, false),
+            Statistic::Deaths => (27, true),
+            _ => return None,
+        };
+        Some(id + (protocol >= 477) as i32 + (after_strider && protocol >= 735) as i32 + (protocol >= 755) as i32)
+    }
+
+    /// The category, id and value of every statistic.
+    pub(crate) struct Statistics(Vec<(i32, i32, i32)>);
+
+    impl PacketSize for Statistics {
+        fn size(&self) -> usize {
+            VarI32::from(self.0.len()).size()
+                + self
+                    .0
+                    .iter()
+                    .map(|(category, id, value)| VarI32::from(*category).size() + VarI32::from(*id).size() + VarI32::from(*value).size())
+                    .sum::<usize>()
+        }
+    }
+
+    impl PacketWrite for Statistics {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            VarI32::from(self.0.len()).write(buffer)?;
+            for (category, id, value) in &self.0 {
+                VarI32::from(*category).write(buffer)?;
+                VarI32::from(*id).write(buffer)?;
+                VarI32::from(*value).write(buffer)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        393, 401, 404 = 0x54;
+        477, 480, 485, 490, 498, 735, 736 = 0x5A;
+        573, 575, 578 = 0x5B;
+        755, 756 = 0x65;
+        759 = 0x67;
+        760 = 0x6A;
+        761 = 0x69;
+        763 = 0x6D;
+    })]
+    pub struct DeclareRecipesPacket {
+        #[falcon(var32)]
+        recipe_count: i32,
+    }
+
+    impl From<DeclareRecipesSpec> for DeclareRecipesPacket {
+        fn from(_: DeclareRecipesSpec) -> Self { DeclareRecipesPacket { recipe_count: 0 } }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        393, 401, 404 = 0x55;
+        477, 480, 485, 490, 498, 735, 736 = 0x5B;
+        573, 575, 578 = 0x5C;
+    })]
+    pub struct TagsPacket {
+        tags: FixedTagLists,
+    }
+
+    impl From<TagsSpec> for TagsPacket {
+        fn from(spec: TagsSpec) -> Self {
+            // entity types since 1.14
+            let registries: &[&str] = if spec.protocol_version >= 477 { &[BLOCK, ITEM, FLUID, ENTITY_TYPE] } else { &[BLOCK, ITEM, FLUID] };
+            TagsPacket {
+                tags: FixedTagLists(registries.iter().map(|registry| TagList::new(&spec.tags, registry)).collect()),
+            }
+        }
+    }
+
+    /// The tag lists of the registries before 1.17, in a fixed order
+    /// without their names.
+    struct FixedTagLists(Vec<TagList>);
+
+    impl PacketSize for FixedTagLists {
+        fn size(&self) -> usize { self.0.iter().map(PacketSize::size).sum() }
+    }
+
+    impl PacketWrite for FixedTagLists {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            self.0.iter().try_for_each(|tags| tags.write(buffer))
+        }
+    }
+
+    /// The tags of one registry with the ids of their entries.
+    pub(crate) struct TagList(Vec<(String, Vec<i32>)>);
+
+    impl TagList {
+        pub(crate) fn new(tags: &TagRegistry, registry: &str) -> Self { TagList(tags.tags(registry).map(|(tag, ids)| (tag.to_owned(), ids.to_vec())).collect()) }
+    }
+
+    impl PacketSize for TagList {
+        fn size(&self) -> usize {
+            VarI32::from(self.0.len()).size()
+                + self
+                    .0
+                    .iter()
+                    .map(|(tag, ids)| {
+                        PacketString::new(32767).size(tag) + VarI32::from(ids.len()).size() + ids.iter().map(|id| VarI32::from(*id).size()).sum::<usize>()
+                    })
+                    .sum::<usize>()
+        }
+    }
+
+    impl PacketWrite for TagList {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            VarI32::from(self.0.len()).write(buffer)?;
+            for (tag, ids) in &self.0 {
+                PacketString::new(32767).write(tag, buffer)?;
+                VarI32::from(ids.len()).write(buffer)?;
+                for id in ids {
+                    VarI32::from(*id).write(buffer)?;
+                }
+            }
+            Ok(())
+        }
+    }
 }
@@ -1,26 +1,23 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
     use bytes::BufMut;
+    use falcon_core::world::block_states::state_ids;
     use falcon_core::world::blocks::Blocks;
     use falcon_core::world::chunks::{SECTION_HEIGHT, SECTION_LENGTH, SECTION_WIDTH};
-    use falcon_core::world::palette::PaletteToI32;
-    use falcon_packet_core::{PacketArray, PacketIter, PacketSize, PacketVec, PacketWrite, PacketWriteSeed, VarI32, WriteError};
+    use falcon_packet_core::{PacketIter, PacketSize, PacketVec, PacketWrite, PacketWriteSeed, VarI32, WriteError};
     use fastnbt::LongArray;
     use serde::Serialize;
 
+    use crate::section_cache::{encode_section, EncodedSection};
     use crate::specs::play::{ChunkDataSpec, ChunkSectionDataSpec};
-    use crate::util::HeightMap;
-    use crate::v1_13::play::build_compacted_data_array;
+    use crate::util::{biomes_2d, HeightMap};
+    use crate::v1_13::play::{build_compacted_data_array, data_size, data_value, data_write, ChunkColumn};
 
     const MAX_BITS_PER_BLOCK: u8 = 14;
 
-    const BIOME_COUNT: u16 = SECTION_WIDTH * SECTION_LENGTH;
-    const BIOMES: [i32; BIOME_COUNT as usize] = [0; BIOME_COUNT as usize];
-
     #[derive(PacketSize, PacketWrite)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         477, 480, 485, 490, 498 = 0x21;
-    }, name = "chunk_data")]
+    })]
     pub struct ChunkDataPacket {
         chunk_x: i32,
         chunk_z: i32,
@@ -32,7 +29,7 @@ mod inner {
         #[falcon(var32)]
         size: usize,
         #[falcon(link = "size with data")]
-        sections: Vec<ChunkSectionData>,
+        sections: ChunkColumn<EncodedSection>,
         #[falcon(var32)]
         block_entity_num: i32, // default 0
     }
@@ -41,31 +38,22 @@ mod inner {
     pub(crate) struct PacketHeightMap {
         #[serde(rename = "MOTION_BLOCKING")]
         motion_blocking: LongArray,
+        #[serde(rename = "WORLD_SURFACE")]
+        world_surface: LongArray,
     }
 
     impl From<HeightMap> for PacketHeightMap {
         fn from(map: HeightMap) -> Self {
             PacketHeightMap {
-                motion_blocking: LongArray::new(
-                    build_compacted_data_array(9, 36, map.motion_blocking().into_iter().map(|v| v as u64))
-                        .into_iter()
-                        .map(|v| v as i64)
-                        .collect(),
-                ),
+                motion_blocking: pack_heights(map.motion_blocking()),
+                world_surface: pack_heights(map.world_surface()),
             }
         }
     }
 
-    #[inline(always)]
-    #[allow(clippy::ptr_arg)]
-    pub(crate) fn data_value(field: &[ChunkSectionData]) -> usize { data_size(field) }
-
-    #[allow(clippy::ptr_arg)]
-    pub(crate) fn data_size(field: &[ChunkSectionData]) -> usize { PacketIter::new(field.iter()).size_ref() + BIOME_COUNT as usize * 4 }
-
-    pub(crate) fn data_write<B: BufMut + ?Sized>(field: &[ChunkSectionData], buffer: &mut B) -> Result<(), WriteError> {
-        PacketIter::new(field.iter()).write_ref(buffer)?;
-        PacketWriteSeed::write(PacketArray::default(), &BIOMES, buffer)
+    fn pack_heights(heights: &[u16]) -> LongArray {
+        let packed = build_compacted_data_array(9, 36, heights.iter().map(|&height| height as u64));
+        LongArray::new(packed.into_iter().map(|v| v as i64).collect())
     }
 
     impl From<ChunkDataSpec> for ChunkDataPacket {
@@ -75,13 +63,12 @@ mod inner {
                 chunk_z: spec.chunk_z,
                 full_chunk: true,
                 bitmask: spec.bitmask,
-                heightmap: HeightMap::from_sections(&spec.sections, Blocks::get_global_id_1976).into(),
+                heightmap: HeightMap::new(&spec).into(),
                 size: 0,
-                sections: spec
-                    .sections
-                    .into_iter()
-                    .map(|e| into_chunk_section(e, Blocks::get_global_id_1976))
-                    .collect(),
+                sections: ChunkColumn {
+                    biomes: biomes_2d(&spec.biomes),
+                    sections: spec.sections.into_iter().map(|section| encode_section(section, into_chunk_section)).collect(),
+                },
                 block_entity_num: 0,
             }
         }
@@ -125,7 +112,9 @@ mod inner {
         }
     }
 
-    pub(crate) fn into_chunk_section(spec: ChunkSectionDataSpec, block_to_int: PaletteToI32<Blocks>) -> ChunkSectionData {
+    pub(crate) fn into_chunk_section(spec: ChunkSectionDataSpec) -> ChunkSectionData {
+        // chunks are only written for 1.13+, which have ids for every block
+        let block_to_int = state_ids(spec.protocol_version).unwrap_or(Blocks::get_global_id_2730);
         let bits_per_block = {
             let actual = spec.palette.calculate_bits_per_entry(block_to_int);
             if actual < 4 {
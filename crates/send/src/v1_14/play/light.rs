@@ -0,0 +1,70 @@
+falcon_packet_core::packet! {
+    use bytes::BufMut;
+    use falcon_packet_core::{PacketSize, PacketWrite, VarI32, WriteError};
+
+    use crate::specs::play::LightUpdateSpec;
+
+    /// Sections -1 up to and including 16.
+    pub(crate) const LIGHT_SECTIONS: usize = 18;
+    pub(crate) const LIGHT_MASK: i32 = (1 << LIGHT_SECTIONS) - 1;
+    const LIGHT_ARRAY_LEN: usize = 2048;
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        477, 480, 485, 490, 498 = 0x24;
+        573, 575, 578 = 0x25;
+    })]
+    pub struct UpdateLightPacket {
+        #[falcon(var32)]
+        chunk_x: i32,
+        #[falcon(var32)]
+        chunk_z: i32,
+        #[falcon(var32)]
+        sky_light_mask: i32,
+        #[falcon(var32)]
+        block_light_mask: i32,
+        #[falcon(var32)]
+        empty_sky_light_mask: i32,
+        #[falcon(var32)]
+        empty_block_light_mask: i32,
+        sky_light: FullLightArrays,
+    }
+
+    impl From<LightUpdateSpec> for UpdateLightPacket {
+        fn from(spec: LightUpdateSpec) -> Self {
+            UpdateLightPacket {
+                chunk_x: spec.chunk_x,
+                chunk_z: spec.chunk_z,
+                sky_light_mask: LIGHT_MASK,
+                block_light_mask: 0,
+                empty_sky_light_mask: 0,
+                empty_block_light_mask: LIGHT_MASK,
+                sky_light: FullLightArrays(LIGHT_SECTIONS),
+            }
+        }
+    }
+
+    /// A number of light arrays with every value set to the maximum light
+    /// level, there is no light engine yet.
+    pub(crate) struct FullLightArrays(pub(crate) usize);
+
+    impl PacketSize for FullLightArrays {
+        fn size(&self) -> usize { self.0 * (VarI32::from(LIGHT_ARRAY_LEN).size() + LIGHT_ARRAY_LEN) }
+    }
+
+    impl PacketWrite for FullLightArrays {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            for _ in 0..self.0 {
+                VarI32::from(LIGHT_ARRAY_LEN).write(buffer)?;
+                if buffer.remaining_mut() < LIGHT_ARRAY_LEN {
+                    return Err(WriteError::EndOfBuffer);
+                }
+                buffer.put_bytes(0xFF, LIGHT_ARRAY_LEN);
+            }
+            Ok(())
+        }
+    }
+}
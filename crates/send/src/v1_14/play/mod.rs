@@ -1,19 +1,22 @@
 mod chunk;
+mod light;
 
 pub use chunk::*;
+pub use light::*;
 
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
     use derive_from_ext::From;
-    use falcon_packet_core::{PacketSize, PacketWrite};
+    use falcon_packet_core::{Angle, PacketSize, PacketWrite};
+    use uuid::Uuid;
 
-    use crate::{JoinGameSpec, ServerDifficultySpec};
+    use crate::util::position_xzy;
+    use crate::{BlockChangeSpec, JoinGameSpec, RespawnSpec, ServerDifficultySpec, SpawnEntitySpec, SpawnPositionSpec};
 
     #[derive(PacketSize, PacketWrite, From)]
     #[from(JoinGameSpec)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         477, 480, 485, 490, 498 = 0x25;
-    }, name = "join_game")]
+    })]
     pub struct JoinGamePacket {
         entity_id: i32,
         game_mode: u8,
@@ -28,20 +31,27 @@ mod inner {
 
     #[derive(PacketSize, PacketWrite, From)]
     #[from(ServerDifficultySpec)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         477, 480, 485, 490, 498, 735, 736 = 0x0D;
-        573, 575, 578 = 0x0E;
-    }, name = "difficulty")]
+        573, 575, 578, 755, 756 = 0x0E;
+        759..=761 = 0x0B;
+        763 = 0x0C;
+    })]
     pub struct ServerDifficultyPacket {
         difficulty: u8,
         locked: bool,
     }
 
     #[derive(PacketSize, PacketWrite)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         477, 480, 485, 490, 498, 735, 736 = 0x40;
         573, 575, 578 = 0x41;
-    }, name = "update_viewpos")]
+        755, 756 = 0x49;
+        759 = 0x48;
+        760 = 0x4B;
+        761 = 0x4A;
+        763 = 0x4E;
+    })]
     pub struct UpdateViewPosition {
         #[falcon(var32)]
         chunk_x: i32,
@@ -52,4 +62,136 @@ mod inner {
     impl From<(i32, i32)> for UpdateViewPosition {
         fn from((chunk_x, chunk_z): (i32, i32)) -> Self { UpdateViewPosition { chunk_x, chunk_z } }
     }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        477, 480, 485, 490, 498, 573, 575, 578, 735, 736, 755, 756 = 0x00;
+    })]
+    pub struct SpawnEntityPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        uuid: Uuid,
+        #[falcon(var32)]
+        entity_type: i32,
+        x: f64,
+        y: f64,
+        z: f64,
+        pitch: Angle,
+        yaw: Angle,
+        data: i32,
+        velocity_x: i16,
+        velocity_y: i16,
+        velocity_z: i16,
+    }
+
+    impl From<SpawnEntitySpec> for SpawnEntityPacket {
+        fn from(spec: SpawnEntitySpec) -> Self {
+            SpawnEntityPacket {
+                entity_id: spec.entity_id,
+                uuid: spec.uuid,
+                entity_type: spec.type_id().unwrap_or_default(),
+                x: spec.position.x,
+                y: spec.position.y,
+                z: spec.position.z,
+                pitch: spec.look.pitch.into(),
+                yaw: spec.look.yaw.into(),
+                data: spec.data,
+                velocity_x: 0,
+                velocity_y: 0,
+                velocity_z: 0,
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        477, 480, 485, 490, 498, 573, 575, 578 = 0x03;
+        735, 736, 755, 756 = 0x02;
+    })]
+    pub struct SpawnLivingEntityPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        uuid: Uuid,
+        #[falcon(var32)]
+        entity_type: i32,
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: Angle,
+        pitch: Angle,
+        head_yaw: Angle,
+        velocity_x: i16,
+        velocity_y: i16,
+        velocity_z: i16,
+    }
+
+    impl From<SpawnEntitySpec> for SpawnLivingEntityPacket {
+        fn from(spec: SpawnEntitySpec) -> Self {
+            SpawnLivingEntityPacket {
+                entity_id: spec.entity_id,
+                uuid: spec.uuid,
+                entity_type: spec.type_id().unwrap_or_default(),
+                x: spec.position.x,
+                y: spec.position.y,
+                z: spec.position.z,
+                yaw: spec.look.yaw.into(),
+                pitch: spec.look.pitch.into(),
+                head_yaw: spec.look.yaw.into(),
+                velocity_x: 0,
+                velocity_y: 0,
+                velocity_z: 0,
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(RespawnSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        477, 480, 485, 490, 498 = 0x3A;
+    })]
+    pub struct RespawnPacket {
+        dimension: i32,
+        game_mode: u8,
+        #[falcon(string = 16)]
+        level_type: String,
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        477, 480, 485, 490, 498 = 0x4D;
+        573, 575, 578 = 0x4E;
+        735, 736 = 0x42;
+    })]
+    pub struct SpawnPositionPacket {
+        position: i64,
+    }
+
+    impl From<SpawnPositionSpec> for SpawnPositionPacket {
+        fn from(spec: SpawnPositionSpec) -> Self {
+            SpawnPositionPacket {
+                position: position_xzy(spec.position),
+            }
+        }
+    }
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        477, 480, 485, 490, 498, 735, 736 = 0x0B;
+        573, 575, 578, 755, 756 = 0x0C;
+        759..=761 = 0x09;
+        763 = 0x0A;
+    })]
+    pub struct BlockChangePacket {
+        position: i64,
+        #[falcon(var32)]
+        block_id: i32,
+    }
+
+    impl From<BlockChangeSpec> for BlockChangePacket {
+        fn from(spec: BlockChangeSpec) -> Self {
+            BlockChangePacket {
+                position: position_xzy(spec.position),
+                block_id: spec.block_id,
+            }
+        }
+    }
 }
@@ -1,20 +1,18 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
     use bytes::BufMut;
-    use falcon_core::world::blocks::Blocks;
+    use falcon_core::world::chunks::BIOME_CELLS;
     use falcon_packet_core::{PacketIter, PacketSize, PacketWrite, WriteError};
 
+    use crate::section_cache::{encode_section, EncodedSection};
     use crate::specs::play::ChunkDataSpec;
-    use crate::util::HeightMap;
-    use crate::v1_14::play::{into_chunk_section, ChunkSectionData, PacketHeightMap};
+    use crate::util::{biomes_3d, HeightMap};
+    use crate::v1_14::play::{into_chunk_section, PacketHeightMap};
 
-    const BIOME_COUNT: u16 = 1024;
-    const BIOMES: [i32; BIOME_COUNT as usize] = [0; BIOME_COUNT as usize];
 
     #[derive(PacketSize, PacketWrite)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         573, 575, 578 = 0x22;
-    }, name = "chunk_data")]
+    })]
     pub struct ChunkDataPacket {
         chunk_x: i32,
         chunk_z: i32,
@@ -24,23 +22,23 @@ mod inner {
         #[falcon(nbt)]
         heightmap: PacketHeightMap,
         #[falcon(array)]
-        biomes: [i32; BIOME_COUNT as usize],
+        biomes: [i32; BIOME_CELLS],
         #[falcon(var32)]
         size: usize,
         #[falcon(link = "size with data")]
-        sections: Vec<ChunkSectionData>,
+        sections: Vec<EncodedSection>,
         #[falcon(var32)]
         block_entity_num: i32, // default 0
     }
 
     #[inline(always)]
     #[allow(clippy::ptr_arg)]
-    pub(crate) fn data_value(field: &[ChunkSectionData]) -> usize { data_size(field) }
+    pub(crate) fn data_value(field: &[EncodedSection]) -> usize { data_size(field) }
 
     #[allow(clippy::ptr_arg)]
-    pub(crate) fn data_size(field: &[ChunkSectionData]) -> usize { PacketIter::new(field.iter()).size_ref() }
+    pub(crate) fn data_size(field: &[EncodedSection]) -> usize { PacketIter::new(field.iter()).size_ref() }
 
-    pub(crate) fn data_write<B: BufMut + ?Sized>(field: &[ChunkSectionData], buffer: &mut B) -> Result<(), WriteError> {
+    pub(crate) fn data_write<B: BufMut + ?Sized>(field: &[EncodedSection], buffer: &mut B) -> Result<(), WriteError> {
         PacketIter::new(field.iter()).write_ref(buffer)
     }
 
@@ -51,14 +49,10 @@ mod inner {
                 chunk_z: spec.chunk_z,
                 full_chunk: true,
                 bitmask: spec.bitmask,
-                heightmap: HeightMap::from_sections(&spec.sections, Blocks::get_global_id_2230).into(),
-                biomes: BIOMES,
+                heightmap: HeightMap::new(&spec).into(),
+                biomes: biomes_3d(&spec.biomes),
                 size: 0,
-                sections: spec
-                    .sections
-                    .into_iter()
-                    .map(|e| into_chunk_section(e, Blocks::get_global_id_2230))
-                    .collect(),
+                sections: spec.sections.into_iter().map(|section| encode_section(section, into_chunk_section)).collect(),
                 block_entity_num: 0,
             }
         }
@@ -1,18 +1,19 @@
 mod chunk;
 
 pub use chunk::*;
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
     use derive_from_ext::From;
-    use falcon_packet_core::{PacketSize, PacketWrite};
+    use falcon_packet_core::{Angle, PacketSize, PacketWrite};
+    use uuid::Uuid;
 
+    use crate::specs::play::{RespawnSpec, SpawnPlayerSpec};
     use crate::JoinGameSpec;
 
     #[derive(PacketSize, PacketWrite, From)]
     #[from(JoinGameSpec)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         573, 575, 578 = 0x26;
-    }, name = "join_game")]
+    })]
     pub struct JoinGamePacket {
         entity_id: i32,
         game_mode: u8,
@@ -26,4 +27,50 @@ mod inner {
         reduced_debug: bool,
         enable_respawn_screen: bool,
     }
+
+    /// The metadata is no longer part of the packet.
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        573, 575, 578 = 0x05;
+        735, 736, 755, 756 = 0x04;
+        759, 760, 761 = 0x02;
+        763 = 0x03;
+    })]
+    pub struct SpawnPlayerPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        uuid: Uuid,
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: Angle,
+        pitch: Angle,
+    }
+
+    impl From<SpawnPlayerSpec> for SpawnPlayerPacket {
+        fn from(spec: SpawnPlayerSpec) -> Self {
+            SpawnPlayerPacket {
+                entity_id: spec.entity_id,
+                uuid: spec.uuid,
+                x: spec.position.x,
+                y: spec.position.y,
+                z: spec.position.z,
+                yaw: spec.look.yaw.into(),
+                pitch: spec.look.pitch.into(),
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(RespawnSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        573, 575, 578 = 0x3B;
+    })]
+    pub struct RespawnPacket {
+        dimension: i32,
+        hashed_seed: i64,
+        game_mode: u8,
+        #[falcon(string = 16)]
+        level_type: String,
+    }
 }
@@ -1,19 +1,25 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
-    use derive_from_ext::From;
+falcon_packet_core::packet! {
     use falcon_packet_core::{PacketSize, PacketWrite};
     use uuid::Uuid;
 
     use crate::LoginSuccessSpec;
 
-    #[derive(PacketSize, PacketWrite, From)]
-    #[from(LoginSuccessSpec)]
-    #[falcon_packet(versions = {
-        735, 736 = 0x02;
-    }, name = "login_success")]
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = login, direction = clientbound, versions = {
+        735, 736, 755, 756 = 0x02;
+    })]
     pub struct LoginSuccessPacket {
         uuid: Uuid,
         #[falcon(string = 16)]
         username: String,
     }
+
+    impl From<LoginSuccessSpec> for LoginSuccessPacket {
+        fn from(spec: LoginSuccessSpec) -> Self {
+            LoginSuccessPacket {
+                uuid: spec.profile.uuid,
+                username: spec.profile.name,
+            }
+        }
+    }
 }
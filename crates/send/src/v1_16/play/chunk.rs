@@ -1,26 +1,23 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
     use bytes::BufMut;
+    use falcon_core::world::block_states::state_ids;
     use falcon_core::world::blocks::Blocks;
-    use falcon_core::world::chunks::{SECTION_HEIGHT, SECTION_LENGTH, SECTION_WIDTH};
-    use falcon_core::world::palette::PaletteToI32;
+    use falcon_core::world::chunks::{BIOME_CELLS, SECTION_HEIGHT, SECTION_LENGTH, SECTION_WIDTH};
     use falcon_packet_core::{PacketIter, PacketSize, PacketWrite, WriteError};
     use fastnbt::LongArray;
     use serde::Serialize;
 
-    use crate::util::HeightMap;
+    use crate::section_cache::{encode_section, EncodedSection};
+    use crate::util::{biomes_3d, HeightMap};
     use crate::v1_14::play::ChunkSectionData;
     use crate::{ChunkDataSpec, ChunkSectionDataSpec};
 
     const MAX_BITS_PER_BLOCK: u8 = 15;
 
-    const BIOME_COUNT: u16 = 1024;
-    const BIOMES: [i32; BIOME_COUNT as usize] = [0; BIOME_COUNT as usize];
-
     #[derive(PacketSize, PacketWrite)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         735, 736 = 0x21;
-    }, name = "chunk_data")]
+    })]
     pub struct ChunkDataPacket {
         chunk_x: i32,
         chunk_z: i32,
@@ -31,11 +28,11 @@ mod inner {
         #[falcon(nbt)]
         heightmap: PacketHeightMap,
         #[falcon(array)]
-        biomes: [i32; BIOME_COUNT as usize],
+        biomes: [i32; BIOME_CELLS],
         #[falcon(var32)]
         size: usize,
         #[falcon(link = "size with data")]
-        sections: Vec<ChunkSectionData>,
+        sections: Vec<EncodedSection>,
         #[falcon(var32)]
         block_entity_num: i32,
     }
@@ -44,29 +41,32 @@ mod inner {
     pub(crate) struct PacketHeightMap {
         #[serde(rename = "MOTION_BLOCKING")]
         motion_blocking: LongArray,
+        #[serde(rename = "WORLD_SURFACE")]
+        world_surface: LongArray,
     }
 
     impl From<HeightMap> for PacketHeightMap {
         fn from(map: HeightMap) -> Self {
             PacketHeightMap {
-                motion_blocking: LongArray::new(
-                    build_compacted_data_array(9, 37, map.motion_blocking().into_iter().map(|v| v as u64))
-                        .into_iter()
-                        .map(|v| v as i64)
-                        .collect(),
-                ),
+                motion_blocking: pack_heights(map.motion_blocking()),
+                world_surface: pack_heights(map.world_surface()),
             }
         }
     }
 
+    fn pack_heights(heights: &[u16]) -> LongArray {
+        let packed = build_compacted_data_array(9, 37, heights.iter().map(|&height| height as u64));
+        LongArray::new(packed.into_iter().map(|v| v as i64).collect())
+    }
+
     #[inline(always)]
     #[allow(clippy::ptr_arg)]
-    pub(crate) fn data_value(field: &[ChunkSectionData]) -> usize { data_size(field) }
+    pub(crate) fn data_value(field: &[EncodedSection]) -> usize { data_size(field) }
 
     #[allow(clippy::ptr_arg)]
-    pub(crate) fn data_size(field: &[ChunkSectionData]) -> usize { PacketIter::new(field.iter()).size_ref() }
+    pub(crate) fn data_size(field: &[EncodedSection]) -> usize { PacketIter::new(field.iter()).size_ref() }
 
-    pub(crate) fn data_write<B: BufMut + ?Sized>(field: &[ChunkSectionData], buffer: &mut B) -> Result<(), WriteError> {
+    pub(crate) fn data_write<B: BufMut + ?Sized>(field: &[EncodedSection], buffer: &mut B) -> Result<(), WriteError> {
         PacketIter::new(field.iter()).write_ref(buffer)
     }
 
@@ -78,20 +78,18 @@ mod inner {
                 full_chunk: true,
                 ignore_old: false,
                 bitmask: spec.bitmask,
-                heightmap: HeightMap::from_sections(&spec.sections, Blocks::get_global_id_2567).into(),
-                biomes: BIOMES,
+                heightmap: HeightMap::new(&spec).into(),
+                biomes: biomes_3d(&spec.biomes),
                 size: 0,
-                sections: spec
-                    .sections
-                    .into_iter()
-                    .map(|e| into_chunk_section(e, Blocks::get_global_id_2567))
-                    .collect(),
+                sections: spec.sections.into_iter().map(|section| encode_section(section, into_chunk_section)).collect(),
                 block_entity_num: 0,
             }
         }
     }
 
-    pub(crate) fn into_chunk_section(spec: ChunkSectionDataSpec, block_to_int: PaletteToI32<Blocks>) -> ChunkSectionData {
+    pub(crate) fn into_chunk_section(spec: ChunkSectionDataSpec) -> ChunkSectionData {
+        // chunks are only written for 1.13+, which have ids for every block
+        let block_to_int = state_ids(spec.protocol_version).unwrap_or(Blocks::get_global_id_2730);
         let bits_per_block = {
             let actual = spec.palette.calculate_bits_per_entry(block_to_int);
             if actual < 4 {
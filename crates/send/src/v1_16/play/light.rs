@@ -0,0 +1,42 @@
+falcon_packet_core::packet! {
+    use falcon_packet_core::{PacketSize, PacketWrite};
+
+    use crate::specs::play::LightUpdateSpec;
+    use crate::v1_14::play::{FullLightArrays, LIGHT_MASK, LIGHT_SECTIONS};
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        735, 736 = 0x24;
+    })]
+    pub struct UpdateLightPacket {
+        #[falcon(var32)]
+        chunk_x: i32,
+        #[falcon(var32)]
+        chunk_z: i32,
+        trust_edges: bool,
+        #[falcon(var32)]
+        sky_light_mask: i32,
+        #[falcon(var32)]
+        block_light_mask: i32,
+        #[falcon(var32)]
+        empty_sky_light_mask: i32,
+        #[falcon(var32)]
+        empty_block_light_mask: i32,
+        sky_light: FullLightArrays,
+    }
+
+    impl From<LightUpdateSpec> for UpdateLightPacket {
+        fn from(spec: LightUpdateSpec) -> Self {
+            UpdateLightPacket {
+                chunk_x: spec.chunk_x,
+                chunk_z: spec.chunk_z,
+                trust_edges: true,
+                sky_light_mask: LIGHT_MASK,
+                block_light_mask: 0,
+                empty_sky_light_mask: 0,
+                empty_block_light_mask: LIGHT_MASK,
+                sky_light: FullLightArrays(LIGHT_SECTIONS),
+            }
+        }
+    }
+}
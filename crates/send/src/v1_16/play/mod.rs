@@ -1,24 +1,27 @@
 mod chunk;
 pub mod dimension;
+mod light;
 
 pub use chunk::*;
+pub use light::*;
 
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
     use bytes::BufMut;
     use derive_from_ext::From;
+    use falcon_core::chat::ChatComponent;
     use falcon_core::data::Identifier;
     use falcon_core::world::dimension::Dimension;
-    use falcon_packet_core::{PacketSize, PacketSizeSeed, PacketString, PacketWrite, PacketWriteSeed, WriteError};
+    use falcon_packet_core::{PacketSize, PacketWrite, WriteError};
+    use uuid::Uuid;
 
     use crate::v1_16::play::dimension::{Codec, DimensionData};
-    use crate::JoinGameSpec;
+    use crate::{JoinGameSpec, RespawnSpec};
 
     #[derive(PacketSize, PacketWrite, From)]
     #[from(JoinGameSpec)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         735, 736 = 0x25;
-    }, name = "join_game")]
+    })]
     pub struct JoinGamePacket {
         entity_id: i32,
         game_mode: u8,
@@ -34,10 +37,8 @@ mod inner {
         #[falcon(nbt)]
         dimension_codec: Codec,
         #[from(skip, default = "init_dimension()")]
-        #[falcon(to_string)]
         dimention: Identifier,
         #[from(skip, default = "init_world()")]
-        #[falcon(to_string)]
         world_name: Identifier,
         hashed_seed: i64,
         max_players: u8,
@@ -53,19 +54,14 @@ mod inner {
 
     fn worlds_value(field: &[Identifier]) -> usize { field.len() }
 
-    fn worlds_size(field: &[Identifier]) -> usize {
-        field
-            .iter()
-            .map(|i| PacketSizeSeed::size(PacketString::new(32767), &i.to_string()))
-            .sum::<usize>()
-    }
+    fn worlds_size(field: &[Identifier]) -> usize { field.iter().map(PacketSize::size).sum() }
 
     fn worlds_write<B>(field: &[Identifier], buffer: &mut B) -> Result<(), WriteError>
     where
         B: BufMut + ?Sized,
     {
         for ident in field {
-            PacketWriteSeed::write(PacketString::new(32767), &ident.to_string(), buffer)?;
+            ident.write(buffer)?;
         }
         Ok(())
     }
@@ -77,4 +73,49 @@ mod inner {
     fn init_dimension_codec() -> Codec { Codec::new(vec![DimensionData::new(Dimension::new("minecraft:overworld", 0))]) }
 
     fn init_dimension() -> Identifier { Identifier::from_static("minecraft", "overworld") }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        735, 736 = 0x0E;
+        755, 756 = 0x0F;
+    })]
+    pub struct SystemChatPacket {
+        #[falcon(string = 262144)]
+        message: String,
+        position: i8,
+        sender: Uuid,
+    }
+
+    impl From<ChatComponent> for SystemChatPacket {
+        fn from(message: ChatComponent) -> Self {
+            SystemChatPacket {
+                message: serde_json::to_string(&message).expect("Invalid message data"),
+                // system message
+                position: 1,
+                sender: Uuid::nil(),
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(RespawnSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        735, 736 = 0x3A;
+    })]
+    pub struct RespawnPacket {
+        #[from(skip, default = "init_dimension()")]
+        dimension_type: Identifier,
+        #[from(skip, default = "init_world()")]
+        world_name: Identifier,
+        hashed_seed: i64,
+        game_mode: u8,
+        #[from(rename = "game_mode")]
+        prev_gamemode: u8,
+        #[from(skip)]
+        is_debug: bool,
+        #[from(skip)]
+        is_flat: bool,
+        #[from(skip)]
+        copy_metadata: bool,
+    }
 }
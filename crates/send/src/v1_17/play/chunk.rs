@@ -0,0 +1,59 @@
+falcon_packet_core::packet! {
+    use bytes::BufMut;
+    use falcon_core::world::biome::Biome;
+    use falcon_packet_core::{PacketBitSet, PacketSize, PacketWrite, VarI32, WriteError};
+
+    use crate::section_cache::{encode_section, EncodedSection};
+    use crate::specs::play::ChunkDataSpec;
+    use crate::util::HeightMap;
+    use crate::v1_16::play::{data_size, data_value, data_write, into_chunk_section, PacketHeightMap};
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        755, 756 = 0x22;
+    })]
+    pub struct ChunkDataPacket {
+        chunk_x: i32,
+        chunk_z: i32,
+        bitmask: PacketBitSet,
+        #[falcon(nbt)]
+        heightmap: PacketHeightMap,
+        #[falcon(var32)]
+        biome_count: usize,
+        #[falcon(link = "biome_count with biomes")]
+        biomes: Vec<i32>,
+        #[falcon(var32)]
+        size: usize,
+        #[falcon(link = "size with data")]
+        sections: Vec<EncodedSection>,
+        #[falcon(var32)]
+        block_entity_num: i32,
+    }
+
+    fn biomes_value(field: &[i32]) -> usize { field.len() }
+
+    fn biomes_size(field: &[i32]) -> usize { field.iter().map(|&b| VarI32::from(b).size()).sum() }
+
+    fn biomes_write<B: BufMut + ?Sized>(field: &[i32], buffer: &mut B) -> Result<(), WriteError> {
+        for &biome in field {
+            VarI32::from(biome).write(buffer)?;
+        }
+        Ok(())
+    }
+
+    impl From<ChunkDataSpec> for ChunkDataPacket {
+        fn from(spec: ChunkDataSpec) -> Self {
+            ChunkDataPacket {
+                chunk_x: spec.chunk_x,
+                chunk_z: spec.chunk_z,
+                bitmask: PacketBitSet::from(spec.bitmask as u64),
+                heightmap: HeightMap::new(&spec).into(),
+                biome_count: 0,
+                biomes: spec.biomes.iter().map(Biome::registry_id).collect(),
+                size: 0,
+                sections: spec.sections.into_iter().map(|section| encode_section(section, into_chunk_section)).collect(),
+                block_entity_num: 0,
+            }
+        }
+    }
+}
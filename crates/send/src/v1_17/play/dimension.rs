@@ -0,0 +1,133 @@
+use falcon_core::world::biome;
+use falcon_core::world::dimension::Dimension;
+use serde::Serialize;
+
+/// The registries sent to 1.16.2+ clients, 1.17 adds the vertical bounds of
+/// every dimension type.
+#[derive(Serialize)]
+pub struct Codec {
+    #[serde(rename = "minecraft:dimension_type")]
+    dimension_types: Registry<DimensionType>,
+    #[serde(rename = "minecraft:worldgen/biome")]
+    biomes: Registry<Biome>,
+}
+
+impl Codec {
+    pub fn new(dimensions: &[Dimension]) -> Self {
+        Codec {
+            dimension_types: Registry::new(
+                "minecraft:dimension_type",
+                dimensions.iter().map(|d| RegistryEntry::new(d.name(), d.id(), DimensionType::new(d))).collect(),
+            ),
+            biomes: Registry::new("minecraft:worldgen/biome", biome::Biome::ALL.iter().map(|b| RegistryEntry::new(b.name(), b.registry_id(), Biome::from(b))).collect()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Registry<T> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    value: Vec<RegistryEntry<T>>,
+}
+
+impl<T> Registry<T> {
+    fn new(kind: &'static str, value: Vec<RegistryEntry<T>>) -> Self { Registry { kind, value } }
+}
+
+#[derive(Serialize)]
+struct RegistryEntry<T> {
+    name: String,
+    id: i32,
+    element: T,
+}
+
+impl<T> RegistryEntry<T> {
+    fn new(name: &str, id: i32, element: T) -> Self {
+        RegistryEntry {
+            name: name.to_owned(),
+            id,
+            element,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DimensionType {
+    piglin_safe: bool,
+    natural: bool,
+    ambient_light: f32,
+    infiniburn: &'static str,
+    respawn_anchor_works: bool,
+    has_skylight: bool,
+    bed_works: bool,
+    effects: String,
+    has_raids: bool,
+    min_y: i32,
+    height: i32,
+    logical_height: i32,
+    coordinate_scale: f64,
+    ultrawarm: bool,
+    has_ceiling: bool,
+}
+
+impl DimensionType {
+    pub fn new(dimension: &Dimension) -> Self {
+        DimensionType {
+            piglin_safe: false,
+            natural: true,
+            ambient_light: 0.0,
+            infiniburn: "minecraft:infiniburn_overworld",
+            respawn_anchor_works: false,
+            has_skylight: true,
+            bed_works: true,
+            effects: dimension.name().to_owned(),
+            has_raids: true,
+            min_y: 0,
+            height: 256,
+            logical_height: 256,
+            coordinate_scale: 1.0,
+            ultrawarm: false,
+            has_ceiling: false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Biome {
+    precipitation: &'static str,
+    depth: f32,
+    temperature: f32,
+    scale: f32,
+    downfall: f32,
+    category: &'static str,
+    effects: BiomeEffects,
+}
+
+impl From<&biome::Biome> for Biome {
+    fn from(biome: &biome::Biome) -> Self {
+        let properties = biome.properties();
+        Biome {
+            precipitation: properties.precipitation,
+            depth: 0.125,
+            temperature: properties.temperature,
+            scale: 0.05,
+            downfall: properties.downfall,
+            category: properties.category,
+            effects: BiomeEffects {
+                sky_color: properties.sky_color,
+                water_fog_color: properties.water_fog_color,
+                fog_color: properties.fog_color,
+                water_color: properties.water_color,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BiomeEffects {
+    sky_color: i32,
+    water_fog_color: i32,
+    fog_color: i32,
+    water_color: i32,
+}
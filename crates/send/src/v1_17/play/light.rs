@@ -0,0 +1,44 @@
+falcon_packet_core::packet! {
+    use falcon_packet_core::{PacketBitSet, PacketSize, PacketWrite};
+
+    use crate::specs::play::LightUpdateSpec;
+    use crate::v1_14::play::{FullLightArrays, LIGHT_MASK, LIGHT_SECTIONS};
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        755, 756 = 0x25;
+    })]
+    pub struct UpdateLightPacket {
+        #[falcon(var32)]
+        chunk_x: i32,
+        #[falcon(var32)]
+        chunk_z: i32,
+        trust_edges: bool,
+        sky_light_mask: PacketBitSet,
+        block_light_mask: PacketBitSet,
+        empty_sky_light_mask: PacketBitSet,
+        empty_block_light_mask: PacketBitSet,
+        #[falcon(var32)]
+        sky_light_count: usize,
+        sky_light: FullLightArrays,
+        #[falcon(var32)]
+        block_light_count: usize,
+    }
+
+    impl From<LightUpdateSpec> for UpdateLightPacket {
+        fn from(spec: LightUpdateSpec) -> Self {
+            UpdateLightPacket {
+                chunk_x: spec.chunk_x,
+                chunk_z: spec.chunk_z,
+                trust_edges: true,
+                sky_light_mask: PacketBitSet::from(LIGHT_MASK as u64),
+                block_light_mask: PacketBitSet::new(),
+                empty_sky_light_mask: PacketBitSet::new(),
+                empty_block_light_mask: PacketBitSet::from(LIGHT_MASK as u64),
+                sky_light_count: LIGHT_SECTIONS,
+                sky_light: FullLightArrays(LIGHT_SECTIONS),
+                block_light_count: 0,
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+mod chunk;
+pub mod dimension;
+mod light;
+
+pub use chunk::*;
+pub use light::*;
+
+falcon_packet_core::packet! {
+    use bytes::BufMut;
+    use derive_from_ext::From;
+    use falcon_core::data::Identifier;
+    use falcon_core::player::data::TeleportFlags;
+    use falcon_core::world::dimension::Dimension;
+    use falcon_packet_core::{PacketSize, PacketSizeSeed, PacketString, PacketWrite, PacketWriteSeed, VarI32, WriteError};
+
+    use crate::specs::play::{PositionAndLookSpec, TagsSpec};
+    use crate::v1_13::play::TagList;
+    use crate::v1_17::play::dimension::{Codec, DimensionType};
+    use crate::util::position_xzy;
+    use crate::{JoinGameSpec, RespawnSpec, SpawnPositionSpec};
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(JoinGameSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        755, 756 = 0x26;
+    })]
+    pub struct JoinGamePacket {
+        entity_id: i32,
+        #[from(skip)]
+        is_hardcore: bool,
+        game_mode: u8,
+        #[from(skip, default = "-1")]
+        prev_gamemode: i8,
+        #[from(skip)]
+        #[falcon(var32)]
+        world_count: usize,
+        #[from(skip, default = "init_worlds()")]
+        #[falcon(link = "world_count with worlds")]
+        worlds: Vec<Identifier>,
+        #[from(skip, default = "Codec::new(&[overworld()])")]
+        #[falcon(nbt)]
+        dimension_codec: Codec,
+        #[from(skip, default = "DimensionType::new(&overworld())")]
+        #[falcon(nbt)]
+        dimension: DimensionType,
+        #[from(skip, default = "init_world()")]
+        world_name: Identifier,
+        hashed_seed: i64,
+        #[from(map = "u8_to_i32")]
+        #[falcon(var32)]
+        max_players: i32,
+        #[falcon(var32)]
+        view_distance: i32,
+        reduced_debug: bool,
+        enable_respawn_screen: bool,
+        #[from(skip)]
+        is_debug: bool,
+        #[from(skip)]
+        is_flat: bool,
+    }
+
+    fn u8_to_i32(n: u8) -> i32 { n as i32 }
+
+    fn worlds_value(field: &[Identifier]) -> usize { field.len() }
+
+    fn worlds_size(field: &[Identifier]) -> usize { field.iter().map(PacketSize::size).sum() }
+
+    fn worlds_write<B>(field: &[Identifier], buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        for ident in field {
+            ident.write(buffer)?;
+        }
+        Ok(())
+    }
+
+    fn init_worlds() -> Vec<Identifier> { vec![init_world()] }
+
+    fn init_world() -> Identifier { Identifier::from_static("falcon", "world") }
+
+    fn overworld() -> Dimension { Dimension::new("minecraft:overworld", 0) }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(PositionAndLookSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        755, 756 = 0x38;
+        759 = 0x36;
+        760 = 0x39;
+        761 = 0x38;
+    })]
+    pub struct PositionLookPacket {
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: f32,
+        pitch: f32,
+        #[falcon(bitflags)]
+        flags: TeleportFlags,
+        #[falcon(var32)]
+        teleport_id: i32,
+        #[from(skip)]
+        dismount_vehicle: bool,
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        755, 756 = 0x66;
+        759 = 0x68;
+        760 = 0x6B;
+        761 = 0x6A;
+        763 = 0x6E;
+    })]
+    pub struct TagsPacket {
+        registries: RegistryTags,
+    }
+
+    impl From<TagsSpec> for TagsPacket {
+        fn from(spec: TagsSpec) -> Self {
+            let registries = spec.tags.registries().map(|registry| (registry.to_owned(), TagList::new(&spec.tags, registry))).collect();
+            TagsPacket {
+                registries: RegistryTags(registries),
+            }
+        }
+    }
+
+    /// The tag lists of any registry with the name of the registry.
+    struct RegistryTags(Vec<(String, TagList)>);
+
+    impl PacketSize for RegistryTags {
+        fn size(&self) -> usize {
+            VarI32::from(self.0.len()).size() + self.0.iter().map(|(registry, tags)| PacketString::new(32767).size(registry) + tags.size()).sum::<usize>()
+        }
+    }
+
+    impl PacketWrite for RegistryTags {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            VarI32::from(self.0.len()).write(buffer)?;
+            for (registry, tags) in &self.0 {
+                PacketString::new(32767).write(registry, buffer)?;
+                tags.write(buffer)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Removes a single entity, only 1.17.
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        755 = 0x3A;
+    })]
+    pub struct DestroyEntityPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+    }
+
+    impl From<i32> for DestroyEntityPacket {
+        fn from(entity_id: i32) -> Self { DestroyEntityPacket { entity_id } }
+    }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(RespawnSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        755, 756 = 0x3D;
+    })]
+    pub struct RespawnPacket {
+        #[from(skip, default = "DimensionType::new(&overworld())")]
+        #[falcon(nbt)]
+        dimension: DimensionType,
+        #[from(skip, default = "init_world()")]
+        world_name: Identifier,
+        hashed_seed: i64,
+        game_mode: u8,
+        #[from(skip, default = "-1")]
+        prev_gamemode: i8,
+        #[from(skip)]
+        is_debug: bool,
+        #[from(skip)]
+        is_flat: bool,
+        #[from(skip)]
+        copy_metadata: bool,
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        755, 756 = 0x4B;
+        759 = 0x4A;
+        760 = 0x4D;
+        761 = 0x4C;
+        763 = 0x50;
+    })]
+    pub struct SpawnPositionPacket {
+        position: i64,
+        angle: f32,
+    }
+
+    impl From<SpawnPositionSpec> for SpawnPositionPacket {
+        fn from(spec: SpawnPositionSpec) -> Self {
+            SpawnPositionPacket {
+                position: position_xzy(spec.position),
+                angle: spec.angle,
+            }
+        }
+    }
+}
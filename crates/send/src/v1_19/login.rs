@@ -0,0 +1,16 @@
+falcon_packet_core::packet! {
+    use derive_from_ext::From;
+    use falcon_core::player::profile::GameProfile;
+    use falcon_packet_core::{PacketSize, PacketWrite};
+
+    use crate::LoginSuccessSpec;
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(LoginSuccessSpec)]
+    #[falcon_packet(state = login, direction = clientbound, versions = {
+        759..=761, 763 = 0x02;
+    })]
+    pub struct LoginSuccessPacket {
+        profile: GameProfile,
+    }
+}
@@ -0,0 +1,107 @@
+falcon_packet_core::packet! {
+    use bytes::BufMut;
+    use falcon_packet_core::{PacketBitSet, PacketIter, PacketSize, PacketWrite, WriteError};
+
+    use crate::section_cache::{encode_section, EncodedSection};
+    use crate::specs::play::ChunkDataSpec;
+    use crate::util::{BiomeSection, HeightMap};
+    use crate::v1_14::play::{FullLightArrays, LIGHT_MASK, LIGHT_SECTIONS};
+    use crate::v1_16::play::{into_chunk_section, PacketHeightMap};
+
+    /// Sections 0 up to and including 15, every section is sent since 1.18.
+    pub(crate) const SECTION_COUNT: usize = 16;
+
+    /// Chunk data and light in one packet, as sent since 1.18.
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        759 = 0x1F;
+        760 = 0x21;
+        761 = 0x20;
+    })]
+    pub struct ChunkDataPacket {
+        chunk_x: i32,
+        chunk_z: i32,
+        #[falcon(nbt)]
+        heightmap: PacketHeightMap,
+        #[falcon(var32)]
+        size: usize,
+        #[falcon(link = "size with data")]
+        sections: Vec<PalettedSection>,
+        #[falcon(var32)]
+        block_entity_num: i32,
+        trust_edges: bool,
+        sky_light_mask: PacketBitSet,
+        block_light_mask: PacketBitSet,
+        empty_sky_light_mask: PacketBitSet,
+        empty_block_light_mask: PacketBitSet,
+        #[falcon(var32)]
+        sky_light_count: usize,
+        sky_light: FullLightArrays,
+        #[falcon(var32)]
+        block_light_count: usize,
+    }
+
+    /// A chunk section followed by its biomes, empty sections are sent as
+    /// a single air block.
+    pub(crate) struct PalettedSection(pub(crate) Option<EncodedSection>, pub(crate) BiomeSection);
+
+    /// No blocks, single valued palette of air and no data array.
+    const EMPTY_BLOCKS: [u8; 5] = [0, 0, 0, 0, 0];
+
+    impl PacketSize for PalettedSection {
+        fn size(&self) -> usize { self.0.as_ref().map(|s| s.size()).unwrap_or(EMPTY_BLOCKS.len()) + self.1.size() }
+    }
+
+    impl PacketWrite for PalettedSection {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            match &self.0 {
+                Some(section) => section.write(buffer)?,
+                None => EMPTY_BLOCKS.write(buffer)?,
+            }
+            self.1.write(buffer)
+        }
+    }
+
+    #[inline(always)]
+    #[allow(clippy::ptr_arg)]
+    pub(crate) fn data_value(field: &[PalettedSection]) -> usize { data_size(field) }
+
+    #[allow(clippy::ptr_arg)]
+    pub(crate) fn data_size(field: &[PalettedSection]) -> usize { PacketIter::new(field.iter()).size_ref() }
+
+    pub(crate) fn data_write<B: BufMut + ?Sized>(field: &[PalettedSection], buffer: &mut B) -> Result<(), WriteError> {
+        PacketIter::new(field.iter()).write_ref(buffer)
+    }
+
+    impl From<ChunkDataSpec> for ChunkDataPacket {
+        fn from(spec: ChunkDataSpec) -> Self {
+            let heightmap = HeightMap::new(&spec).into();
+            let mut sections: Vec<PalettedSection> = (0..SECTION_COUNT).map(|i| PalettedSection(None, BiomeSection::new(&spec.biomes, i))).collect();
+            for section in spec.sections {
+                let index = section.section_index;
+                if index < SECTION_COUNT {
+                    sections[index].0 = Some(encode_section(section, into_chunk_section));
+                }
+            }
+            ChunkDataPacket {
+                chunk_x: spec.chunk_x,
+                chunk_z: spec.chunk_z,
+                heightmap,
+                size: 0,
+                sections,
+                block_entity_num: 0,
+                trust_edges: true,
+                sky_light_mask: PacketBitSet::from(LIGHT_MASK as u64),
+                block_light_mask: PacketBitSet::new(),
+                empty_sky_light_mask: PacketBitSet::new(),
+                empty_block_light_mask: PacketBitSet::from(LIGHT_MASK as u64),
+                sky_light_count: LIGHT_SECTIONS,
+                sky_light: FullLightArrays(LIGHT_SECTIONS),
+                block_light_count: 0,
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use falcon_core::world::biome;
+use falcon_core::world::dimension::Dimension;
+use serde::Serialize;
+
+/// The registries sent to 1.19+ clients, chat types are now registered by
+/// the server as well.
+#[derive(Serialize)]
+pub struct Codec {
+    #[serde(rename = "minecraft:dimension_type")]
+    dimension_types: Registry<DimensionType>,
+    #[serde(rename = "minecraft:worldgen/biome")]
+    biomes: Registry<Biome>,
+    #[serde(rename = "minecraft:chat_type")]
+    chat_types: Registry<ChatType>,
+}
+
+impl Codec {
+    pub fn new(dimensions: &[Dimension], chat: ChatType) -> Self {
+        Codec {
+            dimension_types: Registry::new(
+                "minecraft:dimension_type",
+                dimensions.iter().map(|d| RegistryEntry::new(d.name(), d.id(), DimensionType::new(d))).collect(),
+            ),
+            biomes: Registry::new("minecraft:worldgen/biome", biome::Biome::ALL.iter().map(|b| RegistryEntry::new(b.name(), b.registry_id(), Biome::from(b))).collect()),
+            chat_types: Registry::new("minecraft:chat_type", vec![RegistryEntry::new("minecraft:chat", 0, chat)]),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct Registry<T> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    value: Vec<RegistryEntry<T>>,
+}
+
+impl<T> Registry<T> {
+    pub(crate) fn new(kind: &'static str, value: Vec<RegistryEntry<T>>) -> Self { Registry { kind, value } }
+}
+
+#[derive(Serialize)]
+pub(crate) struct RegistryEntry<T> {
+    name: String,
+    id: i32,
+    element: T,
+}
+
+impl<T> RegistryEntry<T> {
+    pub(crate) fn new(name: &str, id: i32, element: T) -> Self {
+        RegistryEntry {
+            name: name.to_owned(),
+            id,
+            element,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct DimensionType {
+    piglin_safe: bool,
+    has_raids: bool,
+    monster_spawn_light_level: i32,
+    monster_spawn_block_light_limit: i32,
+    natural: bool,
+    ambient_light: f32,
+    infiniburn: &'static str,
+    respawn_anchor_works: bool,
+    has_skylight: bool,
+    bed_works: bool,
+    effects: String,
+    min_y: i32,
+    height: i32,
+    logical_height: i32,
+    coordinate_scale: f64,
+    ultrawarm: bool,
+    has_ceiling: bool,
+}
+
+impl DimensionType {
+    pub(crate) fn new(dimension: &Dimension) -> Self {
+        DimensionType {
+            piglin_safe: false,
+            has_raids: true,
+            monster_spawn_light_level: 0,
+            monster_spawn_block_light_limit: 0,
+            natural: true,
+            ambient_light: 0.0,
+            infiniburn: "#minecraft:infiniburn_overworld",
+            respawn_anchor_works: false,
+            has_skylight: true,
+            bed_works: true,
+            effects: dimension.name().to_owned(),
+            min_y: 0,
+            height: 256,
+            logical_height: 256,
+            coordinate_scale: 1.0,
+            ultrawarm: false,
+            has_ceiling: false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Biome {
+    precipitation: &'static str,
+    temperature: f32,
+    downfall: f32,
+    effects: BiomeEffects,
+}
+
+impl From<&biome::Biome> for Biome {
+    fn from(biome: &biome::Biome) -> Self {
+        let properties = biome.properties();
+        Biome {
+            precipitation: properties.precipitation,
+            temperature: properties.temperature,
+            downfall: properties.downfall,
+            effects: BiomeEffects {
+                sky_color: properties.sky_color,
+                water_fog_color: properties.water_fog_color,
+                fog_color: properties.fog_color,
+                water_color: properties.water_color,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BiomeEffects {
+    sky_color: i32,
+    water_fog_color: i32,
+    fog_color: i32,
+    water_color: i32,
+}
+
+/// How chat messages of the only registered chat type are decorated, the
+/// layout changed in 1.19.1.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum ChatType {
+    V1_19 { chat: TextDisplay, narration: Narration },
+    V1_19_1 { chat: Decoration, narration: Decoration },
+}
+
+impl ChatType {
+    pub fn v1_19() -> Self {
+        ChatType::V1_19 {
+            chat: TextDisplay {
+                decoration: Decoration::new("chat.type.text"),
+            },
+            narration: Narration {
+                decoration: Decoration::new("chat.type.text.narrate"),
+                priority: "chat",
+            },
+        }
+    }
+
+    pub fn v1_19_1() -> Self {
+        ChatType::V1_19_1 {
+            chat: Decoration::new("chat.type.text"),
+            narration: Decoration::new("chat.type.text.narrate"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TextDisplay {
+    decoration: Decoration,
+}
+
+#[derive(Serialize)]
+pub struct Narration {
+    decoration: Decoration,
+    priority: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct Decoration {
+    translation_key: &'static str,
+    parameters: Vec<&'static str>,
+}
+
+impl Decoration {
+    fn new(translation_key: &'static str) -> Self {
+        Decoration {
+            translation_key,
+            parameters: vec!["sender", "content"],
+        }
+    }
+}
@@ -0,0 +1,225 @@
+mod chunk;
+pub mod dimension;
+
+pub use chunk::*;
+
+falcon_packet_core::packet! {
+    use bytes::BufMut;
+    use derive_from_ext::From;
+    use falcon_core::chat::ChatComponent;
+    use falcon_core::data::Identifier;
+    use falcon_core::world::dimension::Dimension;
+    use falcon_packet_core::{Angle, PacketSize, PacketWrite, WriteError};
+    use uuid::Uuid;
+
+    use crate::specs::play::{DeclareCommandsSpec, PlayerInfoSpec, SpawnEntitySpec};
+    use crate::v1_13::play::CommandNodes;
+    use crate::v1_19::play::dimension::{ChatType, Codec};
+    use crate::v1_8_9::play::PlayerInfoList;
+    use crate::{JoinGameSpec, RespawnSpec};
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(JoinGameSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        759 = 0x23;
+    })]
+    pub struct JoinGamePacket {
+        entity_id: i32,
+        #[from(skip)]
+        is_hardcore: bool,
+        game_mode: u8,
+        #[from(skip, default = "-1")]
+        prev_gamemode: i8,
+        #[from(skip)]
+        #[falcon(var32)]
+        world_count: usize,
+        #[from(skip, default = "init_worlds()")]
+        #[falcon(link = "world_count with worlds")]
+        worlds: Vec<Identifier>,
+        #[from(skip, default = "init_registry_codec()")]
+        #[falcon(nbt)]
+        registry_codec: Codec,
+        #[from(skip, default = "init_dimension()")]
+        dimension_type: Identifier,
+        #[from(skip, default = "init_world()")]
+        world_name: Identifier,
+        hashed_seed: i64,
+        #[from(map = "u8_to_i32")]
+        #[falcon(var32)]
+        max_players: i32,
+        #[falcon(var32)]
+        view_distance: i32,
+        #[from(rename = "view_distance")]
+        #[falcon(var32)]
+        simulation_distance: i32,
+        reduced_debug: bool,
+        enable_respawn_screen: bool,
+        #[from(skip)]
+        is_debug: bool,
+        #[from(skip)]
+        is_flat: bool,
+        #[from(skip)]
+        has_death_location: bool,
+    }
+
+    fn u8_to_i32(n: u8) -> i32 { n as i32 }
+
+    fn worlds_value(field: &[Identifier]) -> usize { field.len() }
+
+    fn worlds_size(field: &[Identifier]) -> usize { field.iter().map(PacketSize::size).sum() }
+
+    fn worlds_write<B>(field: &[Identifier], buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        for ident in field {
+            ident.write(buffer)?;
+        }
+        Ok(())
+    }
+
+    fn init_worlds() -> Vec<Identifier> { vec![init_world()] }
+
+    fn init_world() -> Identifier { Identifier::from_static("falcon", "world") }
+
+    fn init_registry_codec() -> Codec { Codec::new(&[Dimension::new("minecraft:overworld", 0)], ChatType::v1_19()) }
+
+    fn init_dimension() -> Identifier { Identifier::from_static("minecraft", "overworld") }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        759 = 0x34;
+        760 = 0x37;
+    })]
+    pub struct PlayerInfoPacket {
+        players: PlayerInfoList,
+    }
+
+    impl From<PlayerInfoSpec> for PlayerInfoPacket {
+        fn from(spec: PlayerInfoSpec) -> Self {
+            PlayerInfoPacket {
+                players: PlayerInfoList::from_spec(spec, true),
+            }
+        }
+    }
+
+    impl From<Vec<Uuid>> for PlayerInfoPacket {
+        fn from(players: Vec<Uuid>) -> Self {
+            PlayerInfoPacket {
+                players: PlayerInfoList::Remove(players),
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        759 = 0x5F;
+    })]
+    pub struct SystemChatPacket {
+        #[falcon(string = 262144)]
+        message: String,
+        #[falcon(var32)]
+        chat_type: i32,
+    }
+
+    impl From<ChatComponent> for SystemChatPacket {
+        fn from(message: ChatComponent) -> Self {
+            SystemChatPacket {
+                message: serde_json::to_string(&message).expect("Invalid message data"),
+                // system message
+                chat_type: 1,
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        759, 760 = 0x0F;
+        761 = 0x0E;
+        763 = 0x10;
+    })]
+    pub struct DeclareCommandsPacket {
+        nodes: CommandNodes,
+        #[falcon(var32)]
+        root: i32,
+    }
+
+    impl From<DeclareCommandsSpec> for DeclareCommandsPacket {
+        fn from(spec: DeclareCommandsSpec) -> Self {
+            DeclareCommandsPacket {
+                nodes: CommandNodes(spec.nodes, true),
+                root: spec.root,
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        759, 760, 761 = 0x00;
+        763 = 0x01;
+    })]
+    pub struct SpawnEntityPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        uuid: Uuid,
+        #[falcon(var32)]
+        entity_type: i32,
+        x: f64,
+        y: f64,
+        z: f64,
+        pitch: Angle,
+        yaw: Angle,
+        head_yaw: Angle,
+        #[falcon(var32)]
+        data: i32,
+        velocity_x: i16,
+        velocity_y: i16,
+        velocity_z: i16,
+    }
+
+    impl From<SpawnEntitySpec> for SpawnEntityPacket {
+        fn from(spec: SpawnEntitySpec) -> Self {
+            SpawnEntityPacket {
+                entity_id: spec.entity_id,
+                uuid: spec.uuid,
+                entity_type: spec.type_id().unwrap_or_default(),
+                x: spec.position.x,
+                y: spec.position.y,
+                z: spec.position.z,
+                pitch: spec.look.pitch.into(),
+                yaw: spec.look.yaw.into(),
+                head_yaw: spec.look.yaw.into(),
+                data: spec.data,
+                velocity_x: 0,
+                velocity_y: 0,
+                velocity_z: 0,
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(RespawnSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        759 = 0x3B;
+        760 = 0x3E;
+        761 = 0x3D;
+    })]
+    pub struct RespawnPacket {
+        #[from(skip, default = "init_dimension()")]
+        dimension_type: Identifier,
+        #[from(skip, default = "init_world()")]
+        world_name: Identifier,
+        hashed_seed: i64,
+        game_mode: u8,
+        #[from(skip, default = "-1")]
+        prev_gamemode: i8,
+        #[from(skip)]
+        is_debug: bool,
+        #[from(skip)]
+        is_flat: bool,
+        #[from(skip)]
+        copy_metadata: bool,
+        #[from(skip)]
+        has_death_location: bool,
+    }
+}
@@ -0,0 +1,101 @@
+falcon_packet_core::packet! {
+    use bytes::BufMut;
+    use derive_from_ext::From;
+    use falcon_core::chat::ChatComponent;
+    use falcon_core::data::Identifier;
+    use falcon_core::world::dimension::Dimension;
+    use falcon_packet_core::{PacketSize, PacketWrite, WriteError};
+
+    use crate::v1_19::play::dimension::{ChatType, Codec};
+    use crate::JoinGameSpec;
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(JoinGameSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        760 = 0x25;
+        761 = 0x24;
+    })]
+    pub struct JoinGamePacket {
+        entity_id: i32,
+        #[from(skip)]
+        is_hardcore: bool,
+        game_mode: u8,
+        #[from(skip, default = "-1")]
+        prev_gamemode: i8,
+        #[from(skip)]
+        #[falcon(var32)]
+        world_count: usize,
+        #[from(skip, default = "init_worlds()")]
+        #[falcon(link = "world_count with worlds")]
+        worlds: Vec<Identifier>,
+        #[from(skip, default = "init_registry_codec()")]
+        #[falcon(nbt)]
+        registry_codec: Codec,
+        #[from(skip, default = "init_dimension()")]
+        dimension_type: Identifier,
+        #[from(skip, default = "init_world()")]
+        world_name: Identifier,
+        hashed_seed: i64,
+        #[from(map = "u8_to_i32")]
+        #[falcon(var32)]
+        max_players: i32,
+        #[falcon(var32)]
+        view_distance: i32,
+        #[from(rename = "view_distance")]
+        #[falcon(var32)]
+        simulation_distance: i32,
+        reduced_debug: bool,
+        enable_respawn_screen: bool,
+        #[from(skip)]
+        is_debug: bool,
+        #[from(skip)]
+        is_flat: bool,
+        #[from(skip)]
+        has_death_location: bool,
+    }
+
+    fn u8_to_i32(n: u8) -> i32 { n as i32 }
+
+    fn worlds_value(field: &[Identifier]) -> usize { field.len() }
+
+    fn worlds_size(field: &[Identifier]) -> usize { field.iter().map(PacketSize::size).sum() }
+
+    fn worlds_write<B>(field: &[Identifier], buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        for ident in field {
+            ident.write(buffer)?;
+        }
+        Ok(())
+    }
+
+    fn init_worlds() -> Vec<Identifier> { vec![init_world()] }
+
+    fn init_world() -> Identifier { Identifier::from_static("falcon", "world") }
+
+    fn init_registry_codec() -> Codec { Codec::new(&[Dimension::new("minecraft:overworld", 0)], ChatType::v1_19_1()) }
+
+    fn init_dimension() -> Identifier { Identifier::from_static("minecraft", "overworld") }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        760 = 0x62;
+        761 = 0x60;
+        763 = 0x64;
+    })]
+    pub struct SystemChatPacket {
+        #[falcon(string = 262144)]
+        message: String,
+        overlay: bool,
+    }
+
+    impl From<ChatComponent> for SystemChatPacket {
+        fn from(message: ChatComponent) -> Self {
+            SystemChatPacket {
+                message: serde_json::to_string(&message).expect("Invalid message data"),
+                overlay: false,
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+falcon_packet_core::packet! {
+    use bytes::BufMut;
+    use falcon_packet_core::{PacketSize, PacketWrite, VarI32, WriteError};
+    use uuid::Uuid;
+
+    use crate::specs::play::PlayerInfoSpec;
+
+    const ADD_PLAYER: u8 = 0x01;
+    const UPDATE_GAME_MODE: u8 = 0x04;
+    const UPDATE_LISTED: u8 = 0x08;
+    const UPDATE_LATENCY: u8 = 0x10;
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        761 = 0x36;
+        763 = 0x3A;
+    })]
+    pub struct PlayerInfoUpdatePacket {
+        players: PlayerInfoActions,
+    }
+
+    impl From<PlayerInfoSpec> for PlayerInfoUpdatePacket {
+        fn from(spec: PlayerInfoSpec) -> Self {
+            PlayerInfoUpdatePacket {
+                players: PlayerInfoActions(spec),
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        761 = 0x35;
+        763 = 0x39;
+    })]
+    pub struct PlayerInfoRemovePacket {
+        #[falcon(var32)]
+        count: usize,
+        #[falcon(link = "count with players")]
+        players: Vec<Uuid>,
+    }
+
+    impl From<Vec<Uuid>> for PlayerInfoRemovePacket {
+        fn from(players: Vec<Uuid>) -> Self { PlayerInfoRemovePacket { count: 0, players } }
+    }
+
+    fn players_value(field: &[Uuid]) -> usize { field.len() }
+
+    fn players_size(field: &[Uuid]) -> usize { field.iter().map(PacketSize::size).sum::<usize>() }
+
+    fn players_write<B>(field: &[Uuid], buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        for uuid in field {
+            uuid.write(buffer)?;
+        }
+        Ok(())
+    }
+
+    /// The action set and players of a player info update, the data of
+    /// every action is written in the order of its bit.
+    pub(crate) struct PlayerInfoActions(PlayerInfoSpec);
+
+    impl PacketSize for PlayerInfoActions {
+        fn size(&self) -> usize {
+            match &self.0 {
+                PlayerInfoSpec::AddPlayers(players) => {
+                    ADD_PLAYER.size()
+                        + VarI32::from(players.len()).size()
+                        + players
+                            .iter()
+                            .map(|player| {
+                                player.profile.size()
+                                    + VarI32::from(player.game_mode as i32).size()
+                                    + true.size()
+                                    + VarI32::from(player.ping).size()
+                            })
+                            .sum::<usize>()
+                },
+                PlayerInfoSpec::UpdateGameMode(players) => {
+                    UPDATE_GAME_MODE.size()
+                        + VarI32::from(players.len()).size()
+                        + players.iter().map(|(uuid, game_mode)| uuid.size() + VarI32::from(*game_mode as i32).size()).sum::<usize>()
+                },
+                PlayerInfoSpec::UpdateLatency(players) => {
+                    UPDATE_LATENCY.size()
+                        + VarI32::from(players.len()).size()
+                        + players.iter().map(|(uuid, ping)| uuid.size() + VarI32::from(*ping).size()).sum::<usize>()
+                },
+            }
+        }
+    }
+
+    impl PacketWrite for PlayerInfoActions {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            match &self.0 {
+                PlayerInfoSpec::AddPlayers(players) => {
+                    (ADD_PLAYER | UPDATE_GAME_MODE | UPDATE_LISTED | UPDATE_LATENCY).write(buffer)?;
+                    VarI32::from(players.len()).write(buffer)?;
+                    for player in players {
+                        player.profile.write(buffer)?;
+                        VarI32::from(player.game_mode as i32).write(buffer)?;
+                        true.write(buffer)?;
+                        VarI32::from(player.ping).write(buffer)?;
+                    }
+                },
+                PlayerInfoSpec::UpdateGameMode(players) => {
+                    UPDATE_GAME_MODE.write(buffer)?;
+                    VarI32::from(players.len()).write(buffer)?;
+                    for (uuid, game_mode) in players {
+                        uuid.write(buffer)?;
+                        VarI32::from(*game_mode as i32).write(buffer)?;
+                    }
+                },
+                PlayerInfoSpec::UpdateLatency(players) => {
+                    UPDATE_LATENCY.write(buffer)?;
+                    VarI32::from(players.len()).write(buffer)?;
+                    for (uuid, ping) in players {
+                        uuid.write(buffer)?;
+                        VarI32::from(*ping).write(buffer)?;
+                    }
+                },
+            }
+            Ok(())
+        }
+    }
+}
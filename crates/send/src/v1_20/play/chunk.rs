@@ -0,0 +1,66 @@
+falcon_packet_core::packet! {
+    use falcon_packet_core::{PacketBitSet, PacketSize, PacketWrite};
+
+    use crate::section_cache::encode_section;
+    use crate::specs::play::ChunkDataSpec;
+    use crate::util::{BiomeSection, HeightMap};
+    use crate::v1_14::play::{FullLightArrays, LIGHT_MASK, LIGHT_SECTIONS};
+    use crate::v1_16::play::{into_chunk_section, PacketHeightMap};
+    use crate::v1_19::play::{data_size, data_value, data_write, PalettedSection, SECTION_COUNT};
+
+    /// Chunk data and light in one packet, light no longer has the
+    /// trust edges flag since 1.20.
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        763 = 0x24;
+    })]
+    pub struct ChunkDataPacket {
+        chunk_x: i32,
+        chunk_z: i32,
+        #[falcon(nbt)]
+        heightmap: PacketHeightMap,
+        #[falcon(var32)]
+        size: usize,
+        #[falcon(link = "size with data")]
+        sections: Vec<PalettedSection>,
+        #[falcon(var32)]
+        block_entity_num: i32,
+        sky_light_mask: PacketBitSet,
+        block_light_mask: PacketBitSet,
+        empty_sky_light_mask: PacketBitSet,
+        empty_block_light_mask: PacketBitSet,
+        #[falcon(var32)]
+        sky_light_count: usize,
+        sky_light: FullLightArrays,
+        #[falcon(var32)]
+        block_light_count: usize,
+    }
+
+    impl From<ChunkDataSpec> for ChunkDataPacket {
+        fn from(spec: ChunkDataSpec) -> Self {
+            let heightmap = HeightMap::new(&spec).into();
+            let mut sections: Vec<PalettedSection> = (0..SECTION_COUNT).map(|i| PalettedSection(None, BiomeSection::new(&spec.biomes, i))).collect();
+            for section in spec.sections {
+                let index = section.section_index;
+                if index < SECTION_COUNT {
+                    sections[index].0 = Some(encode_section(section, into_chunk_section));
+                }
+            }
+            ChunkDataPacket {
+                chunk_x: spec.chunk_x,
+                chunk_z: spec.chunk_z,
+                heightmap,
+                size: 0,
+                sections,
+                block_entity_num: 0,
+                sky_light_mask: PacketBitSet::from(LIGHT_MASK as u64),
+                block_light_mask: PacketBitSet::new(),
+                empty_sky_light_mask: PacketBitSet::new(),
+                empty_block_light_mask: PacketBitSet::from(LIGHT_MASK as u64),
+                sky_light_count: LIGHT_SECTIONS,
+                sky_light: FullLightArrays(LIGHT_SECTIONS),
+                block_light_count: 0,
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use falcon_core::world::biome;
+use falcon_core::world::dimension::Dimension;
+use serde::Serialize;
+
+use crate::v1_19::play::dimension::{ChatType, DimensionType, Registry, RegistryEntry};
+
+/// Damage types the client looks up when joining a world.
+const DAMAGE_TYPES: [&str; 44] = [
+    "arrow",
+    "bad_respawn_point",
+    "cactus",
+    "cramming",
+    "dragon_breath",
+    "drown",
+    "dry_out",
+    "explosion",
+    "fall",
+    "falling_anvil",
+    "falling_block",
+    "falling_stalactite",
+    "fireball",
+    "fireworks",
+    "fly_into_wall",
+    "freeze",
+    "generic",
+    "generic_kill",
+    "hot_floor",
+    "in_fire",
+    "in_wall",
+    "indirect_magic",
+    "lava",
+    "lightning_bolt",
+    "magic",
+    "mob_attack",
+    "mob_attack_no_aggro",
+    "mob_projectile",
+    "on_fire",
+    "out_of_world",
+    "outside_border",
+    "player_attack",
+    "player_explosion",
+    "sonic_boom",
+    "stalagmite",
+    "starve",
+    "sting",
+    "sweet_berry_bush",
+    "thorns",
+    "thrown",
+    "trident",
+    "unattributed_fireball",
+    "wither",
+    "wither_skull",
+];
+
+/// The registries sent to 1.20 clients, damage types and armor trims are
+/// synchronized as well.
+#[derive(Serialize)]
+pub struct Codec {
+    #[serde(rename = "minecraft:dimension_type")]
+    dimension_types: Registry<DimensionType>,
+    #[serde(rename = "minecraft:worldgen/biome")]
+    biomes: Registry<Biome>,
+    #[serde(rename = "minecraft:chat_type")]
+    chat_types: Registry<ChatType>,
+    #[serde(rename = "minecraft:damage_type")]
+    damage_types: Registry<DamageType>,
+    #[serde(rename = "minecraft:trim_pattern")]
+    trim_patterns: Registry<()>,
+    #[serde(rename = "minecraft:trim_material")]
+    trim_materials: Registry<()>,
+}
+
+impl Codec {
+    pub fn new(dimensions: &[Dimension]) -> Self {
+        Codec {
+            dimension_types: Registry::new(
+                "minecraft:dimension_type",
+                dimensions.iter().map(|d| RegistryEntry::new(d.name(), d.id(), DimensionType::new(d))).collect(),
+            ),
+            biomes: Registry::new("minecraft:worldgen/biome", biome::Biome::ALL.iter().map(|b| RegistryEntry::new(b.name(), b.registry_id(), Biome::from(b))).collect()),
+            chat_types: Registry::new("minecraft:chat_type", vec![RegistryEntry::new("minecraft:chat", 0, ChatType::v1_19_1())]),
+            damage_types: Registry::new(
+                "minecraft:damage_type",
+                DAMAGE_TYPES
+                    .iter()
+                    .enumerate()
+                    .map(|(id, name)| RegistryEntry::new(&format!("minecraft:{}", name), id as i32, DamageType::new(name)))
+                    .collect(),
+            ),
+            trim_patterns: Registry::new("minecraft:trim_pattern", vec![]),
+            trim_materials: Registry::new("minecraft:trim_material", vec![]),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Biome {
+    has_precipitation: bool,
+    temperature: f32,
+    downfall: f32,
+    effects: BiomeEffects,
+}
+
+impl From<&biome::Biome> for Biome {
+    fn from(biome: &biome::Biome) -> Self {
+        let properties = biome.properties();
+        Biome {
+            has_precipitation: properties.precipitation != "none",
+            temperature: properties.temperature,
+            downfall: properties.downfall,
+            effects: BiomeEffects {
+                sky_color: properties.sky_color,
+                water_fog_color: properties.water_fog_color,
+                fog_color: properties.fog_color,
+                water_color: properties.water_color,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BiomeEffects {
+    sky_color: i32,
+    water_fog_color: i32,
+    fog_color: i32,
+    water_color: i32,
+}
+
+#[derive(Serialize)]
+struct DamageType {
+    message_id: &'static str,
+    scaling: &'static str,
+    exhaustion: f32,
+}
+
+impl DamageType {
+    fn new(message_id: &'static str) -> Self {
+        DamageType {
+            message_id,
+            scaling: "when_caused_by_living_non_player",
+            exhaustion: 0.1,
+        }
+    }
+}
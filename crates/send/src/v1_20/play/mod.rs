@@ -0,0 +1,146 @@
+mod chunk;
+pub mod dimension;
+
+pub use chunk::*;
+
+falcon_packet_core::packet! {
+    use bytes::BufMut;
+    use derive_from_ext::From;
+    use falcon_core::data::Identifier;
+    use falcon_core::world::dimension::Dimension;
+    use falcon_packet_core::{PacketSize, PacketWrite, WriteError};
+
+    use crate::specs::play::EntityAnimationSpec;
+    use crate::v1_20::play::dimension::Codec;
+    use crate::{JoinGameSpec, RespawnSpec};
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(JoinGameSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        763 = 0x28;
+    })]
+    pub struct JoinGamePacket {
+        entity_id: i32,
+        #[from(skip)]
+        is_hardcore: bool,
+        game_mode: u8,
+        #[from(skip, default = "-1")]
+        prev_gamemode: i8,
+        #[from(skip)]
+        #[falcon(var32)]
+        world_count: usize,
+        #[from(skip, default = "init_worlds()")]
+        #[falcon(link = "world_count with worlds")]
+        worlds: Vec<Identifier>,
+        #[from(skip, default = "init_registry_codec()")]
+        #[falcon(nbt)]
+        registry_codec: Codec,
+        #[from(skip, default = "init_dimension()")]
+        dimension_type: Identifier,
+        #[from(skip, default = "init_world()")]
+        world_name: Identifier,
+        hashed_seed: i64,
+        #[from(map = "u8_to_i32")]
+        #[falcon(var32)]
+        max_players: i32,
+        #[falcon(var32)]
+        view_distance: i32,
+        #[from(rename = "view_distance")]
+        #[falcon(var32)]
+        simulation_distance: i32,
+        reduced_debug: bool,
+        enable_respawn_screen: bool,
+        #[from(skip)]
+        is_debug: bool,
+        #[from(skip)]
+        is_flat: bool,
+        #[from(skip)]
+        has_death_location: bool,
+        #[from(skip)]
+        #[falcon(var32)]
+        portal_cooldown: i32,
+    }
+
+    fn u8_to_i32(n: u8) -> i32 { n as i32 }
+
+    fn worlds_value(field: &[Identifier]) -> usize { field.len() }
+
+    fn worlds_size(field: &[Identifier]) -> usize { field.iter().map(PacketSize::size).sum() }
+
+    fn worlds_write<B>(field: &[Identifier], buffer: &mut B) -> Result<(), WriteError>
+    where
+        B: BufMut + ?Sized,
+    {
+        for ident in field {
+            ident.write(buffer)?;
+        }
+        Ok(())
+    }
+
+    fn init_worlds() -> Vec<Identifier> { vec![init_world()] }
+
+    fn init_world() -> Identifier { Identifier::from_static("falcon", "world") }
+
+    fn init_registry_codec() -> Codec { Codec::new(&[Dimension::new("minecraft:overworld", 0)]) }
+
+    fn init_dimension() -> Identifier { Identifier::from_static("minecraft", "overworld") }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        763 = 0x21;
+    })]
+    pub struct HurtAnimationPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        /// The direction the damage came from.
+        yaw: f32,
+    }
+
+    impl From<EntityAnimationSpec> for HurtAnimationPacket {
+        fn from(spec: EntityAnimationSpec) -> Self {
+            HurtAnimationPacket {
+                entity_id: spec.entity_id,
+                yaw: 0.0,
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(RespawnSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        763 = 0x41;
+    })]
+    pub struct RespawnPacket {
+        #[from(skip, default = "init_dimension()")]
+        dimension_type: Identifier,
+        #[from(skip, default = "init_world()")]
+        world_name: Identifier,
+        hashed_seed: i64,
+        game_mode: u8,
+        #[from(skip, default = "-1")]
+        prev_gamemode: i8,
+        #[from(skip)]
+        is_debug: bool,
+        #[from(skip)]
+        is_flat: bool,
+        /// Nothing of the old player is kept.
+        #[from(skip)]
+        data_kept: u8,
+        #[from(skip)]
+        has_death_location: bool,
+        #[from(skip)]
+        #[falcon(var32)]
+        portal_cooldown: i32,
+    }
+    /// Starts or ends a bundle, the packets between two delimiters are
+    /// handled in the same tick. Since 1.19.4.
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        763 = 0x00;
+    })]
+    pub struct BundleDelimiterPacket {}
+
+    impl From<()> for BundleDelimiterPacket {
+        fn from(_: ()) -> Self { BundleDelimiterPacket {} }
+    }
+}
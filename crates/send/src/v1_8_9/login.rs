@@ -1,14 +1,12 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
-    use derive_from_ext::From;
+falcon_packet_core::packet! {
+    use falcon_core::chat::ChatComponent;
     use falcon_packet_core::special::StrUuid;
     use falcon_packet_core::{PacketSize, PacketWrite};
-    use mc_chat::ChatComponent;
 
     use crate::specs::login::LoginSuccessSpec;
 
     #[derive(PacketSize, PacketWrite)]
-    #[falcon_packet(versions = { -1 = 0x00 }, name = "disconnect")]
+    #[falcon_packet(state = login, direction = clientbound, versions = { -1 = 0x00 })]
     pub struct DisconnectPacket {
         #[falcon(string = 262144)]
         reason: String,
@@ -22,16 +20,33 @@ mod inner {
         }
     }
 
-    #[derive(PacketSize, PacketWrite, From)]
-    #[from(LoginSuccessSpec)]
-    #[falcon_packet(
-        versions = {
-            47, 393, 401, 404, 477, 480, 485, 490, 498, 573, 575, 578 = 0x02;
-        }, name = "login_success"
-    )]
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = login, direction = clientbound, versions = { -1 = 0x03 })]
+    pub struct SetCompressionPacket {
+        #[falcon(var32)]
+        threshold: i32,
+    }
+
+    impl From<i32> for SetCompressionPacket {
+        fn from(threshold: i32) -> Self { SetCompressionPacket { threshold } }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = login, direction = clientbound, versions = {
+        47, 393, 401, 404, 477, 480, 485, 490, 498, 573, 575, 578 = 0x02;
+    })]
     pub struct LoginSuccess {
         uuid: StrUuid,
         #[falcon(string = 16)]
         username: String,
     }
+
+    impl From<LoginSuccessSpec> for LoginSuccess {
+        fn from(spec: LoginSuccessSpec) -> Self {
+            LoginSuccess {
+                uuid: spec.profile.uuid.into(),
+                username: spec.profile.name,
+            }
+        }
+    }
 }
@@ -1,18 +1,25 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
+    use bytes::BufMut;
     use derive_from_ext::From;
-    use falcon_packet_core::{PacketSize, PacketWrite};
-    use mc_chat::ChatComponent;
+    use falcon_core::chat::ChatComponent;
+    use falcon_core::player::data::GameMode;
+    use falcon_core::world::entity::{EntityMetadata, EntityType};
+    use falcon_packet_core::{Angle, FixedPoint, PacketSize, PacketSizeSeed, PacketString, PacketWrite, PacketWriteSeed, VarI32, WriteError};
+    use uuid::Uuid;
 
-    use crate::specs::play::{JoinGameSpec, PlayerAbilitiesSpec};
+    use crate::specs::play::{
+        ChangeGameStateSpec, EntityAnimationSpec, EntityMetadataSpec, EntityStatusSpec, EntityTeleportSpec, EntityVelocitySpec, JoinGameSpec, PlayerAbilitiesSpec,
+        PlayerInfoEntry, PlayerInfoSpec, RespawnSpec, SpawnEntitySpec, SpawnPlayerSpec, SpawnPositionSpec, StatisticsSpec, UpdateHealthSpec,
+    };
+    use crate::util::position_xyz;
     use crate::ServerDifficultySpec;
 
     #[derive(PacketSize, PacketWrite, From)]
     #[from(JoinGameSpec)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         47 = 0x01;
         107 = 0x23;
-    }, name = "join_game")]
+    })]
     pub struct JoinGamePacket {
         entity_id: i32,
         game_mode: u8,
@@ -29,14 +36,18 @@ mod inner {
 
     #[derive(PacketSize, PacketWrite, From)]
     #[from(PlayerAbilitiesSpec)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         47 = 0x39;
         107, 108, 109, 110, 210, 315, 316, 335 = 0x2B;
         338, 340 = 0x2C;
         393, 401, 404 = 0x2E;
         477, 480, 485, 490, 498, 735, 736 = 0x31;
-        573, 575, 578 = 0x32;
-    }, name = "player_abilities")]
+        573, 575, 578, 755, 756 = 0x32;
+        759 = 0x2F;
+        760 = 0x31;
+        761 = 0x30;
+        763 = 0x34;
+    })]
     pub struct PlayerAbilityPacket {
         flags: u8,
         flying_speed: f32,
@@ -44,11 +55,14 @@ mod inner {
     }
 
     #[derive(PacketSize, PacketWrite)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         47 = 0x40;
-        107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 477, 480, 485, 490, 498, 735, 736 = 0x1A;
+        107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 477, 480, 485, 490, 498, 735, 736, 755, 756 = 0x1A;
         393, 401, 404, 573, 575, 578 = 0x1B;
-    }, name = "disconnect")]
+        759, 761 = 0x17;
+        760 = 0x19;
+        763 = 0x1A;
+    })]
     pub struct DisconnectPacket {
         #[falcon(string = 262144)]
         reason: String,
@@ -64,11 +78,694 @@ mod inner {
 
     #[derive(PacketSize, PacketWrite, From)]
     #[from(ServerDifficultySpec)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         47 = 0x41;
         107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 393, 401, 404 = 0x0D;
-    }, name = "difficulty")]
+    })]
     pub struct ServerDifficultyPacket {
         difficulty: u8,
     }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(ChangeGameStateSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x2B;
+        107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 477, 480, 485, 490, 498, 735, 736, 755, 756 = 0x1E;
+        393, 401, 404 = 0x20;
+        573, 575, 578 = 0x1F;
+        759 = 0x1B;
+        760 = 0x1D;
+        761 = 0x1C;
+        763 = 0x1F;
+    })]
+    pub struct ChangeGameStatePacket {
+        reason: u8,
+        value: f32,
+    }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(EntityStatusSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x1A;
+        107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 477, 480, 485, 490, 498, 735, 736, 755, 756 = 0x1B;
+        393, 401, 404, 573, 575, 578 = 0x1C;
+        759 = 0x18;
+        760 = 0x1A;
+        761 = 0x19;
+        763 = 0x1C;
+    })]
+    pub struct EntityStatusPacket {
+        entity_id: i32,
+        status: i8,
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x38;
+        107, 108, 109, 110, 210, 315, 316, 335 = 0x2D;
+        338, 340 = 0x2E;
+        393, 401, 404 = 0x30;
+        477, 480, 485, 490, 498, 735, 736 = 0x33;
+        573, 575, 578 = 0x34;
+        755, 756 = 0x36;
+    })]
+    pub struct PlayerInfoPacket {
+        players: PlayerInfoList,
+    }
+
+    impl From<PlayerInfoSpec> for PlayerInfoPacket {
+        fn from(spec: PlayerInfoSpec) -> Self {
+            PlayerInfoPacket {
+                players: PlayerInfoList::from_spec(spec, false),
+            }
+        }
+    }
+
+    impl From<Vec<Uuid>> for PlayerInfoPacket {
+        fn from(players: Vec<Uuid>) -> Self {
+            PlayerInfoPacket {
+                players: PlayerInfoList::Remove(players),
+            }
+        }
+    }
+
+    /// The action and players of a player info packet before 1.19.3.
+    pub(crate) enum PlayerInfoList {
+        /// The flag adds the empty signature data of 1.19.
+        Add(Vec<PlayerInfoEntry>, bool),
+        UpdateGameMode(Vec<(Uuid, GameMode)>),
+        UpdateLatency(Vec<(Uuid, i32)>),
+        Remove(Vec<Uuid>),
+    }
+
+    impl PlayerInfoList {
+        pub(crate) fn from_spec(spec: PlayerInfoSpec, signature_data: bool) -> Self {
+            match spec {
+                PlayerInfoSpec::AddPlayers(players) => PlayerInfoList::Add(players, signature_data),
+                PlayerInfoSpec::UpdateGameMode(players) => PlayerInfoList::UpdateGameMode(players),
+                PlayerInfoSpec::UpdateLatency(players) => PlayerInfoList::UpdateLatency(players),
+            }
+        }
+    }
+
+    impl PacketSize for PlayerInfoList {
+        fn size(&self) -> usize {
+            match self {
+                PlayerInfoList::Add(players, signature_data) => {
+                    VarI32::from(0).size()
+                        + VarI32::from(players.len()).size()
+                        + players
+                            .iter()
+                            .map(|player| {
+                                player.profile.size()
+                                    + VarI32::from(player.game_mode as i32).size()
+                                    + VarI32::from(player.ping).size()
+                                    + false.size()
+                                    + if *signature_data { false.size() } else { 0 }
+                            })
+                            .sum::<usize>()
+                },
+                PlayerInfoList::UpdateGameMode(players) => {
+                    VarI32::from(1).size()
+                        + VarI32::from(players.len()).size()
+                        + players.iter().map(|(uuid, game_mode)| uuid.size() + VarI32::from(*game_mode as i32).size()).sum::<usize>()
+                },
+                PlayerInfoList::UpdateLatency(players) => {
+                    VarI32::from(2).size()
+                        + VarI32::from(players.len()).size()
+                        + players.iter().map(|(uuid, ping)| uuid.size() + VarI32::from(*ping).size()).sum::<usize>()
+                },
+                PlayerInfoList::Remove(players) => {
+                    VarI32::from(4).size() + VarI32::from(players.len()).size() + players.iter().map(PacketSize::size).sum::<usize>()
+                },
+            }
+        }
+    }
+
+    impl PacketWrite for PlayerInfoList {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            match self {
+                PlayerInfoList::Add(players, signature_data) => {
+                    VarI32::from(0).write(buffer)?;
+                    VarI32::from(players.len()).write(buffer)?;
+                    for player in players {
+                        player.profile.write(buffer)?;
+                        VarI32::from(player.game_mode as i32).write(buffer)?;
+                        VarI32::from(player.ping).write(buffer)?;
+                        // no display name
+                        false.write(buffer)?;
+                        if *signature_data {
+                            // no signature data
+                            false.write(buffer)?;
+                        }
+                    }
+                },
+                PlayerInfoList::UpdateGameMode(players) => {
+                    VarI32::from(1).write(buffer)?;
+                    VarI32::from(players.len()).write(buffer)?;
+                    for (uuid, game_mode) in players {
+                        uuid.write(buffer)?;
+                        VarI32::from(*game_mode as i32).write(buffer)?;
+                    }
+                },
+                PlayerInfoList::UpdateLatency(players) => {
+                    VarI32::from(2).write(buffer)?;
+                    VarI32::from(players.len()).write(buffer)?;
+                    for (uuid, ping) in players {
+                        uuid.write(buffer)?;
+                        VarI32::from(*ping).write(buffer)?;
+                    }
+                },
+                PlayerInfoList::Remove(players) => {
+                    VarI32::from(4).write(buffer)?;
+                    VarI32::from(players.len()).write(buffer)?;
+                    for uuid in players {
+                        uuid.write(buffer)?;
+                    }
+                },
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x02;
+        107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 573, 575, 578 = 0x0F;
+        393, 401, 404, 477, 480, 485, 490, 498 = 0x0E;
+    })]
+    pub struct SystemChatPacket {
+        #[falcon(string = 262144)]
+        message: String,
+        position: i8,
+    }
+
+    impl From<ChatComponent> for SystemChatPacket {
+        fn from(message: ChatComponent) -> Self {
+            SystemChatPacket {
+                message: serde_json::to_string(&message).expect("Invalid message data"),
+                // system message
+                position: 1,
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x37;
+        107, 108, 109, 110, 210, 315, 316, 335, 338, 340 = 0x07;
+    })]
+    pub struct StatisticsPacket {
+        statistics: LegacyStatistics,
+    }
+
+    impl From<StatisticsSpec> for StatisticsPacket {
+        fn from(spec: StatisticsSpec) -> Self {
+            let statistics = spec
+                .statistics
+                .into_iter()
+                .filter_map(|(statistic, value)| statistic.legacy_name().map(|name| (name.into_owned(), value)))
+                .collect();
+            StatisticsPacket {
+                statistics: LegacyStatistics(statistics),
+            }
+        }
+    }
+
+    /// The statistics by their name before 1.13.
+    pub(crate) struct LegacyStatistics(Vec<(String, i32)>);
+
+    impl PacketSize for LegacyStatistics {
+        fn size(&self) -> usize {
+            VarI32::from(self.0.len()).size()
+                + self
+                    .0
+                    .iter()
+                    .map(|(name, value)| PacketString::new(32767).size(name) + VarI32::from(*value).size())
+                    .sum::<usize>()
+        }
+    }
+
+    impl PacketWrite for LegacyStatistics {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            VarI32::from(self.0.len()).write(buffer)?;
+            for (name, value) in &self.0 {
+                PacketString::new(32767).write(name, buffer)?;
+                VarI32::from(*value).write(buffer)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x0E;
+    })]
+    pub struct SpawnObjectPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        object_type: u8,
+        x: FixedPoint<i32, 5>,
+        y: FixedPoint<i32, 5>,
+        z: FixedPoint<i32, 5>,
+        pitch: Angle,
+        yaw: Angle,
+        data: ObjectData,
+    }
+
+    impl From<SpawnEntitySpec> for SpawnObjectPacket {
+        fn from(spec: SpawnEntitySpec) -> Self {
+            SpawnObjectPacket {
+                entity_id: spec.entity_id,
+                object_type: spec.type_id().unwrap_or_default() as u8,
+                x: spec.position.x.into(),
+                y: spec.position.y.into(),
+                z: spec.position.z.into(),
+                pitch: spec.look.pitch.into(),
+                yaw: spec.look.yaw.into(),
+                data: ObjectData(spec.data),
+            }
+        }
+    }
+
+    /// The data of an object, followed by a velocity unless it is zero.
+    struct ObjectData(i32);
+
+    impl PacketSize for ObjectData {
+        fn size(&self) -> usize { if self.0 == 0 { 4 } else { 10 } }
+    }
+
+    impl PacketWrite for ObjectData {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            self.0.write(buffer)?;
+            if self.0 != 0 {
+                [0i16; 3].iter().try_for_each(|velocity| velocity.write(buffer))?;
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x1C;
+        107, 108, 109, 110, 210, 315, 316 = 0x39;
+        335 = 0x3B;
+        338, 340 = 0x3C;
+        393, 401, 404 = 0x3F;
+        477, 480, 485, 490, 498 = 0x43;
+        573, 575, 578, 735, 736 = 0x44;
+        755, 756, 759 = 0x4D;
+        760 = 0x50;
+        761 = 0x4E;
+        763 = 0x52;
+    })]
+    pub struct EntityMetadataPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        entries: MetadataEntries,
+    }
+
+    impl From<EntityMetadataSpec> for EntityMetadataPacket {
+        fn from(spec: EntityMetadataSpec) -> Self {
+            EntityMetadataPacket {
+                entity_id: spec.entity_id,
+                entries: MetadataEntries::from_metadata(spec.entity_type, spec.metadata, spec.protocol_version),
+            }
+        }
+    }
+
+    enum MetadataValue {
+        Byte(u8),
+        Bool(bool),
+        /// Before 1.13, e.g. custom names.
+        String(String),
+        /// A chat component as JSON.
+        Chat(String),
+        OptChat(Option<String>),
+        /// Since 1.14.
+        Pose(i32),
+    }
+
+    /// The entries of entity metadata, the header of an entry and the type
+    /// ids differ between versions.
+    pub(crate) struct MetadataEntries {
+        entries: Vec<(u8, MetadataValue)>,
+        protocol: i32,
+    }
+
+    impl MetadataEntries {
+        pub(crate) fn from_metadata(entity_type: EntityType, metadata: EntityMetadata, protocol: i32) -> Self {
+            let to_json = |component: ChatComponent| serde_json::to_string(&component.for_protocol(protocol)).expect("Invalid name data");
+            let mut entries = Vec::new();
+            // glowing since 1.9
+            let glowing = metadata.glowing && protocol >= 107;
+            let flags = (metadata.sneaking as u8) << 1 | (metadata.sprinting as u8) << 3 | (metadata.invisible as u8) << 5 | (glowing as u8) << 6;
+            entries.push((0, MetadataValue::Byte(flags)));
+            entries.push((
+                2,
+                match protocol {
+                    ..=340 => MetadataValue::String(metadata.custom_name.map(|name| name.to_legacy()).unwrap_or_default()),
+                    _ => MetadataValue::OptChat(metadata.custom_name.map(to_json)),
+                },
+            ));
+            entries.push((3, if protocol < 107 { MetadataValue::Byte(metadata.custom_name_visible as u8) } else { MetadataValue::Bool(metadata.custom_name_visible) }));
+            // no gravity since 1.10
+            if protocol >= 210 {
+                entries.push((5, MetadataValue::Bool(metadata.no_gravity)));
+            }
+            // crouching is a pose since 1.14
+            if protocol >= 477 {
+                entries.push((6, MetadataValue::Pose(if metadata.sneaking { 5 } else { 0 })));
+            }
+            if let (EntityType::TextDisplay, 763, Some(text)) = (entity_type, protocol, metadata.text) {
+                entries.push((22, MetadataValue::Chat(to_json(text))));
+            }
+            MetadataEntries { entries, protocol }
+        }
+
+        fn type_id(&self, value: &MetadataValue) -> i32 {
+            // booleans were bytes in 1.8, chat types came with 1.9
+            match (value, self.protocol) {
+                (MetadataValue::Byte(_), _) => 0,
+                (MetadataValue::String(_), ..=106 | 761..) => 4,
+                (MetadataValue::String(_), 107..=760) => 3,
+                (MetadataValue::Chat(_), ..=760) => 4,
+                (MetadataValue::Chat(_), 761..) => 5,
+                (MetadataValue::OptChat(_), ..=760) => 5,
+                (MetadataValue::OptChat(_), 761..) => 6,
+                (MetadataValue::Bool(_), ..=340) => 6,
+                (MetadataValue::Bool(_), 341..=760) => 7,
+                (MetadataValue::Bool(_), 761..) => 8,
+                (MetadataValue::Pose(_), ..=760) => 18,
+                (MetadataValue::Pose(_), 761) => 19,
+                (MetadataValue::Pose(_), 762..) => 20,
+            }
+        }
+
+        fn header_size(&self, value: &MetadataValue) -> usize {
+            match self.protocol {
+                ..=106 => 1,
+                107..=340 => 2,
+                _ => 1 + VarI32::from(self.type_id(value)).size(),
+            }
+        }
+
+        fn write_header<B: BufMut + ?Sized>(&self, index: u8, value: &MetadataValue, buffer: &mut B) -> Result<(), WriteError> {
+            let type_id = self.type_id(value);
+            match self.protocol {
+                ..=106 => ((type_id as u8) << 5 | index & 0x1F).write(buffer),
+                107..=340 => {
+                    index.write(buffer)?;
+                    (type_id as u8).write(buffer)
+                },
+                _ => {
+                    index.write(buffer)?;
+                    VarI32::from(type_id).write(buffer)
+                },
+            }
+        }
+    }
+
+    impl PacketSize for MetadataEntries {
+        fn size(&self) -> usize {
+            self.entries
+                .iter()
+                .map(|(_, value)| {
+                    self.header_size(value)
+                        + match value {
+                            MetadataValue::Byte(_) | MetadataValue::Bool(_) => 1,
+                            MetadataValue::String(text) | MetadataValue::Chat(text) => PacketString::new(32767).size(text),
+                            MetadataValue::OptChat(text) => 1 + text.as_ref().map(|text| PacketString::new(32767).size(text)).unwrap_or(0),
+                            MetadataValue::Pose(pose) => VarI32::from(*pose).size(),
+                        }
+                })
+                .sum::<usize>()
+                + 1
+        }
+    }
+
+    impl PacketWrite for MetadataEntries {
+        fn write<B>(&self, buffer: &mut B) -> Result<(), WriteError>
+        where
+            B: BufMut + ?Sized,
+        {
+            for (index, value) in &self.entries {
+                self.write_header(*index, value, buffer)?;
+                match value {
+                    MetadataValue::Byte(byte) => byte.write(buffer)?,
+                    MetadataValue::Bool(flag) => flag.write(buffer)?,
+                    MetadataValue::String(text) | MetadataValue::Chat(text) => PacketString::new(32767).write(text, buffer)?,
+                    MetadataValue::OptChat(text) => {
+                        text.is_some().write(buffer)?;
+                        if let Some(text) = text {
+                            PacketString::new(32767).write(text, buffer)?;
+                        }
+                    },
+                    MetadataValue::Pose(pose) => VarI32::from(*pose).write(buffer)?,
+                }
+            }
+            // the end of the entries
+            if self.protocol < 107 { 0x7Fu8 } else { 0xFFu8 }.write(buffer)
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x13;
+        107, 108, 109, 110, 210, 315, 316 = 0x30;
+        335 = 0x31;
+        338, 340 = 0x32;
+        393, 401, 404 = 0x35;
+        477, 480, 485, 490, 498, 735, 736 = 0x37;
+        573, 575, 578 = 0x38;
+        756 = 0x3A;
+        759 = 0x38;
+        760 = 0x3B;
+        761 = 0x3A;
+        763 = 0x3E;
+    })]
+    pub struct DestroyEntitiesPacket {
+        #[falcon(var32)]
+        count: i32,
+        #[falcon(var32)]
+        entity_id: i32,
+    }
+
+    impl From<i32> for DestroyEntitiesPacket {
+        fn from(entity_id: i32) -> Self { DestroyEntitiesPacket { count: 1, entity_id } }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x0C;
+    })]
+    pub struct SpawnPlayerPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        uuid: Uuid,
+        x: FixedPoint<i32, 5>,
+        y: FixedPoint<i32, 5>,
+        z: FixedPoint<i32, 5>,
+        yaw: Angle,
+        pitch: Angle,
+        current_item: i16,
+        metadata: MetadataEntries,
+    }
+
+    impl From<SpawnPlayerSpec> for SpawnPlayerPacket {
+        fn from(spec: SpawnPlayerSpec) -> Self {
+            SpawnPlayerPacket {
+                entity_id: spec.entity_id,
+                uuid: spec.uuid,
+                x: spec.position.x.into(),
+                y: spec.position.y.into(),
+                z: spec.position.z.into(),
+                yaw: spec.look.yaw.into(),
+                pitch: spec.look.pitch.into(),
+                current_item: 0,
+                metadata: MetadataEntries::from_metadata(EntityType::Player, spec.metadata, spec.protocol_version),
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x18;
+    })]
+    pub struct EntityTeleportPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        x: FixedPoint<i32, 5>,
+        y: FixedPoint<i32, 5>,
+        z: FixedPoint<i32, 5>,
+        yaw: Angle,
+        pitch: Angle,
+        on_ground: bool,
+    }
+
+    impl From<EntityTeleportSpec> for EntityTeleportPacket {
+        fn from(spec: EntityTeleportSpec) -> Self {
+            EntityTeleportPacket {
+                entity_id: spec.entity_id,
+                x: spec.position.x.into(),
+                y: spec.position.y.into(),
+                z: spec.position.z.into(),
+                yaw: spec.look.yaw.into(),
+                pitch: spec.look.pitch.into(),
+                on_ground: spec.on_ground,
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x19;
+        107, 108, 109, 110, 210, 315, 316 = 0x34;
+        335 = 0x35;
+        338, 340 = 0x36;
+        393, 401, 404 = 0x39;
+        477, 480, 485, 490, 498, 735, 736 = 0x3B;
+        573, 575, 578 = 0x3C;
+        755, 756 = 0x3E;
+        759 = 0x3C;
+        760 = 0x3F;
+        761 = 0x3E;
+        763 = 0x42;
+    })]
+    pub struct EntityHeadLookPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        head_yaw: Angle,
+    }
+
+    impl From<(i32, f32)> for EntityHeadLookPacket {
+        fn from((entity_id, yaw): (i32, f32)) -> Self { EntityHeadLookPacket { entity_id, head_yaw: yaw.into() } }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x0B;
+        107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 393, 401, 404 = 0x06;
+        477, 480, 485, 490, 498, 573, 575, 578, 755, 756 = 0x06;
+        735, 736 = 0x05;
+        759, 760, 761 = 0x03;
+        763 = 0x04;
+    })]
+    pub struct EntityAnimationPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        animation: u8,
+    }
+
+    impl From<EntityAnimationSpec> for EntityAnimationPacket {
+        fn from(spec: EntityAnimationSpec) -> Self {
+            EntityAnimationPacket {
+                entity_id: spec.entity_id,
+                animation: spec.animation as u8,
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x12;
+        107, 108, 109, 110, 210, 315, 316 = 0x3B;
+        335 = 0x3D;
+        338, 340 = 0x3E;
+        393, 401, 404 = 0x41;
+        477, 480, 485, 490, 498 = 0x45;
+        573, 575, 578, 735, 736 = 0x46;
+        755, 756, 759 = 0x4F;
+        760 = 0x52;
+        761 = 0x50;
+        763 = 0x54;
+    })]
+    pub struct EntityVelocityPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        velocity_x: i16,
+        velocity_y: i16,
+        velocity_z: i16,
+    }
+
+    impl From<EntityVelocitySpec> for EntityVelocityPacket {
+        fn from(spec: EntityVelocitySpec) -> Self {
+            // in 1/8000 of a block per tick
+            let velocity = |blocks: f64| (blocks * 8000.0).clamp(i16::MIN as f64, i16::MAX as f64) as i16;
+            EntityVelocityPacket {
+                entity_id: spec.entity_id,
+                velocity_x: velocity(spec.x),
+                velocity_y: velocity(spec.y),
+                velocity_z: velocity(spec.z),
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(RespawnSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x07;
+        107, 108, 109, 110, 210, 315, 316 = 0x33;
+        335 = 0x34;
+        338, 340 = 0x35;
+        393, 401, 404 = 0x38;
+    })]
+    pub struct RespawnPacket {
+        dimension: i32,
+        difficulty: u8,
+        game_mode: u8,
+        #[falcon(string = 16)]
+        level_type: String,
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x05;
+        107, 108, 109, 110, 210, 315, 316 = 0x43;
+        335 = 0x45;
+        338, 340 = 0x46;
+        393, 401, 404 = 0x49;
+    })]
+    pub struct SpawnPositionPacket {
+        position: i64,
+    }
+
+    impl From<SpawnPositionSpec> for SpawnPositionPacket {
+        fn from(spec: SpawnPositionSpec) -> Self {
+            SpawnPositionPacket {
+                position: position_xyz(spec.position),
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite, From)]
+    #[from(UpdateHealthSpec)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        47 = 0x06;
+        107, 108, 109, 110, 210, 315, 316 = 0x3E;
+        335 = 0x40;
+        338, 340 = 0x41;
+        393, 401, 404 = 0x44;
+        477, 480, 485, 490, 498 = 0x48;
+        573, 575, 578, 735, 736 = 0x49;
+        755, 756, 759 = 0x52;
+        760 = 0x55;
+        761 = 0x53;
+        763 = 0x57;
+    })]
+    pub struct UpdateHealthPacket {
+        health: f32,
+        #[falcon(var32)]
+        food: i32,
+        saturation: f32,
+    }
 }
@@ -1,11 +1,10 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
     use falcon_packet_core::{PacketSize, PacketWrite};
 
     use crate::specs::status::StatusResponseSpec;
 
     #[derive(PacketSize, PacketWrite)]
-    #[falcon_packet(versions = { -1 = 0x00 }, name = "status_response")]
+    #[falcon_packet(state = status, direction = clientbound, versions = { -1 = 0x00 })]
     pub struct StatusResponsePacket {
         #[falcon(string = 32767)]
         response: String,
@@ -20,7 +19,7 @@ mod inner {
     }
 
     #[derive(PacketSize, PacketWrite)]
-    #[falcon_packet(versions = { -1 = 0x01 }, name = "status_pong")]
+    #[falcon_packet(state = status, direction = clientbound, versions = { -1 = 0x01 })]
     pub struct StatusPongPacket {
         payload: i64,
     }
@@ -1,36 +1,45 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
     use derive_from_ext::From;
-    use falcon_packet_core::{PacketSize, PacketWrite};
+    use falcon_packet_core::{Angle, PacketSize, PacketWrite};
+    use falcon_core::player::data::TeleportFlags;
+    use falcon_core::world::entity::EntityType;
+    use uuid::Uuid;
 
-    use crate::specs::play::PositionAndLookSpec;
+    use crate::specs::play::{EntityTeleportSpec, PositionAndLookSpec, SpawnEntitySpec, SpawnPlayerSpec};
+    use crate::v1_8_9::play::MetadataEntries;
 
     #[derive(PacketSize, PacketWrite, From)]
     #[from(PositionAndLookSpec)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         107, 108, 109, 110, 210, 315, 316, 335 = 0x2E;
         338, 340 = 0x2F;
         393, 401, 404 = 0x32;
         477, 480, 485, 490, 498, 735, 736 = 0x35;
         573, 575, 578 = 0x36;
-    }, name = "position_look")]
+        763 = 0x3C;
+    })]
     pub struct PositionLookPacket {
         x: f64,
         y: f64,
         z: f64,
         yaw: f32,
         pitch: f32,
-        flags: u8,
+        #[falcon(bitflags)]
+        flags: TeleportFlags,
         #[falcon(var32)]
         teleport_id: i32,
     }
 
     #[derive(PacketSize, PacketWrite)]
-    #[falcon_packet(versions = {
-        107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 477, 480, 485, 490, 498, 735, 736 = 0x1D;
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 477, 480, 485, 490, 498, 735, 736, 755, 756 = 0x1D;
         393, 401, 404 = 0x1F;
         573, 575, 578 = 0x1E;
-    }, name = "unload_chunk")]
+        759 = 0x1A;
+        760 = 0x1C;
+        761 = 0x1B;
+        763 = 0x1E;
+    })]
     pub struct UnloadChunkPacket {
         chunk_x: i32,
         chunk_z: i32,
@@ -39,4 +48,114 @@ mod inner {
     impl From<(i32, i32)> for UnloadChunkPacket {
         fn from((chunk_x, chunk_z): (i32, i32)) -> Self { UnloadChunkPacket { chunk_x, chunk_z } }
     }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 393, 401, 404 = 0x00;
+    })]
+    pub struct SpawnObjectPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        uuid: Uuid,
+        object_type: u8,
+        x: f64,
+        y: f64,
+        z: f64,
+        pitch: Angle,
+        yaw: Angle,
+        data: i32,
+        velocity_x: i16,
+        velocity_y: i16,
+        velocity_z: i16,
+    }
+
+    impl From<SpawnEntitySpec> for SpawnObjectPacket {
+        fn from(spec: SpawnEntitySpec) -> Self {
+            SpawnObjectPacket {
+                entity_id: spec.entity_id,
+                uuid: spec.uuid,
+                object_type: spec.type_id().unwrap_or_default() as u8,
+                x: spec.position.x,
+                y: spec.position.y,
+                z: spec.position.z,
+                pitch: spec.look.pitch.into(),
+                yaw: spec.look.yaw.into(),
+                data: spec.data,
+                velocity_x: 0,
+                velocity_y: 0,
+                velocity_z: 0,
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        107, 108, 109, 110, 210, 315, 316, 335, 338, 340, 393, 401, 404 = 0x05;
+        477, 480, 485, 490, 498 = 0x05;
+    })]
+    pub struct SpawnPlayerPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        uuid: Uuid,
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: Angle,
+        pitch: Angle,
+        metadata: MetadataEntries,
+    }
+
+    impl From<SpawnPlayerSpec> for SpawnPlayerPacket {
+        fn from(spec: SpawnPlayerSpec) -> Self {
+            SpawnPlayerPacket {
+                entity_id: spec.entity_id,
+                uuid: spec.uuid,
+                x: spec.position.x,
+                y: spec.position.y,
+                z: spec.position.z,
+                yaw: spec.look.yaw.into(),
+                pitch: spec.look.pitch.into(),
+                metadata: MetadataEntries::from_metadata(EntityType::Player, spec.metadata, spec.protocol_version),
+            }
+        }
+    }
+
+    #[derive(PacketSize, PacketWrite)]
+    #[falcon_packet(state = play, direction = clientbound, versions = {
+        107, 108, 109, 110, 210, 315, 316 = 0x4A;
+        335 = 0x4B;
+        338, 340 = 0x4C;
+        393, 401, 404 = 0x50;
+        477, 480, 485, 490, 498, 735, 736 = 0x56;
+        573, 575, 578 = 0x57;
+        755, 756 = 0x61;
+        759 = 0x63;
+        760 = 0x66;
+        761 = 0x64;
+        763 = 0x68;
+    })]
+    pub struct EntityTeleportPacket {
+        #[falcon(var32)]
+        entity_id: i32,
+        x: f64,
+        y: f64,
+        z: f64,
+        yaw: Angle,
+        pitch: Angle,
+        on_ground: bool,
+    }
+
+    impl From<EntityTeleportSpec> for EntityTeleportPacket {
+        fn from(spec: EntityTeleportSpec) -> Self {
+            EntityTeleportPacket {
+                entity_id: spec.entity_id,
+                x: spec.position.x,
+                y: spec.position.y,
+                z: spec.position.z,
+                yaw: spec.look.yaw.into(),
+                pitch: spec.look.pitch.into(),
+                on_ground: spec.on_ground,
+            }
+        }
+    }
 }
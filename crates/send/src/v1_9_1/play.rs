@@ -1,5 +1,4 @@
-#[falcon_send_derive::falcon_send]
-mod inner {
+falcon_packet_core::packet! {
     use derive_from_ext::From;
     use falcon_packet_core::{PacketSize, PacketWrite};
 
@@ -7,10 +6,10 @@ mod inner {
 
     #[derive(PacketSize, PacketWrite, From)]
     #[from(JoinGameSpec)]
-    #[falcon_packet(versions = {
+    #[falcon_packet(state = play, direction = clientbound, versions = {
         108, 109, 110, 210, 315, 316, 335, 338, 340 = 0x23;
         393, 401, 404 = 0x25;
-    }, name = "join_game")]
+    })]
     pub struct JoinGamePacket {
         entity_id: i32,
         game_mode: u8,
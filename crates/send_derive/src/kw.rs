@@ -1,2 +0,0 @@
-syn::custom_keyword!(versions);
-syn::custom_keyword!(name);
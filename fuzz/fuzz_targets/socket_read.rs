@@ -0,0 +1,22 @@
+//! Feeds arbitrary data to the reader of a server connection, every input
+//! has to give packets or an error.
+#![no_main]
+
+use bytes::BufMut;
+use falcon_logic::connection::reader::SocketRead;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // the first byte turns on compression and splits the data up, like a
+    // client that sends its packets in pieces
+    let Some((&settings, data)) = data.split_first() else {
+        return;
+    };
+    let threshold = if settings & 0x80 == 0 { -1 } else { 64 };
+    let chunk_len = (settings & 0x7f) as usize + 1;
+    let mut reader = SocketRead::new(threshold).with_buffer_len(64);
+    for chunk in data.chunks(chunk_len) {
+        reader.put_slice(chunk);
+        while let Ok(Some(_)) = reader.next_packet() {}
+    }
+});